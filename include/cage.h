@@ -0,0 +1,50 @@
+/* Cage C ABI - build with: cargo rustc --release --lib --features ffi --crate-type cdylib */
+#ifndef CAGE_H
+#define CAGE_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define CAGE_OK 0
+#define CAGE_ERR_INVALID_ARGUMENT 1
+#define CAGE_ERR_OPERATION 2
+#define CAGE_ERR_PARTIAL 3
+#define CAGE_ERR_PANIC 4
+
+#define CAGE_FLAG_RECURSIVE (1u << 0)
+#define CAGE_FLAG_BACKUP (1u << 1)
+#define CAGE_FLAG_ARMOR (1u << 2)
+
+typedef struct CageHandle CageHandle;
+
+typedef struct {
+    uint64_t processed;
+    uint64_t failed;
+    uint64_t skipped;
+} CageResult;
+
+typedef struct {
+    uint64_t total_files;
+    uint64_t encrypted_files;
+    uint64_t unencrypted_files;
+} CageStatus;
+
+int32_t cage_new(const char *passphrase, uint32_t flags, CageHandle **out);
+void cage_free(CageHandle *handle);
+
+/* result may be NULL */
+int32_t cage_lock(CageHandle *handle, const char *path, CageResult *result);
+int32_t cage_unlock(CageHandle *handle, const char *path, CageResult *result);
+int32_t cage_status(CageHandle *handle, const char *path, CageStatus *status);
+
+/* Last error on the calling thread, or NULL. Owned by Cage; do not free. */
+const char *cage_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CAGE_H */
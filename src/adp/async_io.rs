@@ -0,0 +1,148 @@
+//! Async I/O bridge for streaming operations (feature `async`)
+//!
+//! The adapters drive the age CLI with blocking `Read`/`Write` handles. This module runs
+//! such a job on tokio's blocking pool and shuttles bytes to and from tokio
+//! `AsyncRead`/`AsyncWrite` handles through bounded channels, so async callers neither
+//! block the runtime nor buffer whole payloads in memory.
+
+use crate::error::{AgeError, AgeResult};
+use std::io::{self, Read, Write};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+
+/// Size of each chunk passed across the bridge
+const BRIDGE_CHUNK: usize = 64 * 1024;
+
+/// Chunks buffered in each direction before the producer waits
+const BRIDGE_DEPTH: usize = 8;
+
+/// Blocking reader fed by the async input pump
+struct ChannelReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+    offset: usize,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.offset >= self.pending.len() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => {
+                    self.pending = chunk;
+                    self.offset = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let available = &self.pending[self.offset..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.offset += count;
+        Ok(count)
+    }
+}
+
+/// Blocking writer drained by the async output pump
+struct ChannelWriter {
+    tx: mpsc::Sender<Vec<u8>>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.tx
+            .blocking_send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "async output closed"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run a blocking streaming `job` against async `input` and `output`.
+///
+/// Must be called from within a tokio runtime. The job's own error takes precedence
+/// over I/O errors from the pumps, since a failed job usually closes the pipes too.
+pub async fn bridge_blocking<R, W, F>(mut input: R, mut output: W, job: F) -> AgeResult<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    F: FnOnce(&mut (dyn Read + Send), &mut (dyn Write + Send)) -> AgeResult<u64> + Send + 'static,
+{
+    let (in_tx, in_rx) = mpsc::channel::<Vec<u8>>(BRIDGE_DEPTH);
+    let (out_tx, mut out_rx) = mpsc::channel::<Vec<u8>>(BRIDGE_DEPTH);
+
+    let worker = tokio::task::spawn_blocking(move || {
+        let mut reader = ChannelReader {
+            rx: in_rx,
+            pending: Vec::new(),
+            offset: 0,
+        };
+        let mut writer = ChannelWriter { tx: out_tx };
+        job(&mut reader, &mut writer)
+    });
+
+    let feed = async move {
+        let mut buf = vec![0u8; BRIDGE_CHUNK];
+        loop {
+            let count = input.read(&mut buf).await?;
+            // A closed channel means the job stopped reading; its result reports why
+            if count == 0 || in_tx.send(buf[..count].to_vec()).await.is_err() {
+                return Ok::<(), io::Error>(());
+            }
+        }
+    };
+
+    let drain = async move {
+        while let Some(chunk) = out_rx.recv().await {
+            output.write_all(&chunk).await?;
+        }
+        output.flush().await
+    };
+
+    let (fed, drained) = tokio::join!(feed, drain);
+    let result = worker.await.map_err(|e| AgeError::InvalidOperation {
+        operation: "stream_async".to_string(),
+        reason: format!("Streaming task failed: {}", e),
+    })??;
+
+    fed.map_err(|e| AgeError::IoError {
+        operation: "stream_async".to_string(),
+        context: "reading async input".to_string(),
+        source: e,
+    })?;
+    drained.map_err(|e| AgeError::IoError {
+        operation: "stream_async".to_string(),
+        context: "writing async output".to_string(),
+        source: e,
+    })?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bridge_round_trips_bytes() {
+        let payload: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let mut output = Vec::new();
+
+        let copied = bridge_blocking(payload.as_slice(), &mut output, |input, output| {
+            io::copy(input, output).map_err(|e| AgeError::IoError {
+                operation: "copy".to_string(),
+                context: "test".to_string(),
+                source: e,
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(copied, payload.len() as u64);
+        assert_eq!(output, payload);
+    }
+}
@@ -8,6 +8,11 @@
 //! - **v1**: Original adapter implementation with basic CLI wrapping
 //! - **v2**: Enhanced adapter with streaming support and improved error handling
 //! - **pipe**: Experimental pipe streaming for passphrase-based encryption
+//! - **progress**: Byte-level progress reporting for streaming operations
+//! - **supervisor**: Process cap, timeouts and reaping for spawned `age` children
+//! - **sandbox**: Environment clearing and seccomp confinement for those children
+//! - **pin**: SHA-256 pinning of the `age` and `age-keygen` binaries
+//! - **async_io**: Tokio bridge for streaming operations (feature `async`)
 //!
 //! # Examples
 //!
@@ -23,8 +28,17 @@
 
 pub mod v1;
 pub mod v2;
+pub mod pin;
 pub mod pipe;
+pub mod progress;
+pub mod sandbox;
+pub mod supervisor;
+#[cfg(feature = "async")]
+pub mod async_io;
 
 // Re-export primary adapter types
-pub use v1::{AgeAdapter, AdapterFactory};
+pub use v1::{AdapterFactory, AdapterSelection, AgeAdapter};
+pub use pin::PinPolicy;
+pub use progress::{ByteProgressFn, ProgressReader};
+pub use sandbox::SandboxMode;
 pub use v2::{AgeAdapterV2, ShellAdapterV2, AdapterV1Compat, StreamingStrategy};
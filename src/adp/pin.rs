@@ -0,0 +1,185 @@
+//! Binary Pinning - Refuse swapped `age` and `age-keygen` binaries
+//!
+//! Automation hosts run whatever `age` comes first on `PATH`. With a SHA-256 pinned in
+//! `[binaries]` (`age_sha256`, `age_keygen_sha256`; `sha256sum "$(command -v age)"`
+//! prints it), adapters and key generation hash the binary they are about to run when
+//! they start and compare it with the pin. `pin_policy` decides what a mismatch or a
+//! missing binary does: `refuse` (the default) fails the adapter, `warn` logs once per
+//! binary and carries on.
+//!
+//! Digests are cached by path, size and modification time, so the many adapters a run
+//! creates hash each binary once while a replaced binary is still noticed.
+
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Binary digests keyed by path, with the size and mtime they were computed for
+static DIGESTS: Mutex<Option<HashMap<PathBuf, (u64, Option<SystemTime>, String)>>> =
+    Mutex::new(None);
+/// Binaries already warned about under `warn`
+static WARNED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// What a binary that does not match its pin does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PinPolicy {
+    /// Fail adapter startup (default)
+    #[default]
+    Refuse,
+    /// Log a warning and run the binary anyway
+    Warn,
+}
+
+impl PinPolicy {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "refuse" => Ok(Self::Refuse),
+            "warn" => Ok(Self::Warn),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "binaries.pin_policy".to_string(),
+                value: value.to_string(),
+                reason: "Valid values: refuse, warn".to_string(),
+            }),
+        }
+    }
+}
+
+/// Normalize a pinned digest: 64 hex digits, lowercased
+pub fn parse_sha256(parameter: &str, value: &str) -> AgeResult<String> {
+    let digest = value.trim().to_lowercase();
+    if digest.len() != 64 || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(AgeError::ConfigurationError {
+            parameter: parameter.to_string(),
+            value: value.to_string(),
+            reason: "Expected a SHA-256 digest (64 hex digits)".to_string(),
+        });
+    }
+    Ok(digest)
+}
+
+/// Hex SHA-256 of the file at `path`
+pub fn file_sha256(path: &Path) -> AgeResult<String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| AgeError::file_error("stat", path.to_path_buf(), e))?;
+    let stamp = (metadata.len(), metadata.modified().ok());
+    let mut cache = DIGESTS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((len, modified, digest)) = cache.get_or_insert_with(HashMap::new).get(path) {
+        if (*len, *modified) == stamp {
+            return Ok(digest.clone());
+        }
+    }
+    let mut hasher = Sha256::new();
+    File::open(path)
+        .and_then(|mut file| std::io::copy(&mut file, &mut hasher))
+        .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    cache
+        .get_or_insert_with(HashMap::new)
+        .insert(path.to_path_buf(), (stamp.0, stamp.1, digest.clone()));
+    Ok(digest)
+}
+
+/// Check `program` as found on `PATH` against `expected` and apply `policy`
+pub fn verify_binary(program: &str, expected: &str, policy: PinPolicy) -> AgeResult<()> {
+    let problem = match which::which(program) {
+        Ok(path) => {
+            let actual = file_sha256(&path)?;
+            if actual == expected {
+                return Ok(());
+            }
+            format!(
+                "{} ({}) has SHA-256 {}, pinned {}",
+                program,
+                path.display(),
+                actual,
+                expected
+            )
+        }
+        Err(_) => format!("{} is pinned but not found on PATH", program),
+    };
+    if policy == PinPolicy::Refuse {
+        return Err(AgeError::SecurityValidationFailed {
+            validation_type: "binary_pin".to_string(),
+            details: problem,
+        });
+    }
+    let mut warned = WARNED.lock().unwrap_or_else(|e| e.into_inner());
+    if warned
+        .get_or_insert_with(HashSet::new)
+        .insert(program.to_string())
+    {
+        tracing::warn!("SECURITY: {}", problem);
+    }
+    Ok(())
+}
+
+/// Check every binary pinned in `config`
+pub fn verify_pins(config: &AgeConfig) -> AgeResult<()> {
+    let pins = [
+        ("age", &config.age_binary_sha256),
+        ("age-keygen", &config.age_keygen_binary_sha256),
+    ];
+    for (program, pin) in pins {
+        if let Some(expected) = pin {
+            verify_binary(program, expected, config.binary_pin_policy)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_sha256_pin() {
+        let digest = "AB".repeat(32);
+        assert_eq!(
+            parse_sha256("binaries.age_sha256", &digest).unwrap(),
+            "ab".repeat(32)
+        );
+        assert!(parse_sha256("binaries.age_sha256", "abc").is_err());
+        assert!(parse_sha256("binaries.age_sha256", &"zz".repeat(32)).is_err());
+        assert_eq!(PinPolicy::parse("WARN").unwrap(), PinPolicy::Warn);
+        assert!(PinPolicy::parse("ignore").is_err());
+    }
+
+    #[test]
+    fn test_file_sha256_notices_replaced_binary() {
+        let temp = TempDir::new().unwrap();
+        let binary = temp.path().join("age");
+        std::fs::write(&binary, b"abc").unwrap();
+        assert_eq!(
+            file_sha256(&binary).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        std::fs::write(&binary, b"abcd").unwrap();
+        assert_eq!(
+            file_sha256(&binary).unwrap(),
+            "88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589"
+        );
+    }
+
+    #[test]
+    fn test_verify_binary_applies_policy() {
+        let pin = "00".repeat(32);
+        let missing = "cage-pin-test-no-such-binary";
+        assert!(matches!(
+            verify_binary(missing, &pin, PinPolicy::Refuse),
+            Err(AgeError::SecurityValidationFailed { .. })
+        ));
+        assert!(verify_binary(missing, &pin, PinPolicy::Warn).is_ok());
+        assert!(verify_pins(&AgeConfig::default()).is_ok());
+    }
+}
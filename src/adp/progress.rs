@@ -0,0 +1,137 @@
+//! Byte-level progress reporting for streaming adapters
+//!
+//! Streaming adapters report cumulative *input* bytes processed through a
+//! `ByteProgressFn` callback so callers can drive byte-based progress bars with
+//! throughput and ETA. Pipe strategies count bytes as age consumes them; the
+//! temp-file strategy watches the staged output file grow while age runs.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Callback receiving the cumulative number of input bytes processed
+pub type ByteProgressFn = Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Interval between output-size samples while age runs on staged files
+const WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reader wrapper that reports cumulative bytes read
+pub struct ProgressReader<R> {
+    inner: R,
+    bytes: u64,
+    callback: ByteProgressFn,
+}
+
+impl<R: Read> ProgressReader<R> {
+    pub fn new(inner: R, callback: ByteProgressFn) -> Self {
+        Self {
+            inner,
+            bytes: 0,
+            callback,
+        }
+    }
+
+    /// Total bytes read so far
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            self.bytes += n as u64;
+            (self.callback)(self.bytes);
+        }
+        Ok(n)
+    }
+}
+
+/// Background sampler reporting the size of a file that an external process is writing.
+///
+/// Reported values are capped at `total` so output expansion (e.g. ASCII armor) never
+/// pushes a progress bar past 100%. Sampling stops when the watcher is dropped.
+pub(crate) struct FileGrowthWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl FileGrowthWatcher {
+    pub(crate) fn start(path: PathBuf, total: u64, callback: ByteProgressFn) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut last = 0;
+            while !stop_flag.load(Ordering::Relaxed) {
+                if let Ok(meta) = fs::metadata(&path) {
+                    let current = meta.len().min(total);
+                    if current != last {
+                        last = current;
+                        callback(current);
+                    }
+                }
+                thread::sleep(WATCH_INTERVAL);
+            }
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for FileGrowthWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    #[test]
+    fn test_progress_reader_reports_cumulative_bytes() {
+        let seen = Arc::new(AtomicU64::new(0));
+        let seen_cb = Arc::clone(&seen);
+        let data = vec![7u8; 10_000];
+        let mut reader = ProgressReader::new(
+            &data[..],
+            Arc::new(move |bytes| seen_cb.store(bytes, Ordering::Relaxed)),
+        );
+
+        let mut sink = Vec::new();
+        io::copy(&mut reader, &mut sink).unwrap();
+        assert_eq!(reader.bytes_read(), 10_000);
+        assert_eq!(seen.load(Ordering::Relaxed), 10_000);
+    }
+
+    #[test]
+    fn test_file_growth_watcher_caps_at_total() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("growing");
+        fs::write(&path, vec![0u8; 4096]).unwrap();
+
+        let seen = Arc::new(AtomicU64::new(0));
+        let seen_cb = Arc::clone(&seen);
+        let watcher = FileGrowthWatcher::start(
+            path,
+            1024,
+            Arc::new(move |bytes| seen_cb.store(bytes, Ordering::Relaxed)),
+        );
+        thread::sleep(WATCH_INTERVAL * 3);
+        drop(watcher);
+
+        assert_eq!(seen.load(Ordering::Relaxed), 1024);
+    }
+}
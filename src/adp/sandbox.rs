@@ -0,0 +1,259 @@
+//! Sandbox - Confinement for spawned `age` processes
+//!
+//! A compromised or swapped `age` binary sees every plaintext cage hands it. With
+//! `[limits] sandbox` set, the supervisor confines each child before it starts:
+//!
+//! - `env`: the child gets an empty environment apart from `PATH` (age plugins are found
+//!   through it), `TMPDIR` and the locale, so tokens and credentials in cage's own
+//!   environment never reach it
+//! - `strict`: `env` plus, on Linux (x86_64, aarch64), `no_new_privs` and a seccomp
+//!   filter refusing network sockets and io_uring. Unix sockets stay available for
+//!   plugins talking to local daemons such as smart-card services.
+//!
+//! Passphrase operations drive `age` through a PTY whose spawn cannot be hooked, so they
+//! get the environment confinement only; `strict` warns once when a child runs without
+//! the filter, there or on other platforms. The working directory is left alone because
+//! age resolves the relative paths it is given against it.
+
+use crate::error::{AgeError, AgeResult};
+use std::ffi::OsString;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Variables a sandboxed child inherits from cage's environment
+pub const KEPT_ENV: &[&str] = &["PATH", "TMPDIR", "LANG", "LC_ALL"];
+
+static MODE: AtomicU8 = AtomicU8::new(SandboxMode::Off as u8);
+static WARNED_UNFILTERED: AtomicBool = AtomicBool::new(false);
+
+/// How far spawned `age` processes are confined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SandboxMode {
+    /// Children inherit cage's environment (default)
+    #[default]
+    Off,
+    /// Clear the environment except `KEPT_ENV`
+    Env,
+    /// `Env` plus no-new-privs and a seccomp filter without network access
+    Strict,
+}
+
+impl SandboxMode {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off" | "none" => Ok(Self::Off),
+            "env" => Ok(Self::Env),
+            "strict" => Ok(Self::Strict),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "limits.sandbox".to_string(),
+                value: value.to_string(),
+                reason: "Valid values: off, env, strict".to_string(),
+            }),
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Env,
+            2 => Self::Strict,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Confine every later spawn according to `mode`
+pub fn configure(mode: SandboxMode) {
+    MODE.store(mode as u8, Ordering::SeqCst);
+}
+
+/// Currently configured mode
+pub fn mode() -> SandboxMode {
+    SandboxMode::from_u8(MODE.load(Ordering::SeqCst))
+}
+
+/// Environment a confined child starts with; `None` when it inherits cage's
+pub fn environment() -> Option<Vec<(&'static str, OsString)>> {
+    if mode() == SandboxMode::Off {
+        return None;
+    }
+    Some(
+        KEPT_ENV
+            .iter()
+            .filter_map(|name| std::env::var_os(name).map(|value| (*name, value)))
+            .collect(),
+    )
+}
+
+/// Confine `command` according to the configured mode
+pub fn apply(command: &mut Command) {
+    apply_mode(command, mode());
+}
+
+fn apply_mode(command: &mut Command, mode: SandboxMode) {
+    if mode == SandboxMode::Off {
+        return;
+    }
+    // Variables the caller set on purpose survive the clear
+    let explicit: Vec<(OsString, OsString)> = command
+        .get_envs()
+        .filter_map(|(name, value)| Some((name.to_os_string(), value?.to_os_string())))
+        .collect();
+    let kept = KEPT_ENV
+        .iter()
+        .filter_map(|name| std::env::var_os(name).map(|value| (OsString::from(name), value)));
+    command.env_clear().envs(kept).envs(explicit);
+    if mode == SandboxMode::Strict && !filter::install(command) {
+        warn_unfiltered("this platform has no seccomp filter");
+    }
+}
+
+/// Warn once that a `strict` child runs without the seccomp filter
+pub fn warn_unfiltered(reason: &str) {
+    if mode() == SandboxMode::Strict && !WARNED_UNFILTERED.swap(true, Ordering::SeqCst) {
+        tracing::warn!(
+            "Strict sandbox: {}; age runs with a cleared environment only",
+            reason
+        );
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod filter {
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    /// Classic BPF instruction (`struct sock_filter`)
+    #[repr(C)]
+    struct SockFilter {
+        code: u16,
+        jt: u8,
+        jf: u8,
+        k: u32,
+    }
+
+    /// `struct sock_fprog`
+    #[repr(C)]
+    struct SockFprog {
+        len: u16,
+        filter: *const SockFilter,
+    }
+
+    const LD_W_ABS: u16 = 0x20;
+    const JEQ_K: u16 = 0x15;
+    const JGE_K: u16 = 0x35;
+    const RET_K: u16 = 0x06;
+
+    const SECCOMP_MODE_FILTER: libc::c_ulong = 2;
+    const RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const RET_ERRNO: u32 = 0x0005_0000;
+    const RET_ALLOW: u32 = 0x7fff_0000;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xc000_00b7;
+    /// Set in x32 syscall numbers on x86_64; refusing them closes that side door
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    /// Offsets into `struct seccomp_data`
+    const DATA_NR: u32 = 0;
+    const DATA_ARCH: u32 = 4;
+    const DATA_ARG0: u32 = 16;
+
+    const fn stmt(code: u16, k: u32) -> SockFilter {
+        SockFilter {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    const fn jump(code: u16, k: u32, jt: u8, jf: u8) -> SockFilter {
+        SockFilter { code, jt, jf, k }
+    }
+
+    /// Allow everything except io_uring and sockets outside `AF_UNIX`
+    static FILTER: [SockFilter; 11] = [
+        stmt(LD_W_ABS, DATA_ARCH),
+        jump(JEQ_K, AUDIT_ARCH, 0, 8),
+        stmt(LD_W_ABS, DATA_NR),
+        jump(JGE_K, X32_SYSCALL_BIT, 6, 0),
+        jump(JEQ_K, libc::SYS_io_uring_setup as u32, 4, 0),
+        jump(JEQ_K, libc::SYS_socket as u32, 0, 2),
+        stmt(LD_W_ABS, DATA_ARG0),
+        jump(JEQ_K, libc::AF_UNIX as u32, 0, 1),
+        stmt(RET_K, RET_ALLOW),
+        stmt(RET_K, RET_ERRNO | libc::EACCES as u32),
+        stmt(RET_K, RET_KILL_PROCESS),
+    ];
+
+    /// Install no-new-privs and the filter in the child between fork and exec
+    pub fn install(command: &mut Command) -> bool {
+        // SAFETY: the hook only issues prctl(2), which is async-signal-safe
+        unsafe {
+            command.pre_exec(|| {
+                let (off, on): (libc::c_ulong, libc::c_ulong) = (0, 1);
+                if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, on, off, off, off) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                let program = SockFprog {
+                    len: FILTER.len() as u16,
+                    filter: FILTER.as_ptr(),
+                };
+                let program = &program as *const SockFprog as libc::c_ulong;
+                if libc::prctl(libc::PR_SET_SECCOMP, SECCOMP_MODE_FILTER, program, off, off) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        true
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod filter {
+    use std::process::Command;
+
+    pub fn install(_command: &mut Command) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sandbox_mode() {
+        assert_eq!(SandboxMode::parse("off").unwrap(), SandboxMode::Off);
+        assert_eq!(SandboxMode::parse("Strict").unwrap(), SandboxMode::Strict);
+        assert!(SandboxMode::parse("jail").is_err());
+        assert_eq!(
+            SandboxMode::from_u8(SandboxMode::Env as u8),
+            SandboxMode::Env
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sandboxed_child_runs_with_cleared_environment() {
+        for mode in [SandboxMode::Env, SandboxMode::Strict] {
+            let mut command = Command::new("sh");
+            command
+                .args(["-c", "echo \"${HOME:-unset} ${CAGE_PROBE:-unset}\""])
+                .env("CAGE_PROBE", "kept");
+            apply_mode(&mut command, mode);
+            let output = command.output().unwrap();
+            assert!(output.status.success(), "{:?} child failed", mode);
+            assert_eq!(String::from_utf8_lossy(&output.stdout), "unset kept\n");
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Process supervisor - Bounded, time-limited and always-reaped `age` child processes
+//!
+//! Every `age` process the adapters start goes through here. The supervisor caps how many
+//! run at once (`[limits] max_processes`), kills a child that outlives `process_timeout`,
+//! reaps what it spawned even when the caller bails out early (`SupervisedChild` kills and
+//! waits on drop), and logs abnormal exits with the operation, pid and runtime. Children
+//! are registered with `core::interrupt` so Ctrl-C terminates them as well, and confined
+//! by `adp::sandbox` when `[limits] sandbox` is set.
+
+use super::sandbox;
+use crate::core::interrupt::{self, ChildRegistration};
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use std::process::{Child, Command, ExitStatus};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Concurrent `age` processes allowed when `max_processes` is unset
+pub const DEFAULT_MAX_PROCESSES: usize = 8;
+
+/// How often a child with a deadline is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+static MAX_PROCESSES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_PROCESSES);
+/// Per-process timeout in seconds; 0 disables it
+static TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+static RUNNING: Mutex<usize> = Mutex::new(0);
+static SLOT_FREED: Condvar = Condvar::new();
+
+/// Apply `max_processes`, `process_timeout` and `process_sandbox` from config to every
+/// later spawn
+pub fn configure(config: &AgeConfig) {
+    let limit = config.max_processes.unwrap_or(DEFAULT_MAX_PROCESSES).max(1);
+    MAX_PROCESSES.store(limit, Ordering::SeqCst);
+    let timeout = config
+        .process_timeout
+        .map_or(0, |timeout| timeout.as_secs());
+    TIMEOUT_SECS.store(timeout, Ordering::SeqCst);
+    sandbox::configure(config.process_sandbox);
+    SLOT_FREED.notify_all();
+}
+
+/// Maximum number of concurrently running `age` processes
+pub fn max_processes() -> usize {
+    MAX_PROCESSES.load(Ordering::SeqCst)
+}
+
+/// Wall-clock limit for a single `age` process, if any
+pub fn process_timeout() -> Option<Duration> {
+    match TIMEOUT_SECS.load(Ordering::SeqCst) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Number of supervised processes currently holding a slot
+pub fn running() -> usize {
+    *RUNNING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Slot under the process cap; released on drop
+#[derive(Debug)]
+pub struct ProcessPermit {
+    _private: (),
+}
+
+impl Drop for ProcessPermit {
+    fn drop(&mut self) {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        *running = running.saturating_sub(1);
+        SLOT_FREED.notify_one();
+    }
+}
+
+/// Block until fewer than `max_processes` supervised children are running.
+///
+/// Used directly by callers that spawn through other APIs (the PTY automator).
+pub fn acquire() -> ProcessPermit {
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    while *running >= max_processes() {
+        running = SLOT_FREED.wait(running).unwrap_or_else(|e| e.into_inner());
+    }
+    *running += 1;
+    ProcessPermit { _private: () }
+}
+
+/// Child process started by `spawn`; killed and reaped on drop if never waited for
+#[derive(Debug)]
+pub struct SupervisedChild {
+    child: Child,
+    label: String,
+    started: Instant,
+    timeout: Option<Duration>,
+    reaped: bool,
+    _registration: ChildRegistration,
+    _permit: ProcessPermit,
+}
+
+/// Spawn `command` under supervision; `label` names the operation in errors and logs
+pub fn spawn(command: &mut Command, label: &str) -> AgeResult<SupervisedChild> {
+    sandbox::apply(command);
+    let permit = acquire();
+    let child = command
+        .spawn()
+        .map_err(|e| AgeError::ProcessExecutionFailed {
+            command: command.get_program().to_string_lossy().into_owned(),
+            exit_code: None,
+            stderr: format!("{}: {}", label, e),
+        })?;
+    let registration = interrupt::register_child(child.id());
+    Ok(SupervisedChild {
+        child,
+        label: label.to_string(),
+        started: Instant::now(),
+        timeout: process_timeout(),
+        reaped: false,
+        _registration: registration,
+        _permit: permit,
+    })
+}
+
+/// `Command::status` under supervision
+pub fn status(command: &mut Command, label: &str) -> AgeResult<ExitStatus> {
+    spawn(command, label)?.wait()
+}
+
+impl SupervisedChild {
+    /// Process id of the child
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Underlying child, e.g. to take its stdio pipes
+    pub fn child_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+
+    /// Wait for the child to exit, killing it once the process timeout has passed
+    pub fn wait(mut self) -> AgeResult<ExitStatus> {
+        let status = match self.timeout {
+            None => self.child.wait().map_err(|e| self.wait_error(e))?,
+            Some(timeout) => loop {
+                if let Some(status) = self.child.try_wait().map_err(|e| self.wait_error(e))? {
+                    break status;
+                }
+                if self.started.elapsed() >= timeout {
+                    self.terminate();
+                    tracing::warn!(
+                        "Killed age ({}, pid {}) after exceeding the {}s process timeout",
+                        self.label,
+                        self.id(),
+                        timeout.as_secs()
+                    );
+                    return Err(AgeError::OperationTimeout {
+                        operation: self.label.clone(),
+                        timeout_seconds: timeout.as_secs(),
+                    });
+                }
+                thread::sleep(POLL_INTERVAL);
+            },
+        };
+        self.reaped = true;
+        if !status.success() {
+            log_abnormal_exit(&self.label, self.id(), self.started.elapsed(), &status);
+        }
+        Ok(status)
+    }
+
+    fn wait_error(&self, error: std::io::Error) -> AgeError {
+        AgeError::ProcessExecutionFailed {
+            command: "age".into(),
+            exit_code: None,
+            stderr: format!(
+                "{}: failed to wait for pid {}: {}",
+                self.label,
+                self.id(),
+                error
+            ),
+        }
+    }
+
+    fn terminate(&mut self) {
+        if !self.reaped {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            self.reaped = true;
+        }
+    }
+}
+
+impl Drop for SupervisedChild {
+    fn drop(&mut self) {
+        self.terminate();
+    }
+}
+
+/// Report a child that exited non-zero or was killed by a signal.
+///
+/// Exits caused by a cage interrupt are expected and not logged.
+pub fn log_abnormal_exit(label: &str, pid: u32, elapsed: Duration, status: &dyn std::fmt::Debug) {
+    if interrupt::interrupted().is_some() {
+        return;
+    }
+    tracing::warn!(
+        "age exited abnormally: operation={} pid={} status={:?} runtime={:.1?} running={}/{}",
+        label,
+        pid,
+        status,
+        elapsed,
+        running(),
+        max_processes()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dropped_child_is_reaped() {
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let Ok(child) = spawn(&mut command, "test_sleep") else {
+            return;
+        };
+        let pid = child.id() as libc::pid_t;
+        drop(child);
+        // A reaped child no longer exists, not even as a zombie
+        let rc = unsafe { libc::kill(pid, 0) };
+        assert_eq!(rc, -1);
+    }
+}
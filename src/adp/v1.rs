@@ -9,7 +9,9 @@
 use super::v2::{AdapterV1Compat, ShellAdapterV2};
 use crate::core::OutputFormat;
 use crate::error::{AgeError, AgeResult};
+use crate::pty::PtyAutomator;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Core Age operations interface that all adapters must implement
 pub trait AgeAdapter {
@@ -40,19 +42,21 @@ pub trait AgeAdapter {
 
 /// Shell-based Age adapter using PTY automation methods
 pub struct ShellAdapter {
-    pty_automator: crate::pty::PtyAgeAutomator,
+    pty_automator: Arc<dyn PtyAutomator>,
     audit_logger: crate::audit::AuditLogger,
 }
 
 impl ShellAdapter {
     /// Create new ShellAdapter with PTY automation
     pub fn new() -> AgeResult<Self> {
-        let pty_automator = crate::pty::PtyAgeAutomator::new()?;
-        let audit_logger = crate::audit::AuditLogger::new(None)?;
+        Self::with_automator(Arc::new(crate::pty::PtyAgeAutomator::new()?))
+    }
 
+    /// Create a ShellAdapter driving `automator`, e.g. `pty::FakeAutomator` in tests
+    pub fn with_automator(automator: Arc<dyn PtyAutomator>) -> AgeResult<Self> {
         Ok(Self {
-            pty_automator,
-            audit_logger,
+            pty_automator: automator,
+            audit_logger: crate::audit::AuditLogger::new(None)?,
         })
     }
 
@@ -133,7 +137,7 @@ impl AgeAdapter for ShellAdapter {
 
     fn clone_box(&self) -> Box<dyn AgeAdapter> {
         Box::new(ShellAdapter {
-            pty_automator: crate::pty::PtyAgeAutomator::new().unwrap(),
+            pty_automator: Arc::clone(&self.pty_automator),
             audit_logger: crate::audit::AuditLogger::new(None).unwrap(),
         })
     }
@@ -198,6 +202,15 @@ impl AgeAdapter for RageAdapter {
 /// Adapter factory for creating the appropriate Age adapter
 pub struct AdapterFactory;
 
+/// Adapter picked from a configured fallback chain
+pub struct AdapterSelection {
+    pub adapter: Box<dyn AgeAdapter>,
+    /// Name of the chain entry that was created
+    pub chosen: String,
+    /// Earlier chain entries that failed, as (name, reason)
+    pub skipped: Vec<(String, String)>,
+}
+
 impl AdapterFactory {
     /// Create the default adapter (currently ShellAdapter)
     pub fn create_default() -> AgeResult<Box<dyn AgeAdapter>> {
@@ -206,13 +219,26 @@ impl AdapterFactory {
     }
 
     /// Create specific adapter by name
+    ///
+    /// `shell`/`shell-v2` is the default v2 shell adapter, `shell-v1` the PTY-only
+    /// adapter, `pipe` the v2 adapter with pipe streaming, and `rage`/`library` the
+    /// in-process backend (not yet implemented).
     pub fn create_adapter(adapter_type: &str) -> AgeResult<Box<dyn AgeAdapter>> {
         match adapter_type {
-            "shell" => {
+            "shell" | "shell-v2" => {
                 let v2 = ShellAdapterV2::new()?;
                 Ok(Box::new(AdapterV1Compat::new(v2)))
             }
-            "rage" => Ok(Box::new(RageAdapter::new()?)),
+            "shell-v1" => Ok(Box::new(ShellAdapter::new()?)),
+            "pipe" => {
+                let config = crate::core::AgeConfig {
+                    streaming_strategy: Some("pipe".to_string()),
+                    ..crate::core::AgeConfig::default()
+                };
+                let v2 = ShellAdapterV2::with_config(config)?;
+                Ok(Box::new(AdapterV1Compat::new(v2)))
+            }
+            "rage" | "library" => Ok(Box::new(RageAdapter::new()?)),
             _ => Err(AgeError::InvalidAdapter(format!(
                 "Unknown adapter type: {}",
                 adapter_type
@@ -220,9 +246,51 @@ impl AdapterFactory {
         }
     }
 
+    /// Try each adapter in `chain` in order and return the first that can be created.
+    ///
+    /// An empty chain falls back to `create_default`.
+    pub fn create_from_chain(chain: &[String]) -> AgeResult<AdapterSelection> {
+        if chain.is_empty() {
+            return Ok(AdapterSelection {
+                adapter: Self::create_default()?,
+                chosen: Self::recommended_adapter().to_string(),
+                skipped: Vec::new(),
+            });
+        }
+
+        let mut skipped = Vec::new();
+        for name in chain {
+            match Self::create_adapter(name) {
+                Ok(adapter) => {
+                    return Ok(AdapterSelection {
+                        adapter,
+                        chosen: name.clone(),
+                        skipped,
+                    })
+                }
+                Err(e) => skipped.push((name.clone(), e.to_string())),
+            }
+        }
+
+        let reasons: Vec<String> = skipped
+            .iter()
+            .map(|(name, reason)| format!("{}: {}", name, reason))
+            .collect();
+        Err(AgeError::InvalidAdapter(format!(
+            "No adapter in chain could be created ({})",
+            reasons.join("; ")
+        )))
+    }
+
+    /// Create the adapter selected by `config.adapter_chain`
+    pub fn create_configured(config: &crate::core::AgeConfig) -> AgeResult<Box<dyn AgeAdapter>> {
+        super::pin::verify_pins(config)?;
+        Ok(Self::create_from_chain(&config.adapter_chain)?.adapter)
+    }
+
     /// List available adapters
     pub fn available_adapters() -> Vec<&'static str> {
-        vec!["shell", "rage"]
+        vec!["shell", "shell-v2", "shell-v1", "pipe", "rage", "library"]
     }
 
     /// Get recommended adapter for current environment
@@ -246,6 +314,34 @@ mod tests {
         assert_eq!(AdapterFactory::recommended_adapter(), "shell");
     }
 
+    #[test]
+    fn test_create_adapter_by_name() {
+        assert!(matches!(
+            AdapterFactory::create_adapter("nope"),
+            Err(AgeError::InvalidAdapter(_))
+        ));
+        assert!(AdapterFactory::create_adapter("library").is_err());
+    }
+
+    #[test]
+    fn test_create_from_chain_reports_skipped() {
+        let chain = vec!["library".to_string(), "bogus".to_string()];
+        let Err(AgeError::InvalidAdapter(message)) = AdapterFactory::create_from_chain(&chain)
+        else {
+            panic!("chain of unusable adapters must fail");
+        };
+        assert!(message.contains("library:"));
+        assert!(message.contains("bogus:"));
+
+        // Falls through to a working adapter when age is installed
+        let chain = vec!["library".to_string(), "shell-v2".to_string()];
+        if let Ok(selection) = AdapterFactory::create_from_chain(&chain) {
+            assert_eq!(selection.chosen, "shell-v2");
+            assert_eq!(selection.skipped.len(), 1);
+            assert_eq!(selection.skipped[0].0, "library");
+        }
+    }
+
     #[test]
     fn test_shell_adapter_creation() {
         // This test will fail if Age is not installed, which is expected
@@ -3,11 +3,13 @@
 //! This module extends the adapter pattern to support both file and streaming operations,
 //! providing a unified trait for all encryption backends with enhanced capabilities.
 
-use crate::core::OutputFormat;
+use crate::core::{HeaderFormat, OutputFormat};
 use crate::error::{AgeError, AgeResult};
 use crate::pty::PtyAgeAutomator;
-use crate::core::{Identity, Recipient};
+use crate::core::{Identity, IdentityProviderRegistry, Recipient, ResolvedIdentity};
 use crate::lang;
+use super::progress::{ByteProgressFn, FileGrowthWatcher, ProgressReader};
+use super::supervisor;
 use std::env;
 use std::fs::File;
 use std::io::{Read, Write};
@@ -347,11 +349,15 @@ impl super::v1::AgeAdapter for AdapterV1Compat {
 #[derive(Clone)]
 pub struct ShellAdapterV2 {
     config: Option<crate::core::AgeConfig>,
+    progress: Option<ByteProgressFn>,
 }
 
 impl Default for ShellAdapterV2 {
     fn default() -> Self {
-        Self { config: None }
+        Self {
+            config: None,
+            progress: None,
+        }
     }
 }
 
@@ -369,10 +375,14 @@ impl ShellAdapterV2 {
     pub fn new() -> AgeResult<Self> {
         let automator = PtyAgeAutomator::new()?;
         automator.check_age_binary()?;
-        Ok(Self { config: None })
+        Ok(Self {
+            config: None,
+            progress: None,
+        })
     }
 
     pub fn with_config(config: crate::core::AgeConfig) -> AgeResult<Self> {
+        super::pin::verify_pins(&config)?;
         let automator = if let Ok(automator) = PtyAgeAutomator::with_config(&config) {
             automator
         } else {
@@ -381,9 +391,53 @@ impl ShellAdapterV2 {
         automator.check_age_binary()?;
         Ok(Self {
             config: Some(config),
+            progress: None,
+        })
+    }
+
+    /// Report cumulative input bytes processed by streaming operations
+    pub fn with_progress(mut self, callback: ByteProgressFn) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Run `op` with the input wrapped in a byte-counting reader when progress is enabled
+    fn with_input_progress<T>(
+        &self,
+        input: &mut (dyn Read + Send),
+        op: impl FnOnce(&mut (dyn Read + Send)) -> AgeResult<T>,
+    ) -> AgeResult<T> {
+        match &self.progress {
+            Some(callback) => {
+                let mut tracked = ProgressReader::new(input, Arc::clone(callback));
+                op(&mut tracked)
+            }
+            None => op(input),
+        }
+    }
+
+    /// Sample the staged output file while age runs (temp-file strategy)
+    fn watch_staged_output(&self, path: &Path, total: u64) -> Option<FileGrowthWatcher> {
+        self.progress.as_ref().map(|callback| {
+            FileGrowthWatcher::start(path.to_path_buf(), total, Arc::clone(callback))
         })
     }
 
+    fn report_progress(&self, bytes: u64) {
+        if let Some(callback) = &self.progress {
+            callback(bytes);
+        }
+    }
+
+    /// Resolve `Identity::Provider` references using the adapter configuration
+    fn resolve_provider(&self, identity: &Identity) -> AgeResult<ResolvedIdentity> {
+        let registry = match self.config {
+            Some(ref config) => IdentityProviderRegistry::from_config(config),
+            None => IdentityProviderRegistry::default(),
+        };
+        registry.resolve(identity)
+    }
+
     fn get_automator(&self) -> AgeResult<PtyAgeAutomator> {
         if let Some(ref config) = self.config {
             PtyAgeAutomator::with_config(config)
@@ -433,11 +487,7 @@ impl ShellAdapterV2 {
         cmd.arg(output);
         cmd.arg(input);
 
-        let status = cmd.status().map_err(|e| AgeError::ProcessExecutionFailed {
-            command: "age".into(),
-            exit_code: None,
-            stderr: e.to_string(),
-        })?;
+        let status = supervisor::status(&mut cmd, "encrypt_with_recipients")?;
 
         if status.success() {
             Ok(())
@@ -464,11 +514,7 @@ impl ShellAdapterV2 {
         cmd.arg(output);
         cmd.arg(input);
 
-        let status = cmd.status().map_err(|e| AgeError::ProcessExecutionFailed {
-            command: "age".into(),
-            exit_code: None,
-            stderr: e.to_string(),
-        })?;
+        let status = supervisor::status(&mut cmd, "decrypt_with_identity_file")?;
 
         if status.success() {
             Ok(())
@@ -491,6 +537,11 @@ impl AgeAdapterV2 for ShellAdapterV2 {
         recipients: Option<&[Recipient]>,
         format: OutputFormat,
     ) -> AgeResult<()> {
+        if let Identity::Provider(_) = identity {
+            let resolved = self.resolve_provider(identity)?;
+            return self.encrypt_file(input, output, resolved.identity(), recipients, format);
+        }
+
         if let Some(recips) = recipients {
             if !recips.is_empty() {
                 return self.encrypt_with_recipients(input, output, recips, format);
@@ -504,6 +555,12 @@ impl AgeAdapterV2 for ShellAdapterV2 {
                     "PromptPassphrase not supported in ShellAdapterV2".into(),
                 ));
             }
+            Identity::Provider(_) => {
+                return Err(AgeError::InvalidOperation {
+                    operation: "encrypt_file".into(),
+                    reason: lang::ERR_IDENTITY_PROVIDER_UNRESOLVED.into(),
+                });
+            }
             Identity::IdentityFile(_) | Identity::SshKey(_) => {
                 return Err(AgeError::AdapterNotImplemented(
                     "Identity-based encryption not yet implemented".into(),
@@ -516,6 +573,10 @@ impl AgeAdapterV2 for ShellAdapterV2 {
 
     fn decrypt_file(&self, input: &Path, output: &Path, identity: &Identity) -> AgeResult<()> {
         match identity {
+            Identity::Provider(_) => {
+                let resolved = self.resolve_provider(identity)?;
+                self.decrypt_file(input, output, resolved.identity())
+            }
             Identity::Passphrase(pass) => {
                 let automator = self.get_automator()?;
                 automator.decrypt(input, output, pass)
@@ -537,6 +598,11 @@ impl AgeAdapterV2 for ShellAdapterV2 {
         recipients: Option<&[Recipient]>,
         format: OutputFormat,
     ) -> AgeResult<u64> {
+        if let Identity::Provider(_) = identity {
+            let resolved = self.resolve_provider(identity)?;
+            return self.encrypt_stream(input, output, resolved.identity(), recipients, format);
+        }
+
         let strategy = streaming_strategy_from_env();
 
         // Handle identity-based encryption by deriving recipient from identity file (CAGE-12)
@@ -571,13 +637,15 @@ impl AgeAdapterV2 for ShellAdapterV2 {
         if matches!(strategy, StreamingStrategy::Pipe | StreamingStrategy::Auto) {
             // Recipients-based pipe streaming
             if can_use_pipe_recipients {
-                match self.encrypt_stream_pipe(input, output, recipients_list, format) {
+                match self.with_input_progress(input, |input| {
+                    self.encrypt_stream_pipe(input, output, recipients_list, format)
+                }) {
                     Ok(bytes) => return Ok(bytes),
                     Err(err) => {
                         if strategy == StreamingStrategy::Pipe {
                             return Err(err);
                         } else {
-                            eprintln!("[cage] {} ({err})", lang::WARN_STREAM_PIPE_FALLBACK);
+                            tracing::warn!("{} ({err})", lang::WARN_STREAM_PIPE_FALLBACK);
                         }
                     }
                 }
@@ -585,13 +653,17 @@ impl AgeAdapterV2 for ShellAdapterV2 {
             // Passphrase-based pipe streaming (CAGE-12b)
             else if can_use_pipe_passphrase {
                 if let Identity::Passphrase(pass) = identity {
-                    match self.encrypt_stream_pipe_passphrase(input, output, pass, format) {
+                    match self.with_input_progress(input, |input| {
+                        self.encrypt_stream_pipe_passphrase(input, output, pass, format)
+                    }) {
                         Ok(bytes) => return Ok(bytes),
                         Err(err) => {
                             if strategy == StreamingStrategy::Pipe {
                                 return Err(err);
                             } else {
-                                eprintln!("[cage] Passphrase pipe failed, falling back to temp file ({err})");
+                                tracing::warn!(
+                                    "Passphrase pipe failed, falling back to temp file ({err})"
+                                );
                             }
                         }
                     }
@@ -609,6 +681,11 @@ impl AgeAdapterV2 for ShellAdapterV2 {
         output: &mut (dyn Write + Send),
         identity: &Identity,
     ) -> AgeResult<u64> {
+        if let Identity::Provider(_) = identity {
+            let resolved = self.resolve_provider(identity)?;
+            return self.decrypt_stream(input, output, resolved.identity());
+        }
+
         let strategy = streaming_strategy_from_env();
 
         // Check if we can use pipe streaming
@@ -629,13 +706,15 @@ impl AgeAdapterV2 for ShellAdapterV2 {
         if matches!(strategy, StreamingStrategy::Pipe | StreamingStrategy::Auto) {
             // Identity-based pipe streaming
             if can_use_pipe_identity {
-                match self.decrypt_stream_pipe(input, output, identity) {
+                match self.with_input_progress(input, |input| {
+                    self.decrypt_stream_pipe(input, output, identity)
+                }) {
                     Ok(bytes) => return Ok(bytes),
                     Err(err) => {
                         if strategy == StreamingStrategy::Pipe {
                             return Err(err);
                         } else {
-                            eprintln!("[cage] {} ({err})", lang::WARN_STREAM_PIPE_FALLBACK);
+                            tracing::warn!("{} ({err})", lang::WARN_STREAM_PIPE_FALLBACK);
                         }
                     }
                 }
@@ -643,13 +722,17 @@ impl AgeAdapterV2 for ShellAdapterV2 {
             // Passphrase-based pipe streaming (CAGE-12b)
             else if can_use_pipe_passphrase {
                 if let Identity::Passphrase(pass) = identity {
-                    match self.decrypt_stream_pipe_passphrase(input, output, pass) {
+                    match self.with_input_progress(input, |input| {
+                        self.decrypt_stream_pipe_passphrase(input, output, pass)
+                    }) {
                         Ok(bytes) => return Ok(bytes),
                         Err(err) => {
                             if strategy == StreamingStrategy::Pipe {
                                 return Err(err);
                             } else {
-                                eprintln!("[cage] Passphrase pipe failed, falling back to temp file ({err})");
+                                tracing::warn!(
+                                    "Passphrase pipe failed, falling back to temp file ({err})"
+                                );
                             }
                         }
                     }
@@ -696,6 +779,7 @@ impl AgeAdapterV2 for ShellAdapterV2 {
             Identity::PromptPassphrase => Err(AgeError::AdapterNotImplemented(
                 "PromptPassphrase not supported in ShellAdapterV2".into(),
             )),
+            Identity::Provider(_) => self.resolve_provider(identity).map(|_| ()),
         }
     }
 
@@ -745,7 +829,11 @@ impl AgeAdapterV2 for ShellAdapterV2 {
     }
 
     fn is_encrypted(&self, file: &Path) -> bool {
-        file.extension().map_or(false, |e| e == "cage")
+        // Judge by the header; fall back to the extension when the file cannot be read
+        match HeaderFormat::detect_file(file) {
+            Ok(format) => format.is_some(),
+            Err(_) => file.extension().map_or(false, |e| e == "cage"),
+        }
     }
 
     fn health_check(&self) -> AgeResult<HealthStatus> {
@@ -941,6 +1029,7 @@ impl ShellAdapterV2 {
         })?;
 
         let output_path = temp_dir.path().join("stream_output");
+        let watcher = self.watch_staged_output(&output_path, bytes_copied);
 
         if let Some(recips) = recipients {
             if !recips.is_empty() {
@@ -967,6 +1056,8 @@ impl ShellAdapterV2 {
             };
             self.encrypt_with_passphrase(&input_path, &output_path, &pass, format)?;
         }
+        drop(watcher);
+        self.report_progress(bytes_copied);
 
         let mut encrypted = File::open(&output_path)
             .map_err(|e| AgeError::file_error("open", output_path.clone(), e))?;
@@ -997,14 +1088,11 @@ impl ShellAdapterV2 {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let mut child = cmd.spawn().map_err(|e| AgeError::ProcessExecutionFailed {
-            command: "age".into(),
-            exit_code: None,
-            stderr: e.to_string(),
-        })?;
+        let mut child = supervisor::spawn(&mut cmd, "encrypt_stream_pipe")?;
 
         let mut child_stdin =
             child
+                .child_mut()
                 .stdin
                 .take()
                 .ok_or_else(|| AgeError::ProcessExecutionFailed {
@@ -1012,15 +1100,17 @@ impl ShellAdapterV2 {
                     exit_code: None,
                     stderr: "Failed to open stdin for age".into(),
                 })?;
-        let child_stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| AgeError::ProcessExecutionFailed {
-                command: "age".into(),
-                exit_code: None,
-                stderr: "Failed to open stdout for age".into(),
-            })?;
-        let mut stderr_pipe = child.stderr.take();
+        let child_stdout =
+            child
+                .child_mut()
+                .stdout
+                .take()
+                .ok_or_else(|| AgeError::ProcessExecutionFailed {
+                    command: "age".into(),
+                    exit_code: None,
+                    stderr: "Failed to open stdout for age".into(),
+                })?;
+        let mut stderr_pipe = child.child_mut().stderr.take();
         let mut stderr_buffer = Vec::new();
 
         let bytes_copied = thread::scope(|scope| -> AgeResult<u64> {
@@ -1069,11 +1159,7 @@ impl ShellAdapterV2 {
             Ok(bytes_written)
         })?;
 
-        let status = child.wait().map_err(|e| AgeError::ProcessExecutionFailed {
-            command: "age".into(),
-            exit_code: None,
-            stderr: e.to_string(),
-        })?;
+        let status = child.wait()?;
 
         if !status.success() {
             let stderr_msg = if !stderr_buffer.is_empty() {
@@ -1118,6 +1204,7 @@ impl ShellAdapterV2 {
         })?;
 
         let output_path = temp_dir.path().join("stream_output");
+        let watcher = self.watch_staged_output(&output_path, bytes_copied);
 
         match identity {
             Identity::Passphrase(pass) => {
@@ -1132,8 +1219,17 @@ impl ShellAdapterV2 {
                     "PromptPassphrase not supported in ShellAdapterV2".into(),
                 ));
             }
+            Identity::Provider(_) => {
+                return Err(AgeError::InvalidOperation {
+                    operation: "decrypt_stream".into(),
+                    reason: lang::ERR_IDENTITY_PROVIDER_UNRESOLVED.into(),
+                });
+            }
         }
 
+        drop(watcher);
+        self.report_progress(bytes_copied);
+
         let mut decrypted = File::open(&output_path)
             .map_err(|e| AgeError::file_error("open", output_path.clone(), e))?;
         std::io::copy(&mut decrypted, output).map_err(|e| AgeError::IoError {
@@ -1163,6 +1259,12 @@ impl ShellAdapterV2 {
                     "Passphrase-based streaming requires PTY; pipe strategy unavailable".into(),
                 ))
             }
+            Identity::Provider(_) => {
+                return Err(AgeError::InvalidOperation {
+                    operation: "decrypt_stream_pipe".into(),
+                    reason: lang::ERR_IDENTITY_PROVIDER_UNRESOLVED.into(),
+                })
+            }
         };
 
         let mut cmd = Command::new("age");
@@ -1173,14 +1275,11 @@ impl ShellAdapterV2 {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let mut child = cmd.spawn().map_err(|e| AgeError::ProcessExecutionFailed {
-            command: "age".into(),
-            exit_code: None,
-            stderr: e.to_string(),
-        })?;
+        let mut child = supervisor::spawn(&mut cmd, "decrypt_stream_pipe")?;
 
         let mut child_stdin =
             child
+                .child_mut()
                 .stdin
                 .take()
                 .ok_or_else(|| AgeError::ProcessExecutionFailed {
@@ -1188,15 +1287,17 @@ impl ShellAdapterV2 {
                     exit_code: None,
                     stderr: "Failed to open stdin for age".into(),
                 })?;
-        let child_stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| AgeError::ProcessExecutionFailed {
-                command: "age".into(),
-                exit_code: None,
-                stderr: "Failed to open stdout for age".into(),
-            })?;
-        let mut stderr_pipe = child.stderr.take();
+        let child_stdout =
+            child
+                .child_mut()
+                .stdout
+                .take()
+                .ok_or_else(|| AgeError::ProcessExecutionFailed {
+                    command: "age".into(),
+                    exit_code: None,
+                    stderr: "Failed to open stdout for age".into(),
+                })?;
+        let mut stderr_pipe = child.child_mut().stderr.take();
         let mut stderr_buffer = Vec::new();
 
         let bytes_copied = thread::scope(|scope| -> AgeResult<u64> {
@@ -1245,11 +1346,7 @@ impl ShellAdapterV2 {
             Ok(bytes_written)
         })?;
 
-        let status = child.wait().map_err(|e| AgeError::ProcessExecutionFailed {
-            command: "age".into(),
-            exit_code: None,
-            stderr: e.to_string(),
-        })?;
+        let status = child.wait()?;
 
         if !status.success() {
             let stderr_msg = if !stderr_buffer.is_empty() {
@@ -1266,6 +1363,34 @@ impl ShellAdapterV2 {
 
         Ok(bytes_copied)
     }
+
+    /// Decrypt a stream without ever staging plaintext on disk.
+    ///
+    /// Only identity files and SSH keys are accepted because passphrase decryption
+    /// requires the PTY path, which round-trips through temporary files.
+    pub fn decrypt_stream_no_spill(
+        &self,
+        input: &mut (dyn Read + Send),
+        output: &mut (dyn Write + Send),
+        identity: &Identity,
+    ) -> AgeResult<u64> {
+        match identity {
+            Identity::IdentityFile(_) | Identity::SshKey(_) => self
+                .with_input_progress(input, |input| {
+                    self.decrypt_stream_pipe(input, output, identity)
+                }),
+            Identity::Provider(_) => {
+                let resolved = self.resolve_provider(identity)?;
+                self.decrypt_stream_no_spill(input, output, resolved.identity())
+            }
+            Identity::Passphrase(_) | Identity::PromptPassphrase => {
+                Err(AgeError::InvalidOperation {
+                    operation: "decrypt_stream_no_spill".into(),
+                    reason: lang::ERR_STREAM_PIPE_REQUIRES_IDENTITY.into(),
+                })
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -0,0 +1,366 @@
+//! Anomaly Flags - Heuristics marking decryptions that may indicate key misuse
+//!
+//! Decryption events run through an `AnomalyDetector` before they are logged:
+//!
+//! - `burst`: more than `audit_unlock_limit` decryptions within `audit_unlock_window`
+//! - `off_hours`: a decryption outside `audit_unlock_hours` (local time, `HH-HH`; a range
+//!   such as `22-06` wraps midnight)
+//!
+//! Each decryption event carries a `severity` (`info`, `warning` with one flag,
+//! `critical` with both) and its `anomalies`, and flagged events are logged at WARN or
+//! ERROR so monitoring can alert on them. Both heuristics are off unless configured.
+//!
+//! Every `cage` run is its own process, so the burst counter keeps recent decryption
+//! times per user: `$CAGE_AUDIT_STATE_FILE`, else `$XDG_DATA_HOME/cage/unlock-rate.json`,
+//! else `~/.local/share/cage/unlock-rate.json`. A batch of decryptions is recorded with
+//! one load and save, and only the newest `limit + 1` times are kept. The state is best
+//! effort; a file that cannot be read or written only narrows the count to the current
+//! run.
+
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use chrono::{DateTime, Local, Timelike, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+/// How urgently an audit event deserves attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    /// Severity of an event raising `anomalies`
+    pub fn of(anomalies: &[Anomaly]) -> Self {
+        match anomalies.len() {
+            0 => Self::Info,
+            1 => Self::Warning,
+            _ => Self::Critical,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// Audit log level events of this severity are written at
+    pub fn level(self) -> &'static str {
+        match self {
+            Self::Info => "INFO",
+            Self::Warning => "WARN",
+            Self::Critical => "ERROR",
+        }
+    }
+}
+
+/// Heuristic raised by a decryption
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anomaly {
+    /// Unusually many decryptions in a short window
+    Burst,
+    /// Decryption outside the configured hours
+    OffHours,
+}
+
+impl Anomaly {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Burst => "burst",
+            Self::OffHours => "off_hours",
+        }
+    }
+}
+
+/// Hours of the day decryptions are expected in, `start` inclusive to `end` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnlockHours {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl UnlockHours {
+    /// Parse `HH-HH`, e.g. `08-18` or `22-06`
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        let invalid = |reason: &str| AgeError::ConfigurationError {
+            parameter: "audit.unlock_hours".to_string(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        };
+        let (start, end) = value
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| invalid("Expected HH-HH, e.g. 08-18"))?;
+        let hour = |part: &str| {
+            part.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|hour| *hour <= 24)
+                .ok_or_else(|| invalid("Hours must be 0-24"))
+        };
+        let (start, end) = (hour(start)? % 24, hour(end)? % 24);
+        if start == end {
+            return Err(invalid("Start and end hour must differ"));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Whether `hour` (0-23) falls inside the range
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start < self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+impl fmt::Display for UnlockHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.start, self.end)
+    }
+}
+
+/// Applies the configured heuristics to decryptions
+#[derive(Debug)]
+pub struct AnomalyDetector {
+    limit: Option<usize>,
+    window: Duration,
+    hours: Option<UnlockHours>,
+    state_path: Option<PathBuf>,
+    /// Decryption times seen by this process, used when the state file is unavailable
+    recent: Mutex<Vec<DateTime<Utc>>>,
+}
+
+impl AnomalyDetector {
+    /// Detector for the configured heuristics; `None` when none is enabled
+    pub fn from_config(config: &AgeConfig) -> Option<Self> {
+        if config.audit_unlock_limit.is_none() && config.audit_unlock_hours.is_none() {
+            return None;
+        }
+        Some(Self {
+            limit: config.audit_unlock_limit,
+            window: config.audit_unlock_window,
+            hours: config.audit_unlock_hours,
+            state_path: Self::default_state_path(),
+            recent: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Keep the burst counter in `path` instead of the default location
+    pub fn with_state_path(mut self, path: PathBuf) -> Self {
+        self.state_path = Some(path);
+        self
+    }
+
+    /// State file location from the environment; `None` without `$HOME` or an override
+    pub fn default_state_path() -> Option<PathBuf> {
+        if let Ok(file) = std::env::var("CAGE_AUDIT_STATE_FILE") {
+            if !file.is_empty() {
+                return Some(PathBuf::from(file));
+            }
+        }
+        let base = match std::env::var("XDG_DATA_HOME") {
+            Ok(xdg) if !xdg.is_empty() => PathBuf::from(xdg),
+            _ => PathBuf::from(std::env::var("HOME").ok()?).join(".local/share"),
+        };
+        Some(base.join("cage").join("unlock-rate.json"))
+    }
+
+    /// Record a decryption at `now` and return the anomalies it raises
+    pub fn record(&self, now: DateTime<Utc>) -> Vec<Anomaly> {
+        self.record_many(now, 1).pop().unwrap_or_default()
+    }
+
+    /// Record `count` decryptions at `now`, returning the anomalies each one raises
+    pub fn record_many(&self, now: DateTime<Utc>, count: usize) -> Vec<Vec<Anomaly>> {
+        let off_hours = self
+            .hours
+            .is_some_and(|hours| !hours.contains(now.with_timezone(&Local).hour()));
+        let before = match (self.limit, count) {
+            (Some(limit), 1..) => Some((limit, self.count_recent(now, count, limit))),
+            _ => None,
+        };
+        (1..=count)
+            .map(|nth| {
+                let mut anomalies = Vec::new();
+                if let Some((limit, before)) = before {
+                    if before + nth > limit {
+                        anomalies.push(Anomaly::Burst);
+                    }
+                }
+                if off_hours {
+                    anomalies.push(Anomaly::OffHours);
+                }
+                anomalies
+            })
+            .collect()
+    }
+
+    /// Add `count` decryptions at `now` and return how many were already within the
+    /// window; at most `limit + 1` times are kept, enough to flag the next one
+    fn count_recent(&self, now: DateTime<Utc>, count: usize, limit: usize) -> usize {
+        let mut recent = self
+            .recent
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(path) = &self.state_path {
+            match load_state(path) {
+                Ok(stored) => *recent = stored,
+                Err(e) => tracing::warn!("Unlock rate state unavailable: {}", e),
+            }
+        }
+        let window = chrono::Duration::from_std(self.window).unwrap_or(chrono::Duration::MAX);
+        recent.retain(|at| now.signed_duration_since(*at) < window);
+        recent.sort();
+        let before = recent.len();
+        let keep = limit.saturating_add(1);
+        recent.extend(std::iter::repeat(now).take(count.min(keep)));
+        if recent.len() > keep {
+            recent.drain(..recent.len() - keep);
+        }
+        if let Some(path) = &self.state_path {
+            if let Err(e) = save_state(path, &recent) {
+                tracing::warn!("Failed to save unlock rate state: {}", e);
+            }
+        }
+        before
+    }
+}
+
+/// Recorded decryption times; a missing file has none
+fn load_state(path: &Path) -> AgeResult<Vec<DateTime<Utc>>> {
+    match fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data).map_err(|e| AgeError::InvalidOperation {
+            operation: "read_unlock_rate".to_string(),
+            reason: format!("{}: {}", path.display(), e),
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(AgeError::file_error(
+            "read_unlock_rate",
+            path.to_path_buf(),
+            e,
+        )),
+    }
+}
+
+/// Write the decryption times, replacing the previous file in one rename
+///
+/// Each writer stages into its own temporary file, so concurrent runs never rename
+/// one another's half-written state.
+fn save_state(path: &Path, recent: &[DateTime<Utc>]) -> AgeResult<()> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)
+        .map_err(|e| AgeError::file_error("create_unlock_rate_dir", dir.to_path_buf(), e))?;
+    let json = serde_json::to_vec(recent).map_err(|e| AgeError::InvalidOperation {
+        operation: "save_unlock_rate".to_string(),
+        reason: e.to_string(),
+    })?;
+    let mut staged = NamedTempFile::new_in(dir)
+        .map_err(|e| AgeError::file_error("write_unlock_rate", dir.to_path_buf(), e))?;
+    staged
+        .write_all(&json)
+        .map_err(|e| AgeError::file_error("write_unlock_rate", staged.path().to_path_buf(), e))?;
+    staged
+        .persist(path)
+        .map_err(|e| AgeError::file_error("write_unlock_rate", path.to_path_buf(), e.error))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_unlock_hours_parse_and_wrap() {
+        let office = UnlockHours::parse("08-18").unwrap();
+        assert!(office.contains(8) && office.contains(17));
+        assert!(!office.contains(18) && !office.contains(3));
+        assert_eq!(office.to_string(), "08-18");
+
+        let night = UnlockHours::parse("22-06").unwrap();
+        assert!(night.contains(23) && night.contains(0) && night.contains(5));
+        assert!(!night.contains(6) && !night.contains(12));
+
+        assert_eq!(UnlockHours::parse("0-24").unwrap().start, 0);
+        assert!(UnlockHours::parse("9-9").is_err());
+        assert!(UnlockHours::parse("8-25").is_err());
+        assert!(UnlockHours::parse("eight").is_err());
+    }
+
+    #[test]
+    fn test_burst_counted_across_detectors() {
+        let temp = TempDir::new().unwrap();
+        let state = temp.path().join("unlock-rate.json");
+        let config = AgeConfig {
+            audit_unlock_limit: Some(2),
+            audit_unlock_window: Duration::from_secs(600),
+            ..AgeConfig::default()
+        };
+        let detector = || {
+            AnomalyDetector::from_config(&config)
+                .unwrap()
+                .with_state_path(state.clone())
+        };
+        let start = Utc::now();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+
+        assert!(detector().record(at(0)).is_empty());
+        assert!(detector().record(at(1)).is_empty());
+        // A later process still sees the first two
+        assert_eq!(detector().record(at(2)), vec![Anomaly::Burst]);
+        // Once the window has passed the count starts over
+        assert!(detector().record(at(700)).is_empty());
+
+        assert_eq!(Severity::of(&[]), Severity::Info);
+        assert_eq!(Severity::of(&[Anomaly::Burst]).level(), "WARN");
+        assert_eq!(
+            Severity::of(&[Anomaly::Burst, Anomaly::OffHours]),
+            Severity::Critical
+        );
+        assert!(AnomalyDetector::from_config(&AgeConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_record_many_flags_within_batch_and_caps_state() {
+        let temp = TempDir::new().unwrap();
+        let state = temp.path().join("unlock-rate.json");
+        let config = AgeConfig {
+            audit_unlock_limit: Some(2),
+            audit_unlock_window: Duration::from_secs(600),
+            ..AgeConfig::default()
+        };
+        let detector = AnomalyDetector::from_config(&config)
+            .unwrap()
+            .with_state_path(state.clone());
+        let now = Utc::now();
+
+        let flags = detector.record_many(now, 50);
+        assert_eq!(flags.len(), 50);
+        assert!(flags[0].is_empty() && flags[1].is_empty());
+        assert!(flags[2..].iter().all(|f| f == &vec![Anomaly::Burst]));
+        assert_eq!(load_state(&state).unwrap().len(), 3);
+
+        // The capped state still flags the next decryption
+        assert_eq!(detector.record(now), vec![Anomaly::Burst]);
+        assert_eq!(load_state(&state).unwrap().len(), 3);
+        assert!(detector.record_many(now, 0).is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+//! Audit Chain - Tamper evidence for audit log files
+//!
+//! When a signing key is configured, every line written to the audit log file gets a
+//! ` #chain=<hex>` suffix holding `HMAC-SHA256(key, previous_tag || line)`. Editing,
+//! reordering or deleting interior lines breaks the tag after the change, which
+//! `cage audit verify` reports. The first line chains from an all-zero tag.
+//!
+//! Nothing records where the chain ends, so truncating the log (dropping its last lines)
+//! leaves a shorter chain that still verifies; keep the log on append-only storage or
+//! ship it to a remote output when that matters.
+//!
+//! Each line chains from the last tagged line in the file at the time it is written, so
+//! several loggers in one process share a single chain. Signing an existing unsigned log
+//! first appends a signed [`PREFIX_MARKER`] line recording how many unsigned lines
+//! precede it; the verifier accepts exactly that prefix. Appenders in separate processes
+//! are not serialized and can still race into a break.
+
+use crate::error::{AgeError, AgeResult};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Separator between a log line and its chain tag
+pub const CHAIN_MARKER: &str = " #chain=";
+
+/// First chained line of a log that already held unsigned lines: `#chain-start
+/// unsigned_prefix=<count>`
+pub const PREFIX_MARKER: &str = "#chain-start unsigned_prefix=";
+
+/// Bytes read per step when looking for the last tag from the end of the log
+const TAIL_BLOCK: u64 = 8 * 1024;
+
+/// Tag preceding the first line of a log
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// HMAC chain signer for one audit log file
+#[derive(Clone)]
+pub struct AuditChain {
+    key: Vec<u8>,
+    log_path: PathBuf,
+    /// Shared by every chain on the same file in this process
+    writer: Arc<Mutex<()>>,
+}
+
+impl std::fmt::Debug for AuditChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the key
+        f.debug_struct("AuditChain")
+            .field("log_path", &self.log_path)
+            .finish()
+    }
+}
+
+impl AuditChain {
+    /// Sign lines appended to `log_path`, continuing from its last chained line
+    pub fn resume(key: Vec<u8>, log_path: &Path) -> AgeResult<Self> {
+        if key.is_empty() {
+            return Err(AgeError::ConfigurationError {
+                parameter: "audit_signing_key".to_string(),
+                value: String::new(),
+                reason: "Signing key must not be empty".to_string(),
+            });
+        }
+
+        Ok(Self {
+            key,
+            log_path: log_path.to_path_buf(),
+            writer: writer_lock(log_path),
+        })
+    }
+
+    /// Tag `line` (which must not contain a newline) against the last chained line of the
+    /// log and hand it to `write` with its newline.
+    ///
+    /// `write` must append to the log file; it runs under the file's writer lock so tags
+    /// land in file order. The first signed line of a non-empty unsigned log is preceded
+    /// by a [`PREFIX_MARKER`] line.
+    pub fn append(
+        &self,
+        line: &str,
+        mut write: impl FnMut(&str) -> AgeResult<()>,
+    ) -> AgeResult<()> {
+        let _writer = self.writer.lock().unwrap_or_else(|p| p.into_inner());
+        let previous = match last_tag(&self.log_path)? {
+            Some(tag) => tag,
+            None => match unsigned_lines(&self.log_path)? {
+                0 => GENESIS.to_string(),
+                count => {
+                    let marker = format!("{}{}", PREFIX_MARKER, count);
+                    let tag = chain_tag(&self.key, GENESIS, &marker);
+                    write(&format!("{}{}{}\n", marker, CHAIN_MARKER, tag))?;
+                    tag
+                }
+            },
+        };
+        let tag = chain_tag(&self.key, &previous, line);
+        write(&format!("{}{}{}\n", line, CHAIN_MARKER, tag))
+    }
+}
+
+/// Process-wide writer lock for `path`
+fn writer_lock(path: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let key = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .entry(key)
+        .or_default()
+        .clone()
+}
+
+/// Tag of the last chained line of `path`, read backwards from the end of the file
+fn last_tag(path: &Path) -> AgeResult<Option<String>> {
+    let read_error = |e| AgeError::file_error("read", path.to_path_buf(), e);
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(read_error(e)),
+    };
+
+    let mut end = file.metadata().map_err(read_error)?.len();
+    // Start of the line that straddles the previous block boundary
+    let mut carry = Vec::new();
+    while end > 0 {
+        let start = end.saturating_sub(TAIL_BLOCK);
+        let mut block = vec![0u8; (end - start) as usize];
+        file.seek(SeekFrom::Start(start)).map_err(read_error)?;
+        file.read_exact(&mut block).map_err(read_error)?;
+        block.extend_from_slice(&carry);
+
+        let mut lines: Vec<&[u8]> = block.split(|b| *b == b'\n').collect();
+        // Unless this block starts the file, its first line may be cut off
+        let partial = if start > 0 { lines.remove(0) } else { &[][..] };
+        for line in lines.iter().rev() {
+            let line = String::from_utf8_lossy(line);
+            if let Some((_, tag)) = line.rsplit_once(CHAIN_MARKER) {
+                return Ok(Some(tag.to_string()));
+            }
+        }
+        carry = partial.to_vec();
+        end = start;
+    }
+    Ok(None)
+}
+
+/// Number of non-empty lines in `path` (0 when it does not exist)
+fn unsigned_lines(path: &Path) -> AgeResult<usize> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(AgeError::file_error("read", path.to_path_buf(), e)),
+    };
+    let mut count = 0;
+    for line in BufReader::new(file).split(b'\n') {
+        let line = line.map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        if !line.is_empty() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn chain_tag(key: &[u8], previous: &str, line: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(previous.as_bytes());
+    mac.update(line.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Outcome of verifying an audit log chain
+#[derive(Debug, Clone, Default)]
+pub struct ChainReport {
+    /// Lines whose tag matched
+    pub verified_lines: usize,
+    /// Lines without a chain tag (written while signing was disabled)
+    pub unsigned_lines: usize,
+    /// Unsigned lines before a matching [`PREFIX_MARKER`]; these do not count as
+    /// `unsigned_lines`
+    pub prefix_lines: usize,
+    /// 1-based line numbers whose tag did not match
+    pub broken_lines: Vec<usize>,
+}
+
+impl ChainReport {
+    pub fn is_intact(&self) -> bool {
+        self.broken_lines.is_empty() && self.unsigned_lines == 0
+    }
+}
+
+/// Verify every line of `log_path` against `key`.
+///
+/// Detects modified, reordered and interior deleted lines; lines dropped from the end
+/// of the log go unnoticed (see the module docs).
+///
+/// After a broken line the chain re-synchronizes on the stored tag, so each tampered
+/// region is reported once rather than poisoning the rest of the file. Unsigned lines
+/// before the first chained line are accepted when that line is a valid
+/// [`PREFIX_MARKER`] recording exactly their number.
+pub fn verify_log(log_path: &Path, key: &[u8]) -> AgeResult<ChainReport> {
+    let file = fs::File::open(log_path)
+        .map_err(|e| AgeError::file_error("read", log_path.to_path_buf(), e))?;
+
+    let mut report = ChainReport::default();
+    let mut previous = GENESIS.to_string();
+    let mut chained = false;
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| AgeError::file_error("read", log_path.to_path_buf(), e))?;
+        if line.is_empty() {
+            continue;
+        }
+        let Some((content, tag)) = line.rsplit_once(CHAIN_MARKER) else {
+            report.unsigned_lines += 1;
+            continue;
+        };
+        if chain_tag(key, &previous, content) == tag {
+            report.verified_lines += 1;
+            let prefix = content
+                .strip_prefix(PREFIX_MARKER)
+                .and_then(|count| count.parse::<usize>().ok());
+            if !chained && prefix == Some(report.unsigned_lines) {
+                report.prefix_lines = report.unsigned_lines;
+                report.unsigned_lines = 0;
+            }
+        } else {
+            report.broken_lines.push(index + 1);
+        }
+        chained = true;
+        previous = tag.to_string();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn append(chain: &AuditChain, log: &Path, line: &str) {
+        chain
+            .append(line, |entry| {
+                let mut file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(log)
+                    .unwrap();
+                std::io::Write::write_all(&mut file, entry.as_bytes()).unwrap();
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_chain_detects_tampering() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("audit.log");
+        let key = b"test-key".to_vec();
+
+        // Two chains on one file interleave without forking it
+        let first = AuditChain::resume(key.clone(), &log).unwrap();
+        let second = AuditChain::resume(key.clone(), &log).unwrap();
+        append(&first, &log, "first");
+        append(&second, &log, "second");
+        append(&first, &log, "third");
+        let contents = fs::read_to_string(&log).unwrap();
+
+        let report = verify_log(&log, &key).unwrap();
+        assert_eq!(report.verified_lines, 3);
+        assert!(report.is_intact());
+
+        fs::write(&log, contents.replace("second", "edited")).unwrap();
+        let report = verify_log(&log, &key).unwrap();
+        assert_eq!(report.broken_lines, vec![2]);
+
+        let report = verify_log(&log, b"wrong-key").unwrap();
+        assert_eq!(report.broken_lines.len(), 3);
+    }
+
+    #[test]
+    fn test_chain_accepts_marked_unsigned_prefix() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("audit.log");
+        let key = b"test-key".to_vec();
+        fs::write(&log, "legacy one\nlegacy two\n").unwrap();
+
+        let chain = AuditChain::resume(key.clone(), &log).unwrap();
+        append(&chain, &log, "signed");
+        append(&chain, &log, "signed again");
+        let contents = fs::read_to_string(&log).unwrap();
+        assert_eq!(contents.matches(PREFIX_MARKER).count(), 1);
+
+        let report = verify_log(&log, &key).unwrap();
+        assert_eq!(report.prefix_lines, 2);
+        assert_eq!(report.verified_lines, 3);
+        assert!(report.is_intact());
+
+        // A line slipped into the prefix no longer matches the recorded count
+        fs::write(&log, format!("inserted\n{}", contents)).unwrap();
+        let report = verify_log(&log, &key).unwrap();
+        assert_eq!(report.unsigned_lines, 3);
+        assert!(!report.is_intact());
+    }
+}
@@ -5,7 +5,17 @@
 //!
 //! Security Guardian: Edgar - Production security and audit framework
 
-use super::core::TelemetryFormat;
+pub mod anomaly;
+pub mod chain;
+pub mod output;
+pub mod query;
+
+pub use anomaly::{Anomaly, AnomalyDetector, Severity, UnlockHours};
+pub use chain::{verify_log, AuditChain, ChainReport};
+pub use output::{AuditOutput, OutputSpec, OutputTarget};
+pub use query::{AuditEvent, AuditQuery};
+
+use super::core::{AgeConfig, TelemetryFormat};
 use super::error::{AgeError, AgeResult};
 use super::forge::{OperationResult, RepositoryStatus};
 #[allow(unused_imports)]
@@ -19,18 +29,22 @@ use std::path::{Path, PathBuf};
 pub struct AuditLogger {
     component: String,
     log_file: Option<std::fs::File>,
+    log_path: Option<PathBuf>,
     telemetry_format: TelemetryFormat,
+    chain: Option<AuditChain>,
+    outputs: Vec<AuditOutput>,
+    anomaly: Option<AnomalyDetector>,
 }
 
 impl AuditLogger {
     /// Create new audit logger for specified component
     pub fn new(log_path_opt: Option<PathBuf>) -> AgeResult<Self> {
-        let log_file = if let Some(log_path) = log_path_opt {
+        let log_file = if let Some(log_path) = &log_path_opt {
             Some(
                 OpenOptions::new()
                     .create(true)
                     .append(true)
-                    .open(&log_path)
+                    .open(log_path)
                     .map_err(|e| AgeError::file_error("open", log_path.to_path_buf(), e))?,
             )
         } else {
@@ -40,7 +54,11 @@ impl AuditLogger {
         Ok(Self {
             component: "cage_automation".to_string(),
             log_file,
+            log_path: log_path_opt,
             telemetry_format: TelemetryFormat::default(),
+            chain: None,
+            outputs: Vec::new(),
+            anomaly: None,
         })
     }
 
@@ -55,7 +73,11 @@ impl AuditLogger {
         Ok(Self {
             component: "cage_automation".to_string(),
             log_file: Some(log_file),
+            log_path: Some(log_path.to_path_buf()),
             telemetry_format: TelemetryFormat::default(),
+            chain: None,
+            outputs: Vec::new(),
+            anomaly: None,
         })
     }
 
@@ -66,6 +88,56 @@ impl AuditLogger {
         Ok(logger)
     }
 
+    /// Audit logger set up from `config`: log file and format, chain signing key
+    /// (`$CAGE_AUDIT_KEY_FILE` first), extra outputs and anomaly heuristics
+    pub fn from_config(config: &AgeConfig) -> AgeResult<Self> {
+        let mut logger = Self::with_format(
+            config.audit_log_path.clone().map(PathBuf::from),
+            config.telemetry_format,
+        )?;
+        let signing_key_file = std::env::var("CAGE_AUDIT_KEY_FILE")
+            .ok()
+            .filter(|f| !f.is_empty())
+            .or_else(|| config.audit_signing_key_file.clone());
+        if let Some(key_file) = signing_key_file {
+            let key = std::fs::read(&key_file)
+                .map_err(|e| AgeError::file_error("read", PathBuf::from(&key_file), e))?;
+            logger = logger.with_signing_key(key.trim_ascii().to_vec())?;
+        }
+        let mut logger = logger.with_outputs(&config.audit_outputs)?;
+        if let Some(detector) = AnomalyDetector::from_config(config) {
+            logger = logger.with_anomaly_detector(detector);
+        }
+        Ok(logger)
+    }
+
+    /// Chain-sign lines written to the log file with `key` (see [`chain`]).
+    ///
+    /// No-op without a log file; stderr output is never signed.
+    pub fn with_signing_key(mut self, key: Vec<u8>) -> AgeResult<Self> {
+        if let Some(log_path) = &self.log_path {
+            self.chain = Some(AuditChain::resume(key, log_path)?);
+        }
+        Ok(self)
+    }
+
+    /// Also send every event to the outputs in `specs` (see [`output`]), each in its
+    /// own telemetry format
+    pub fn with_outputs(mut self, specs: &[String]) -> AgeResult<Self> {
+        for spec in specs {
+            self.outputs
+                .push(AuditOutput::open(&OutputSpec::parse(spec)?)?);
+        }
+        Ok(self)
+    }
+
+    /// Tag successful decryption events with the anomalies `detector` raises (see
+    /// [`anomaly`])
+    pub fn with_anomaly_detector(mut self, detector: AnomalyDetector) -> Self {
+        self.anomaly = Some(detector);
+        self
+    }
+
     /// Log operation start
     pub fn log_operation_start(
         &self,
@@ -139,17 +211,13 @@ impl AuditLogger {
 
     /// Log operation start (single path variant)
     pub fn log_operation_start_single(&self, operation: &str, path: &Path) -> AgeResult<()> {
-        if matches!(self.telemetry_format, TelemetryFormat::Json) {
-            let event = json!({
-                "event_type": "operation_start",
-                "operation": operation,
-                "path": path.display().to_string(),
-            });
-            self.log_json_event("INFO", event)
-        } else {
-            let message = format!("OPERATION_START {} {}", operation, path.display());
-            self.log_event("INFO", &message)
-        }
+        let event = json!({
+            "event_type": "operation_start",
+            "operation": operation,
+            "path": path.display().to_string(),
+        });
+        let message = format!("OPERATION_START {} {}", operation, path.display());
+        self.emit("INFO", &message, Some(event))
     }
 
     /// Log operation complete
@@ -159,68 +227,68 @@ impl AuditLogger {
         path: &Path,
         result: &OperationResult,
     ) -> AgeResult<()> {
-        if matches!(self.telemetry_format, TelemetryFormat::Json) {
-            let event = json!({
-                "event_type": "operation_complete",
-                "operation": operation,
-                "path": path.display().to_string(),
-                "processed_count": result.processed_files.len(),
-                "failed_count": result.failed_files.len(),
-                "execution_time_ms": result.execution_time_ms,
-                "processed_files": result.processed_files.clone(),
-            });
-            self.log_json_event("INFO", event)
-        } else {
-            let message = format!(
-                "OPERATION_COMPLETE {} {} - processed: {}, failed: {}, duration: {}ms",
-                operation,
-                path.display(),
-                result.processed_files.len(),
-                result.failed_files.len(),
-                result.execution_time_ms
-            );
-            self.log_event("INFO", &message)
-        }
+        let event = json!({
+            "event_type": "operation_complete",
+            "operation": operation,
+            "path": path.display().to_string(),
+            "processed_count": result.processed_files.len(),
+            "failed_count": result.failed_files.len(),
+            "skipped_count": result.skipped_files.len(),
+            "execution_time_ms": result.execution_time_ms,
+            "bytes_in": result.bytes_in,
+            "bytes_out": result.bytes_out,
+            "processed_files": result.processed_files.clone(),
+            "skipped_files": result
+                .skipped_files
+                .iter()
+                .map(|s| json!({"path": s.path, "reason": s.reason}))
+                .collect::<Vec<_>>(),
+            "warnings": result.warnings.clone(),
+        });
+        let message = format!(
+            "OPERATION_COMPLETE {} {} - processed: {}, failed: {}, skipped: {}, bytes_in: {}, bytes_out: {}, duration: {}ms",
+            operation,
+            path.display(),
+            result.processed_files.len(),
+            result.failed_files.len(),
+            result.skipped_files.len(),
+            result.bytes_in,
+            result.bytes_out,
+            result.execution_time_ms
+        );
+        self.emit("INFO", &message, Some(event))
     }
 
     /// Log status check
     pub fn log_status_check(&self, path: &Path, status: &RepositoryStatus) -> AgeResult<()> {
-        if matches!(self.telemetry_format, TelemetryFormat::Json) {
-            let event = json!({
-                "event_type": "status_check",
-                "path": path.display().to_string(),
-                "total_files": status.total_files,
-                "encrypted_files": status.encrypted_files,
-                "unencrypted_files": status.unencrypted_files,
-            });
-            self.log_json_event("INFO", event)
-        } else {
-            let message = format!(
-                "STATUS_CHECK {} - total: {}, encrypted: {}, unencrypted: {}",
-                path.display(),
-                status.total_files,
-                status.encrypted_files,
-                status.unencrypted_files
-            );
-            self.log_event("INFO", &message)
-        }
+        let event = json!({
+            "event_type": "status_check",
+            "path": path.display().to_string(),
+            "total_files": status.total_files,
+            "encrypted_files": status.encrypted_files,
+            "unencrypted_files": status.unencrypted_files,
+        });
+        let message = format!(
+            "STATUS_CHECK {} - total: {}, encrypted: {}, unencrypted: {}",
+            path.display(),
+            status.total_files,
+            status.encrypted_files,
+            status.unencrypted_files
+        );
+        self.emit("INFO", &message, Some(event))
     }
 
     /// Log authority operation with structured metadata
     pub fn log_authority_operation(&self, operation: &str, recipient: &str) -> AgeResult<()> {
-        if matches!(self.telemetry_format, TelemetryFormat::Json) {
-            // Redact sensitive recipient data - only log hash for audit trail
-            let recipient_hash = format!("{:x}", md5::compute(recipient.as_bytes()));
-            let event = json!({
-                "event_type": "authority_operation",
-                "operation": operation,
-                "recipient_hash": recipient_hash,
-            });
-            self.log_json_event("INFO", event)
-        } else {
-            let message = format!("AUTHORITY_OPERATION {} recipient: {}", operation, recipient);
-            self.log_event("INFO", &message)
-        }
+        // Redact sensitive recipient data - only log hash for audit trail
+        let recipient_hash = format!("{:x}", md5::compute(recipient.as_bytes()));
+        let event = json!({
+            "event_type": "authority_operation",
+            "operation": operation,
+            "recipient_hash": recipient_hash,
+        });
+        let message = format!("AUTHORITY_OPERATION {} recipient: {}", operation, recipient);
+        self.emit("INFO", &message, Some(event))
     }
 
     /// Log structured encryption event with metadata
@@ -241,56 +309,52 @@ impl AuditLogger {
         streaming_strategy: Option<&str>,
         authority_tier: Option<&str>,
     ) -> AgeResult<()> {
-        if matches!(self.telemetry_format, TelemetryFormat::Json) {
-            let recipient_hash = recipients.as_ref().map(|r| {
-                let mut sorted = r.clone();
-                sorted.sort();
-                format!("{:x}", md5::compute(sorted.join(",").as_bytes()))
-            });
-
-            let mut event = json!({
-                "event_type": "encryption",
-                "path": path.display().to_string(),
-                "identity_type": identity_type,
-                "recipient_count": recipients.as_ref().map(|r| r.len()).unwrap_or(0),
-                "recipient_group_hash": recipient_hash,
-                "success": success,
-            });
-
-            // Add optional metadata
-            if let Some(obj) = event.as_object_mut() {
-                if let Some(strategy) = streaming_strategy {
-                    obj.insert("streaming_strategy".to_string(), json!(strategy));
-                }
-                if let Some(tier) = authority_tier {
-                    obj.insert("authority_tier".to_string(), json!(tier));
-                }
+        let recipient_hash = recipients.as_ref().map(|r| {
+            let mut sorted = r.clone();
+            sorted.sort();
+            format!("{:x}", md5::compute(sorted.join(",").as_bytes()))
+        });
+
+        let mut event = json!({
+            "event_type": "encryption",
+            "path": path.display().to_string(),
+            "identity_type": identity_type,
+            "recipient_count": recipients.as_ref().map(|r| r.len()).unwrap_or(0),
+            "recipient_group_hash": recipient_hash,
+            "success": success,
+        });
+
+        // Add optional metadata
+        if let Some(obj) = event.as_object_mut() {
+            if let Some(strategy) = streaming_strategy {
+                obj.insert("streaming_strategy".to_string(), json!(strategy));
             }
+            if let Some(tier) = authority_tier {
+                obj.insert("authority_tier".to_string(), json!(tier));
+            }
+        }
 
-            self.log_json_event("INFO", event)
+        let msg = if success {
+            format!(
+                "ENCRYPTION {} identity:{} recipients:{} {}{}",
+                path.display(),
+                identity_type,
+                recipients.as_ref().map(|r| r.len()).unwrap_or(0),
+                streaming_strategy
+                    .map(|s| format!("strategy:{} ", s))
+                    .unwrap_or_default(),
+                authority_tier
+                    .map(|t| format!("tier:{}", t))
+                    .unwrap_or_default()
+            )
         } else {
-            let msg = if success {
-                format!(
-                    "ENCRYPTION {} identity:{} recipients:{} {}{}",
-                    path.display(),
-                    identity_type,
-                    recipients.as_ref().map(|r| r.len()).unwrap_or(0),
-                    streaming_strategy
-                        .map(|s| format!("strategy:{} ", s))
-                        .unwrap_or_default(),
-                    authority_tier
-                        .map(|t| format!("tier:{}", t))
-                        .unwrap_or_default()
-                )
-            } else {
-                format!(
-                    "ENCRYPTION_FAILED {} identity:{}",
-                    path.display(),
-                    identity_type
-                )
-            };
-            self.log_event("INFO", &msg)
-        }
+            format!(
+                "ENCRYPTION_FAILED {} identity:{}",
+                path.display(),
+                identity_type
+            )
+        };
+        self.emit("INFO", &msg, Some(event))
     }
 
     /// Log structured encryption event with metadata (simplified version for backwards compat)
@@ -311,6 +375,9 @@ impl AuditLogger {
     /// * `identity_type` - Type of identity used (passphrase, age_identity, ssh_identity)
     /// * `success` - Whether the operation succeeded
     /// * `streaming_strategy` - Optional streaming strategy used
+    ///
+    /// Successful decryptions run through the anomaly detector, if any; the event
+    /// carries the resulting `severity` and `anomalies` and is logged at that level.
     pub fn log_decryption_event_extended(
         &self,
         path: &Path,
@@ -318,41 +385,96 @@ impl AuditLogger {
         success: bool,
         streaming_strategy: Option<&str>,
     ) -> AgeResult<()> {
-        if matches!(self.telemetry_format, TelemetryFormat::Json) {
-            let mut event = json!({
-                "event_type": "decryption",
-                "path": path.display().to_string(),
-                "identity_type": identity_type,
-                "success": success,
-            });
+        let anomalies = match &self.anomaly {
+            Some(detector) if success => detector.record(Utc::now()),
+            _ => Vec::new(),
+        };
+        self.emit_decryption(path, identity_type, success, streaming_strategy, anomalies)
+    }
 
-            // Add optional metadata
-            if let Some(obj) = event.as_object_mut() {
-                if let Some(strategy) = streaming_strategy {
-                    obj.insert("streaming_strategy".to_string(), json!(strategy));
-                }
+    /// Log one decryption event per `(path, success)` pair
+    ///
+    /// The successful ones reach the anomaly detector as a single batch, so a bulk
+    /// unlock costs one state load and save rather than one per file.
+    pub fn log_decryption_events(
+        &self,
+        outcomes: &[(&Path, bool)],
+        identity_type: &str,
+    ) -> AgeResult<()> {
+        let succeeded = outcomes.iter().filter(|(_, success)| *success).count();
+        let mut flags = match &self.anomaly {
+            Some(detector) => detector.record_many(Utc::now(), succeeded),
+            None => Vec::new(),
+        }
+        .into_iter();
+        let mut first_error = None;
+        for (path, success) in outcomes {
+            let anomalies = if *success {
+                flags.next().unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            if let Err(e) = self.emit_decryption(path, identity_type, *success, None, anomalies) {
+                first_error.get_or_insert(e);
             }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
 
-            self.log_json_event("INFO", event)
-        } else {
-            let msg = if success {
-                format!(
-                    "DECRYPTION {} identity:{} {}",
-                    path.display(),
-                    identity_type,
-                    streaming_strategy
-                        .map(|s| format!("strategy:{}", s))
-                        .unwrap_or_default()
-                )
+    /// Write a decryption event carrying `anomalies` at their severity
+    fn emit_decryption(
+        &self,
+        path: &Path,
+        identity_type: &str,
+        success: bool,
+        streaming_strategy: Option<&str>,
+        anomalies: Vec<Anomaly>,
+    ) -> AgeResult<()> {
+        let severity = Severity::of(&anomalies);
+        let mut event = json!({
+            "event_type": "decryption",
+            "path": path.display().to_string(),
+            "identity_type": identity_type,
+            "success": success,
+            "severity": severity,
+            "anomalies": anomalies,
+        });
+
+        // Add optional metadata
+        if let Some(obj) = event.as_object_mut() {
+            if let Some(strategy) = streaming_strategy {
+                obj.insert("streaming_strategy".to_string(), json!(strategy));
+            }
+        }
+
+        let msg = if success {
+            let flags = if anomalies.is_empty() {
+                String::new()
             } else {
+                let names: Vec<&str> = anomalies.iter().map(|a| a.as_str()).collect();
                 format!(
-                    "DECRYPTION_FAILED {} identity:{}",
-                    path.display(),
-                    identity_type
+                    " severity:{} anomalies:{}",
+                    severity.as_str(),
+                    names.join(",")
                 )
             };
-            self.log_event("INFO", &msg)
-        }
+            format!(
+                "DECRYPTION {} identity:{} {}{}",
+                path.display(),
+                identity_type,
+                streaming_strategy
+                    .map(|s| format!("strategy:{}", s))
+                    .unwrap_or_default(),
+                flags
+            )
+        } else {
+            format!(
+                "DECRYPTION_FAILED {} identity:{}",
+                path.display(),
+                identity_type
+            )
+        };
+        self.emit(severity.level(), &msg, Some(event))
     }
 
     /// Log structured decryption event with metadata (simplified version for backwards compat)
@@ -365,97 +487,80 @@ impl AuditLogger {
         self.log_decryption_event_extended(path, identity_type, success, None)
     }
 
-    /// Log structured JSON event
-    fn log_json_event(&self, level: &str, mut event: serde_json::Value) -> AgeResult<()> {
-        // Add common fields
-        if let Some(obj) = event.as_object_mut() {
-            obj.insert("timestamp".to_string(), json!(Utc::now().to_rfc3339()));
-            obj.insert("level".to_string(), json!(level));
-            obj.insert("component".to_string(), json!(self.component));
-        }
-
-        let log_entry = format!("{}\n", event.to_string());
-
-        // Output
-        eprint!("{}", log_entry);
+    /// Log emergency operation
+    pub fn log_emergency_operation(&self, operation: &str, path: &Path) -> AgeResult<()> {
+        let message = format!("EMERGENCY_OPERATION {} {}", operation, path.display());
+        self.log_event("WARN", &message)
+    }
 
-        // Also log to file if configured
-        if let Some(ref mut file) = &mut self.log_file.as_ref() {
-            let mut file_handle = file.try_clone().map_err(|e| AgeError::AuditLogFailed {
-                operation: "file_write".to_string(),
-                reason: e.to_string(),
-            })?;
+    /// Append an entry to the log file, chain-signing it when a key is configured
+    fn write_to_file(&self, log_entry: &str) -> AgeResult<()> {
+        let Some(file) = self.log_file.as_ref() else {
+            return Ok(());
+        };
+        let mut file_handle = file.try_clone().map_err(|e| AgeError::AuditLogFailed {
+            operation: "file_write".to_string(),
+            reason: e.to_string(),
+        })?;
 
+        let mut write = |entry: &str| {
             file_handle
-                .write_all(log_entry.as_bytes())
+                .write_all(entry.as_bytes())
                 .map_err(|e| AgeError::AuditLogFailed {
                     operation: "write".to_string(),
                     reason: e.to_string(),
-                })?;
-
-            file_handle.flush().map_err(|e| AgeError::AuditLogFailed {
-                operation: "flush".to_string(),
-                reason: e.to_string(),
-            })?;
+                })
+        };
+        match &self.chain {
+            Some(chain) => chain.append(log_entry.trim_end_matches('\n'), &mut write)?,
+            None => write(log_entry)?,
         }
 
-        Ok(())
-    }
-
-    /// Log emergency operation
-    pub fn log_emergency_operation(&self, operation: &str, path: &Path) -> AgeResult<()> {
-        let message = format!("EMERGENCY_OPERATION {} {}", operation, path.display());
-        self.log_event("WARN", &message)
+        file_handle.flush().map_err(|e| AgeError::AuditLogFailed {
+            operation: "flush".to_string(),
+            reason: e.to_string(),
+        })
     }
 
     /// Core event logging function
     fn log_event(&self, level: &str, message: &str) -> AgeResult<()> {
+        self.emit(level, message, None)
+    }
+
+    /// Render an event in each format and write it to stderr, the log file and every
+    /// extra output. Events without structured fields carry `message` in JSON.
+    fn emit(&self, level: &str, message: &str, event: Option<serde_json::Value>) -> AgeResult<()> {
         let timestamp = Utc::now();
 
-        let log_entry = match self.telemetry_format {
-            TelemetryFormat::Text => {
-                format!(
-                    "[{}] [{}] [{}] {}\n",
-                    timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
-                    level,
-                    self.component,
-                    message
-                )
-            }
-            TelemetryFormat::Json => {
-                let json_event = json!({
-                    "timestamp": timestamp.to_rfc3339(),
-                    "level": level,
-                    "component": self.component,
-                    "message": message,
-                });
-                format!("{}\n", json_event.to_string())
-            }
+        let text_entry = format!(
+            "[{}] [{}] [{}] {}\n",
+            timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            level,
+            self.component,
+            message
+        );
+        let mut json_event = event.unwrap_or_else(|| json!({ "message": message }));
+        if let Some(obj) = json_event.as_object_mut() {
+            obj.insert("timestamp".to_string(), json!(timestamp.to_rfc3339()));
+            obj.insert("level".to_string(), json!(level));
+            obj.insert("component".to_string(), json!(self.component));
+        }
+        let json_entry = format!("{}\n", json_event);
+        let render = |format: TelemetryFormat| match format {
+            TelemetryFormat::Text => &text_entry,
+            TelemetryFormat::Json => &json_entry,
         };
 
         // Always log to stderr for immediate visibility
+        let log_entry = render(self.telemetry_format);
         eprint!("{}", log_entry);
 
         // Also log to file if configured
-        if let Some(ref mut file) = &mut self.log_file.as_ref() {
-            let mut file_handle = file.try_clone().map_err(|e| AgeError::AuditLogFailed {
-                operation: "file_write".to_string(),
-                reason: e.to_string(),
-            })?;
+        self.write_to_file(log_entry)?;
 
-            file_handle
-                .write_all(log_entry.as_bytes())
-                .map_err(|e| AgeError::AuditLogFailed {
-                    operation: "write".to_string(),
-                    reason: e.to_string(),
-                })?;
-
-            file_handle.flush().map_err(|e| AgeError::AuditLogFailed {
-                operation: "flush".to_string(),
-                reason: e.to_string(),
-            })?;
+        for output in &self.outputs {
+            output.write(render(output.format()))?;
         }
-
         Ok(())
     }
 }
@@ -559,6 +664,23 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_signed_audit_log_verifies() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let key = b"audit-key".to_vec();
+        let logger = AuditLogger::with_file("test", temp_file.path())
+            .unwrap()
+            .with_signing_key(key.clone())
+            .unwrap();
+
+        logger.log_info("first event").unwrap();
+        logger.log_warning("second event").unwrap();
+
+        let report = verify_log(temp_file.path(), &key).unwrap();
+        assert_eq!(report.verified_lines, 2);
+        assert!(report.is_intact());
+    }
+
     #[test]
     fn test_json_telemetry_format() {
         use std::fs;
@@ -619,6 +741,55 @@ mod tests {
         assert!(log_content.contains("\"recipient_group_hash\":"));
     }
 
+    #[test]
+    fn test_decryption_event_flags_burst() {
+        use std::fs;
+        let temp_file = NamedTempFile::new().unwrap();
+        let state_dir = tempfile::TempDir::new().unwrap();
+        let config = crate::core::AgeConfig {
+            audit_unlock_limit: Some(1),
+            ..crate::core::AgeConfig::default()
+        };
+        let detector = AnomalyDetector::from_config(&config)
+            .unwrap()
+            .with_state_path(state_dir.path().join("unlock-rate.json"));
+        let logger =
+            AuditLogger::with_format(Some(temp_file.path().to_path_buf()), TelemetryFormat::Json)
+                .unwrap()
+                .with_anomaly_detector(detector);
+
+        for _ in 0..2 {
+            logger
+                .log_decryption_event(Path::new("/secret.txt.cage"), "identity-file", true)
+                .unwrap();
+        }
+
+        let log_content = fs::read_to_string(temp_file.path()).unwrap();
+        let events: Vec<serde_json::Value> = log_content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events[0]["severity"], "info");
+        assert_eq!(events[0]["level"], "INFO");
+        assert_eq!(events[1]["severity"], "warning");
+        assert_eq!(events[1]["level"], "WARN");
+        assert_eq!(events[1]["anomalies"], json!(["burst"]));
+
+        // A batch flags its own successes and leaves failures alone
+        let batch = [(Path::new("/a.cage"), false), (Path::new("/b.cage"), true)];
+        logger
+            .log_decryption_events(&batch, "identity-file")
+            .unwrap();
+        let log_content = fs::read_to_string(temp_file.path()).unwrap();
+        let events: Vec<serde_json::Value> = log_content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events[2]["success"], false);
+        assert_eq!(events[2]["severity"], "info");
+        assert_eq!(events[3]["anomalies"], json!(["burst"]));
+    }
+
     #[test]
     fn test_operation_complete_json() {
         use crate::forge::OperationResult;
@@ -674,4 +845,41 @@ mod tests {
         assert!(log_content.contains("/test/path"));
         assert!(!log_content.contains("\"event_type\""));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_events_fan_out_to_every_output() {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::net::UnixListener;
+        use tempfile::TempDir;
+
+        let temp = TempDir::new().unwrap();
+        let text_log = temp.path().join("audit.log");
+        let json_log = temp.path().join("audit.ndjson");
+        let socket = temp.path().join("collector.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        let logger = AuditLogger::with_format(Some(text_log.clone()), TelemetryFormat::Text)
+            .unwrap()
+            .with_outputs(&[
+                format!("json:{}", json_log.display()),
+                format!("json:unix:{}", socket.display()),
+            ])
+            .unwrap();
+        logger
+            .log_operation_start_single("lock", Path::new("/repo/a.txt"))
+            .unwrap();
+
+        let text = std::fs::read_to_string(&text_log).unwrap();
+        assert!(text.contains("OPERATION_START lock /repo/a.txt"));
+        let json = std::fs::read_to_string(&json_log).unwrap();
+        assert!(json.contains("\"event_type\":\"operation_start\""));
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        let event: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(event["path"], "/repo/a.txt");
+        assert_eq!(event["level"], "INFO");
+    }
 }
@@ -0,0 +1,244 @@
+//! Audit Output - Extra destinations for audit events
+//!
+//! Besides stderr and the primary log file, an `AuditLogger` can fan each event out to
+//! any number of outputs, each with its own telemetry format. An output is configured as
+//! `<format>:<target>`, where the target is a file path or `unix:<socket path>`:
+//!
+//! ```text
+//! text:/var/log/cage/audit.log       human-readable lines appended to a file
+//! json:unix:/run/collector.sock      NDJSON streamed to a collector socket
+//! ```
+//!
+//! File outputs must be writable, so a bad path fails at startup. Socket outputs are
+//! best effort: an unreachable collector never fails an operation, and the connection
+//! is retried on the next event. A collector that stops reading gets a short write
+//! timeout; once a write times out the socket is dropped for the rest of the run, so a
+//! stalled collector costs at most one timeout instead of one per event.
+
+use crate::core::TelemetryFormat;
+use crate::error::{AgeError, AgeResult};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Mutex;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// Prefix marking a socket target in an output spec
+const SOCKET_PREFIX: &str = "unix:";
+
+/// How long one event may block on a collector socket
+#[cfg(unix)]
+const SOCKET_WRITE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Where an output writes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputTarget {
+    /// Append to a file
+    File(PathBuf),
+    /// Stream to a Unix domain socket
+    Socket(PathBuf),
+}
+
+/// Parsed `<format>:<target>` output spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputSpec {
+    pub format: TelemetryFormat,
+    pub target: OutputTarget,
+}
+
+impl OutputSpec {
+    pub fn parse(spec: &str) -> AgeResult<Self> {
+        let invalid = |reason: &str| AgeError::ConfigurationError {
+            parameter: "audit.outputs".to_string(),
+            value: spec.to_string(),
+            reason: reason.to_string(),
+        };
+        let (format, target) = spec
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| invalid("Expected <format>:<target>, e.g. json:unix:/run/c.sock"))?;
+        let format = match format.to_lowercase().as_str() {
+            "text" => TelemetryFormat::Text,
+            "json" | "ndjson" => TelemetryFormat::Json,
+            _ => return Err(invalid("Valid formats: text, json")),
+        };
+        let target = match target.strip_prefix(SOCKET_PREFIX) {
+            Some(socket) if !socket.is_empty() => OutputTarget::Socket(PathBuf::from(socket)),
+            Some(_) => return Err(invalid("Missing socket path after unix:")),
+            None if !target.is_empty() => OutputTarget::File(PathBuf::from(target)),
+            None => return Err(invalid("Missing output path")),
+        };
+        if cfg!(not(unix)) && matches!(target, OutputTarget::Socket(_)) {
+            return Err(invalid("Socket outputs need a Unix platform"));
+        }
+        Ok(Self { format, target })
+    }
+}
+
+enum Sink {
+    File(File),
+    #[cfg(unix)]
+    Socket {
+        path: PathBuf,
+        stream: Option<UnixStream>,
+        /// Set once a write timed out; the collector is not tried again
+        stalled: bool,
+    },
+}
+
+/// Connect to a collector socket with the write timeout applied
+#[cfg(unix)]
+fn connect(path: &std::path::Path) -> Option<UnixStream> {
+    let stream = UnixStream::connect(path).ok()?;
+    stream.set_write_timeout(Some(SOCKET_WRITE_TIMEOUT)).ok()?;
+    Some(stream)
+}
+
+/// An open output with its telemetry format
+pub struct AuditOutput {
+    format: TelemetryFormat,
+    sink: Mutex<Sink>,
+}
+
+impl AuditOutput {
+    /// Open the output described by `spec`; sockets connect lazily
+    pub fn open(spec: &OutputSpec) -> AgeResult<Self> {
+        let sink = match &spec.target {
+            OutputTarget::File(path) => Sink::File(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| AgeError::file_error("open", path.clone(), e))?,
+            ),
+            #[cfg(unix)]
+            OutputTarget::Socket(path) => Sink::Socket {
+                path: path.clone(),
+                stream: connect(path),
+                stalled: false,
+            },
+            #[cfg(not(unix))]
+            OutputTarget::Socket(_) => unreachable!("rejected by OutputSpec::parse"),
+        };
+        Ok(Self {
+            format: spec.format,
+            sink: Mutex::new(sink),
+        })
+    }
+
+    pub fn format(&self) -> TelemetryFormat {
+        self.format
+    }
+
+    /// Write one newline-terminated entry
+    pub fn write(&self, entry: &str) -> AgeResult<()> {
+        let mut sink = self
+            .sink
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match &mut *sink {
+            Sink::File(file) => file
+                .write_all(entry.as_bytes())
+                .and_then(|_| file.flush())
+                .map_err(|e| AgeError::AuditLogFailed {
+                    operation: "write".to_string(),
+                    reason: e.to_string(),
+                }),
+            #[cfg(unix)]
+            Sink::Socket {
+                path,
+                stream,
+                stalled,
+            } => {
+                if *stalled {
+                    return Ok(());
+                }
+                if stream.is_none() {
+                    *stream = connect(path);
+                }
+                let Some(socket) = stream else {
+                    return Ok(());
+                };
+                match socket.write_all(entry.as_bytes()) {
+                    Ok(()) => {}
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                        ) =>
+                    {
+                        tracing::warn!(
+                            "Audit collector {} stopped reading; dropping it for this run",
+                            path.display()
+                        );
+                        *stream = None;
+                        *stalled = true;
+                    }
+                    // Drop a broken connection and reconnect on the next event
+                    Err(_) => *stream = None,
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_spec_parse() {
+        assert_eq!(
+            OutputSpec::parse("text:/var/log/cage.log").unwrap(),
+            OutputSpec {
+                format: TelemetryFormat::Text,
+                target: OutputTarget::File(PathBuf::from("/var/log/cage.log")),
+            }
+        );
+        assert_eq!(
+            OutputSpec::parse("json:unix:/run/collector.sock").unwrap(),
+            OutputSpec {
+                format: TelemetryFormat::Json,
+                target: OutputTarget::Socket(PathBuf::from("/run/collector.sock")),
+            }
+        );
+        assert!(OutputSpec::parse("/var/log/cage.log").is_err());
+        assert!(OutputSpec::parse("yaml:/tmp/out").is_err());
+        assert!(OutputSpec::parse("json:unix:").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stalled_socket_is_dropped() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let socket = temp.path().join("collector.sock");
+        // Accepts connections into its backlog but never reads them
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+        let output = AuditOutput::open(&OutputSpec {
+            format: TelemetryFormat::Json,
+            target: OutputTarget::Socket(socket),
+        })
+        .unwrap();
+
+        let entry = "x".repeat(64 * 1024);
+        let started = std::time::Instant::now();
+        for _ in 0..256 {
+            output.write(&entry).unwrap();
+        }
+        // One timeout at most, not one per event
+        assert!(started.elapsed() < SOCKET_WRITE_TIMEOUT * 8);
+        let sink = output.sink.lock().unwrap();
+        assert!(matches!(
+            &*sink,
+            Sink::Socket {
+                stalled: true,
+                stream: None,
+                ..
+            }
+        ));
+    }
+}
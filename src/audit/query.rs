@@ -0,0 +1,282 @@
+//! Audit Query - Filter events from text or JSON audit logs
+//!
+//! Reads logs written by `AuditLogger` in either telemetry format (chain tags from a
+//! signed log are ignored) and normalizes each line into an [`AuditEvent`] so operators
+//! can filter by time range, operation, path glob and result.
+
+use super::chain::CHAIN_MARKER;
+use crate::error::{AgeError, AgeResult};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use globset::{Glob, GlobMatcher};
+use serde::Serialize;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Normalized audit log event
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    /// Event kind, e.g. `operation_complete`, `encryption`
+    pub event_type: String,
+    /// Operation name for operation events (`lock`, `unlock`, ...)
+    pub operation: Option<String>,
+    pub path: Option<String>,
+    /// Outcome when the event records one
+    pub success: Option<bool>,
+    pub message: String,
+}
+
+impl AuditEvent {
+    /// Parse one log line in either telemetry format; `None` for unrecognized lines
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line
+            .rsplit_once(CHAIN_MARKER)
+            .map_or(line, |(content, _)| content)
+            .trim();
+        if line.starts_with('{') {
+            Self::parse_json(line)
+        } else {
+            Self::parse_text(line)
+        }
+    }
+
+    fn parse_json(line: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        let field = |name: &str| value.get(name).and_then(|v| v.as_str()).map(String::from);
+        let timestamp = DateTime::parse_from_rfc3339(&field("timestamp")?)
+            .ok()?
+            .with_timezone(&Utc);
+        let level = field("level").unwrap_or_default();
+
+        // Plain messages logged in JSON mode carry the text format inside `message`
+        let Some(event_type) = field("event_type") else {
+            let message = field("message").unwrap_or_default();
+            let mut event = Self::from_message(timestamp, level, &message);
+            event.message = message;
+            return Some(event);
+        };
+
+        let success = value
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .or_else(|| {
+                value
+                    .get("failed_count")
+                    .and_then(|v| v.as_u64())
+                    .map(|failed| failed == 0)
+            })
+            .or_else(|| (level == "ERROR").then_some(false));
+        Some(Self {
+            timestamp,
+            level,
+            operation: field("operation").or_else(|| Some(event_type.clone())),
+            event_type,
+            path: field("path"),
+            success,
+            message: line.to_string(),
+        })
+    }
+
+    /// `[2025-01-01 12:00:00 UTC] [LEVEL] [component] MESSAGE`
+    fn parse_text(line: &str) -> Option<Self> {
+        let rest = line.strip_prefix('[')?;
+        let (stamp, rest) = rest.split_once("] [")?;
+        let (level, rest) = rest.split_once("] [")?;
+        let (_component, message) = rest.split_once("] ")?;
+        let timestamp = NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S UTC")
+            .ok()?
+            .and_utc();
+        Some(Self::from_message(timestamp, level.to_string(), message))
+    }
+
+    /// Interpret a text-format message (`OPERATION_COMPLETE lock /path - ...`)
+    fn from_message(timestamp: DateTime<Utc>, level: String, message: &str) -> Self {
+        let mut words = message.split_whitespace();
+        let kind = words.next().unwrap_or_default();
+        let (operation, path) = if kind.starts_with("OPERATION_") {
+            (
+                words.next().map(String::from),
+                words.next().map(String::from),
+            )
+        } else {
+            (Some(kind.to_lowercase()), words.next().map(String::from))
+        };
+
+        let success = if level == "ERROR" || kind.ends_with("_FAILED") || kind.ends_with("_FAILURE")
+        {
+            Some(false)
+        } else if kind == "OPERATION_COMPLETE" {
+            message
+                .split("failed: ")
+                .nth(1)
+                .and_then(|rest| rest.split(',').next())
+                .and_then(|n| n.trim().parse::<u64>().ok())
+                .map(|failed| failed == 0)
+        } else if matches!(kind, "OPERATION_SUCCESS" | "ENCRYPTION" | "DECRYPTION") {
+            Some(true)
+        } else {
+            None
+        };
+
+        Self {
+            timestamp,
+            level,
+            event_type: kind.to_lowercase(),
+            operation: operation.map(|o| o.to_lowercase()),
+            path,
+            success,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Filters applied to audit events; unset filters match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    operation: Option<String>,
+    path: Option<GlobMatcher>,
+    success: Option<bool>,
+}
+
+impl AuditQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Match operation or event type, case-insensitively
+    pub fn operation(mut self, operation: &str) -> Self {
+        self.operation = Some(operation.to_lowercase());
+        self
+    }
+
+    pub fn path_glob(mut self, pattern: &str) -> AgeResult<Self> {
+        let glob = Glob::new(pattern).map_err(|e| AgeError::ConfigurationError {
+            parameter: "path".to_string(),
+            value: pattern.to_string(),
+            reason: e.to_string(),
+        })?;
+        self.path = Some(glob.compile_matcher());
+        Ok(self)
+    }
+
+    /// Keep only successful (`true`) or failed (`false`) events
+    pub fn success(mut self, success: bool) -> Self {
+        self.success = Some(success);
+        self
+    }
+
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        if self.since.is_some_and(|since| event.timestamp < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| event.timestamp > until) {
+            return false;
+        }
+        if let Some(operation) = &self.operation {
+            let hit = event.operation.as_deref() == Some(operation.as_str())
+                || event.event_type == *operation;
+            if !hit {
+                return false;
+            }
+        }
+        if let Some(matcher) = &self.path {
+            if !event.path.as_deref().is_some_and(|p| matcher.is_match(p)) {
+                return false;
+            }
+        }
+        if let Some(success) = self.success {
+            if event.success != Some(success) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Read `log_path` and return matching events in file order
+    pub fn run(&self, log_path: &Path) -> AgeResult<Vec<AuditEvent>> {
+        let file = fs::File::open(log_path)
+            .map_err(|e| AgeError::file_error("read", log_path.to_path_buf(), e))?;
+        let mut events = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| AgeError::file_error("read", log_path.to_path_buf(), e))?;
+            if let Some(event) = AuditEvent::parse(&line) {
+                if self.matches(&event) {
+                    events.push(event);
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a plain `YYYY-MM-DD` date (midnight UTC)
+pub fn parse_time_bound(value: &str) -> AgeResult<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .map_err(|_| AgeError::ConfigurationError {
+            parameter: "time".to_string(),
+            value: value.to_string(),
+            reason: "Expected RFC 3339 timestamp or YYYY-MM-DD".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_query_filters_mixed_formats() {
+        let log = NamedTempFile::new().unwrap();
+        fs::write(
+            log.path(),
+            concat!(
+                "[2025-01-02 10:00:00 UTC] [INFO] [cage_automation] OPERATION_COMPLETE lock /repo/a.txt - processed: 1, failed: 0, skipped: 0\n",
+                "[2025-01-03 10:00:00 UTC] [INFO] [cage_automation] OPERATION_COMPLETE unlock /repo/b.txt.cage - processed: 0, failed: 1, skipped: 0 #chain=abc\n",
+                "{\"timestamp\":\"2025-01-04T10:00:00+00:00\",\"level\":\"INFO\",\"component\":\"cage_automation\",\"event_type\":\"encryption\",\"path\":\"/other/c.txt\",\"success\":true}\n",
+                "not an audit line\n",
+            ),
+        )
+        .unwrap();
+
+        let all = AuditQuery::new().run(log.path()).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let failures = AuditQuery::new().success(false).run(log.path()).unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].operation.as_deref(), Some("unlock"));
+
+        let repo = AuditQuery::new()
+            .path_glob("/repo/**")
+            .unwrap()
+            .since(parse_time_bound("2025-01-02").unwrap())
+            .until(parse_time_bound("2025-01-02T23:59:59Z").unwrap())
+            .run(log.path())
+            .unwrap();
+        assert_eq!(repo.len(), 1);
+        assert_eq!(repo[0].path.as_deref(), Some("/repo/a.txt"));
+
+        let encryption = AuditQuery::new()
+            .operation("ENCRYPTION")
+            .run(log.path())
+            .unwrap();
+        assert_eq!(encryption.len(), 1);
+    }
+}
@@ -6,23 +6,36 @@
 
 use std::env;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter, Write};
+use std::io::{BufReader, BufWriter, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 // Import cage library modules
+use cage::core::interrupt;
+use cage::core::meta::{self, CipherMeta};
+use cage::core::permissions;
+use cage::core::scheduling::{parse_byte_rate, parse_byte_size};
 use cage::core::{
-    AgeConfig, BatchOperation, BatchRequest, Identity, LockRequest, Recipient, RotateRequest,
-    StatusRequest, StreamRequest, UnlockRequest,
+    default_prompter, parse_duration, Confirmation, FleetCommand, FleetManifest,
+    IdentityProviderRegistry, IoPriority, LockPlan, MemoryBudget, Prompter, NON_INTERACTIVE_ENV,
 };
-use cage::{
-    AgeError, AgeResult, CageManager, LockOptions, OutputFormat, PassphraseManager, PassphraseMode,
-    UnlockOptions,
+use cage::adp::ByteProgressFn;
+use cage::mgr::rekey_plan::DEFAULT_REKEY_THROUGHPUT;
+use cage::mgr::{run_fleet, run_self_test, QueueWorker, RelockStore, SelfTestStatus, Session};
+use cage::prelude::{
+    AgeConfig, AgeError, AgeResult, AuthorityTier, BatchOperation, BatchOrder, BatchRequest,
+    CageManager, Identity, LockOptions, LockRequest, MtimeWindow, MultiRecipientConfig,
+    OutputFormat, PassphraseManager, PassphraseMode, Recipient, RetentionPolicyConfig,
+    RotateRequest, StatusRequest, StreamRequest, UnlockOptions, UnlockRequest, VerifyRequest,
 };
+use cage::PassphrasePolicy;
 
 // Import RSB utilities for enhanced CLI experience
 use rsb::prelude::*;
 use rsb::progress::{ProgressManager, ProgressStyle, TerminalConfig, TerminalReporter};
+use tracing_subscriber::fmt::{format, FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
 
 /// Print the Cage logo
 fn logo() {
@@ -34,6 +47,49 @@ fn logo() {
     );
 }
 
+/// Render the library's `tracing` events on stderr.
+///
+/// By default warnings and errors read like the rest of the CLI output. `CAGE_LOG` takes an
+/// `EnvFilter` directive (e.g. `cage=debug`) and switches to full records with their spans.
+fn install_log_subscriber() {
+    match env::var("CAGE_LOG") {
+        Ok(directives) => tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::new(directives))
+            .with_writer(std::io::stderr)
+            .init(),
+        Err(_) => tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .event_format(CliEventFormat)
+            .with_writer(std::io::stderr)
+            .init(),
+    }
+}
+
+/// Message and fields of an event, with the CLI's warning/error glyph
+struct CliEventFormat;
+
+impl<S, N> FormatEvent<S, N> for CliEventFormat
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: format::Writer<'_>,
+        event: &tracing::Event<'_>,
+    ) -> std::fmt::Result {
+        let mut message = String::new();
+        ctx.format_fields(format::Writer::new(&mut message), event)?;
+        let line = match *event.metadata().level() {
+            tracing::Level::ERROR => cage::lang::fmt_error(&message),
+            tracing::Level::WARN => cage::lang::fmt_warning(&message),
+            _ => message,
+        };
+        std::fmt::Write::write_fmt(&mut writer, format_args!("{}\n", line))
+    }
+}
+
 /// Main function using RSB bootstrap
 fn main() {
     // Check for version or help flags before RSB processing
@@ -51,21 +107,49 @@ fn main() {
         return;
     }
 
+    // `cage show` without --clip prints the secret itself, so nothing else may go to stdout
+    let shows_plaintext = args
+        .iter()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .is_some_and(|command| command == "show")
+        && !args.iter().any(|arg| arg == "--clip");
+
     let args = bootstrap!();
     options!(&args);
 
-    // Print banner with enhanced information
-    println!("🔒 Cage - Age Encryption Automation CLI");
-    println!("🛡️ Secure Age encryption with PTY automation");
-    println!(
-        "📦 Version: {} | Built with RSB Framework",
-        env!("CARGO_PKG_VERSION")
-    );
+    if is_true("opt_non_interactive") {
+        env::set_var(NON_INTERACTIVE_ENV, "1");
+    }
+
+    // First Ctrl-C/SIGTERM stops after the current file; a second one exits at once
+    interrupt::install();
+    install_log_subscriber();
+    apply_profile_override();
+
+    let print0 = match print0_mode() {
+        Ok(print0) => print0,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Print banner with enhanced information; --print0, --stdout and --json keep stdout for data
+    let json = is_true("opt_json") || get_var("opt_format") == "json";
+    if print0.is_none() && !is_true("opt_stdout") && !shows_plaintext && !json {
+        println!("🔒 Cage - Age Encryption Automation CLI");
+        println!("🛡️ Secure Age encryption with PTY automation");
+        println!(
+            "📦 Version: {} | Built with RSB Framework",
+            env!("CARGO_PKG_VERSION")
+        );
 
-    if is_true("opt_verbose") {
-        println!("🔍 Verbose mode enabled");
+        if is_true("opt_verbose") {
+            println!("🔍 Verbose mode enabled");
+        }
+        println!();
     }
-    println!();
 
     // Pre-dispatch for setup commands
     if pre_dispatch!(&args, {
@@ -79,10 +163,20 @@ fn main() {
     dispatch!(&args, {
         "lock" => cmd_lock,
         "unlock" => cmd_unlock,
+        "show" => cmd_show,
+        "open" => cmd_open,
+        "close" => cmd_close,
         "status" => cmd_status,
         "rotate" => cmd_rotate,
         "verify" => cmd_verify,
+        "convert" => cmd_convert,
+        "rekey" => cmd_rekey,
+        "relock" => cmd_relock,
+        "emergency-unlock" => cmd_emergency_unlock,
+        "mount" => cmd_mount,
+        "audit" => cmd_audit,
         "batch" => cmd_batch,
+        "fleet" => cmd_fleet,
         "test" => cmd_test,
         "demo" => cmd_demo,
         "proxy" => cmd_proxy,
@@ -90,10 +184,52 @@ fn main() {
         "config" => cmd_config,
         "stream" => cmd_stream,
         "adapter" => cmd_adapter,
-        "keygen" => cmd_keygen
+        "keygen" => cmd_keygen,
+        "key" => cmd_key,
+        "recipients" => cmd_recipients,
+        "worker" => cmd_worker,
+        "tui" => cmd_tui
     });
 }
 
+/// Resolve `--tier <X|M|R|I|D>` into recipients from every configured group at or above it
+fn resolve_tier_recipients_from_cli() -> Result<Option<MultiRecipientConfig>, String> {
+    let tier_value = get_var("opt_tier");
+    if tier_value.is_empty() {
+        return Ok(None);
+    }
+
+    let tier = AuthorityTier::from_str(tier_value.trim()).ok_or_else(|| {
+        format!(
+            "Invalid --tier '{}'. Valid tiers: X, M, R, I, D",
+            tier_value
+        )
+    })?;
+    let config = AgeConfig::load_default().map_err(|e| format!("Failed to load config: {}", e))?;
+    MultiRecipientConfig::from_tier(&config, tier)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Parse `--pin-group <NAME=HASH[,NAME=HASH...]>` into expected recipient group hashes
+fn collect_group_pins_from_cli() -> Result<Vec<(String, String)>, String> {
+    let value = get_var("opt_pin_group");
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|entry| match entry.split_once('=') {
+            Some((name, hash)) if !name.trim().is_empty() && !hash.trim().is_empty() => {
+                Ok((name.trim().to_string(), hash.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Invalid --pin-group '{}'. Expected NAME=HASH",
+                entry
+            )),
+        })
+        .collect()
+}
+
 fn collect_lock_recipients_from_cli() -> Vec<Recipient> {
     let mut recipients = Vec::new();
 
@@ -155,9 +291,54 @@ fn parse_unlock_identity_from_cli() -> Option<Identity> {
         return Some(Identity::SshKey(PathBuf::from(ssh_identity_path)));
     }
 
+    parse_identity_provider_from_cli()
+}
+
+fn parse_identity_provider_from_cli() -> Option<Identity> {
+    let provider = get_var("opt_identity_provider");
+    if !provider.is_empty() {
+        return Some(Identity::Provider(provider));
+    }
+
     None
 }
 
+/// Run the `--passphrase-cmd`-style option named `opt_name`, if it was given.
+fn read_passphrase_cmd(manager: &PassphraseManager, opt_name: &str) -> Option<AgeResult<String>> {
+    let command = get_var(opt_name);
+    if command.is_empty() {
+        return None;
+    }
+
+    Some(manager.get_passphrase_with_mode(
+        "Passphrase command",
+        false,
+        PassphraseMode::Command(command),
+    ))
+}
+
+/// Export `--max-throughput`, `--nice` and `--ionice` for the manager's scheduling setup
+fn apply_scheduling_overrides() -> Result<(), String> {
+    let throughput = get_var("opt_max_throughput");
+    if !throughput.is_empty() {
+        parse_byte_rate(&throughput).map_err(|e| e.to_string())?;
+        std::env::set_var("CAGE_MAX_THROUGHPUT", throughput);
+    }
+
+    let nice = get_var("opt_nice");
+    if !nice.is_empty() {
+        std::env::set_var("CAGE_NICE", nice);
+    }
+
+    let ionice = get_var("opt_ionice");
+    if !ionice.is_empty() {
+        IoPriority::parse(&ionice).map_err(|e| e.to_string())?;
+        std::env::set_var("CAGE_IONICE", ionice);
+    }
+
+    Ok(())
+}
+
 fn apply_streaming_strategy_override() {
     let strategy = get_var("opt_streaming_strategy");
     if !strategy.is_empty() {
@@ -165,6 +346,204 @@ fn apply_streaming_strategy_override() {
     }
 }
 
+/// Manager for this run; the first one also applies the process-wide settings of its
+/// config, after the command has set its overrides
+fn cli_manager() -> cage::AgeResult<CageManager> {
+    static PROCESS_SETTINGS: std::sync::Once = std::sync::Once::new();
+    let manager = CageManager::with_defaults()?;
+    PROCESS_SETTINGS.call_once(|| manager.apply_process_settings());
+    Ok(manager)
+}
+
+/// Route removed files into a quarantine directory when `--quarantine <DIR>` is given
+fn apply_quarantine_override() {
+    let dir = get_var("opt_quarantine");
+    if !dir.is_empty() {
+        std::env::set_var("CAGE_QUARANTINE_DIR", dir);
+    }
+}
+
+/// Select a performance preset for this run when `--profile <NAME>` is given
+fn apply_profile_override() {
+    let profile = get_var("opt_profile");
+    if !profile.is_empty() {
+        std::env::set_var("CAGE_PROFILE", profile);
+    }
+}
+
+/// `--progress`, or `progress = true` from the config (directly or through a profile)
+fn progress_requested() -> bool {
+    is_true("opt_progress") || AgeConfig::load_default().is_ok_and(|c| c.progress == Some(true))
+}
+
+/// Paths listed by `--files-from=<PATH|->`, or `None` when the flag is absent.
+///
+/// Entries are newline separated, or NUL separated with `-0`/`--null` (for `find -print0`);
+/// blank entries are ignored. `-` reads stdin, which `--stdin-passphrase` also needs.
+fn read_files_from() -> Result<Option<Vec<PathBuf>>, String> {
+    let source = get_var("opt_files_from");
+    if source.is_empty() {
+        return Ok(None);
+    }
+    let contents = if source == "-" {
+        if is_true("opt_stdin_passphrase") {
+            return Err("--files-from=- cannot be combined with --stdin-passphrase".to_string());
+        }
+        let mut contents = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read file list from stdin: {}", e))?;
+        contents
+    } else {
+        fs::read(&source).map_err(|e| format!("Failed to read file list {}: {}", source, e))?
+    };
+    let nul = is_true("opt_null") || is_true("opt_0");
+    Ok(Some(parse_path_list(&contents, nul)))
+}
+
+fn parse_path_list(contents: &[u8], nul: bool) -> Vec<PathBuf> {
+    let separator = if nul { b'\0' } else { b'\n' };
+    contents
+        .split(|byte| *byte == separator)
+        .map(|entry| match nul {
+            true => entry,
+            false => entry.strip_suffix(b"\r").unwrap_or(entry),
+        })
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(entry: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(entry))
+}
+
+/// Elsewhere list entries are taken as UTF-8 (invalid bytes replaced)
+#[cfg(not(unix))]
+fn path_from_bytes(entry: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(entry).into_owned())
+}
+
+/// Add `--files-from` paths to the command's path arguments.
+///
+/// Returns `Err(exit code)` when the list cannot be read, or `Err(0)` when it was given
+/// but empty: a pipeline that selected nothing has nothing to do.
+fn extend_with_files_from(paths: &mut Vec<PathBuf>) -> Result<(), i32> {
+    match read_files_from() {
+        Ok(Some(listed)) if listed.is_empty() && paths.is_empty() => {
+            echo!("No paths in --files-from; nothing to do");
+            Err(0)
+        }
+        Ok(listed) => {
+            paths.extend(listed.unwrap_or_default());
+            Ok(())
+        }
+        Err(e) => {
+            stderr!("❌ {}", e);
+            Err(1)
+        }
+    }
+}
+
+/// Path list `--print0` writes to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Print0 {
+    Processed,
+    Failed,
+}
+
+/// `--print0` (processed paths) or `--print0=failed`; `None` when not given.
+///
+/// Stdout then carries nothing but NUL-terminated paths for `xargs -0`, so the banner
+/// and summaries are dropped and the flags that print to stdout are refused.
+fn print0_mode() -> Result<Option<Print0>, String> {
+    let mode = match get_var("opt_print0").as_str() {
+        "" | "false" => return Ok(None),
+        "true" | "1" | "processed" => Print0::Processed,
+        "failed" => Print0::Failed,
+        other => {
+            return Err(format!(
+                "Invalid --print0 value '{}': use --print0 or --print0=failed",
+                other
+            ))
+        }
+    };
+    if is_true("opt_verbose") || is_true("opt_tree") {
+        return Err("--print0 cannot be combined with --verbose or --tree".to_string());
+    }
+    Ok(Some(mode))
+}
+
+/// Write `paths` NUL-terminated to stdout
+fn write_print0<'a>(paths: impl IntoIterator<Item = &'a String>) {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    for path in paths {
+        let _ = out.write_all(path.as_bytes());
+        let _ = out.write_all(b"\0");
+    }
+    let _ = out.flush();
+}
+
+/// Stream the processed files of `result` under `--print0`
+fn print0_processed(result: &cage::OperationResult) {
+    if print0_mode() == Ok(Some(Print0::Processed)) {
+        write_print0(&result.processed_files);
+    }
+}
+
+/// Failure list written on interrupt when `--failed-out` is not given
+const INTERRUPT_CHECKPOINT: &str = "cage-interrupted.txt";
+
+/// Write failed paths to `--failed-out <PATH>`, one per line, for `--files-from` retries.
+///
+/// Paths under the working directory are written relative to it. The file is written even
+/// when nothing failed so retry loops can stop on an empty list. An interrupted run always
+/// leaves its list, in `cage-interrupted.txt` unless `--failed-out` says otherwise.
+fn finish_failed_out(code: i32, failed_paths: &[String]) -> i32 {
+    if print0_mode() == Ok(Some(Print0::Failed)) {
+        write_print0(failed_paths);
+    }
+    let mut target = get_var("opt_failed_out");
+    if target.is_empty() {
+        if interrupt::interrupted().is_none() {
+            return code;
+        }
+        target = INTERRUPT_CHECKPOINT.to_string();
+    }
+
+    let cwd = env::current_dir().ok();
+    let mut contents = String::new();
+    for entry in failed_paths {
+        let path = Path::new(entry);
+        let relative = cwd
+            .as_deref()
+            .and_then(|cwd| path.strip_prefix(cwd).ok())
+            .unwrap_or(path);
+        contents.push_str(&relative.display().to_string());
+        contents.push('\n');
+    }
+
+    match fs::write(&target, contents) {
+        Ok(()) => {
+            if interrupt::interrupted().is_some() {
+                stderr!(
+                    "⏸️  Interrupted: {} paths left for retry in {}",
+                    failed_paths.len(),
+                    target
+                );
+            }
+            code
+        }
+        Err(e) => {
+            stderr!("❌ Failed to write --failed-out {}: {}", target, e);
+            1
+        }
+    }
+}
+
 // RSB Command Handler Functions
 
 /// Initialize cage configuration
@@ -237,10 +616,375 @@ fn cmd_install(_args: Args) -> i32 {
     0
 }
 
+/// Key utilities: `cage key from-ssh <key>`
+fn cmd_key(args: Args) -> i32 {
+    use cage::keygen::{named_identity_path, ssh_key_to_age, write_identity_file};
+
+    let subcommand = args.get_or(1, "");
+    if subcommand != "from-ssh" {
+        stderr!("❌ Unknown key subcommand: '{}'", subcommand);
+        stderr!("Usage: cage key from-ssh <ssh-key> [--output <path>] [--force] [--stdout-only]");
+        return 1;
+    }
+    let source = PathBuf::from(args.get_or(2, ""));
+    if source.as_os_str().is_empty() {
+        stderr!("❌ SSH key path required");
+        stderr!("Usage: cage key from-ssh ~/.ssh/id_ed25519");
+        return 1;
+    }
+
+    let conversion = match ssh_key_to_age(&source) {
+        Ok(conversion) => conversion,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+
+    echo!("🔑 Fingerprint: {}", conversion.fingerprint);
+    echo!("📋 SSH recipient: {}", conversion.ssh_recipient);
+    if let Some(recipient) = &conversion.age_recipient {
+        echo!("📋 Age recipient: {}", recipient);
+    }
+    if let Some(note) = &conversion.note {
+        echo!("ℹ️  {}", note);
+    }
+    let Some(identity) = &conversion.age_identity else {
+        return 0;
+    };
+
+    if is_true("opt_stdout_only") {
+        println!("{}", identity);
+        return 0;
+    }
+    let output = match get_var("opt_output") {
+        path if !path.is_empty() => PathBuf::from(path),
+        _ => {
+            let name = source
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "ssh".to_string());
+            match named_identity_path(&name) {
+                Ok(path) => path,
+                Err(e) => {
+                    stderr!("❌ {}", e);
+                    return 1;
+                }
+            }
+        }
+    };
+    let force = is_true("opt_force") || is_true("opt_f");
+    match write_identity_file(&conversion, &source, &output, force) {
+        Ok(()) => {
+            echo!("✅ Identity written: {}", output.display());
+            0
+        }
+        Err(e) => {
+            stderr!("❌ {}", e);
+            1
+        }
+    }
+}
+
+const RECIPIENTS_USAGE: &str = "Usage: cage recipients import --github <user>|--gitlab <user> [--group <name>] [--fingerprint <SHA256:..>[,..]]
+       cage recipients import <file|-> [--group <name>]
+       cage recipients export --group <name>";
+
+/// Recipient group utilities: import from forges or recipients files, export to files
+fn cmd_recipients(args: Args) -> i32 {
+    let group = get_var("opt_group").trim().to_string();
+    let subcommand = args.get_or(1, "");
+    if subcommand == "import" {
+        if let Err(code) = require_admin("recipients import") {
+            return code;
+        }
+    }
+    match (subcommand.as_str(), args.get_or(2, "")) {
+        ("import", file) if !file.is_empty() => import_recipients_file(&file, &group),
+        ("import", _) => import_forge_recipients(&group),
+        ("export", _) => export_recipients_group(&group),
+        (subcommand, _) => {
+            stderr!("❌ Unknown recipients subcommand: '{}'", subcommand);
+            stderr!("{}", RECIPIENTS_USAGE);
+            1
+        }
+    }
+}
+
+/// Print a group in the recipients file format
+fn export_recipients_group(group_name: &str) -> i32 {
+    if group_name.is_empty() {
+        stderr!("❌ --group is required");
+        stderr!("{}", RECIPIENTS_USAGE);
+        return 1;
+    }
+    let config = match AgeConfig::load_default() {
+        Ok(config) => config,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return e.exit_code();
+        }
+    };
+    match config.get_recipient_group(group_name) {
+        Some(group) => {
+            print!("{}", cage::core::export_recipients(group));
+            0
+        }
+        None => {
+            stderr!("❌ Recipient group '{}' not found", group_name);
+            1
+        }
+    }
+}
+
+/// Replace a stored group with the contents of a recipients file (`-` reads stdin)
+fn import_recipients_file(file: &str, group_name: &str) -> i32 {
+    use cage::core::parse_recipients;
+
+    if !get_var("opt_github").is_empty() || !get_var("opt_gitlab").is_empty() {
+        stderr!("❌ Import either a recipients file or a forge user, not both");
+        return 1;
+    }
+    let contents = if file == "-" {
+        let mut buffer = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut buffer).map(|_| buffer)
+    } else {
+        std::fs::read_to_string(file)
+    };
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(e) => {
+            stderr!("❌ Failed to read {}: {}", file, e);
+            return 1;
+        }
+    };
+    let name = Some(group_name).filter(|n| !n.is_empty());
+    let mut parsed = match parse_recipients(&contents, name) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            stderr!("❌ {}: {}", file, e);
+            return 1;
+        }
+    };
+    if !parsed.hash_matches() {
+        stderr!(
+            "⚠️  {} was edited since export: its hash header does not match its recipients",
+            file
+        );
+    }
+    if let Some(name) = name {
+        parsed.group.name = name.to_string();
+    }
+    let mut group = parsed.group;
+
+    let mut store = match load_group_store() {
+        Ok(store) => store,
+        Err(code) => return code,
+    };
+    let previous = store.get(&group.name).cloned();
+    let existing = previous
+        .as_ref()
+        .map(|g| g.recipients.clone())
+        .unwrap_or_default();
+    let removed: Vec<&String> = existing
+        .iter()
+        .filter(|r| !group.contains_recipient(r))
+        .collect();
+    let added = group
+        .recipients
+        .iter()
+        .filter(|r| !existing.contains(r))
+        .count();
+    if !removed.is_empty() {
+        let mut confirmation = Confirmation::new(
+            "recipients-import",
+            format!(
+                "Importing {} removes {} recipient(s) from group '{}'",
+                file,
+                removed.len(),
+                group.name
+            ),
+        );
+        for recipient in &removed {
+            confirmation = confirmation.detail(recipient.as_str());
+        }
+        if let Err(code) = confirm_or_sure(&confirmation) {
+            return code;
+        }
+    }
+
+    // Keep bookkeeping such as created_at; recipients, comments and tier come from the file
+    if let Some(previous) = previous {
+        for (key, value) in previous.metadata {
+            if !key.starts_with(cage::core::groups::COMMENT_METADATA_PREFIX) {
+                group.metadata.entry(key).or_insert(value);
+            }
+        }
+    }
+    group.set_metadata("last_modified".to_string(), chrono::Utc::now().to_rfc3339());
+    let (name, hash, total) = (group.name.clone(), group.group_hash(), group.len());
+    store.insert(group);
+    if let Err(e) = store.save() {
+        stderr!("❌ {}", e);
+        return e.exit_code();
+    }
+    echo!(
+        "✅ Group '{}': {} added, {} removed, {} total ({})",
+        name,
+        added,
+        removed.len(),
+        total,
+        store.path().display()
+    );
+    echo!("📌 Pin with --pin-group {}={}", name, hash);
+    0
+}
+
+/// Register a GitHub/GitLab user's published SSH keys to a stored group
+fn import_forge_recipients(group_name: &str) -> i32 {
+    use cage::core::set_recipient_comment;
+    use cage::keygen::{import_public_keys, KeySource};
+
+    let source = match (get_var("opt_github"), get_var("opt_gitlab")) {
+        (github, gitlab) if !github.is_empty() && gitlab.is_empty() => KeySource::github(&github),
+        (github, gitlab) if github.is_empty() && !gitlab.is_empty() => KeySource::gitlab(&gitlab),
+        _ => {
+            stderr!("❌ Exactly one of --github or --gitlab is required");
+            stderr!("{}", RECIPIENTS_USAGE);
+            return 1;
+        }
+    };
+    let source = match source {
+        Ok(source) => source,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+    let group_name = match group_name {
+        "" => source.user().to_string(),
+        name => name.to_string(),
+    };
+
+    echo!("🔎 Fetching {}", source.url());
+    let import = match import_public_keys(&source) {
+        Ok(import) => import,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+    for skipped in &import.skipped {
+        stderr!("⚠️  Skipped {}", skipped);
+    }
+
+    // Pinned fingerprints confirm offline; otherwise the operator compares them
+    let pins: Vec<String> = get_var("opt_fingerprint")
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let keys: Vec<_> = if pins.is_empty() {
+        import.keys
+    } else {
+        let missing: Vec<&String> = pins
+            .iter()
+            .filter(|pin| !import.keys.iter().any(|k| &k.fingerprint == *pin))
+            .collect();
+        if !missing.is_empty() {
+            stderr!(
+                "❌ Pinned fingerprint(s) not published by {}:",
+                source.label()
+            );
+            for pin in missing {
+                stderr!("   {}", pin);
+            }
+            return 1;
+        }
+        import
+            .keys
+            .into_iter()
+            .filter(|k| pins.contains(&k.fingerprint))
+            .collect()
+    };
+    if keys.is_empty() {
+        stderr!(
+            "❌ No age-compatible SSH keys published by {}",
+            source.label()
+        );
+        return 1;
+    }
+
+    if pins.is_empty() {
+        let mut confirmation = Confirmation::new(
+            "recipients-import",
+            format!(
+                "Register {} key(s) published by {} to group '{}'",
+                keys.len(),
+                source.label(),
+                group_name
+            ),
+        );
+        for key in &keys {
+            confirmation = confirmation.detail(format!(
+                "{}  {}",
+                key.fingerprint,
+                key.ssh_recipient.split(' ').next().unwrap_or_default()
+            ));
+        }
+        confirmation = confirmation.detail(
+            "Compare with fingerprints the key owner shared out of band (ssh-keygen -lf <key>.pub)",
+        );
+        if let Err(code) = confirm_or_sure(&confirmation) {
+            return code;
+        }
+    }
+
+    let mut store = match load_group_store() {
+        Ok(store) => store,
+        Err(code) => return code,
+    };
+    let group = store.group_mut(&group_name);
+    let mut added = 0;
+    for key in &keys {
+        if !group.contains_recipient(&key.ssh_recipient) {
+            group.add_recipient(key.ssh_recipient.clone());
+            added += 1;
+        }
+        set_recipient_comment(
+            group,
+            &key.ssh_recipient,
+            format!("{} {}", source.label(), key.fingerprint),
+        );
+    }
+    group.set_metadata("last_modified".to_string(), chrono::Utc::now().to_rfc3339());
+    let hash = group.group_hash();
+    let total = group.len();
+    if let Err(e) = store.save() {
+        stderr!("❌ {}", e);
+        return e.exit_code();
+    }
+
+    echo!(
+        "✅ Group '{}': {} added, {} total ({})",
+        group_name,
+        added,
+        total,
+        store.path().display()
+    );
+    echo!("📌 Pin with --pin-group {}={}", group_name, hash);
+    0
+}
+
 /// Generate Age identity keypair
 fn cmd_keygen(_args: Args) -> i32 {
     use cage::keygen::{KeygenRequest, KeygenService};
 
+    let roster = get_var("opt_roster");
+    if !roster.is_empty() {
+        return keygen_roster(PathBuf::from(roster));
+    }
+
     // Parse CLI flags
     let output_path = {
         let path_str = get_var("opt_output");
@@ -279,6 +1023,7 @@ fn cmd_keygen(_args: Args) -> i32 {
     let export_mode = is_true("opt_export");
     let proxy_mode = is_true("opt_proxy");
     let json_output = !is_true("opt_no_json");
+    let wrap_to = Some(get_var("opt_wrap_to")).filter(|recipient| !recipient.is_empty());
 
     // Build request
     let request = KeygenRequest {
@@ -291,23 +1036,20 @@ fn cmd_keygen(_args: Args) -> i32 {
         json_output,
         proxy_mode,
         export_mode,
+        wrap_to,
     };
 
-    // Load config (needed for group registration)
-    let config = if !request.register_groups.is_empty() {
-        match AgeConfig::load_default() {
-            Ok(cfg) => Some(cfg),
-            Err(e) => {
-                stderr!("❌ Failed to load config for group registration: {}", e);
-                return 1;
-            }
+    // Load config (binary pins and group registration)
+    let config = match AgeConfig::load_default() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            stderr!("❌ Failed to load config: {}", e);
+            return 1;
         }
-    } else {
-        None
     };
 
     // Create service and generate
-    let service = KeygenService::new(config);
+    let service = KeygenService::new(Some(config));
     match service.generate(&request) {
         Ok(summary) => {
             if json_output && !proxy_mode {
@@ -320,6 +1062,7 @@ fn cmd_keygen(_args: Args) -> i32 {
                     "fingerprint_md5": summary.fingerprint_md5,
                     "fingerprint_sha256": summary.fingerprint_sha256,
                     "registered_groups": summary.registered_groups,
+                    "wrapped_to": summary.wrapped_to,
                 });
                 println!("{}", serde_json::to_string_pretty(&json_obj).unwrap());
             } else if !proxy_mode {
@@ -327,6 +1070,9 @@ fn cmd_keygen(_args: Args) -> i32 {
                 if let Some(ref path) = summary.output_path {
                     echo!("✅ Identity generated: {}", path.display());
                 }
+                if let Some(ref admin) = summary.wrapped_to {
+                    echo!("🔒 Encrypted to: {}", admin);
+                }
                 if let Some(ref recipient) = summary.public_recipient {
                     echo!("📋 Public key: {}", recipient);
                 }
@@ -349,14 +1095,84 @@ fn cmd_keygen(_args: Args) -> i32 {
     }
 }
 
-struct InitReport {
-    config_dir: PathBuf,
-    data_dir: PathBuf,
-    cache_dir: PathBuf,
-    backup_dir: PathBuf,
-    config_file: PathBuf,
-    created_paths: Vec<PathBuf>,
-    config_created: bool,
+/// `cage keygen --roster <FILE> --group <NAME>`: one identity per team member
+fn keygen_roster(roster_path: PathBuf) -> i32 {
+    use cage::keygen::{KeygenService, RosterRequest};
+
+    let group = get_var("opt_group");
+    if group.is_empty() {
+        stderr!("❌ --roster needs --group <NAME> to register the recipients with");
+        return 1;
+    }
+    let bundle_dir = match get_var("opt_output") {
+        dir if dir.is_empty() => PathBuf::from(format!("{}-keys", group)),
+        dir => PathBuf::from(dir),
+    };
+    let request = RosterRequest {
+        roster_path,
+        group,
+        bundle_dir,
+        force: is_true("opt_force") || is_true("opt_f"),
+    };
+
+    let config = match AgeConfig::load_default() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            stderr!("❌ Failed to load config: {}", e);
+            return 1;
+        }
+    };
+    let mut store = match load_group_store() {
+        Ok(store) => store,
+        Err(code) => return code,
+    };
+    let service = KeygenService::new(Some(config));
+    let summary = match service.generate_roster(&request, &mut store) {
+        Ok(summary) => summary,
+        Err(e) => {
+            stderr!("❌ Key generation failed: {}", e);
+            return 1;
+        }
+    };
+
+    if !is_true("opt_no_json") {
+        println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+    } else {
+        for member in &summary.members {
+            echo!(
+                "✅ {}: {} ({})",
+                member.name,
+                member.public_recipient,
+                member.bundle_path.display()
+            );
+        }
+        echo!(
+            "📝 Group '{}': {} member(s) ({})",
+            summary.group,
+            summary.members.len(),
+            summary.groups_file.display()
+        );
+        echo!(
+            "📌 Pin with --pin-group {}={}",
+            summary.group,
+            summary.group_hash
+        );
+    }
+    stderr!(
+        "🔐 Hand each member their own bundle from {} and delete it afterwards",
+        request.bundle_dir.display()
+    );
+    0
+}
+
+struct InitReport {
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    cache_dir: PathBuf,
+    backup_dir: PathBuf,
+    config_file: PathBuf,
+    created_paths: Vec<PathBuf>,
+    config_created: bool,
     config_overwritten: bool,
 }
 
@@ -505,77 +1321,136 @@ fn write_default_config(path: &Path, backup_dir: &Path) -> AgeResult<()> {
 fn default_config_contents(backup_dir: &Path) -> String {
     let backup_str = backup_dir.to_string_lossy();
     format!(
-        "# Cage configuration generated by `cage init`\n# Adjust paths and policies as needed.\n\n[backup]\ncleanup_on_success = true\ndirectory = \"{}\"\nretention = \"keep_last:5\"\n\n[streaming]\nstrategy = \"auto\"\n",
+        "# Cage configuration generated by `cage init`\n# Adjust paths and policies as needed.\n\nversion = {}\n\n[backup]\ncleanup_on_success = true\ndirectory = \"{}\"\nretention = \"keep_last:5\"\n\n[streaming]\nstrategy = \"auto\"\n",
+        cage::core::CONFIG_VERSION,
         backup_str
     )
 }
 
 /// Lock (encrypt) files using RSB dispatch
 fn cmd_lock(args: Args) -> i32 {
+    apply_quarantine_override();
     let paths_str = args.get_or(1, "");
-    let paths: Vec<PathBuf> = if paths_str.is_empty() {
+    let mut paths: Vec<PathBuf> = if paths_str.is_empty() {
         // Get remaining arguments as paths
         args.remaining().iter().map(PathBuf::from).collect()
     } else {
         vec![PathBuf::from(paths_str)]
     };
+    if let Err(code) = extend_with_files_from(&mut paths) {
+        return code;
+    }
 
-    if paths.is_empty() {
+    let plan_path = get_var("opt_plan");
+    let plan = if plan_path.is_empty() {
+        None
+    } else {
+        if !paths.is_empty() {
+            stderr!("❌ --plan cannot be combined with path arguments");
+            return 1;
+        }
+        match LockPlan::load(Path::new(&plan_path)) {
+            Ok(plan) => Some(plan),
+            Err(e) => {
+                stderr!("❌ {}", e);
+                return 1;
+            }
+        }
+    };
+
+    if paths.is_empty() && plan.is_none() {
         stderr!("❌ No files specified for lock operation");
         stderr!("Usage: cage lock <path> [options]");
         return 1;
     }
 
     let recipients = collect_lock_recipients_from_cli();
-    let using_recipients = !recipients.is_empty();
+    let tier_config = match resolve_tier_recipients_from_cli() {
+        Ok(config) => config,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+    if tier_config.is_some() && !recipients.is_empty() {
+        stderr!("❌ --tier cannot be combined with explicit recipient flags");
+        return 1;
+    }
+    let using_recipients = !recipients.is_empty() || tier_config.is_some();
+    let group_pins = match collect_group_pins_from_cli() {
+        Ok(pins) => pins,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
 
     let cmd_args: Vec<String> = std::env::args().collect();
 
     apply_streaming_strategy_override();
+    if let Err(e) = apply_scheduling_overrides() {
+        stderr!("❌ {}", e);
+        return 1;
+    }
 
-    let passphrase_value = if using_recipients {
-        None
-    } else {
-        if let Some(_insecure_pass) = PassphraseManager::detect_insecure_usage(&cmd_args) {
-            stderr!("⚠️  WARNING: Passphrase detected on command line!");
-            stderr!("   This is insecure and visible in process list.");
-            if !is_true("opt_i_am_sure") {
-                stderr!("   Use interactive prompt instead, or add --i-am-sure to override");
-                return 1;
-            }
-        }
+    let provider_identity = parse_identity_provider_from_cli();
 
-        let passphrase_manager = PassphraseManager::new();
-        let passphrase = if is_true("opt_stdin_passphrase") {
-            match passphrase_manager.get_passphrase_with_mode(
-                "Enter passphrase",
-                false,
-                PassphraseMode::Stdin,
-            ) {
-                Ok(pass) => pass,
-                Err(e) => {
-                    stderr!("❌ Failed to read passphrase from stdin: {}", e);
-                    return 1;
-                }
-            }
-        } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
-            env_pass
-        } else if let Some(insecure_pass) = PassphraseManager::detect_insecure_usage(&cmd_args) {
-            insecure_pass
+    let plan_needs_passphrase = plan.as_ref().map_or(true, |p| p.needs_passphrase());
+    let passphrase_value =
+        if using_recipients || provider_identity.is_some() || !plan_needs_passphrase {
+            None
         } else {
-            match passphrase_manager.get_passphrase("Enter passphrase for encryption", false) {
-                Ok(pass) => pass,
-                Err(e) => {
-                    stderr!("❌ Failed to get passphrase: {}", e);
-                    return 1;
+            if let Some(_insecure_pass) = PassphraseManager::detect_insecure_usage(&cmd_args) {
+                if let Err(code) = confirm_insecure_passphrase() {
+                    stderr!("   Use interactive prompt instead, or add --i-am-sure to override");
+                    return code;
                 }
             }
-        };
 
-        Some(passphrase)
-    };
+            let passphrase_manager = PassphraseManager::new();
+            let passphrase = if let Some(result) =
+                read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd")
+            {
+                match result {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to read passphrase from command: {}", e);
+                        return 1;
+                    }
+                }
+            } else if is_true("opt_stdin_passphrase") {
+                match passphrase_manager.get_passphrase_with_mode(
+                    "Enter passphrase",
+                    false,
+                    PassphraseMode::Stdin,
+                ) {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to read passphrase from stdin: {}", e);
+                        return 1;
+                    }
+                }
+            } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
+                env_pass
+            } else if let Some(insecure_pass) = PassphraseManager::detect_insecure_usage(&cmd_args)
+            {
+                insecure_pass
+            } else {
+                match passphrase_manager.get_passphrase("Enter passphrase for encryption", false) {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to get passphrase: {}", e);
+                        return e.exit_code();
+                    }
+                }
+            };
 
-    let identity = if let Some(ref pass) = passphrase_value {
+            Some(passphrase)
+        };
+
+    let identity = if let Some(provider) = provider_identity {
+        provider
+    } else if let Some(ref pass) = passphrase_value {
         Identity::Passphrase(pass.clone())
     } else {
         Identity::Passphrase(String::new())
@@ -588,9 +1463,12 @@ fn cmd_lock(args: Args) -> i32 {
     } else {
         Some(pattern_val)
     };
-    let backup = is_true("opt_backup");
+    // --backup-dir implies --backup
+    let backup = is_true("opt_backup") || !get_var("opt_backup_dir").is_empty();
     let verbose = is_true("opt_verbose");
-    let show_progress = is_true("opt_progress");
+    let show_progress = progress_requested();
+    // --force always re-encrypts, even when --skip-existing is also given
+    let skip_existing = is_true("opt_skip_existing") && !is_true("opt_force");
 
     // In-place operation flags
     let in_place = is_true("opt_in_place");
@@ -609,11 +1487,19 @@ fn cmd_lock(args: Args) -> i32 {
         None
     };
 
+    if let Some(plan) = plan {
+        if in_place || using_recipients {
+            stderr!("❌ --plan cannot be combined with --in-place or recipient flags");
+            return 1;
+        }
+        return execute_lock_plan(&plan, &identity, verbose);
+    }
+
     // Handle in-place operations with safety checks
     if in_place {
-        if using_recipients {
+        if using_recipients || matches!(identity, Identity::Provider(_)) {
             stderr!(
-                "❌ In-place mode currently requires a passphrase. Remove recipient flags to continue."
+                "❌ In-place mode currently requires a passphrase. Remove recipient and provider flags to continue."
             );
             return 1;
         }
@@ -640,14 +1526,18 @@ fn cmd_lock(args: Args) -> i32 {
             }
             Err(e) => {
                 stderr!("❌ In-place lock failed: {}", e);
-                1
+                exit_code_for(&*e)
             }
         }
     } else {
-        match execute_lock_operation(
+        let mut failed_paths = Vec::new();
+        let code = match execute_lock_operation(
             paths,
             &identity,
             &recipients,
+            tier_config.as_ref(),
+            &group_pins,
+            skip_existing,
             recursive,
             pattern.clone(),
             backup,
@@ -655,6 +1545,7 @@ fn cmd_lock(args: Args) -> i32 {
             audit_log,
             verbose,
             show_progress,
+            &mut failed_paths,
         ) {
             Ok(_) => {
                 if verbose {
@@ -664,61 +1555,60 @@ fn cmd_lock(args: Args) -> i32 {
             }
             Err(e) => {
                 stderr!("❌ Lock failed: {}", e);
-                1
+                exit_code_for(&*e)
             }
-        }
+        };
+        finish_failed_out(code, &failed_paths)
     }
 }
 
 /// Unlock (decrypt) files using RSB dispatch
 fn cmd_unlock(args: Args) -> i32 {
     let paths_str = args.get_or(1, "");
-    let paths: Vec<PathBuf> = if paths_str.is_empty() {
+    let mut paths: Vec<PathBuf> = if paths_str.is_empty() {
         args.remaining().iter().map(PathBuf::from).collect()
     } else {
         vec![PathBuf::from(paths_str)]
     };
+    if let Err(code) = extend_with_files_from(&mut paths) {
+        return code;
+    }
 
     if paths.is_empty() {
         stderr!("❌ No files specified for unlock operation");
         stderr!("Usage: cage unlock <path> [options]");
         return 1;
     }
+    let to_stdout = is_true("opt_stdout");
+    if to_stdout && std::io::stdout().is_terminal() && !is_true("opt_force") {
+        stderr!("❌ Refusing to write plaintext to a terminal; pipe the output or pass --force");
+        return 1;
+    }
 
     let identity_override = parse_unlock_identity_from_cli();
+    if to_stdout && identity_override.is_none() {
+        stderr!("❌ --stdout needs --identity, --ssh-identity or a provider (passphrases stage plaintext on disk)");
+        return 1;
+    }
     apply_streaming_strategy_override();
+    apply_quarantine_override();
+    if let Err(e) = apply_scheduling_overrides() {
+        stderr!("❌ {}", e);
+        return 1;
+    }
 
-    let identity = if let Some(identity) = identity_override {
-        identity
-    } else {
-        let passphrase_manager = PassphraseManager::new();
-        let passphrase = if is_true("opt_stdin_passphrase") {
-            match passphrase_manager.get_passphrase_with_mode(
-                "Enter passphrase",
-                false,
-                PassphraseMode::Stdin,
-            ) {
-                Ok(pass) => pass,
-                Err(e) => {
-                    stderr!("❌ Failed to read passphrase from stdin: {}", e);
-                    return 1;
-                }
-            }
-        } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
-            env_pass
-        } else {
-            match passphrase_manager.get_passphrase("Enter passphrase for decryption", false) {
-                Ok(pass) => pass,
-                Err(e) => {
-                    stderr!("❌ Failed to get passphrase: {}", e);
-                    return 1;
-                }
-            }
-        };
-
-        Identity::Passphrase(passphrase)
+    let identity = match identity_override {
+        Some(identity) => identity,
+        None => match passphrase_identity_from_cli("Enter passphrase for decryption", false) {
+            Ok(identity) => identity,
+            Err(code) => return code,
+        },
     };
 
+    if to_stdout {
+        return unlock_to_stdout(&paths, &identity);
+    }
+
     let selective = is_true("opt_selective");
     let pattern = get_var("opt_pattern");
     let pattern = if pattern.is_empty() {
@@ -728,7 +1618,7 @@ fn cmd_unlock(args: Args) -> i32 {
     };
     let preserve = is_true("opt_preserve");
     let verbose = is_true("opt_verbose");
-    let show_progress = is_true("opt_progress");
+    let show_progress = progress_requested();
 
     let audit_log = if !get_var("opt_audit_log").is_empty() {
         Some(PathBuf::from(get_var("opt_audit_log")))
@@ -736,7 +1626,8 @@ fn cmd_unlock(args: Args) -> i32 {
         None
     };
 
-    match execute_unlock_operation(
+    let mut failed_paths = Vec::new();
+    let code = match execute_unlock_operation(
         paths,
         &identity,
         selective,
@@ -745,6 +1636,7 @@ fn cmd_unlock(args: Args) -> i32 {
         audit_log,
         verbose,
         show_progress,
+        &mut failed_paths,
     ) {
         Ok(_) => {
             if verbose {
@@ -754,7 +1646,211 @@ fn cmd_unlock(args: Args) -> i32 {
         }
         Err(e) => {
             stderr!("❌ Unlock failed: {}", e);
-            1
+            exit_code_for(&*e)
+        }
+    };
+    finish_failed_out(code, &failed_paths)
+}
+
+/// `unlock --stdout`: decrypt one file into a pipe without plaintext on disk
+fn unlock_to_stdout(paths: &[PathBuf], identity: &Identity) -> i32 {
+    let [path] = paths else {
+        stderr!("❌ --stdout decrypts one file; pass a single path");
+        return 1;
+    };
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+    let mut writer = BufWriter::new(std::io::stdout());
+    let result = crud_manager
+        .unlock_to_writer(path, identity, &mut writer)
+        .and_then(|_| {
+            writer
+                .flush()
+                .map_err(|e| AgeError::file_error("write", PathBuf::from("<stdout>"), e))
+        });
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            stderr!("❌ Unlock failed: {}", e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Passphrase from `--passphrase-cmd`, `--stdin-passphrase`, `CAGE_PASSPHRASE` or a
+/// prompt; errors are reported here and returned as the exit code
+fn passphrase_identity_from_cli(prompt: &str, confirm: bool) -> Result<Identity, i32> {
+    let passphrase_manager = PassphraseManager::new();
+    let passphrase =
+        if let Some(result) = read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd") {
+            result.map_err(|e| {
+                stderr!("❌ Failed to read passphrase from command: {}", e);
+                1
+            })?
+        } else if is_true("opt_stdin_passphrase") {
+            passphrase_manager
+                .get_passphrase_with_mode("Enter passphrase", false, PassphraseMode::Stdin)
+                .map_err(|e| {
+                    stderr!("❌ Failed to read passphrase from stdin: {}", e);
+                    1
+                })?
+        } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
+            env_pass
+        } else {
+            passphrase_manager
+                .get_passphrase(prompt, confirm)
+                .map_err(|e| {
+                    stderr!("❌ Failed to get passphrase: {}", e);
+                    e.exit_code()
+                })?
+        };
+    Ok(Identity::Passphrase(passphrase))
+}
+
+/// Decrypt a repository into a tracked session workspace
+fn cmd_open(args: Args) -> i32 {
+    let repository = PathBuf::from(args.get_or(1, "."));
+    let workspace = get_var("opt_workspace");
+    let workspace = (!workspace.is_empty()).then(|| PathBuf::from(workspace));
+    let identity = match parse_unlock_identity_from_cli() {
+        Some(identity) => identity,
+        None => match passphrase_identity_from_cli("Enter passphrase for decryption", false) {
+            Ok(identity) => identity,
+            Err(code) => return code,
+        },
+    };
+
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+    match crud_manager.open_session(&repository, &identity, workspace.as_deref()) {
+        Ok(result) => {
+            let workspace = Session::load(&repository)
+                .ok()
+                .flatten()
+                .map(|session| session.workspace.display().to_string())
+                .unwrap_or_default();
+            echo!(
+                "🔓 Opened {} file(s) in {}",
+                result.processed_files.len(),
+                workspace
+            );
+            for file in &result.failed_files {
+                stderr!("❌ Could not open {}", file);
+            }
+            echo!("   Edit there, then: cage close {}", repository.display());
+            if result.failed_files.is_empty() {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            stderr!("❌ Open failed: {}", e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Encrypt a session's changes back into the repository and remove its plaintext
+fn cmd_close(args: Args) -> i32 {
+    let repository = PathBuf::from(args.get_or(1, "."));
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    let session = match Session::load(&repository) {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            stderr!("❌ No session is open for {}", repository.display());
+            return 1;
+        }
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return exit_code_for(&e);
+        }
+    };
+    let changes = match AgeConfig::load_default().and_then(|config| session.changes(&config)) {
+        Ok(changes) => changes,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return exit_code_for(&e);
+        }
+    };
+
+    if is_true("opt_discard") {
+        if !changes.is_clean() {
+            let confirmation = Confirmation::new("close_discard", "Discard unencrypted changes")
+                .detail(format!(
+                    "{} changed and {} new file(s) in {} will be lost",
+                    changes.changed.len(),
+                    changes.added.len(),
+                    session.workspace.display()
+                ));
+            if let Err(code) = confirm_or_sure(&confirmation) {
+                stderr!("❌ Discard cancelled");
+                return code;
+            }
+        }
+        return match crud_manager.discard_session(&repository) {
+            Ok(()) => {
+                echo!("🗑️  Session discarded; plaintext removed");
+                0
+            }
+            Err(e) => {
+                stderr!("❌ Discard failed: {}", e);
+                exit_code_for(&e)
+            }
+        };
+    }
+
+    // A passphrase is only needed when something has to be encrypted; a typo would lose
+    // the edits, so an interactive prompt asks twice
+    let identity = if changes.is_clean() {
+        None
+    } else {
+        match passphrase_identity_from_cli("Enter passphrase to encrypt changes", true) {
+            Ok(identity) => Some(identity),
+            Err(code) => return code,
+        }
+    };
+    match crud_manager.close_session(&repository, identity.as_ref()) {
+        Ok(result) => {
+            for file in &result.processed_files {
+                echo!("🔒 Encrypted {}", file);
+            }
+            print_skipped_files(&result);
+            print_warnings(&result);
+            if result.failed_files.is_empty() {
+                echo!(
+                    "✅ Session closed ({} unchanged file(s))",
+                    changes.unchanged
+                );
+                0
+            } else {
+                for file in &result.failed_files {
+                    stderr!("❌ Could not encrypt {}", file);
+                }
+                stderr!("   Session kept open in {}", session.workspace.display());
+                1
+            }
+        }
+        Err(e) => {
+            stderr!("❌ Close failed: {}", e);
+            exit_code_for(&e)
         }
     }
 }
@@ -768,8 +1864,14 @@ fn cmd_status(args: Args) -> i32 {
     };
 
     let verbose = is_true("opt_verbose");
+    let fast = is_true("opt_fast");
+    let recursive = is_true("opt_recursive");
+
+    if get_var("opt_format") == "json" || is_true("opt_json") {
+        return print_status_tree(&path, recursive, fast);
+    }
 
-    match execute_status_operation(&path, verbose) {
+    match execute_status_operation(&path, verbose, fast, recursive) {
         Ok(_) => 0,
         Err(e) => {
             stderr!("❌ Status check failed: {}", e);
@@ -780,6 +1882,9 @@ fn cmd_status(args: Args) -> i32 {
 
 /// Rotate encryption keys using RSB dispatch
 fn cmd_rotate(args: Args) -> i32 {
+    if let Err(code) = require_admin("rotate") {
+        return code;
+    }
     let repository = PathBuf::from(args.get_or(1, ""));
     if repository.as_os_str().is_empty() {
         stderr!("❌ Repository path required for rotation");
@@ -787,16 +1892,32 @@ fn cmd_rotate(args: Args) -> i32 {
         return 1;
     }
 
-    // Get old passphrase securely
-    let passphrase_manager = PassphraseManager::new();
+    // Get old passphrase securely; the new one follows the configured policy
+    let passphrase_policy = match AgeConfig::load_default() {
+        Ok(config) => PassphrasePolicy::from_config(&config),
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+    let passphrase_manager = PassphraseManager::new().with_policy(passphrase_policy);
     let old_passphrase = {
         let old_pass_var = get_var("opt_old_passphrase");
         if !old_pass_var.is_empty() {
             // Command line provided (warn but allow)
             stderr!("⚠️  Warning: Old passphrase on command line is insecure");
             old_pass_var
-        } else if is_true("opt_stdin_passphrase") {
-            match passphrase_manager.get_passphrase_with_mode(
+        } else if let Some(result) = read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd")
+        {
+            match result {
+                Ok(pass) => pass,
+                Err(e) => {
+                    stderr!("❌ Failed to read old passphrase from command: {}", e);
+                    return 1;
+                }
+            }
+        } else if is_true("opt_stdin_passphrase") {
+            match passphrase_manager.get_passphrase_with_mode(
                 "Enter old passphrase",
                 false,
                 PassphraseMode::Stdin,
@@ -812,7 +1933,7 @@ fn cmd_rotate(args: Args) -> i32 {
                 Ok(pass) => pass,
                 Err(e) => {
                     stderr!("❌ Failed to get old passphrase: {}", e);
-                    return 1;
+                    return e.exit_code();
                 }
             }
         }
@@ -825,6 +1946,16 @@ fn cmd_rotate(args: Args) -> i32 {
             // Command line provided (warn but allow)
             stderr!("⚠️  Warning: New passphrase on command line is insecure");
             new_pass_var
+        } else if let Some(result) =
+            read_passphrase_cmd(&passphrase_manager, "opt_new_passphrase_cmd")
+        {
+            match result {
+                Ok(pass) => pass,
+                Err(e) => {
+                    stderr!("❌ Failed to read new passphrase from command: {}", e);
+                    return 1;
+                }
+            }
         } else if is_true("opt_stdin_passphrase") {
             match passphrase_manager.get_passphrase_with_mode(
                 "Enter new passphrase",
@@ -843,7 +1974,7 @@ fn cmd_rotate(args: Args) -> i32 {
                 Ok(pass) => pass,
                 Err(e) => {
                     stderr!("❌ Failed to get new passphrase: {}", e);
-                    return 1;
+                    return e.exit_code();
                 }
             }
         }
@@ -872,6 +2003,424 @@ fn cmd_rotate(args: Args) -> i32 {
     }
 }
 
+/// Convert ciphertext between ASCII armor and binary without decrypting
+fn cmd_convert(args: Args) -> i32 {
+    let path = args.get_or(1, "");
+    if path.is_empty() {
+        stderr!("❌ Usage: cage convert <file> --to ascii|binary [--output <PATH>]");
+        return 1;
+    }
+
+    let format = match get_var("opt_to").as_str() {
+        "ascii" | "armor" => OutputFormat::AsciiArmor,
+        "binary" => OutputFormat::Binary,
+        other => {
+            stderr!("❌ Invalid --to '{}'. Expected ascii or binary", other);
+            return 1;
+        }
+    };
+    let output = get_var("opt_output");
+    let output = (!output.is_empty()).then(|| PathBuf::from(output));
+
+    let crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    match crud_manager.convert(Path::new(&path), format, output.as_deref()) {
+        Ok(result) => {
+            if result.skipped_files.is_empty() {
+                echo!("✅ Converted {} to {}", path, format.description());
+            } else {
+                echo!("ℹ️  {} is already {}", path, format.description());
+            }
+            0
+        }
+        Err(e) => {
+            stderr!("❌ Convert failed: {}", e);
+            1
+        }
+    }
+}
+
+/// Remove plaintext left by `unlock --for` once its window has passed
+fn cmd_relock(_args: Args) -> i32 {
+    let all = is_true("opt_all");
+    let wait = is_true("opt_wait");
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    loop {
+        let result = match crud_manager.relock_due(all) {
+            Ok(result) => result,
+            Err(e) => {
+                stderr!("❌ Relock failed: {}", e);
+                return exit_code_for(&e);
+            }
+        };
+        for file in &result.processed_files {
+            echo!("🔒 Relocked {}", file);
+        }
+        for file in &result.failed_files {
+            stderr!("❌ Could not relock {} (lock it manually)", file);
+        }
+        if !wait {
+            if result.processed_files.is_empty() && result.failed_files.is_empty() {
+                echo!("Nothing due for relock");
+            }
+            return if result.failed_files.is_empty() { 0 } else { 1 };
+        }
+
+        // Sleep until the next recorded expiry; stop once nothing is pending
+        let now = chrono::Utc::now();
+        let next = RelockStore::default_path()
+            .and_then(|path| RelockStore::load(&path).ok())
+            .and_then(|store| store.next_expiry(now));
+        match next.and_then(|expiry| (expiry - now).to_std().ok()) {
+            Some(delay) => std::thread::sleep(delay),
+            None => return if result.failed_files.is_empty() { 0 } else { 1 },
+        }
+    }
+}
+
+/// Decrypt a repository with an escrow identity or emergency passphrase, keeping the
+/// encrypted originals
+fn cmd_emergency_unlock(args: Args) -> i32 {
+    let repository = PathBuf::from(args.get_or(1, "."));
+    let confirmation = get_var("opt_confirm");
+    let quarantine = get_var("opt_quarantine");
+    let quarantine = (!quarantine.is_empty()).then(|| PathBuf::from(quarantine));
+    let identity = match parse_unlock_identity_from_cli() {
+        Some(identity) => identity,
+        None => match passphrase_identity_from_cli("Enter emergency passphrase", false) {
+            Ok(identity) => identity,
+            Err(code) => return code,
+        },
+    };
+
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    match crud_manager.emergency_unlock(
+        &repository,
+        &identity,
+        &confirmation,
+        quarantine.as_deref(),
+    ) {
+        Ok(result) => {
+            for file in &result.affected_files {
+                echo!("🔓 Recovered {}", file);
+            }
+            for event in &result.security_events {
+                echo!("   {}", event);
+            }
+            for action in &result.recovery_actions {
+                echo!("➡️  {}", action);
+            }
+            0
+        }
+        Err(e) => {
+            stderr!("❌ Emergency unlock failed: {}", e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Add recipients to encrypted files by rewriting only their headers
+fn cmd_rekey(args: Args) -> i32 {
+    if let Err(code) = require_admin("rekey") {
+        return code;
+    }
+    let path = args.get_or(1, "");
+    let group = get_var("opt_group").trim().to_string();
+    if !path.is_empty() && !group.is_empty() {
+        return rekey_group_from_cli(Path::new(&path), &group);
+    }
+    let recipients: Vec<String> = get_var("opt_add_recipient")
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if path.is_empty() || recipients.is_empty() {
+        stderr!("❌ Usage: cage rekey <path> --add-recipient <KEY[,KEY...]> --identity <PATH>");
+        stderr!("         cage rekey <path> --group <NAME> [--plan] --identity <PATH>");
+        return 1;
+    }
+
+    let Some(identity) = parse_unlock_identity_from_cli() else {
+        stderr!("❌ Rekey requires --identity, --ssh-identity or --identity-provider");
+        stderr!("   An existing recipient's key is needed to unwrap the file key");
+        return 1;
+    };
+    let output = get_var("opt_output");
+    let output = (!output.is_empty()).then(|| PathBuf::from(output));
+    let verbose = is_true("opt_verbose");
+
+    let crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    match crud_manager.rekey_add_recipients(
+        Path::new(&path),
+        &identity,
+        &recipients,
+        output.as_deref(),
+    ) {
+        Ok(result) => {
+            echo!(
+                "🔑 Rekeyed {} file(s), {} failed",
+                result.processed_files.len(),
+                result.failed_files.len()
+            );
+            if verbose {
+                for failed in &result.failed_files {
+                    echo!("  ✗ {}", failed);
+                }
+            }
+            if result.failed_files.is_empty() {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            stderr!("❌ Rekey failed: {}", e);
+            1
+        }
+    }
+}
+
+/// `rekey --group`: show or execute the re-encryption plan for a changed group
+fn rekey_group_from_cli(path: &Path, group: &str) -> i32 {
+    let crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    if is_true("opt_plan") {
+        let throughput = AgeConfig::load_default()
+            .ok()
+            .and_then(|config| config.max_throughput)
+            .unwrap_or(DEFAULT_REKEY_THROUGHPUT);
+        return match crud_manager.plan_group_rekey(path, group) {
+            Ok(plan) => {
+                echo!("🔑 Rekey plan for {}", plan.summary(throughput));
+                for entry in plan.pending() {
+                    echo!("  - {} ({} bytes)", entry.path.display(), entry.bytes);
+                }
+                if is_true("opt_verbose") {
+                    for unknown in &plan.unknown {
+                        echo!("  ? {} (no .meta sidecar)", unknown.display());
+                    }
+                }
+                0
+            }
+            Err(e) => {
+                stderr!("❌ Rekey plan failed: {}", e);
+                1
+            }
+        };
+    }
+
+    let Some(identity) = parse_unlock_identity_from_cli() else {
+        stderr!("❌ Rekey requires --identity, --ssh-identity or --identity-provider");
+        stderr!("   The files are decrypted before being encrypted to the current group");
+        return 1;
+    };
+    match crud_manager.rekey_group(path, group, &identity) {
+        Ok(result) => {
+            echo!(
+                "🔑 Re-encrypted {} file(s) to group '{}', {} failed",
+                result.processed_files.len(),
+                group,
+                result.failed_files.len()
+            );
+            for failed in &result.failed_files {
+                echo!("  ✗ {}", failed);
+            }
+            if result.failed_files.is_empty() {
+                0
+            } else {
+                stderr!("   Run the same command again to resume the remaining files");
+                1
+            }
+        }
+        Err(e) => {
+            stderr!("❌ Rekey failed: {}", e);
+            1
+        }
+    }
+}
+
+/// Resolve the audit log to read: explicit argument, else the configured log path
+fn resolve_audit_log_arg(args: &Args, index: usize) -> Option<String> {
+    let log = args.get_or(index, "");
+    if !log.is_empty() {
+        return Some(log);
+    }
+    AgeConfig::load_default().ok()?.audit_log_path
+}
+
+/// Print audit events matching `--since/--until/--operation/--path/--result` filters
+fn execute_audit_query(log: &str) -> cage::AgeResult<usize> {
+    use cage::audit::query::parse_time_bound;
+    use cage::audit::AuditQuery;
+
+    let mut query = AuditQuery::new();
+    let since = get_var("opt_since");
+    if !since.is_empty() {
+        query = query.since(parse_time_bound(&since)?);
+    }
+    let until = get_var("opt_until");
+    if !until.is_empty() {
+        query = query.until(parse_time_bound(&until)?);
+    }
+    let operation = get_var("opt_operation");
+    if !operation.is_empty() {
+        query = query.operation(&operation);
+    }
+    let path = get_var("opt_path");
+    if !path.is_empty() {
+        query = query.path_glob(&path)?;
+    }
+    match get_var("opt_result").as_str() {
+        "" => {}
+        "success" | "ok" => query = query.success(true),
+        "failure" | "failed" => query = query.success(false),
+        other => {
+            return Err(AgeError::ConfigurationError {
+                parameter: "result".to_string(),
+                value: other.to_string(),
+                reason: "Expected success or failure".to_string(),
+            })
+        }
+    }
+
+    let events = query.run(Path::new(log))?;
+    if get_var("opt_format") == "json" || is_true("opt_json") {
+        for event in &events {
+            println!("{}", serde_json::to_string(event).unwrap_or_default());
+        }
+    } else {
+        println!(
+            "{:<20} {:<5} {:<20} {:<7} PATH",
+            "TIME", "LEVEL", "OPERATION", "RESULT"
+        );
+        for event in &events {
+            let result = match event.success {
+                Some(true) => "ok",
+                Some(false) => "FAILED",
+                None => "-",
+            };
+            println!(
+                "{:<20} {:<5} {:<20} {:<7} {}",
+                event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                event.level,
+                event.operation.as_deref().unwrap_or(&event.event_type),
+                result,
+                event.path.as_deref().unwrap_or("-")
+            );
+        }
+    }
+    Ok(events.len())
+}
+
+/// Audit log tooling (`cage audit verify|query <log>`)
+fn cmd_audit(args: Args) -> i32 {
+    let subcommand = args.get_or(1, "");
+    match subcommand.as_str() {
+        "query" => {
+            let Some(log) = resolve_audit_log_arg(&args, 2) else {
+                stderr!("❌ Usage: cage audit query <log> [--since T] [--until T] [--operation OP] [--path GLOB] [--result success|failure] [--json]");
+                return 1;
+            };
+            match execute_audit_query(&log) {
+                Ok(count) => {
+                    stderr!("{} matching event(s)", count);
+                    0
+                }
+                Err(e) => {
+                    stderr!("❌ Audit query failed: {}", e);
+                    1
+                }
+            }
+        }
+        "verify" => {
+            let log = resolve_audit_log_arg(&args, 2).unwrap_or_default();
+            let key_file = get_var("opt_key_file");
+            let key_file = if key_file.is_empty() {
+                env::var("CAGE_AUDIT_KEY_FILE").unwrap_or_default()
+            } else {
+                key_file
+            };
+            if log.is_empty() || key_file.is_empty() {
+                stderr!("❌ Usage: cage audit verify <log> --key-file <PATH>");
+                return 1;
+            }
+
+            let key = match fs::read(&key_file) {
+                Ok(key) => key.trim_ascii().to_vec(),
+                Err(e) => {
+                    stderr!("❌ Failed to read key file {}: {}", key_file, e);
+                    return 1;
+                }
+            };
+            match cage::audit::verify_log(Path::new(&log), &key) {
+                Ok(report) => {
+                    echo!("🔏 Audit log: {}", log);
+                    echo!("  Verified lines: {}", report.verified_lines);
+                    echo!("  Unsigned lines: {}", report.unsigned_lines);
+                    if report.prefix_lines > 0 {
+                        echo!(
+                            "  Unsigned prefix: {} line(s) before signing began",
+                            report.prefix_lines
+                        );
+                    }
+                    if report.broken_lines.is_empty() {
+                        echo!("  Chain: intact");
+                    } else {
+                        echo!("  Chain: BROKEN at line(s) {:?}", report.broken_lines);
+                    }
+                    if report.is_intact() {
+                        0
+                    } else {
+                        1
+                    }
+                }
+                Err(e) => {
+                    stderr!("❌ Audit verification failed: {}", e);
+                    1
+                }
+            }
+        }
+        _ => {
+            stderr!("❌ Unknown audit subcommand: '{}'", subcommand);
+            stderr!("Available: verify, query");
+            1
+        }
+    }
+}
+
 /// Verify file integrity using RSB dispatch
 fn cmd_verify(args: Args) -> i32 {
     let path = if args.remaining().is_empty() {
@@ -880,9 +2429,74 @@ fn cmd_verify(args: Args) -> i32 {
         PathBuf::from(args.get_or(1, "."))
     };
 
+    if is_true("opt_partial") {
+        return verify_partial_from_cli(&path);
+    }
+    let against_source = get_var("opt_against_source");
+    if !against_source.is_empty() {
+        return verify_against_source_from_cli(&path, Path::new(&against_source));
+    }
+
     let verbose = is_true("opt_verbose");
+    let deep = is_true("opt_deep");
+    let stream = is_true("opt_stream");
 
-    match execute_verify_operation(&path, verbose) {
+    if stream && !deep {
+        stderr!("❌ --stream requires --deep");
+        stderr!("Usage: cage verify <path> --deep --stream --identity <PATH>");
+        return 1;
+    }
+
+    let identity = if deep {
+        if let Some(identity) = parse_unlock_identity_from_cli() {
+            Some(identity)
+        } else if stream {
+            stderr!("❌ Streaming verification requires --identity or --ssh-identity");
+            stderr!("   Passphrase decryption would stage plaintext in temporary files");
+            return 1;
+        } else {
+            let passphrase_manager = PassphraseManager::new();
+            let passphrase = if let Some(result) =
+                read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd")
+            {
+                match result {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to read passphrase from command: {}", e);
+                        return 1;
+                    }
+                }
+            } else if is_true("opt_stdin_passphrase") {
+                match passphrase_manager.get_passphrase_with_mode(
+                    "Enter passphrase",
+                    false,
+                    PassphraseMode::Stdin,
+                ) {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to read passphrase from stdin: {}", e);
+                        return 1;
+                    }
+                }
+            } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
+                env_pass
+            } else {
+                match passphrase_manager.get_passphrase("Enter passphrase for verification", false)
+                {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to get passphrase: {}", e);
+                        return e.exit_code();
+                    }
+                }
+            };
+            Some(Identity::Passphrase(passphrase))
+        }
+    } else {
+        None
+    };
+
+    match execute_verify_operation(&path, identity, stream, verbose) {
         Ok(_) => {
             if verbose {
                 echo!("✅ Verification completed");
@@ -896,6 +2510,153 @@ fn cmd_verify(args: Args) -> i32 {
     }
 }
 
+/// `cage verify --partial [--clean]`: list (and delete) empty or truncated ciphertext.
+///
+/// Exits 1 while partial outputs remain, so scripts can gate on a clean tree.
+fn verify_partial_from_cli(path: &Path) -> i32 {
+    let manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize manager: {}", e);
+            return 1;
+        }
+    };
+    let suspects = match manager.scan_partial_outputs(path) {
+        Ok(suspects) => suspects,
+        Err(e) => {
+            stderr!("❌ Partial output scan failed: {}", e);
+            return 1;
+        }
+    };
+
+    if get_var("opt_format") == "json" || is_true("opt_json") {
+        println!("{}", serde_json::to_string(&suspects).unwrap_or_default());
+    } else if suspects.is_empty() {
+        echo!("✅ No partial ciphertext under {}", path.display());
+    } else {
+        echo!("⚠️  {} partial ciphertext file(s):", suspects.len());
+        for suspect in &suspects {
+            echo!(
+                "  - {} ({}, {} bytes)",
+                suspect.path.display(),
+                suspect.defect.describe(),
+                suspect.bytes
+            );
+        }
+    }
+
+    if suspects.is_empty() {
+        return 0;
+    }
+    if !is_true("opt_clean") {
+        return 1;
+    }
+    let confirmation = Confirmation::new(
+        "clean-partial",
+        format!("Delete {} partial ciphertext file(s)?", suspects.len()),
+    )
+    .detail("Their plaintext, if it still exists, is not touched.");
+    if let Err(code) = confirm_or_sure(&confirmation) {
+        return code;
+    }
+    match manager.remove_partial_outputs(&suspects) {
+        Ok(removed) => {
+            echo!("🧹 Removed {} partial output(s)", removed.len());
+            0
+        }
+        Err(e) => {
+            stderr!("❌ Failed to remove partial outputs: {}", e);
+            1
+        }
+    }
+}
+
+/// `cage verify <repo> --against-source <dir>`: confirm the encrypted repository
+/// corresponds exactly to a plaintext tree, using the checksum database.
+///
+/// Exits 1 on any difference.
+fn verify_against_source_from_cli(path: &Path, source_dir: &Path) -> i32 {
+    let comparison =
+        match cli_manager().and_then(|manager| manager.verify_against_source(path, source_dir)) {
+            Ok(comparison) => comparison,
+            Err(e) => {
+                stderr!("❌ Source comparison failed: {}", e);
+                return 1;
+            }
+        };
+
+    if get_var("opt_format") == "json" || is_true("opt_json") {
+        println!("{}", serde_json::to_string(&comparison).unwrap_or_default());
+    } else {
+        echo!(
+            "{} {} of {} file(s) match {}",
+            if comparison.is_exact() { "✅" } else { "❌" },
+            comparison.matched.len(),
+            comparison.matched.len() + comparison.missing.len() + comparison.changed.len(),
+            source_dir.display()
+        );
+        let sections = [
+            ("Not locked", &comparison.missing),
+            ("Changed since lock", &comparison.changed),
+            (
+                "Ciphertext modified or missing",
+                &comparison.ciphertext_modified,
+            ),
+            ("Encrypted but not in source", &comparison.extra),
+        ];
+        for (label, paths) in sections {
+            for path in paths {
+                echo!("  {}: {}", label, path);
+            }
+        }
+    }
+    if comparison.is_exact() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Prompter for CLI confirmations: the terminal when attached, otherwise decline
+fn cli_prompter() -> Arc<dyn Prompter> {
+    default_prompter()
+}
+
+/// `--i-am-sure` grants every confirmation; otherwise ask the operator.
+///
+/// Declining yields exit code 1; a forbidden prompt yields its own code.
+fn confirm_or_sure(confirmation: &Confirmation) -> Result<(), i32> {
+    if is_true("opt_i_am_sure") {
+        return Ok(());
+    }
+    match cli_prompter().confirm(confirmation) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(1),
+        Err(e) => {
+            stderr!("❌ {}", e);
+            Err(e.exit_code())
+        }
+    }
+}
+
+/// Exit code for a boxed CLI error: `AgeError`s pick their own, anything else is 1
+fn exit_code_for(error: &(dyn std::error::Error + 'static)) -> i32 {
+    error
+        .downcast_ref::<AgeError>()
+        .map_or(1, AgeError::exit_code)
+}
+
+/// Confirm use of a passphrase visible on the command line
+fn confirm_insecure_passphrase() -> Result<(), i32> {
+    confirm_or_sure(
+        &Confirmation::new(
+            "insecure-passphrase",
+            "Passphrase detected on command line!",
+        )
+        .detail("This is insecure and visible in process list."),
+    )
+}
+
 /// Batch process files using RSB dispatch
 fn cmd_batch(args: Args) -> i32 {
     let directory = PathBuf::from(args.get_or(1, ""));
@@ -919,19 +2680,40 @@ fn cmd_batch(args: Args) -> i32 {
         return 1;
     }
 
+    apply_quarantine_override();
+    if let Err(e) = apply_scheduling_overrides() {
+        stderr!("❌ {}", e);
+        return 1;
+    }
+
     // Get passphrase securely for batch operations
     let passphrase_manager = PassphraseManager::new();
     let passphrase = {
         let pass_var = get_var("opt_passphrase");
         if !pass_var.is_empty() {
             // Command line provided (warn but allow with confirmation)
-            stderr!("⚠️  Warning: Batch passphrase on command line is insecure");
-            stderr!("   This will be applied to multiple files!");
-            if !is_true("opt_i_am_sure") {
+            let confirmation = Confirmation::new(
+                "batch-passphrase",
+                "Batch passphrase on command line is insecure",
+            )
+            .detail(format!(
+                "This will be applied to multiple files in {}!",
+                directory.display()
+            ));
+            if let Err(code) = confirm_or_sure(&confirmation) {
                 stderr!("   Add --i-am-sure to confirm or use interactive prompt");
-                return 1;
+                return code;
             }
             pass_var
+        } else if let Some(result) = read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd")
+        {
+            match result {
+                Ok(pass) => pass,
+                Err(e) => {
+                    stderr!("❌ Failed to read passphrase from command: {}", e);
+                    return 1;
+                }
+            }
         } else if is_true("opt_stdin_passphrase") {
             match passphrase_manager.get_passphrase_with_mode(
                 "Enter passphrase for batch operation",
@@ -955,7 +2737,7 @@ fn cmd_batch(args: Args) -> i32 {
                 Ok(pass) => pass,
                 Err(e) => {
                     stderr!("❌ Failed to get passphrase: {}", e);
-                    return 1;
+                    return e.exit_code();
                 }
             }
         }
@@ -963,10 +2745,11 @@ fn cmd_batch(args: Args) -> i32 {
 
     let verbose = is_true("opt_verbose");
     let force = is_true("opt_i_am_sure");
-    let backup = is_true("opt_backup");
+    let backup = is_true("opt_backup") || !get_var("opt_backup_dir").is_empty();
     let preserve = is_true("opt_preserve");
 
-    match execute_batch_operation(
+    let mut failed_paths = Vec::new();
+    let code = match execute_batch_operation(
         &directory,
         &operation,
         &passphrase,
@@ -975,6 +2758,7 @@ fn cmd_batch(args: Args) -> i32 {
         force,
         backup,
         preserve,
+        &mut failed_paths,
     ) {
         Ok(_) => {
             if verbose {
@@ -986,7 +2770,8 @@ fn cmd_batch(args: Args) -> i32 {
             stderr!("❌ Batch operation failed: {}", e);
             1
         }
-    }
+    };
+    finish_failed_out(code, &failed_paths)
 }
 
 /// Run test suite using RSB dispatch
@@ -994,11 +2779,15 @@ fn cmd_test(_args: Args) -> i32 {
     if is_true("opt_progress_demo") {
         return run_progress_demo();
     }
+    if is_true("opt_self") {
+        return run_self_test_command();
+    }
 
     echo!(
         r#"🧪 Running Age Automation Test Suite...
 
 Available Tests:
+  --self             Round-trip lock/unlock/rotate/stream/verify in a temp workspace
   --progress-demo    Demonstrate progress indicators and styles
 
 Planned Tests:
@@ -1008,12 +2797,43 @@ Planned Tests:
   - Performance benchmarks
   - Compatibility tests
 
-Usage: cage test --progress-demo
-✅ Test suite framework ready for implementation"#
+Usage: cage test --self | --progress-demo"#
     );
     0
 }
 
+/// Run `cage test --self` and print one line per capability
+fn run_self_test_command() -> i32 {
+    echo!("🧪 Cage self-test (temporary workspace, throwaway keys)");
+    let report = run_self_test();
+    for check in &report.checks {
+        let marker = match check.status {
+            SelfTestStatus::Passed => "✅",
+            SelfTestStatus::Failed => "❌",
+            SelfTestStatus::Skipped => "⏭️ ",
+        };
+        echo!(
+            "  {} {:<12} {} ({} ms)",
+            marker,
+            check.name,
+            check.detail,
+            check.duration_ms
+        );
+    }
+
+    if report.passed() {
+        echo!("✅ All {} checks passed", report.checks.len());
+        0
+    } else {
+        stderr!(
+            "❌ {} of {} checks did not pass",
+            report.failed_count(),
+            report.checks.len()
+        );
+        1
+    }
+}
+
 /// Show demonstration using RSB dispatch
 fn cmd_demo(_args: Args) -> i32 {
     echo!(
@@ -1038,6 +2858,7 @@ This demonstration showcases Age encryption operations:
 🛠️  Advanced Usage:
   CAGE_PASSPHRASE=secret cage lock file.txt          # Environment variable (secure)
   echo 'secret' | cage lock file.txt --stdin-passphrase  # Stdin input (automation)
+  cage unlock file.txt.cage --passphrase-cmd "pass show repo/key"  # Secret from command
   cage rotate /repo                                   # Interactive with confirmation
 
 ⚠️  Insecure (not recommended):
@@ -1050,11 +2871,243 @@ This demonstration showcases Age encryption operations:
 
 // Operation Implementation Functions
 
+/// Execute a `--plan` lock run and report per-file failures
+fn execute_lock_plan(plan: &LockPlan, identity: &Identity, verbose: bool) -> i32 {
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+
+    match crud_manager.lock_plan(plan, identity) {
+        Ok(result) => {
+            if verbose {
+                for file in &result.processed_files {
+                    echo!("  🔒 {}", file);
+                }
+            }
+            for failed in &result.failed_files {
+                stderr!("  ❌ {}", failed);
+            }
+            print0_processed(&result);
+            if print0_mode() == Ok(None) {
+                echo!(
+                    "Plan {}: {} entries, {}",
+                    plan.source.display(),
+                    plan.entries.len(),
+                    result.summary()
+                );
+            }
+            let failed_paths: Vec<String> = result.failed_files.clone();
+            let code = if failed_paths.is_empty() { 0 } else { 1 };
+            finish_failed_out(code, &failed_paths)
+        }
+        Err(e) => {
+            stderr!("❌ Lock plan failed: {}", e);
+            1
+        }
+    }
+}
+
+const FLEET_USAGE: &str =
+    "Usage: cage fleet <status|lock|verify> --manifest <fleet.toml> [--deep] [--json]";
+
+/// Run status, lock or verify across every repository of a fleet manifest
+fn cmd_fleet(args: Args) -> i32 {
+    let command = match FleetCommand::parse(&args.get_or(1, "")) {
+        Ok(command) => command,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            stderr!("{}", FLEET_USAGE);
+            return 1;
+        }
+    };
+    let manifest_path = get_var("opt_manifest");
+    if manifest_path.is_empty() {
+        stderr!("❌ --manifest <FILE> is required");
+        stderr!("{}", FLEET_USAGE);
+        return 1;
+    }
+    let manifest = match FleetManifest::load(Path::new(&manifest_path)) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return e.exit_code();
+        }
+    };
+    let passphrase = if command == FleetCommand::Lock && manifest.needs_passphrase() {
+        match passphrase_identity_from_cli("Enter passphrase for repos without recipients", false) {
+            Ok(identity) => Some(identity),
+            Err(code) => return code,
+        }
+    } else {
+        None
+    };
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return 1;
+        }
+    };
+
+    let report = run_fleet(
+        &mut crud_manager,
+        &manifest,
+        command,
+        passphrase.as_ref(),
+        is_true("opt_deep"),
+    );
+    if get_var("opt_format") == "json" || is_true("opt_json") {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        for repo in &report.repos {
+            let mark = if repo.ok() { "✅" } else { "❌" };
+            match (&repo.error, repo.encryption_percentage) {
+                (Some(error), _) => echo!("{} {}: {}", mark, repo.name, error),
+                (None, Some(percentage)) => echo!(
+                    "{} {}: {} files, {:.1}% encrypted",
+                    mark,
+                    repo.name,
+                    repo.files,
+                    percentage
+                ),
+                (None, None) => echo!(
+                    "{} {}: {} files, {} failed",
+                    mark,
+                    repo.name,
+                    repo.files,
+                    repo.failed_files.len()
+                ),
+            }
+            for failed in &repo.failed_files {
+                echo!("    - {}", failed);
+            }
+        }
+        echo!("{}", report.summary());
+    }
+    if report.failed_repos() == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+/// Per-run backup retention and extension from `--backup-retention` / `--backup-extension`
+fn backup_overrides_from_cli(
+) -> Result<(Option<RetentionPolicyConfig>, Option<String>), Box<dyn std::error::Error>> {
+    let retention = match get_var("opt_backup_retention") {
+        value if value.is_empty() => None,
+        value => Some(RetentionPolicyConfig::parse(&value)?),
+    };
+    let extension = Some(get_var("opt_backup_extension")).filter(|e| !e.is_empty());
+    Ok((retention, extension))
+}
+
+/// Resolve `--backup-dir <PATH>`, creating the directory when missing
+fn backup_dir_from_cli() -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let value = get_var("opt_backup_dir");
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let dir = PathBuf::from(value);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create backup directory {}: {}", dir.display(), e))?;
+    Ok(Some(dir))
+}
+
+/// `--newer-than` / `--older-than` bounds on the files a directory lock/unlock visits
+fn mtime_window_from_cli() -> Result<MtimeWindow, Box<dyn std::error::Error>> {
+    let now = std::time::SystemTime::now();
+    let bound = |var: &str| -> AgeResult<Option<std::time::SystemTime>> {
+        match get_var(var) {
+            value if value.is_empty() => Ok(None),
+            value => MtimeWindow::parse_bound(&value, now).map(Some),
+        }
+    };
+    Ok(MtimeWindow {
+        newer_than: bound("opt_newer_than")?,
+        older_than: bound("opt_older_than")?,
+    })
+}
+
+/// `--chunked` / `--chunk-size <SIZE>`: chunk size for delta-friendly containers
+fn chunk_size_from_cli() -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    let value = get_var("opt_chunk_size");
+    if !value.is_empty() {
+        return Ok(Some(parse_byte_size(&value)?));
+    }
+    Ok(is_true("opt_chunked").then_some(cage::core::chunked::DEFAULT_CHUNK_SIZE))
+}
+
+/// Admin token from `--admin-token-file <PATH>` or `CAGE_ADMIN_TOKEN`
+fn admin_token_from_cli() -> Result<Option<String>, String> {
+    let file = get_var("opt_admin_token_file");
+    if !file.is_empty() {
+        return std::fs::read_to_string(&file)
+            .map(|token| Some(token.trim().to_string()))
+            .map_err(|e| format!("Failed to read admin token file {}: {}", file, e));
+    }
+    Ok(env::var(cage::core::roles::ADMIN_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.is_empty()))
+}
+
+/// Group store whose saves carry the CLI's admin token (see `core::roles`)
+fn load_group_store() -> Result<cage::core::GroupStore, i32> {
+    let token = admin_token_from_cli().map_err(|e| {
+        stderr!("❌ {}", e);
+        1
+    })?;
+    cage::core::GroupStore::load_default()
+        .map(|store| store.with_admin_token(token))
+        .map_err(|e| {
+            stderr!("❌ {}", e);
+            e.exit_code()
+        })
+}
+
+/// Refuse an admin-only operation without the admin token (see `core::roles`)
+fn require_admin(operation: &str) -> Result<(), i32> {
+    // Sealing must work on a config whose seal broke, so it cannot verify seals first
+    let config = if operation == "config seal" {
+        AgeConfig::load_default_unsealed()
+    } else {
+        AgeConfig::load_default()
+    }
+    .map_err(|e| {
+        stderr!("❌ Failed to load configuration: {}", e);
+        e.exit_code()
+    })?;
+    if !cage::core::roles::requires_admin(&config, operation) {
+        return Ok(());
+    }
+    let token = admin_token_from_cli().map_err(|e| {
+        stderr!("❌ {}", e);
+        1
+    })?;
+    cage::core::roles::authorize(&config, operation, token.as_deref()).map_err(|e| {
+        stderr!("❌ {}", e);
+        e.exit_code()
+    })
+}
+
+/// `--adapter <NAME>` override for lock/unlock requests
+fn adapter_from_cli() -> Option<String> {
+    let value = get_var("opt_adapter");
+    (!value.is_empty()).then_some(value)
+}
+
 /// Execute lock operation with RSB integration
 fn execute_lock_operation(
     paths: Vec<PathBuf>,
     identity: &Identity,
     recipients: &[Recipient],
+    tier_config: Option<&MultiRecipientConfig>,
+    group_pins: &[(String, String)],
+    skip_existing: bool,
     recursive: bool,
     pattern: Option<String>,
     backup: bool,
@@ -1062,6 +3115,7 @@ fn execute_lock_operation(
     _audit_log: Option<PathBuf>,
     verbose: bool,
     show_progress: bool,
+    failed_paths: &mut Vec<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if verbose {
         echo!("🔐 Executing lock operation...");
@@ -1071,8 +3125,20 @@ fn execute_lock_operation(
     if paths.is_empty() {
         return Err("No paths provided for lock operation".into());
     }
+    let attestation = Some(get_var("opt_attest"))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from);
+    if attestation.is_some() && paths.len() > 1 {
+        return Err("--attest describes one lock; pass a single path".into());
+    }
+    let output_path = Some(get_var("opt_output"))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from);
+    if output_path.is_some() && paths.len() > 1 {
+        return Err("--output names one ciphertext; pass a single file".into());
+    }
 
-    if recipients.is_empty() {
+    if recipients.is_empty() && tier_config.is_none() {
         if let Identity::Passphrase(pass) = identity {
             if pass.len() < 8 {
                 stderr!("⚠️  Warning: Passphrase is less than 8 characters. Consider using a stronger passphrase.");
@@ -1080,15 +3146,27 @@ fn execute_lock_operation(
         }
     }
 
+    let (backup_retention, backup_extension) = backup_overrides_from_cli()?;
     let options = LockOptions {
         recursive,
         format,
         pattern_filter: pattern,
+        modified: mtime_window_from_cli()?,
         backup_before_lock: backup,
-        backup_dir: None,
+        backup_dir: backup_dir_from_cli()?,
+        backup_retention,
+        backup_extension,
+        skip_existing,
+        verify_decryptable: None,
+        output_mode: None,
+        preserve_ownership: is_true("opt_preserve_ownership"),
+        meta: None,
+        chunk_size: chunk_size_from_cli()?,
+        output_path,
+        encryption_key: None,
     };
 
-    let mut crud_manager = CageManager::with_defaults()?;
+    let mut crud_manager = cli_manager()?;
 
     // Setup progress reporting if requested
     let progress_manager = if show_progress {
@@ -1140,11 +3218,34 @@ fn execute_lock_operation(
             lock_request = lock_request.with_pattern(pattern_val);
         }
 
-        if !recipients.is_empty() {
-            lock_request = lock_request.with_recipients(recipients.to_vec());
+        if !recipients.is_empty() {
+            lock_request = lock_request.with_recipients(recipients.to_vec());
+        }
+
+        if let Some(config) = tier_config {
+            lock_request = lock_request.with_multi_recipient_config(config.clone());
+        }
+
+        for (group, hash) in group_pins {
+            lock_request = lock_request.pin_group_hash(group.clone(), hash.clone());
         }
 
+        lock_request.modified = options.modified;
         lock_request.backup = backup;
+        lock_request.backup_dir = options.backup_dir.clone();
+        lock_request.backup_retention = options.backup_retention.clone();
+        lock_request.backup_extension = options.backup_extension.clone();
+        lock_request.skip_existing = options.skip_existing;
+        lock_request.preflight_recipients = is_true("opt_preflight_recipients");
+        lock_request.verify_decryptable = is_true("opt_verify_decryptable");
+        lock_request.preserve_ownership = options.preserve_ownership;
+        lock_request.write_meta = is_true("opt_write_meta");
+        lock_request.adapter = adapter_from_cli();
+        lock_request.attestation = attestation.clone();
+        lock_request.chunk_size = options.chunk_size;
+        lock_request.output_path = options.output_path.clone();
+        lock_request.common.allow_risky_path = is_true("opt_allow_risky_path");
+        lock_request.common.strict = is_true("opt_strict");
 
         let result = match crud_manager.lock_with_request(&lock_request) {
             Ok(result) => {
@@ -1161,14 +3262,30 @@ fn execute_lock_operation(
                 if let Some(ref task) = progress_task {
                     task.fail(&format!("✗ Failed to encrypt {}: {}", path.display(), e));
                 }
+                // The failing path and any not yet attempted are all retry candidates
+                failed_paths.extend(paths[index..].iter().map(|p| p.display().to_string()));
                 return Err(e.into());
             }
         };
+        failed_paths.extend(result.failed_files.iter().cloned());
+        print0_processed(&result);
+        if let Err(e) = interrupt::check("lock") {
+            failed_paths.extend(paths[index + 1..].iter().map(|p| p.display().to_string()));
+            return Err(e.into());
+        }
+        if is_true("opt_tree") {
+            print_tree_report(path, &result);
+        }
 
         if verbose {
             echo!("    Processed: {} files", result.processed_files.len());
             echo!("    Failed: {} files", result.failed_files.len());
+            echo!("    Skipped: {} files", result.skipped_files.len());
+            echo!("    Bytes in/out: {}/{}", result.bytes_in, result.bytes_out);
             echo!("    Duration: {}ms", result.execution_time_ms);
+            print_throughput(&result);
+            print_skipped_files(&result);
+            print_warnings(&result);
 
             if !result.failed_files.is_empty() {
                 echo!("    Failed files:");
@@ -1212,17 +3329,35 @@ fn execute_in_place_lock_operation(
     }
 
     // Safety validation
-    let safety_validator = SafetyValidator::new(danger_mode, i_am_sure);
-
+    let config = AgeConfig::load_default()?;
+    let quarantine = cage::mgr::Quarantine::from_config(&config);
+    let safety_validator = SafetyValidator::new(danger_mode, i_am_sure)
+        .with_prompter(cli_prompter())
+        .with_denylist(config.risky_path_denylist)
+        .allow_risky_path(is_true("opt_allow_risky_path"));
+
+    let verify_decryptable = is_true("opt_verify_decryptable");
+    let (backup_retention, backup_extension) = backup_overrides_from_cli()?;
     let options = LockOptions {
         recursive,
         format,
         pattern_filter: pattern,
+        modified: mtime_window_from_cli()?,
         backup_before_lock: backup,
-        backup_dir: None,
+        backup_dir: backup_dir_from_cli()?,
+        backup_retention,
+        backup_extension,
+        skip_existing: false,
+        verify_decryptable: None,
+        output_mode: None,
+        preserve_ownership: is_true("opt_preserve_ownership"),
+        meta: None,
+        chunk_size: chunk_size_from_cli()?,
+        output_path: None,
+        encryption_key: None,
     };
 
-    let mut crud_manager = CageManager::with_defaults()?;
+    let mut crud_manager = cli_manager()?;
 
     // Setup progress reporting if requested
     let progress_manager = if show_progress {
@@ -1239,7 +3374,12 @@ fn execute_in_place_lock_operation(
         None
     };
 
+    // Collected for --print0; in-place files keep their name
+    let mut locked = cage::OperationResult::new();
     for (index, path) in paths.iter().enumerate() {
+        // Stop between files; the file in flight is rolled back by InPlaceOperation
+        interrupt::check("lock")?;
+
         let progress_task = progress_manager.as_ref().map(|pm| {
             let style = if paths.len() > 1 {
                 ProgressStyle::Counter {
@@ -1277,10 +3417,16 @@ fn execute_in_place_lock_operation(
                     .with_format(options.format)
                     .recursive(options.recursive);
 
-            let lock_request = match options.pattern_filter.clone() {
+            let mut lock_request = match options.pattern_filter.clone() {
                 Some(pattern_val) => lock_request.with_pattern(pattern_val),
                 None => lock_request,
             };
+            lock_request.in_place = true;
+            lock_request.modified = options.modified;
+            lock_request.verify_decryptable = verify_decryptable;
+            lock_request.preserve_ownership = options.preserve_ownership;
+            lock_request.common.allow_risky_path = is_true("opt_allow_risky_path");
+            lock_request.common.strict = is_true("opt_strict");
 
             let result = match crud_manager.lock_with_request(&lock_request) {
                 Ok(result) => {
@@ -1309,6 +3455,7 @@ fn execute_in_place_lock_operation(
                 echo!("    Processed: {} files", result.processed_files.len());
                 echo!("    Failed: {} files", result.failed_files.len());
             }
+            locked.processed_files.extend(result.processed_files);
         } else if path.is_file() {
             // Single file in-place operation
 
@@ -1317,6 +3464,9 @@ fn execute_in_place_lock_operation(
             }
 
             // 1. Safety validation
+            if let Some(reason) = safety_validator.validate_target(path)? {
+                stderr!("⚠️  Risky path override for {}: {}", path.display(), reason);
+            }
             if let Err(e) = safety_validator.validate_in_place_operation(&path) {
                 if let Some(ref task) = progress_task {
                     task.fail(&format!("✗ Safety validation failed: {}", e));
@@ -1329,7 +3479,9 @@ fn execute_in_place_lock_operation(
             }
 
             // 2. Create in-place operation
-            let mut in_place_op = InPlaceOperation::new(&path);
+            let mut in_place_op = InPlaceOperation::new(&path)
+                .with_output_mode(config.ciphertext_mode)
+                .with_preserve_ownership(options.preserve_ownership || config.preserve_ownership);
 
             if let Some(ref task) = progress_task {
                 task.update_message("Executing atomic encryption");
@@ -1338,7 +3490,26 @@ fn execute_in_place_lock_operation(
             // 3. Execute with atomic replacement
             if let Err(e) = in_place_op.execute_lock(passphrase, danger_mode, |src, dst, pass| {
                 // Use the CageManager's encrypt_to_path method
-                match crud_manager.encrypt_to_path(src, dst, pass, format) {
+                let encrypted = crud_manager
+                    .encrypt_to_path(src, dst, pass, format)
+                    .and_then(|_| {
+                        if verify_decryptable {
+                            let identity = Identity::Passphrase(pass.to_string());
+                            crud_manager.verify_round_trip(src, dst, &identity)
+                        } else {
+                            Ok(())
+                        }
+                    })
+                    .and_then(|_| match &quarantine {
+                        // The plaintext is about to be replaced; keep a copy first
+                        Some(quarantine) => quarantine.preserve(src).map(|kept| {
+                            if verbose {
+                                echo!("    🗑️  Quarantined {}", kept.display());
+                            }
+                        }),
+                        None => Ok(()),
+                    });
+                match encrypted {
                     Ok(_) => {
                         if verbose {
                             echo!("    ✅ Encrypted {} -> {}", src.display(), dst.display());
@@ -1366,13 +3537,11 @@ fn execute_in_place_lock_operation(
                 task.complete(&recovery_msg);
             }
 
+            locked.add_success(path.display().to_string());
             if verbose {
                 echo!("    ✅ In-place operation completed for {}", path.display());
-                if !danger_mode {
-                    echo!(
-                        "    📝 Recovery file created: {}.tmp.recover",
-                        path.display()
-                    );
+                if let Some(recovery) = in_place_op.recovery_file() {
+                    echo!("    📝 Recovery file created: {}", recovery.display());
                     echo!("    ⚠️  Delete recovery file once you've verified encryption!");
                 }
             }
@@ -1381,6 +3550,7 @@ fn execute_in_place_lock_operation(
         }
     }
 
+    print0_processed(&locked);
     if verbose {
         echo!("✅ All in-place lock operations completed");
     }
@@ -1398,6 +3568,7 @@ fn execute_unlock_operation(
     _audit_log: Option<PathBuf>,
     verbose: bool,
     show_progress: bool,
+    failed_paths: &mut Vec<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if verbose {
         echo!("🔓 Executing unlock operation...");
@@ -1414,14 +3585,19 @@ fn execute_unlock_operation(
         }
     }
 
+    let relock_after = relock_window_from_cli()?;
     let options = UnlockOptions {
         selective,
         verify_before_unlock: true,
         pattern_filter: pattern,
+        modified: mtime_window_from_cli()?,
         preserve_encrypted: preserve,
+        verify_decryptable: None,
+        output_mode: None,
+        preserve_ownership: is_true("opt_preserve_ownership"),
     };
 
-    let mut crud_manager = CageManager::with_defaults()?;
+    let mut crud_manager = cli_manager()?;
 
     // Setup progress reporting if requested
     let progress_manager = if show_progress {
@@ -1467,11 +3643,18 @@ fn execute_unlock_operation(
         // Use the new request API (CAGE-11)
         let mut unlock_request = UnlockRequest::new(path.clone(), identity.clone())
             .selective(options.selective)
-            .preserve_encrypted(options.preserve_encrypted);
+            .preserve_encrypted(options.preserve_encrypted)
+            .verify_decryptable(is_true("opt_verify_decryptable"))
+            .preserve_ownership(options.preserve_ownership);
 
         if let Some(pattern_val) = options.pattern_filter.clone() {
             unlock_request = unlock_request.with_pattern(pattern_val);
         }
+        unlock_request.modified = options.modified;
+        unlock_request.relock_after = relock_after;
+        unlock_request.adapter = adapter_from_cli();
+        unlock_request.common.allow_risky_path = is_true("opt_allow_risky_path");
+        unlock_request.common.strict = is_true("opt_strict");
 
         let result = match crud_manager.unlock_with_request(&unlock_request) {
             Ok(result) => {
@@ -1488,28 +3671,115 @@ fn execute_unlock_operation(
                 if let Some(ref task) = progress_task {
                     task.fail(&format!("✗ Failed to decrypt {}: {}", path.display(), e));
                 }
+                // The failing path and any not yet attempted are all retry candidates
+                failed_paths.extend(paths[index..].iter().map(|p| p.display().to_string()));
                 return Err(e.into());
             }
         };
+        failed_paths.extend(result.failed_files.iter().cloned());
+        print0_processed(&result);
+        if let Err(e) = interrupt::check("unlock") {
+            failed_paths.extend(paths[index + 1..].iter().map(|p| p.display().to_string()));
+            return Err(e.into());
+        }
+        if is_true("opt_tree") {
+            print_tree_report(path, &result);
+        }
 
         if verbose {
             echo!("    Processed: {} files", result.processed_files.len());
             echo!("    Failed: {} files", result.failed_files.len());
+            echo!("    Skipped: {} files", result.skipped_files.len());
+            echo!("    Bytes in/out: {}/{}", result.bytes_in, result.bytes_out);
             echo!("    Duration: {}ms", result.execution_time_ms);
+            print_throughput(&result);
+            print_skipped_files(&result);
+            print_warnings(&result);
         }
     }
 
+    if let Some(window) = relock_after {
+        let timer = schedule_relock_timer(window)?;
+        echo!(
+            "⏲️  Plaintext will be relocked in {}s ({}; or run: cage relock)",
+            window.as_secs(),
+            timer
+        );
+    }
+
     Ok(())
 }
 
+/// `--for <DUR>` on unlock: how long plaintext may stay before `cage relock` removes it
+fn relock_window_from_cli() -> Result<Option<std::time::Duration>, Box<dyn std::error::Error>> {
+    let value = get_var("opt_for");
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match parse_duration(&value) {
+        Some(window) if !window.is_zero() => Ok(Some(window)),
+        _ => Err(format!("Invalid --for '{}': expected a duration like 30m", value).into()),
+    }
+}
+
+/// Run `cage relock` once `window` has passed.
+///
+/// Prefers a transient systemd user timer, which survives logout; otherwise spawns a
+/// detached `cage relock --wait` that sleeps until the recorded expiries.
+fn schedule_relock_timer(
+    window: std::time::Duration,
+) -> Result<String, Box<dyn std::error::Error>> {
+    use std::process::{Command, Stdio};
+
+    let exe = env::current_exe()?;
+    let mut systemd = Command::new("systemd-run");
+    systemd
+        .args(["--user", "--quiet", "--collect"])
+        .arg(format!("--on-active={}s", window.as_secs().max(1)));
+    if let Ok(store) = env::var("CAGE_RELOCK_FILE") {
+        systemd.arg(format!("--setenv=CAGE_RELOCK_FILE={}", store));
+    }
+    let status = systemd
+        .arg(&exe)
+        .arg("relock")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if matches!(status, Ok(status) if status.success()) {
+        return Ok("systemd user timer".to_string());
+    }
+
+    let mut waiter = Command::new(&exe);
+    waiter
+        .args(["relock", "--wait"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        // Own process group, so closing the terminal does not take it down
+        use std::os::unix::process::CommandExt;
+        waiter.process_group(0);
+    }
+    waiter.spawn()?;
+    Ok("background relock process".to_string())
+}
+
 /// Execute status operation with RSB integration
-fn execute_status_operation(path: &Path, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn execute_status_operation(
+    path: &Path,
+    verbose: bool,
+    fast: bool,
+    recursive: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     if verbose {
         echo!("📊 Checking status: {}", path.display());
     }
 
-    let crud_manager = CageManager::with_defaults()?;
-    let mut status_request = StatusRequest::new(path.to_path_buf());
+    let crud_manager = cli_manager()?;
+    let mut status_request = StatusRequest::new(path.to_path_buf()).fast(fast);
+    status_request.recursive = recursive;
     status_request.common.verbose = verbose;
     let status = crud_manager.status_with_request(&status_request)?;
 
@@ -1542,9 +3812,38 @@ fn execute_status_operation(path: &Path, verbose: bool) -> Result<(), Box<dyn st
         }
     }
 
+    if verbose && path.is_file() && status.encrypted_files > 0 {
+        match CipherMeta::load(path)? {
+            Some(sidecar) => {
+                echo!("  🏷️  Sidecar {}:", meta::meta_path(path).display());
+                for line in sidecar.describe() {
+                    echo!("    {}", line);
+                }
+            }
+            None => echo!("  No .meta sidecar; recipients unknown"),
+        }
+    }
+
     Ok(())
 }
 
+/// `cage status --json`: the nested per-directory status tree for dashboards
+fn print_status_tree(path: &Path, recursive: bool, fast: bool) -> i32 {
+    let mut request = StatusRequest::new(path.to_path_buf()).fast(fast);
+    request.recursive = recursive;
+    let tree = cli_manager().and_then(|manager| manager.status_tree(&request));
+    match tree {
+        Ok(tree) => {
+            println!("{}", serde_json::to_string_pretty(&tree).unwrap());
+            0
+        }
+        Err(e) => {
+            stderr!("❌ Status check failed: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
 /// Execute rotate operation with RSB integration
 fn execute_rotate_operation(
     repository: &Path,
@@ -1557,7 +3856,7 @@ fn execute_rotate_operation(
         echo!("🔄 Rotating keys for: {}", repository.display());
     }
 
-    let mut crud_manager = CageManager::with_defaults()?;
+    let mut crud_manager = cli_manager()?;
     let mut rotate_request = RotateRequest::new(
         repository.to_path_buf(),
         Identity::Passphrase(old_passphrase.to_string()),
@@ -1578,13 +3877,23 @@ fn execute_rotate_operation(
 }
 
 /// Execute verify operation with RSB integration
-fn execute_verify_operation(path: &Path, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn execute_verify_operation(
+    path: &Path,
+    identity: Option<Identity>,
+    stream: bool,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     if verbose {
         echo!("🔍 Verifying integrity: {}", path.display());
     }
 
-    let crud_manager = CageManager::with_defaults()?;
-    let result = crud_manager.verify(path)?;
+    let mut crud_manager = cli_manager()?;
+    let mut verify_request = VerifyRequest::new(path.to_path_buf());
+    if let Some(identity) = identity {
+        verify_request = verify_request.deep_verify(identity).stream_verify(stream);
+    }
+    verify_request.common.verbose = verbose;
+    let result = crud_manager.verify_with_request(&verify_request)?;
 
     echo!(
         "🔍 Verification Result:
@@ -1596,6 +3905,18 @@ fn execute_verify_operation(path: &Path, verbose: bool) -> Result<(), Box<dyn st
         result.overall_status
     );
 
+    if !result.stream_digests.is_empty() {
+        echo!("  🔐 Plaintext digests (streamed, not written to disk):");
+        for digest in &result.stream_digests {
+            echo!(
+                "    - {} ({} bytes, sha256 {})",
+                digest.file_path.display(),
+                digest.plaintext_bytes,
+                digest.sha256
+            );
+        }
+    }
+
     if !result.failed_files.is_empty() {
         echo!("  ❌ Failed verification:");
         for failed in &result.failed_files {
@@ -1616,6 +3937,7 @@ fn execute_batch_operation(
     force: bool,
     backup: bool,
     preserve: bool,
+    failed_paths: &mut Vec<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if verbose {
         echo!(
@@ -1641,6 +3963,7 @@ fn execute_batch_operation(
 
     request.common.verbose = verbose;
     request.common.force = force;
+    request.common.strict = is_true("opt_strict");
 
     if let Some(pattern) = pattern {
         request = request.with_pattern(pattern);
@@ -1649,13 +3972,39 @@ fn execute_batch_operation(
     if backup {
         request = request.backup(true);
     }
+    if let Some(dir) = backup_dir_from_cli()? {
+        request = request.with_backup_dir(dir);
+    }
 
     if preserve && matches!(batch_operation, BatchOperation::Unlock) {
         request = request.preserve_encrypted(true);
     }
 
-    let mut crud_manager = CageManager::with_defaults()?;
-    let result = crud_manager.batch_with_request(&request)?;
+    let size_bound = |var: &str| -> AgeResult<Option<u64>> {
+        match get_var(var) {
+            value if value.is_empty() => Ok(None),
+            value => parse_byte_size(&value).map(Some),
+        }
+    };
+    request = request.with_size_range(size_bound("opt_min_size")?, size_bound("opt_max_size")?);
+    let order = get_var("opt_order");
+    if !order.is_empty() {
+        request = request.ordered(BatchOrder::parse(&order)?);
+    }
+
+    let mut crud_manager = cli_manager()?;
+    let result = match crud_manager.batch_with_request(&request) {
+        Ok(result) => result,
+        Err(e) => {
+            failed_paths.push(directory.display().to_string());
+            return Err(e.into());
+        }
+    };
+    failed_paths.extend(result.failed_files.iter().cloned());
+    print0_processed(&result);
+    if print0_mode() != Ok(None) {
+        return Ok(());
+    }
 
     let operation_label = match batch_operation {
         BatchOperation::Lock => "lock",
@@ -1667,14 +4016,22 @@ fn execute_batch_operation(
   Operation: {}
   Processed files: {}
   Failed files: {}
+  Skipped files: {}
   Success rate: {:.1}%
+  Bytes in/out: {}/{}
   Duration: {}ms",
         operation_label,
         result.processed_files.len(),
         result.failed_files.len(),
+        result.skipped_files.len(),
         result.success_rate(),
+        result.bytes_in,
+        result.bytes_out,
         result.execution_time_ms
     );
+    print_throughput(&result);
+    print_skipped_files(&result);
+    print_warnings(&result);
 
     if !result.failed_files.is_empty() {
         echo!("  ❌ Failed files:");
@@ -1686,24 +4043,105 @@ fn execute_batch_operation(
     Ok(())
 }
 
+/// Print input throughput when the result carries byte statistics
+fn print_throughput(result: &cage::OperationResult) {
+    if let Some(rate) = result.throughput_bytes_per_sec() {
+        echo!("    Throughput: {:.1} KiB/s", rate / 1024.0);
+    }
+}
+
+/// Print the per-directory tree report for `--tree` (JSON with `--json`)
+fn print_tree_report(root: &Path, result: &cage::OperationResult) {
+    let report = result.tree_report(root);
+    if get_var("opt_format") == "json" || is_true("opt_json") {
+        println!("{}", serde_json::to_string(&report).unwrap_or_default());
+    } else {
+        print!("{}", report.render());
+    }
+}
+
+/// List skipped files with their reasons
+fn print_skipped_files(result: &cage::OperationResult) {
+    if !result.skipped_files.is_empty() {
+        echo!("    Skipped files:");
+        for skipped in &result.skipped_files {
+            echo!("      - {} ({})", skipped.path, skipped.reason);
+        }
+    }
+}
+
+/// List non-fatal warnings with their category
+fn print_warnings(result: &cage::OperationResult) {
+    if !result.warnings.is_empty() {
+        stderr!("    ⚠️  Warnings:");
+        for warning in &result.warnings {
+            stderr!(
+                "      - [{}] {}",
+                warning.category.as_str(),
+                warning.message
+            );
+        }
+    }
+}
+
 /// Proxy command - Forward arguments to Age binary with PTY automation
 fn cmd_proxy(args: Args) -> i32 {
     if let Err(e) = execute_proxy_command(args) {
-        echo!("❌ Proxy command failed: {}", e);
+        stderr!("❌ Proxy command failed: {}", e);
         return 1;
     }
     0
 }
 
-fn execute_proxy_command(args: Args) -> cage::AgeResult<()> {
-    use cage::pty::PtyAgeAutomator;
+/// Expand `--group <NAME[,NAME...]>` into age recipients from the configured groups
+fn collect_proxy_group_recipients(config: &AgeConfig) -> cage::AgeResult<Vec<String>> {
+    let groups = get_var("opt_group");
+    let names: Vec<&str> = groups
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut recipients = Vec::new();
+    for name in names {
+        let group =
+            config
+                .get_recipient_group(name)
+                .ok_or_else(|| AgeError::ConfigurationError {
+                    parameter: "group".to_string(),
+                    value: name.to_string(),
+                    reason: "Recipient group not found in configuration".to_string(),
+                })?;
+        if group.is_empty() {
+            return Err(AgeError::ConfigurationError {
+                parameter: "group".to_string(),
+                value: name.to_string(),
+                reason: "Recipient group has no recipients".to_string(),
+            });
+        }
+        for recipient in &group.recipients {
+            if !recipients.contains(recipient) {
+                recipients.push(recipient.clone());
+            }
+        }
+    }
+    Ok(recipients)
+}
 
-    echo!("🔗 Cage Age Proxy - PTY automation for direct Age commands");
+fn execute_proxy_command(args: Args) -> cage::AgeResult<()> {
+    stderr!("🔗 Cage Age Proxy - direct Age commands with identity injection");
+    let config = AgeConfig::load_default()?;
 
     // Build Age command arguments from --age-* flags
     let mut age_args = Vec::new();
 
     // Check common Age flags using RSB pattern
+    if is_true("opt_age_e") || is_true("opt_age_encrypt") {
+        age_args.push("-e".to_string());
+    }
     if is_true("opt_age_p") || is_true("opt_age_passphrase") {
         age_args.push("-p".to_string());
     }
@@ -1748,13 +4186,43 @@ fn execute_proxy_command(args: Args) -> cage::AgeResult<()> {
         age_args.push(recipient_val);
     }
 
-    // Add remaining positional arguments (files) - only add file paths
-    for remaining_arg in args.remaining() {
-        if !remaining_arg.starts_with("--")
-            && !remaining_arg.contains("target/debug/cage")
-            && std::path::Path::new(&remaining_arg).exists()
-        {
-            age_args.push(remaining_arg);
+    // Inject recipients from configured groups
+    for recipient in collect_proxy_group_recipients(&config)? {
+        age_args.push("-r".to_string());
+        age_args.push(recipient);
+    }
+
+    // Inject an identity from a provider alias/URI; the staged key file must outlive age
+    let mut provider_passphrase = None;
+    let provider_ref = get_var("opt_identity_provider");
+    let _resolved_identity = if provider_ref.is_empty() {
+        None
+    } else {
+        let resolved = IdentityProviderRegistry::from_config(&config)
+            .resolve(&Identity::Provider(provider_ref))?;
+        match resolved.identity() {
+            Identity::IdentityFile(path) | Identity::SshKey(path) => {
+                age_args.push("-i".to_string());
+                age_args.push(path.display().to_string());
+            }
+            Identity::Passphrase(passphrase) => provider_passphrase = Some(passphrase.clone()),
+            _ => {}
+        }
+        Some(resolved)
+    };
+
+    // Everything after a literal `--` is forwarded verbatim; otherwise only file paths
+    let remaining = args.remaining();
+    if let Some(split) = remaining.iter().position(|arg| arg == "--") {
+        age_args.extend(remaining[split + 1..].iter().cloned());
+    } else {
+        for remaining_arg in remaining {
+            if !remaining_arg.starts_with("--")
+                && !remaining_arg.contains("target/debug/cage")
+                && std::path::Path::new(&remaining_arg).exists()
+            {
+                age_args.push(remaining_arg);
+            }
         }
     }
 
@@ -1764,59 +4232,128 @@ fn execute_proxy_command(args: Args) -> cage::AgeResult<()> {
         echo!("  cage proxy --age-p --age-o=/tmp/output.age input.txt");
         echo!("  cage proxy --age-d --age-i=key.txt encrypted.age");
         echo!("  cage proxy --age-passphrase --age-output=/tmp/out.age file.txt");
+        echo!("  cage proxy --group team -- -e < plain.txt > secret.age");
         return Ok(());
     }
 
-    echo!("🔧 Age command: age {}", age_args.join(" "));
+    stderr!("🔧 Age command: age {}", age_args.join(" "));
 
-    // Check if this requires PTY automation (passphrase operations)
-    let is_encrypt = age_args
+    // Passphrase mode needs PTY automation; key-based operations stream natively
+    let is_passphrase = age_args
         .iter()
         .any(|arg| arg == "-p" || arg == "--passphrase");
     let is_decrypt = age_args.iter().any(|arg| arg == "-d" || arg == "--decrypt");
-    let needs_pty = is_encrypt || is_decrypt; // Both encrypt and decrypt may need PTY for passphrases
+    let has_identity = age_args
+        .iter()
+        .any(|arg| arg == "-i" || arg == "--identity");
+    let needs_pty = is_passphrase || provider_passphrase.is_some() || (is_decrypt && !has_identity);
+
+    // Both paths run the age binary, so it must match any pinned hash first and is
+    // confined by the configured sandbox like every other age process
+    cage::adp::pin::verify_pins(&config)?;
+    cage::adp::supervisor::configure(&config);
+    let outcome = run_proxy_age(&age_args, needs_pty, provider_passphrase);
+    if is_decrypt {
+        let identity_type = if needs_pty {
+            "passphrase"
+        } else {
+            "identity-file"
+        };
+        log_proxy_decryption(&config, &age_args, identity_type, outcome.is_ok());
+    }
+    outcome?;
 
-    // Create PTY automator
-    let pty_automator = PtyAgeAutomator::new()?;
+    stderr!("✅ Age proxy command completed successfully");
+    Ok(())
+}
 
-    if needs_pty {
-        echo!("🔐 PTY automation required for passphrase operations");
+/// Run age with `age_args`, through PTY automation when a passphrase is needed
+fn run_proxy_age(
+    age_args: &[String],
+    needs_pty: bool,
+    provider_passphrase: Option<String>,
+) -> cage::AgeResult<()> {
+    use cage::pty::PtyAgeAutomator;
 
-        // Create passphrase manager and get passphrase from user
-        let passphrase_manager = PassphraseManager::new();
-        let passphrase = if is_true("opt_stdin_passphrase") {
-            passphrase_manager.get_passphrase_with_mode(
-                "Enter passphrase for Age operation",
-                false,
-                PassphraseMode::Stdin,
-            )?
-        } else {
-            passphrase_manager.get_passphrase("Enter passphrase for Age operation", false)?
+    if needs_pty {
+        stderr!("🔐 PTY automation required for passphrase operations");
+        let pty_automator = PtyAgeAutomator::new()?;
+
+        let passphrase = match provider_passphrase {
+            Some(passphrase) => passphrase,
+            None => {
+                let passphrase_manager = PassphraseManager::new();
+                if is_true("opt_stdin_passphrase") {
+                    passphrase_manager.get_passphrase_with_mode(
+                        "Enter passphrase for Age operation",
+                        false,
+                        PassphraseMode::Stdin,
+                    )?
+                } else {
+                    passphrase_manager
+                        .get_passphrase("Enter passphrase for Age operation", false)?
+                }
+            }
         };
 
         // Execute with PTY automation
-        let output = pty_automator.execute_age_command(&age_args, Some(&passphrase))?;
+        let output = pty_automator.execute_age_command(age_args, Some(&passphrase))?;
 
         // Print Age output (if any)
         if !output.is_empty() {
             print!("{}", output);
         }
     } else {
-        echo!("⚡ Direct Age execution (no passphrase needed)");
-
-        // Execute without passphrase using PTY (for cross-platform compatibility)
-        let output = pty_automator.execute_age_command(&age_args, None)?;
-
-        // Print Age output (if any)
-        if !output.is_empty() {
-            print!("{}", output);
+        stderr!("⚡ Streaming stdin/stdout through age");
+
+        let mut command = std::process::Command::new("age");
+        command
+            .args(age_args)
+            .stdin(std::process::Stdio::inherit())
+            .stdout(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit());
+        cage::adp::sandbox::apply(&mut command);
+        let status = command.status().map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AgeError::AgeBinaryNotFound(format!("age command not found: {}", e))
+            } else {
+                AgeError::ProcessExecutionFailed {
+                    command: "age".to_string(),
+                    exit_code: None,
+                    stderr: e.to_string(),
+                }
+            }
+        })?;
+        if !status.success() {
+            return Err(AgeError::ProcessExecutionFailed {
+                command: "age".to_string(),
+                exit_code: status.code(),
+                stderr: "age exited with a failure status".to_string(),
+            });
         }
     }
-
-    echo!("✅ Age proxy command completed successfully");
     Ok(())
 }
 
+/// Audit a proxied decryption so it reaches the anomaly heuristics like `cage unlock`
+fn log_proxy_decryption(
+    config: &AgeConfig,
+    age_args: &[String],
+    identity_type: &str,
+    success: bool,
+) {
+    let input = age_args
+        .last()
+        .map(std::path::Path::new)
+        .filter(|path| path.is_file())
+        .unwrap_or(std::path::Path::new("<stream>"));
+    let logged = cage::AuditLogger::from_config(config)
+        .and_then(|logger| logger.log_decryption_event(input, identity_type, success));
+    if let Err(e) = logged {
+        stderr!("⚠️  Failed to audit proxied decryption: {}", e);
+    }
+}
+
 /// Show version information with logo
 fn show_version() {
     logo();
@@ -1842,26 +4379,127 @@ fn show_help() {
     println!("COMMANDS:");
     println!("  lock           Encrypt files/directories");
     println!("  unlock         Decrypt files/directories");
+    println!("  show           Print a small secret, or copy it with --clip (clipboard feature)");
+    println!("  open           Decrypt a repository into a tracked workspace (tmpfs by default)");
+    println!("  close          Encrypt a session's changes back and remove its plaintext");
     println!("  status         Check encryption status");
     println!("  rotate         Rotate encryption keys");
     println!("  verify         Verify file integrity");
+    println!("  convert        Rewrap ciphertext as ascii armor or binary (no keys needed)");
+    println!("  rekey          Add recipients by rewriting headers (no payload re-encryption)");
+    println!("                 --group <NAME> [--plan]: re-encrypt to the group's current members");
+    println!("  relock         Remove plaintext from `unlock --for` once expired (--all: now)");
+    println!(
+        "  emergency-unlock  Decrypt with an escrow identity or passphrase, keeping originals"
+    );
+    println!(
+        "                 --confirm=CONFIRM_EMERGENCY_UNLOCK [--quarantine=<DIR>] (else prompts)"
+    );
+    println!(
+        "  mount          Read-only FUSE view of decrypted files (requires the mount feature)"
+    );
+    println!("  audit          Audit log tooling (verify chain signatures, query events)");
     println!("  batch          Bulk operations");
+    println!("  fleet          Run status/lock/verify across repositories in a --manifest file");
     println!("  keygen         Generate Age identity keypairs");
-    println!("  proxy          Direct Age commands with PTY");
+    println!("  key from-ssh   Derive an age identity/recipient from an SSH key");
+    println!("  recipients     Import (GitHub/GitLab keys or a file) and export recipient groups");
+    println!("  worker         Run NDJSON job queue (bulk service mode)");
+    println!("  proxy          Direct Age commands (PTY or stdin/stdout streaming)");
     println!("  config         Show/manage configuration");
     println!("  adapter        Inspect adapter capabilities");
+    println!("  tui            Interactive repository triage (requires the tui feature)");
     println!("  test           Run test suite & demos");
     println!("  demo           Show demonstrations");
     println!();
     println!("GLOBAL OPTIONS:");
     println!("  --verbose, -v          Show detailed operation progress");
+    println!(
+        "  --non-interactive      Never prompt; fail with exit code 3 instead (CAGE_NON_INTERACTIVE=1)"
+    );
     println!("  --progress             Display professional progress indicators");
+    println!(
+        "  --profile <NAME>       Performance preset: laptop, server or io-limited (CAGE_PROFILE)"
+    );
     println!("  --format <FORMAT>      Encryption format: binary (default) or ascii");
+    println!("  --to <ascii|binary>    Convert: target ciphertext encoding");
+    println!("  --add-recipient <KEY[,..]>  Rekey: recipients to add to existing files");
+    println!("  --key-file <PATH>      Audit verify: HMAC key used to sign the log");
+    println!(
+        "  --admin-token-file <PATH>  Admin token for rekey/rotate/policy edits (CAGE_ADMIN_TOKEN)"
+    );
+    println!("  --since/--until <T>    Audit query: time range (RFC 3339 or YYYY-MM-DD)");
+    println!("  --operation <OP> --path <GLOB> --result <success|failure>  Audit query filters");
     println!("  --audit-log <PATH>     Write audit log for security compliance");
+    println!(
+        "  --skip-existing        Lock: skip unchanged files locked before to the same recipients"
+    );
+    println!(
+        "  --chunked              Lock: delta-friendly chunked output (1M chunks) for rsync/restic"
+    );
+    println!("  --chunk-size <SIZE>    Lock: chunk size for --chunked output, e.g. 4M");
+    println!(
+        "  --output <PATH>        Lock: ciphertext path for a single file (default <file>.cage)"
+    );
+    println!(
+        "  --fast                 Status: answer from .cage/state.json, re-listing changed dirs"
+    );
+    println!("  --recursive --json     Status: nested per-directory/per-file JSON for dashboards");
+    println!("  --manifest <FILE>      Fleet: TOML/JSON list of repositories with identities/groups");
+    println!(
+        "  --allow-risky-path     Lock/unlock: permit recursive or in-place runs on /, $HOME, denylist"
+    );
+    println!(
+        "  --strict               Lock/unlock/batch: fail files on skips and backup/cleanup problems"
+    );
+    println!("  --preflight-recipients Lock: check every recipient with age before encrypting");
+    println!("  --verify-decryptable   Lock/unlock: round-trip outputs before deleting anything");
+    println!("  --preserve-ownership   Lock/unlock: keep the source uid/gid on outputs (as root)");
+    println!("  --tree                 Lock/unlock: per-directory counts, sizes and failures");
+    println!(
+        "  --newer-than <AGE|T>   Lock/unlock: only files modified after (7d, 12h, 2026-01-31)"
+    );
+    println!("  --older-than <AGE|T>   Lock/unlock: only files modified before");
+    println!("  --for <DUR>            Unlock: keep ciphertext and relock after DUR (30m, 2h)");
+    println!("  --stdout               Unlock: one file's plaintext to stdout (identity/SSH key, not a TTY)");
+    println!("  --clip                 Show: copy to the clipboard instead of printing");
+    println!("  --clear-after <DUR>    Show: clear the clipboard after DUR (default 45s)");
+    println!("  --workspace <DIR>      Open: decrypt here instead of /dev/shm/cage-session-*");
+    println!("  --discard              Close: drop the workspace without encrypting changes");
+    println!("  --write-meta           Lock: record recipients in a <file>.cage.meta sidecar");
+    println!(
+        "  --attest <PATH>        Lock: write an in-toto attestation (DSSE envelope, unsigned)"
+    );
+    println!("  --plan <FILE>          Lock: per-path options from a TOML/JSON plan file");
+    println!(
+        "  --adapter <NAME>       Lock/unlock: backend for this run (shell-v2, shell-v1, pipe)"
+    );
+    println!("  --backup-dir <PATH>    Lock/batch: write backups here (created if missing)");
+    println!("  --backup-retention <P> Lock: retention for this run's backups (keep_last:N, keep_days:N, ...)");
+    println!("  --backup-extension <E> Lock: backup file extension (default: .bak)");
+    println!("  --quarantine <DIR>     Lock/unlock/batch: keep removed or replaced files in DIR");
+    println!("  --group <NAME[,..]>    Proxy: add recipients from configured groups");
+    println!("                         Recipients import/export: group to register or print");
+    println!("  --failed-out <PATH>    Lock/unlock/batch: write failed paths for retry");
+    println!("  --files-from=<PATH|->  Lock/unlock: also process paths listed in PATH or stdin");
+    println!(
+        "  --print0[=failed]      Lock/unlock/batch: NUL-terminated processed (or failed) paths on stdout"
+    );
+    println!("  -0, --null             --files-from entries are NUL separated (find -print0)");
+    println!("  --min-size/--max-size <SIZE>  Batch: only files within this size (512K, 1G)");
+    println!(
+        "  --order <ORDER>        Batch: largest-first or smallest-first (default: traversal)"
+    );
+    println!("  --force                Lock: re-encrypt everything (overrides --skip-existing)");
     println!(
         "  --streaming-strategy <temp|pipe|auto>  Select streaming mode (pipe needs recipients + identity file)"
     );
     println!();
+    println!("SCHEDULING OPTIONS (lock, unlock, batch):");
+    println!("  --max-throughput <RATE>  Cap throughput across files (e.g. 50M, 512K)");
+    println!("  --nice <N>               Process nice value inherited by age (-20..19)");
+    println!("  --ionice <CLASS>         I/O class: idle or best-effort[:0-7] (Linux)");
+    println!();
     println!("IN-PLACE OPERATION OPTIONS:");
     println!("  --in-place             Encrypt/decrypt files in-place (overwrites original)");
     println!("  --danger-mode          Skip recovery file creation (requires DANGER_MODE=1)");
@@ -1872,17 +4510,42 @@ fn show_help() {
     println!("  --recipients <LIST>        Comma-separated recipients");
     println!("  --recipients-file <PATH>   Use age recipients file");
     println!("  --ssh-recipient <KEYS>     Convert SSH public keys to recipients");
+    println!("  --tier <X|M|R|I|D>         Lock to all recipient groups at or above a tier");
+    println!(
+        "  --pin-group <NAME=HASH>    Fail if a recipient group's hash has drifted (comma list)"
+    );
+    println!("  --github/--gitlab <USER>   Recipients import: fetch the user's published SSH keys");
+    println!("  --fingerprint <SHA256:..>  Recipients import: only accept these keys (no prompt)");
     println!("  --identity <PATH>          Decrypt with age identity file");
     println!("  --ssh-identity <PATH>      Decrypt with SSH private key");
+    println!("  --identity-provider <URI>  Fetch identity/passphrase (exec:, env:, fd:, vault://, awskms://, gcpkms://, or config alias)");
+    println!("  --passphrase-cmd <CMD>     Read passphrase from command stdout (no shell, e.g. \"pass show repo/key\")");
+    println!("  --new-passphrase-cmd <CMD> Rotate only: read the new passphrase from a command");
+    println!();
+    println!("VERIFY OPTIONS:");
+    println!("  --deep                 Attempt full decryption of each file");
+    println!("  --stream               With --deep, hash plaintext in memory (needs --identity)");
+    println!("  --partial              List empty or truncated ciphertext (exit 1 if any)");
+    println!("  --clean                With --partial, delete what was found");
+    println!(
+        "  --against-source <DIR> Match ciphertext to a plaintext tree via the checksum database"
+    );
     println!();
     println!("EXAMPLES:");
     println!("  cage lock secret.txt --progress");
     println!("  cage unlock secret.txt.cage --progress");
     println!("  cage lock document.pdf --in-place");
     println!("  cage status /encrypted-files --verbose");
+    println!("  cage status . --recursive --json         # Nested coverage for dashboards");
+    println!("  cage fleet verify --manifest fleet.toml --deep --json");
     println!("  cage keygen                              # Generate identity to default path");
     println!("  cage keygen --export                     # Generate to current directory");
+    println!("  cage keygen --roster team.txt --group ops  # One key per member (name, email)");
+    println!("  cage keygen --wrap-to age1admin...       # Identity encrypted to an admin key");
+    println!("  cage recipients import --github alice --group team");
+    println!("  cage recipients export --group team > team.recipients");
     println!("  cage proxy --age-p --age-a --age-o=output.age input.txt");
+    println!("  cage proxy --group team -- -e < plain.txt > secret.age");
     println!();
     println!("For detailed help on a specific command, use:");
     println!("  cage <command> --help");
@@ -1910,52 +4573,266 @@ fn cmd_config(args: Args) -> i32 {
                     echo!("");
                     echo!("{}", config.format_layers());
                     echo!("");
+                    // Mark values that came from CAGE_* variables
+                    let from_env = |key: &str| {
+                        config
+                            .env_override_for(key)
+                            .map(|var| format!("  [env: {}]", var))
+                            .unwrap_or_default()
+                    };
                     echo!("Current Settings:");
-                    echo!("  Output format: {:?}", config.output_format);
-                    echo!("  TTY method: {:?}", config.tty_method);
                     echo!(
-                        "  Encrypted file extension: .{}",
-                        config.encrypted_file_extension
+                        "  Output format: {:?}{}",
+                        config.output_format,
+                        from_env("output_format")
+                    );
+                    echo!(
+                        "  TTY method: {:?}{}",
+                        config.tty_method,
+                        from_env("tty_method")
+                    );
+                    echo!(
+                        "  Encrypted file extension: .{}{}",
+                        config.encrypted_file_extension,
+                        from_env("encrypted_file_extension")
+                    );
+                    echo!(
+                        "  Backup cleanup: {}{}",
+                        config.backup_cleanup,
+                        from_env("backup_cleanup")
                     );
-                    echo!("  Backup cleanup: {}", config.backup_cleanup);
                     echo!(
-                        "  Streaming strategy: {}",
+                        "  Backup retention: {:?}{}",
+                        config.backup_retention,
+                        from_env("backup_retention")
+                    );
+                    echo!(
+                        "  Streaming strategy: {}{}",
                         config
                             .streaming_strategy
                             .as_ref()
-                            .unwrap_or(&"auto".to_string())
+                            .unwrap_or(&"auto".to_string()),
+                        from_env("streaming_strategy")
+                    );
+                    echo!(
+                        "  Telemetry format: {:?}{}",
+                        config.telemetry_format,
+                        from_env("telemetry_format")
                     );
 
-                    if let Some(backup_dir) = &config.backup_directory {
-                        echo!("  Backup directory: {}", backup_dir);
-                    }
+                    if let Some(backup_dir) = &config.backup_directory {
+                        echo!(
+                            "  Backup directory: {}{}",
+                            backup_dir,
+                            from_env("backup_directory")
+                        );
+                    }
+                    if let Some(audit_log) = &config.audit_log_path {
+                        echo!("  Audit log: {}{}", audit_log, from_env("audit_log_path"));
+                    }
+
+                    if !config.env_overrides.is_empty() {
+                        echo!("");
+                        echo!("Environment overrides:");
+                        for (key, var) in &config.env_overrides {
+                            echo!("  {} <- {}", key, var);
+                        }
+                    }
+
+                    echo!("");
+                    echo!("Use 'cage config path' to see only the active config file path");
+                    0
+                }
+                Err(e) => {
+                    echo!("❌ Failed to load configuration: {}", e);
+                    1
+                }
+            }
+        }
+        "path" => {
+            // Show just the path where config was loaded from
+            match AgeConfig::load_default() {
+                Ok(config) => {
+                    if let Some(path) = config.source_path {
+                        echo!("{}", path.display());
+                    } else {
+                        echo!("No configuration file loaded (using defaults)");
+                    }
+                    0
+                }
+                Err(e) => {
+                    echo!("❌ Failed to load configuration: {}", e);
+                    1
+                }
+            }
+        }
+        "validate" => {
+            // Check a config file without running an operation
+            let path = match args.get_or(2, "").as_str() {
+                "" => match AgeConfig::get_config_search_paths()
+                    .into_iter()
+                    .find(|p| p.exists())
+                {
+                    Some(path) => path,
+                    None => {
+                        echo!("No configuration file found (defaults are always valid)");
+                        return 0;
+                    }
+                },
+                explicit => PathBuf::from(explicit),
+            };
+
+            match AgeConfig::validate_file(&path) {
+                Ok(issues) if issues.is_empty() => {
+                    echo!("✅ {} is valid", path.display());
+                    0
+                }
+                Ok(issues) => {
+                    stderr!("❌ {} has {} problem(s):", path.display(), issues.len());
+                    for issue in &issues {
+                        stderr!("  {}:{}", path.display(), issue);
+                    }
+                    1
+                }
+                Err(e) => {
+                    stderr!("❌ Failed to read configuration: {}", e);
+                    1
+                }
+            }
+        }
+        "explain" => {
+            use cage::core::config::{ConfigKeyDoc, CONFIG_SCHEMA};
+
+            let name = args.get_or(2, "");
+            if name.is_empty() {
+                echo!("Usage: cage config explain <key>");
+                echo!("");
+                echo!("Documented keys:");
+                for doc in CONFIG_SCHEMA {
+                    echo!("  {:<28} {}", doc.key, doc.summary);
+                }
+                return 0;
+            }
+            let Some(doc) = ConfigKeyDoc::find(&name) else {
+                stderr!("❌ Unknown config key: {}", name);
+                stderr!("Run 'cage config explain' to list documented keys");
+                return 1;
+            };
+            let config = match AgeConfig::load_default() {
+                Ok(config) => config,
+                Err(e) => {
+                    stderr!("❌ Failed to load configuration: {}", e);
+                    return exit_code_for(&e);
+                }
+            };
+
+            echo!("{}", doc.key);
+            echo!("  {}", doc.summary);
+            echo!("");
+            if let Some(file_key) = doc.file_key {
+                match file_key.split_once('.') {
+                    Some((section, key)) => echo!("  Config file: [{}] {}", section, key),
+                    None => echo!("  Config file: {} (top level)", file_key),
+                }
+            }
+            if let Some(variable) = doc.env_var() {
+                echo!("  Environment: {}", variable);
+            }
+            echo!("  Accepted:    {}", doc.values);
+            echo!("  Default:     {}", doc.default);
+            echo!(
+                "  Effective:   {} (from {})",
+                config.effective_value(doc.key).unwrap_or_default(),
+                config.value_source(doc)
+            );
+            0
+        }
+        "seal" => {
+            use cage::core::{ConfigSeal, SealStatus};
+
+            if let Err(code) = require_admin("config seal") {
+                return code;
+            }
 
-                    echo!("");
-                    echo!("Use 'cage config path' to see only the active config file path");
-                    0
+            // Never load the config here: a broken seal must not block re-sealing it
+            let seal = match ConfigSeal::from_env() {
+                Ok(Some(seal)) => seal,
+                Ok(None) => {
+                    stderr!("❌ No seal key configured");
+                    stderr!(
+                        "Set CAGE_CONFIG_KEY_FILE, CAGE_CONFIG_KEY_COMMAND or CAGE_CONFIG_KEY_IDENTITY"
+                    );
+                    return 1;
                 }
                 Err(e) => {
-                    echo!("❌ Failed to load configuration: {}", e);
-                    1
+                    stderr!("❌ {}", e);
+                    return exit_code_for(&e);
+                }
+            };
+            let config_path = AgeConfig::get_config_search_paths()
+                .into_iter()
+                .find(|p| p.exists());
+            let groups_path = cage::core::GroupStore::default_path().filter(|p| p.exists());
+            let files: Vec<PathBuf> = config_path.into_iter().chain(groups_path).collect();
+            if files.is_empty() {
+                echo!("No config or recipient group files to seal");
+                return 0;
+            }
+
+            let check_only = is_true("opt_check");
+            let mut broken = 0;
+            for path in &files {
+                let status = std::fs::read(path)
+                    .map_err(|e| cage::AgeError::file_error("read", path.clone(), e))
+                    .and_then(|contents| seal.check(path, &contents));
+                let result = match status {
+                    Ok(status) if check_only => Ok(status),
+                    Ok(status) => seal.seal(path).map(|_| status),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(SealStatus::Valid) => echo!("✅ {} (seal valid)", path.display()),
+                    Ok(status) if check_only => {
+                        broken += 1;
+                        let problem = match status {
+                            SealStatus::Missing => "not sealed",
+                            _ => "changed since it was sealed",
+                        };
+                        stderr!("❌ {} ({})", path.display(), problem);
+                    }
+                    Ok(_) => echo!("🔏 Sealed {}", path.display()),
+                    Err(e) => {
+                        broken += 1;
+                        stderr!("❌ {}: {}", path.display(), e);
+                    }
                 }
             }
+            i32::from(broken > 0)
         }
-        "path" => {
-            // Show just the path where config was loaded from
-            match AgeConfig::load_default() {
-                Ok(config) => {
-                    if let Some(path) = config.source_path {
-                        echo!("{}", path.display());
-                    } else {
-                        echo!("No configuration file loaded (using defaults)");
+        "admin-hash" => {
+            // Hash for [admin] token_hash; the token itself never goes into the config
+            let token = match admin_token_from_cli() {
+                Ok(Some(token)) => token,
+                Ok(None) => {
+                    let mut line = String::new();
+                    if let Err(e) = std::io::stdin().read_line(&mut line) {
+                        stderr!("❌ Failed to read the admin token from stdin: {}", e);
+                        return 1;
                     }
-                    0
+                    line
                 }
                 Err(e) => {
-                    echo!("❌ Failed to load configuration: {}", e);
-                    1
+                    stderr!("❌ {}", e);
+                    return 1;
                 }
+            };
+            if token.trim().is_empty() {
+                stderr!("❌ Admin token is empty");
+                return 1;
             }
+            echo!("[admin]");
+            echo!("token_hash = \"{}\"", cage::core::roles::token_hash(&token));
+            0
         }
         "paths" => {
             // Show all search paths
@@ -1973,6 +4850,10 @@ fn cmd_config(args: Args) -> i32 {
             echo!("  cage config show  - Display current configuration and search paths");
             echo!("  cage config path  - Show the active configuration file path");
             echo!("  cage config paths - List all configuration search paths");
+            echo!("  cage config validate [FILE] - Check a config file for errors");
+            echo!("  cage config explain [KEY]   - Describe a config key and its effective value");
+            echo!("  cage config seal [--check]  - Seal (or check) config and group files");
+            echo!("  cage config admin-hash      - Print [admin] token_hash for a token on stdin");
             1
         }
     }
@@ -2008,14 +4889,31 @@ Options:
   --output <PATH>          Destination file to write (required)
   --format <binary|ascii>  Output format for encryption (default: binary)
   --buffer-size <BYTES>    Streaming buffer size (default: 8192)
+  --progress               Show byte progress with throughput and ETA
   --recipient, --recipients, --recipients-file, --ssh-recipient  Same as lock CLI
   --identity, --ssh-identity                                Same as unlock CLI
-  --stdin-passphrase / CAGE_PASSPHRASE / --i-am-sure        Same semantics as lock/unlock
+  --passphrase-cmd / --stdin-passphrase / CAGE_PASSPHRASE / --i-am-sure  Same semantics as lock/unlock
 "
     );
 }
 
 fn resolve_stream_buffer_size() -> usize {
+    let requested = parse_stream_buffer_size();
+    let budget = AgeConfig::load_default()
+        .map(|config| MemoryBudget::from_config(&config))
+        .unwrap_or_default();
+    let clamped = budget.clamp_buffer(requested);
+    if clamped < requested {
+        stderr!(
+            "⚠️  Buffer size {} exceeds memory budget. Using {} bytes.",
+            requested,
+            clamped
+        );
+    }
+    clamped
+}
+
+fn parse_stream_buffer_size() -> usize {
     let raw = get_var("opt_buffer_size");
     if raw.is_empty() {
         return 8192;
@@ -2037,15 +4935,295 @@ fn resolve_stream_buffer_size() -> usize {
     }
 }
 
+/// Interactive tree view for triaging a repository's encryption status
+#[cfg(all(feature = "tui", unix))]
+fn cmd_tui(args: Args) -> i32 {
+    let root = PathBuf::from(args.get_or(1, "."));
+    if !root.is_dir() {
+        stderr!("❌ TUI target must be a directory: {}", root.display());
+        return 1;
+    }
+    match cage::tui::run(&root) {
+        Ok(()) => 0,
+        Err(e) => {
+            stderr!("❌ TUI failed: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+#[cfg(not(all(feature = "tui", unix)))]
+fn cmd_tui(_args: Args) -> i32 {
+    stderr!("❌ This build does not include the TUI; rebuild with --features tui (Unix only)");
+    1
+}
+
+/// Serve decrypted views of a repository's `.cage` files until interrupted
+#[cfg(all(feature = "mount", unix))]
+fn cmd_mount(args: Args) -> i32 {
+    let repository = PathBuf::from(args.get_or(1, "."));
+    let mountpoint = args.get_or(2, "");
+    if mountpoint.is_empty() {
+        stderr!("❌ Usage: cage mount <repo> <mountpoint>");
+        return 1;
+    }
+    let mountpoint = PathBuf::from(mountpoint);
+    if !mountpoint.is_dir() {
+        stderr!(
+            "❌ Mountpoint must be a directory: {}",
+            mountpoint.display()
+        );
+        return 1;
+    }
+    let identity = match parse_unlock_identity_from_cli() {
+        Some(identity) => identity,
+        None => match passphrase_identity_from_cli("Enter passphrase for decryption", false) {
+            Ok(identity) => identity,
+            Err(code) => return code,
+        },
+    };
+    let config = match AgeConfig::load_default() {
+        Ok(config) => config,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return exit_code_for(&e);
+        }
+    };
+
+    echo!(
+        "📂 Mounting {} read-only at {} (Ctrl-C or fusermount -u to stop)",
+        repository.display(),
+        mountpoint.display()
+    );
+    match cage::mount::run(&repository, &mountpoint, &identity, config) {
+        Ok(()) => 0,
+        Err(e) => {
+            stderr!("❌ Mount failed: {}", e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+#[cfg(not(all(feature = "mount", unix)))]
+fn cmd_mount(_args: Args) -> i32 {
+    stderr!("❌ This build does not include mount; rebuild with --features mount (Unix only)");
+    1
+}
+
+/// Decrypt a small secret for viewing: to stdout, or with --clip to the clipboard
+fn cmd_show(args: Args) -> i32 {
+    let path = args.get_or(1, "");
+    if path.is_empty() {
+        stderr!("❌ Usage: cage show <file> [--clip [--clear-after <DUR>]]");
+        return 1;
+    }
+    let path = PathBuf::from(path);
+    let Some(identity) = parse_unlock_identity_from_cli() else {
+        stderr!("❌ show needs --identity, --ssh-identity or a provider (passphrases stage plaintext on disk)");
+        return 1;
+    };
+    let mut crud_manager = match cli_manager() {
+        Ok(manager) => manager,
+        Err(e) => {
+            stderr!("❌ Failed to initialize Cage: {}", e);
+            return 1;
+        }
+    };
+
+    if is_true("opt_clip") {
+        return show_on_clipboard(&mut crud_manager, &path, &identity);
+    }
+    let mut writer = BufWriter::new(std::io::stdout());
+    let result = crud_manager
+        .unlock_to_writer(&path, &identity, &mut writer)
+        .and_then(|_| {
+            writer
+                .flush()
+                .map_err(|e| AgeError::file_error("write", PathBuf::from("<stdout>"), e))
+        });
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            stderr!("❌ Show failed: {}", e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Copy the plaintext of `path` to the clipboard and clear it after `--clear-after`
+#[cfg(feature = "clipboard")]
+fn show_on_clipboard(crud_manager: &mut CageManager, path: &Path, identity: &Identity) -> i32 {
+    use cage::clip::{Clipboard, DEFAULT_CLEAR_AFTER, MAX_CLIP_BYTES};
+
+    let value = get_var("opt_clear_after");
+    let clear_after = if value.is_empty() {
+        DEFAULT_CLEAR_AFTER
+    } else {
+        match parse_duration(&value) {
+            Some(delay) => delay,
+            None => {
+                stderr!("❌ Invalid --clear-after '{}' (use e.g. 30s, 2m)", value);
+                return 1;
+            }
+        }
+    };
+    let clipboard = match Clipboard::detect() {
+        Ok(clipboard) => clipboard,
+        Err(e) => {
+            stderr!("❌ {}", e);
+            return exit_code_for(&e);
+        }
+    };
+    // Armor grows ciphertext by a third, so past twice the limit the plaintext cannot fit
+    if fs::metadata(path).map_or(0, |m| m.len()) > 2 * MAX_CLIP_BYTES as u64 {
+        stderr!(
+            "❌ {} is too large for the clipboard (limit {} KiB)",
+            path.display(),
+            MAX_CLIP_BYTES / 1024
+        );
+        return 1;
+    }
+
+    let mut plaintext = Vec::new();
+    let result = crud_manager
+        .unlock_to_writer(path, identity, &mut plaintext)
+        .and_then(|_| {
+            if plaintext.len() > MAX_CLIP_BYTES {
+                return Err(AgeError::InvalidOperation {
+                    operation: "show".to_string(),
+                    reason: format!("Plaintext exceeds the {} KiB limit", MAX_CLIP_BYTES / 1024),
+                });
+            }
+            clipboard.copy(&plaintext)?;
+            stderr!(
+                "📋 Copied {} to the clipboard; clearing in {}s (Ctrl-C clears now)",
+                path.display(),
+                clear_after.as_secs()
+            );
+            clipboard.clear_after(&plaintext, clear_after)
+        });
+    plaintext.fill(0);
+
+    match result {
+        Ok(true) => {
+            stderr!("🧹 Clipboard cleared");
+            0
+        }
+        Ok(false) => {
+            stderr!("📋 Clipboard changed since; left as is");
+            0
+        }
+        Err(e) => {
+            stderr!("❌ Show failed: {}", e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn show_on_clipboard(_crud_manager: &mut CageManager, _path: &Path, _identity: &Identity) -> i32 {
+    stderr!("❌ This build does not include --clip; rebuild with --features clipboard");
+    1
+}
+
+/// Work-queue bulk service mode: execute NDJSON job records and emit result records
+fn cmd_worker(_args: Args) -> i32 {
+    let queue = get_var("opt_queue");
+    if queue.is_empty() {
+        stderr!("❌ Worker mode requires --queue <PATH> (use - for stdin)");
+        print_worker_usage();
+        return 1;
+    }
+
+    let parallel = get_var("opt_parallel");
+    let parallelism = if parallel.is_empty() {
+        1
+    } else {
+        match parallel.parse::<usize>() {
+            Ok(value) if value > 0 => value,
+            _ => {
+                stderr!("❌ Invalid --parallel value '{}'", parallel);
+                return 1;
+            }
+        }
+    };
+
+    let memory_budget = AgeConfig::load_default()
+        .map(|config| MemoryBudget::from_config(&config))
+        .unwrap_or_default();
+    let worker = QueueWorker::new()
+        .with_parallelism(parallelism)
+        .with_memory_budget(memory_budget);
+    let output_path = get_var("opt_output");
+    let mut writer: Box<dyn Write> = if output_path.is_empty() || output_path == "-" {
+        Box::new(BufWriter::new(std::io::stdout()))
+    } else {
+        match File::create(&output_path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                stderr!("❌ Failed to create output file '{}': {}", output_path, e);
+                return 1;
+            }
+        }
+    };
+
+    let summary = if queue == "-" {
+        worker.run(std::io::stdin().lock(), &mut writer)
+    } else {
+        match File::open(&queue) {
+            Ok(file) => worker.run(BufReader::new(file), &mut writer),
+            Err(e) => {
+                stderr!("❌ Failed to open queue '{}': {}", queue, e);
+                return 1;
+            }
+        }
+    };
+
+    match summary {
+        Ok(summary) => {
+            stderr!(
+                "📋 Worker finished: {} jobs, {} succeeded, {} failed",
+                summary.total,
+                summary.succeeded,
+                summary.failed
+            );
+            if summary.failed == 0 {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            stderr!("❌ Worker failed: {}", e);
+            1
+        }
+    }
+}
+
+fn print_worker_usage() {
+    println!(
+        "Usage:
+  cage worker --queue <PATH|-> [--output <PATH>] [--parallel <N>]
+
+Reads newline-delimited JSON jobs such as
+  {{\"id\":\"j1\",\"operation\":\"lock\",\"request\":{{...LockRequest...}}}}
+and writes one JSON result record per job to --output (default: stdout).
+Operations: lock, unlock, rotate, verify, batch. Secrets must be passed by
+reference using provider identities (e.g. {{\"provider\":\"env:CAGE_KEY\"}}).
+"
+    );
+}
+
 fn open_stream_io(
     input_path: &str,
     output_path: &str,
     buffer_size: usize,
+    output_mode: Option<u32>,
 ) -> Result<(BufReader<File>, BufWriter<File>), String> {
     let input_file = File::open(input_path)
         .map_err(|e| format!("Failed to open input file '{}': {}", input_path, e))?;
 
-    let output_file = File::create(output_path)
+    let output_file = permissions::create_output(Path::new(output_path), output_mode)
         .map_err(|e| format!("Failed to create output file '{}': {}", output_path, e))?;
 
     Ok((
@@ -2054,6 +5232,68 @@ fn open_stream_io(
     ))
 }
 
+/// Run a stream request, showing byte-level progress (throughput/ETA) with `--progress`.
+///
+/// The operation runs on a worker thread while this thread samples the byte counter
+/// reported by the streaming adapter and feeds it to a `ProgressStyle::Bytes` task.
+fn run_stream_request(
+    request: &StreamRequest,
+    reader: &mut BufReader<File>,
+    writer: &mut BufWriter<File>,
+    input_path: &str,
+    label: &str,
+) -> AgeResult<u64> {
+    if !progress_requested() {
+        let mut crud_manager = cli_manager()?;
+        return crud_manager.stream_with_request(request, reader, writer);
+    }
+
+    let total_bytes = fs::metadata(input_path).map(|m| m.len()).unwrap_or(0);
+    let progress_manager = ProgressManager::new();
+    progress_manager.add_reporter(Arc::new(TerminalReporter::with_config(TerminalConfig {
+        use_colors: true,
+        use_unicode: true,
+        use_stderr: true,
+        ..Default::default()
+    })));
+    let task = progress_manager.start_task(
+        &format!("{} {}", label, input_path),
+        ProgressStyle::Bytes { total_bytes },
+    );
+
+    let processed = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&processed);
+    let callback: ByteProgressFn = Arc::new(move |bytes| counter.store(bytes, Ordering::Relaxed));
+
+    let result = std::thread::scope(|scope| {
+        let handle = scope.spawn(move || -> AgeResult<u64> {
+            let mut crud_manager = cli_manager()?;
+            crud_manager.stream_with_progress(request, reader, writer, Some(callback))
+        });
+
+        while !handle.is_finished() {
+            task.update(processed.load(Ordering::Relaxed), label);
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        handle.join().unwrap_or_else(|_| {
+            Err(AgeError::InvalidOperation {
+                operation: "stream".to_string(),
+                reason: "Streaming worker thread panicked".to_string(),
+            })
+        })
+    });
+
+    match &result {
+        Ok(bytes) => {
+            task.update(*bytes, label);
+            task.complete(&format!("✓ {} complete ({} bytes)", label, bytes));
+        }
+        Err(e) => task.fail(&format!("✗ {} failed: {}", label, e)),
+    }
+    result
+}
+
 fn stream_encrypt(_args: Args) -> i32 {
     let input_path = get_var("opt_input");
     let output_path = get_var("opt_output");
@@ -2076,17 +5316,25 @@ fn stream_encrypt(_args: Args) -> i32 {
         None
     } else {
         if let Some(_insecure_pass) = PassphraseManager::detect_insecure_usage(&cmd_args) {
-            stderr!("⚠️  WARNING: Passphrase detected on command line!");
-            stderr!("   This is insecure and visible in process list.");
-            if !is_true("opt_i_am_sure") {
+            if let Err(code) = confirm_insecure_passphrase() {
                 stderr!("   Use interactive prompt instead, or add --i-am-sure to override");
-                return 1;
+                return code;
             }
         }
 
         let passphrase_manager = PassphraseManager::new();
 
-        let passphrase = if is_true("opt_stdin_passphrase") {
+        let passphrase = if let Some(result) =
+            read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd")
+        {
+            match result {
+                Ok(pass) => pass,
+                Err(e) => {
+                    stderr!("❌ Failed to read passphrase from command: {}", e);
+                    return 1;
+                }
+            }
+        } else if is_true("opt_stdin_passphrase") {
             match passphrase_manager.get_passphrase_with_mode(
                 "Enter passphrase",
                 false,
@@ -2109,7 +5357,7 @@ fn stream_encrypt(_args: Args) -> i32 {
                 Ok(pass) => pass,
                 Err(e) => {
                     stderr!("❌ Failed to get passphrase: {}", e);
-                    return 1;
+                    return e.exit_code();
                 }
             }
         };
@@ -2136,23 +5384,25 @@ fn stream_encrypt(_args: Args) -> i32 {
     request.buffer_size = buffer_size;
     request.common.verbose = verbose;
 
-    let (mut reader, mut writer) = match open_stream_io(&input_path, &output_path, buffer_size) {
-        Ok(handles) => handles,
-        Err(err) => {
-            stderr!("❌ {}", err);
-            return 1;
-        }
-    };
-
-    let mut crud_manager = match CageManager::with_defaults() {
-        Ok(manager) => manager,
-        Err(e) => {
-            stderr!("❌ Failed to create CageManager: {}", e);
-            return 1;
-        }
-    };
+    let output_mode = AgeConfig::load_default()
+        .ok()
+        .and_then(|config| config.ciphertext_mode);
+    let (mut reader, mut writer) =
+        match open_stream_io(&input_path, &output_path, buffer_size, output_mode) {
+            Ok(handles) => handles,
+            Err(err) => {
+                stderr!("❌ {}", err);
+                return 1;
+            }
+        };
 
-    match crud_manager.stream_with_request(&request, &mut reader, &mut writer) {
+    match run_stream_request(
+        &request,
+        &mut reader,
+        &mut writer,
+        &input_path,
+        "Encrypting",
+    ) {
         Ok(bytes) => {
             if let Err(e) = writer.flush() {
                 stderr!("❌ Failed to flush output: {}", e);
@@ -2190,31 +5440,40 @@ fn stream_decrypt(_args: Args) -> i32 {
     } else {
         let passphrase_manager = PassphraseManager::new();
 
-        let passphrase = if is_true("opt_stdin_passphrase") {
-            match passphrase_manager.get_passphrase_with_mode(
-                "Enter passphrase",
-                false,
-                PassphraseMode::Stdin,
-            ) {
-                Ok(pass) => pass,
-                Err(e) => {
-                    stderr!("❌ Failed to read passphrase from stdin: {}", e);
-                    return 1;
+        let passphrase =
+            if let Some(result) = read_passphrase_cmd(&passphrase_manager, "opt_passphrase_cmd") {
+                match result {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to read passphrase from command: {}", e);
+                        return 1;
+                    }
                 }
-            }
-        } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
-            env_pass
-        } else {
-            match passphrase_manager
-                .get_passphrase("Enter passphrase for streaming decryption", false)
-            {
-                Ok(pass) => pass,
-                Err(e) => {
-                    stderr!("❌ Failed to get passphrase: {}", e);
-                    return 1;
+            } else if is_true("opt_stdin_passphrase") {
+                match passphrase_manager.get_passphrase_with_mode(
+                    "Enter passphrase",
+                    false,
+                    PassphraseMode::Stdin,
+                ) {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to read passphrase from stdin: {}", e);
+                        return 1;
+                    }
                 }
-            }
-        };
+            } else if let Ok(env_pass) = std::env::var("CAGE_PASSPHRASE") {
+                env_pass
+            } else {
+                match passphrase_manager
+                    .get_passphrase("Enter passphrase for streaming decryption", false)
+                {
+                    Ok(pass) => pass,
+                    Err(e) => {
+                        stderr!("❌ Failed to get passphrase: {}", e);
+                        return e.exit_code();
+                    }
+                }
+            };
 
         Identity::Passphrase(passphrase)
     };
@@ -2223,23 +5482,25 @@ fn stream_decrypt(_args: Args) -> i32 {
     request.buffer_size = buffer_size;
     request.common.verbose = verbose;
 
-    let (mut reader, mut writer) = match open_stream_io(&input_path, &output_path, buffer_size) {
-        Ok(handles) => handles,
-        Err(err) => {
-            stderr!("❌ {}", err);
-            return 1;
-        }
-    };
-
-    let mut crud_manager = match CageManager::with_defaults() {
-        Ok(manager) => manager,
-        Err(e) => {
-            stderr!("❌ Failed to create CageManager: {}", e);
-            return 1;
-        }
-    };
+    let output_mode = AgeConfig::load_default()
+        .ok()
+        .and_then(|config| config.plaintext_mode);
+    let (mut reader, mut writer) =
+        match open_stream_io(&input_path, &output_path, buffer_size, output_mode) {
+            Ok(handles) => handles,
+            Err(err) => {
+                stderr!("❌ {}", err);
+                return 1;
+            }
+        };
 
-    match crud_manager.stream_with_request(&request, &mut reader, &mut writer) {
+    match run_stream_request(
+        &request,
+        &mut reader,
+        &mut writer,
+        &input_path,
+        "Decrypting",
+    ) {
         Ok(bytes) => {
             if let Err(e) = writer.flush() {
                 stderr!("❌ Failed to flush output: {}", e);
@@ -2258,6 +5519,29 @@ fn stream_decrypt(_args: Args) -> i32 {
     }
 }
 
+/// Report which adapter the configured fallback chain selects and why others were skipped
+fn print_adapter_selection() {
+    let chain = AgeConfig::load_default()
+        .map(|config| config.adapter_chain)
+        .unwrap_or_default();
+    echo!("Selection:");
+    if chain.is_empty() {
+        echo!("  Chain: (default)");
+    } else {
+        echo!("  Chain: {}", chain.join(" → "));
+    }
+    match cage::AdapterFactory::create_from_chain(&chain) {
+        Ok(selection) => {
+            echo!("  ✓ Selected: {}", selection.chosen);
+            for (name, reason) in &selection.skipped {
+                echo!("  ✗ Skipped {}: {}", name, reason);
+            }
+        }
+        Err(e) => echo!("  ✗ No usable adapter: {}", e),
+    }
+    echo!("");
+}
+
 /// Adapter command - inspect adapter capabilities and health
 fn cmd_adapter(args: Args) -> i32 {
     use cage::adp::v2::{AgeAdapterV2, ShellAdapterV2};
@@ -2277,6 +5561,7 @@ fn cmd_adapter(args: Args) -> i32 {
                     echo!("Adapter: {}", adapter.adapter_name());
                     echo!("Version: {}", adapter.adapter_version());
                     echo!("");
+                    print_adapter_selection();
 
                     // Health check
                     echo!("Health Status:");
@@ -2633,4 +5918,16 @@ mod tests {
         // Test basic RSB integration
         // This will use the global context system
     }
+
+    #[test]
+    fn test_parse_path_list() {
+        assert_eq!(
+            parse_path_list(b"a.txt\r\n\ndir/b c.txt\n", false),
+            vec![PathBuf::from("a.txt"), PathBuf::from("dir/b c.txt")]
+        );
+        assert_eq!(
+            parse_path_list(b"line\nbreak.txt\0other.txt\0", true),
+            vec![PathBuf::from("line\nbreak.txt"), PathBuf::from("other.txt")]
+        );
+    }
 }
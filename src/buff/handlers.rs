@@ -0,0 +1,384 @@
+//! Ready-made chunk handlers for [`FileChunker`](super::FileChunker).
+//!
+//! Each handler implements [`ChunkHandler`] and can be passed to
+//! [`FileChunker::process_with_handler`](super::FileChunker::process_with_handler) or
+//! called from a `process` closure when several handlers run over the same chunks:
+//!
+//! - [`DigestHandler`] – per-chunk and whole-file SHA-256 (or BLAKE3 with the `blake3`
+//!   feature) digests
+//! - [`ManifestBuilder`] – JSON manifest of chunk offsets, sizes, digests and outputs
+//! - [`EncryptingHandler`] – encrypts each chunk to its own age file through an adapter
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::ChunkSpec;
+use crate::adp::AgeAdapterV2;
+use crate::core::{Identity, OutputFormat, Recipient};
+use crate::error::{AgeError, AgeResult};
+
+/// Work performed on each chunk.
+pub trait ChunkHandler {
+    fn handle(&mut self, chunk: &ChunkSpec, data: &[u8]) -> AgeResult<()>;
+}
+
+/// Digest algorithm used by [`DigestHandler`] and [`ManifestBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestAlgorithm {
+    Sha256,
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    #[cfg(feature = "blake3")]
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            #[cfg(feature = "blake3")]
+            DigestAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            #[cfg(feature = "blake3")]
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    fn finish_hex(self) -> String {
+        match self {
+            Self::Sha256(hasher) => hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            #[cfg(feature = "blake3")]
+            Self::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+fn digest_hex(algorithm: DigestAlgorithm, data: &[u8]) -> String {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(data);
+    hasher.finish_hex()
+}
+
+/// Hex digest of one chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkDigest {
+    pub id: usize,
+    pub digest: String,
+}
+
+/// Computes a digest per chunk and over the chunks in order.
+pub struct DigestHandler {
+    algorithm: DigestAlgorithm,
+    whole: Hasher,
+    next_id: usize,
+    contiguous: bool,
+    chunks: Vec<ChunkDigest>,
+}
+
+impl DigestHandler {
+    pub fn new(algorithm: DigestAlgorithm) -> Self {
+        Self {
+            algorithm,
+            whole: Hasher::new(algorithm),
+            next_id: 0,
+            contiguous: true,
+            chunks: Vec::new(),
+        }
+    }
+
+    pub fn chunk_digests(&self) -> &[ChunkDigest] {
+        &self.chunks
+    }
+
+    /// Whole-file digest, or `None` if chunks were skipped (e.g. resumed from a
+    /// checkpoint) or arrived out of order.
+    pub fn finish(self) -> (Option<String>, Vec<ChunkDigest>) {
+        let whole = self.contiguous.then(|| self.whole.finish_hex());
+        (whole, self.chunks)
+    }
+}
+
+impl ChunkHandler for DigestHandler {
+    fn handle(&mut self, chunk: &ChunkSpec, data: &[u8]) -> AgeResult<()> {
+        self.contiguous &= chunk.id == self.next_id;
+        self.next_id = chunk.id + 1;
+        self.whole.update(data);
+        self.chunks.push(ChunkDigest {
+            id: chunk.id,
+            digest: digest_hex(self.algorithm, data),
+        });
+        Ok(())
+    }
+}
+
+/// One chunk in a [`ChunkManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub id: usize,
+    pub start: u64,
+    pub size: u64,
+    pub digest: String,
+    /// File the chunk was written to, when a handler produced one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+}
+
+/// Description of a chunked file, suitable for reassembly and integrity checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    pub source: PathBuf,
+    pub algorithm: DigestAlgorithm,
+    pub total_bytes: u64,
+    pub chunks: Vec<ManifestEntry>,
+}
+
+impl ChunkManifest {
+    /// Write the manifest as pretty JSON.
+    pub fn write_to(&self, path: &Path) -> AgeResult<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| AgeError::InvalidOperation {
+            operation: "chunk_manifest".into(),
+            reason: format!("Serialization error: {e}"),
+        })?;
+        fs::write(path, json)
+            .map_err(|e| AgeError::file_error("chunk_manifest_write", path.to_path_buf(), e))
+    }
+
+    pub fn load(path: &Path) -> AgeResult<Self> {
+        let file = File::open(path)
+            .map_err(|e| AgeError::file_error("chunk_manifest_open", path.to_path_buf(), e))?;
+        serde_json::from_reader(file).map_err(|e| AgeError::ConfigurationError {
+            parameter: "chunk_manifest".into(),
+            value: path.display().to_string(),
+            reason: format!("Invalid JSON: {e}"),
+        })
+    }
+}
+
+/// Records offsets and digests of every chunk into a [`ChunkManifest`].
+pub struct ManifestBuilder {
+    source: PathBuf,
+    algorithm: DigestAlgorithm,
+    entries: Vec<ManifestEntry>,
+}
+
+impl ManifestBuilder {
+    pub fn new(source: impl Into<PathBuf>, algorithm: DigestAlgorithm) -> Self {
+        Self {
+            source: source.into(),
+            algorithm,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Attach the file produced for chunk `id` (see [`EncryptingHandler::outputs`]).
+    pub fn record_output(&mut self, id: usize, output: PathBuf) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.output = Some(output);
+        }
+    }
+
+    pub fn finish(mut self) -> ChunkManifest {
+        self.entries.sort_by_key(|entry| entry.id);
+        ChunkManifest {
+            total_bytes: self.entries.iter().map(|entry| entry.size).sum(),
+            source: self.source,
+            algorithm: self.algorithm,
+            chunks: self.entries,
+        }
+    }
+}
+
+impl ChunkHandler for ManifestBuilder {
+    fn handle(&mut self, chunk: &ChunkSpec, data: &[u8]) -> AgeResult<()> {
+        self.entries.push(ManifestEntry {
+            id: chunk.id,
+            start: chunk.start,
+            size: data.len() as u64,
+            digest: digest_hex(self.algorithm, data),
+            output: None,
+        });
+        Ok(())
+    }
+}
+
+/// Encrypts each chunk to `<output_dir>/<name>.<id>.age` through an adapter.
+pub struct EncryptingHandler<'a> {
+    adapter: &'a dyn AgeAdapterV2,
+    identity: Identity,
+    recipients: Option<Vec<Recipient>>,
+    format: OutputFormat,
+    output_dir: PathBuf,
+    name: String,
+    outputs: Vec<(usize, PathBuf)>,
+}
+
+impl<'a> EncryptingHandler<'a> {
+    /// `name` prefixes each chunk file, usually the source file name.
+    pub fn new(
+        adapter: &'a dyn AgeAdapterV2,
+        identity: Identity,
+        output_dir: impl Into<PathBuf>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            adapter,
+            identity,
+            recipients: None,
+            format: OutputFormat::Binary,
+            output_dir: output_dir.into(),
+            name: name.into(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_recipients(mut self, recipients: Vec<Recipient>) -> Self {
+        self.recipients = Some(recipients);
+        self
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Path of the encrypted file for chunk `id`.
+    pub fn output_path(&self, id: usize) -> PathBuf {
+        self.output_dir.join(format!("{}.{:06}.age", self.name, id))
+    }
+
+    /// `(chunk id, encrypted file)` for every chunk handled so far.
+    pub fn outputs(&self) -> &[(usize, PathBuf)] {
+        &self.outputs
+    }
+}
+
+impl ChunkHandler for EncryptingHandler<'_> {
+    fn handle(&mut self, chunk: &ChunkSpec, data: &[u8]) -> AgeResult<()> {
+        fs::create_dir_all(&self.output_dir)
+            .map_err(|e| AgeError::file_error("chunk_output_dir", self.output_dir.clone(), e))?;
+        let path = self.output_path(chunk.id);
+        let mut output = File::create(&path)
+            .map_err(|e| AgeError::file_error("chunk_encrypt", path.clone(), e))?;
+        let mut input = data;
+        self.adapter.encrypt_stream(
+            &mut input,
+            &mut output,
+            &self.identity,
+            self.recipients.as_deref(),
+            self.format,
+        )?;
+        output
+            .sync_all()
+            .map_err(|e| AgeError::file_error("chunk_encrypt", path.clone(), e))?;
+        self.outputs.push((chunk.id, path));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buff::{ChunkerConfig, FileChunker};
+    use std::io::Write;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn chunker_for(data: &[u8]) -> (NamedTempFile, FileChunker) {
+        let file = NamedTempFile::new().unwrap();
+        file.reopen().unwrap().write_all(data).unwrap();
+        let config = ChunkerConfig {
+            chunk_size: 4096,
+            enable_progress: false,
+            ..ChunkerConfig::default()
+        };
+        let chunker = FileChunker::new(file.path(), config).unwrap();
+        (file, chunker)
+    }
+
+    #[test]
+    fn test_digest_handler_matches_whole_file() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let (_file, chunker) = chunker_for(&data);
+
+        let mut handler = DigestHandler::new(DigestAlgorithm::Sha256);
+        chunker.process_with_handler(&mut handler).unwrap();
+        let (whole, chunks) = handler.finish();
+
+        assert_eq!(whole, Some(digest_hex(DigestAlgorithm::Sha256, &data)));
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(
+            chunks[2].digest,
+            digest_hex(DigestAlgorithm::Sha256, &data[8192..])
+        );
+
+        // A gap means the whole-file digest cannot be trusted
+        let mut partial = DigestHandler::new(DigestAlgorithm::Sha256);
+        partial
+            .handle(&chunker.chunks()[1], &data[4096..8192])
+            .unwrap();
+        assert_eq!(partial.finish().0, None);
+    }
+
+    #[test]
+    fn test_manifest_builder_round_trip() {
+        let data = vec![b'q'; 9000];
+        let (file, chunker) = chunker_for(&data);
+        let temp = TempDir::new().unwrap();
+
+        let mut builder = ManifestBuilder::new(file.path(), DigestAlgorithm::Sha256);
+        chunker.process_with_handler(&mut builder).unwrap();
+        builder.record_output(1, temp.path().join("part.1"));
+        let manifest = builder.finish();
+
+        assert_eq!(manifest.total_bytes, 9000);
+        assert_eq!(manifest.chunks[1].start, 4096);
+        assert_eq!(manifest.chunks[1].output, Some(temp.path().join("part.1")));
+
+        let path = temp.path().join("manifest.json");
+        manifest.write_to(&path).unwrap();
+        assert_eq!(ChunkManifest::load(&path).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_encrypting_handler_writes_one_file_per_chunk() {
+        let Ok(adapter) = crate::adp::ShellAdapterV2::new() else {
+            println!("Skipping chunk encryption test - Age not available");
+            return;
+        };
+        let (_file, chunker) = chunker_for(&vec![b'e'; 9000]);
+        let temp = TempDir::new().unwrap();
+
+        let mut handler = EncryptingHandler::new(
+            &adapter,
+            Identity::Passphrase("chunk-test-passphrase".into()),
+            temp.path(),
+            "data.bin",
+        );
+        if let Err(e) = chunker.process_with_handler(&mut handler) {
+            println!("Skipping chunk encryption test - {}", e);
+            return;
+        }
+
+        assert_eq!(handler.outputs().len(), 3);
+        assert!(handler.output_path(2).ends_with("data.bin.000002.age"));
+        assert!(handler.outputs().iter().all(|(_, path)| path.exists()));
+    }
+}
@@ -9,14 +9,22 @@ use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::MemoryBudget;
 use crate::error::{AgeError, AgeResult};
 
 use rsb::progress::{ProgressManager, ProgressStyle, TerminalConfig, TerminalReporter};
 
+pub mod handlers;
+
+pub use handlers::{
+    ChunkDigest, ChunkHandler, ChunkManifest, DigestAlgorithm, DigestHandler, EncryptingHandler,
+    ManifestBuilder, ManifestEntry,
+};
+
 /// Default chunk size if the caller does not supply one (64 MiB)
 const DEFAULT_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
 
@@ -38,6 +46,8 @@ pub struct ChunkerConfig {
     pub checkpoint_dir: Option<PathBuf>,
     /// Enable progress reporting (requires RSB `progress` feature).
     pub enable_progress: bool,
+    /// Memory budget; `chunk_size` is clamped so one in-memory chunk fits it.
+    pub memory_budget: MemoryBudget,
 }
 
 impl Default for ChunkerConfig {
@@ -46,6 +56,7 @@ impl Default for ChunkerConfig {
             chunk_size: DEFAULT_CHUNK_SIZE,
             checkpoint_dir: None,
             enable_progress: true,
+            memory_budget: MemoryBudget::unlimited(),
         }
     }
 }
@@ -69,6 +80,86 @@ pub struct ChunkProcessingSummary {
     pub chunks_total: usize,
     pub chunks_completed: usize,
     pub checkpoint_cleared: bool,
+    /// `(chunk id, duration)` for chunks handled in this run (resumed chunks excluded).
+    pub chunk_durations: Vec<(usize, Duration)>,
+    /// Wall time of this run.
+    pub elapsed: Duration,
+    /// Bytes handled in this run per second of `elapsed`.
+    pub bytes_per_sec: f64,
+}
+
+/// Timing for one processed chunk, passed to metrics callbacks.
+#[derive(Debug, Clone)]
+pub struct ChunkMetrics {
+    pub chunk_id: usize,
+    pub bytes: u64,
+    /// Time spent reading and handling this chunk.
+    pub duration: Duration,
+    /// Time since this run started.
+    pub elapsed: Duration,
+    pub bytes_per_sec: f64,
+    /// Estimated time for the remaining chunks at the current rate.
+    pub eta: Option<Duration>,
+    pub chunks_completed: usize,
+    pub chunks_total: usize,
+}
+
+/// Callback receiving [`ChunkMetrics`] after each chunk.
+pub type ChunkMetricsFn = Arc<dyn Fn(&ChunkMetrics) + Send + Sync>;
+
+/// Timing state for one processing run.
+struct RunTimer {
+    started: Instant,
+    run_bytes: u64,
+    remaining_bytes: u64,
+    durations: Vec<(usize, Duration)>,
+}
+
+impl RunTimer {
+    fn new(remaining_bytes: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            run_bytes: 0,
+            remaining_bytes,
+            durations: Vec::new(),
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        let secs = self.started.elapsed().as_secs_f64();
+        if secs > 0.0 {
+            self.run_bytes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    fn record(
+        &mut self,
+        chunk: &ChunkSpec,
+        chunk_started: Instant,
+        chunks_completed: usize,
+        chunks_total: usize,
+    ) -> ChunkMetrics {
+        let duration = chunk_started.elapsed();
+        self.durations.push((chunk.id, duration));
+        self.run_bytes += chunk.size;
+        self.remaining_bytes = self.remaining_bytes.saturating_sub(chunk.size);
+
+        let bytes_per_sec = self.rate();
+        let eta = (bytes_per_sec > 0.0)
+            .then(|| Duration::from_secs_f64(self.remaining_bytes as f64 / bytes_per_sec));
+        ChunkMetrics {
+            chunk_id: chunk.id,
+            bytes: chunk.size,
+            duration,
+            elapsed: self.started.elapsed(),
+            bytes_per_sec,
+            eta,
+            chunks_completed,
+            chunks_total,
+        }
+    }
 }
 
 /// Entry point for chunk planning and processing.
@@ -83,7 +174,7 @@ pub struct FileChunker {
 
 impl FileChunker {
     /// Construct a chunker for the given file. Verifies the file exists and records metadata.
-    pub fn new<P: AsRef<Path>>(source: P, config: ChunkerConfig) -> AgeResult<Self> {
+    pub fn new<P: AsRef<Path>>(source: P, mut config: ChunkerConfig) -> AgeResult<Self> {
         let source = source.as_ref().to_path_buf();
         if !source.exists() {
             return Err(AgeError::file_error(
@@ -97,6 +188,7 @@ impl FileChunker {
             .map_err(|e| AgeError::file_error("chunker_metadata", source.clone(), e))?;
 
         let total_size = metadata.len();
+        config.chunk_size = config.memory_budget.clamp_chunk(config.chunk_size);
         let chunk_size = config.chunk_size.max(1);
         let chunks = Self::plan_chunks(total_size, chunk_size);
         let checkpoint_path = checkpoint_path(&source, config.checkpoint_dir.as_ref());
@@ -120,12 +212,34 @@ impl FileChunker {
     /// The handler is responsible for performing domain-specific work (e.g., encryption,
     /// hashing, manifest generation). If the handler returns an error, processing stops
     /// and the checkpoint remains on disk for later resumption.
-    pub fn process<F>(&self, mut handler: F) -> AgeResult<ChunkProcessingSummary>
+    pub fn process<F>(&self, handler: F) -> AgeResult<ChunkProcessingSummary>
+    where
+        F: FnMut(&ChunkSpec, &[u8]) -> AgeResult<()>,
+    {
+        self.process_with_metrics(handler, None)
+    }
+
+    /// Process the file with a [`ChunkHandler`] such as those in [`handlers`].
+    pub fn process_with_handler<H>(&self, handler: &mut H) -> AgeResult<ChunkProcessingSummary>
+    where
+        H: ChunkHandler + ?Sized,
+    {
+        self.process(|chunk, data| handler.handle(chunk, data))
+    }
+
+    /// Like [`process`](Self::process), also calling `metrics` after each handled chunk
+    /// with its duration, throughput and an ETA for the rest of the file.
+    pub fn process_with_metrics<F>(
+        &self,
+        mut handler: F,
+        metrics: Option<ChunkMetricsFn>,
+    ) -> AgeResult<ChunkProcessingSummary>
     where
         F: FnMut(&ChunkSpec, &[u8]) -> AgeResult<()>,
     {
         let mut checkpoint = self.load_checkpoint()?;
         let completed: HashSet<usize> = checkpoint.completed_chunks.iter().copied().collect();
+        let mut timer = RunTimer::new(self.remaining_bytes(&completed));
 
         let mut file = File::open(&self.source)
             .map_err(|e| AgeError::file_error("chunker_open", self.source.clone(), e))?;
@@ -178,6 +292,7 @@ impl FileChunker {
                 });
             }
 
+            let chunk_started = Instant::now();
             file.seek(SeekFrom::Start(chunk.start))
                 .map_err(|e| AgeError::file_error("chunker_seek", self.source.clone(), e))?;
 
@@ -195,11 +310,22 @@ impl FileChunker {
             checkpoint.bytes_processed = processed_bytes;
             self.save_checkpoint(&checkpoint)?;
 
+            let chunk_metrics =
+                timer.record(chunk, chunk_started, processed_chunks, self.chunks.len());
+            if let Some(callback) = &metrics {
+                callback(&chunk_metrics);
+            }
             if let Some(task) = &progress_task {
                 let pct = (processed_chunks as f64 / self.chunks.len() as f64) * 100.0;
                 task.update(
                     chunk.id as u64 + 1,
-                    &format!("Chunk {} complete ({:.1}%)", chunk.id, pct),
+                    &format!(
+                        "Chunk {} complete ({:.1}%, {:.1} MiB/s, ETA {})",
+                        chunk.id,
+                        pct,
+                        chunk_metrics.bytes_per_sec / (1024.0 * 1024.0),
+                        format_eta(chunk_metrics.eta)
+                    ),
                 );
             }
         }
@@ -208,16 +334,105 @@ impl FileChunker {
         let _ = fs::remove_file(&self.checkpoint_path);
 
         if let Some(task) = &mut progress_task {
-            task.complete("Chunk processing complete");
+            task.complete(&format!(
+                "Chunk processing complete in {:.1}s",
+                timer.started.elapsed().as_secs_f64()
+            ));
         }
 
-        Ok(ChunkProcessingSummary {
+        Ok(self.summary(processed_bytes, processed_chunks, timer))
+    }
+
+    /// Async variant of [`process`](Self::process) (feature `async`).
+    ///
+    /// Chunks are read with tokio file I/O and handed to `handler` by value so the
+    /// returned future may hold them across await points. Checkpoints behave as in
+    /// `process`; terminal progress is not rendered.
+    #[cfg(feature = "async")]
+    pub async fn process_async<F, Fut>(&self, mut handler: F) -> AgeResult<ChunkProcessingSummary>
+    where
+        F: FnMut(ChunkSpec, Vec<u8>) -> Fut,
+        Fut: std::future::Future<Output = AgeResult<()>>,
+    {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut checkpoint = self.load_checkpoint()?;
+        let completed: HashSet<usize> = checkpoint.completed_chunks.iter().copied().collect();
+        let mut timer = RunTimer::new(self.remaining_bytes(&completed));
+
+        let mut file = tokio::fs::File::open(&self.source)
+            .await
+            .map_err(|e| AgeError::file_error("chunker_open", self.source.clone(), e))?;
+
+        let mut processed_chunks = 0usize;
+        let mut processed_bytes = checkpoint.bytes_processed;
+
+        for chunk in &self.chunks {
+            if completed.contains(&chunk.id) {
+                processed_chunks += 1;
+                continue;
+            }
+
+            if chunk.size > usize::MAX as u64 {
+                return Err(AgeError::ConfigurationError {
+                    parameter: "chunk_size".into(),
+                    value: chunk.size.to_string(),
+                    reason: "Chunk size exceeds usize limits".into(),
+                });
+            }
+
+            let chunk_started = Instant::now();
+            file.seek(SeekFrom::Start(chunk.start))
+                .await
+                .map_err(|e| AgeError::file_error("chunker_seek", self.source.clone(), e))?;
+
+            let mut buffer = Vec::with_capacity(chunk.size as usize);
+            (&mut file)
+                .take(chunk.size)
+                .read_to_end(&mut buffer)
+                .await
+                .map_err(|e| AgeError::file_error("chunker_read", self.source.clone(), e))?;
+
+            handler(chunk.clone(), buffer).await?;
+            processed_chunks += 1;
+            processed_bytes = chunk.end + 1;
+
+            checkpoint.completed_chunks.push(chunk.id);
+            checkpoint.bytes_processed = processed_bytes;
+            self.save_checkpoint(&checkpoint)?;
+            timer.record(chunk, chunk_started, processed_chunks, self.chunks.len());
+        }
+
+        let _ = tokio::fs::remove_file(&self.checkpoint_path).await;
+
+        Ok(self.summary(processed_bytes, processed_chunks, timer))
+    }
+
+    /// Bytes in chunks not yet recorded in the checkpoint.
+    fn remaining_bytes(&self, completed: &HashSet<usize>) -> u64 {
+        self.chunks
+            .iter()
+            .filter(|chunk| !completed.contains(&chunk.id))
+            .map(|chunk| chunk.size)
+            .sum()
+    }
+
+    fn summary(
+        &self,
+        processed_bytes: u64,
+        chunks_completed: usize,
+        timer: RunTimer,
+    ) -> ChunkProcessingSummary {
+        ChunkProcessingSummary {
             total_bytes: self.total_size,
             processed_bytes,
             chunks_total: self.chunks.len(),
-            chunks_completed: processed_chunks,
+            chunks_completed,
             checkpoint_cleared: true,
-        })
+            bytes_per_sec: timer.rate(),
+            elapsed: timer.started.elapsed(),
+            chunk_durations: timer.durations,
+        }
     }
 
     fn load_checkpoint(&self) -> AgeResult<ChunkCheckpoint> {
@@ -315,6 +530,14 @@ fn checkpoint_path(source: &Path, checkpoint_dir: Option<&PathBuf>) -> PathBuf {
     }
 }
 
+fn format_eta(eta: Option<Duration>) -> String {
+    match eta.map(|d| d.as_secs()) {
+        None => "--".to_string(),
+        Some(secs) if secs >= 3600 => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+        Some(secs) => format!("{}m{:02}s", secs / 60, secs % 60),
+    }
+}
+
 fn metadata_modified(path: &Path) -> AgeResult<SystemTime> {
     fs::metadata(path)
         .map_err(|e| AgeError::file_error("chunker_metadata", path.to_path_buf(), e))?
@@ -340,6 +563,7 @@ mod tests {
                 chunk_size: 4096,
                 checkpoint_dir: None,
                 enable_progress: false,
+                memory_budget: MemoryBudget::unlimited(),
             },
         )
         .unwrap();
@@ -374,4 +598,101 @@ mod tests {
         assert_eq!(summary.chunks_total, chunker.chunks().len());
         assert_eq!(collected.len(), chunker.chunks().len());
     }
+
+    #[test]
+    fn test_chunk_metrics_callback_and_timing() {
+        use std::sync::Mutex;
+
+        let file = NamedTempFile::new().unwrap();
+        file.reopen()
+            .unwrap()
+            .write_all(&vec![b'm'; 10_000])
+            .unwrap();
+        let config = ChunkerConfig {
+            chunk_size: 4096,
+            enable_progress: false,
+            ..ChunkerConfig::default()
+        };
+        let chunker = FileChunker::new(file.path(), config).unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let callback: ChunkMetricsFn = Arc::new(move |metrics: &ChunkMetrics| {
+            sink.lock().unwrap().push(metrics.clone());
+        });
+        let summary = chunker
+            .process_with_metrics(|_, _| Ok(()), Some(callback))
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[2].chunks_completed, 3);
+        assert_eq!(seen[2].bytes, 10_000 - 2 * 4096);
+        assert!(seen.iter().all(|m| m.duration <= m.elapsed));
+        if seen[2].bytes_per_sec > 0.0 {
+            assert_eq!(seen[2].eta, Some(Duration::ZERO));
+        }
+        assert_eq!(
+            summary
+                .chunk_durations
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert!(summary.elapsed >= seen[2].elapsed);
+        assert_eq!(format_eta(Some(Duration::from_secs(3725))), "1h02m");
+        assert_eq!(format_eta(None), "--");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_chunk_processing_async() {
+        let file = NamedTempFile::new().unwrap();
+        file.reopen()
+            .unwrap()
+            .write_all(&vec![b'z'; 10_000])
+            .unwrap();
+
+        let config = ChunkerConfig {
+            chunk_size: 4096,
+            enable_progress: false,
+            ..ChunkerConfig::default()
+        };
+        let chunker = FileChunker::new(file.path(), config).unwrap();
+
+        let mut sizes = Vec::new();
+        let summary = chunker
+            .process_async(|_chunk, data| {
+                sizes.push(data.len());
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(sizes, vec![4096, 4096, 1808]);
+        assert_eq!(summary.processed_bytes, 10_000);
+        assert!(!file.path().with_extension("cage.chunk").exists());
+    }
+
+    #[test]
+    fn test_chunk_size_clamped_to_memory_budget() {
+        let file = NamedTempFile::new().unwrap();
+        let mut f = file.reopen().unwrap();
+        f.write_all(&vec![b'a'; 32 * 1024]).unwrap();
+
+        let chunker = FileChunker::new(
+            file.path(),
+            ChunkerConfig {
+                chunk_size: 1024 * 1024,
+                checkpoint_dir: None,
+                enable_progress: false,
+                memory_budget: MemoryBudget::new(Some(8 * 1024)),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(chunker.chunks().len(), 4);
+        assert!(chunker.chunks().iter().all(|c| c.size <= 8 * 1024));
+    }
 }
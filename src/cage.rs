@@ -0,0 +1,51 @@
+//! Legacy Paths - Deprecated shims for the pre-flattening `cage::cage::*` layout
+//!
+//! Before MOD4-07 everything lived under `src/cage/`, so downstream code imported
+//! `cage::cage::core::AgeConfig`, `cage::cage::lifecycle::CrudManager` and so on. Those
+//! paths keep resolving here so upgrades compile, but every use warns with the flattened
+//! replacement. New subsystems land only in the flattened layout and are not mirrored here.
+//!
+//! | Legacy path                          | Replacement                |
+//! |--------------------------------------|----------------------------|
+//! | `cage::cage::{adp,core,mgr,...}`     | `cage::{adp,core,mgr,...}` |
+//! | `cage::cage::config`                 | `cage::core::config`       |
+//! | `cage::cage::strings`                | `cage::lang`               |
+//! | `cage::cage::lifecycle`              | `cage::mgr`                |
+//! | `cage::cage::lifecycle::CrudManager` | `cage::mgr::CageManager`   |
+//! | `cage::cage::adapter`                | `cage::adp::v1`            |
+//! | `cage::cage::adapter_v2`             | `cage::adp::v2`            |
+//! | `cage::cage::security`               | `cage::audit`              |
+//! | `cage::cage::manager`                | `cage::mgr`                |
+//! | `cage::cage::operations`             | `cage::forge`              |
+//! | `cage::cage::chunker`                | `cage::buff`               |
+//! | `cage::cage::requests`               | `cage::core::requests`     |
+//! | `cage::cage::pty_wrap`               | `cage::pty::wrap`          |
+//! | `cage::cage::{CageManager,...}`      | `cage::{CageManager,...}`  |
+
+pub use crate::{adp, audit, buff, core, error, forge, keygen, mgr, passphrase, pty};
+
+pub use crate::adp::v1 as adapter;
+pub use crate::adp::v2 as adapter_v2;
+pub use crate::audit as security;
+pub use crate::buff as chunker;
+pub use crate::core::config;
+pub use crate::core::requests;
+pub use crate::forge as operations;
+pub use crate::lang as strings;
+pub use crate::mgr as manager;
+pub use crate::pty::wrap as pty_wrap;
+
+// Root types the old docs imported straight from `cage::cage`
+pub use crate::{
+    CageManager, InPlaceOperation, LockOptions, OutputFormat, PassphraseManager, PassphraseMode,
+    RecoveryManager, SafetyValidator,
+};
+
+/// Old home of the manager, now `cage::mgr`
+pub mod lifecycle {
+    pub use crate::mgr::*;
+
+    /// The manager's name before it became `CageManager`
+    #[deprecated(since = "0.6.0", note = "renamed to `cage::mgr::CageManager`")]
+    pub type CrudManager = crate::mgr::CageManager;
+}
@@ -0,0 +1,290 @@
+//! Clipboard - Copy a decrypted secret to the clipboard and clear it again (feature `clipboard`)
+//!
+//! `cage show <file> --clip` decrypts into memory, hands the plaintext to the platform's
+//! clipboard tool and waits, clearing the clipboard after the delay (or on Ctrl-C) unless
+//! something else was copied in the meantime. No plaintext file is written. The usual
+//! command-line tools are driven, so the feature adds no dependencies:
+//!
+//! - macOS: `pbcopy` / `pbpaste`
+//! - Wayland: `wl-copy` / `wl-paste`
+//! - X11: `xclip`, or `xsel`
+//! - Windows: `clip.exe` (cannot be read back, so it is cleared unconditionally)
+
+use crate::core::interrupt;
+use crate::error::{AgeError, AgeResult};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Delay before the clipboard is cleared when none is given
+pub const DEFAULT_CLEAR_AFTER: Duration = Duration::from_secs(45);
+
+/// Largest plaintext copied to the clipboard; this is for credentials, not documents
+pub const MAX_CLIP_BYTES: usize = 64 * 1024;
+
+/// Command-line clipboard tools, in detection order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardTool {
+    Pbcopy,
+    WlCopy,
+    Xclip,
+    Xsel,
+    ClipExe,
+}
+
+impl ClipboardTool {
+    /// First tool that is installed and has a session to talk to
+    pub fn detect() -> AgeResult<Self> {
+        Self::detect_from(
+            std::env::var_os("WAYLAND_DISPLAY").is_some(),
+            std::env::var_os("DISPLAY").is_some(),
+            |program| which::which(program).is_ok(),
+        )
+        .ok_or_else(|| AgeError::DependencyMissing {
+            dependency: "clipboard tool".to_string(),
+            installation_guide: "Install wl-clipboard (Wayland) or xclip/xsel (X11)".to_string(),
+        })
+    }
+
+    /// First candidate with a session (`wayland`, `x11`, or the platform) that is
+    /// `installed`
+    fn detect_from(wayland: bool, x11: bool, installed: impl Fn(&str) -> bool) -> Option<Self> {
+        let candidates = [
+            (Self::Pbcopy, cfg!(target_os = "macos")),
+            (Self::WlCopy, wayland),
+            (Self::Xclip, x11),
+            (Self::Xsel, x11),
+            (Self::ClipExe, cfg!(windows)),
+        ];
+        candidates
+            .into_iter()
+            .find(|(tool, session)| *session && installed(tool.program()))
+            .map(|(tool, _)| tool)
+    }
+
+    /// Program that sets the clipboard from stdin
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Pbcopy => "pbcopy",
+            Self::WlCopy => "wl-copy",
+            Self::Xclip => "xclip",
+            Self::Xsel => "xsel",
+            Self::ClipExe => "clip.exe",
+        }
+    }
+
+    fn copy_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Xclip => &["-selection", "clipboard"],
+            Self::Xsel => &["--clipboard", "--input"],
+            Self::Pbcopy | Self::WlCopy | Self::ClipExe => &[],
+        }
+    }
+
+    /// Program and arguments printing the clipboard, if the platform has one
+    fn paste_command(self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            Self::Pbcopy => Some(("pbpaste", &[])),
+            Self::WlCopy => Some(("wl-paste", &["--no-newline"])),
+            Self::Xclip => Some(("xclip", &["-selection", "clipboard", "-o"])),
+            Self::Xsel => Some(("xsel", &["--clipboard", "--output"])),
+            Self::ClipExe => None,
+        }
+    }
+}
+
+/// Clipboard driven through a command-line tool
+#[derive(Debug, Clone, Copy)]
+pub struct Clipboard {
+    tool: ClipboardTool,
+}
+
+impl Clipboard {
+    /// Clipboard using the detected tool
+    pub fn detect() -> AgeResult<Self> {
+        ClipboardTool::detect().map(Self::with_tool)
+    }
+
+    /// Clipboard using a specific tool
+    pub fn with_tool(tool: ClipboardTool) -> Self {
+        Self { tool }
+    }
+
+    /// Tool this clipboard runs
+    pub fn tool(&self) -> ClipboardTool {
+        self.tool
+    }
+
+    /// Replace the clipboard contents with `data`
+    pub fn copy(&self, data: &[u8]) -> AgeResult<()> {
+        self.run(self.tool.copy_args(), Some(data))
+    }
+
+    /// Empty the clipboard
+    pub fn clear(&self) -> AgeResult<()> {
+        match self.tool {
+            ClipboardTool::WlCopy => self.run(&["--clear"], None),
+            _ => self.copy(b""),
+        }
+    }
+
+    /// Wait `delay` (or until Ctrl-C), then clear the clipboard if it still holds `data`
+    ///
+    /// Returns whether it was cleared; something copied in the meantime is left alone.
+    pub fn clear_after(&self, data: &[u8], delay: Duration) -> AgeResult<bool> {
+        let ours: [u8; 32] = Sha256::digest(data).into();
+        let deadline = Instant::now() + delay;
+        while Instant::now() < deadline && interrupt::interrupted().is_none() {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        match self.digest() {
+            Some(current) if current != ours => Ok(false),
+            _ => self.clear().map(|_| true),
+        }
+    }
+
+    /// SHA-256 of the current contents, or `None` when they cannot be read
+    fn digest(&self) -> Option<[u8; 32]> {
+        let (program, args) = self.tool.paste_command()?;
+        let output = Command::new(program)
+            .args(args)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| Sha256::digest(&output.stdout).into())
+    }
+
+    fn run(&self, args: &[&str], input: Option<&[u8]>) -> AgeResult<()> {
+        let program = self.tool.program();
+        let io_error = |source| AgeError::IoError {
+            operation: "clipboard".to_string(),
+            context: program.to_string(),
+            source,
+        };
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(io_error)?;
+        // Dropping stdin after the write lets tools that fork (xclip, wl-copy) take over
+        if let (Some(mut stdin), Some(data)) = (child.stdin.take(), input) {
+            stdin.write_all(data).map_err(io_error)?;
+        }
+        let status = child.wait().map_err(io_error)?;
+        if !status.success() {
+            return Err(AgeError::ProcessExecutionFailed {
+                command: program.to_string(),
+                exit_code: status.code(),
+                stderr: String::new(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_detection_order() {
+        let all = |_: &str| true;
+        assert_eq!(
+            ClipboardTool::detect_from(true, true, all),
+            Some(ClipboardTool::WlCopy)
+        );
+        assert_eq!(
+            ClipboardTool::detect_from(false, true, all),
+            Some(ClipboardTool::Xclip)
+        );
+        assert_eq!(
+            ClipboardTool::detect_from(true, true, |program| program == "xsel"),
+            Some(ClipboardTool::Xsel)
+        );
+        // A tool without a session to talk to is never picked
+        assert_eq!(ClipboardTool::detect_from(false, false, all), None);
+        assert_eq!(ClipboardTool::detect_from(false, true, |_| false), None);
+    }
+
+    #[test]
+    fn test_tool_arguments() {
+        assert_eq!(
+            ClipboardTool::Xclip.copy_args(),
+            ["-selection", "clipboard"]
+        );
+        assert_eq!(ClipboardTool::Xsel.copy_args(), ["--clipboard", "--input"]);
+        assert!(ClipboardTool::Pbcopy.copy_args().is_empty());
+        assert!(ClipboardTool::WlCopy.copy_args().is_empty());
+        assert!(ClipboardTool::ClipExe.copy_args().is_empty());
+
+        let paste = |tool: ClipboardTool| tool.paste_command().map(|(p, a)| (p, a.to_vec()));
+        assert_eq!(paste(ClipboardTool::Pbcopy), Some(("pbpaste", vec![])));
+        assert_eq!(
+            paste(ClipboardTool::WlCopy),
+            Some(("wl-paste", vec!["--no-newline"]))
+        );
+        assert_eq!(
+            paste(ClipboardTool::Xclip),
+            Some(("xclip", vec!["-selection", "clipboard", "-o"]))
+        );
+        assert_eq!(
+            paste(ClipboardTool::Xsel),
+            Some(("xsel", vec!["--clipboard", "--output"]))
+        );
+        assert_eq!(paste(ClipboardTool::ClipExe), None);
+    }
+
+    /// Put a stub `xsel` that keeps the clipboard in `<dir>/clipboard` first on PATH
+    #[cfg(unix)]
+    fn stub_xsel(dir: &TempDir) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let store = dir.path().join("clipboard");
+        let script = dir.path().join("xsel");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\ncase \"$2\" in\n  --input) cat > '{0}' ;;\n  \
+                 --output) cat '{0}' ;;\nesac\n",
+                store.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let mut dirs = vec![dir.path().to_path_buf()];
+        dirs.extend(std::env::split_paths(&path));
+        std::env::set_var("PATH", std::env::join_paths(dirs).unwrap());
+        store
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clear_after_with_stub_tool() {
+        let dir = TempDir::new().unwrap();
+        let store = stub_xsel(&dir);
+        let clipboard = Clipboard::with_tool(ClipboardTool::Xsel);
+
+        clipboard.copy(b"hunter2").unwrap();
+        assert_eq!(std::fs::read(&store).unwrap(), b"hunter2");
+        assert!(clipboard.clear_after(b"hunter2", Duration::ZERO).unwrap());
+        assert!(std::fs::read(&store).unwrap().is_empty());
+
+        // Something else copied in the meantime is left alone
+        clipboard.copy(b"hunter2").unwrap();
+        std::fs::write(&store, "copied later").unwrap();
+        assert!(!clipboard.clear_after(b"hunter2", Duration::ZERO).unwrap());
+        assert_eq!(std::fs::read(&store).unwrap(), b"copied later");
+    }
+}
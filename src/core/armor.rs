@@ -0,0 +1,178 @@
+//! Armor - Guardrails for ASCII armor on large inputs
+//!
+//! ASCII armor base64-encodes the ciphertext in 64-column lines, so an armored file is
+//! about a third larger than the binary one; on multi-GB inputs that is gigabytes of
+//! extra disk and transfer for no security benefit. `AgeConfig::armor_threshold`
+//! (`[limits] armor_threshold = "1G"`, `off` to disable) sets the input size at which
+//! an armored lock or `cage convert --to ascii` is flagged, and `armor_oversize` decides
+//! whether that is a warning (`warn`, the default) or a refusal (`refuse`).
+//!
+//! Armor is always produced and read as a stream (`age -a`, `ArmoredWriter`,
+//! `ArmoredReader`); structural checks on existing armored files read only the head and
+//! tail, so verifying a multi-GB armored file never buffers it.
+
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const ARMOR_BEGIN: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+pub const ARMOR_END: &[u8] = b"-----END AGE ENCRYPTED FILE-----";
+/// Base64 characters per armored line
+const COLUMNS: u64 = 64;
+/// Bytes read from the end of a file when looking for the END line
+const TAIL_BYTES: u64 = 128;
+
+/// Input size from which armor is flagged unless configured otherwise (1 GiB)
+pub const DEFAULT_ARMOR_THRESHOLD: u64 = 1024 * 1024 * 1024;
+
+/// What happens when armor is requested for an input over the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArmorPolicy {
+    /// Encrypt anyway and print a warning (default)
+    #[default]
+    Warn,
+    /// Fail the file; use binary output or raise the threshold
+    Refuse,
+}
+
+impl ArmorPolicy {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "warn" => Ok(Self::Warn),
+            "refuse" => Ok(Self::Refuse),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "limits.armor_oversize".to_string(),
+                value: value.to_string(),
+                reason: "Valid values: warn, refuse".to_string(),
+            }),
+        }
+    }
+}
+
+/// Size of the armored encoding of `binary_len` bytes of ciphertext
+pub fn armored_len(binary_len: u64) -> u64 {
+    let encoded = binary_len.div_ceil(3) * 4;
+    let newlines = encoded.div_ceil(COLUMNS);
+    encoded + newlines + ARMOR_BEGIN.len() as u64 + ARMOR_END.len() as u64 + 2
+}
+
+/// Check an input about to be armored against the configured threshold.
+///
+/// Returns a warning to show when the input is over it under `ArmorPolicy::Warn`, and an
+/// error under `ArmorPolicy::Refuse`.
+pub fn check_input(input: &Path, config: &AgeConfig) -> AgeResult<Option<String>> {
+    let Some(threshold) = config.armor_threshold else {
+        return Ok(None);
+    };
+    let len = std::fs::metadata(input)
+        .map_err(|e| AgeError::file_error("stat", input.to_path_buf(), e))?
+        .len();
+    if len < threshold {
+        return Ok(None);
+    }
+    let message = format!(
+        "{} is {} bytes; ASCII armor grows it to about {} bytes (threshold {})",
+        input.display(),
+        len,
+        armored_len(len),
+        threshold
+    );
+    match config.armor_oversize {
+        ArmorPolicy::Warn => Ok(Some(format!("{}; consider binary output", message))),
+        ArmorPolicy::Refuse => Err(AgeError::InvalidOperation {
+            operation: "armor".to_string(),
+            reason: format!(
+                "{}; use binary output or raise [limits] armor_threshold",
+                message
+            ),
+        }),
+    }
+}
+
+/// Whether the armored file at `path` ends with its END line, reading only the tail
+pub fn is_terminated(path: &Path) -> AgeResult<bool> {
+    let io_error = |e| AgeError::file_error("read", path.to_path_buf(), e);
+    let mut file = File::open(path).map_err(io_error)?;
+    let len = file.metadata().map_err(io_error)?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))
+        .map_err(io_error)?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).map_err(io_error)?;
+    Ok(tail.trim_ascii_end().ends_with(ARMOR_END))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Sparse armored file of `len` bytes: BEGIN line, a hole, END line at the end
+    fn sparse_armor(path: &Path, len: u64, terminated: bool) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(ARMOR_BEGIN).unwrap();
+        file.write_all(b"\n").unwrap();
+        if terminated {
+            let end = [ARMOR_END, b"\n"].concat();
+            file.seek(SeekFrom::Start(len - end.len() as u64)).unwrap();
+            file.write_all(&end).unwrap();
+        } else {
+            file.set_len(len).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_armored_len_matches_encoding() {
+        // 48 bytes -> 64 base64 characters on one line
+        assert_eq!(
+            armored_len(48),
+            64 + 1 + ARMOR_BEGIN.len() as u64 + ARMOR_END.len() as u64 + 2
+        );
+        let gib = DEFAULT_ARMOR_THRESHOLD;
+        assert!(armored_len(gib) > gib + gib / 3);
+    }
+
+    #[test]
+    fn test_large_input_warns_or_refuses() {
+        let temp = TempDir::new().unwrap();
+        let input = temp.path().join("huge.bin");
+        File::create(&input)
+            .unwrap()
+            .set_len(DEFAULT_ARMOR_THRESHOLD + 1)
+            .unwrap();
+        let small = temp.path().join("small.bin");
+        std::fs::write(&small, "x").unwrap();
+
+        let config = AgeConfig::default();
+        assert!(check_input(&small, &config).unwrap().is_none());
+        let warning = check_input(&input, &config).unwrap().unwrap();
+        assert!(warning.contains("huge.bin"));
+
+        let refusing = AgeConfig {
+            armor_oversize: ArmorPolicy::Refuse,
+            ..AgeConfig::default()
+        };
+        assert!(check_input(&input, &refusing).is_err());
+        let disabled = AgeConfig {
+            armor_threshold: None,
+            armor_oversize: ArmorPolicy::Refuse,
+            ..AgeConfig::default()
+        };
+        assert!(check_input(&input, &disabled).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_terminated_reads_only_the_tail() {
+        let temp = TempDir::new().unwrap();
+        let len = 3 * DEFAULT_ARMOR_THRESHOLD / 2;
+        let complete = temp.path().join("complete.cage");
+        let truncated = temp.path().join("truncated.cage");
+        sparse_armor(&complete, len, true);
+        sparse_armor(&truncated, len, false);
+
+        assert!(is_terminated(&complete).unwrap());
+        assert!(!is_terminated(&truncated).unwrap());
+    }
+}
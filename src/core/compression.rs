@@ -0,0 +1,102 @@
+//! Compression Detection - Diagnose compressed ciphertext stacks
+//!
+//! Ciphertext is sometimes gzipped (`secrets.env.cage.gz`) or zstd-compressed for
+//! transport. Cage does not decompress these itself; verify and unlock sniff the magic
+//! bytes instead and report the wrapper with the command that removes it, rather than
+//! calling the file an invalid age file.
+
+use crate::error::{AgeError, AgeResult};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Compression format wrapped around a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionWrap {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl CompressionWrap {
+    /// Identify a compression wrapper from the first bytes of a file
+    pub fn detect(prefix: &[u8]) -> Option<Self> {
+        if prefix.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if prefix.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else if prefix.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if prefix.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Self::Xz)
+        } else {
+            None
+        }
+    }
+
+    /// Sniff the first bytes of `path`
+    pub fn detect_file(path: &Path) -> AgeResult<Option<Self>> {
+        let mut prefix = [0u8; 6];
+        let read = File::open(path)
+            .and_then(|mut file| file.read(&mut prefix))
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        Ok(Self::detect(&prefix[..read]))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::Bzip2 => "bzip2",
+            Self::Xz => "xz",
+        }
+    }
+
+    /// Shell command that decompresses while keeping the original
+    pub fn decompress_command(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip -dk",
+            Self::Zstd => "zstd -d",
+            Self::Bzip2 => "bzip2 -dk",
+            Self::Xz => "xz -dk",
+        }
+    }
+
+    /// Diagnostic for a compressed file found where ciphertext was expected
+    pub fn diagnostic(&self, path: &Path) -> String {
+        format!(
+            "{} is {}-compressed, not a raw age file; decompress it first (`{} {}`) and retry",
+            path.display(),
+            self.name(),
+            self.decompress_command(),
+            path.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detects_compression_wrappers() {
+        assert_eq!(
+            CompressionWrap::detect(&[0x1f, 0x8b, 0x08]),
+            Some(CompressionWrap::Gzip)
+        );
+        assert_eq!(
+            CompressionWrap::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Some(CompressionWrap::Zstd)
+        );
+        assert_eq!(CompressionWrap::detect(b"age-encryption.org/v1"), None);
+        assert_eq!(CompressionWrap::detect(&[0x1f]), None);
+
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("secret.txt.cage.gz");
+        std::fs::write(&path, [0x1f, 0x8b, 0x08, 0x00]).unwrap();
+        let wrap = CompressionWrap::detect_file(&path).unwrap().unwrap();
+        assert!(wrap.diagnostic(&path).contains("gzip -dk"));
+    }
+}
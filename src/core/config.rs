@@ -6,14 +6,15 @@
 //! Security Guardian: Edgar - Production-ready configuration management
 
 use crate::error::{AgeError, AgeResult};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Output format for Age encryption
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OutputFormat {
     /// Binary output (.age files) - default and most efficient
     Binary,
@@ -109,6 +110,61 @@ impl Default for TelemetryFormat {
     }
 }
 
+/// How repository locks treat files hardlinked to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HardlinkPolicy {
+    /// Encrypt one instance and hardlink its output for every other link (default)
+    #[default]
+    Relink,
+    /// Encrypt one instance and report the other links as skipped
+    Report,
+}
+
+impl HardlinkPolicy {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "relink" => Ok(Self::Relink),
+            "report" => Ok(Self::Report),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "traversal.hardlinks".to_string(),
+                value: value.to_string(),
+                reason: "Valid values: relink, report".to_string(),
+            }),
+        }
+    }
+}
+
+/// Order of file listings in status, verify and operation results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListingOrder {
+    /// Lexicographic by path, so repeated runs list identically (default)
+    #[default]
+    Sorted,
+    /// Whatever order the filesystem returns; skips the sort on very large trees
+    Filesystem,
+}
+
+impl ListingOrder {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "sorted" => Ok(Self::Sorted),
+            "filesystem" => Ok(Self::Filesystem),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "traversal.order".to_string(),
+                value: value.to_string(),
+                reason: "Valid values: sorted, filesystem".to_string(),
+            }),
+        }
+    }
+
+    /// Put `items` in this order, comparing the path each one refers to
+    pub fn arrange<T>(self, items: &mut [T], path: impl Fn(&T) -> &Path) {
+        if self == Self::Sorted {
+            items.sort_by(|a, b| path(a).cmp(path(b)));
+        }
+    }
+}
+
 /// Security validation level
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SecurityLevel {
@@ -137,7 +193,7 @@ impl Default for SecurityLevel {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RetentionPolicyConfig {
     KeepAll,
     KeepDays(u32),
@@ -151,6 +207,13 @@ impl Default for RetentionPolicyConfig {
     }
 }
 
+impl RetentionPolicyConfig {
+    /// Parse `keep_all`, `keep_days:N`, `keep_last:N` or `keep_last_and_days:N,D`
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        parse_retention_policy(value)
+    }
+}
+
 /// Age automation configuration
 #[derive(Debug, Clone)]
 pub struct AgeConfig {
@@ -175,6 +238,15 @@ pub struct AgeConfig {
     /// Path to Age binary (None for auto-detection)
     pub age_binary_path: Option<String>,
 
+    /// Pinned SHA-256 of the `age` binary on PATH (see `adp::pin`)
+    pub age_binary_sha256: Option<String>,
+
+    /// Pinned SHA-256 of the `age-keygen` binary on PATH
+    pub age_keygen_binary_sha256: Option<String>,
+
+    /// Whether a binary that does not match its pin is refused or only warned about
+    pub binary_pin_policy: crate::adp::PinPolicy,
+
     /// Path to script binary (None for auto-detection)
     pub script_binary_path: Option<String>,
 
@@ -187,6 +259,22 @@ pub struct AgeConfig {
     /// Audit log file path (None for stderr)
     pub audit_log_path: Option<String>,
 
+    /// Key file for chain-signing audit log lines (None disables signing)
+    pub audit_signing_key_file: Option<String>,
+
+    /// Extra audit outputs as `<format>:<target>` specs (see `audit::output`)
+    pub audit_outputs: Vec<String>,
+
+    /// Decryptions within `audit_unlock_window` above which events are flagged as a
+    /// burst (None disables the heuristic; see `audit::anomaly`)
+    pub audit_unlock_limit: Option<usize>,
+
+    /// Window the unlock limit is counted over
+    pub audit_unlock_window: Duration,
+
+    /// Local hours decryptions are expected in; others are flagged (None disables)
+    pub audit_unlock_hours: Option<crate::audit::UnlockHours>,
+
     /// Enable security validation
     pub security_validation: bool,
 
@@ -220,6 +308,36 @@ pub struct AgeConfig {
     /// Default streaming strategy (temp, pipe, auto)
     pub streaming_strategy: Option<String>,
 
+    /// Adapters tried in order at startup (empty uses the factory default)
+    pub adapter_chain: Vec<String>,
+
+    /// Handling of hardlinked files during repository locks
+    pub hardlink_policy: HardlinkPolicy,
+
+    /// Order of file listings (sorted by path unless set to filesystem order)
+    pub listing_order: ListingOrder,
+
+    /// Globs refused as recursive or in-place targets without `--allow-risky-path`
+    pub risky_path_denylist: Vec<String>,
+
+    /// Recovery recipient whose presence satisfies `verify_decryptable` on recipient locks
+    pub escrow_recipient: Option<String>,
+
+    /// SHA-256 of the admin token; when set, `admin_operations` need it (see `core::roles`)
+    pub admin_token_hash: Option<String>,
+
+    /// Operations reserved for admins once `admin_token_hash` is set
+    pub admin_operations: Vec<String>,
+
+    /// Tries at confirming a new interactive passphrase (1-10)
+    pub passphrase_confirm_attempts: u32,
+
+    /// New passphrases shorter than this get a nudge towards a longer one
+    pub passphrase_min_length: usize,
+
+    /// Show a masked strength meter when choosing a new passphrase
+    pub passphrase_strength_meter: bool,
+
     /// Telemetry output format for audit trails (text or json)
     pub telemetry_format: TelemetryFormat,
 
@@ -231,6 +349,66 @@ pub struct AgeConfig {
 
     /// Default extensions considered encrypted (includes .padlock for Padlock)
     pub encrypted_extensions: Vec<String>,
+
+    /// Named identity provider URIs (alias -> URI)
+    pub identity_providers: std::collections::HashMap<String, String>,
+
+    /// Throughput cap (bytes/sec) for batch and recursive operations
+    pub max_throughput: Option<u64>,
+
+    /// Process nice value applied before operations (-20..19)
+    pub process_nice: Option<i32>,
+
+    /// I/O scheduling class hint applied before operations
+    pub io_priority: Option<crate::core::IoPriority>,
+
+    /// Cap (bytes) on total in-flight buffer memory across streams, chunks and workers
+    pub memory_budget: Option<u64>,
+
+    /// Performance preset filling unset knobs (see `core::profile`)
+    pub profile: Option<crate::core::PerformanceProfile>,
+
+    /// Show progress in the CLI without `--progress` (None leaves it off)
+    pub progress: Option<bool>,
+
+    /// Input size (bytes) from which ASCII armor is flagged (None disables the check)
+    pub armor_threshold: Option<u64>,
+
+    /// Warn about or refuse armor for inputs over `armor_threshold`
+    pub armor_oversize: crate::core::ArmorPolicy,
+
+    /// Cap on concurrently running `age` processes (None uses the supervisor default)
+    pub max_processes: Option<usize>,
+
+    /// Kill an `age` process that runs longer than this (None disables the limit)
+    pub process_timeout: Option<Duration>,
+
+    /// Confinement of spawned `age` processes (see `adp::sandbox`)
+    pub process_sandbox: crate::adp::SandboxMode,
+
+    /// Mode for created ciphertext, e.g. 0o644 (None inherits the umask)
+    pub ciphertext_mode: Option<u32>,
+
+    /// Mode for created plaintext, e.g. 0o600 (None inherits the umask)
+    pub plaintext_mode: Option<u32>,
+
+    /// Give outputs and backups the uid/gid of their source file (effective as root)
+    pub preserve_ownership: bool,
+
+    /// Write a `.meta` sidecar with recipients and groups next to each ciphertext
+    pub meta_sidecar: bool,
+
+    /// Record plaintext/ciphertext hashes in `.cage/checksums.json` at lock time
+    pub checksum_db: bool,
+
+    /// Move removed files here instead of unlinking them (None disables quarantine)
+    pub quarantine_directory: Option<String>,
+
+    /// Retention policy applied to quarantined files
+    pub quarantine_retention: RetentionPolicyConfig,
+
+    /// Config keys set from `CAGE_*` environment variables, as (key, variable)
+    pub env_overrides: Vec<(String, String)>,
 }
 
 impl AgeConfig {
@@ -315,6 +493,14 @@ impl AgeConfig {
             });
         }
 
+        if !(1..=10).contains(&self.passphrase_confirm_attempts) {
+            return Err(AgeError::ConfigurationError {
+                parameter: "passphrase_confirm_attempts".to_string(),
+                value: self.passphrase_confirm_attempts.to_string(),
+                reason: "Must be between 1 and 10".to_string(),
+            });
+        }
+
         // Validate retry settings
         if self.max_retries > 10 {
             return Err(AgeError::ConfigurationError {
@@ -324,6 +510,29 @@ impl AgeConfig {
             });
         }
 
+        if let Some(nice) = self.process_nice {
+            if !(-20..=19).contains(&nice) {
+                return Err(AgeError::ConfigurationError {
+                    parameter: "scheduling.nice".to_string(),
+                    value: nice.to_string(),
+                    reason: "Must be between -20 and 19".to_string(),
+                });
+            }
+        }
+
+        let adapters = crate::adp::v1::AdapterFactory::available_adapters();
+        if let Some(unknown) = self
+            .adapter_chain
+            .iter()
+            .find(|name| !adapters.contains(&name.as_str()))
+        {
+            return Err(AgeError::ConfigurationError {
+                parameter: "adapter".to_string(),
+                value: unknown.clone(),
+                reason: format!("Valid values: {}", adapters.join(", ")),
+            });
+        }
+
         if let Some(strategy) = &self.streaming_strategy {
             match strategy.as_str() {
                 "temp" | "pipe" | "auto" => {}
@@ -410,13 +619,379 @@ impl AgeConfig {
     }
 
     pub fn load_default() -> AgeResult<Self> {
-        for path in default_config_paths() {
-            if path.exists() {
-                return Self::load_from_path(&path);
+        let mut config = match default_config_paths().into_iter().find(|p| p.exists()) {
+            Some(path) => Self::load_from_path(&path)?,
+            None => AgeConfig::default(),
+        };
+        config.apply_env_overrides()?;
+        config.apply_profile();
+        super::groups::GroupStore::load_default()?.merge_into(&mut config);
+        Ok(config)
+    }
+
+    /// Load the config file and environment overrides without checking seals, persisting
+    /// upgrades or merging the group store.
+    ///
+    /// Only for settings that must stay readable while a seal is broken, such as the admin
+    /// gate of `cage config seal`; never for deciding what to encrypt to.
+    pub fn load_default_unsealed() -> AgeResult<Self> {
+        let mut config = match default_config_paths().into_iter().find(|p| p.exists()) {
+            Some(path) => Self::load_from_path_unsealed(&path)?,
+            None => AgeConfig::default(),
+        };
+        config.apply_env_overrides()?;
+        Ok(config)
+    }
+
+    /// Fill the knobs the configured `profile` covers that no layer has set
+    pub fn apply_profile(&mut self) {
+        if let Some(profile) = self.profile {
+            profile.apply(self);
+        }
+    }
+
+    /// Apply `CAGE_*` environment overrides on top of file values (see `ENV_OVERRIDES`)
+    pub fn apply_env_overrides(&mut self) -> AgeResult<()> {
+        self.apply_overrides_from(|name| env::var(name).ok())
+    }
+
+    fn apply_overrides_from<F>(&mut self, lookup: F) -> AgeResult<()>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for (variable, key) in ENV_OVERRIDES {
+            let Some(value) = lookup(variable).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            self.set_key(key, &value)
+                .map_err(|e| AgeError::ConfigurationError {
+                    parameter: variable.to_string(),
+                    value: value.clone(),
+                    reason: match e {
+                        AgeError::ConfigurationError { reason, .. } => reason,
+                        other => other.to_string(),
+                    },
+                })?;
+            self.env_overrides.retain(|(existing, _)| existing != key);
+            self.env_overrides
+                .push((key.to_string(), variable.to_string()));
+        }
+        self.validate()
+    }
+
+    /// Environment variable that set `key`, if any
+    pub fn env_override_for(&self, key: &str) -> Option<&str> {
+        self.env_overrides
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, variable)| variable.as_str())
+    }
+
+    /// Effective value of a documented key (see `CONFIG_SCHEMA`) in its config string form
+    pub fn effective_value(&self, key: &str) -> Option<String> {
+        let or_unset = |value: Option<String>| value.unwrap_or_else(|| "(unset)".to_string());
+        let list = |items: &[String]| {
+            if items.is_empty() {
+                "(none)".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        let lower = |value: &dyn std::fmt::Debug| format!("{:?}", value).to_lowercase();
+        let mode = |mode: Option<u32>| or_unset(mode.map(|m| format!("{:04o}", m)));
+
+        let value = match key {
+            "output_format" => match self.output_format {
+                OutputFormat::Binary => "binary".to_string(),
+                OutputFormat::AsciiArmor => "ascii".to_string(),
+            },
+            "tty_method" => lower(&self.tty_method),
+            "security_level" => lower(&self.security_level),
+            "operation_timeout" => self.operation_timeout.as_secs().to_string(),
+            "max_retries" => self.max_retries.to_string(),
+            "age_binary_path" => or_unset(self.age_binary_path.clone()),
+            "age_binary_sha256" => or_unset(self.age_binary_sha256.clone()),
+            "age_keygen_binary_sha256" => or_unset(self.age_keygen_binary_sha256.clone()),
+            "binary_pin_policy" => lower(&self.binary_pin_policy),
+            "temp_dir_override" => or_unset(self.temp_dir_override.clone()),
+            "adapter" => list(&self.adapter_chain),
+            "audit_logging" => self.audit_logging.to_string(),
+            "audit_log_path" => or_unset(self.audit_log_path.clone()),
+            "audit_signing_key_file" => or_unset(self.audit_signing_key_file.clone()),
+            "audit_outputs" => list(&self.audit_outputs),
+            "audit_unlock_limit" => or_unset(self.audit_unlock_limit.map(|n| n.to_string())),
+            "audit_unlock_window" => format!("{}s", self.audit_unlock_window.as_secs()),
+            "audit_unlock_hours" => or_unset(self.audit_unlock_hours.map(|h| h.to_string())),
+            "telemetry_format" => lower(&self.telemetry_format),
+            "secure_deletion" => self.secure_deletion.to_string(),
+            "encrypted_file_extension" => self.encrypted_file_extension.clone(),
+            "backup_cleanup" => self.backup_cleanup.to_string(),
+            "backup_directory" => or_unset(self.backup_directory.clone()),
+            "backup_retention" => format_retention_policy(&self.backup_retention),
+            "streaming_strategy" => self
+                .streaming_strategy
+                .clone()
+                .unwrap_or_else(|| "auto".to_string()),
+            "hardlink_policy" => lower(&self.hardlink_policy),
+            "listing_order" => lower(&self.listing_order),
+            "risky_path_denylist" => list(&self.risky_path_denylist),
+            "escrow_recipient" => or_unset(self.escrow_recipient.clone()),
+            "admin_token_hash" => or_unset(self.admin_token_hash.clone()),
+            "admin_operations" => list(&self.admin_operations),
+            "passphrase_confirm_attempts" => self.passphrase_confirm_attempts.to_string(),
+            "passphrase_min_length" => self.passphrase_min_length.to_string(),
+            "passphrase_strength_meter" => self.passphrase_strength_meter.to_string(),
+            "identity_providers" => {
+                let mut providers: Vec<String> = self
+                    .identity_providers
+                    .iter()
+                    .map(|(alias, uri)| format!("{} = {}", alias, uri))
+                    .collect();
+                providers.sort();
+                list(&providers)
             }
+            "max_throughput" => or_unset(self.max_throughput.map(|rate| rate.to_string())),
+            "process_nice" => or_unset(self.process_nice.map(|nice| nice.to_string())),
+            "io_priority" => or_unset(self.io_priority.map(|priority| match priority {
+                crate::core::IoPriority::Idle => "idle".to_string(),
+                crate::core::IoPriority::BestEffort(level) => format!("best-effort:{}", level),
+            })),
+            "memory_budget" => or_unset(self.memory_budget.map(|bytes| bytes.to_string())),
+            "profile" => or_unset(self.profile.map(|profile| profile.as_str().to_string())),
+            "progress" => self.progress.unwrap_or(false).to_string(),
+            "armor_threshold" => self
+                .armor_threshold
+                .map_or_else(|| "off".to_string(), |bytes| bytes.to_string()),
+            "armor_oversize" => lower(&self.armor_oversize),
+            "max_processes" => or_unset(self.max_processes.map(|limit| limit.to_string())),
+            "process_timeout" => self
+                .process_timeout
+                .map_or(0, |timeout| timeout.as_secs())
+                .to_string(),
+            "process_sandbox" => lower(&self.process_sandbox),
+            "ciphertext_mode" => mode(self.ciphertext_mode),
+            "plaintext_mode" => mode(self.plaintext_mode),
+            "preserve_ownership" => self.preserve_ownership.to_string(),
+            "meta_sidecar" => self.meta_sidecar.to_string(),
+            "checksum_db" => self.checksum_db.to_string(),
+            "quarantine_directory" => or_unset(self.quarantine_directory.clone()),
+            "quarantine_retention" => format_retention_policy(&self.quarantine_retention),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Layer that set `doc`'s effective value: environment, the loaded file, or the default
+    pub fn value_source(&self, doc: &ConfigKeyDoc) -> ConfigSource {
+        if let Some(variable) = self.env_override_for(doc.key) {
+            return ConfigSource::Env(variable.to_string());
         }
+        let (Some(path), Some(file_key)) = (&self.source_path, doc.file_key) else {
+            return ConfigSource::Default;
+        };
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        let contents = match super::config_migrate::migrate_config_str(&contents) {
+            Ok(Some(migration)) => migration.contents,
+            _ => contents,
+        };
+        let table: toml::Table = toml::from_str(&contents).unwrap_or_default();
+        let set_in_file = match file_key.split_once('.') {
+            Some((section, key)) => table.get(section).and_then(|s| s.get(key)).is_some(),
+            None => table.contains_key(file_key),
+        };
+        if set_in_file {
+            ConfigSource::File(path.clone())
+        } else {
+            ConfigSource::Default
+        }
+    }
 
-        Ok(AgeConfig::default())
+    /// Set one config key from its string form
+    fn set_key(&mut self, key: &str, value: &str) -> AgeResult<()> {
+        let trimmed = value.trim();
+        let invalid = |reason: &str| AgeError::ConfigurationError {
+            parameter: key.to_string(),
+            value: trimmed.to_string(),
+            reason: reason.to_string(),
+        };
+        let flag = || match trimmed.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "0" | "false" | "no" | "off" => Ok(false),
+            _ => Err(invalid("Expected true/false")),
+        };
+
+        match key {
+            "output_format" => {
+                self.output_format = match trimmed.to_lowercase().as_str() {
+                    "binary" => OutputFormat::Binary,
+                    "ascii" | "armor" | "ascii_armor" => OutputFormat::AsciiArmor,
+                    _ => return Err(invalid("Valid values: binary, ascii")),
+                }
+            }
+            "tty_method" => {
+                self.tty_method = match trimmed.to_lowercase().as_str() {
+                    "script" => TtyMethod::Script,
+                    "expect" => TtyMethod::Expect,
+                    "auto" => TtyMethod::Auto,
+                    _ => return Err(invalid("Valid values: script, expect, auto")),
+                }
+            }
+            "security_level" => {
+                self.security_level = match trimmed.to_lowercase().as_str() {
+                    "basic" => SecurityLevel::Basic,
+                    "standard" => SecurityLevel::Standard,
+                    "paranoid" => SecurityLevel::Paranoid,
+                    _ => return Err(invalid("Valid values: basic, standard, paranoid")),
+                }
+            }
+            "operation_timeout" => {
+                let secs: u64 = trimmed.parse().map_err(|_| invalid("Expected seconds"))?;
+                self.operation_timeout = Duration::from_secs(secs);
+            }
+            "max_retries" => {
+                self.max_retries = trimmed.parse().map_err(|_| invalid("Expected a number"))?
+            }
+            "age_binary_path" => self.age_binary_path = Some(trimmed.to_string()),
+            "age_binary_sha256" => {
+                self.age_binary_sha256 = Some(crate::adp::pin::parse_sha256(key, trimmed)?)
+            }
+            "age_keygen_binary_sha256" => {
+                self.age_keygen_binary_sha256 = Some(crate::adp::pin::parse_sha256(key, trimmed)?)
+            }
+            "binary_pin_policy" => self.binary_pin_policy = crate::adp::PinPolicy::parse(trimmed)?,
+            "temp_dir_override" => self.temp_dir_override = Some(trimmed.to_string()),
+            "audit_logging" => self.audit_logging = flag()?,
+            "audit_log_path" => self.audit_log_path = Some(trimmed.to_string()),
+            "audit_signing_key_file" => self.audit_signing_key_file = Some(trimmed.to_string()),
+            "audit_outputs" => {
+                self.audit_outputs = trimmed
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|spec| !spec.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            "audit_unlock_limit" => {
+                let limit: usize = trimmed.parse().map_err(|_| invalid("Expected a number"))?;
+                self.audit_unlock_limit = (limit > 0).then_some(limit)
+            }
+            "audit_unlock_window" => {
+                self.audit_unlock_window = super::mtime::parse_duration(trimmed)
+                    .filter(|window| !window.is_zero())
+                    .ok_or_else(|| invalid("Expected a duration such as 10m or 1h"))?
+            }
+            "audit_unlock_hours" => {
+                self.audit_unlock_hours = Some(crate::audit::UnlockHours::parse(trimmed)?)
+            }
+            "telemetry_format" => {
+                self.telemetry_format = match trimmed.to_lowercase().as_str() {
+                    "text" => TelemetryFormat::Text,
+                    "json" => TelemetryFormat::Json,
+                    _ => return Err(invalid("Valid values: text, json")),
+                }
+            }
+            "secure_deletion" => self.secure_deletion = flag()?,
+            "encrypted_file_extension" => {
+                self.encrypted_file_extension = trimmed.trim_start_matches('.').to_string()
+            }
+            "backup_cleanup" => self.backup_cleanup = flag()?,
+            "backup_directory" => self.backup_directory = Some(trimmed.to_string()),
+            "backup_retention" => self.backup_retention = parse_retention_policy(trimmed)?,
+            "streaming_strategy" => self.streaming_strategy = Some(trimmed.to_lowercase()),
+            "hardlink_policy" => self.hardlink_policy = HardlinkPolicy::parse(trimmed)?,
+            "listing_order" => self.listing_order = ListingOrder::parse(trimmed)?,
+            "risky_path_denylist" => {
+                self.risky_path_denylist = trimmed
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            "escrow_recipient" => self.escrow_recipient = Some(trimmed.to_string()),
+            "admin_token_hash" => {
+                self.admin_token_hash = Some(super::roles::parse_token_hash(trimmed)?)
+            }
+            "admin_operations" => {
+                let operations: Vec<String> = trimmed
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty())
+                    .map(String::from)
+                    .collect();
+                if let Some(unknown) = operations
+                    .iter()
+                    .find(|op| !super::roles::GATEABLE_OPERATIONS.contains(&op.as_str()))
+                {
+                    return Err(invalid(&format!(
+                        "Unknown operation '{}'; gateable: {}",
+                        unknown,
+                        super::roles::GATEABLE_OPERATIONS.join(", ")
+                    )));
+                }
+                self.admin_operations = operations
+            }
+            "passphrase_confirm_attempts" => {
+                self.passphrase_confirm_attempts =
+                    trimmed.parse().map_err(|_| invalid("Expected a number"))?
+            }
+            "passphrase_min_length" => {
+                self.passphrase_min_length =
+                    trimmed.parse().map_err(|_| invalid("Expected a number"))?
+            }
+            "passphrase_strength_meter" => self.passphrase_strength_meter = flag()?,
+            "adapter" => {
+                self.adapter_chain = trimmed
+                    .split(',')
+                    .map(|name| name.trim().to_lowercase())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            }
+            "max_throughput" => {
+                self.max_throughput = Some(crate::core::scheduling::parse_byte_rate(trimmed)?)
+            }
+            "process_nice" => {
+                self.process_nice = Some(trimmed.parse().map_err(|_| invalid("Expected -20..19"))?)
+            }
+            "io_priority" => self.io_priority = Some(crate::core::IoPriority::parse(trimmed)?),
+            "memory_budget" => {
+                self.memory_budget = Some(crate::core::scheduling::parse_byte_size(trimmed)?)
+            }
+            "profile" => self.profile = Some(crate::core::PerformanceProfile::parse(trimmed)?),
+            "progress" => self.progress = Some(flag()?),
+            "armor_threshold" => {
+                self.armor_threshold = match trimmed.to_lowercase().as_str() {
+                    "off" | "none" | "0" => None,
+                    _ => Some(crate::core::scheduling::parse_byte_size(trimmed)?),
+                }
+            }
+            "armor_oversize" => self.armor_oversize = crate::core::ArmorPolicy::parse(trimmed)?,
+            "max_processes" => {
+                let limit: usize = trimmed.parse().map_err(|_| invalid("Expected a number"))?;
+                if limit == 0 {
+                    return Err(invalid("Must be at least 1"));
+                }
+                self.max_processes = Some(limit)
+            }
+            "process_timeout" => {
+                let secs: u64 = trimmed.parse().map_err(|_| invalid("Expected seconds"))?;
+                self.process_timeout = (secs > 0).then_some(Duration::from_secs(secs))
+            }
+            "process_sandbox" => self.process_sandbox = crate::adp::SandboxMode::parse(trimmed)?,
+            "ciphertext_mode" => {
+                self.ciphertext_mode = Some(crate::core::permissions::parse_mode(trimmed)?)
+            }
+            "plaintext_mode" => {
+                self.plaintext_mode = Some(crate::core::permissions::parse_mode(trimmed)?)
+            }
+            "preserve_ownership" => self.preserve_ownership = flag()?,
+            "meta_sidecar" => self.meta_sidecar = flag()?,
+            "checksum_db" => self.checksum_db = flag()?,
+            "quarantine_directory" => self.quarantine_directory = Some(trimmed.to_string()),
+            "quarantine_retention" => self.quarantine_retention = parse_retention_policy(trimmed)?,
+            _ => return Err(invalid("Unknown config key")),
+        }
+        Ok(())
     }
 
     /// Get the paths that will be checked for configuration files
@@ -469,12 +1044,86 @@ impl AgeConfig {
         layers
     }
 
+    /// Check a config file without loading it; an empty list means it is valid
+    pub fn validate_file(path: &Path) -> AgeResult<Vec<super::config_check::ConfigIssue>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        // Check what loading would see after migration, without rewriting the file
+        let contents = match super::config_migrate::migrate_config_str(&contents) {
+            Ok(Some(migration)) => migration.contents,
+            Ok(None) => contents,
+            Err(e) => {
+                return Ok(vec![super::config_check::ConfigIssue {
+                    kind: super::config_check::ConfigIssueKind::InvalidValue,
+                    line: 0,
+                    column: 0,
+                    key: "version".to_string(),
+                    message: e.to_string(),
+                    suggestion: None,
+                }])
+            }
+        };
+        let mut issues = super::config_check::check_config_str(&contents);
+
+        // Cross-field rules live in `validate`, so run a full parse once the schema passes
+        if issues.is_empty() {
+            if let Err(e) = Self::parse_contents(path, &contents) {
+                issues.push(super::config_check::ConfigIssue {
+                    kind: super::config_check::ConfigIssueKind::InvalidValue,
+                    line: 0,
+                    column: 0,
+                    key: String::new(),
+                    message: e.to_string(),
+                    suggestion: None,
+                });
+            }
+        }
+        Ok(issues)
+    }
+
     fn load_from_path(path: &Path) -> AgeResult<Self> {
-        let contents = fs::read_to_string(path).map_err(|e| AgeError::ConfigurationError {
-            parameter: "config_file".to_string(),
-            value: path.display().to_string(),
-            reason: e.to_string(),
-        })?;
+        let contents = read_config_file(path)?;
+        super::config_seal::verify(path, contents.as_bytes())?;
+
+        let contents = match super::config_migrate::migrate_config_str(&contents)? {
+            Some(migration) => {
+                // A read-only (or tampered) config still loads from the in-memory upgrade
+                let _ = super::config_seal::rewrite_sealed(path, || {
+                    super::config_migrate::persist_migration(path, &migration)
+                });
+                migration.contents
+            }
+            None => contents,
+        };
+        Self::parse_contents(path, &contents)
+    }
+
+    /// `load_from_path` without the seal check; an upgrade stays in memory
+    fn load_from_path_unsealed(path: &Path) -> AgeResult<Self> {
+        let contents = read_config_file(path)?;
+        let contents = match super::config_migrate::migrate_config_str(&contents)? {
+            Some(migration) => migration.contents,
+            None => contents,
+        };
+        Self::parse_contents(path, &contents)
+    }
+
+    fn parse_contents(path: &Path, contents: &str) -> AgeResult<Self> {
+        // Surface the first hard problem with its position; unknown keys stay non-fatal
+        if let Some(issue) = super::config_check::check_config_str(&contents)
+            .into_iter()
+            .find(|issue| issue.is_fatal())
+        {
+            return Err(AgeError::ConfigurationError {
+                parameter: if issue.key.is_empty() {
+                    "config_file".to_string()
+                } else {
+                    issue.key.clone()
+                },
+                value: path.display().to_string(),
+                reason: issue.to_string(),
+            });
+        }
 
         let file: AgeConfigFile =
             toml::from_str(&contents).map_err(|e| AgeError::ConfigurationError {
@@ -498,12 +1147,170 @@ impl AgeConfig {
             }
         }
 
+        if let Some(chain) = file.adapter {
+            config.adapter_chain = chain;
+        }
+
+        if let Some(profile) = file.profile {
+            config.profile = Some(crate::core::PerformanceProfile::parse(&profile)?);
+        }
+        config.progress = file.progress;
+
+        if let Some(traversal) = file.traversal {
+            if let Some(hardlinks) = traversal.hardlinks {
+                config.hardlink_policy = HardlinkPolicy::parse(&hardlinks)?;
+            }
+            if let Some(order) = traversal.order {
+                config.listing_order = ListingOrder::parse(&order)?;
+            }
+        }
+
+        if let Some(safety) = file.safety {
+            if let Some(denylist) = safety.denylist {
+                config.risky_path_denylist = denylist;
+            }
+            if let Some(escrow) = safety.escrow_recipient {
+                config.escrow_recipient = Some(escrow);
+            }
+        }
+
+        if let Some(admin) = file.admin {
+            if let Some(hash) = admin.token_hash {
+                config.set_key("admin_token_hash", &hash)?;
+            }
+            if let Some(operations) = admin.operations {
+                config.set_key("admin_operations", &operations.join(","))?;
+            }
+        }
+
+        if let Some(passphrase_cfg) = file.passphrase {
+            if let Some(attempts) = passphrase_cfg.confirm_attempts {
+                config.passphrase_confirm_attempts = attempts;
+            }
+            if let Some(min_length) = passphrase_cfg.min_length {
+                config.passphrase_min_length = min_length;
+            }
+            if let Some(meter) = passphrase_cfg.strength_meter {
+                config.passphrase_strength_meter = meter;
+            }
+        }
+
         if let Some(streaming_cfg) = file.streaming {
             if let Some(strategy) = streaming_cfg.strategy {
                 config.streaming_strategy = Some(strategy);
             }
         }
 
+        if let Some(providers) = file.identity_providers {
+            config.identity_providers = providers;
+        }
+
+        if let Some(scheduling_cfg) = file.scheduling {
+            if let Some(rate) = scheduling_cfg.max_throughput {
+                config.max_throughput = Some(crate::core::scheduling::parse_byte_rate(&rate)?);
+            }
+            config.process_nice = scheduling_cfg.nice;
+            if let Some(ionice) = scheduling_cfg.ionice {
+                config.io_priority = Some(crate::core::IoPriority::parse(&ionice)?);
+            }
+        }
+
+        if let Some(limits_cfg) = file.limits {
+            if let Some(budget) = limits_cfg.memory_budget {
+                config.memory_budget = Some(crate::core::scheduling::parse_byte_size(&budget)?);
+            }
+            if let Some(threshold) = limits_cfg.armor_threshold {
+                config.set_key("armor_threshold", &threshold)?;
+            }
+            if let Some(oversize) = limits_cfg.armor_oversize {
+                config.armor_oversize = crate::core::ArmorPolicy::parse(&oversize)?;
+            }
+            if let Some(limit) = limits_cfg.max_processes {
+                config.set_key("max_processes", &limit.to_string())?;
+            }
+            if let Some(secs) = limits_cfg.process_timeout {
+                config.process_timeout = (secs > 0).then_some(Duration::from_secs(secs));
+            }
+            if let Some(sandbox) = limits_cfg.sandbox {
+                config.process_sandbox = crate::adp::SandboxMode::parse(&sandbox)?;
+            }
+        }
+
+        if let Some(permissions_cfg) = file.permissions {
+            if let Some(mode) = permissions_cfg.ciphertext_mode {
+                config.ciphertext_mode = Some(crate::core::permissions::parse_mode(&mode)?);
+            }
+            if let Some(mode) = permissions_cfg.plaintext_mode {
+                config.plaintext_mode = Some(crate::core::permissions::parse_mode(&mode)?);
+            }
+            if let Some(preserve) = permissions_cfg.preserve_ownership {
+                config.preserve_ownership = preserve;
+            }
+        }
+
+        if let Some(metadata_cfg) = file.metadata {
+            if let Some(sidecar) = metadata_cfg.sidecar {
+                config.meta_sidecar = sidecar;
+            }
+            if let Some(checksums) = metadata_cfg.checksums {
+                config.checksum_db = checksums;
+            }
+        }
+
+        if let Some(quarantine_cfg) = file.quarantine {
+            if let Some(dir) = quarantine_cfg.directory {
+                config.quarantine_directory = Some(dir);
+            }
+            if let Some(retention) = quarantine_cfg.retention {
+                config.quarantine_retention = parse_retention_policy(&retention)?;
+            }
+        }
+
+        if let Some(binaries_cfg) = file.binaries {
+            if let Some(digest) = binaries_cfg.age_sha256 {
+                config.age_binary_sha256 = Some(crate::adp::pin::parse_sha256(
+                    "binaries.age_sha256",
+                    &digest,
+                )?);
+            }
+            if let Some(digest) = binaries_cfg.age_keygen_sha256 {
+                config.age_keygen_binary_sha256 = Some(crate::adp::pin::parse_sha256(
+                    "binaries.age_keygen_sha256",
+                    &digest,
+                )?);
+            }
+            if let Some(policy) = binaries_cfg.pin_policy {
+                config.binary_pin_policy = crate::adp::PinPolicy::parse(&policy)?;
+            }
+        }
+
+        if let Some(audit_cfg) = file.audit {
+            if let Some(log_path) = audit_cfg.log_path {
+                config.audit_log_path = Some(log_path);
+            }
+            if let Some(key_file) = audit_cfg.signing_key_file {
+                config.audit_signing_key_file = Some(key_file);
+            }
+            if let Some(outputs) = audit_cfg.outputs {
+                config.audit_outputs = outputs;
+            }
+            if let Some(limit) = audit_cfg.unlock_limit {
+                config.audit_unlock_limit = (limit > 0).then_some(limit);
+            }
+            if let Some(window) = audit_cfg.unlock_window {
+                config.audit_unlock_window = super::mtime::parse_duration(&window)
+                    .filter(|window| !window.is_zero())
+                    .ok_or_else(|| AgeError::ConfigurationError {
+                        parameter: "audit.unlock_window".to_string(),
+                        value: window.clone(),
+                        reason: "Expected a duration such as 10m or 1h".to_string(),
+                    })?;
+            }
+            if let Some(hours) = audit_cfg.unlock_hours {
+                config.audit_unlock_hours = Some(crate::audit::UnlockHours::parse(&hours)?);
+            }
+        }
+
         config.validate()?;
         Ok(config)
     }
@@ -601,10 +1408,18 @@ impl Default for AgeConfig {
             max_passphrase_length: 1024,
             operation_timeout: Duration::from_secs(120),
             age_binary_path: None,
+            age_binary_sha256: None,
+            age_keygen_binary_sha256: None,
+            binary_pin_policy: crate::adp::PinPolicy::default(),
             script_binary_path: None,
             expect_binary_path: None,
             audit_logging: true,
             audit_log_path: None,
+            audit_signing_key_file: None,
+            audit_outputs: Vec::new(),
+            audit_unlock_limit: None,
+            audit_unlock_window: Duration::from_secs(600),
+            audit_unlock_hours: None,
             security_validation: true,
             health_checks: true,
             max_retries: 2,
@@ -616,6 +1431,19 @@ impl Default for AgeConfig {
             backup_directory: None,
             backup_retention: RetentionPolicyConfig::default(),
             streaming_strategy: None,
+            adapter_chain: Vec::new(),
+            hardlink_policy: HardlinkPolicy::default(),
+            listing_order: ListingOrder::default(),
+            risky_path_denylist: Vec::new(),
+            escrow_recipient: None,
+            admin_token_hash: None,
+            admin_operations: super::roles::GATEABLE_OPERATIONS
+                .iter()
+                .map(|op| op.to_string())
+                .collect(),
+            passphrase_confirm_attempts: 3,
+            passphrase_min_length: 8,
+            passphrase_strength_meter: false,
             telemetry_format: TelemetryFormat::default(),
             recipient_groups: std::collections::HashMap::new(),
             padlock_extension_support: true,
@@ -624,14 +1452,51 @@ impl Default for AgeConfig {
                 "age".to_string(),
                 "padlock".to_string(),
             ],
+            identity_providers: std::collections::HashMap::new(),
+            max_throughput: None,
+            process_nice: None,
+            io_priority: None,
+            memory_budget: None,
+            profile: None,
+            progress: None,
+            armor_threshold: Some(crate::core::armor::DEFAULT_ARMOR_THRESHOLD),
+            armor_oversize: crate::core::ArmorPolicy::default(),
+            max_processes: None,
+            process_timeout: None,
+            process_sandbox: crate::adp::SandboxMode::default(),
+            ciphertext_mode: None,
+            plaintext_mode: None,
+            preserve_ownership: false,
+            meta_sidecar: false,
+            checksum_db: false,
+            quarantine_directory: None,
+            quarantine_retention: RetentionPolicyConfig::KeepDays(7),
+            env_overrides: Vec::new(),
         }
     }
 }
 
 #[derive(Default, Deserialize)]
-struct AgeConfigFile {
+pub(super) struct AgeConfigFile {
+    #[allow(dead_code)] // read by `config_migrate`, kept here so its type is checked
+    version: Option<u32>,
+    adapter: Option<Vec<String>>,
+    profile: Option<String>,
+    progress: Option<bool>,
     backup: Option<BackupConfigSection>,
     streaming: Option<StreamingConfigSection>,
+    traversal: Option<TraversalConfigSection>,
+    safety: Option<SafetyConfigSection>,
+    admin: Option<AdminConfigSection>,
+    passphrase: Option<PassphraseConfigSection>,
+    identity_providers: Option<std::collections::HashMap<String, String>>,
+    scheduling: Option<SchedulingConfigSection>,
+    limits: Option<LimitsConfigSection>,
+    permissions: Option<PermissionsConfigSection>,
+    metadata: Option<MetadataConfigSection>,
+    quarantine: Option<QuarantineConfigSection>,
+    audit: Option<AuditConfigSection>,
+    binaries: Option<BinariesConfigSection>,
 }
 
 #[derive(Default, Deserialize)]
@@ -641,11 +1506,578 @@ struct BackupConfigSection {
     retention: Option<String>,
 }
 
+#[derive(Default, Deserialize)]
+struct QuarantineConfigSection {
+    directory: Option<String>,
+    retention: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct BinariesConfigSection {
+    age_sha256: Option<String>,
+    age_keygen_sha256: Option<String>,
+    pin_policy: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct AuditConfigSection {
+    log_path: Option<String>,
+    signing_key_file: Option<String>,
+    outputs: Option<Vec<String>>,
+    unlock_limit: Option<usize>,
+    unlock_window: Option<String>,
+    unlock_hours: Option<String>,
+}
+
 #[derive(Default, Deserialize)]
 struct StreamingConfigSection {
     strategy: Option<String>,
 }
 
+#[derive(Default, Deserialize)]
+struct TraversalConfigSection {
+    hardlinks: Option<String>,
+    order: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct SafetyConfigSection {
+    denylist: Option<Vec<String>>,
+    escrow_recipient: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct AdminConfigSection {
+    token_hash: Option<String>,
+    operations: Option<Vec<String>>,
+}
+
+#[derive(Default, Deserialize)]
+struct PassphraseConfigSection {
+    confirm_attempts: Option<u32>,
+    min_length: Option<usize>,
+    strength_meter: Option<bool>,
+}
+
+#[derive(Default, Deserialize)]
+struct LimitsConfigSection {
+    memory_budget: Option<String>,
+    armor_threshold: Option<String>,
+    armor_oversize: Option<String>,
+    max_processes: Option<usize>,
+    process_timeout: Option<u64>,
+    sandbox: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct PermissionsConfigSection {
+    ciphertext_mode: Option<String>,
+    plaintext_mode: Option<String>,
+    preserve_ownership: Option<bool>,
+}
+
+#[derive(Default, Deserialize)]
+struct MetadataConfigSection {
+    sidecar: Option<bool>,
+    checksums: Option<bool>,
+}
+
+#[derive(Default, Deserialize)]
+struct SchedulingConfigSection {
+    max_throughput: Option<String>,
+    nice: Option<i32>,
+    ionice: Option<String>,
+}
+
+/// Documentation for one config key
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigKeyDoc {
+    /// Key name used by `CAGE_*` overrides (see `ENV_OVERRIDES`)
+    pub key: &'static str,
+    /// Dotted location in `config.toml`; a name without a dot is a top-level value
+    pub file_key: Option<&'static str>,
+    pub summary: &'static str,
+    pub values: &'static str,
+    pub default: &'static str,
+}
+
+impl ConfigKeyDoc {
+    /// `CAGE_*` variable overriding this key, if any
+    pub fn env_var(&self) -> Option<&'static str> {
+        ENV_OVERRIDES
+            .iter()
+            .find(|(_, key)| *key == self.key)
+            .map(|(variable, _)| *variable)
+    }
+
+    /// Look up a key by its name, its dotted file location or its `CAGE_*` variable
+    pub fn find(name: &str) -> Option<&'static ConfigKeyDoc> {
+        let name = name.trim();
+        CONFIG_SCHEMA.iter().find(|doc| {
+            doc.key == name
+                || doc.file_key == Some(name)
+                || doc
+                    .env_var()
+                    .is_some_and(|var| var.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// Every documented config key; `cage config explain` and `config_check` read this list
+pub const CONFIG_SCHEMA: &[ConfigKeyDoc] = &[
+    ConfigKeyDoc {
+        key: "output_format",
+        file_key: None,
+        summary: "Default ciphertext encoding",
+        values: "binary, ascii",
+        default: "binary",
+    },
+    ConfigKeyDoc {
+        key: "tty_method",
+        file_key: None,
+        summary: "How passphrase prompts of the age binary are automated",
+        values: "script, expect, auto",
+        default: "auto",
+    },
+    ConfigKeyDoc {
+        key: "security_level",
+        file_key: None,
+        summary: "Depth of input validation before operations",
+        values: "basic, standard, paranoid",
+        default: "standard",
+    },
+    ConfigKeyDoc {
+        key: "operation_timeout",
+        file_key: None,
+        summary: "Seconds a single operation may run",
+        values: "1-3600",
+        default: "120",
+    },
+    ConfigKeyDoc {
+        key: "max_retries",
+        file_key: None,
+        summary: "Retry attempts for failed age invocations",
+        values: "number",
+        default: "2",
+    },
+    ConfigKeyDoc {
+        key: "age_binary_path",
+        file_key: None,
+        summary: "Path to the age binary",
+        values: "path",
+        default: "found on PATH",
+    },
+    ConfigKeyDoc {
+        key: "age_binary_sha256",
+        file_key: Some("binaries.age_sha256"),
+        summary: "Pinned SHA-256 of the age binary on PATH",
+        values: "64 hex digits",
+        default: "not pinned",
+    },
+    ConfigKeyDoc {
+        key: "age_keygen_binary_sha256",
+        file_key: Some("binaries.age_keygen_sha256"),
+        summary: "Pinned SHA-256 of the age-keygen binary on PATH",
+        values: "64 hex digits",
+        default: "not pinned",
+    },
+    ConfigKeyDoc {
+        key: "binary_pin_policy",
+        file_key: Some("binaries.pin_policy"),
+        summary: "What a binary that does not match its pin does",
+        values: "refuse, warn",
+        default: "refuse",
+    },
+    ConfigKeyDoc {
+        key: "temp_dir_override",
+        file_key: None,
+        summary: "Directory for temporary files",
+        values: "path",
+        default: "system temp dir",
+    },
+    ConfigKeyDoc {
+        key: "adapter",
+        file_key: Some("adapter"),
+        summary: "Adapters tried in order at startup",
+        values: "list of library, shell-v2, shell-v1",
+        default: "factory default",
+    },
+    ConfigKeyDoc {
+        key: "audit_logging",
+        file_key: None,
+        summary: "Write an audit trail of operations",
+        values: "true, false",
+        default: "true",
+    },
+    ConfigKeyDoc {
+        key: "audit_log_path",
+        file_key: Some("audit.log_path"),
+        summary: "File the audit trail is appended to",
+        values: "path",
+        default: "stderr only",
+    },
+    ConfigKeyDoc {
+        key: "audit_signing_key_file",
+        file_key: Some("audit.signing_key_file"),
+        summary: "Key file for chain-signing audit log lines",
+        values: "path",
+        default: "unsigned",
+    },
+    ConfigKeyDoc {
+        key: "audit_outputs",
+        file_key: Some("audit.outputs"),
+        summary: "Extra audit outputs, each in its own format",
+        values: "list of <text|json>:<file> or <text|json>:unix:<socket>",
+        default: "none",
+    },
+    ConfigKeyDoc {
+        key: "audit_unlock_limit",
+        file_key: Some("audit.unlock_limit"),
+        summary: "Decryptions per window above which events are flagged as a burst",
+        values: "number (0 disables)",
+        default: "off",
+    },
+    ConfigKeyDoc {
+        key: "audit_unlock_window",
+        file_key: Some("audit.unlock_window"),
+        summary: "Window the unlock limit is counted over",
+        values: "duration (90s, 10m, 1h)",
+        default: "10m",
+    },
+    ConfigKeyDoc {
+        key: "audit_unlock_hours",
+        file_key: Some("audit.unlock_hours"),
+        summary: "Local hours decryptions are expected in; others are flagged",
+        values: "HH-HH (22-06 wraps midnight)",
+        default: "any time",
+    },
+    ConfigKeyDoc {
+        key: "telemetry_format",
+        file_key: None,
+        summary: "Format of audit events on stderr and the audit log",
+        values: "text, json",
+        default: "text",
+    },
+    ConfigKeyDoc {
+        key: "secure_deletion",
+        file_key: None,
+        summary: "Shred temporary files instead of unlinking them",
+        values: "true, false",
+        default: "true",
+    },
+    ConfigKeyDoc {
+        key: "encrypted_file_extension",
+        file_key: None,
+        summary: "Extension given to encrypted files",
+        values: "extension without the dot",
+        default: "cage",
+    },
+    ConfigKeyDoc {
+        key: "backup_cleanup",
+        file_key: Some("backup.cleanup_on_success"),
+        summary: "Delete backups after successful operations",
+        values: "true, false",
+        default: "true",
+    },
+    ConfigKeyDoc {
+        key: "backup_directory",
+        file_key: Some("backup.directory"),
+        summary: "Directory backups are kept in, mirroring source paths",
+        values: "path",
+        default: "next to the source file",
+    },
+    ConfigKeyDoc {
+        key: "backup_retention",
+        file_key: Some("backup.retention"),
+        summary: "Which backups are kept",
+        values: "keep_all, keep_days:N, keep_last:N, keep_last_and_days:N,D",
+        default: "keep_last:3",
+    },
+    ConfigKeyDoc {
+        key: "streaming_strategy",
+        file_key: Some("streaming.strategy"),
+        summary: "How data is streamed through age",
+        values: "auto, pipe, temp",
+        default: "auto",
+    },
+    ConfigKeyDoc {
+        key: "hardlink_policy",
+        file_key: Some("traversal.hardlinks"),
+        summary: "Handling of hardlinked files during repository locks",
+        values: "relink, report",
+        default: "relink",
+    },
+    ConfigKeyDoc {
+        key: "listing_order",
+        file_key: Some("traversal.order"),
+        summary: "Order of status, verify and result listings",
+        values: "sorted, filesystem",
+        default: "sorted",
+    },
+    ConfigKeyDoc {
+        key: "risky_path_denylist",
+        file_key: Some("safety.denylist"),
+        summary: "Globs refused as recursive or in-place targets without --allow-risky-path",
+        values: "list of globs",
+        default: "none",
+    },
+    ConfigKeyDoc {
+        key: "escrow_recipient",
+        file_key: Some("safety.escrow_recipient"),
+        summary: "Recovery recipient that satisfies --verify-decryptable",
+        values: "age recipient",
+        default: "none",
+    },
+    ConfigKeyDoc {
+        key: "admin_token_hash",
+        file_key: Some("admin.token_hash"),
+        summary: "SHA-256 of the token admin-only operations require (cage config admin-hash)",
+        values: "64 hex digits",
+        default: "unset (no gating)",
+    },
+    ConfigKeyDoc {
+        key: "admin_operations",
+        file_key: Some("admin.operations"),
+        summary: "Operations reserved for admins once admin.token_hash is set",
+        values: "rekey, rotate, reset, recipients import, config seal",
+        default: "all of them",
+    },
+    ConfigKeyDoc {
+        key: "passphrase_confirm_attempts",
+        file_key: Some("passphrase.confirm_attempts"),
+        summary: "Tries at confirming a new interactive passphrase",
+        values: "1-10",
+        default: "3",
+    },
+    ConfigKeyDoc {
+        key: "passphrase_min_length",
+        file_key: Some("passphrase.min_length"),
+        summary: "Shorter new passphrases get a nudge towards a longer one",
+        values: "number",
+        default: "8",
+    },
+    ConfigKeyDoc {
+        key: "passphrase_strength_meter",
+        file_key: Some("passphrase.strength_meter"),
+        summary: "Show a masked strength meter for new passphrases",
+        values: "true, false",
+        default: "false",
+    },
+    ConfigKeyDoc {
+        key: "identity_providers",
+        file_key: Some("identity_providers"),
+        summary: "Named identity provider URIs",
+        values: "table of alias = URI",
+        default: "none",
+    },
+    ConfigKeyDoc {
+        key: "max_throughput",
+        file_key: Some("scheduling.max_throughput"),
+        summary: "Throughput cap for batch and recursive operations",
+        values: "rate, e.g. 50M",
+        default: "unlimited",
+    },
+    ConfigKeyDoc {
+        key: "process_nice",
+        file_key: Some("scheduling.nice"),
+        summary: "Nice value applied before operations",
+        values: "-20..19",
+        default: "unchanged",
+    },
+    ConfigKeyDoc {
+        key: "io_priority",
+        file_key: Some("scheduling.ionice"),
+        summary: "I/O scheduling class applied before operations",
+        values: "idle, best-effort[:0-7]",
+        default: "unchanged",
+    },
+    ConfigKeyDoc {
+        key: "memory_budget",
+        file_key: Some("limits.memory_budget"),
+        summary: "Cap on in-flight buffer memory across streams and workers",
+        values: "size, e.g. 256M",
+        default: "unlimited",
+    },
+    ConfigKeyDoc {
+        key: "profile",
+        file_key: Some("profile"),
+        summary: "Performance preset filling unset limits, streaming and progress knobs",
+        values: "laptop, server, io-limited",
+        default: "(unset)",
+    },
+    ConfigKeyDoc {
+        key: "progress",
+        file_key: Some("progress"),
+        summary: "Show progress without --progress",
+        values: "true, false",
+        default: "false",
+    },
+    ConfigKeyDoc {
+        key: "armor_threshold",
+        file_key: Some("limits.armor_threshold"),
+        summary: "Input size from which ASCII armor is flagged",
+        values: "size, or off",
+        default: "1G",
+    },
+    ConfigKeyDoc {
+        key: "armor_oversize",
+        file_key: Some("limits.armor_oversize"),
+        summary: "What happens to armor requests over armor_threshold",
+        values: "warn, refuse",
+        default: "warn",
+    },
+    ConfigKeyDoc {
+        key: "max_processes",
+        file_key: Some("limits.max_processes"),
+        summary: "Concurrently running age processes",
+        values: "number >= 1",
+        default: "8",
+    },
+    ConfigKeyDoc {
+        key: "process_timeout",
+        file_key: Some("limits.process_timeout"),
+        summary: "Seconds before a running age process is killed",
+        values: "seconds, 0 disables",
+        default: "0",
+    },
+    ConfigKeyDoc {
+        key: "process_sandbox",
+        file_key: Some("limits.sandbox"),
+        summary: "Confinement of spawned age processes",
+        values: "off, env (cleared environment), strict (env + no network, Linux)",
+        default: "off",
+    },
+    ConfigKeyDoc {
+        key: "ciphertext_mode",
+        file_key: Some("permissions.ciphertext_mode"),
+        summary: "Mode for created ciphertext",
+        values: "octal mode, e.g. 0644",
+        default: "umask",
+    },
+    ConfigKeyDoc {
+        key: "plaintext_mode",
+        file_key: Some("permissions.plaintext_mode"),
+        summary: "Mode for created plaintext",
+        values: "octal mode, e.g. 0600",
+        default: "umask",
+    },
+    ConfigKeyDoc {
+        key: "preserve_ownership",
+        file_key: Some("permissions.preserve_ownership"),
+        summary: "Outputs and backups keep the source owner (as root)",
+        values: "true, false",
+        default: "false",
+    },
+    ConfigKeyDoc {
+        key: "meta_sidecar",
+        file_key: Some("metadata.sidecar"),
+        summary: "Write a .meta sidecar next to each ciphertext",
+        values: "true, false",
+        default: "false",
+    },
+    ConfigKeyDoc {
+        key: "checksum_db",
+        file_key: Some("metadata.checksums"),
+        summary: "Record plaintext/ciphertext hashes in .cage/checksums.json",
+        values: "true, false",
+        default: "false",
+    },
+    ConfigKeyDoc {
+        key: "quarantine_directory",
+        file_key: Some("quarantine.directory"),
+        summary: "Move removed files here instead of unlinking them",
+        values: "path",
+        default: "disabled",
+    },
+    ConfigKeyDoc {
+        key: "quarantine_retention",
+        file_key: Some("quarantine.retention"),
+        summary: "Which quarantined files are kept",
+        values: "keep_all, keep_days:N, keep_last:N, keep_last_and_days:N,D",
+        default: "keep_days:7",
+    },
+];
+
+/// Layer a config value comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File(PathBuf),
+    Env(String),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File(path) => write!(f, "config file {}", path.display()),
+            ConfigSource::Env(variable) => write!(f, "environment ({})", variable),
+        }
+    }
+}
+
+/// Environment variables overriding config keys, as (variable, key)
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("CAGE_OUTPUT_FORMAT", "output_format"),
+    ("CAGE_TTY_METHOD", "tty_method"),
+    ("CAGE_SECURITY_LEVEL", "security_level"),
+    ("CAGE_OPERATION_TIMEOUT", "operation_timeout"),
+    ("CAGE_MAX_RETRIES", "max_retries"),
+    ("CAGE_AGE_BINARY", "age_binary_path"),
+    ("CAGE_AGE_SHA256", "age_binary_sha256"),
+    ("CAGE_AGE_KEYGEN_SHA256", "age_keygen_binary_sha256"),
+    ("CAGE_BINARY_PIN_POLICY", "binary_pin_policy"),
+    ("CAGE_TEMP_DIR", "temp_dir_override"),
+    ("CAGE_AUDIT_LOGGING", "audit_logging"),
+    ("CAGE_AUDIT_LOG", "audit_log_path"),
+    ("CAGE_AUDIT_KEY_FILE", "audit_signing_key_file"),
+    ("CAGE_AUDIT_OUTPUTS", "audit_outputs"),
+    ("CAGE_AUDIT_UNLOCK_LIMIT", "audit_unlock_limit"),
+    ("CAGE_AUDIT_UNLOCK_WINDOW", "audit_unlock_window"),
+    ("CAGE_AUDIT_UNLOCK_HOURS", "audit_unlock_hours"),
+    ("CAGE_TELEMETRY_FORMAT", "telemetry_format"),
+    ("CAGE_SECURE_DELETION", "secure_deletion"),
+    ("CAGE_EXTENSION", "encrypted_file_extension"),
+    ("CAGE_BACKUP_CLEANUP", "backup_cleanup"),
+    ("CAGE_BACKUP_DIR", "backup_directory"),
+    ("CAGE_BACKUP_RETENTION", "backup_retention"),
+    ("CAGE_STREAMING_STRATEGY", "streaming_strategy"),
+    ("CAGE_ADAPTER", "adapter"),
+    ("CAGE_HARDLINKS", "hardlink_policy"),
+    ("CAGE_LISTING_ORDER", "listing_order"),
+    ("CAGE_RISKY_PATHS", "risky_path_denylist"),
+    ("CAGE_ESCROW_RECIPIENT", "escrow_recipient"),
+    ("CAGE_PASSPHRASE_ATTEMPTS", "passphrase_confirm_attempts"),
+    ("CAGE_PASSPHRASE_MIN_LENGTH", "passphrase_min_length"),
+    ("CAGE_PASSPHRASE_METER", "passphrase_strength_meter"),
+    ("CAGE_MAX_THROUGHPUT", "max_throughput"),
+    ("CAGE_NICE", "process_nice"),
+    ("CAGE_IONICE", "io_priority"),
+    ("CAGE_MEMORY_BUDGET", "memory_budget"),
+    ("CAGE_PROFILE", "profile"),
+    ("CAGE_PROGRESS", "progress"),
+    ("CAGE_ARMOR_THRESHOLD", "armor_threshold"),
+    ("CAGE_ARMOR_OVERSIZE", "armor_oversize"),
+    ("CAGE_MAX_PROCESSES", "max_processes"),
+    ("CAGE_PROCESS_TIMEOUT", "process_timeout"),
+    ("CAGE_PROCESS_SANDBOX", "process_sandbox"),
+    ("CAGE_CIPHERTEXT_MODE", "ciphertext_mode"),
+    ("CAGE_PLAINTEXT_MODE", "plaintext_mode"),
+    ("CAGE_PRESERVE_OWNERSHIP", "preserve_ownership"),
+    ("CAGE_META_SIDECAR", "meta_sidecar"),
+    ("CAGE_CHECKSUM_DB", "checksum_db"),
+    ("CAGE_QUARANTINE_DIR", "quarantine_directory"),
+    ("CAGE_QUARANTINE_RETENTION", "quarantine_retention"),
+];
+
+fn read_config_file(path: &Path) -> AgeResult<String> {
+    fs::read_to_string(path).map_err(|e| AgeError::ConfigurationError {
+        parameter: "config_file".to_string(),
+        value: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
 fn default_config_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
@@ -670,7 +2102,19 @@ fn default_config_paths() -> Vec<PathBuf> {
     paths
 }
 
-fn parse_retention_policy(value: &str) -> AgeResult<RetentionPolicyConfig> {
+/// Config string form of a retention policy, as accepted by `parse_retention_policy`
+pub(super) fn format_retention_policy(policy: &RetentionPolicyConfig) -> String {
+    match policy {
+        RetentionPolicyConfig::KeepAll => "keep_all".to_string(),
+        RetentionPolicyConfig::KeepDays(days) => format!("keep_days:{}", days),
+        RetentionPolicyConfig::KeepLast(count) => format!("keep_last:{}", count),
+        RetentionPolicyConfig::KeepLastAndDays { last, days } => {
+            format!("keep_last_and_days:{},{}", last, days)
+        }
+    }
+}
+
+pub(super) fn parse_retention_policy(value: &str) -> AgeResult<RetentionPolicyConfig> {
     let trimmed = value.trim();
     let lower = trimmed.to_lowercase();
 
@@ -786,6 +2230,40 @@ mod tests {
         assert!(bad_config.validate().is_err());
     }
 
+    #[test]
+    fn test_env_overrides_are_applied_and_recorded() {
+        let env: std::collections::HashMap<&str, &str> = [
+            ("CAGE_EXTENSION", ".sealed"),
+            ("CAGE_BACKUP_RETENTION", "keep_last:9"),
+            ("CAGE_TELEMETRY_FORMAT", "json"),
+            ("CAGE_AUDIT_LOG", "/var/log/cage.log"),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut config = AgeConfig::default();
+        config
+            .apply_overrides_from(|name| env.get(name).map(|v| v.to_string()))
+            .unwrap();
+
+        assert_eq!(config.encrypted_file_extension, "sealed");
+        assert!(matches!(
+            config.backup_retention,
+            RetentionPolicyConfig::KeepLast(9)
+        ));
+        assert_eq!(config.telemetry_format, TelemetryFormat::Json);
+        assert_eq!(
+            config.env_override_for("audit_log_path"),
+            Some("CAGE_AUDIT_LOG")
+        );
+        assert_eq!(config.env_override_for("backup_directory"), None);
+
+        let err = AgeConfig::default()
+            .apply_overrides_from(|name| (name == "CAGE_NICE").then(|| "99".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("nice") || err.to_string().contains("CAGE_NICE"));
+    }
+
     #[test]
     fn test_config_builders() {
         let config = AgeConfig::production()
@@ -856,4 +2334,194 @@ mod tests {
         ));
         assert_eq!(config.streaming_strategy.as_deref(), Some("pipe"));
     }
+
+    #[test]
+    fn test_load_traversal_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[traversal]\nhardlinks='report'\norder='filesystem'\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.hardlink_policy, HardlinkPolicy::Report);
+        assert_eq!(config.listing_order, ListingOrder::Filesystem);
+        assert_eq!(AgeConfig::default().hardlink_policy, HardlinkPolicy::Relink);
+        assert_eq!(AgeConfig::default().listing_order, ListingOrder::Sorted);
+        assert!(HardlinkPolicy::parse("copy").is_err());
+    }
+
+    #[test]
+    fn test_load_passphrase_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[passphrase]\nconfirm_attempts=5\nmin_length=12\nstrength_meter=true\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.passphrase_confirm_attempts, 5);
+        assert_eq!(config.passphrase_min_length, 12);
+        assert!(config.passphrase_strength_meter);
+
+        std::fs::write(&config_path, "[passphrase]\nconfirm_attempts=0\n").unwrap();
+        let issues = AgeConfig::validate_file(&config_path).unwrap();
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].key, "passphrase.confirm_attempts");
+    }
+
+    #[test]
+    fn test_load_scheduling_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[scheduling]\nmax_throughput='50M'\nnice=10\nionice='idle'\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.max_throughput, Some(50 * 1024 * 1024));
+        assert_eq!(config.process_nice, Some(10));
+        assert_eq!(config.io_priority, Some(crate::core::IoPriority::Idle));
+    }
+
+    #[test]
+    fn test_load_process_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[limits]\nmax_processes=2\nprocess_timeout=300\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.max_processes, Some(2));
+        assert_eq!(config.process_timeout, Some(Duration::from_secs(300)));
+
+        std::fs::write(&config_path, "[limits]\nmax_processes=0\n").unwrap();
+        assert!(AgeConfig::load_from_path(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_load_permissions_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[permissions]\nciphertext_mode='0644'\nplaintext_mode='0600'\npreserve_ownership=true\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.ciphertext_mode, Some(0o644));
+        assert_eq!(config.plaintext_mode, Some(0o600));
+        assert!(config.preserve_ownership);
+    }
+
+    #[test]
+    fn test_load_quarantine_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[quarantine]\ndirectory='/var/tmp/cage-trash'\nretention='keep_last:2'\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(
+            config.quarantine_directory.as_deref(),
+            Some("/var/tmp/cage-trash")
+        );
+        assert!(matches!(
+            config.quarantine_retention,
+            RetentionPolicyConfig::KeepLast(2)
+        ));
+    }
+
+    #[test]
+    fn test_load_armor_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[limits]\narmor_threshold='256M'\narmor_oversize='refuse'\n",
+        )
+        .unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.armor_threshold, Some(256 * 1024 * 1024));
+        assert_eq!(config.armor_oversize, crate::core::ArmorPolicy::Refuse);
+
+        std::fs::write(&config_path, "[limits]\narmor_threshold='off'\n").unwrap();
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert_eq!(config.armor_threshold, None);
+    }
+
+    #[test]
+    fn test_load_metadata_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(&config_path, "[metadata]\nsidecar=true\n").unwrap();
+
+        let config = AgeConfig::load_from_path(&config_path).unwrap();
+        assert!(config.meta_sidecar);
+        assert!(!AgeConfig::default().meta_sidecar);
+    }
+
+    #[test]
+    fn test_schema_documents_every_key() {
+        for (variable, key) in ENV_OVERRIDES {
+            assert!(
+                ConfigKeyDoc::find(key).is_some(),
+                "{} ({}) undocumented",
+                key,
+                variable
+            );
+        }
+        let config = AgeConfig::default();
+        for doc in CONFIG_SCHEMA {
+            assert!(
+                config.effective_value(doc.key).is_some(),
+                "{} has no value",
+                doc.key
+            );
+        }
+    }
+
+    #[test]
+    fn test_value_source_layers() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(&config_path, "[traversal]\norder = \"filesystem\"\n").unwrap();
+
+        let mut config = AgeConfig::load_from_path(&config_path).unwrap();
+        config
+            .apply_overrides_from(|name| (name == "CAGE_MAX_RETRIES").then(|| "5".to_string()))
+            .unwrap();
+
+        let order = ConfigKeyDoc::find("traversal.order").unwrap();
+        assert_eq!(
+            config.effective_value(order.key).as_deref(),
+            Some("filesystem")
+        );
+        assert_eq!(config.value_source(order), ConfigSource::File(config_path));
+
+        let retries = ConfigKeyDoc::find("cage_max_retries").unwrap();
+        assert_eq!(config.effective_value(retries.key).as_deref(), Some("5"));
+        assert_eq!(
+            config.value_source(retries),
+            ConfigSource::Env("CAGE_MAX_RETRIES".to_string())
+        );
+
+        let hardlinks = ConfigKeyDoc::find("hardlink_policy").unwrap();
+        assert_eq!(config.value_source(hardlinks), ConfigSource::Default);
+        assert!(ConfigKeyDoc::find("no_such_key").is_none());
+    }
 }
@@ -0,0 +1,609 @@
+//! Config Check - Schema-level validation of `config.toml` files
+//!
+//! Loading a config stops at the first problem with a bare `ConfigurationError`. This
+//! module walks the whole file instead and reports every syntax error, unknown key, type
+//! mismatch and invalid value with its line/column and, where a near match exists, a
+//! suggested key or value. `cage config validate` prints the result.
+
+use super::config::{parse_retention_policy, AgeConfigFile, CONFIG_SCHEMA};
+use std::fmt;
+
+/// Known sections and their keys, from `CONFIG_SCHEMA` plus the schema `version`.
+/// An empty key list means the section is a free-form string map (or a top-level value).
+fn schema() -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut sections: Vec<(&'static str, Vec<&'static str>)> = vec![("version", Vec::new())];
+    for file_key in CONFIG_SCHEMA.iter().filter_map(|doc| doc.file_key) {
+        let (section, key) = match file_key.split_once('.') {
+            Some((section, key)) => (section, Some(key)),
+            None => (file_key, None),
+        };
+        let index = match sections.iter().position(|(name, _)| *name == section) {
+            Some(index) => index,
+            None => {
+                sections.push((section, Vec::new()));
+                sections.len() - 1
+            }
+        };
+        sections[index].1.extend(key);
+    }
+    sections
+}
+
+/// Accepted `streaming.strategy` values
+const STRATEGIES: &[&str] = &["auto", "pipe", "pipes", "temp", "tempfile"];
+
+/// Category of a config problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigIssueKind {
+    /// File is not valid TOML
+    Syntax,
+    /// Section or key Cage does not recognize (ignored when loading)
+    UnknownKey,
+    /// Value has the wrong TOML type
+    InvalidType,
+    /// Value has the right type but is not accepted
+    InvalidValue,
+}
+
+/// One problem found in a config file
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub kind: ConfigIssueKind,
+    /// 1-based line, 0 when the position is unknown
+    pub line: usize,
+    /// 1-based column, 0 when the position is unknown
+    pub column: usize,
+    /// Dotted key, e.g. `backup.retention`
+    pub key: String,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl ConfigIssue {
+    /// Whether loading the config fails on this issue
+    pub fn is_fatal(&self) -> bool {
+        self.kind != ConfigIssueKind::UnknownKey
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "{}:{}: ", self.line, self.column)?;
+        }
+        if !self.key.is_empty() {
+            write!(f, "{}: ", self.key)?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean `{}`?)", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check config file contents against the schema; an empty result means the file is valid
+pub fn check_config_str(contents: &str) -> Vec<ConfigIssue> {
+    let table: toml::Table = match toml::from_str(contents) {
+        Ok(table) => table,
+        Err(e) => return vec![issue_from_toml(contents, ConfigIssueKind::Syntax, &e)],
+    };
+
+    let mut issues = Vec::new();
+    let schema = schema();
+    let section_names: Vec<&str> = schema.iter().map(|(name, _)| *name).collect();
+
+    for (section, value) in &table {
+        let Some((_, keys)) = schema.iter().find(|(name, _)| name == section) else {
+            issues.push(located(
+                contents,
+                ConfigIssueKind::UnknownKey,
+                section,
+                None,
+                "unknown section".to_string(),
+                suggest(section, &section_names),
+            ));
+            continue;
+        };
+        let (Some(entries), false) = (value.as_table(), keys.is_empty()) else {
+            continue;
+        };
+        for key in entries.keys() {
+            if !keys.contains(&key.as_str()) {
+                issues.push(located(
+                    contents,
+                    ConfigIssueKind::UnknownKey,
+                    section,
+                    Some(key),
+                    "unknown key".to_string(),
+                    suggest(key, keys),
+                ));
+            }
+        }
+    }
+
+    // Types are checked by the real deserializer so messages match what loading reports
+    if let Err(e) = toml::from_str::<AgeConfigFile>(contents) {
+        issues.push(issue_from_toml(contents, ConfigIssueKind::InvalidType, &e));
+        return issues;
+    }
+
+    check_values(contents, &table, &mut issues);
+    issues
+}
+
+fn check_values(contents: &str, table: &toml::Table, issues: &mut Vec<ConfigIssue>) {
+    let string_at = |section: &str, key: &str| {
+        table
+            .get(section)
+            .and_then(|s| s.get(key))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    let mut invalid = |section: &str, key: &str, message: String, suggestion: Option<String>| {
+        issues.push(located(
+            contents,
+            ConfigIssueKind::InvalidValue,
+            section,
+            Some(key),
+            message,
+            suggestion,
+        ));
+    };
+
+    for section in ["backup", "quarantine"] {
+        if let Some(value) = string_at(section, "retention") {
+            if let Err(e) = parse_retention_policy(&value) {
+                invalid(section, "retention", reason(e), None);
+            }
+        }
+    }
+
+    if let Some(value) = string_at("streaming", "strategy") {
+        let lower = value.to_lowercase();
+        if !STRATEGIES.contains(&lower.as_str()) {
+            invalid(
+                "streaming",
+                "strategy",
+                format!(
+                    "invalid strategy '{}', expected one of: {}",
+                    value,
+                    STRATEGIES.join(", ")
+                ),
+                suggest(&lower, STRATEGIES),
+            );
+        }
+    }
+
+    if let Some(value) = string_at("traversal", "hardlinks") {
+        if let Err(e) = crate::core::config::HardlinkPolicy::parse(&value) {
+            invalid(
+                "traversal",
+                "hardlinks",
+                reason(e),
+                suggest(&value.to_lowercase(), &["relink", "report"]),
+            );
+        }
+    }
+    if let Some(value) = string_at("traversal", "order") {
+        if let Err(e) = crate::core::config::ListingOrder::parse(&value) {
+            invalid(
+                "traversal",
+                "order",
+                reason(e),
+                suggest(&value.to_lowercase(), &["sorted", "filesystem"]),
+            );
+        }
+    }
+
+    if let Some(denylist) = table
+        .get("safety")
+        .and_then(|s| s.get("denylist"))
+        .and_then(|v| v.as_array())
+    {
+        for entry in denylist.iter().filter_map(|v| v.as_str()) {
+            if let Err(e) = globset::Glob::new(entry) {
+                invalid(
+                    "safety",
+                    "denylist",
+                    format!("invalid glob '{}': {}", entry, e),
+                    None,
+                );
+            }
+        }
+    }
+
+    if let Some(value) = string_at("admin", "token_hash") {
+        if let Err(e) = crate::core::roles::parse_token_hash(&value) {
+            invalid("admin", "token_hash", reason(e), None);
+        }
+    }
+    if let Some(operations) = table
+        .get("admin")
+        .and_then(|s| s.get("operations"))
+        .and_then(|v| v.as_array())
+    {
+        let gateable = crate::core::roles::GATEABLE_OPERATIONS;
+        for operation in operations.iter().filter_map(|v| v.as_str()) {
+            if !gateable.contains(&operation) {
+                invalid(
+                    "admin",
+                    "operations",
+                    format!(
+                        "unknown operation '{}', expected one of: {}",
+                        operation,
+                        gateable.join(", ")
+                    ),
+                    suggest(operation, gateable),
+                );
+            }
+        }
+    }
+
+    if let Some(outputs) = table
+        .get("audit")
+        .and_then(|s| s.get("outputs"))
+        .and_then(|v| v.as_array())
+    {
+        for spec in outputs.iter().filter_map(|v| v.as_str()) {
+            if let Err(e) = crate::audit::OutputSpec::parse(spec) {
+                invalid(
+                    "audit",
+                    "outputs",
+                    format!("'{}': {}", spec, reason(e)),
+                    None,
+                );
+            }
+        }
+    }
+    if let Some(value) = string_at("audit", "unlock_window") {
+        if crate::core::parse_duration(&value).is_none_or(|window| window.is_zero()) {
+            invalid(
+                "audit",
+                "unlock_window",
+                "expected a duration such as 10m or 1h".to_string(),
+                None,
+            );
+        }
+    }
+    if let Some(value) = string_at("audit", "unlock_hours") {
+        if let Err(e) = crate::audit::UnlockHours::parse(&value) {
+            invalid("audit", "unlock_hours", reason(e), None);
+        }
+    }
+
+    if let Some(attempts) = table
+        .get("passphrase")
+        .and_then(|s| s.get("confirm_attempts"))
+        .and_then(|v| v.as_integer())
+    {
+        if !(1..=10).contains(&attempts) {
+            invalid(
+                "passphrase",
+                "confirm_attempts",
+                "must be between 1 and 10".to_string(),
+                None,
+            );
+        }
+    }
+
+    if let Some(value) = string_at("scheduling", "max_throughput") {
+        if let Err(e) = crate::core::scheduling::parse_byte_rate(&value) {
+            invalid("scheduling", "max_throughput", reason(e), None);
+        }
+    }
+    if let Some(value) = string_at("scheduling", "ionice") {
+        if let Err(e) = crate::core::IoPriority::parse(&value) {
+            invalid("scheduling", "ionice", reason(e), None);
+        }
+    }
+    if let Some(nice) = table
+        .get("scheduling")
+        .and_then(|s| s.get("nice"))
+        .and_then(|v| v.as_integer())
+    {
+        if !(-20..=19).contains(&nice) {
+            invalid(
+                "scheduling",
+                "nice",
+                "must be between -20 and 19".to_string(),
+                None,
+            );
+        }
+    }
+    if let Some(value) = string_at("limits", "memory_budget") {
+        if let Err(e) = crate::core::scheduling::parse_byte_size(&value) {
+            invalid("limits", "memory_budget", reason(e), None);
+        }
+    }
+    if let Some(value) = string_at("limits", "armor_threshold") {
+        if !matches!(value.trim().to_lowercase().as_str(), "off" | "none" | "0") {
+            if let Err(e) = crate::core::scheduling::parse_byte_size(&value) {
+                invalid("limits", "armor_threshold", reason(e), None);
+            }
+        }
+    }
+    for key in ["age_sha256", "age_keygen_sha256"] {
+        if let Some(value) = string_at("binaries", key) {
+            if let Err(e) = crate::adp::pin::parse_sha256(key, &value) {
+                invalid("binaries", key, reason(e), None);
+            }
+        }
+    }
+    if let Some(value) = string_at("binaries", "pin_policy") {
+        if let Err(e) = crate::adp::PinPolicy::parse(&value) {
+            invalid(
+                "binaries",
+                "pin_policy",
+                reason(e),
+                suggest(&value, &["refuse", "warn"]),
+            );
+        }
+    }
+    if let Some(value) = string_at("limits", "sandbox") {
+        if let Err(e) = crate::adp::SandboxMode::parse(&value) {
+            invalid(
+                "limits",
+                "sandbox",
+                reason(e),
+                suggest(&value, &["off", "env", "strict"]),
+            );
+        }
+    }
+    if let Some(value) = string_at("limits", "armor_oversize") {
+        if let Err(e) = crate::core::ArmorPolicy::parse(&value) {
+            invalid(
+                "limits",
+                "armor_oversize",
+                reason(e),
+                suggest(&value, &["warn", "refuse"]),
+            );
+        }
+    }
+    for key in ["ciphertext_mode", "plaintext_mode"] {
+        if let Some(value) = string_at("permissions", key) {
+            if let Err(e) = crate::core::permissions::parse_mode(&value) {
+                invalid("permissions", key, reason(e), None);
+            }
+        }
+    }
+    if let Some(limit) = table
+        .get("limits")
+        .and_then(|s| s.get("max_processes"))
+        .and_then(|v| v.as_integer())
+    {
+        if limit < 1 {
+            invalid(
+                "limits",
+                "max_processes",
+                "must be at least 1".to_string(),
+                None,
+            );
+        }
+    }
+
+    // `adapter` is a top-level array, so it is located outside any section
+    if let Some(chain) = table.get("adapter").and_then(|v| v.as_array()) {
+        let adapters = crate::adp::v1::AdapterFactory::available_adapters();
+        let (line, column) = locate(contents, "", Some("adapter")).unwrap_or((0, 0));
+        for name in chain.iter().filter_map(|v| v.as_str()) {
+            if !adapters.contains(&name) {
+                issues.push(ConfigIssue {
+                    kind: ConfigIssueKind::InvalidValue,
+                    line,
+                    column,
+                    key: "adapter".to_string(),
+                    message: format!(
+                        "unknown adapter '{}', expected one of: {}",
+                        name,
+                        adapters.join(", ")
+                    ),
+                    suggestion: suggest(name, &adapters),
+                });
+            }
+        }
+    }
+    if let Some(profile) = table.get("profile").and_then(|v| v.as_str()) {
+        if let Err(error) = crate::core::PerformanceProfile::parse(profile) {
+            let (line, column) = locate(contents, "", Some("profile")).unwrap_or((0, 0));
+            issues.push(ConfigIssue {
+                kind: ConfigIssueKind::InvalidValue,
+                line,
+                column,
+                key: "profile".to_string(),
+                message: reason(error),
+                suggestion: suggest(profile, crate::core::PerformanceProfile::NAMES),
+            });
+        }
+    }
+}
+
+fn reason(error: crate::error::AgeError) -> String {
+    match error {
+        crate::error::AgeError::ConfigurationError { reason, .. } => reason,
+        other => other.to_string(),
+    }
+}
+
+fn issue_from_toml(contents: &str, kind: ConfigIssueKind, error: &toml::de::Error) -> ConfigIssue {
+    let (line, column) = error
+        .span()
+        .map(|span| position(contents, span.start))
+        .unwrap_or((0, 0));
+    ConfigIssue {
+        kind,
+        line,
+        column,
+        key: String::new(),
+        message: error.message().to_string(),
+        suggestion: None,
+    }
+}
+
+/// Build an issue positioned at `section.key` (or the section header)
+fn located(
+    contents: &str,
+    kind: ConfigIssueKind,
+    section: &str,
+    key: Option<&str>,
+    message: String,
+    suggestion: Option<String>,
+) -> ConfigIssue {
+    let (line, column) = locate(contents, section, key).unwrap_or((0, 0));
+    ConfigIssue {
+        kind,
+        line,
+        column,
+        key: match key {
+            Some(key) => format!("{}.{}", section, key),
+            None => section.to_string(),
+        },
+        message,
+        suggestion,
+    }
+}
+
+/// 1-based line/column of a byte offset
+fn position(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+/// Find the line defining `[section]` or `key` within it
+fn locate(contents: &str, section: &str, key: Option<&str>) -> Option<(usize, usize)> {
+    let mut current = String::new();
+    for (index, raw) in contents.lines().enumerate() {
+        let indent = raw.len() - raw.trim_start().len();
+        let line = raw.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            current = header
+                .split(']')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            if key.is_none() && current == section {
+                return Some((index + 1, indent + 1));
+            }
+            continue;
+        }
+        let Some(key) = key else { continue };
+        let defines_key = line
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='));
+        if current == section && defines_key {
+            return Some((index + 1, indent + 1));
+        }
+    }
+    None
+}
+
+/// Closest candidate within a small edit distance
+fn suggest(input: &str, candidates: &[&str]) -> Option<String> {
+    let limit = (input.len() / 3).max(2);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_positions_and_suggestions() {
+        let contents = "\
+[backup]
+retension = \"keep_all\"
+retention = \"keep_forever\"
+
+[streaming]
+strategy = \"pipez\"
+
+[audti]
+log_path = \"/tmp/audit.log\"
+";
+        let issues = check_config_str(contents);
+        assert_eq!(issues.len(), 4, "{:?}", issues);
+
+        let key = issues.iter().find(|i| i.key == "backup.retension").unwrap();
+        assert_eq!((key.line, key.column), (2, 1));
+        assert_eq!(key.suggestion.as_deref(), Some("retention"));
+        assert!(!key.is_fatal());
+
+        let retention = issues.iter().find(|i| i.key == "backup.retention").unwrap();
+        assert_eq!(retention.kind, ConfigIssueKind::InvalidValue);
+        assert_eq!(retention.line, 3);
+
+        let strategy = issues
+            .iter()
+            .find(|i| i.key == "streaming.strategy")
+            .unwrap();
+        assert_eq!(strategy.suggestion.as_deref(), Some("pipe"));
+
+        let section = issues.iter().find(|i| i.key == "audti").unwrap();
+        assert_eq!(section.line, 8);
+        assert_eq!(section.suggestion.as_deref(), Some("audit"));
+    }
+
+    #[test]
+    fn test_syntax_and_type_errors() {
+        let syntax = check_config_str("[backup\nretention = 1\n");
+        assert_eq!(syntax[0].kind, ConfigIssueKind::Syntax);
+        assert_eq!(syntax[0].line, 1);
+
+        let typed = check_config_str("[backup]\ncleanup_on_success = \"yes\"\n");
+        assert_eq!(typed.len(), 1);
+        assert_eq!(typed[0].kind, ConfigIssueKind::InvalidType);
+        assert_eq!(typed[0].line, 2);
+
+        assert!(check_config_str("[backup]\nretention = \"keep_last:3\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_adapter_chain_values() {
+        assert!(
+            check_config_str("adapter = [\"library\", \"shell-v2\", \"shell-v1\"]\n").is_empty()
+        );
+
+        let issues = check_config_str("\nadapter = [\"shell-v3\"]\n");
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].key, "adapter");
+        assert_eq!(issues[0].line, 2);
+        assert!(issues[0].suggestion.is_some());
+    }
+
+    #[test]
+    fn test_profile_value() {
+        assert!(check_config_str("profile = \"io-limited\"\nprogress = true\n").is_empty());
+
+        let issues = check_config_str("profile = \"laptp\"\n");
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].key, "profile");
+        assert_eq!(issues[0].suggestion.as_deref(), Some("laptop"));
+    }
+}
@@ -0,0 +1,254 @@
+//! Config Migration - Versioned `config.toml` schema upgrades
+//!
+//! Config files carry a top-level `version`. Files without one predate versioning and
+//! count as version 0. Loading upgrades older files one step at a time to
+//! `CONFIG_VERSION` and rewrites them, keeping the original as `<file>.v<old>.bak`.
+//! Files written by a newer Cage are rejected instead of being half understood.
+//! Migrations edit lines in place so comments and layout survive.
+
+use crate::error::{AgeError, AgeResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config schema version written and understood by this build
+pub const CONFIG_VERSION: u32 = 1;
+
+/// One upgrade step: rewrites contents from version `n` to `n + 1`, noting each change
+type MigrationStep = fn(&str, &mut Vec<String>) -> String;
+
+/// `MIGRATIONS[n]` upgrades version `n` to `n + 1`
+const MIGRATIONS: &[MigrationStep] = &[migrate_v0_to_v1];
+
+/// Upgraded config contents
+#[derive(Debug, Clone)]
+pub struct ConfigMigration {
+    pub from: u32,
+    pub to: u32,
+    pub contents: String,
+    /// Human-readable description of each rewrite
+    pub changes: Vec<String>,
+}
+
+/// Schema version declared by `contents` (0 when absent)
+pub fn config_version(contents: &str) -> AgeResult<u32> {
+    let table: toml::Table =
+        toml::from_str(contents).map_err(|e| invalid_version(e.to_string()))?;
+    version_of(&table)
+}
+
+fn version_of(table: &toml::Table) -> AgeResult<u32> {
+    match table.get("version") {
+        None => Ok(0),
+        Some(value) => value
+            .as_integer()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                invalid_version(format!("Expected a non-negative integer, found {}", value))
+            }),
+    }
+}
+
+/// Upgrade `contents` to `CONFIG_VERSION`; `None` when already current
+///
+/// Unparsable contents are returned as current so the normal load reports the syntax error.
+pub fn migrate_config_str(contents: &str) -> AgeResult<Option<ConfigMigration>> {
+    let Ok(table) = toml::from_str::<toml::Table>(contents) else {
+        return Ok(None);
+    };
+    let from = version_of(&table)?;
+    if from > CONFIG_VERSION {
+        return Err(AgeError::ConfigurationError {
+            parameter: "version".to_string(),
+            value: from.to_string(),
+            reason: format!(
+                "Config was written by a newer Cage (schema v{}); this build supports up to v{}. Upgrade cage to use it",
+                from, CONFIG_VERSION
+            ),
+        });
+    }
+    if from == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    let mut changes = Vec::new();
+    let mut migrated = contents.to_string();
+    for step in &MIGRATIONS[from as usize..] {
+        migrated = step(&migrated, &mut changes);
+    }
+    changes.push(format!("version {} -> {}", from, CONFIG_VERSION));
+    Ok(Some(ConfigMigration {
+        from,
+        to: CONFIG_VERSION,
+        contents: set_version(&migrated, CONFIG_VERSION),
+        changes,
+    }))
+}
+
+/// Back up the original file as `<file>.v<from>.bak` and write the migrated contents
+pub fn persist_migration(path: &Path, migration: &ConfigMigration) -> AgeResult<PathBuf> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".v{}.bak", migration.from));
+    let backup = PathBuf::from(backup);
+    fs::copy(path, &backup)
+        .map_err(|e| AgeError::file_error("backup_config", backup.clone(), e))?;
+    fs::write(path, &migration.contents)
+        .map_err(|e| AgeError::file_error("write_config", path.to_path_buf(), e))?;
+    Ok(backup)
+}
+
+fn invalid_version(reason: String) -> AgeError {
+    AgeError::ConfigurationError {
+        parameter: "version".to_string(),
+        value: String::new(),
+        reason,
+    }
+}
+
+/// Replace the top-level `version` line, or add one before the first table
+fn set_version(contents: &str, version: u32) -> String {
+    let line = format!("version = {}", version);
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let first_section = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    if let Some(existing) = lines[..first_section]
+        .iter_mut()
+        .find(|l| key_value(l).is_some_and(|(key, _)| key == "version"))
+    {
+        *existing = line;
+    } else {
+        lines.insert(first_section, String::new());
+        lines.insert(first_section, line);
+    }
+    let mut joined = lines.join("\n");
+    joined.push('\n');
+    joined
+}
+
+/// Split a `key = value` line into its trimmed key and unquoted value
+fn key_value(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() || key.starts_with('#') || key.starts_with('[') {
+        return None;
+    }
+    Some((key, value.trim().trim_matches(|c| c == '"' || c == '\'')))
+}
+
+/// Rewrite string values of `section.key` lines with `rewrite`
+fn rewrite_values(
+    contents: &str,
+    targets: &[(&str, &str)],
+    rewrite: impl Fn(&str) -> Option<String>,
+    changes: &mut Vec<String>,
+) -> String {
+    let mut section = String::new();
+    let mut out = Vec::new();
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('[') {
+            section = header
+                .split(']')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+        }
+        let replacement = key_value(line)
+            .filter(|(key, _)| targets.contains(&(section.as_str(), *key)))
+            .and_then(|(key, value)| {
+                let new = rewrite(value)?;
+                changes.push(format!("{}.{}: '{}' -> '{}'", section, key, value, new));
+                let indent = &line[..line.len() - line.trim_start().len()];
+                Some(format!("{}{} = \"{}\"", indent, key, new))
+            });
+        out.push(replacement.unwrap_or_else(|| line.to_string()));
+    }
+    let mut joined = out.join("\n");
+    if contents.ends_with('\n') {
+        joined.push('\n');
+    }
+    joined
+}
+
+/// v0 -> v1: hyphenated retention policies and streaming strategy aliases
+fn migrate_v0_to_v1(contents: &str, changes: &mut Vec<String>) -> String {
+    let contents = rewrite_values(
+        contents,
+        &[("backup", "retention"), ("quarantine", "retention")],
+        |value| {
+            let lower = value.to_lowercase();
+            if lower == "keep-all" {
+                return Some("keep_all".to_string());
+            }
+            let (policy, count) = lower.rsplit_once('-')?;
+            count.parse::<u64>().ok()?;
+            match policy {
+                "keep-last" => Some(format!("keep_last:{}", count)),
+                "keep-days" => Some(format!("keep_days:{}", count)),
+                _ => None,
+            }
+        },
+        changes,
+    );
+    rewrite_values(
+        &contents,
+        &[("streaming", "strategy")],
+        |value| match value.to_lowercase().as_str() {
+            "pipes" => Some("pipe".to_string()),
+            "tempfile" => Some("temp".to_string()),
+            _ => None,
+        },
+        changes,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LEGACY: &str = "\
+# my settings
+[backup]
+retention = \"keep-last-5\"
+
+[streaming]
+strategy = 'tempfile'
+";
+
+    #[test]
+    fn test_migrates_legacy_config() {
+        let migration = migrate_config_str(LEGACY).unwrap().unwrap();
+        assert_eq!((migration.from, migration.to), (0, CONFIG_VERSION));
+        assert_eq!(
+            migration.contents,
+            "# my settings\nversion = 1\n\n[backup]\nretention = \"keep_last:5\"\n\n[streaming]\nstrategy = \"temp\"\n"
+        );
+        assert_eq!(migration.changes.len(), 3);
+        assert!(migrate_config_str(&migration.contents).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rejects_newer_and_invalid_versions() {
+        let newer = format!("version = {}\n", CONFIG_VERSION + 1);
+        let err = migrate_config_str(&newer).unwrap_err().to_string();
+        assert!(err.contains("newer Cage"), "{}", err);
+        assert!(config_version("version = -1\n").is_err());
+        assert!(config_version("version = \"1\"\n").is_err());
+    }
+
+    #[test]
+    fn test_persist_keeps_backup() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(&path, LEGACY).unwrap();
+
+        let migration = migrate_config_str(LEGACY).unwrap().unwrap();
+        let backup = persist_migration(&path, &migration).unwrap();
+        assert_eq!(backup, temp.path().join("config.toml.v0.bak"));
+        assert_eq!(fs::read_to_string(backup).unwrap(), LEGACY);
+        assert_eq!(fs::read_to_string(&path).unwrap(), migration.contents);
+    }
+}
@@ -0,0 +1,381 @@
+//! Config Seal - Tamper detection for the config file and recipient groups
+//!
+//! Anyone who can write `config.toml` or `groups.json` can quietly add a recipient to every
+//! future lock. With a seal key configured, cage writes `<file>.seal` holding
+//! `HMAC-SHA256(key, file name || contents)` whenever it rewrites one of these files itself
+//! (`cage recipients ...`, config upgrades), and verifies the seal every time it loads them.
+//! Hand edits are accepted by re-sealing with `cage config seal`. Cage never rewrites a file
+//! whose current contents no longer match its seal, since re-sealing it would launder the
+//! tamper into a valid seal.
+//!
+//! The key never comes from the config it protects. The first of these is used:
+//!
+//! - `CAGE_CONFIG_KEY_FILE` - file holding the key
+//! - `CAGE_CONFIG_KEY_COMMAND` - command printing the key, e.g. an OS keychain lookup
+//!   (`security find-generic-password -w -s cage`, `secret-tool lookup service cage`)
+//! - `CAGE_CONFIG_KEY_IDENTITY` - age identity file the key is derived from
+//!
+//! `CAGE_CONFIG_INTEGRITY` picks what a missing or broken seal does: `warn` (the default
+//! once a key is configured), `fail`, or `off`.
+
+use crate::error::{AgeError, AgeResult};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const KEY_FILE_ENV: &str = "CAGE_CONFIG_KEY_FILE";
+pub const KEY_COMMAND_ENV: &str = "CAGE_CONFIG_KEY_COMMAND";
+pub const KEY_IDENTITY_ENV: &str = "CAGE_CONFIG_KEY_IDENTITY";
+pub const POLICY_ENV: &str = "CAGE_CONFIG_INTEGRITY";
+/// Extension appended to a sealed file's path for its seal
+pub const SEAL_EXTENSION: &str = "seal";
+
+const DOMAIN: &[u8] = b"cage-config-seal/v1\n";
+
+/// Keys printed by `CAGE_CONFIG_KEY_COMMAND`, so a keychain is asked once per process
+static COMMAND_KEYS: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+/// Files already warned about, so repeated config loads do not repeat the warning
+static WARNED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+
+/// What a missing or mismatched seal does at load time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealPolicy {
+    Off,
+    Warn,
+    Fail,
+}
+
+impl SealPolicy {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "fail" => Ok(Self::Fail),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: POLICY_ENV.to_string(),
+                value: value.to_string(),
+                reason: "Valid values: off, warn, fail".to_string(),
+            }),
+        }
+    }
+}
+
+/// State of one file's seal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealStatus {
+    Valid,
+    /// The file exists but has no seal
+    Missing,
+    /// The file changed since it was sealed, or the seal was forged
+    Mismatch,
+}
+
+/// Seal key and policy
+#[derive(Clone)]
+pub struct ConfigSeal {
+    key: Vec<u8>,
+    policy: SealPolicy,
+}
+
+impl std::fmt::Debug for ConfigSeal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the key
+        f.debug_struct("ConfigSeal")
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl ConfigSeal {
+    pub fn new(key: Vec<u8>, policy: SealPolicy) -> AgeResult<Self> {
+        if key.is_empty() {
+            return Err(AgeError::ConfigurationError {
+                parameter: "config_seal_key".to_string(),
+                value: String::new(),
+                reason: "Seal key must not be empty".to_string(),
+            });
+        }
+        Ok(Self { key, policy })
+    }
+
+    /// Seal settings from the environment; `None` when no key is configured or policy is `off`
+    pub fn from_env() -> AgeResult<Option<Self>> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let policy = match var(POLICY_ENV) {
+            Some(value) => SealPolicy::parse(&value)?,
+            None => SealPolicy::Warn,
+        };
+        if policy == SealPolicy::Off {
+            return Ok(None);
+        }
+
+        let key = if let Some(path) = var(KEY_FILE_ENV) {
+            let key = fs::read(&path)
+                .map_err(|e| AgeError::file_error("read_seal_key", PathBuf::from(&path), e))?;
+            key.trim_ascii().to_vec()
+        } else if let Some(command) = var(KEY_COMMAND_ENV) {
+            command_key(&command)?
+        } else if let Some(path) = var(KEY_IDENTITY_ENV) {
+            let identity = fs::read_to_string(&path)
+                .map_err(|e| AgeError::file_error("read_seal_key", PathBuf::from(&path), e))?;
+            identity_key(&identity)
+        } else if policy == SealPolicy::Fail {
+            return Err(AgeError::ConfigurationError {
+                parameter: POLICY_ENV.to_string(),
+                value: "fail".to_string(),
+                reason: format!(
+                    "No seal key configured; set {}, {} or {}",
+                    KEY_FILE_ENV, KEY_COMMAND_ENV, KEY_IDENTITY_ENV
+                ),
+            });
+        } else {
+            return Ok(None);
+        };
+        Self::new(key, policy).map(Some)
+    }
+
+    pub fn policy(&self) -> SealPolicy {
+        self.policy
+    }
+
+    /// `<path>.seal`
+    pub fn seal_path(path: &Path) -> PathBuf {
+        let mut seal = path.as_os_str().to_owned();
+        seal.push(format!(".{}", SEAL_EXTENSION));
+        PathBuf::from(seal)
+    }
+
+    /// Hex tag of `contents` stored as `path`; the file name keeps seals from being swapped
+    pub fn tag(&self, path: &Path, contents: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(DOMAIN);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        mac.update(name.as_bytes());
+        mac.update(b"\n");
+        mac.update(contents);
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+
+    /// Write the seal of `path`'s current contents
+    pub fn seal(&self, path: &Path) -> AgeResult<()> {
+        let contents =
+            fs::read(path).map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        let seal_path = Self::seal_path(path);
+        fs::write(&seal_path, format!("{}\n", self.tag(path, &contents)))
+            .map_err(|e| AgeError::file_error("write_seal", seal_path, e))?;
+        forget_warning(path);
+        Ok(())
+    }
+
+    /// Compare `contents` of `path` against its seal
+    pub fn check(&self, path: &Path, contents: &[u8]) -> AgeResult<SealStatus> {
+        let seal_path = Self::seal_path(path);
+        let stored = match fs::read_to_string(&seal_path) {
+            Ok(stored) => stored,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SealStatus::Missing),
+            Err(e) => return Err(AgeError::file_error("read_seal", seal_path, e)),
+        };
+        let expected = self.tag(path, contents);
+        // Compare without short-circuiting on the first differing byte
+        let stored = stored.trim().as_bytes();
+        let differs = stored.len() != expected.len()
+            || stored
+                .iter()
+                .zip(expected.as_bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                != 0;
+        Ok(if differs {
+            SealStatus::Mismatch
+        } else {
+            SealStatus::Valid
+        })
+    }
+
+    /// Refuse to rewrite `path` when its current contents do not match its seal.
+    ///
+    /// A missing file or one without a seal yet may be written; a mismatch fails under
+    /// every policy.
+    pub fn check_before_rewrite(&self, path: &Path) -> AgeResult<()> {
+        let contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(AgeError::file_error("read", path.to_path_buf(), e)),
+        };
+        match self.check(path, &contents)? {
+            SealStatus::Valid | SealStatus::Missing => Ok(()),
+            SealStatus::Mismatch => Err(AgeError::SecurityValidationFailed {
+                validation_type: "config_seal".to_string(),
+                details: format!(
+                    "{} changed outside cage (seal mismatch); review it and run `cage config \
+                     seal` before cage rewrites it",
+                    path.display()
+                ),
+            }),
+        }
+    }
+
+    /// Apply the policy to `path`'s seal: warn once per file, or fail under `fail`
+    pub fn enforce(&self, path: &Path, contents: &[u8]) -> AgeResult<()> {
+        let problem = match self.check(path, contents)? {
+            SealStatus::Valid => return Ok(()),
+            SealStatus::Missing => "has no seal",
+            SealStatus::Mismatch => "changed outside cage (seal mismatch)",
+        };
+        let details = format!(
+            "{} {}; review it, then run `cage config seal` to accept it",
+            path.display(),
+            problem
+        );
+        if self.policy == SealPolicy::Fail {
+            return Err(AgeError::SecurityValidationFailed {
+                validation_type: "config_seal".to_string(),
+                details,
+            });
+        }
+        if first_warning(path) {
+            tracing::warn!("SECURITY: {}", details);
+        }
+        Ok(())
+    }
+}
+
+/// Check `contents` of `path` against its seal when a seal key is configured
+pub fn verify(path: &Path, contents: &[u8]) -> AgeResult<()> {
+    match ConfigSeal::from_env()? {
+        Some(seal) => seal.enforce(path, contents),
+        None => Ok(()),
+    }
+}
+
+/// Rewrite `path` through `write` and re-seal it, when a seal key is configured.
+///
+/// The file must still match its seal (or have none yet) before `write` runs, so a tampered
+/// file is refused instead of re-sealed.
+pub fn rewrite_sealed<T>(path: &Path, write: impl FnOnce() -> AgeResult<T>) -> AgeResult<T> {
+    let Some(seal) = ConfigSeal::from_env()? else {
+        return write();
+    };
+    seal.check_before_rewrite(path)?;
+    let written = write()?;
+    seal.seal(path)?;
+    Ok(written)
+}
+
+/// Key derived from the secret lines of an age (or SSH) identity file
+fn identity_key(identity: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN);
+    for line in identity.lines().map(str::trim) {
+        if !line.is_empty() && !line.starts_with('#') {
+            hasher.update(line.as_bytes());
+            hasher.update(b"\n");
+        }
+    }
+    hasher.finalize().to_vec()
+}
+
+fn command_key(command: &str) -> AgeResult<Vec<u8>> {
+    let mut cache = COMMAND_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(key) = cache.get(command) {
+        return Ok(key.clone());
+    }
+    let argv = crate::passphrase::split_command_line(command)?;
+    let key = super::identity_provider::run_secret_command(&argv)?
+        .trim()
+        .as_bytes()
+        .to_vec();
+    cache.insert(command.to_string(), key.clone());
+    Ok(key)
+}
+
+fn first_warning(path: &Path) -> bool {
+    let mut warned = WARNED.lock().unwrap_or_else(|e| e.into_inner());
+    warned
+        .get_or_insert_with(HashSet::new)
+        .insert(path.to_path_buf())
+}
+
+fn forget_warning(path: &Path) {
+    let mut warned = WARNED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(warned) = warned.as_mut() {
+        warned.remove(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_detects_edits_and_swaps() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = temp.path().join("config.toml");
+        let groups = temp.path().join("groups.json");
+        fs::write(&config, "[safety]\nescrow_recipient = \"age1escrow\"\n").unwrap();
+        fs::write(&groups, "{}").unwrap();
+
+        let seal = ConfigSeal::new(b"key".to_vec(), SealPolicy::Fail).unwrap();
+        let contents = fs::read(&config).unwrap();
+        assert_eq!(seal.check(&config, &contents).unwrap(), SealStatus::Missing);
+        assert!(seal.enforce(&config, &contents).is_err());
+
+        seal.seal(&config).unwrap();
+        assert_eq!(seal.check(&config, &contents).unwrap(), SealStatus::Valid);
+        assert!(seal.enforce(&config, &contents).is_ok());
+
+        let edited = b"[safety]\nescrow_recipient = \"age1attacker\"\n";
+        assert_eq!(seal.check(&config, edited).unwrap(), SealStatus::Mismatch);
+
+        // A seal is bound to the file name, so it cannot vouch for the other file
+        fs::copy(
+            ConfigSeal::seal_path(&config),
+            ConfigSeal::seal_path(&groups),
+        )
+        .unwrap();
+        assert_eq!(
+            seal.check(&groups, &contents).unwrap(),
+            SealStatus::Mismatch
+        );
+
+        let other = ConfigSeal::new(b"other".to_vec(), SealPolicy::Warn).unwrap();
+        assert_eq!(
+            other.check(&config, &contents).unwrap(),
+            SealStatus::Mismatch
+        );
+        assert!(other.enforce(&config, &contents).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_file_is_not_resealed() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let groups = temp.path().join("groups.json");
+        let seal = ConfigSeal::new(b"key".to_vec(), SealPolicy::Warn).unwrap();
+        assert!(seal.check_before_rewrite(&groups).is_ok());
+
+        fs::write(&groups, "{}").unwrap();
+        assert!(seal.check_before_rewrite(&groups).is_ok());
+        seal.seal(&groups).unwrap();
+        assert!(seal.check_before_rewrite(&groups).is_ok());
+
+        fs::write(&groups, r#"{"ops":["age1attacker"]}"#).unwrap();
+        assert!(matches!(
+            seal.check_before_rewrite(&groups),
+            Err(AgeError::SecurityValidationFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_identity_key_ignores_comments() {
+        let key =
+            identity_key("# created: 2024-01-01\n# public key: age1abc\nAGE-SECRET-KEY-1XYZ\n");
+        let recreated = identity_key("# created: 2025-06-30\nAGE-SECRET-KEY-1XYZ");
+        assert_eq!(key, recreated);
+        assert_ne!(key, identity_key("AGE-SECRET-KEY-1ABC"));
+    }
+}
@@ -0,0 +1,246 @@
+//! Deterministic Encryption - Reproducible ciphertext for golden-file tests
+//!
+//! **Test-only. Never use this for real data.** Every random value age normally draws
+//! from the OS (file key, X25519 ephemeral secret, payload nonce) is taken from an
+//! injected RNG instead, so the same seed, recipients and plaintext always produce the
+//! same bytes. Anyone who knows the seed can decrypt the output.
+//!
+//! The output is a standard age v1 file that any age implementation decrypts. Only
+//! X25519 (`age1...`) recipients are supported; scrypt and SSH stanzas are rejected.
+//! Available with the `deterministic` feature.
+
+use super::header::AgeHeader;
+use super::OutputFormat;
+use crate::error::{AgeError, AgeResult};
+use age::secrecy::ExposeSecret;
+use age_core::format::{FileKey, Stanza};
+use age_core::primitives::{aead_encrypt, hkdf};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use bech32::FromBase32;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand_chacha::rand_core::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::io::{self, Read, Write};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const X25519_LABEL: &[u8] = b"age-encryption.org/v1/X25519";
+const PAYLOAD_LABEL: &[u8] = b"payload";
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Age encryptor drawing all randomness from an injected RNG (test-only)
+pub struct DeterministicEncryptor<R: RngCore + CryptoRng> {
+    rng: R,
+    format: OutputFormat,
+}
+
+impl DeterministicEncryptor<ChaCha20Rng> {
+    /// Seeded ChaCha20 RNG; the usual choice for snapshot tests
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self::with_rng(ChaCha20Rng::from_seed(seed))
+    }
+}
+
+impl<R: RngCore + CryptoRng> DeterministicEncryptor<R> {
+    pub fn with_rng(rng: R) -> Self {
+        Self {
+            rng,
+            format: OutputFormat::Binary,
+        }
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Encrypt `input` to `recipients` (`age1...` keys); returns plaintext bytes read
+    pub fn encrypt(
+        &mut self,
+        recipients: &[String],
+        input: &mut dyn Read,
+        output: &mut dyn Write,
+    ) -> AgeResult<u64> {
+        if recipients.is_empty() {
+            return Err(invalid("At least one recipient is required"));
+        }
+        let public_keys = recipients
+            .iter()
+            .map(|recipient| parse_x25519(recipient))
+            .collect::<AgeResult<Vec<_>>>()?;
+
+        let mut key_bytes = [0u8; 16];
+        self.rng.fill_bytes(&mut key_bytes);
+        let file_key = FileKey::new(Box::new(key_bytes));
+        let stanzas = public_keys
+            .iter()
+            .map(|public| self.wrap_file_key(&file_key, public))
+            .collect();
+        let header = AgeHeader::new(&file_key, stanzas);
+
+        let mut nonce = [0u8; 16];
+        self.rng.fill_bytes(&mut nonce);
+        let payload_key = hkdf(&nonce, PAYLOAD_LABEL, file_key.expose_secret());
+
+        let armor = match self.format {
+            OutputFormat::Binary => age::armor::Format::Binary,
+            OutputFormat::AsciiArmor => age::armor::Format::AsciiArmor,
+        };
+        let mut writer = age::armor::ArmoredWriter::wrap_output(output, armor).map_err(io_error)?;
+        header.write_to(&mut writer).map_err(io_error)?;
+        writer.write_all(&nonce).map_err(io_error)?;
+        let read = write_payload(&payload_key, input, &mut writer)?;
+        writer.finish().map_err(io_error)?;
+        Ok(read)
+    }
+
+    fn wrap_file_key(&mut self, file_key: &FileKey, recipient: &PublicKey) -> Stanza {
+        let mut secret = [0u8; 32];
+        self.rng.fill_bytes(&mut secret);
+        let ephemeral = StaticSecret::from(secret);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let shared = ephemeral.diffie_hellman(recipient);
+
+        let mut salt = [0u8; 64];
+        salt[..32].copy_from_slice(ephemeral_public.as_bytes());
+        salt[32..].copy_from_slice(recipient.as_bytes());
+        let wrap_key = hkdf(&salt, X25519_LABEL, shared.as_bytes());
+
+        Stanza {
+            tag: "X25519".to_string(),
+            args: vec![STANDARD_NO_PAD.encode(ephemeral_public.as_bytes())],
+            body: aead_encrypt(&wrap_key, file_key.expose_secret()),
+        }
+    }
+}
+
+/// age STREAM: 64 KiB chunks, nonce = 11-byte big-endian counter + last-chunk flag
+fn write_payload(key: &[u8; 32], input: &mut dyn Read, output: &mut dyn Write) -> AgeResult<u64> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let mut current = read_chunk(input)?;
+    let mut total = current.len() as u64;
+    let mut counter: u128 = 0;
+    loop {
+        // A chunk is last when nothing follows it; only an empty input has an empty chunk
+        let next = if current.len() == CHUNK_SIZE {
+            read_chunk(input)?
+        } else {
+            Vec::new()
+        };
+        let last = next.is_empty();
+
+        let mut nonce = [0u8; 12];
+        nonce[..11].copy_from_slice(&counter.to_be_bytes()[5..]);
+        nonce[11] = u8::from(last);
+        let sealed = cipher
+            .encrypt(Nonce::from_slice(&nonce), current.as_slice())
+            .map_err(|_| invalid("Payload encryption failed"))?;
+        output.write_all(&sealed).map_err(io_error)?;
+
+        if last {
+            return Ok(total);
+        }
+        total += next.len() as u64;
+        current = next;
+        counter += 1;
+    }
+}
+
+fn read_chunk(input: &mut dyn Read) -> AgeResult<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    input
+        .take(CHUNK_SIZE as u64)
+        .read_to_end(&mut chunk)
+        .map_err(io_error)?;
+    Ok(chunk)
+}
+
+fn parse_x25519(recipient: &str) -> AgeResult<PublicKey> {
+    let decoded = bech32::decode(recipient)
+        .ok()
+        .filter(|(hrp, _, _)| hrp == "age")
+        .and_then(|(_, data, _)| Vec::<u8>::from_base32(&data).ok())
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok());
+    decoded.map(PublicKey::from).ok_or_else(|| {
+        invalid(&format!(
+            "Deterministic mode supports X25519 (age1...) recipients only: {}",
+            recipient
+        ))
+    })
+}
+
+fn invalid(reason: &str) -> AgeError {
+    AgeError::InvalidOperation {
+        operation: "deterministic_encrypt".to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn io_error(source: io::Error) -> AgeError {
+    AgeError::IoError {
+        operation: "deterministic_encrypt".to_string(),
+        context: "write".to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::x25519;
+
+    fn encrypt(seed: u8, recipient: &str, plaintext: &[u8], format: OutputFormat) -> Vec<u8> {
+        let mut output = Vec::new();
+        DeterministicEncryptor::from_seed([seed; 32])
+            .with_format(format)
+            .encrypt(&[recipient.to_string()], &mut &plaintext[..], &mut output)
+            .unwrap();
+        output
+    }
+
+    fn decrypt(identity: &x25519::Identity, ciphertext: &[u8]) -> Vec<u8> {
+        let reader = age::armor::ArmoredReader::new(ciphertext);
+        let decryptor = age::Decryptor::new(reader).unwrap();
+        let mut plaintext = Vec::new();
+        decryptor
+            .decrypt(std::iter::once(identity as &dyn age::Identity))
+            .unwrap()
+            .read_to_end(&mut plaintext)
+            .unwrap();
+        plaintext
+    }
+
+    #[test]
+    fn test_same_seed_same_ciphertext_and_age_compatible() {
+        let identity = x25519::Identity::generate();
+        let recipient = identity.to_public().to_string();
+
+        // Empty, sub-chunk and multi-chunk (exact multiple) payloads
+        for plaintext in [Vec::new(), b"golden".to_vec(), vec![3u8; CHUNK_SIZE * 2]] {
+            let first = encrypt(7, &recipient, &plaintext, OutputFormat::Binary);
+            let second = encrypt(7, &recipient, &plaintext, OutputFormat::Binary);
+            assert_eq!(first, second);
+            assert_ne!(
+                first,
+                encrypt(8, &recipient, &plaintext, OutputFormat::Binary)
+            );
+            assert_eq!(decrypt(&identity, &first), plaintext);
+        }
+
+        let armored = encrypt(7, &recipient, b"golden", OutputFormat::AsciiArmor);
+        assert!(armored.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert_eq!(decrypt(&identity, &armored), b"golden");
+    }
+
+    #[test]
+    fn test_rejects_non_x25519_recipients() {
+        let mut output = Vec::new();
+        let result = DeterministicEncryptor::from_seed([0; 32]).encrypt(
+            &["ssh-ed25519 AAAA".to_string()],
+            &mut &b"x"[..],
+            &mut output,
+        );
+        assert!(result.is_err());
+    }
+}
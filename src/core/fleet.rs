@@ -0,0 +1,323 @@
+//! Fleet Manifests - Many repositories driven by one command
+//!
+//! Platform teams look after dozens of encrypted repositories. A fleet manifest lists
+//! them once, each with its own selection, recipients and identity, so
+//! `cage fleet <status|lock|verify> --manifest fleet.toml` can run across all of them:
+//!
+//! ```toml
+//! [[repo]]
+//! name = "payments"
+//! path = "../payments"
+//! group = "payments-ops"              # lock: recipient group from the config
+//! identity = "~/.age/payments.txt"    # verify --deep: age identity file
+//!
+//! [[repo]]
+//! path = "/srv/infra-secrets"
+//! pattern = "*.env"
+//! recipients = ["age1..."]
+//! ssh_identity = "~/.ssh/id_ed25519"
+//! ```
+//!
+//! Files ending in `.json` use the same structure as JSON. Relative paths are resolved
+//! against the manifest's directory and `~/` against the home directory. Repositories
+//! are recursive unless `recursive = false`; locking a repository without recipients
+//! uses the invocation's passphrase.
+
+use super::config::AgeConfig;
+use super::plan::PlanEntry;
+use super::requests::{Identity, LockRequest, StatusRequest, VerifyRequest};
+use crate::error::{AgeError, AgeResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Command run across every repository of a fleet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FleetCommand {
+    Status,
+    Lock,
+    Verify,
+}
+
+impl FleetCommand {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value {
+            "status" => Ok(Self::Status),
+            "lock" => Ok(Self::Lock),
+            "verify" => Ok(Self::Verify),
+            _ => Err(AgeError::InvalidOperation {
+                operation: "fleet".to_string(),
+                reason: format!("unknown fleet command '{}' (status, lock, verify)", value),
+            }),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Lock => "lock",
+            Self::Verify => "verify",
+        }
+    }
+}
+
+/// One repository of a fleet
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FleetRepo {
+    /// Label in reports; defaults to the path
+    #[serde(default)]
+    pub name: Option<String>,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default = "default_recursive")]
+    pub recursive: bool,
+    /// `binary` (default) or `ascii`, for lock
+    #[serde(default)]
+    pub format: Option<String>,
+    /// age or SSH public keys, for lock
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    #[serde(default)]
+    pub recipients_file: Option<PathBuf>,
+    /// Recipient group from the config, for lock
+    #[serde(default)]
+    pub group: Option<String>,
+    /// age identity file, for deep verify
+    #[serde(default)]
+    pub identity: Option<PathBuf>,
+    /// SSH private key, for deep verify
+    #[serde(default)]
+    pub ssh_identity: Option<PathBuf>,
+    /// Identity provider URI or alias, for deep verify
+    #[serde(default)]
+    pub identity_provider: Option<String>,
+}
+
+fn default_recursive() -> bool {
+    true
+}
+
+impl FleetRepo {
+    /// Name shown in reports
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+
+    /// Identity used to decrypt this repository's files, if one is configured
+    pub fn identity(&self) -> Option<Identity> {
+        if let Some(path) = &self.identity {
+            Some(Identity::IdentityFile(path.clone()))
+        } else if let Some(path) = &self.ssh_identity {
+            Some(Identity::SshKey(path.clone()))
+        } else {
+            self.identity_provider.clone().map(Identity::Provider)
+        }
+    }
+
+    fn plan_entry(&self) -> PlanEntry {
+        PlanEntry {
+            path: self.path.clone(),
+            pattern: self.pattern.clone(),
+            format: self.format.clone(),
+            recursive: self.recursive,
+            recipients: self.recipients.clone(),
+            recipients_file: self.recipients_file.clone(),
+            group: self.group.clone(),
+            backup: false,
+        }
+    }
+
+    /// Whether locking encrypts to public keys rather than a passphrase
+    pub fn has_recipients(&self) -> bool {
+        self.plan_entry().has_recipients()
+    }
+
+    /// Status request covering this repository
+    pub fn status_request(&self) -> StatusRequest {
+        let mut request = StatusRequest::new(self.path.clone());
+        request.recursive = self.recursive;
+        request.pattern = self.pattern.clone();
+        request
+    }
+
+    /// Lock request for this repository; `passphrase` serves repos without recipients
+    pub fn lock_request(
+        &self,
+        passphrase: Option<&Identity>,
+        config: &AgeConfig,
+    ) -> AgeResult<LockRequest> {
+        let identity = match passphrase {
+            Some(identity) => identity.clone(),
+            None if self.has_recipients() => Identity::PromptPassphrase,
+            None => {
+                return Err(AgeError::InvalidOperation {
+                    operation: "fleet lock".to_string(),
+                    reason: format!("{} has no recipients and no passphrase", self.label()),
+                })
+            }
+        };
+        self.plan_entry().to_lock_request(&identity, config)
+    }
+
+    /// Verify request; deep when `deep` is set and the repository has an identity
+    pub fn verify_request(&self, deep: bool) -> VerifyRequest {
+        let mut request = VerifyRequest::new(self.path.clone());
+        request.recursive = self.recursive;
+        request.pattern = self.pattern.clone();
+        match self.identity() {
+            Some(identity) if deep => request.deep_verify(identity),
+            _ => request,
+        }
+    }
+}
+
+/// Parsed fleet manifest
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FleetManifest {
+    #[serde(rename = "repo", alias = "repos")]
+    pub repos: Vec<FleetRepo>,
+    /// Manifest file the repositories were loaded from
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+impl FleetManifest {
+    /// Load a TOML or JSON manifest and resolve relative paths against its directory
+    pub fn load(path: &Path) -> AgeResult<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        let is_json = path.extension().is_some_and(|ext| ext == "json");
+        let parsed: Result<Self, String> = if is_json {
+            serde_json::from_str(&contents).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|e| e.to_string())
+        };
+        let invalid = |reason: String| AgeError::ConfigurationError {
+            parameter: "fleet".to_string(),
+            value: path.display().to_string(),
+            reason,
+        };
+        let mut manifest = parsed.map_err(invalid)?;
+        if manifest.repos.is_empty() {
+            return Err(invalid("Manifest lists no repositories".to_string()));
+        }
+
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        let resolve = |file: &Path| match (file.strip_prefix("~"), std::env::var("HOME")) {
+            (Ok(rest), Ok(home)) => PathBuf::from(home).join(rest),
+            _ => base.join(file),
+        };
+        let mut labels = HashSet::new();
+        for repo in &mut manifest.repos {
+            repo.path = resolve(repo.path.as_path());
+            for file in [
+                &mut repo.recipients_file,
+                &mut repo.identity,
+                &mut repo.ssh_identity,
+            ] {
+                if let Some(value) = file.as_mut() {
+                    *value = resolve(value.as_path());
+                }
+            }
+            let identities = [
+                repo.identity.is_some(),
+                repo.ssh_identity.is_some(),
+                repo.identity_provider.is_some(),
+            ];
+            if identities.iter().filter(|set| **set).count() > 1 {
+                return Err(invalid(format!(
+                    "{}: set only one of identity, ssh_identity, identity_provider",
+                    repo.label()
+                )));
+            }
+            if !labels.insert(repo.label()) {
+                return Err(invalid(format!("duplicate repository '{}'", repo.label())));
+            }
+        }
+        manifest.source = path.to_path_buf();
+        Ok(manifest)
+    }
+
+    /// Whether locking needs the invocation's passphrase for some repository
+    pub fn needs_passphrase(&self) -> bool {
+        self.repos.iter().any(|repo| !repo.has_recipients())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_fleet_manifest() {
+        let temp = TempDir::new().unwrap();
+        let manifest_path = temp.path().join("fleet.toml");
+        fs::write(
+            &manifest_path,
+            r#"
+[[repo]]
+name = "payments"
+path = "payments"
+recipients = ["age1example"]
+identity = "keys/payments.txt"
+
+[[repo]]
+path = "/srv/infra"
+recursive = false
+pattern = "*.env"
+"#,
+        )
+        .unwrap();
+
+        let manifest = FleetManifest::load(&manifest_path).unwrap();
+        assert_eq!(manifest.repos.len(), 2);
+        assert!(manifest.needs_passphrase());
+
+        let payments = &manifest.repos[0];
+        assert_eq!(payments.path, temp.path().join("payments"));
+        assert!(payments.recursive);
+        assert!(matches!(
+            payments.identity(),
+            Some(Identity::IdentityFile(path)) if path == temp.path().join("keys/payments.txt")
+        ));
+        assert!(payments.verify_request(true).deep_verify);
+        let lock = payments.lock_request(None, &AgeConfig::default()).unwrap();
+        assert!(lock.recipients.is_some());
+
+        let infra = &manifest.repos[1];
+        assert_eq!(infra.label(), "/srv/infra");
+        assert!(!infra.status_request().recursive);
+        assert!(!infra.verify_request(true).deep_verify);
+        assert!(infra.lock_request(None, &AgeConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_fleet_manifest_rejects_ambiguous_repos() {
+        let temp = TempDir::new().unwrap();
+        let manifest_path = temp.path().join("fleet.json");
+        fs::write(&manifest_path, r#"{"repo": []}"#).unwrap();
+        assert!(FleetManifest::load(&manifest_path).is_err());
+
+        fs::write(
+            &manifest_path,
+            r#"{"repo": [{"path": "a", "name": "x"}, {"path": "b", "name": "x"}]}"#,
+        )
+        .unwrap();
+        assert!(FleetManifest::load(&manifest_path).is_err());
+
+        fs::write(
+            &manifest_path,
+            r#"{"repo": [{"path": "a", "identity": "k.txt", "ssh_identity": "id"}]}"#,
+        )
+        .unwrap();
+        assert!(FleetManifest::load(&manifest_path).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+//! Recipient Group Store - Persisted recipient groups
+//!
+//! Groups registered from the CLI (e.g. `cage recipients import`) live in
+//! `~/.config/cage/groups.json` (`CAGE_GROUPS_FILE` overrides the path), keyed by group
+//! name. `AgeConfig::load_default` merges them into `recipient_groups`, so `--group`,
+//! `--tier` and `--pin-group` see them like any other configured group. Groups already
+//! defined by the embedding application win over stored ones of the same name.
+//!
+//! # Recipients file format
+//!
+//! `cage recipients export` writes, and `import` reads, a plain-text file that `age -R`
+//! also accepts, so recipient sets can be reviewed in PRs and shared with padlock:
+//!
+//! ```text
+//! # cage recipients v1
+//! # group: team
+//! # tier: R
+//! # hash: 0c5b...
+//!
+//! # github:alice SHA256:yf23...
+//! ssh-ed25519 AAAAC3Nz...
+//! age1qyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqs3290gq
+//! ```
+//!
+//! - `# key: value` lines before the first recipient are headers: `group`, `tier`
+//!   (X, M, R, I or D) and `hash` (the group hash, checked by importers)
+//! - Any other `#` line directly above a recipient is that recipient's comment;
+//!   a blank line ends a comment
+//! - Each remaining line is one recipient: an `age1...` key, or an `ssh-ed25519` /
+//!   `ssh-rsa` public key whose trailing text is also taken as its comment
+
+use super::config::AgeConfig;
+use super::requests::{AuthorityTier, RecipientGroup};
+use crate::error::{AgeError, AgeResult};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Environment override for the group store location
+pub const GROUPS_FILE_ENV: &str = "CAGE_GROUPS_FILE";
+const GROUPS_FILE: &str = "groups.json";
+/// First line of an exported recipients file
+pub const RECIPIENTS_FILE_MAGIC: &str = "# cage recipients v1";
+/// Group metadata key prefix holding a recipient's comment
+pub const COMMENT_METADATA_PREFIX: &str = "comment:";
+
+/// Recipient groups persisted to a JSON file
+#[derive(Debug, Clone)]
+pub struct GroupStore {
+    path: PathBuf,
+    groups: BTreeMap<String, RecipientGroup>,
+    /// Admin token for `save` when `recipients import` is gated (see `core::roles`)
+    admin_token: Option<String>,
+}
+
+impl GroupStore {
+    /// Store location: `CAGE_GROUPS_FILE`, else the cage config directory
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(custom) = env::var(GROUPS_FILE_ENV) {
+            if !custom.is_empty() {
+                return Some(PathBuf::from(custom));
+            }
+        }
+        let base = match env::var("XDG_CONFIG_HOME") {
+            Ok(xdg) => PathBuf::from(xdg),
+            Err(_) => PathBuf::from(env::var("HOME").ok()?).join(".config"),
+        };
+        Some(base.join("cage").join(GROUPS_FILE))
+    }
+
+    /// Load the default store; empty when no location can be determined
+    pub fn load_default() -> AgeResult<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load(&path),
+            None => Ok(Self {
+                path: PathBuf::from(GROUPS_FILE),
+                groups: BTreeMap::new(),
+                admin_token: None,
+            }),
+        }
+    }
+
+    /// Load `path`; a missing file is an empty store, a corrupt one an error
+    pub fn load(path: &Path) -> AgeResult<Self> {
+        let groups = match fs::read(path) {
+            Ok(data) => {
+                super::config_seal::verify(path, &data)?;
+                serde_json::from_slice(&data).map_err(|e| AgeError::ConfigurationError {
+                    parameter: "recipient_groups".to_string(),
+                    value: path.display().to_string(),
+                    reason: e.to_string(),
+                })?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(AgeError::file_error("read_groups", path.to_path_buf(), e)),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            groups,
+            admin_token: None,
+        })
+    }
+
+    /// Authorize `save` with this admin token instead of `CAGE_ADMIN_TOKEN`
+    pub fn with_admin_token(mut self, token: Option<String>) -> Self {
+        self.admin_token = token;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the store, replacing the previous file in one rename.
+    ///
+    /// Every group edit counts as `recipients import`, so once that operation is gated the
+    /// admin token is required.
+    pub fn save(&self) -> AgeResult<()> {
+        let token = self.admin_token.clone().or_else(super::roles::env_token);
+        super::roles::authorize(
+            &AgeConfig::load_default_unsealed()?,
+            "recipients import",
+            token.as_deref(),
+        )?;
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| AgeError::file_error("create_groups_dir", dir.to_path_buf(), e))?;
+        }
+        let json =
+            serde_json::to_vec_pretty(&self.groups).map_err(|e| AgeError::InvalidOperation {
+                operation: "save_groups".to_string(),
+                reason: e.to_string(),
+            })?;
+        let mut staging = self.path.as_os_str().to_owned();
+        staging.push(".tmp");
+        let staging = PathBuf::from(staging);
+        super::config_seal::rewrite_sealed(&self.path, || {
+            fs::write(&staging, json)
+                .map_err(|e| AgeError::file_error("write_groups", staging.clone(), e))?;
+            fs::rename(&staging, &self.path)
+                .map_err(|e| AgeError::file_error("write_groups", self.path.clone(), e))
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RecipientGroup> {
+        self.groups.get(name)
+    }
+
+    /// Stored group `name`, created empty if missing
+    pub fn group_mut(&mut self, name: &str) -> &mut RecipientGroup {
+        self.groups.entry(name.to_string()).or_insert_with(|| {
+            let mut group = RecipientGroup::new(name.to_string());
+            group.set_metadata("created_at".to_string(), chrono::Utc::now().to_rfc3339());
+            group
+        })
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.groups.keys().cloned().collect()
+    }
+
+    /// Remove every stored group, returning them
+    pub fn clear(&mut self) -> Vec<RecipientGroup> {
+        std::mem::take(&mut self.groups).into_values().collect()
+    }
+
+    /// Store `group` under its name, replacing any stored group of that name
+    pub fn insert(&mut self, group: RecipientGroup) {
+        self.groups.insert(group.name.clone(), group);
+    }
+
+    /// Add stored groups to `config`, keeping groups it already defines
+    pub fn merge_into(&self, config: &mut AgeConfig) {
+        for (name, group) in &self.groups {
+            config
+                .recipient_groups
+                .entry(name.clone())
+                .or_insert_with(|| group.clone());
+        }
+    }
+}
+
+/// A parsed recipients file
+#[derive(Debug, Clone)]
+pub struct RecipientsFile {
+    /// Group built from the file; comments land in `comment:<recipient>` metadata
+    pub group: RecipientGroup,
+    /// `hash` header, if the file declared one
+    pub declared_hash: Option<String>,
+}
+
+impl RecipientsFile {
+    /// Whether the declared hash (if any) matches the recipients in the file
+    pub fn hash_matches(&self) -> bool {
+        self.declared_hash
+            .as_deref()
+            .is_none_or(|hash| hash == self.group.group_hash())
+    }
+}
+
+/// Render `group` in the recipients file format
+pub fn export_recipients(group: &RecipientGroup) -> String {
+    let mut out = format!("{}\n# group: {}\n", RECIPIENTS_FILE_MAGIC, group.name);
+    if let Some(tier) = group.tier {
+        out.push_str(&format!("# tier: {}\n", tier.as_str()));
+    }
+    out.push_str(&format!("# hash: {}\n", group.group_hash()));
+    for recipient in &group.recipients {
+        out.push('\n');
+        if let Some(comment) = recipient_comment(group, recipient) {
+            out.push_str(&format!("# {}\n", comment));
+        }
+        out.push_str(recipient);
+        out.push('\n');
+    }
+    out
+}
+
+/// Parse a recipients file; `name` is used when the file has no `group` header
+pub fn parse_recipients(contents: &str, name: Option<&str>) -> AgeResult<RecipientsFile> {
+    let mut header_name = None;
+    let mut tier = None;
+    let mut declared_hash = None;
+    let mut entries: Vec<(String, Option<String>)> = Vec::new();
+    let mut pending_comment: Option<String> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        let invalid = |reason: String| AgeError::ConfigurationError {
+            parameter: "recipients".to_string(),
+            value: format!("line {}", index + 1),
+            reason,
+        };
+        if line.is_empty() {
+            pending_comment = None;
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.trim();
+            let header = comment
+                .split_once(':')
+                .filter(|_| entries.is_empty())
+                .map(|(key, value)| (key.trim(), value.trim()));
+            match header {
+                Some(("group", value)) => header_name = Some(value.to_string()),
+                Some(("tier", value)) => {
+                    tier = Some(AuthorityTier::from_str(value).ok_or_else(|| {
+                        invalid(format!(
+                            "Unknown tier '{}' (expected X, M, R, I or D)",
+                            value
+                        ))
+                    })?)
+                }
+                Some(("hash", value)) => declared_hash = Some(value.to_string()),
+                _ if comment.is_empty() || line == RECIPIENTS_FILE_MAGIC => {}
+                _ => pending_comment = Some(comment.to_string()),
+            }
+            continue;
+        }
+
+        let (recipient, trailing) = parse_recipient_line(line).map_err(invalid)?;
+        if entries.iter().any(|(existing, _)| *existing == recipient) {
+            return Err(invalid(format!("Duplicate recipient {}", recipient)));
+        }
+        entries.push((recipient, pending_comment.take().or(trailing)));
+    }
+
+    let name = header_name
+        .or_else(|| name.map(String::from))
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| AgeError::ConfigurationError {
+            parameter: "recipients".to_string(),
+            value: "group".to_string(),
+            reason: "No '# group:' header; pass a group name".to_string(),
+        })?;
+    let mut group = RecipientGroup::new(name);
+    group.tier = tier;
+    for (recipient, comment) in entries {
+        if let Some(comment) = comment {
+            set_recipient_comment(&mut group, &recipient, comment);
+        }
+        group.add_recipient(recipient);
+    }
+    Ok(RecipientsFile {
+        group,
+        declared_hash,
+    })
+}
+
+/// Comment recorded for `recipient` in `group`
+pub fn recipient_comment<'a>(group: &'a RecipientGroup, recipient: &str) -> Option<&'a str> {
+    group
+        .get_metadata(&format!("{}{}", COMMENT_METADATA_PREFIX, recipient))
+        .map(String::as_str)
+}
+
+/// Record `comment` for `recipient` in `group`
+pub fn set_recipient_comment(group: &mut RecipientGroup, recipient: &str, comment: String) {
+    group.set_metadata(format!("{}{}", COMMENT_METADATA_PREFIX, recipient), comment);
+}
+
+/// Split a recipient line into the recipient and any trailing SSH key comment
+fn parse_recipient_line(line: &str) -> Result<(String, Option<String>), String> {
+    let mut fields = line.split_whitespace();
+    let first = fields.next().unwrap_or_default();
+    if first.starts_with("ssh-") {
+        let encoded = fields
+            .next()
+            .ok_or_else(|| format!("SSH key '{}' has no key data", first))?;
+        let recipient = format!("{} {}", first, encoded);
+        age::ssh::Recipient::from_str(&recipient)
+            .map_err(|e| format!("Invalid SSH recipient: {:?}", e))?;
+        let trailing = Some(fields.collect::<Vec<_>>().join(" ")).filter(|c| !c.is_empty());
+        return Ok((recipient, trailing));
+    }
+    if fields.next().is_some() {
+        return Err(format!(
+            "Unexpected text after recipient '{}'; put comments on the line above",
+            first
+        ));
+    }
+    let is_age_key = first.starts_with("age1")
+        && first
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if !is_age_key {
+        return Err(format!("Not an age or SSH recipient: '{}'", first));
+    }
+    if !first[4..].contains('1') {
+        age::x25519::Recipient::from_str(first)
+            .map_err(|e| format!("Invalid age recipient: {}", e))?;
+    }
+    Ok((first.to_string(), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_round_trip_and_merge() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("cage/groups.json");
+        let mut store = GroupStore::load(&path).unwrap();
+        assert!(store.names().is_empty());
+
+        store
+            .group_mut("team")
+            .add_recipient("age1example".to_string());
+        store.save().unwrap();
+
+        let store = GroupStore::load(&path).unwrap();
+        assert_eq!(store.get("team").unwrap().recipients, ["age1example"]);
+
+        let mut config = AgeConfig::default();
+        config.add_recipient_group(RecipientGroup::new("team".to_string()));
+        store.merge_into(&mut config);
+        assert!(config.get_recipient_group("team").unwrap().is_empty());
+
+        fs::write(&path, b"{not json").unwrap();
+        assert!(GroupStore::load(&path).is_err());
+    }
+
+    const SSH_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHOc/MEtg7CheMZfb49U6aRgMkccqjaAkVTkjoobtBk2";
+    const AGE_KEY: &str = "age100j42qsz5awgsvwycyh77k8uatpwm7p83wrkujrmuw3zlz9heyxs5z4lvg";
+
+    #[test]
+    fn test_recipients_file_round_trip() {
+        let mut group = RecipientGroup::with_tier("team".to_string(), AuthorityTier::Repository);
+        group.add_recipient(SSH_KEY.to_string());
+        group.add_recipient(AGE_KEY.to_string());
+        set_recipient_comment(&mut group, SSH_KEY, "github:alice".to_string());
+
+        let text = export_recipients(&group);
+        assert!(text.starts_with(RECIPIENTS_FILE_MAGIC));
+        assert!(text.contains("# tier: R\n"));
+        assert!(text.contains(&format!("# github:alice\n{}\n", SSH_KEY)));
+
+        let parsed = parse_recipients(&text, None).unwrap();
+        assert!(parsed.hash_matches());
+        assert_eq!(parsed.group.name, "team");
+        assert_eq!(parsed.group.tier, Some(AuthorityTier::Repository));
+        assert_eq!(parsed.group.recipients, group.recipients);
+        assert_eq!(
+            recipient_comment(&parsed.group, SSH_KEY),
+            Some("github:alice")
+        );
+        assert_eq!(recipient_comment(&parsed.group, AGE_KEY), None);
+    }
+
+    #[test]
+    fn test_parse_recipients_plain_and_invalid() {
+        // A plain age recipients file works with a group name from the caller
+        let plain = format!("{} laptop\n\n# stale hash\n{}\n", SSH_KEY, AGE_KEY);
+        let parsed = parse_recipients(&format!("# hash: 00\n{}", plain), Some("ops")).unwrap();
+        assert_eq!(parsed.group.name, "ops");
+        assert!(!parsed.hash_matches());
+        assert_eq!(recipient_comment(&parsed.group, SSH_KEY), Some("laptop"));
+        assert_eq!(
+            recipient_comment(&parsed.group, AGE_KEY),
+            Some("stale hash")
+        );
+
+        assert!(parse_recipients(AGE_KEY, None).is_err());
+        let err = parse_recipients(&format!("{} # bob", AGE_KEY), Some("ops"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 1"), "{}", err);
+        assert!(parse_recipients("age1notakey", Some("ops")).is_err());
+        assert!(parse_recipients("# tier: Z\n", Some("ops")).is_err());
+        assert!(parse_recipients(&format!("{0}\n{0}\n", AGE_KEY), Some("ops")).is_err());
+    }
+}
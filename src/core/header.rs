@@ -0,0 +1,629 @@
+//! Age Header - Parse and rewrite age v1 file headers
+//!
+//! An age file starts with a text header listing one recipient stanza per recipient,
+//! each wrapping the same symmetric file key, followed by a MAC over the header. The
+//! payload after it is encrypted only under that file key, so adding a recipient
+//! needs just the file key: unwrap it with an authorized identity, wrap it for the new
+//! recipients, and write a fresh header in front of the untouched payload.
+//!
+//! Passphrase (scrypt) files are excluded: the age spec requires the scrypt stanza to be
+//! the only stanza, so they must be rotated instead.
+//!
+//! This is also the one place that recognizes age files: `HeaderFormat::detect` tells
+//! binary from armored ciphertext by the first line, and `AgeHeader::read_file` parses
+//! the version line, stanzas and MAC line of either, so verify, status and the adapters
+//! agree on what counts as an age file.
+
+use crate::core::{Identity, Recipient};
+use crate::error::{AgeError, AgeResult};
+use age::secrecy::ExposeSecret;
+use age_core::format::{FileKey, Stanza};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use tempfile::NamedTempFile;
+
+const VERSION: &[u8] = b"age-encryption.org/v1";
+const VERSION_LINE: &[u8] = b"age-encryption.org/v1\n";
+const STANZA_PREFIX: &[u8] = b"-> ";
+const MAC_MARKER: &[u8] = b"---";
+const ARMOR_BEGIN: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+const COLUMNS: usize = 64;
+
+/// Upper bound on header size so a malformed file cannot exhaust memory
+const MAX_HEADER_BYTES: usize = 1024 * 1024;
+
+/// Encoding of an age file, told apart by its first line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderFormat {
+    /// `age-encryption.org/v1` header followed by the binary payload
+    Binary,
+    /// The binary file base64-encoded between BEGIN and END armor lines
+    Armored,
+}
+
+impl HeaderFormat {
+    /// Bytes needed from the start of a file to tell the formats apart
+    pub const DETECT_BYTES: usize = ARMOR_BEGIN.len();
+
+    /// Format of a file starting with `head`, or `None` when it is not age ciphertext
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        if head.starts_with(ARMOR_BEGIN) {
+            Some(Self::Armored)
+        } else if head.starts_with(VERSION) {
+            Some(Self::Binary)
+        } else {
+            None
+        }
+    }
+
+    /// Format of the file at `path`, reading only its first line
+    pub fn detect_file(path: &Path) -> AgeResult<Option<Self>> {
+        let mut head = Vec::with_capacity(Self::DETECT_BYTES);
+        fs::File::open(path)
+            .and_then(|file| file.take(Self::DETECT_BYTES as u64).read_to_end(&mut head))
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        Ok(Self::detect(&head))
+    }
+}
+
+/// Parsed age v1 header
+pub struct AgeHeader {
+    stanzas: Vec<Stanza>,
+    mac: Vec<u8>,
+    /// Header bytes covered by the MAC (through the `---` marker)
+    authenticated: Vec<u8>,
+}
+
+impl AgeHeader {
+    /// Read the header of the binary or armored age file at `path`
+    pub fn read_file(path: &Path) -> AgeResult<Self> {
+        let file = fs::File::open(path)
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        // ArmoredReader passes binary input through untouched
+        let mut reader = BufReader::new(age::armor::ArmoredReader::new(BufReader::new(file)));
+        Self::read_from(&mut reader)
+    }
+
+    /// Read a binary header, leaving `reader` positioned at the payload
+    pub fn read_from<R: BufRead>(reader: &mut R) -> AgeResult<Self> {
+        let mut authenticated = Vec::new();
+        let mut line = Vec::new();
+
+        read_header_line(reader, &mut line)?;
+        if line != VERSION_LINE {
+            return Err(header_error("missing age-encryption.org/v1 version line"));
+        }
+        authenticated.extend_from_slice(&line);
+
+        let mut stanzas = Vec::new();
+        loop {
+            read_header_line(reader, &mut line)?;
+            if let Some(mac) = line.strip_prefix(b"--- ") {
+                let mac = STANDARD_NO_PAD
+                    .decode(mac.strip_suffix(b"\n").unwrap_or(mac))
+                    .map_err(|_| header_error("header MAC is not valid base64"))?;
+                authenticated.extend_from_slice(MAC_MARKER);
+                return Ok(Self {
+                    stanzas,
+                    mac,
+                    authenticated,
+                });
+            }
+
+            let Some(tag_line) = line.strip_prefix(STANZA_PREFIX) else {
+                return Err(header_error("expected a recipient stanza or header MAC"));
+            };
+            let tag_line = std::str::from_utf8(tag_line)
+                .map_err(|_| header_error("stanza line is not valid UTF-8"))?;
+            let mut args = tag_line.trim_end_matches('\n').split(' ').map(String::from);
+            let tag = args.next().unwrap_or_default();
+            let args = args.collect();
+            if tag.is_empty() {
+                return Err(header_error("recipient stanza has no type"));
+            }
+            authenticated.extend_from_slice(&line);
+
+            // Body lines are exactly 64 columns; a shorter (possibly empty) line ends it
+            let mut encoded = Vec::new();
+            loop {
+                read_header_line(reader, &mut line)?;
+                authenticated.extend_from_slice(&line);
+                let chunk = line.strip_suffix(b"\n").unwrap_or(&line);
+                if chunk.len() > COLUMNS {
+                    return Err(header_error("stanza body line exceeds 64 columns"));
+                }
+                encoded.extend_from_slice(chunk);
+                if chunk.len() < COLUMNS {
+                    break;
+                }
+            }
+            let body = STANDARD_NO_PAD
+                .decode(&encoded)
+                .map_err(|_| header_error("stanza body is not valid base64"))?;
+            stanzas.push(Stanza { tag, args, body });
+
+            if authenticated.len() > MAX_HEADER_BYTES {
+                return Err(header_error("header exceeds size limit"));
+            }
+        }
+    }
+
+    /// Build a header for `stanzas`, authenticated under `file_key`
+    pub fn new(file_key: &FileKey, stanzas: Vec<Stanza>) -> Self {
+        let mut authenticated = VERSION_LINE.to_vec();
+        for stanza in &stanzas {
+            authenticated.extend_from_slice(STANZA_PREFIX);
+            authenticated.extend_from_slice(stanza.tag.as_bytes());
+            for arg in &stanza.args {
+                authenticated.push(b' ');
+                authenticated.extend_from_slice(arg.as_bytes());
+            }
+            authenticated.push(b'\n');
+
+            let encoded = STANDARD_NO_PAD.encode(&stanza.body);
+            let mut chunks = encoded.as_bytes().chunks(COLUMNS).peekable();
+            while let Some(chunk) = chunks.next() {
+                authenticated.extend_from_slice(chunk);
+                authenticated.push(b'\n');
+                // A full final line must be followed by an empty one to end the body
+                if chunks.peek().is_none() && chunk.len() == COLUMNS {
+                    authenticated.push(b'\n');
+                }
+            }
+            if encoded.is_empty() {
+                authenticated.push(b'\n');
+            }
+        }
+        authenticated.extend_from_slice(MAC_MARKER);
+
+        let mac = header_mac(file_key)
+            .chain_update(&authenticated)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        Self {
+            stanzas,
+            mac,
+            authenticated,
+        }
+    }
+
+    pub fn stanzas(&self) -> &[Stanza] {
+        &self.stanzas
+    }
+
+    /// Stanza types in header order (`X25519`, `ssh-ed25519`, `scrypt`, ...)
+    pub fn stanza_tags(&self) -> Vec<&str> {
+        self.stanzas.iter().map(|s| s.tag.as_str()).collect()
+    }
+
+    /// Decoded header MAC
+    pub fn mac(&self) -> &[u8] {
+        &self.mac
+    }
+
+    /// Whether the file was encrypted to a passphrase rather than recipients
+    pub fn is_passphrase(&self) -> bool {
+        self.stanzas.iter().any(|s| s.tag == "scrypt")
+    }
+
+    /// Unwrap the file key with any of `identities` and verify the header MAC
+    pub fn unwrap_file_key(&self, identities: &[Box<dyn age::Identity>]) -> AgeResult<FileKey> {
+        let file_key = identities
+            .iter()
+            .find_map(|identity| identity.unwrap_stanzas(&self.stanzas))
+            .ok_or_else(|| AgeError::InvalidOperation {
+                operation: "unwrap_file_key".to_string(),
+                reason: "No provided identity matches a recipient of this file".to_string(),
+            })?
+            .map_err(|e| AgeError::InvalidOperation {
+                operation: "unwrap_file_key".to_string(),
+                reason: e.to_string(),
+            })?;
+
+        header_mac(&file_key)
+            .chain_update(&self.authenticated)
+            .verify_slice(&self.mac)
+            .map_err(|_| header_error("header MAC does not match; file may be tampered"))?;
+        Ok(file_key)
+    }
+
+    /// Write the header in binary form
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.authenticated)?;
+        writer.write_all(b" ")?;
+        writer.write_all(STANDARD_NO_PAD.encode(&self.mac).as_bytes())?;
+        writer.write_all(b"\n")
+    }
+}
+
+fn header_mac(file_key: &FileKey) -> Hmac<Sha256> {
+    let key = age_core::primitives::hkdf(&[], b"header", file_key.expose_secret());
+    Hmac::<Sha256>::new_from_slice(&key).expect("HMAC accepts any key length")
+}
+
+fn header_error(reason: &str) -> AgeError {
+    AgeError::InvalidOperation {
+        operation: "parse_header".to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn read_header_line<R: BufRead>(reader: &mut R, line: &mut Vec<u8>) -> AgeResult<()> {
+    line.clear();
+    let read = io::Read::take(&mut *reader, MAX_HEADER_BYTES as u64)
+        .read_until(b'\n', line)
+        .map_err(|e| AgeError::IoError {
+            operation: "read_header".to_string(),
+            context: "age header".to_string(),
+            source: e,
+        })?;
+    if read == 0 || !line.ends_with(b"\n") {
+        return Err(header_error("truncated header"));
+    }
+    Ok(())
+}
+
+/// Load age identities for header unwrapping (identity files and unencrypted SSH keys)
+pub fn load_identities(identity: &Identity) -> AgeResult<Vec<Box<dyn age::Identity>>> {
+    let io_error = |path: &Path, e: io::Error| AgeError::file_error("read", path.to_path_buf(), e);
+    match identity {
+        Identity::IdentityFile(path) => age::IdentityFile::from_file(path.display().to_string())
+            .map_err(|e| io_error(path, e))?
+            .into_identities()
+            .map_err(|e| AgeError::ConfigurationError {
+                parameter: "identity".to_string(),
+                value: path.display().to_string(),
+                reason: e.to_string(),
+            }),
+        Identity::SshKey(path) => {
+            let file = fs::File::open(path).map_err(|e| io_error(path, e))?;
+            let key = age::ssh::Identity::from_buffer(
+                BufReader::new(file),
+                Some(path.display().to_string()),
+            )
+            .map_err(|e| io_error(path, e))?;
+            Ok(vec![Box::new(key)])
+        }
+        _ => Err(AgeError::InvalidOperation {
+            operation: "rekey".to_string(),
+            reason: "Rekey requires an identity file or SSH key".to_string(),
+        }),
+    }
+}
+
+/// Parse an age (`age1...`) or SSH public key recipient
+pub fn parse_recipient(recipient: &str) -> AgeResult<Box<dyn age::Recipient + Send>> {
+    let recipient = recipient.trim();
+    let parsed: Option<Box<dyn age::Recipient + Send>> = if recipient.starts_with("age1") {
+        age::x25519::Recipient::from_str(recipient)
+            .ok()
+            .map(|r| Box::new(r) as _)
+    } else {
+        age::ssh::Recipient::from_str(recipient)
+            .ok()
+            .map(|r| Box::new(r) as _)
+    };
+    parsed.ok_or_else(|| AgeError::ConfigurationError {
+        parameter: "recipient".to_string(),
+        value: recipient.to_string(),
+        reason: "Not a valid age or SSH public key".to_string(),
+    })
+}
+
+/// Public keys named by `recipients`, reading recipients files (`#` comments allowed)
+pub(crate) fn recipient_keys(recipients: &[Recipient]) -> AgeResult<Vec<String>> {
+    let mut keys = Vec::new();
+    for recipient in recipients {
+        match recipient {
+            Recipient::PublicKey(key) => keys.push(key.clone()),
+            Recipient::MultipleKeys(list) | Recipient::SshRecipients(list) => {
+                keys.extend(list.iter().cloned())
+            }
+            Recipient::RecipientsFile(path) => {
+                let contents = fs::read_to_string(path)
+                    .map_err(|e| AgeError::file_error("read", path.clone(), e))?;
+                keys.extend(
+                    contents
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                        .map(String::from),
+                );
+            }
+            Recipient::SelfRecipient => {
+                return Err(AgeError::AdapterNotImplemented(
+                    "Self recipient flow not yet implemented".into(),
+                ))
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Check every recipient with the age crate before any file is touched.
+///
+/// Each key is parsed, then a small probe is encrypted in memory to all of them, so a
+/// malformed key fails the whole operation up front instead of partway through a
+/// repository. Returns the number of recipients checked.
+pub fn preflight_recipients(recipients: &[Recipient]) -> AgeResult<usize> {
+    let keys = recipient_keys(recipients)?;
+    let mut parsed = Vec::with_capacity(keys.len());
+    let mut invalid = Vec::new();
+    for key in &keys {
+        match parse_recipient(key) {
+            Ok(recipient) => parsed.push(recipient),
+            Err(_) => invalid.push(key.as_str()),
+        }
+    }
+    if !invalid.is_empty() {
+        return Err(AgeError::ConfigurationError {
+            parameter: "recipient".to_string(),
+            value: invalid.join(", "),
+            reason: format!(
+                "{} of {} recipients are not valid age or SSH public keys",
+                invalid.len(),
+                keys.len()
+            ),
+        });
+    }
+    if parsed.is_empty() {
+        return Ok(0);
+    }
+
+    let probe_error = |reason: String| AgeError::InvalidOperation {
+        operation: "recipient_preflight".to_string(),
+        reason,
+    };
+    let encryptor = age::Encryptor::with_recipients(
+        parsed
+            .iter()
+            .map(|recipient| recipient.as_ref() as &dyn age::Recipient),
+    )
+    .map_err(|e| probe_error(e.to_string()))?;
+    encryptor
+        .wrap_output(io::sink())
+        .and_then(|mut writer| {
+            writer.write_all(b"cage recipient preflight")?;
+            writer.finish()
+        })
+        .map_err(|e| probe_error(e.to_string()))?;
+    Ok(parsed.len())
+}
+
+/// Add `recipients` to the age file at `path`, re-writing only the header.
+///
+/// The payload is streamed through unchanged; armored input stays armored. The result
+/// is staged beside `target` and renamed into place, so `target` may equal `path`.
+pub fn add_recipients(
+    path: &Path,
+    target: &Path,
+    identities: &[Box<dyn age::Identity>],
+    recipients: &[Box<dyn age::Recipient + Send>],
+) -> AgeResult<()> {
+    let read_error = |e: io::Error| AgeError::file_error("read", path.to_path_buf(), e);
+
+    let mut input = BufReader::new(fs::File::open(path).map_err(read_error)?);
+    let armored = input
+        .fill_buf()
+        .map_err(read_error)?
+        .starts_with(ARMOR_BEGIN);
+    let mut reader = BufReader::new(age::armor::ArmoredReader::new(input));
+
+    let header = AgeHeader::read_from(&mut reader)?;
+    if header.is_passphrase() {
+        return Err(AgeError::InvalidOperation {
+            operation: "rekey".to_string(),
+            reason: format!(
+                "{} is passphrase-encrypted and cannot gain recipients; use rotate",
+                path.display()
+            ),
+        });
+    }
+    let file_key = header.unwrap_file_key(identities)?;
+
+    let mut stanzas: Vec<Stanza> = header
+        .stanzas()
+        .iter()
+        .map(|s| Stanza {
+            tag: s.tag.clone(),
+            args: s.args.clone(),
+            body: s.body.clone(),
+        })
+        .collect();
+    for recipient in recipients {
+        let (wrapped, _labels) =
+            recipient
+                .wrap_file_key(&file_key)
+                .map_err(|e| AgeError::EncryptionFailed {
+                    input: path.to_path_buf(),
+                    output: target.to_path_buf(),
+                    reason: e.to_string(),
+                })?;
+        stanzas.extend(wrapped);
+    }
+    let rewritten = AgeHeader::new(&file_key, stanzas);
+
+    let parent = target
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let staged = NamedTempFile::new_in(parent)
+        .map_err(|e| AgeError::file_error("create_temp", parent.to_path_buf(), e))?;
+    let format = if armored {
+        age::armor::Format::AsciiArmor
+    } else {
+        age::armor::Format::Binary
+    };
+    let staged = age::armor::ArmoredWriter::wrap_output(staged, format)
+        .and_then(|mut writer| {
+            rewritten.write_to(&mut writer)?;
+            io::copy(&mut reader, &mut writer)?;
+            writer.finish()
+        })
+        .map_err(|e| AgeError::file_error("rekey", target.to_path_buf(), e))?;
+    staged
+        .persist(target)
+        .map_err(|e| AgeError::file_error("persist", target.to_path_buf(), e.error))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::x25519;
+    use tempfile::TempDir;
+
+    fn encrypt_to(recipient: &x25519::Recipient, plaintext: &[u8]) -> Vec<u8> {
+        let encryptor =
+            age::Encryptor::with_recipients(std::iter::once(recipient as &dyn age::Recipient))
+                .unwrap();
+        let mut out = Vec::new();
+        let mut writer = encryptor.wrap_output(&mut out).unwrap();
+        writer.write_all(plaintext).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    fn decrypt_with(identity: &x25519::Identity, ciphertext: &[u8]) -> Vec<u8> {
+        let decryptor = age::Decryptor::new(ciphertext).unwrap();
+        let mut reader = decryptor
+            .decrypt(std::iter::once(identity as &dyn age::Identity))
+            .unwrap();
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        out
+    }
+
+    fn payload(mut bytes: &[u8]) -> &[u8] {
+        AgeHeader::read_from(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_header_format_detection() {
+        let recipient = x25519::Identity::generate().to_public();
+        let binary = encrypt_to(&recipient, b"data");
+        assert_eq!(HeaderFormat::detect(&binary), Some(HeaderFormat::Binary));
+        assert_eq!(
+            HeaderFormat::detect(b"-----BEGIN AGE ENCRYPTED FILE-----\nYWdl"),
+            Some(HeaderFormat::Armored)
+        );
+        assert_eq!(HeaderFormat::detect(b"age-encr"), None);
+        assert_eq!(HeaderFormat::detect(b"plain text"), None);
+    }
+
+    #[test]
+    fn test_read_file_parses_binary_and_armored() {
+        use age::armor::{ArmoredWriter, Format};
+
+        let temp = TempDir::new().unwrap();
+        let recipient = x25519::Identity::generate().to_public();
+        let binary = encrypt_to(&recipient, b"data");
+        let mut armored = Vec::new();
+        let mut writer = ArmoredWriter::wrap_output(&mut armored, Format::AsciiArmor).unwrap();
+        writer.write_all(&binary).unwrap();
+        writer.finish().unwrap();
+
+        for (name, bytes) in [("binary.age", &binary), ("armored.age", &armored)] {
+            let path = temp.path().join(name);
+            fs::write(&path, bytes).unwrap();
+            let header = AgeHeader::read_file(&path).unwrap();
+            assert_eq!(header.stanza_tags(), vec!["X25519"]);
+            assert_eq!(header.mac().len(), 32);
+            assert!(!header.is_passphrase());
+        }
+
+        let plain = temp.path().join("plain.txt");
+        fs::write(&plain, b"age-encryption.org/v1\nnot a stanza\n").unwrap();
+        assert!(AgeHeader::read_file(&plain).is_err());
+    }
+
+    #[test]
+    fn test_read_from_rejects_damaged_headers_without_panicking() {
+        let recipient = x25519::Identity::generate().to_public();
+        let file = encrypt_to(&recipient, b"data");
+        let header_len = file.len() - payload(&file).len();
+
+        // Every truncation inside the header is an error
+        for len in 0..header_len {
+            assert!(AgeHeader::read_from(&mut &file[..len]).is_err());
+        }
+
+        // Random byte flips may parse or fail, but never panic or run away
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..2_000 {
+            let mut mutated = file[..header_len].to_vec();
+            for _ in 0..1 + state % 4 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let index = (state % header_len as u64) as usize;
+                mutated[index] = (state >> 32) as u8;
+            }
+            let _ = AgeHeader::read_from(&mut mutated.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_add_recipients_preserves_payload() {
+        let temp = TempDir::new().unwrap();
+        let owner = x25519::Identity::generate();
+        let newcomer = x25519::Identity::generate();
+        let plaintext = vec![7u8; 200_000];
+
+        let path = temp.path().join("data.age");
+        let original = encrypt_to(&owner.to_public(), &plaintext);
+        fs::write(&path, &original).unwrap();
+
+        let identities: Vec<Box<dyn age::Identity>> = vec![Box::new(owner.clone())];
+        let recipients = vec![parse_recipient(&newcomer.to_public().to_string()).unwrap()];
+        add_recipients(&path, &path, &identities, &recipients).unwrap();
+
+        let rekeyed = fs::read(&path).unwrap();
+        assert_eq!(decrypt_with(&owner, &rekeyed), plaintext);
+        assert_eq!(decrypt_with(&newcomer, &rekeyed), plaintext);
+
+        // Only the header changed
+        assert_eq!(payload(&rekeyed), payload(&original));
+    }
+
+    #[test]
+    fn test_preflight_recipients() {
+        let temp = TempDir::new().unwrap();
+        let key = x25519::Identity::generate().to_public().to_string();
+        let file = temp.path().join("recipients.txt");
+        fs::write(&file, format!("# team\n{}\n\n", key)).unwrap();
+
+        let valid = vec![
+            Recipient::PublicKey(key.clone()),
+            Recipient::RecipientsFile(file),
+        ];
+        assert_eq!(preflight_recipients(&valid).unwrap(), 2);
+
+        let invalid = vec![Recipient::MultipleKeys(vec![key, "age1typo".to_string()])];
+        match preflight_recipients(&invalid) {
+            Err(AgeError::ConfigurationError { value, .. }) => assert_eq!(value, "age1typo"),
+            other => panic!("expected configuration error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_add_recipients_requires_authorized_identity() {
+        let temp = TempDir::new().unwrap();
+        let owner = x25519::Identity::generate();
+        let stranger = x25519::Identity::generate();
+        let path = temp.path().join("data.age");
+        fs::write(&path, encrypt_to(&owner.to_public(), b"secret")).unwrap();
+
+        let identities: Vec<Box<dyn age::Identity>> = vec![Box::new(stranger.clone())];
+        let recipients = vec![parse_recipient(&stranger.to_public().to_string()).unwrap()];
+        assert!(add_recipients(&path, &path, &identities, &recipients).is_err());
+    }
+}
@@ -0,0 +1,435 @@
+//! Pluggable Identity Providers
+//!
+//! Identity providers fetch an age identity or passphrase at operation time from an
+//! external source instead of requiring the secret up front. Providers are addressed by
+//! URI, either directly via `Identity::Provider("vault://...")` or through a named alias
+//! declared in the `[identity_providers]` config table.
+//!
+//! Supported schemes:
+//! - `exec:<command> [args...]` - run a command (no shell; quotes group arguments) and read
+//!   the secret from stdout
+//! - `env:<VAR>` - read the secret from an environment variable
+//! - `fd:<N>` - read the secret from an inherited file descriptor (Unix)
+//! - `vault://<path>#<field>` - `vault kv get -field=<field> <path>`
+//! - `awskms://<ciphertext-file>` - `aws kms decrypt` of a ciphertext blob
+//! - `gcpkms://<key-resource>?ciphertext=<file>` - `gcloud kms decrypt`
+//!
+//! Secrets beginning with `AGE-SECRET-KEY-` are treated as age identities and staged in a
+//! private temporary identity file that lives as long as the `ResolvedIdentity`.
+
+use crate::core::{AgeConfig, Identity};
+use crate::error::{AgeError, AgeResult};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tempfile::NamedTempFile;
+
+/// Prefix identifying raw age identities returned by a provider
+const AGE_SECRET_KEY_PREFIX: &str = "AGE-SECRET-KEY-";
+
+/// Source of identities or passphrases resolved at operation time
+pub trait IdentityProvider: Send + Sync {
+    /// URI scheme handled by this provider (e.g. "vault")
+    fn scheme(&self) -> &'static str;
+
+    /// Fetch the raw secret referenced by `uri`
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String>;
+}
+
+/// Identity produced by a provider, keeping any staged identity file alive
+#[derive(Debug)]
+pub struct ResolvedIdentity {
+    identity: Identity,
+    _staged: Option<NamedTempFile>,
+}
+
+impl ResolvedIdentity {
+    /// Wrap an identity that needed no resolution
+    pub fn passthrough(identity: Identity) -> Self {
+        Self {
+            identity,
+            _staged: None,
+        }
+    }
+
+    /// Access the concrete identity
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+}
+
+/// Registry of identity providers keyed by URI scheme
+pub struct IdentityProviderRegistry {
+    providers: Vec<Box<dyn IdentityProvider>>,
+    aliases: std::collections::HashMap<String, String>,
+}
+
+impl IdentityProviderRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+            aliases: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Registry with the built-in providers and aliases from configuration
+    pub fn from_config(config: &AgeConfig) -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(ExecIdentityProvider));
+        registry.register(Box::new(EnvIdentityProvider));
+        registry.register(Box::new(FdIdentityProvider));
+        registry.register(Box::new(VaultIdentityProvider));
+        registry.register(Box::new(AwsKmsIdentityProvider));
+        registry.register(Box::new(GcpKmsIdentityProvider));
+        registry.aliases = config.identity_providers.clone();
+        registry
+    }
+
+    /// Register an additional provider (replaces any provider with the same scheme)
+    pub fn register(&mut self, provider: Box<dyn IdentityProvider>) {
+        self.providers.retain(|p| p.scheme() != provider.scheme());
+        self.providers.push(provider);
+    }
+
+    /// Resolve `Identity::Provider` references; other identities pass through unchanged
+    pub fn resolve(&self, identity: &Identity) -> AgeResult<ResolvedIdentity> {
+        let reference = match identity {
+            Identity::Provider(reference) => reference,
+            other => return Ok(ResolvedIdentity::passthrough(other.clone())),
+        };
+
+        let uri = self
+            .aliases
+            .get(reference)
+            .map(String::as_str)
+            .unwrap_or(reference);
+        let scheme = uri_scheme(uri).ok_or_else(|| AgeError::ConfigurationError {
+            parameter: "identity_provider".to_string(),
+            value: reference.clone(),
+            reason: "Provider reference is neither a URI nor a configured alias".to_string(),
+        })?;
+
+        let provider = self
+            .providers
+            .iter()
+            .find(|p| p.scheme() == scheme)
+            .ok_or_else(|| AgeError::ConfigurationError {
+                parameter: "identity_provider".to_string(),
+                value: scheme.to_string(),
+                reason: "No identity provider registered for scheme".to_string(),
+            })?;
+
+        let secret = provider.fetch_secret(uri)?;
+        let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+        if secret.is_empty() {
+            return Err(AgeError::PassphraseError {
+                message: format!("Identity provider '{}' returned an empty secret", scheme),
+            });
+        }
+
+        if secret.starts_with(AGE_SECRET_KEY_PREFIX) {
+            let staged = stage_identity_file(&secret)?;
+            let identity = Identity::IdentityFile(staged.path().to_path_buf());
+            return Ok(ResolvedIdentity {
+                identity,
+                _staged: Some(staged),
+            });
+        }
+
+        Ok(ResolvedIdentity::passthrough(Identity::Passphrase(secret)))
+    }
+}
+
+impl Default for IdentityProviderRegistry {
+    fn default() -> Self {
+        Self::from_config(&AgeConfig::default())
+    }
+}
+
+/// `exec:` provider - runs a command without a shell and reads stdout
+pub struct ExecIdentityProvider;
+
+impl IdentityProvider for ExecIdentityProvider {
+    fn scheme(&self) -> &'static str {
+        "exec"
+    }
+
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String> {
+        let command_line = uri.strip_prefix("exec:").unwrap_or(uri);
+        run_secret_command(&crate::passphrase::split_command_line(command_line)?)
+    }
+}
+
+/// `env:` provider - reads the secret from an environment variable
+pub struct EnvIdentityProvider;
+
+impl IdentityProvider for EnvIdentityProvider {
+    fn scheme(&self) -> &'static str {
+        "env"
+    }
+
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String> {
+        let var = uri.strip_prefix("env:").unwrap_or(uri);
+        std::env::var(var).map_err(|_| AgeError::ConfigurationError {
+            parameter: "identity_provider".to_string(),
+            value: uri.to_string(),
+            reason: format!("Environment variable {} is not set", var),
+        })
+    }
+}
+
+/// `fd:` provider - reads the secret from a file descriptor handed down by the caller
+pub struct FdIdentityProvider;
+
+impl IdentityProvider for FdIdentityProvider {
+    fn scheme(&self) -> &'static str {
+        "fd"
+    }
+
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String> {
+        let fd: u32 = uri
+            .strip_prefix("fd:")
+            .unwrap_or(uri)
+            .parse()
+            .map_err(|_| AgeError::ConfigurationError {
+                parameter: "identity_provider".to_string(),
+                value: uri.to_string(),
+                reason: "Expected fd:<number>".to_string(),
+            })?;
+
+        let path = format!("/dev/fd/{}", fd);
+        std::fs::read_to_string(&path)
+            .map_err(|e| AgeError::file_error("read", std::path::PathBuf::from(path), e))
+    }
+}
+
+/// `vault://` provider - reads a KV field through the Vault CLI
+pub struct VaultIdentityProvider;
+
+impl IdentityProvider for VaultIdentityProvider {
+    fn scheme(&self) -> &'static str {
+        "vault"
+    }
+
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String> {
+        let target = uri.strip_prefix("vault://").unwrap_or(uri);
+        let (path, field) = target.split_once('#').unwrap_or((target, "identity"));
+        run_secret_command(&[
+            "vault".to_string(),
+            "kv".to_string(),
+            "get".to_string(),
+            format!("-field={}", field),
+            path.to_string(),
+        ])
+    }
+}
+
+/// `awskms://` provider - decrypts a KMS ciphertext blob through the AWS CLI
+pub struct AwsKmsIdentityProvider;
+
+impl IdentityProvider for AwsKmsIdentityProvider {
+    fn scheme(&self) -> &'static str {
+        "awskms"
+    }
+
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String> {
+        let blob = uri.strip_prefix("awskms://").unwrap_or(uri);
+        let encoded = run_secret_command(&[
+            "aws".to_string(),
+            "kms".to_string(),
+            "decrypt".to_string(),
+            "--ciphertext-blob".to_string(),
+            format!("fileb://{}", blob),
+            "--output".to_string(),
+            "text".to_string(),
+            "--query".to_string(),
+            "Plaintext".to_string(),
+        ])?;
+
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        let decoded = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| AgeError::InvalidOperation {
+                operation: "awskms_decrypt".to_string(),
+                reason: format!("KMS plaintext is not valid base64: {}", e),
+            })?;
+        String::from_utf8(decoded).map_err(|_| AgeError::InvalidOperation {
+            operation: "awskms_decrypt".to_string(),
+            reason: "KMS plaintext is not valid UTF-8".to_string(),
+        })
+    }
+}
+
+/// `gcpkms://` provider - decrypts a ciphertext file through the gcloud CLI
+pub struct GcpKmsIdentityProvider;
+
+impl IdentityProvider for GcpKmsIdentityProvider {
+    fn scheme(&self) -> &'static str {
+        "gcpkms"
+    }
+
+    fn fetch_secret(&self, uri: &str) -> AgeResult<String> {
+        let target = uri.strip_prefix("gcpkms://").unwrap_or(uri);
+        let (key, ciphertext) =
+            target
+                .split_once("?ciphertext=")
+                .ok_or_else(|| AgeError::ConfigurationError {
+                    parameter: "identity_provider".to_string(),
+                    value: uri.to_string(),
+                    reason: "Expected gcpkms://<key-resource>?ciphertext=<file>".to_string(),
+                })?;
+        run_secret_command(&[
+            "gcloud".to_string(),
+            "kms".to_string(),
+            "decrypt".to_string(),
+            format!("--key={}", key),
+            format!("--ciphertext-file={}", ciphertext),
+            "--plaintext-file=-".to_string(),
+        ])
+    }
+}
+
+/// Extract the scheme of a provider URI (`exec:`, `env:`, `vault://`, ...)
+fn uri_scheme(uri: &str) -> Option<&str> {
+    let (scheme, _) = uri.split_once(':')?;
+    if scheme.is_empty() || !scheme.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some(scheme)
+}
+
+/// Run a secret-producing command directly (no shell) and capture stdout
+pub(crate) fn run_secret_command(argv: &[String]) -> AgeResult<String> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| AgeError::InvalidOperation {
+            operation: "secret_command".to_string(),
+            reason: "Secret command is empty".to_string(),
+        })?;
+
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| AgeError::ProcessExecutionFailed {
+            command: program.clone(),
+            exit_code: None,
+            stderr: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(AgeError::ProcessExecutionFailed {
+            command: program.clone(),
+            exit_code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| AgeError::InvalidOperation {
+        operation: "secret_command".to_string(),
+        reason: format!("Output of '{}' is not valid UTF-8", program),
+    })
+}
+
+/// Write an age identity to a private temporary file
+fn stage_identity_file(secret: &str) -> AgeResult<NamedTempFile> {
+    let mut staged = NamedTempFile::new().map_err(|e| AgeError::TemporaryResourceError {
+        resource_type: "file".to_string(),
+        operation: "stage_identity".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(staged.path(), std::fs::Permissions::from_mode(0o600));
+    }
+
+    writeln!(staged, "{}", secret)
+        .and_then(|_| staged.flush())
+        .map_err(|e| AgeError::file_error("stage_identity", staged.path().to_path_buf(), e))?;
+    Ok(staged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passthrough_identity() {
+        let registry = IdentityProviderRegistry::default();
+        let resolved = registry
+            .resolve(&Identity::Passphrase("secret".to_string()))
+            .unwrap();
+        assert!(matches!(resolved.identity(), Identity::Passphrase(p) if p == "secret"));
+    }
+
+    #[test]
+    fn test_env_provider_and_alias() {
+        std::env::set_var("CAGE_TEST_PROVIDER_SECRET", "from-env\n");
+        let mut config = AgeConfig::default();
+        config.identity_providers.insert(
+            "ci".to_string(),
+            "env:CAGE_TEST_PROVIDER_SECRET".to_string(),
+        );
+        let registry = IdentityProviderRegistry::from_config(&config);
+
+        let resolved = registry
+            .resolve(&Identity::Provider("ci".to_string()))
+            .unwrap();
+        assert!(matches!(resolved.identity(), Identity::Passphrase(p) if p == "from-env"));
+    }
+
+    #[test]
+    fn test_exec_provider_stages_age_identity() {
+        let registry = IdentityProviderRegistry::default();
+        let resolved = registry
+            .resolve(&Identity::Provider(
+                "exec:echo AGE-SECRET-KEY-1TESTONLY".to_string(),
+            ))
+            .unwrap();
+        match resolved.identity() {
+            Identity::IdentityFile(path) => {
+                let contents = std::fs::read_to_string(path).unwrap();
+                assert_eq!(contents.trim(), "AGE-SECRET-KEY-1TESTONLY");
+            }
+            other => panic!("expected staged identity file, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_exec_provider_honors_quoting() {
+        let registry = IdentityProviderRegistry::default();
+        let resolved = registry
+            .resolve(&Identity::Provider(
+                "exec:printf '%s' 'two  spaced words'".to_string(),
+            ))
+            .unwrap();
+        assert!(matches!(
+            resolved.identity(),
+            Identity::Passphrase(secret) if secret == "two  spaced words"
+        ));
+    }
+
+    #[test]
+    fn test_unknown_reference_rejected() {
+        let registry = IdentityProviderRegistry::default();
+        assert!(registry
+            .resolve(&Identity::Provider("missing-alias".to_string()))
+            .is_err());
+        assert!(registry
+            .resolve(&Identity::Provider("s3://bucket/key".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_fd_provider_rejects_non_numeric() {
+        let registry = IdentityProviderRegistry::default();
+        let err = registry
+            .resolve(&Identity::Provider("fd:stdin".to_string()))
+            .unwrap_err();
+        assert!(matches!(err, AgeError::ConfigurationError { .. }));
+    }
+}
@@ -0,0 +1,123 @@
+//! Interrupt - Graceful SIGINT/SIGTERM handling for long-running operations
+//!
+//! `install` replaces the default handlers so the first Ctrl-C (or SIGTERM) only records
+//! the signal and terminates the `age` children registered with `register_child`. The
+//! file in flight then fails and is rolled back by its normal error path, repository loops
+//! stop before the next file and report the rest as failed, and the CLI writes that list
+//! for a resumed run. A second signal exits immediately without cleanup.
+
+use crate::error::{AgeError, AgeResult};
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// Signal that interrupted the process; 0 while running normally
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// Child processes terminated on interrupt; a slot holding 0 is free
+const MAX_CHILDREN: usize = 64;
+static CHILDREN: [AtomicI32; MAX_CHILDREN] = [const { AtomicI32::new(0) }; MAX_CHILDREN];
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    // Only async-signal-safe work here: atomics, kill(2) and _exit(2)
+    if SIGNAL.swap(signal, Ordering::SeqCst) != 0 {
+        unsafe { libc::_exit(128 + signal) };
+    }
+    for slot in &CHILDREN {
+        let pid = slot.load(Ordering::SeqCst);
+        if pid > 0 {
+            unsafe { libc::kill(pid, libc::SIGTERM) };
+        }
+    }
+}
+
+/// Handle SIGINT and SIGTERM as described in the module docs (no-op off Unix)
+pub fn install() {
+    #[cfg(unix)]
+    unsafe {
+        let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+/// Signal number received since `install`, if any
+pub fn interrupted() -> Option<i32> {
+    match SIGNAL.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+/// `Err(AgeError::Interrupted)` once a signal has been received
+pub fn check(operation: &str) -> AgeResult<()> {
+    match interrupted() {
+        Some(signal) => Err(AgeError::Interrupted {
+            operation: operation.to_string(),
+            signal,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Forget a handled interrupt, for services that keep running after cancelling a job
+pub fn clear() {
+    SIGNAL.store(0, Ordering::SeqCst);
+}
+
+/// Registration of a child process; unregisters on drop
+#[derive(Debug)]
+pub struct ChildRegistration {
+    slot: Option<usize>,
+}
+
+impl Drop for ChildRegistration {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot {
+            CHILDREN[slot].store(0, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Terminate process `pid` if the operation is interrupted while the guard is alive.
+///
+/// Registration is best effort: with every slot taken the child is simply not tracked.
+pub fn register_child(pid: u32) -> ChildRegistration {
+    let Ok(pid) = i32::try_from(pid) else {
+        return ChildRegistration { slot: None };
+    };
+    let slot = CHILDREN.iter().position(|slot| {
+        slot.compare_exchange(0, pid, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    });
+    ChildRegistration { slot }
+}
+
+/// `Command::status` with the child registered for termination on interrupt
+pub fn status_tracked(command: &mut Command) -> std::io::Result<ExitStatus> {
+    let mut child = command.spawn()?;
+    let _registration = register_child(child.id());
+    child.wait()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registered(pid: i32) -> usize {
+        CHILDREN
+            .iter()
+            .filter(|slot| slot.load(Ordering::SeqCst) == pid)
+            .count()
+    }
+
+    #[test]
+    fn test_child_registration_frees_slot() {
+        let pid = i32::MAX - 7;
+        let registration = register_child(pid as u32);
+        assert!(registration.slot.is_some());
+        assert_eq!(registered(pid), 1);
+        drop(registration);
+        assert_eq!(registered(pid), 0);
+    }
+}
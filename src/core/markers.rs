@@ -0,0 +1,186 @@
+//! Lock Markers - Idempotency state for repeated lock runs
+//!
+//! After a successful `skip_existing` lock, Cage records the source file's size, mtime
+//! and SHA-256 together with the lock key (recipients and output format) in a marker
+//! keyed by the encrypted output path. A later `skip_existing` run skips files whose
+//! source and key both still match, so changing recipients or format re-encrypts.
+//!
+//! Markers live outside the repository so they are never picked up by traversal:
+//! `$CAGE_MARKER_DIR`, else `$XDG_DATA_HOME/cage/markers`, else
+//! `~/.local/share/cage/markers`.
+
+use crate::error::{AgeError, AgeResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Source fingerprint recorded when a file was locked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockMarker {
+    pub source: PathBuf,
+    pub size: u64,
+    pub mtime_ns: u128,
+    pub sha256: String,
+    /// What the output was encrypted to (`LockOptions::encryption_key` or the format)
+    #[serde(default)]
+    pub key: String,
+}
+
+/// Directory of lock markers keyed by output path
+#[derive(Debug, Clone)]
+pub struct MarkerStore {
+    root: PathBuf,
+}
+
+impl MarkerStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Default store location, or `None` when no home directory can be determined
+    pub fn from_env() -> Option<Self> {
+        if let Ok(dir) = std::env::var("CAGE_MARKER_DIR") {
+            if !dir.is_empty() {
+                return Some(Self::new(PathBuf::from(dir)));
+            }
+        }
+        let base = match std::env::var("XDG_DATA_HOME") {
+            Ok(xdg) if !xdg.is_empty() => PathBuf::from(xdg),
+            _ => PathBuf::from(std::env::var("HOME").ok()?).join(".local/share"),
+        };
+        Some(Self::new(base.join("cage").join("markers")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Marker file for an encrypted output path
+    pub fn marker_path(&self, output: &Path) -> PathBuf {
+        let absolute = std::path::absolute(output).unwrap_or_else(|_| output.to_path_buf());
+        let key = Sha256::digest(absolute.to_string_lossy().as_bytes());
+        self.root.join(format!("{:x}.json", key))
+    }
+
+    /// Load the marker recorded for `output`, if any
+    pub fn load(&self, output: &Path) -> Option<LockMarker> {
+        let data = fs::read(self.marker_path(output)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Record `source`, encrypted under `key`, as the current plaintext behind `output`
+    pub fn record(&self, source: &Path, output: &Path, key: &str) -> AgeResult<()> {
+        let marker = fingerprint(source, key)?;
+        fs::create_dir_all(&self.root)
+            .map_err(|e| AgeError::file_error("create_marker_dir", self.root.clone(), e))?;
+        let path = self.marker_path(output);
+        let json = serde_json::to_vec(&marker).map_err(|e| AgeError::InvalidOperation {
+            operation: "record_marker".to_string(),
+            reason: e.to_string(),
+        })?;
+        fs::write(&path, json).map_err(|e| AgeError::file_error("write_marker", path, e))
+    }
+
+    /// Whether `output` exists and was produced under `key` from the current contents of
+    /// `source`.
+    ///
+    /// Size and mtime are the fast path; if only the mtime moved, the content hash decides
+    /// (and the marker is refreshed so the next check is cheap again).
+    pub fn is_unchanged(&self, source: &Path, output: &Path, key: &str) -> AgeResult<bool> {
+        if !output.exists() {
+            return Ok(false);
+        }
+        let marker = match self.load(output) {
+            Some(marker) => marker,
+            None => return Ok(false),
+        };
+        if marker.key != key {
+            return Ok(false);
+        }
+
+        let meta =
+            fs::metadata(source).map_err(|e| AgeError::file_error("stat", source.into(), e))?;
+        if meta.len() != marker.size {
+            return Ok(false);
+        }
+        if mtime_ns(&meta) == marker.mtime_ns {
+            return Ok(true);
+        }
+
+        if sha256_file(source)? == marker.sha256 {
+            self.record(source, output, key)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+fn mtime_ns(meta: &fs::Metadata) -> u128 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+fn fingerprint(source: &Path, key: &str) -> AgeResult<LockMarker> {
+    let meta = fs::metadata(source).map_err(|e| AgeError::file_error("stat", source.into(), e))?;
+    Ok(LockMarker {
+        source: source.to_path_buf(),
+        size: meta.len(),
+        mtime_ns: mtime_ns(&meta),
+        sha256: sha256_file(source)?,
+        key: key.to_string(),
+    })
+}
+
+/// Hex SHA-256 of the contents of `path`
+pub fn sha256_file(path: &Path) -> AgeResult<String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_marker_detects_changes() {
+        let repo = TempDir::new().unwrap();
+        let store = MarkerStore::new(repo.path().join("markers"));
+        let source = repo.path().join("secret.txt");
+        let output = repo.path().join("secret.txt.cage");
+        fs::write(&source, "v1").unwrap();
+
+        // No output yet
+        assert!(!store.is_unchanged(&source, &output, "k1").unwrap());
+
+        fs::write(&output, "ciphertext").unwrap();
+        assert!(!store.is_unchanged(&source, &output, "k1").unwrap());
+
+        store.record(&source, &output, "k1").unwrap();
+        assert!(store.is_unchanged(&source, &output, "k1").unwrap());
+
+        // Same plaintext, different recipients or format
+        assert!(!store.is_unchanged(&source, &output, "k2").unwrap());
+
+        fs::write(&source, "version two").unwrap();
+        assert!(!store.is_unchanged(&source, &output, "k1").unwrap());
+    }
+}
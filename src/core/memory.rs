@@ -0,0 +1,136 @@
+//! Memory Budget for In-Flight Buffers
+//!
+//! Streaming buffer size, chunk size and worker parallelism are independent knobs; left
+//! unchecked, a parallel batch with large chunks can exhaust RAM. `MemoryBudget` caps the
+//! total buffer memory those components may hold at once. Components clamp their own
+//! buffers to the budget, and parallel workers reduce concurrency rather than exceed it.
+//!
+//! The budget comes from `AgeConfig::memory_budget` (`[limits] memory_budget = "512M"`)
+//! and can be overridden with `CAGE_MEMORY_BUDGET`. An override that does not parse is
+//! reported once and ignored, leaving the configured budget in force.
+
+use crate::core::scheduling::parse_byte_size;
+use crate::core::AgeConfig;
+use std::sync::Once;
+
+/// Smallest buffer any component is clamped down to
+pub const MIN_BUFFER_SIZE: usize = 4096;
+
+/// Total in-flight buffer memory allowed across stream, chunker and worker buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryBudget {
+    limit: Option<u64>,
+}
+
+impl MemoryBudget {
+    /// Budget of `limit` bytes; `None` means unlimited
+    pub fn new(limit: Option<u64>) -> Self {
+        Self { limit }
+    }
+
+    /// Unlimited budget
+    pub fn unlimited() -> Self {
+        Self { limit: None }
+    }
+
+    /// Build from config, honoring a `CAGE_MEMORY_BUDGET` override
+    pub fn from_config(config: &AgeConfig) -> Self {
+        let value = std::env::var("CAGE_MEMORY_BUDGET").unwrap_or_default();
+        Self::with_override(config, &value)
+    }
+
+    /// Budget from `value` when set and valid, otherwise from config
+    fn with_override(config: &AgeConfig, value: &str) -> Self {
+        static WARNED: Once = Once::new();
+        if value.is_empty() {
+            return Self::new(config.memory_budget);
+        }
+        match parse_byte_size(value) {
+            Ok(limit) => Self::new(Some(limit)),
+            Err(e) => {
+                WARNED.call_once(|| {
+                    tracing::warn!("Ignoring CAGE_MEMORY_BUDGET={:?}: {}", value, e);
+                });
+                Self::new(config.memory_budget)
+            }
+        }
+    }
+
+    /// Configured limit in bytes
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Clamp a streaming buffer so a single read/write pair fits the budget
+    pub fn clamp_buffer(&self, requested: usize) -> usize {
+        match self.limit {
+            Some(limit) => {
+                let per_buffer = usize::try_from(limit / 2).unwrap_or(usize::MAX);
+                requested.min(per_buffer).max(MIN_BUFFER_SIZE)
+            }
+            None => requested,
+        }
+    }
+
+    /// Clamp a chunk size so one in-memory chunk fits the budget
+    pub fn clamp_chunk(&self, requested: u64) -> u64 {
+        match self.limit {
+            Some(limit) => requested.min(limit).max(MIN_BUFFER_SIZE as u64),
+            None => requested,
+        }
+    }
+
+    /// Reduce `requested` parallelism so `per_worker` bytes per worker fit the budget.
+    ///
+    /// Always allows at least one worker; a single worker is expected to clamp its own
+    /// buffers with `clamp_buffer`/`clamp_chunk`.
+    pub fn max_parallelism(&self, requested: usize, per_worker: u64) -> usize {
+        let requested = requested.max(1);
+        match self.limit {
+            Some(limit) if per_worker > 0 => {
+                let fits = usize::try_from(limit / per_worker).unwrap_or(usize::MAX);
+                requested.min(fits).max(1)
+            }
+            _ => requested,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_budget_passes_through() {
+        let budget = MemoryBudget::unlimited();
+        assert_eq!(budget.clamp_buffer(1 << 30), 1 << 30);
+        assert_eq!(budget.clamp_chunk(1 << 40), 1 << 40);
+        assert_eq!(budget.max_parallelism(32, 1 << 30), 32);
+    }
+
+    #[test]
+    fn test_budget_clamps_and_degrades() {
+        let budget = MemoryBudget::new(Some(64 * 1024 * 1024));
+        assert_eq!(budget.clamp_buffer(1 << 30), 32 * 1024 * 1024);
+        assert_eq!(budget.clamp_buffer(16), MIN_BUFFER_SIZE);
+        assert_eq!(budget.clamp_chunk(1 << 30), 64 * 1024 * 1024);
+        assert_eq!(budget.max_parallelism(8, 16 * 1024 * 1024), 4);
+        assert_eq!(budget.max_parallelism(8, 1 << 30), 1);
+    }
+
+    #[test]
+    fn test_malformed_override_keeps_config_budget() {
+        let config = AgeConfig {
+            memory_budget: Some(512 * 1024 * 1024),
+            ..AgeConfig::default()
+        };
+        let kept = MemoryBudget::with_override(&config, "64MX");
+        assert_eq!(kept.limit(), Some(512 * 1024 * 1024));
+        let overridden = MemoryBudget::with_override(&config, "64M");
+        assert_eq!(overridden.limit(), Some(64 * 1024 * 1024));
+        assert_eq!(
+            MemoryBudget::with_override(&config, "").limit(),
+            config.memory_budget
+        );
+    }
+}
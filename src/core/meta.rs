@@ -0,0 +1,372 @@
+//! Meta - Self-describing sidecars recording who a file was encrypted to
+//!
+//! With `[metadata] sidecar = true` (or a request's `write_meta`), every lock writes
+//! `<output>.meta` next to the ciphertext: the recipients (with short fingerprints), the
+//! recipient groups and their `group_hash`, and when the file was encrypted. `cage status
+//! --verbose` reads it to answer "who can decrypt this?" without reconstructing config
+//! history. The sidecar is informational only; age never consults it.
+//!
+//! Sidecars are excluded from traversal, so they are neither locked nor counted as
+//! plaintext, and unlock removes them together with the ciphertext.
+
+use super::config::{AgeConfig, OutputFormat};
+use super::requests::{Identity, MultiRecipientConfig, Recipient, RecipientGroup};
+use crate::error::{AgeError, AgeResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extension appended to the ciphertext path for its sidecar
+pub const META_EXTENSION: &str = "meta";
+
+/// One recipient a file was encrypted to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetaRecipient {
+    /// Public key, `file:<path>` for a recipients file or `self`
+    pub recipient: String,
+    /// First 16 hex digits of the recipient's SHA-256
+    pub fingerprint: String,
+}
+
+/// Recipient group a file was encrypted to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetaGroup {
+    pub name: String,
+    pub group_hash: String,
+    /// Members at encryption time, so a rekey can drop exactly the removed ones
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+/// Contents of a `.meta` sidecar
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherMeta {
+    /// RFC 3339 time of encryption (empty until `write` stamps it)
+    pub encrypted_at: String,
+    /// Encrypted with a passphrase rather than recipients
+    pub passphrase: bool,
+    pub recipients: Vec<MetaRecipient>,
+    pub groups: Vec<MetaGroup>,
+}
+
+impl CipherMeta {
+    /// Description of a passphrase-encrypted file
+    pub fn passphrase() -> Self {
+        Self {
+            encrypted_at: String::new(),
+            passphrase: true,
+            recipients: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Description of a file encrypted to `recipients` and the groups of `multi`
+    pub fn for_recipients(recipients: &[Recipient], multi: Option<&MultiRecipientConfig>) -> Self {
+        let mut keys: Vec<String> = Vec::new();
+        let mut push = |key: String| {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        };
+        for recipient in recipients {
+            match recipient {
+                Recipient::PublicKey(key) => push(key.clone()),
+                Recipient::MultipleKeys(list) | Recipient::SshRecipients(list) => {
+                    list.iter().cloned().for_each(&mut push)
+                }
+                Recipient::RecipientsFile(path) => push(format!("file:{}", path.display())),
+                Recipient::SelfRecipient => push("self".to_string()),
+            }
+        }
+        let mut groups = Vec::new();
+        if let Some(multi) = multi {
+            multi.flatten_recipients().into_iter().for_each(&mut push);
+            groups = multi
+                .all_groups()
+                .into_iter()
+                .map(|group| MetaGroup {
+                    name: group.name.clone(),
+                    group_hash: group.group_hash(),
+                    recipients: group.recipients.clone(),
+                })
+                .collect();
+        }
+
+        Self {
+            encrypted_at: String::new(),
+            passphrase: false,
+            recipients: keys
+                .into_iter()
+                .map(|recipient| MetaRecipient {
+                    fingerprint: fingerprint(&recipient),
+                    recipient,
+                })
+                .collect(),
+            groups,
+        }
+    }
+
+    /// The same file re-encrypted to the current members of `group`.
+    ///
+    /// Members recorded for the group are replaced by its current ones; other recipients
+    /// and groups are kept. A sidecar that does not record the group's members (written
+    /// before members were recorded) cannot tell them from other recipients, so only the
+    /// members of its other recorded groups are kept besides the current ones.
+    pub fn with_group_membership(&self, group: &RecipientGroup) -> Self {
+        let previous = self
+            .groups
+            .iter()
+            .find(|g| g.name == group.name)
+            .map(|g| g.recipients.as_slice())
+            .filter(|members| !members.is_empty());
+        let others: Vec<&String> = self
+            .groups
+            .iter()
+            .filter(|g| g.name != group.name)
+            .flat_map(|g| &g.recipients)
+            .collect();
+        let mut keys: Vec<String> = self
+            .recipients
+            .iter()
+            .map(|r| r.recipient.clone())
+            .filter(|key| match previous {
+                Some(previous) => !previous.contains(key),
+                None => others.contains(&key),
+            })
+            .collect();
+        for key in &group.recipients {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+
+        let mut updated = Self::for_recipients(&[Recipient::MultipleKeys(keys)], None);
+        updated.groups = self
+            .groups
+            .iter()
+            .filter(|g| g.name != group.name)
+            .cloned()
+            .chain(std::iter::once(MetaGroup {
+                name: group.name.clone(),
+                group_hash: group.group_hash(),
+                recipients: group.recipients.clone(),
+            }))
+            .collect();
+        updated
+    }
+
+    /// Recipients to encrypt to again, reversing the `file:` and `self` notation
+    pub fn to_recipients(&self) -> Vec<Recipient> {
+        let mut keys = Vec::new();
+        let mut recipients = Vec::new();
+        for entry in &self.recipients {
+            match entry.recipient.as_str() {
+                "self" => recipients.push(Recipient::SelfRecipient),
+                other => match other.strip_prefix("file:") {
+                    Some(path) => recipients.push(Recipient::RecipientsFile(PathBuf::from(path))),
+                    None => keys.push(other.to_string()),
+                },
+            }
+        }
+        if !keys.is_empty() {
+            recipients.insert(0, Recipient::MultipleKeys(keys));
+        }
+        recipients
+    }
+
+    /// Write the sidecar for `output`, stamped with the current time
+    pub fn write(&self, output: &Path) -> AgeResult<PathBuf> {
+        let path = meta_path(output);
+        let stamped = Self {
+            encrypted_at: chrono::Utc::now().to_rfc3339(),
+            ..self.clone()
+        };
+        let json = serde_json::to_vec_pretty(&stamped).map_err(|e| AgeError::InvalidOperation {
+            operation: "write_meta".to_string(),
+            reason: e.to_string(),
+        })?;
+        fs::write(&path, json).map_err(|e| AgeError::file_error("write_meta", path.clone(), e))?;
+        Ok(path)
+    }
+
+    /// Sidecar recorded for `output`, if there is one
+    pub fn load(output: &Path) -> AgeResult<Option<Self>> {
+        let path = meta_path(output);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AgeError::file_error("read_meta", path, e)),
+        };
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| AgeError::InvalidOperation {
+                operation: "read_meta".to_string(),
+                reason: format!("{}: {}", path.display(), e),
+            })
+    }
+
+    /// Human description, e.g. for `cage status --verbose`
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![format!("Encrypted at: {}", self.encrypted_at)];
+        if self.passphrase {
+            lines.push("Decryptable with: passphrase".to_string());
+        }
+        for group in &self.groups {
+            lines.push(format!("Group: {} ({})", group.name, group.group_hash));
+        }
+        for recipient in &self.recipients {
+            lines.push(format!(
+                "Recipient: {} [{}]",
+                recipient.recipient, recipient.fingerprint
+            ));
+        }
+        lines
+    }
+}
+
+/// Sidecar path for a ciphertext `output` (`secret.txt.cage` -> `secret.txt.cage.meta`)
+pub fn meta_path(output: &Path) -> PathBuf {
+    let mut path = output.as_os_str().to_os_string();
+    path.push(".");
+    path.push(META_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// Whether `path` is the sidecar of an encrypted file
+pub fn is_sidecar(path: &Path, config: &AgeConfig) -> bool {
+    path.extension().is_some_and(|ext| ext == META_EXTENSION)
+        && config.is_encrypted_file(&path.with_extension(""))
+}
+
+/// Remove the sidecar of `output`, if any
+pub fn remove_sidecar(output: &Path) -> AgeResult<()> {
+    let path = meta_path(output);
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(AgeError::file_error("remove_meta", path, e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Short fingerprint of a recipient string
+pub fn fingerprint(recipient: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(recipient.as_bytes()));
+    digest[..16].to_string()
+}
+
+/// Fingerprint of what a lock encrypts to: the recipient set (in any order), the
+/// identity behind `self` recipients and the output format.
+///
+/// A recipients file contributes its current contents, so editing the file changes the
+/// key. Earlier output is only reused for a lock with the same key.
+pub fn recipients_key(
+    recipients: &[Recipient],
+    identity: &Identity,
+    format: OutputFormat,
+) -> String {
+    let mut entries = Vec::new();
+    for recipient in recipients {
+        match recipient {
+            Recipient::PublicKey(key) => entries.push(key.trim().to_string()),
+            Recipient::MultipleKeys(keys) | Recipient::SshRecipients(keys) => {
+                entries.extend(keys.iter().map(|key| key.trim().to_string()))
+            }
+            Recipient::RecipientsFile(path) => entries.push(format!(
+                "file:{}",
+                fs::read_to_string(path).unwrap_or_else(|_| path.display().to_string())
+            )),
+            Recipient::SelfRecipient => entries.push("self".to_string()),
+        }
+    }
+    entries.sort();
+    entries.dedup();
+    match identity {
+        Identity::IdentityFile(path) | Identity::SshKey(path) => {
+            entries.push(format!("identity:{}", path.display()))
+        }
+        _ => {}
+    }
+    entries.push(format!("format:{:?}", format));
+    fingerprint(&entries.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sidecar_round_trip_and_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("secret.txt.cage");
+        fs::write(&output, "ciphertext").unwrap();
+
+        let mut ops = RecipientGroup::new("ops".to_string());
+        ops.add_recipient("age1ops".to_string());
+        let multi = MultiRecipientConfig::new().add_group(ops);
+        let meta = CipherMeta::for_recipients(
+            &[Recipient::PublicKey("age1alice".to_string())],
+            Some(&multi),
+        );
+        let sidecar = meta.write(&output).unwrap();
+        assert_eq!(sidecar, temp_dir.path().join("secret.txt.cage.meta"));
+
+        let loaded = CipherMeta::load(&output).unwrap().unwrap();
+        assert!(!loaded.encrypted_at.is_empty());
+        let keys: Vec<&str> = loaded
+            .recipients
+            .iter()
+            .map(|r| r.recipient.as_str())
+            .collect();
+        assert_eq!(keys, vec!["age1alice", "age1ops"]);
+        assert_eq!(loaded.groups[0].name, "ops");
+
+        let config = AgeConfig::default();
+        assert!(is_sidecar(&sidecar, &config));
+        assert!(!is_sidecar(&temp_dir.path().join("notes.meta"), &config));
+
+        remove_sidecar(&output).unwrap();
+        assert!(CipherMeta::load(&output).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_group_membership_replaces_removed_members() {
+        let mut team = RecipientGroup::new("team".to_string());
+        team.add_recipient("age1bob".to_string());
+        team.add_recipient("age1mallory".to_string());
+        let multi = MultiRecipientConfig::new().add_group(team.clone());
+        let meta = CipherMeta::for_recipients(
+            &[Recipient::PublicKey("age1alice".to_string())],
+            Some(&multi),
+        );
+
+        team.recipients.retain(|r| r != "age1mallory");
+        team.add_recipient("age1carol".to_string());
+        let updated = meta.with_group_membership(&team);
+        let keys: Vec<&str> = updated
+            .recipients
+            .iter()
+            .map(|r| r.recipient.as_str())
+            .collect();
+        assert_eq!(keys, vec!["age1alice", "age1bob", "age1carol"]);
+        assert_eq!(updated.groups[0].group_hash, team.group_hash());
+        assert!(matches!(
+            updated.to_recipients().as_slice(),
+            [Recipient::MultipleKeys(list)] if list.len() == 3
+        ));
+
+        // A sidecar without recorded members cannot tell who was removed: only the
+        // current members (and other groups') keep access
+        let mut legacy = meta.clone();
+        legacy.groups[0].recipients.clear();
+        let updated = legacy.with_group_membership(&team);
+        let keys: Vec<&str> = updated
+            .recipients
+            .iter()
+            .map(|r| r.recipient.as_str())
+            .collect();
+        assert_eq!(keys, vec!["age1bob", "age1carol"]);
+    }
+}
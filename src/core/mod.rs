@@ -6,25 +6,95 @@
 //!
 //! # Submodules
 //!
+//! - `armor` - Size guardrails for ASCII armor and tail-only armor checks
+//! - `chunked` - Delta-friendly chunked containers for rsync/restic backups
 //! - `config` - Configuration types (AgeConfig, OutputFormat, SecurityLevel, etc.)
+//! - `config_check` - Schema validation of config files with positioned issues
+//! - `config_migrate` - Config schema versions and upgrades of older files
+//! - `config_seal` - HMAC seals detecting edits to the config and recipient groups
 //! - `requests` - Request structures for encryption operations (Lock, Unlock, Rotate, etc.)
 //! - `engine` - Age encryption engine automation interface
 //! - `recovery` - In-place operation recovery and safety validation
+//! - `interrupt` - SIGINT/SIGTERM cancellation and child process cleanup
+//! - `identity_provider` - Pluggable identity providers (exec, env, Vault, KMS)
+//! - `scheduling` - Throughput throttling and nice/ionice scheduling hints
+//! - `memory` - Memory budget bounding in-flight buffer memory
+//! - `permissions` - Explicit modes for created ciphertext and plaintext
+//! - `markers` - Lock markers for idempotent repeated lock runs
+//! - `mtime` - Modification-time windows (`--newer-than` / `--older-than`)
+//! - `meta` - `.meta` sidecars recording the recipients a file was encrypted to
+//! - `header` - Age file detection, header parsing and header-only recipient rewrapping
+//! - `plan` - Lock plans with per-path options for one invocation
+//! - `fleet` - Fleet manifests listing many repositories for `cage fleet`
+//! - `prompt` - Confirmation prompts routed through a pluggable `Prompter`
+//! - `groups` - Recipient groups persisted outside the config file
+//! - `roles` - Admin token gating for rekey, rotate and policy edits
+//! - `profile` - Named performance presets (laptop, server, io-limited)
 
+pub mod armor;
+pub mod chunked;
+pub mod compression;
 pub mod config;
+pub mod config_check;
+pub mod config_migrate;
+pub mod config_seal;
+#[cfg(feature = "deterministic")]
+pub mod deterministic;
 pub mod engine;
+pub mod fleet;
+pub mod groups;
+pub mod header;
+pub mod identity_provider;
+pub mod interrupt;
+pub mod markers;
+pub mod memory;
+pub mod meta;
+pub mod mtime;
+pub mod permissions;
+pub mod plan;
+pub mod profile;
+pub mod prompt;
 pub mod recovery;
 pub mod requests;
+pub mod roles;
+pub mod scheduling;
 
 // Re-export commonly used types
+pub use armor::ArmorPolicy;
+pub use chunked::{ChunkManifest, ChunkStats};
+pub use compression::CompressionWrap;
 pub use config::{
-    AgeConfig, OutputFormat, RetentionPolicyConfig, SecurityLevel, TelemetryFormat, TtyMethod,
+    AgeConfig, HardlinkPolicy, ListingOrder, OutputFormat, RetentionPolicyConfig, SecurityLevel,
+    TelemetryFormat, TtyMethod,
 };
+pub use config_check::{ConfigIssue, ConfigIssueKind};
+pub use config_migrate::{ConfigMigration, CONFIG_VERSION};
+pub use config_seal::{ConfigSeal, SealPolicy, SealStatus};
+#[cfg(feature = "deterministic")]
+pub use deterministic::DeterministicEncryptor;
 pub use engine::AgeAutomator;
-pub use recovery::{InPlaceOperation, InPlaceOptions, RecoveryManager, SafetyValidator};
+pub use fleet::{FleetCommand, FleetManifest, FleetRepo};
+pub use groups::{
+    export_recipients, parse_recipients, recipient_comment, set_recipient_comment, GroupStore,
+    RecipientsFile, GROUPS_FILE_ENV,
+};
+pub use header::{AgeHeader, HeaderFormat};
+pub use identity_provider::{IdentityProvider, IdentityProviderRegistry, ResolvedIdentity};
+pub use markers::{LockMarker, MarkerStore};
+pub use memory::MemoryBudget;
+pub use mtime::{parse_duration, MtimeWindow};
+pub use plan::{LockPlan, PlanEntry};
+pub use profile::PerformanceProfile;
+pub use prompt::{
+    default_prompter, is_non_interactive, require_interactive, Confirmation, DenyPrompter,
+    NonInteractivePrompter, Prompter, ScriptedPrompter, TtyPrompter, NON_INTERACTIVE_ENV,
+};
+pub use recovery::{
+    risky_path_reason, InPlaceOperation, InPlaceOptions, RecoveryManager, SafetyValidator,
+};
 pub use requests::{
-    AuthorityTier, BatchOperation, BatchRequest, CommonOptions, FromCliArgs, Identity,
+    AuthorityTier, BatchOperation, BatchOrder, BatchRequest, CommonOptions, FromCliArgs, Identity,
     LockRequest, MultiRecipientConfig, Recipient, RecipientGroup, ReportFormat, RotateRequest,
-    StatusRequest, StreamOperation, StreamRequest, ToOperationParams, UnlockRequest,
-    VerifyRequest,
+    StatusRequest, StreamOperation, StreamRequest, ToOperationParams, UnlockRequest, VerifyRequest,
 };
+pub use scheduling::{IoPriority, Throttle};
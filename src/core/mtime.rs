@@ -0,0 +1,107 @@
+//! Mtime - Modification-time windows for traversal filters
+//!
+//! `--newer-than` / `--older-than` take either a duration before now or an absolute
+//! time, so incremental runs (say, locking only last night's exports) need no glob
+//! gymnastics. The window is applied by `forge::FileFilter` to every file a traversal
+//! visits.
+
+use crate::error::{AgeError, AgeResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Modification-time bounds on the files a filter accepts (both exclusive)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtimeWindow {
+    /// Only files modified after this time
+    pub newer_than: Option<SystemTime>,
+    /// Only files modified before this time
+    pub older_than: Option<SystemTime>,
+}
+
+impl MtimeWindow {
+    pub fn is_unbounded(&self) -> bool {
+        self.newer_than.is_none() && self.older_than.is_none()
+    }
+
+    /// Whether `path` was modified inside the window (unreadable mtimes never are)
+    pub fn contains(&self, path: &Path) -> bool {
+        if self.is_unbounded() {
+            return true;
+        }
+        let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+            return false;
+        };
+        self.newer_than.is_none_or(|bound| modified > bound)
+            && self.older_than.is_none_or(|bound| modified < bound)
+    }
+
+    /// Parse a bound: a duration before `now` (`90s`, `30m`, `12h`, `7d`, `2w`), an RFC 3339
+    /// timestamp or a `YYYY-MM-DD` date (midnight UTC)
+    pub fn parse_bound(value: &str, now: SystemTime) -> AgeResult<SystemTime> {
+        let value = value.trim();
+        let invalid = || AgeError::ConfigurationError {
+            parameter: "mtime".to_string(),
+            value: value.to_string(),
+            reason: "Expected a duration like 7d or 12h, an RFC 3339 timestamp or YYYY-MM-DD"
+                .to_string(),
+        };
+
+        if value.ends_with(|c: char| c.is_ascii_alphabetic())
+            && value[..value.len() - 1].parse::<u64>().is_ok()
+        {
+            return parse_duration(value)
+                .and_then(|duration| now.checked_sub(duration))
+                .ok_or_else(invalid);
+        }
+        if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(value) {
+            return Ok(SystemTime::from(timestamp));
+        }
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|midnight| SystemTime::from(midnight.and_utc()))
+            .ok_or_else(invalid)
+    }
+}
+
+/// Parse a duration written as a count and unit: `90s`, `30m`, `12h`, `7d` or `2w`
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit = value.chars().last().filter(char::is_ascii_alphabetic)?;
+    let count = value[..value.len() - 1].parse::<u64>().ok()?;
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86_400,
+        'w' => 604_800,
+        _ => return None,
+    };
+    count.checked_mul(seconds).map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bound() {
+        let now = SystemTime::now();
+        assert_eq!(
+            MtimeWindow::parse_bound("7d", now).unwrap(),
+            now - Duration::from_secs(7 * 86_400)
+        );
+        assert_eq!(
+            MtimeWindow::parse_bound("90s", now).unwrap(),
+            now - Duration::from_secs(90)
+        );
+        let date = MtimeWindow::parse_bound("2026-01-31", now).unwrap();
+        let stamp = MtimeWindow::parse_bound("2026-01-31T00:00:00Z", now).unwrap();
+        assert_eq!(date, stamp);
+        assert!(MtimeWindow::parse_bound("7y", now).is_err());
+        assert!(MtimeWindow::parse_bound("yesterday", now).is_err());
+        assert_eq!(parse_duration("30m"), Some(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("30"), None);
+    }
+}
@@ -0,0 +1,210 @@
+//! Permissions - Explicit file modes for created ciphertext and plaintext
+//!
+//! Outputs normally inherit the ambient umask. When `[permissions] ciphertext_mode` /
+//! `plaintext_mode` (or a request's `output_mode`) is set, the output is created with that
+//! mode before any content is written, so it never exists with wider permissions. The
+//! mode is applied with `fchmod`, so the umask cannot narrow it either.
+//!
+//! With `preserve_ownership`, outputs and backups are handed back to the owner of the file
+//! they were made from, so root running over a user's tree does not leave root-owned files.
+//! Other users cannot give files away, so for them the setting is skipped with a single
+//! warning per process.
+
+use crate::error::{AgeError, AgeResult};
+use std::fs::File;
+use std::path::Path;
+
+/// Parse an octal file mode such as `0600`, `600` or `0o600`
+pub fn parse_mode(value: &str) -> AgeResult<u32> {
+    let trimmed = value.trim();
+    let digits = trimmed.strip_prefix("0o").unwrap_or(trimmed);
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if !digits.is_empty() && mode <= 0o7777 => Ok(mode),
+        _ => Err(AgeError::ConfigurationError {
+            parameter: "mode".to_string(),
+            value: value.to_string(),
+            reason: "Expected an octal mode such as 0600".to_string(),
+        }),
+    }
+}
+
+/// Create (or truncate) `path` for writing with `mode` applied before any data lands
+pub fn create_output(path: &Path, mode: Option<u32>) -> AgeResult<File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        if let Some(mode) = mode {
+            options.mode(mode);
+        }
+    }
+    let file = options
+        .open(path)
+        .map_err(|e| AgeError::file_error("create", path.to_path_buf(), e))?;
+    if let Some(mode) = mode {
+        apply_mode(&file, path, mode)?;
+    }
+    Ok(file)
+}
+
+/// Pre-create an empty `path` with `mode` for a writer that opens it by name (`age -o`)
+pub fn prepare_output(path: &Path, mode: Option<u32>) -> AgeResult<()> {
+    if mode.is_some() {
+        create_output(path, mode)?;
+    }
+    Ok(())
+}
+
+/// Set `mode` on an existing output, e.g. after an adapter replaced it via rename
+pub fn enforce_mode(path: &Path, mode: Option<u32>) -> AgeResult<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Some(mode) = mode {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+                .map_err(|e| AgeError::file_error("set_permissions", path.to_path_buf(), e))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+/// Owner of `path` as (uid, gid); `None` where ownership is not supported
+pub fn owner_of(path: &Path) -> AgeResult<Option<(u32, u32)>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let metadata = std::fs::symlink_metadata(path)
+            .map_err(|e| AgeError::file_error("read_metadata", path.to_path_buf(), e))?;
+        Ok(Some((metadata.uid(), metadata.gid())))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(None)
+    }
+}
+
+/// Give `path` the `owner` captured with `owner_of`; a no-op when it already matches
+pub fn set_owner(path: &Path, owner: Option<(u32, u32)>) -> AgeResult<()> {
+    #[cfg(unix)]
+    {
+        if let Some((uid, gid)) = owner {
+            if owner_of(path)? != Some((uid, gid)) {
+                std::os::unix::fs::chown(path, Some(uid), Some(gid))
+                    .map_err(|e| AgeError::file_error("chown", path.to_path_buf(), e))?;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (path, owner);
+    Ok(())
+}
+
+/// Give `target` the owner of `source`; skipped (with one warning per process) unless
+/// running as root
+pub fn copy_ownership(source: &Path, target: &Path) -> AgeResult<()> {
+    if !can_change_owner() {
+        static SKIPPED: std::sync::Once = std::sync::Once::new();
+        SKIPPED
+            .call_once(|| tracing::warn!("Not running as root; skipping ownership preservation"));
+        return Ok(());
+    }
+    set_owner(target, owner_of(source)?)
+}
+
+/// Whether this process may hand files to another owner (effective uid 0)
+pub fn can_change_owner() -> bool {
+    #[cfg(unix)]
+    {
+        unsafe { libc::geteuid() == 0 }
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+#[cfg(unix)]
+fn apply_mode(file: &File, path: &Path, mode: u32) -> AgeResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(std::fs::Permissions::from_mode(mode))
+        .map_err(|e| AgeError::file_error("set_permissions", path.to_path_buf(), e))
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_file: &File, _path: &Path, _mode: u32) -> AgeResult<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(parse_mode("0600").unwrap(), 0o600);
+        assert_eq!(parse_mode("644").unwrap(), 0o644);
+        assert_eq!(parse_mode("0o640").unwrap(), 0o640);
+        assert!(parse_mode("0800").is_err());
+        assert!(parse_mode("").is_err());
+        assert!(parse_mode("17777").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_output_ignores_umask_and_narrows_existing() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_dir = TempDir::new().unwrap();
+        let mode_of = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+
+        // Streaming outputs are created through `create_output`
+        let stream_out = temp_dir.path().join("stream.cage");
+        create_output(&stream_out, Some(0o644)).unwrap();
+        assert_eq!(mode_of(&stream_out), 0o644);
+
+        // A pre-existing world-readable file is narrowed before anything is written to it
+        let plaintext = temp_dir.path().join("secret.txt");
+        std::fs::write(&plaintext, "old").unwrap();
+        std::fs::set_permissions(&plaintext, std::fs::Permissions::from_mode(0o666)).unwrap();
+        prepare_output(&plaintext, Some(0o600)).unwrap();
+        assert_eq!(mode_of(&plaintext), 0o600);
+        assert_eq!(std::fs::metadata(&plaintext).unwrap().len(), 0);
+
+        enforce_mode(&plaintext, Some(0o640)).unwrap();
+        assert_eq!(mode_of(&plaintext), 0o640);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_ownership_between_own_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.txt");
+        let target = temp_dir.path().join("source.txt.cage");
+        std::fs::write(&source, "a").unwrap();
+        std::fs::write(&target, "b").unwrap();
+
+        // Same owner: succeeds with or without privileges
+        copy_ownership(&source, &target).unwrap();
+        assert_eq!(owner_of(&target).unwrap(), owner_of(&source).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_ownership_skipped_without_root() {
+        if can_change_owner() {
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("target.txt");
+        std::fs::write(&target, "b").unwrap();
+
+        // Would need chown to root's uid; a non-root run skips instead of failing
+        let before = owner_of(&target).unwrap();
+        copy_ownership(Path::new("/"), &target).unwrap();
+        assert_eq!(owner_of(&target).unwrap(), before);
+    }
+}
@@ -0,0 +1,233 @@
+//! Lock Plans - Per-path lock options for one invocation
+//!
+//! A plan file lists targets that each carry their own pattern, format and recipients,
+//! so `cage lock --plan plan.toml` can lock heterogeneous paths in a single audited run:
+//!
+//! ```toml
+//! [[entry]]
+//! path = "secrets/"
+//! recursive = true
+//! pattern = "*.env"
+//! group = "ops"
+//!
+//! [[entry]]
+//! path = "deploy/key.pem"
+//! format = "ascii"
+//! recipients = ["age1..."]
+//! ```
+//!
+//! Files ending in `.json` use the same structure as JSON. Relative paths are resolved
+//! against the plan file's directory. Entries without recipients or a group use the
+//! invocation's passphrase.
+
+use super::config::AgeConfig;
+use super::requests::{Identity, LockRequest, Recipient};
+use super::OutputFormat;
+use crate::error::{AgeError, AgeResult};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One target in a lock plan
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanEntry {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// `binary` (default) or `ascii`
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub recursive: bool,
+    /// age or SSH public keys
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    #[serde(default)]
+    pub recipients_file: Option<PathBuf>,
+    /// Recipient group from the config
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub backup: bool,
+}
+
+impl PlanEntry {
+    /// Whether this entry encrypts to public keys rather than a passphrase
+    pub fn has_recipients(&self) -> bool {
+        !self.recipients.is_empty() || self.recipients_file.is_some() || self.group.is_some()
+    }
+
+    fn format(&self) -> AgeResult<OutputFormat> {
+        match self.format.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("binary") => Ok(OutputFormat::Binary),
+            Some("ascii") | Some("armor") => Ok(OutputFormat::AsciiArmor),
+            Some(other) => Err(AgeError::ConfigurationError {
+                parameter: "plan.format".to_string(),
+                value: other.to_string(),
+                reason: "Valid values: binary, ascii".to_string(),
+            }),
+        }
+    }
+
+    /// Build the lock request for this entry; `identity` is used for passphrase entries
+    pub fn to_lock_request(
+        &self,
+        identity: &Identity,
+        config: &AgeConfig,
+    ) -> AgeResult<LockRequest> {
+        let mut recipients = Vec::new();
+        if !self.recipients.is_empty() {
+            recipients.push(Recipient::MultipleKeys(self.recipients.clone()));
+        }
+        if let Some(file) = &self.recipients_file {
+            recipients.push(Recipient::RecipientsFile(file.clone()));
+        }
+        if let Some(name) = &self.group {
+            let group = config
+                .get_recipient_group(name)
+                .filter(|group| !group.is_empty())
+                .ok_or_else(|| AgeError::ConfigurationError {
+                    parameter: "plan.group".to_string(),
+                    value: name.clone(),
+                    reason: "Recipient group not found or empty".to_string(),
+                })?;
+            recipients.push(Recipient::MultipleKeys(group.recipients.clone()));
+        }
+
+        let mut request = LockRequest::new(self.path.clone(), identity.clone())
+            .recursive(self.recursive)
+            .with_format(self.format()?);
+        request.backup = self.backup;
+        if let Some(pattern) = &self.pattern {
+            request = request.with_pattern(pattern.clone());
+        }
+        if !recipients.is_empty() {
+            request = request.with_recipients(recipients);
+        }
+        Ok(request)
+    }
+}
+
+/// Parsed lock plan
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockPlan {
+    #[serde(rename = "entry", alias = "entries")]
+    pub entries: Vec<PlanEntry>,
+    /// Plan file the entries were loaded from
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+impl LockPlan {
+    /// Load a TOML or JSON plan and resolve relative paths against its directory
+    pub fn load(path: &Path) -> AgeResult<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        let is_json = path.extension().is_some_and(|ext| ext == "json");
+        let parsed: Result<Self, String> = if is_json {
+            serde_json::from_str(&contents).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|e| e.to_string())
+        };
+        let mut plan = parsed.map_err(|reason| AgeError::ConfigurationError {
+            parameter: "plan".to_string(),
+            value: path.display().to_string(),
+            reason,
+        })?;
+
+        if plan.entries.is_empty() {
+            return Err(AgeError::ConfigurationError {
+                parameter: "plan".to_string(),
+                value: path.display().to_string(),
+                reason: "Plan has no entries".to_string(),
+            });
+        }
+
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        for entry in &mut plan.entries {
+            entry.path = base.join(&entry.path);
+            if let Some(file) = &entry.recipients_file {
+                entry.recipients_file = Some(base.join(file));
+            }
+            entry.format()?;
+        }
+        plan.source = path.to_path_buf();
+        Ok(plan)
+    }
+
+    /// Whether any entry needs the invocation's passphrase
+    pub fn needs_passphrase(&self) -> bool {
+        self.entries.iter().any(|entry| !entry.has_recipients())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_plan_and_build_requests() {
+        let temp = TempDir::new().unwrap();
+        let plan_path = temp.path().join("plan.toml");
+        fs::write(
+            &plan_path,
+            r#"
+[[entry]]
+path = "secrets"
+recursive = true
+pattern = "*.env"
+
+[[entry]]
+path = "keys/deploy.pem"
+format = "ascii"
+recipients = ["age1example"]
+"#,
+        )
+        .unwrap();
+
+        let plan = LockPlan::load(&plan_path).unwrap();
+        assert_eq!(plan.entries.len(), 2);
+        assert!(plan.needs_passphrase());
+        assert_eq!(plan.entries[0].path, temp.path().join("secrets"));
+
+        let config = AgeConfig::default();
+        let identity = Identity::Passphrase("pw".to_string());
+        let first = plan.entries[0].to_lock_request(&identity, &config).unwrap();
+        assert!(first.recursive);
+        assert_eq!(first.pattern.as_deref(), Some("*.env"));
+        assert!(first.recipients.is_none());
+
+        let second = plan.entries[1].to_lock_request(&identity, &config).unwrap();
+        assert_eq!(second.format, OutputFormat::AsciiArmor);
+        assert!(second.recipients.is_some());
+    }
+
+    #[test]
+    fn test_plan_rejects_bad_entries() {
+        let temp = TempDir::new().unwrap();
+        let plan_path = temp.path().join("plan.json");
+        fs::write(&plan_path, r#"{"entry": [{"path": "a", "format": "hex"}]}"#).unwrap();
+        assert!(LockPlan::load(&plan_path).is_err());
+
+        fs::write(&plan_path, r#"{"entry": [{"path": "a", "colour": "red"}]}"#).unwrap();
+        assert!(LockPlan::load(&plan_path).is_err());
+
+        let entry = PlanEntry {
+            path: PathBuf::from("a"),
+            pattern: None,
+            format: None,
+            recursive: false,
+            recipients: Vec::new(),
+            recipients_file: None,
+            group: Some("missing".to_string()),
+            backup: false,
+        };
+        let identity = Identity::Passphrase("pw".to_string());
+        assert!(entry
+            .to_lock_request(&identity, &AgeConfig::default())
+            .is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Profile - Named performance presets
+//!
+//! A profile bundles the performance knobs (`limits.max_processes`, `limits.memory_budget`,
+//! `streaming.strategy`, the scheduling hints and the progress display) under one name, so
+//! `profile = "laptop"` in `config.toml`, `CAGE_PROFILE` or `--profile laptop` gives sane
+//! settings without tuning each knob:
+//!
+//! ```text
+//! profile      processes  memory budget  streaming  scheduling  progress
+//! laptop       2          256M           temp       nice 10     on
+//! server       CPU count  2G             auto       -           off
+//! io-limited   1          64M            auto       ionice idle on
+//! ```
+//!
+//! A profile only fills knobs that are still unset, so a value set in the file or through
+//! its own `CAGE_*` variable always wins over the preset. `AgeConfig::load_default` applies
+//! the profile once every layer is loaded; library callers that set `AgeConfig::profile`
+//! themselves call `AgeConfig::apply_profile`.
+
+use crate::core::{AgeConfig, IoPriority};
+use crate::error::{AgeError, AgeResult};
+
+const MIB: u64 = 1024 * 1024;
+
+/// Named bundle of performance settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerformanceProfile {
+    /// Few concurrent processes, a small buffer budget and a lowered CPU priority
+    Laptop,
+    /// One process per CPU and a large buffer budget, for unattended runs
+    Server,
+    /// Serial processing on the idle I/O class, for slow or shared disks
+    IoLimited,
+}
+
+impl PerformanceProfile {
+    /// Accepted profile names
+    pub const NAMES: &'static [&'static str] = &["laptop", "server", "io-limited"];
+
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().replace('_', "-").as_str() {
+            "laptop" => Ok(Self::Laptop),
+            "server" => Ok(Self::Server),
+            "io-limited" => Ok(Self::IoLimited),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "profile".to_string(),
+                value: value.to_string(),
+                reason: format!("Valid values: {}", Self::NAMES.join(", ")),
+            }),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Laptop => "laptop",
+            Self::Server => "server",
+            Self::IoLimited => "io-limited",
+        }
+    }
+
+    /// Fill the knobs this profile covers that are still unset in `config`
+    pub fn apply(&self, config: &mut AgeConfig) {
+        let (processes, budget_mib, strategy, progress) = match self {
+            Self::Laptop => (2, 256, "temp", true),
+            Self::Server => (cpu_count(), 2048, "auto", false),
+            Self::IoLimited => (1, 64, "auto", true),
+        };
+        config.max_processes.get_or_insert(processes);
+        config.memory_budget.get_or_insert(budget_mib * MIB);
+        config
+            .streaming_strategy
+            .get_or_insert_with(|| strategy.to_string());
+        config.progress.get_or_insert(progress);
+        match self {
+            Self::Laptop => {
+                config.process_nice.get_or_insert(10);
+            }
+            Self::IoLimited => {
+                config.io_priority.get_or_insert(IoPriority::Idle);
+            }
+            Self::Server => {}
+        }
+    }
+}
+
+fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_fills_only_unset_knobs() {
+        assert_eq!(
+            PerformanceProfile::parse("IO_limited").unwrap(),
+            PerformanceProfile::IoLimited
+        );
+        assert!(PerformanceProfile::parse("desktop").is_err());
+
+        let mut config = AgeConfig {
+            max_processes: Some(4),
+            ..AgeConfig::default()
+        };
+        PerformanceProfile::IoLimited.apply(&mut config);
+        assert_eq!(config.max_processes, Some(4));
+        assert_eq!(config.memory_budget, Some(64 * MIB));
+        assert_eq!(config.streaming_strategy.as_deref(), Some("auto"));
+        assert_eq!(config.io_priority, Some(IoPriority::Idle));
+        assert_eq!(config.progress, Some(true));
+    }
+}
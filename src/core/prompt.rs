@@ -0,0 +1,224 @@
+//! Confirmation Prompts - Pluggable operator confirmation policy
+//!
+//! Destructive steps (danger-mode in-place writes, emergency reset and unlock, insecure
+//! passphrase use) ask a [`Prompter`] instead of reading the terminal directly, so
+//! embedders decide whether and how an operator is asked.
+//!
+//! - [`TtyPrompter`] asks on stderr and reads the answer from stdin
+//! - [`DenyPrompter`] declines everything, for unattended runs
+//! - [`ScriptedPrompter`] replays canned answers and records what was asked
+//!
+//! Setting `CAGE_NON_INTERACTIVE=1` (the CLI's `--non-interactive`) forbids terminal
+//! prompts process-wide: anything that would ask fails at once with
+//! `AgeError::InteractionRequired` instead of waiting on input that never comes.
+
+use crate::error::{AgeError, AgeResult};
+use crate::lang::fmt_warning;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+/// Environment switch forbidding every terminal prompt
+pub const NON_INTERACTIVE_ENV: &str = "CAGE_NON_INTERACTIVE";
+
+/// Whether terminal prompts are forbidden for this process
+pub fn is_non_interactive() -> bool {
+    std::env::var(NON_INTERACTIVE_ENV)
+        .is_ok_and(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// Fail with `InteractionRequired` when terminal prompts are forbidden
+pub fn require_interactive(operation: &str, prompt: &str) -> AgeResult<()> {
+    if is_non_interactive() {
+        return Err(AgeError::InteractionRequired {
+            operation: operation.to_string(),
+            prompt: prompt.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// A confirmation to put to the operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    /// Stable identifier of the guarded action (e.g. "reset")
+    pub action: String,
+    /// One-line warning shown first
+    pub message: String,
+    /// Extra context lines shown under the message
+    pub details: Vec<String>,
+    /// Exact phrase to type; `None` asks a yes/no question
+    pub phrase: Option<String>,
+}
+
+impl Confirmation {
+    pub fn new(action: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            message: message.into(),
+            details: Vec::new(),
+            phrase: None,
+        }
+    }
+
+    pub fn detail(mut self, line: impl Into<String>) -> Self {
+        self.details.push(line.into());
+        self
+    }
+
+    /// Require the operator to type `phrase` exactly
+    pub fn phrase(mut self, phrase: impl Into<String>) -> Self {
+        self.phrase = Some(phrase.into());
+        self
+    }
+
+    /// Whether `answer` confirms this request
+    pub fn accepts(&self, answer: &str) -> bool {
+        let answer = answer.trim();
+        match &self.phrase {
+            Some(phrase) => answer == phrase,
+            None => matches!(answer.to_lowercase().as_str(), "y" | "yes"),
+        }
+    }
+}
+
+/// Decides whether a confirmation is granted
+pub trait Prompter: Send + Sync {
+    /// `Ok(false)` declines; errors are reserved for failing to ask at all
+    fn confirm(&self, confirmation: &Confirmation) -> AgeResult<bool>;
+}
+
+/// Prompt on stderr and read one answer line from stdin
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TtyPrompter;
+
+impl Prompter for TtyPrompter {
+    fn confirm(&self, confirmation: &Confirmation) -> AgeResult<bool> {
+        require_interactive(&confirmation.action, &confirmation.message)?;
+        let mut stderr = io::stderr().lock();
+        let io_error = |operation: &str| {
+            let operation = operation.to_string();
+            move |source| AgeError::IoError {
+                operation,
+                context: "confirmation_prompt".to_string(),
+                source,
+            }
+        };
+
+        writeln!(stderr, "{}", fmt_warning(&confirmation.message))
+            .map_err(io_error("write_prompt"))?;
+        for line in &confirmation.details {
+            writeln!(stderr, "   {}", line).map_err(io_error("write_prompt"))?;
+        }
+        match &confirmation.phrase {
+            Some(phrase) => write!(stderr, "Type '{}' to confirm: ", phrase),
+            None => write!(stderr, "Continue? [y/N]: "),
+        }
+        .map_err(io_error("write_prompt"))?;
+        stderr.flush().map_err(io_error("flush_stderr"))?;
+
+        let mut input = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut input)
+            .map_err(io_error("read_line"))?;
+        Ok(confirmation.accepts(&input))
+    }
+}
+
+/// Decline every confirmation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyPrompter;
+
+impl Prompter for DenyPrompter {
+    fn confirm(&self, _confirmation: &Confirmation) -> AgeResult<bool> {
+        Ok(false)
+    }
+}
+
+/// Fail every confirmation with `InteractionRequired`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonInteractivePrompter;
+
+impl Prompter for NonInteractivePrompter {
+    fn confirm(&self, confirmation: &Confirmation) -> AgeResult<bool> {
+        Err(AgeError::InteractionRequired {
+            operation: confirmation.action.clone(),
+            prompt: confirmation.message.clone(),
+        })
+    }
+}
+
+/// Replay queued answers in order, declining once they run out
+#[derive(Debug, Default)]
+pub struct ScriptedPrompter {
+    answers: Mutex<VecDeque<bool>>,
+    asked: Mutex<Vec<Confirmation>>,
+}
+
+impl ScriptedPrompter {
+    pub fn new(answers: impl IntoIterator<Item = bool>) -> Self {
+        Self {
+            answers: Mutex::new(answers.into_iter().collect()),
+            asked: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Confirmations asked so far
+    pub fn asked(&self) -> Vec<Confirmation> {
+        self.asked.lock().map(|a| a.clone()).unwrap_or_default()
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn confirm(&self, confirmation: &Confirmation) -> AgeResult<bool> {
+        if let Ok(mut asked) = self.asked.lock() {
+            asked.push(confirmation.clone());
+        }
+        Ok(self
+            .answers
+            .lock()
+            .ok()
+            .and_then(|mut answers| answers.pop_front())
+            .unwrap_or(false))
+    }
+}
+
+/// Fail in non-interactive mode, prompt when stdin is a terminal, otherwise decline
+pub fn default_prompter() -> Arc<dyn Prompter> {
+    if is_non_interactive() {
+        Arc::new(NonInteractivePrompter)
+    } else if io::stdin().is_terminal() {
+        Arc::new(TtyPrompter)
+    } else {
+        Arc::new(DenyPrompter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_accepts() {
+        let typed = Confirmation::new("reset", "Reset repository").phrase("CONFIRM_RESET");
+        assert!(typed.accepts("CONFIRM_RESET\n"));
+        assert!(!typed.accepts("yes"));
+
+        let yes_no = Confirmation::new("batch", "Apply to many files");
+        assert!(yes_no.accepts("Y\n"));
+        assert!(!yes_no.accepts(""));
+    }
+
+    #[test]
+    fn test_scripted_prompter_records_and_runs_out() {
+        let prompter = ScriptedPrompter::new([true]);
+        let confirmation = Confirmation::new("reset", "Reset repository");
+        assert!(prompter.confirm(&confirmation).unwrap());
+        assert!(!prompter.confirm(&confirmation).unwrap());
+        assert_eq!(prompter.asked().len(), 2);
+        assert!(!DenyPrompter.confirm(&confirmation).unwrap());
+        let err = NonInteractivePrompter.confirm(&confirmation).unwrap_err();
+        assert_eq!(err.exit_code(), crate::error::EXIT_INTERACTION_REQUIRED);
+    }
+}
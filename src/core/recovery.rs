@@ -9,17 +9,53 @@
 //! - Layer 3: --danger-mode with confirmation
 //! - Layer 4: DANGER_MODE=1 environment variable
 //! - Layer 5: --i-am-sure automation override
+//!
+//! Recursive and in-place operations additionally refuse risky targets (`/`, `$HOME`,
+//! system directories, config denylist) unless `--allow-risky-path` is given.
 
+use super::permissions;
+use super::prompt::{Confirmation, Prompter, TtyPrompter};
 use crate::error::{AgeError, AgeResult};
-use crate::lang::fmt_warning;
 use chrono::Utc;
+use std::hash::{BuildHasher, Hasher};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Default suffix for recovery files
+pub const RECOVERY_SUFFIX: &str = ".tmp.recover";
+
+/// Attempts at finding an unused recovery name before giving up
+const MAX_NAME_ATTEMPTS: usize = 16;
+
+/// Sibling path `<name>.<pid>-<random>{suffix}`, unique across concurrent runs
+fn unique_sibling(original: &Path, suffix: &str) -> PathBuf {
+    let name = original
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // RandomState is seeded per instance, giving a cheap random suffix without extra deps
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    original.with_file_name(format!(
+        "{}.{}-{:08x}{}",
+        name,
+        std::process::id(),
+        random as u32,
+        suffix
+    ))
+}
 
 /// Recovery file manager for creating and managing .tmp.recover files
+///
+/// Recovery files are named `<file>.<pid>-<random>.tmp.recover` and created exclusively,
+/// so concurrent runs and pre-existing files never collide.
 pub struct RecoveryManager {
     create_recovery: bool,
     danger_mode: bool,
+    suffix: String,
+    created: Mutex<Vec<PathBuf>>,
 }
 
 impl RecoveryManager {
@@ -27,7 +63,51 @@ impl RecoveryManager {
         Self {
             create_recovery,
             danger_mode,
+            suffix: RECOVERY_SUFFIX.to_string(),
+            created: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Use a custom recovery file suffix (default `.tmp.recover`)
+    pub fn with_suffix(mut self, suffix: &str) -> Self {
+        self.suffix = suffix.to_string();
+        self
+    }
+
+    /// Recovery files created by this manager, in creation order
+    pub fn created_files(&self) -> Vec<PathBuf> {
+        self.created
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Recovery files on disk for `original`, including legacy `<stem>.tmp.recover` names
+    pub fn recovery_files_for(&self, original: &Path) -> AgeResult<Vec<PathBuf>> {
+        let name = original
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let parent = original
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let prefix = format!("{}.", name);
+        let legacy = original.with_extension(self.suffix.trim_start_matches('.'));
+
+        let mut found = Vec::new();
+        let entries = std::fs::read_dir(parent)
+            .map_err(|e| AgeError::file_error("read_dir", parent.to_path_buf(), e))?;
+        for entry in entries.flatten() {
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            let unique = entry_name.starts_with(&prefix) && entry_name.ends_with(&self.suffix);
+            if unique || path.file_name() == legacy.file_name() {
+                found.push(path);
+            }
         }
+        found.sort();
+        Ok(found)
     }
 
     /// Create recovery file with passphrase and instructions
@@ -44,7 +124,6 @@ impl RecoveryManager {
             });
         }
 
-        let recovery_path = original.with_extension("tmp.recover");
         let content = format!(
             r#"# CAGE RECOVERY INFORMATION
 # Generated: {}
@@ -66,8 +145,29 @@ impl RecoveryManager {
             passphrase
         );
 
-        // Create with restrictive permissions (600)
-        std::fs::write(&recovery_path, content)
+        // Create exclusively so an existing or concurrently created file is never reused
+        let mut attempts = 0;
+        let (recovery_path, mut file) = loop {
+            let candidate = unique_sibling(original, &self.suffix);
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            match options.open(&candidate) {
+                Ok(file) => break (candidate, file),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    attempts += 1;
+                    if attempts >= MAX_NAME_ATTEMPTS {
+                        return Err(AgeError::file_error("create_recovery", candidate, e));
+                    }
+                }
+                Err(e) => return Err(AgeError::file_error("create_recovery", candidate, e)),
+            }
+        };
+        file.write_all(content.as_bytes())
             .map_err(|e| AgeError::file_error("create_recovery", recovery_path.clone(), e))?;
 
         // Set restrictive permissions on Unix
@@ -79,15 +179,95 @@ impl RecoveryManager {
                 .map_err(|e| AgeError::file_error("set_permissions", recovery_path.clone(), e))?;
         }
 
+        self.created
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(recovery_path.clone());
         Ok(recovery_path)
     }
 }
 
+/// Directories refused as recursive or in-place targets regardless of config
+const SYSTEM_PATHS: &[&str] = &[
+    "/bin",
+    "/boot",
+    "/dev",
+    "/etc",
+    "/lib",
+    "/lib64",
+    "/opt",
+    "/proc",
+    "/root",
+    "/sbin",
+    "/sys",
+    "/usr",
+    "/var",
+    "/Applications",
+    "/Library",
+    "/System",
+];
+
+/// Why `path` is a risky target for recursive or in-place work, if it is one.
+///
+/// Risky targets are `/`, `$HOME` itself, system directories, paths matching a
+/// `denylist` glob (`~/` expands to `$HOME`) and directories containing a denylisted path.
+pub fn risky_path_reason(path: &Path, denylist: &[String]) -> Option<String> {
+    let target = path
+        .canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let home = std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from);
+
+    if target.parent().is_none() {
+        return Some("filesystem root".to_string());
+    }
+    if home.as_deref() == Some(target.as_path()) {
+        return Some("home directory root".to_string());
+    }
+    if SYSTEM_PATHS
+        .iter()
+        .any(|system| target == Path::new(system))
+    {
+        return Some("system directory".to_string());
+    }
+
+    for entry in denylist {
+        let expanded = match (entry.strip_prefix("~/"), &home) {
+            (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+            _ => entry.clone(),
+        };
+        let matched = globset::Glob::new(&expanded)
+            .map(|glob| glob.compile_matcher().is_match(&target))
+            .unwrap_or(false);
+        if matched {
+            return Some(format!("matches denylist entry '{}'", entry));
+        }
+        // Recursing into a parent would reach the denylisted path
+        let literal: PathBuf = Path::new(&expanded)
+            .components()
+            .take_while(|c| {
+                !c.as_os_str()
+                    .to_string_lossy()
+                    .contains(['*', '?', '[', '{'])
+            })
+            .collect();
+        if literal != target && literal.starts_with(&target) {
+            return Some(format!("contains denylisted path '{}'", entry));
+        }
+    }
+    None
+}
+
 /// Safety validator for in-place operations
 pub struct SafetyValidator {
     danger_mode: bool,
     i_am_sure: bool,
     env_danger: bool,
+    denylist: Vec<String>,
+    allow_risky_path: bool,
+    prompter: Arc<dyn Prompter>,
 }
 
 impl SafetyValidator {
@@ -100,9 +280,51 @@ impl SafetyValidator {
             danger_mode,
             i_am_sure,
             env_danger,
+            denylist: Vec::new(),
+            allow_risky_path: false,
+            prompter: Arc::new(TtyPrompter),
         }
     }
 
+    /// Ask danger-mode confirmations through `prompter` instead of the terminal
+    pub fn with_prompter(mut self, prompter: Arc<dyn Prompter>) -> Self {
+        self.prompter = prompter;
+        self
+    }
+
+    /// Extra risky-path globs, usually `AgeConfig::risky_path_denylist`
+    pub fn with_denylist(mut self, denylist: Vec<String>) -> Self {
+        self.denylist = denylist;
+        self
+    }
+
+    /// Permit risky targets (`--allow-risky-path`)
+    pub fn allow_risky_path(mut self, allow: bool) -> Self {
+        self.allow_risky_path = allow;
+        self
+    }
+
+    /// Refuse a risky recursive or in-place target unless overridden.
+    ///
+    /// Returns the risk reason when the override let a risky target through, so the
+    /// caller can audit it.
+    pub fn validate_target(&self, path: &Path) -> AgeResult<Option<String>> {
+        let Some(reason) = risky_path_reason(path, &self.denylist) else {
+            return Ok(None);
+        };
+        if self.allow_risky_path {
+            return Ok(Some(reason));
+        }
+        Err(AgeError::InvalidOperation {
+            operation: "risky-path".to_string(),
+            reason: format!(
+                "Refusing to operate on {} ({}); pass --allow-risky-path to override",
+                path.display(),
+                reason
+            ),
+        })
+    }
+
     /// Validate in-place operation safety requirements
     pub fn validate_in_place_operation(&self, file: &Path) -> AgeResult<()> {
         // Check if file exists and will be replaced
@@ -125,33 +347,18 @@ impl SafetyValidator {
             }
 
             if !self.i_am_sure {
-                // Prompt for confirmation
-                eprintln!(
-                    "{}",
-                    fmt_warning("DANGER MODE: This action is UNRECOVERABLE!")
-                );
-                eprintln!("   File: {}", file.display());
-                eprintln!("   No recovery file will be created.");
-                eprintln!("   If encryption fails or you forget the passphrase, your file is LOST FOREVER.");
-                eprintln!();
-                eprint!("Type 'DELETE MY FILE' to confirm this unrecoverable action: ");
-
-                io::stderr().flush().map_err(|e| AgeError::IoError {
-                    operation: "flush_stderr".to_string(),
-                    context: "confirmation_prompt".to_string(),
-                    source: e,
-                })?;
-
-                let mut input = String::new();
-                io::stdin()
-                    .read_line(&mut input)
-                    .map_err(|e| AgeError::IoError {
-                        operation: "read_line".to_string(),
-                        context: "confirmation_input".to_string(),
-                        source: e,
-                    })?;
-
-                if input.trim() != "DELETE MY FILE" {
+                let confirmation = Confirmation::new(
+                    "in-place-danger",
+                    "DANGER MODE: This action is UNRECOVERABLE!",
+                )
+                .detail(format!("File: {}", file.display()))
+                .detail("No recovery file will be created.")
+                .detail(
+                    "If encryption fails or you forget the passphrase, your file is LOST FOREVER.",
+                )
+                .phrase("DELETE MY FILE");
+
+                if !self.prompter.confirm(&confirmation)? {
                     return Err(AgeError::InvalidOperation {
                         operation: "in-place-danger".to_string(),
                         reason: "User cancelled dangerous operation".to_string(),
@@ -169,6 +376,8 @@ pub struct InPlaceOperation {
     original: PathBuf,
     temp_encrypted: PathBuf,
     recovery_file: Option<PathBuf>,
+    output_mode: Option<u32>,
+    preserve_ownership: bool,
     completed: bool,
 }
 
@@ -176,12 +385,31 @@ impl InPlaceOperation {
     pub fn new(file: &Path) -> Self {
         Self {
             original: file.to_path_buf(),
-            temp_encrypted: file.with_extension("tmp.cage"),
+            temp_encrypted: unique_sibling(file, ".tmp.cage"),
             recovery_file: None,
+            output_mode: None,
+            preserve_ownership: false,
             completed: false,
         }
     }
 
+    /// Give the ciphertext this mode instead of the original file's permissions
+    pub fn with_output_mode(mut self, mode: Option<u32>) -> Self {
+        self.output_mode = mode;
+        self
+    }
+
+    /// Hand the replaced file back to the original uid/gid after the rename
+    pub fn with_preserve_ownership(mut self, preserve: bool) -> Self {
+        self.preserve_ownership = preserve;
+        self
+    }
+
+    /// Recovery file created by the last lock, if any
+    pub fn recovery_file(&self) -> Option<&Path> {
+        self.recovery_file.as_deref()
+    }
+
     /// Execute in-place lock operation
     pub fn execute_lock<F>(
         &mut self,
@@ -203,6 +431,7 @@ impl InPlaceOperation {
         }
 
         // 2. Encrypt original -> temp
+        permissions::prepare_output(&self.temp_encrypted, self.output_mode)?;
         encrypt_fn(&self.original, &self.temp_encrypted, passphrase)?;
 
         // 3. Verify temp file exists and is readable
@@ -214,15 +443,24 @@ impl InPlaceOperation {
             });
         }
 
-        // 4. Preserve metadata
+        // 4. Preserve metadata; an explicit mode replaces the original permissions
         self.copy_metadata(&self.original, &self.temp_encrypted)?;
+        permissions::enforce_mode(&self.temp_encrypted, self.output_mode)?;
+
+        let owner = if self.preserve_ownership {
+            permissions::owner_of(&self.original)?
+        } else {
+            None
+        };
 
         // 5. Atomic replace (this is the dangerous moment)
         std::fs::rename(&self.temp_encrypted, &self.original)
             .map_err(|e| AgeError::file_error("atomic_replace", self.original.clone(), e))?;
 
         self.completed = true;
-        Ok(())
+
+        // 6. Restore ownership on the final name
+        permissions::set_owner(&self.original, owner)
     }
 
     /// Copy metadata from source to destination
@@ -326,21 +564,107 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("DANGER_MODE=1"));
     }
 
+    #[test]
+    fn test_risky_path_reasons() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        let secrets = project.join("prod-secrets");
+        std::fs::create_dir_all(&secrets).unwrap();
+        let canonical = project.canonicalize().unwrap();
+        let denylist = vec![format!("{}/prod-*", canonical.display())];
+
+        assert_eq!(
+            risky_path_reason(Path::new("/"), &[]).as_deref(),
+            Some("filesystem root")
+        );
+        assert_eq!(
+            risky_path_reason(Path::new("/etc"), &[]).as_deref(),
+            Some("system directory")
+        );
+        assert!(risky_path_reason(&project, &[]).is_none());
+        assert!(risky_path_reason(&secrets, &denylist)
+            .unwrap()
+            .starts_with("matches denylist"));
+
+        let literal = vec![secrets.canonicalize().unwrap().display().to_string()];
+        assert!(risky_path_reason(&project, &literal)
+            .unwrap()
+            .starts_with("contains denylisted"));
+
+        let validator = SafetyValidator::new(false, false).with_denylist(denylist);
+        assert!(validator.validate_target(&secrets).is_err());
+        let reason = validator.allow_risky_path(true).validate_target(&secrets);
+        assert!(reason.unwrap().is_some());
+    }
+
     #[test]
     fn test_in_place_operation_cleanup_on_drop() {
         let temp_dir = TempDir::new().unwrap();
         let test_file = temp_dir.path().join("test.txt");
         std::fs::write(&test_file, "content").unwrap();
 
-        {
-            let mut op = InPlaceOperation::new(&test_file);
+        let temp_path = {
+            let op = InPlaceOperation::new(&test_file);
             // Create temp file to simulate partial operation
             std::fs::write(&op.temp_encrypted, "temp content").unwrap();
             // Drop without completing - should clean up
-        }
+            op.temp_encrypted.clone()
+        };
 
         // Temp file should be cleaned up
-        let temp_path = test_file.with_extension("tmp.cage");
         assert!(!temp_path.exists());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_in_place_lock_applies_output_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("test.txt");
+        std::fs::write(&test_file, "content").unwrap();
+        std::fs::set_permissions(&test_file, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        let mut op = InPlaceOperation::new(&test_file).with_output_mode(Some(0o644));
+        op.execute_lock("passphrase", true, |_, dst, _| {
+            std::fs::write(dst, "ciphertext")
+                .map_err(|e| AgeError::file_error("write", dst.into(), e))
+        })
+        .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&test_file).unwrap(), "ciphertext");
+        let mode = std::fs::metadata(&test_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o644);
+    }
+
+    #[test]
+    fn test_recovery_files_never_collide() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("test.txt");
+        std::fs::write(&test_file, "content").unwrap();
+
+        // A pre-existing legacy recovery file is left untouched
+        let legacy = test_file.with_extension("tmp.recover");
+        std::fs::write(&legacy, "old recovery").unwrap();
+
+        let manager = RecoveryManager::new(true, false);
+        let first = manager
+            .create_recovery_file(&test_file, "one", "encrypt")
+            .unwrap();
+        let second = manager
+            .create_recovery_file(&test_file, "two", "encrypt")
+            .unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&legacy).unwrap(), "old recovery");
+        assert_eq!(manager.created_files(), vec![first.clone(), second.clone()]);
+
+        let mut expected = vec![legacy, first, second];
+        expected.sort();
+        assert_eq!(manager.recovery_files_for(&test_file).unwrap(), expected);
+
+        // Concurrent in-place operations get distinct temp paths
+        let a = InPlaceOperation::new(&test_file);
+        let b = InPlaceOperation::new(&test_file);
+        assert_ne!(a.temp_encrypted, b.temp_encrypted);
+    }
 }
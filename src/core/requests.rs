@@ -2,18 +2,27 @@
 //!
 //! This module provides typed request structs to unify CLI and library entry points,
 //! enabling a clean API for all encryption operations while maintaining backward compatibility.
-
-use crate::core::{AgeConfig, OutputFormat};
+//!
+//! Request structs implement `Serialize`/`Deserialize` so daemons and queue workers can
+//! accept them as JSON. Secrets never travel with the request: `Identity::Passphrase`
+//! refuses to serialize and cannot be deserialized, so remote callers reference secrets
+//! through `Identity::Provider` (e.g. `vault://...`, `env:VAR` or `fd:3`) instead. The
+//! `CommonOptions::config` override is likewise local-only and skipped.
+
+use crate::core::{AgeConfig, MtimeWindow, OutputFormat, RetentionPolicyConfig};
+use crate::error::{AgeError, AgeResult};
 use md5;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 // ============================================================================
 // COMMON REQUEST OPTIONS
 // ============================================================================
 
 /// Common options shared across multiple request types
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CommonOptions {
     /// Enable verbose output
     pub verbose: bool,
@@ -27,14 +36,24 @@ pub struct CommonOptions {
     /// Force operation without confirmations
     pub force: bool,
 
-    /// Custom configuration override
+    /// Allow recursive or in-place operations on risky paths (`/`, `$HOME`, denylist)
+    pub allow_risky_path: bool,
+
+    /// Fail files on problems that otherwise only warn: skipped special files, backup
+    /// retention and cleanup failures, and ciphertext not removed after an unlock
+    pub strict: bool,
+
+    /// Custom configuration override (not serialized)
+    #[serde(skip)]
     pub config: Option<AgeConfig>,
 }
 
 /// Identity configuration for encryption/decryption operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Identity {
-    /// Use passphrase-based encryption
+    /// Use passphrase-based encryption (never serialized; use `Provider` remotely)
+    #[serde(skip)]
     Passphrase(String),
 
     /// Use identity file (age -i flag)
@@ -45,10 +64,14 @@ pub enum Identity {
 
     /// Prompt for passphrase interactively
     PromptPassphrase,
+
+    /// Resolve at operation time from a provider URI or configured alias
+    Provider(String),
 }
 
 /// Recipient configuration for encryption operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Recipient {
     /// Single recipient public key
     PublicKey(String),
@@ -105,6 +128,33 @@ impl AuthorityTier {
             _ => None,
         }
     }
+
+    /// All tiers ordered from highest (Skull) to lowest (Distro) authority
+    pub fn hierarchy() -> [AuthorityTier; 5] {
+        [
+            AuthorityTier::Skull,
+            AuthorityTier::Master,
+            AuthorityTier::Repository,
+            AuthorityTier::Ignition,
+            AuthorityTier::Distro,
+        ]
+    }
+
+    /// Position in the hierarchy; 0 is the highest authority (Skull)
+    pub fn rank(&self) -> u8 {
+        match self {
+            AuthorityTier::Skull => 0,
+            AuthorityTier::Master => 1,
+            AuthorityTier::Repository => 2,
+            AuthorityTier::Ignition => 3,
+            AuthorityTier::Distro => 4,
+        }
+    }
+
+    /// Whether this tier carries at least the authority of `other`
+    pub fn is_at_or_above(&self, other: AuthorityTier) -> bool {
+        self.rank() <= other.rank()
+    }
 }
 
 /// Recipient group representing a collection of recipients with tier metadata
@@ -198,7 +248,7 @@ impl RecipientGroup {
 }
 
 /// Multi-recipient configuration for operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultiRecipientConfig {
     /// Primary recipient group
     pub primary_group: Option<RecipientGroup>,
@@ -248,6 +298,76 @@ impl MultiRecipientConfig {
         self
     }
 
+    /// Build a config from every configured group at or above `min_tier`.
+    ///
+    /// Groups are ordered from highest to lowest tier (by name within a tier); the
+    /// highest-tier group becomes the primary group. Untiered and empty groups are
+    /// skipped, and hierarchy enforcement is enabled on the result.
+    pub fn from_tier(config: &AgeConfig, min_tier: AuthorityTier) -> AgeResult<Self> {
+        let mut groups: Vec<RecipientGroup> = Vec::new();
+        for tier in AuthorityTier::hierarchy()
+            .into_iter()
+            .filter(|tier| tier.is_at_or_above(min_tier))
+        {
+            let mut tier_groups: Vec<&RecipientGroup> = config
+                .get_groups_by_tier(tier)
+                .into_iter()
+                .filter(|group| !group.is_empty())
+                .collect();
+            tier_groups.sort_by(|a, b| a.name.cmp(&b.name));
+            groups.extend(tier_groups.into_iter().cloned());
+        }
+
+        let mut groups = groups.into_iter();
+        let primary = groups.next().ok_or_else(|| AgeError::ConfigurationError {
+            parameter: "tier".to_string(),
+            value: min_tier.as_str().to_string(),
+            reason: "No non-empty recipient groups configured at or above this tier".to_string(),
+        })?;
+
+        Ok(groups.fold(
+            Self::new()
+                .with_primary_group(primary)
+                .with_hierarchy_enforcement(true),
+            |config, group| config.add_group(group),
+        ))
+    }
+
+    /// Check tier hierarchy rules: every group carries a tier and no additional group
+    /// outranks the primary group.
+    pub fn validate_hierarchy(&self) -> AgeResult<()> {
+        let violation = |reason: String| AgeError::InvalidOperation {
+            operation: "lock".to_string(),
+            reason,
+        };
+
+        let groups = self.all_groups();
+        for group in &groups {
+            if group.tier.is_none() {
+                return Err(violation(format!(
+                    "Hierarchy enforcement requires a tier on recipient group '{}'",
+                    group.name
+                )));
+            }
+        }
+
+        if let Some(primary_tier) = self.primary_group.as_ref().and_then(|g| g.tier) {
+            if let Some(group) = self
+                .additional_groups
+                .iter()
+                .find(|g| g.tier.map_or(false, |t| t.rank() < primary_tier.rank()))
+            {
+                return Err(violation(format!(
+                    "Recipient group '{}' outranks primary group tier {}",
+                    group.name,
+                    primary_tier.as_str()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Flatten all groups into a single recipient list
     pub fn flatten_recipients(&self) -> Vec<String> {
         let mut all_recipients = Vec::new();
@@ -294,7 +414,7 @@ impl Default for MultiRecipientConfig {
 // ============================================================================
 
 /// Request structure for lock (encryption) operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockRequest {
     /// Target file or directory to encrypt
     pub target: PathBuf,
@@ -317,16 +437,73 @@ pub struct LockRequest {
     /// File pattern filter (glob patterns)
     pub pattern: Option<String>,
 
+    /// Only lock files modified inside this window (directory targets)
+    #[serde(default)]
+    pub modified: MtimeWindow,
+
     /// Create backup before locking
     pub backup: bool,
 
     /// Custom backup directory
     pub backup_dir: Option<PathBuf>,
 
+    /// Backup retention for this request (overrides config)
+    #[serde(default)]
+    pub backup_retention: Option<RetentionPolicyConfig>,
+
+    /// Backup file extension for this request (default `.bak`)
+    #[serde(default)]
+    pub backup_extension: Option<String>,
+
     /// In-place encryption (overwrite original)
     pub in_place: bool,
 
+    /// Skip files whose encrypted output is up to date with the source (per lock marker)
+    #[serde(default)]
+    pub skip_existing: bool,
+
+    /// Parse and probe every recipient with the age crate before touching any file
+    #[serde(default)]
+    pub preflight_recipients: bool,
+
+    /// Prove each output decrypts (or the escrow recipient is included) before reporting success
+    #[serde(default)]
+    pub verify_decryptable: bool,
+
+    /// Mode for created ciphertext (None uses `ciphertext_mode` from config)
+    #[serde(default)]
+    pub output_mode: Option<u32>,
+
+    /// Give ciphertext and backups the owner of the source file (see `preserve_ownership`)
+    #[serde(default)]
+    pub preserve_ownership: bool,
+
+    /// Write a `.meta` sidecar recording recipients and groups (also enabled by config)
+    #[serde(default)]
+    pub write_meta: bool,
+
+    /// Expected `group_hash` per recipient group name; locking fails if a group drifted
+    #[serde(default)]
+    pub pinned_group_hashes: std::collections::HashMap<String, String>,
+
+    /// Adapter to use for this request instead of the manager's default (see `AdapterFactory`)
+    #[serde(default)]
+    pub adapter: Option<String>,
+
+    /// Write an in-toto attestation of the lock to this path (see `mgr::attestation`)
+    #[serde(default)]
+    pub attestation: Option<PathBuf>,
+
+    /// Write delta-friendly chunked containers with this chunk size (see `core::chunked`)
+    #[serde(default)]
+    pub chunk_size: Option<u64>,
+
+    /// Ciphertext path when the target is a single file (default `<target>.<extension>`)
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+
     /// Common options
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
@@ -341,9 +518,23 @@ impl LockRequest {
             format: OutputFormat::Binary,
             recursive: false,
             pattern: None,
+            modified: MtimeWindow::default(),
             backup: true,
             backup_dir: None,
+            backup_retention: None,
+            backup_extension: None,
             in_place: false,
+            skip_existing: false,
+            preflight_recipients: false,
+            verify_decryptable: false,
+            output_mode: None,
+            preserve_ownership: false,
+            write_meta: false,
+            pinned_group_hashes: std::collections::HashMap::new(),
+            adapter: None,
+            attestation: None,
+            chunk_size: None,
+            output_path: None,
             common: CommonOptions::default(),
         }
     }
@@ -354,6 +545,141 @@ impl LockRequest {
         self
     }
 
+    /// Builder method to override backup retention for this request
+    pub fn with_backup_retention(mut self, retention: RetentionPolicyConfig) -> Self {
+        self.backup_retention = Some(retention);
+        self
+    }
+
+    /// Builder method to override the backup file extension
+    pub fn with_backup_extension(mut self, extension: String) -> Self {
+        self.backup_extension = Some(extension);
+        self
+    }
+
+    /// Builder method to skip files whose output is already up to date
+    pub fn skip_existing(mut self, enabled: bool) -> Self {
+        self.skip_existing = enabled;
+        self
+    }
+
+    /// Builder method to validate all recipients before any file is encrypted
+    pub fn preflight_recipients(mut self, enabled: bool) -> Self {
+        self.preflight_recipients = enabled;
+        self
+    }
+
+    /// Builder method to round-trip check each output before the lock counts as done
+    pub fn verify_decryptable(mut self, enabled: bool) -> Self {
+        self.verify_decryptable = enabled;
+        self
+    }
+
+    /// Builder method to create ciphertext with an explicit mode (e.g. `0o644`)
+    pub fn with_output_mode(mut self, mode: u32) -> Self {
+        self.output_mode = Some(mode);
+        self
+    }
+
+    /// Builder method to keep the source file's uid/gid on outputs and backups
+    pub fn preserve_ownership(mut self, enabled: bool) -> Self {
+        self.preserve_ownership = enabled;
+        self
+    }
+
+    /// Builder method to write a `.meta` sidecar next to each ciphertext
+    pub fn write_meta(mut self, enabled: bool) -> Self {
+        self.write_meta = enabled;
+        self
+    }
+
+    /// Builder method to write an attestation document of the lock to `path`
+    pub fn with_attestation(mut self, path: PathBuf) -> Self {
+        self.attestation = Some(path);
+        self
+    }
+
+    /// Builder method to write chunked containers so re-locks rewrite only changed chunks
+    pub fn with_chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Builder method to write the ciphertext of a single-file lock to `path`
+    pub fn with_output_path(mut self, path: PathBuf) -> Self {
+        self.output_path = Some(path);
+        self
+    }
+
+    /// Builder method to run this request on a specific adapter (`shell-v1`, `pipe`, ...)
+    pub fn with_adapter(mut self, adapter: impl Into<String>) -> Self {
+        self.adapter = Some(adapter.into());
+        self
+    }
+
+    /// Explicit recipients plus every group of the multi-recipient config
+    pub fn all_recipients(&self) -> Vec<Recipient> {
+        let mut recipients = self.recipients.clone().unwrap_or_default();
+        if let Some(multi) = &self.multi_recipient_config {
+            recipients.push(Recipient::MultipleKeys(multi.flatten_recipients()));
+        }
+        recipients
+    }
+
+    /// Builder method to pin the expected `group_hash` of a recipient group
+    pub fn pin_group_hash(mut self, group: String, hash: String) -> Self {
+        self.pinned_group_hashes.insert(group, hash);
+        self
+    }
+
+    /// Fail closed if any pinned recipient group is missing or has drifted.
+    ///
+    /// Each pin is checked against the group in `config` and, when present, the
+    /// same-named group carried by `multi_recipient_config`.
+    pub fn verify_pinned_groups(&self, config: &AgeConfig) -> AgeResult<()> {
+        let mut pins: Vec<(&String, &String)> = self.pinned_group_hashes.iter().collect();
+        pins.sort();
+
+        for (name, expected) in pins {
+            let request_group = self.multi_recipient_config.as_ref().and_then(|multi| {
+                multi
+                    .all_groups()
+                    .into_iter()
+                    .find(|group| &group.name == name)
+            });
+            let groups: Vec<&RecipientGroup> = config
+                .get_recipient_group(name)
+                .into_iter()
+                .chain(request_group)
+                .collect();
+
+            if groups.is_empty() {
+                return Err(AgeError::InvalidOperation {
+                    operation: "lock".to_string(),
+                    reason: format!("Pinned recipient group '{}' not found", name),
+                });
+            }
+
+            for group in groups {
+                let actual = group.group_hash();
+                if !actual.eq_ignore_ascii_case(expected.trim()) {
+                    return Err(AgeError::InvalidOperation {
+                        operation: "lock".to_string(),
+                        reason: format!(
+                            "Recipient group '{}' has drifted: pinned hash {} but found {} ({} recipients); review the group and update the pin",
+                            name,
+                            expected,
+                            actual,
+                            group.len()
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Builder method to set multi-recipient configuration
     pub fn with_multi_recipient_config(mut self, config: MultiRecipientConfig) -> Self {
         self.multi_recipient_config = Some(config);
@@ -372,6 +698,12 @@ impl LockRequest {
         self
     }
 
+    /// Builder method to only lock files modified inside `window`
+    pub fn modified_within(mut self, window: MtimeWindow) -> Self {
+        self.modified = window;
+        self
+    }
+
     /// Builder method to set output format
     pub fn with_format(mut self, format: OutputFormat) -> Self {
         self.format = format;
@@ -384,7 +716,7 @@ impl LockRequest {
 // ============================================================================
 
 /// Request structure for unlock (decryption) operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnlockRequest {
     /// Target file or directory to decrypt
     pub target: PathBuf,
@@ -398,6 +730,10 @@ pub struct UnlockRequest {
     /// File pattern filter
     pub pattern: Option<String>,
 
+    /// Only unlock files modified inside this window (directory targets)
+    #[serde(default)]
+    pub modified: MtimeWindow,
+
     /// Verify integrity before unlocking
     pub verify_first: bool,
 
@@ -410,7 +746,28 @@ pub struct UnlockRequest {
     /// In-place decryption
     pub in_place: bool,
 
+    /// Re-decrypt and compare with the written plaintext before deleting the ciphertext
+    #[serde(default)]
+    pub verify_decryptable: bool,
+
+    /// Mode for created plaintext (None uses `plaintext_mode` from config)
+    #[serde(default)]
+    pub output_mode: Option<u32>,
+
+    /// Give plaintext the owner of the ciphertext it was decrypted from
+    #[serde(default)]
+    pub preserve_ownership: bool,
+
+    /// Keep the ciphertext and record the plaintext for `relock_due` after this long
+    #[serde(default)]
+    pub relock_after: Option<Duration>,
+
+    /// Adapter to use for this request instead of the manager's default (see `AdapterFactory`)
+    #[serde(default)]
+    pub adapter: Option<String>,
+
     /// Common options
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
@@ -422,10 +779,16 @@ impl UnlockRequest {
             identity,
             recursive: false,
             pattern: None,
+            modified: MtimeWindow::default(),
             verify_first: true,
             selective: false,
             preserve_encrypted: false,
             in_place: false,
+            verify_decryptable: false,
+            output_mode: None,
+            preserve_ownership: false,
+            relock_after: None,
+            adapter: None,
             common: CommonOptions::default(),
         }
     }
@@ -448,11 +811,47 @@ impl UnlockRequest {
         self
     }
 
+    /// Builder method to confirm the plaintext round-trips before ciphertext is deleted
+    pub fn verify_decryptable(mut self, enabled: bool) -> Self {
+        self.verify_decryptable = enabled;
+        self
+    }
+
+    /// Builder method to create plaintext with an explicit mode (e.g. `0o600`)
+    pub fn with_output_mode(mut self, mode: u32) -> Self {
+        self.output_mode = Some(mode);
+        self
+    }
+
+    /// Builder method to keep the ciphertext's uid/gid on the plaintext
+    pub fn preserve_ownership(mut self, enabled: bool) -> Self {
+        self.preserve_ownership = enabled;
+        self
+    }
+
+    /// Builder method to run this request on a specific adapter (`shell-v1`, `pipe`, ...)
+    pub fn with_adapter(mut self, adapter: impl Into<String>) -> Self {
+        self.adapter = Some(adapter.into());
+        self
+    }
+
     /// Builder method to set pattern filter
     pub fn with_pattern(mut self, pattern: String) -> Self {
         self.pattern = Some(pattern);
         self
     }
+
+    /// Builder method to only unlock files modified inside `window`
+    pub fn modified_within(mut self, window: MtimeWindow) -> Self {
+        self.modified = window;
+        self
+    }
+
+    /// Builder method to relock the plaintext after `window` (see `mgr::relock`)
+    pub fn relock_after(mut self, window: Duration) -> Self {
+        self.relock_after = Some(window);
+        self
+    }
 }
 
 // ============================================================================
@@ -460,7 +859,7 @@ impl UnlockRequest {
 // ============================================================================
 
 /// Request structure for key rotation operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RotateRequest {
     /// Target file or directory for rotation
     pub target: PathBuf,
@@ -487,6 +886,7 @@ pub struct RotateRequest {
     pub atomic: bool,
 
     /// Common options
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
@@ -524,7 +924,7 @@ impl RotateRequest {
 // ============================================================================
 
 /// Request structure for verification operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyRequest {
     /// Target file or directory to verify
     pub target: PathBuf,
@@ -541,15 +941,20 @@ pub struct VerifyRequest {
     /// Deep verification (attempt decryption)
     pub deep_verify: bool,
 
+    /// Stream deep verification into a hashing sink instead of a temp file
+    pub stream_verify: bool,
+
     /// Report format
     pub report_format: ReportFormat,
 
     /// Common options
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
 /// Report format for verification results
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ReportFormat {
     /// Simple text output
     Simple,
@@ -570,6 +975,7 @@ impl VerifyRequest {
             recursive: false,
             pattern: None,
             deep_verify: false,
+            stream_verify: false,
             report_format: ReportFormat::Simple,
             common: CommonOptions::default(),
         }
@@ -582,6 +988,12 @@ impl VerifyRequest {
         self
     }
 
+    /// Builder method for streaming deep verification (plaintext never touches disk)
+    pub fn stream_verify(mut self, enabled: bool) -> Self {
+        self.stream_verify = enabled;
+        self
+    }
+
     /// Builder method for report format
     pub fn with_report_format(mut self, format: ReportFormat) -> Self {
         self.report_format = format;
@@ -594,7 +1006,7 @@ impl VerifyRequest {
 // ============================================================================
 
 /// Request structure for status operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusRequest {
     /// Target directory to check
     pub target: PathBuf,
@@ -611,7 +1023,13 @@ pub struct StatusRequest {
     /// Report format
     pub report_format: ReportFormat,
 
+    /// Answer from the `.cage/state.json` cache, re-listing only changed directories
+    /// (always recursive)
+    #[serde(default)]
+    pub fast: bool,
+
     /// Common options
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
@@ -624,6 +1042,7 @@ impl StatusRequest {
             pattern: None,
             detailed: false,
             report_format: ReportFormat::Simple,
+            fast: false,
             common: CommonOptions::default(),
         }
     }
@@ -633,6 +1052,12 @@ impl StatusRequest {
         self.detailed = enabled;
         self
     }
+
+    /// Builder method to answer from the repository state cache
+    pub fn fast(mut self, enabled: bool) -> Self {
+        self.fast = enabled;
+        self
+    }
 }
 
 // ============================================================================
@@ -640,7 +1065,7 @@ impl StatusRequest {
 // ============================================================================
 
 /// Request structure for streaming encryption/decryption
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamRequest {
     /// Operation type (encrypt or decrypt)
     pub operation: StreamOperation,
@@ -658,11 +1083,13 @@ pub struct StreamRequest {
     pub buffer_size: usize,
 
     /// Common options
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
 /// Stream operation type
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StreamOperation {
     /// Stream encryption
     Encrypt,
@@ -701,7 +1128,8 @@ impl StreamRequest {
 // ============================================================================
 
 /// Batch operation type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BatchOperation {
     /// Batch encrypt (lock)
     Lock,
@@ -709,8 +1137,36 @@ pub enum BatchOperation {
     Unlock,
 }
 
+/// Order in which a batch processes the files it selected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOrder {
+    /// Traversal order (default)
+    #[default]
+    Traversal,
+    /// Biggest files first, so the long runs are scheduled early
+    LargestFirst,
+    /// Smallest files first, so most files are done quickly
+    SmallestFirst,
+}
+
+impl BatchOrder {
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        match value.trim().to_lowercase().replace('_', "-").as_str() {
+            "traversal" => Ok(Self::Traversal),
+            "largest-first" | "largest" => Ok(Self::LargestFirst),
+            "smallest-first" | "smallest" => Ok(Self::SmallestFirst),
+            _ => Err(AgeError::ConfigurationError {
+                parameter: "order".to_string(),
+                value: value.to_string(),
+                reason: "Valid values: traversal, largest-first, smallest-first".to_string(),
+            }),
+        }
+    }
+}
+
 /// Request structure for batch directory operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchRequest {
     /// Target directory for the batch operation
     pub target: PathBuf,
@@ -736,13 +1192,30 @@ pub struct BatchRequest {
     /// Create backups before encrypting
     pub backup: bool,
 
+    /// Directory for backups instead of beside each file
+    #[serde(default)]
+    pub backup_dir: Option<PathBuf>,
+
     /// Unlock option: preserve encrypted file after decrypting
     pub preserve_encrypted: bool,
 
     /// Unlock option: verify before attempting decrypt
     pub verify_before_unlock: bool,
 
+    /// Skip files smaller than this many bytes
+    #[serde(default)]
+    pub min_size: Option<u64>,
+
+    /// Skip files larger than this many bytes
+    #[serde(default)]
+    pub max_size: Option<u64>,
+
+    /// Processing order of the selected files
+    #[serde(default)]
+    pub order: BatchOrder,
+
     /// Common request options (verbosity, dry-run, etc.)
+    #[serde(default)]
     pub common: CommonOptions,
 }
 
@@ -758,8 +1231,12 @@ impl BatchRequest {
             recursive: true,
             format: OutputFormat::Binary,
             backup: false,
+            backup_dir: None,
             preserve_encrypted: false,
             verify_before_unlock: true,
+            min_size: None,
+            max_size: None,
+            order: BatchOrder::Traversal,
             common: CommonOptions::default(),
         }
     }
@@ -794,6 +1271,12 @@ impl BatchRequest {
         self
     }
 
+    /// Builder: write backups into `dir` instead of beside each file
+    pub fn with_backup_dir(mut self, dir: PathBuf) -> Self {
+        self.backup_dir = Some(dir);
+        self
+    }
+
     /// Builder: configure unlock preservation behaviour
     pub fn preserve_encrypted(mut self, enabled: bool) -> Self {
         self.preserve_encrypted = enabled;
@@ -805,6 +1288,47 @@ impl BatchRequest {
         self.verify_before_unlock = enabled;
         self
     }
+
+    /// Builder: only process files between `min` and `max` bytes (inclusive)
+    pub fn with_size_range(mut self, min: Option<u64>, max: Option<u64>) -> Self {
+        self.min_size = min;
+        self.max_size = max;
+        self
+    }
+
+    /// Builder: set the processing order
+    pub fn ordered(mut self, order: BatchOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Apply the size range and ordering to collected `files`.
+    ///
+    /// Sorting is stable, so files of equal size keep their traversal order. Files whose
+    /// size cannot be read are dropped when a size bound is set.
+    pub fn select_files(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        let size_of = |path: &Path| std::fs::metadata(path).ok().map(|m| m.len());
+        let bounded = self.min_size.is_some() || self.max_size.is_some();
+        let mut sized: Vec<(PathBuf, u64)> = files
+            .into_iter()
+            .filter_map(|file| match size_of(&file) {
+                Some(bytes) => Some((file, bytes)),
+                None if bounded => None,
+                None => Some((file, 0)),
+            })
+            .filter(|(_, bytes)| {
+                self.min_size.is_none_or(|min| *bytes >= min)
+                    && self.max_size.is_none_or(|max| *bytes <= max)
+            })
+            .collect();
+
+        match self.order {
+            BatchOrder::Traversal => {}
+            BatchOrder::LargestFirst => sized.sort_by(|a, b| b.1.cmp(&a.1)),
+            BatchOrder::SmallestFirst => sized.sort_by_key(|(_, bytes)| *bytes),
+        }
+        sized.into_iter().map(|(file, _)| file).collect()
+    }
 }
 
 // ============================================================================
@@ -865,4 +1389,144 @@ mod tests {
         let _ssh = Identity::SshKey(PathBuf::from("~/.ssh/id_rsa"));
         let _prompt = Identity::PromptPassphrase;
     }
+
+    #[test]
+    fn test_request_serde_roundtrip_with_provider() {
+        let request = LockRequest::new(
+            PathBuf::from("/repo/secrets"),
+            Identity::Provider("vault://secret/cage#passphrase".to_string()),
+        )
+        .recursive(true)
+        .with_format(OutputFormat::AsciiArmor);
+
+        let json = serde_json::to_string(&request).unwrap();
+        let decoded: LockRequest = serde_json::from_str(&json).unwrap();
+        assert!(decoded.recursive);
+        assert_eq!(decoded.format, OutputFormat::AsciiArmor);
+        assert!(
+            matches!(decoded.identity, Identity::Provider(ref uri) if uri == "vault://secret/cage#passphrase")
+        );
+
+        let batch: BatchRequest = serde_json::from_str(
+            r#"{"target":"/repo","operation":"unlock","identity":{"provider":"fd:3"},
+                "recipients":null,"pattern":null,"recursive":true,"format":"binary",
+                "backup":false,"preserve_encrypted":true,"verify_before_unlock":false}"#,
+        )
+        .unwrap();
+        assert_eq!(batch.operation, BatchOperation::Unlock);
+        assert!(!batch.common.verbose);
+    }
+
+    #[test]
+    fn test_request_serde_excludes_secrets() {
+        let request = UnlockRequest::new(
+            PathBuf::from("/repo/file.cage"),
+            Identity::Passphrase("secret".to_string()),
+        );
+        assert!(serde_json::to_string(&request).is_err());
+
+        let json = r#"{"target":"/f","operation":"decrypt","identity":{"passphrase":"x"}}"#;
+        assert!(serde_json::from_str::<StreamRequest>(json).is_err());
+    }
+
+    fn tiered_group(name: &str, tier: AuthorityTier, recipient: &str) -> RecipientGroup {
+        let mut group = RecipientGroup::with_tier(name.to_string(), tier);
+        group.add_recipient(recipient.to_string());
+        group
+    }
+
+    #[test]
+    fn test_multi_recipient_config_from_tier() {
+        let mut config = AgeConfig::default();
+        config.add_recipient_group(tiered_group("ops", AuthorityTier::Master, "age1ops"));
+        config.add_recipient_group(tiered_group("root", AuthorityTier::Skull, "age1root"));
+        config.add_recipient_group(tiered_group("repo", AuthorityTier::Repository, "age1repo"));
+        config.add_recipient_group(tiered_group("ci", AuthorityTier::Ignition, "age1ci"));
+        config.add_recipient_group(RecipientGroup::new("untiered".to_string()));
+
+        let multi = MultiRecipientConfig::from_tier(&config, AuthorityTier::Repository).unwrap();
+        let names: Vec<&str> = multi.all_groups().iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["root", "ops", "repo"]);
+        assert!(multi.enforce_hierarchy);
+        assert!(multi.validate_hierarchy().is_ok());
+
+        assert!(
+            MultiRecipientConfig::from_tier(&AgeConfig::default(), AuthorityTier::Distro).is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_hierarchy_rejects_violations() {
+        let outranked = MultiRecipientConfig::new()
+            .with_primary_group(tiered_group("repo", AuthorityTier::Repository, "age1repo"))
+            .add_group(tiered_group("root", AuthorityTier::Skull, "age1root"));
+        assert!(outranked.validate_hierarchy().is_err());
+
+        let untiered = MultiRecipientConfig::new()
+            .with_primary_group(tiered_group("root", AuthorityTier::Skull, "age1root"))
+            .add_group(RecipientGroup::new("misc".to_string()));
+        assert!(untiered.validate_hierarchy().is_err());
+
+        assert!(AuthorityTier::Skull.is_at_or_above(AuthorityTier::Distro));
+        assert!(!AuthorityTier::Ignition.is_at_or_above(AuthorityTier::Master));
+    }
+
+    #[test]
+    fn test_pinned_group_hash_detects_drift() {
+        let mut config = AgeConfig::default();
+        config.add_recipient_group(tiered_group("ops", AuthorityTier::Master, "age1ops"));
+        let pinned = config.get_recipient_group("ops").unwrap().group_hash();
+
+        let request = LockRequest::new(PathBuf::from("/repo"), Identity::Passphrase(String::new()))
+            .pin_group_hash("ops".to_string(), pinned);
+        assert!(request.verify_pinned_groups(&config).is_ok());
+
+        config
+            .get_recipient_group_mut("ops")
+            .unwrap()
+            .add_recipient("age1intruder".to_string());
+        assert!(request.verify_pinned_groups(&config).is_err());
+
+        let missing = request.pin_group_hash("absent".to_string(), "00".to_string());
+        assert!(missing.verify_pinned_groups(&AgeConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_batch_size_range_and_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let files: Vec<PathBuf> = [("a", 10), ("b", 5000), ("c", 300), ("d", 0)]
+            .iter()
+            .map(|(name, bytes)| {
+                let path = temp_dir.path().join(name);
+                std::fs::write(&path, vec![0u8; *bytes]).unwrap();
+                path
+            })
+            .collect();
+        let names = |selected: Vec<PathBuf>| -> Vec<String> {
+            selected
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+        let request = BatchRequest::new(
+            temp_dir.path().to_path_buf(),
+            BatchOperation::Lock,
+            Identity::Passphrase(String::new()),
+        );
+
+        assert_eq!(
+            names(request.select_files(files.clone())),
+            ["a", "b", "c", "d"]
+        );
+        let largest = request.clone().ordered(BatchOrder::LargestFirst);
+        assert_eq!(
+            names(largest.select_files(files.clone())),
+            ["b", "c", "a", "d"]
+        );
+        let small = request
+            .with_size_range(Some(1), Some(1024))
+            .ordered(BatchOrder::parse("smallest-first").unwrap());
+        assert_eq!(names(small.select_files(files)), ["a", "c"]);
+        assert!(BatchOrder::parse("random").is_err());
+    }
 }
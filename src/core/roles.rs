@@ -0,0 +1,121 @@
+//! Roles - Admin gating for operations that change who can decrypt
+//!
+//! Routine lock/unlock/status work for every operator. Once `[admin] token_hash` is set,
+//! the operations listed in `[admin] operations` (all of `GATEABLE_OPERATIONS` by default)
+//! also need the admin token, taken from `CAGE_ADMIN_TOKEN` or the file named by
+//! `--admin-token-file`. The config stores only the token's SHA-256, so operators can read
+//! it without learning the token.
+//!
+//! Library entry points enforce the gate too: `CageManager::reset` (token from
+//! `CageManager::set_admin_token` or the environment) and every `GroupStore::save`, which
+//! counts as `recipients import`.
+//!
+//! This is a speed bump for delegation, not a sandbox: an operator who can edit the config
+//! can drop the `[admin]` section. Seal the config (`core::config_seal`) so that edit is
+//! flagged or refused.
+
+use super::config::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use sha2::{Digest, Sha256};
+
+/// Environment variable holding the admin token
+pub const ADMIN_TOKEN_ENV: &str = "CAGE_ADMIN_TOKEN";
+
+/// Operations that can be reserved for admins
+pub const GATEABLE_OPERATIONS: &[&str] = &[
+    "rekey",
+    "rotate",
+    "reset",
+    "recipients import",
+    "config seal",
+];
+
+/// Admin token from `CAGE_ADMIN_TOKEN`, for callers that were not handed one
+pub fn env_token() -> Option<String> {
+    std::env::var(ADMIN_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+}
+
+/// Hex SHA-256 of a token, the form stored in `[admin] token_hash`
+pub fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+/// Validate a configured `token_hash`, normalized to lowercase
+pub fn parse_token_hash(value: &str) -> AgeResult<String> {
+    let value = value.trim();
+    if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AgeError::ConfigurationError {
+            parameter: "admin_token_hash".to_string(),
+            value: value.to_string(),
+            reason: "Expected the hex SHA-256 of the admin token (cage config admin-hash)"
+                .to_string(),
+        });
+    }
+    Ok(value.to_lowercase())
+}
+
+/// Whether `operation` needs the admin token under `config`
+pub fn requires_admin(config: &AgeConfig, operation: &str) -> bool {
+    config.admin_token_hash.is_some() && config.admin_operations.iter().any(|op| op == operation)
+}
+
+/// Refuse `operation` unless it is ungated or `token` matches `[admin] token_hash`
+pub fn authorize(config: &AgeConfig, operation: &str, token: Option<&str>) -> AgeResult<()> {
+    let Some(expected) = config.admin_token_hash.as_deref() else {
+        return Ok(());
+    };
+    if !requires_admin(config, operation) {
+        return Ok(());
+    }
+
+    let suggestion = match token {
+        None => format!(
+            "'{}' is an admin operation; set {} or pass --admin-token-file",
+            operation, ADMIN_TOKEN_ENV
+        ),
+        Some(token) if constant_time_eq(&token_hash(token), expected) => {
+            tracing::info!(target: "cage::audit", operation, "ADMIN_AUTHORIZED");
+            return Ok(());
+        }
+        Some(_) => "The admin token does not match [admin] token_hash".to_string(),
+    };
+    tracing::warn!(target: "cage::audit", operation, "ADMIN_DENIED");
+    Err(AgeError::PermissionDenied {
+        operation: operation.to_string(),
+        path: config.source_path.clone().unwrap_or_default(),
+        suggestion,
+    })
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_operations_need_the_token() {
+        let mut config = AgeConfig::default();
+        assert!(authorize(&config, "rekey", None).is_ok());
+
+        config.admin_token_hash = Some(token_hash("s3cret"));
+        assert!(authorize(&config, "rekey", None).is_err());
+        assert!(authorize(&config, "rekey", Some("guess")).is_err());
+        assert!(authorize(&config, "rekey", Some("s3cret\n")).is_ok());
+        assert!(authorize(&config, "lock", None).is_ok());
+
+        assert!(authorize(&config, "reset", None).is_err());
+
+        config.admin_operations = vec!["config seal".to_string()];
+        assert!(authorize(&config, "rekey", None).is_ok());
+        assert!(authorize(&config, "config seal", None).is_err());
+    }
+}
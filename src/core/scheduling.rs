@@ -0,0 +1,287 @@
+//! Throughput Throttling and Scheduling Hints
+//!
+//! Background encryption jobs on shared hosts should not starve production workloads.
+//! This module provides a simple byte-rate throttle applied between files during batch
+//! and recursive operations, plus nice/ionice-style priority hints applied to the Cage
+//! process (and therefore inherited by the `age` child processes it spawns).
+//!
+//! Settings come from the `[scheduling]` config section and can be overridden through
+//! `CAGE_MAX_THROUGHPUT`, `CAGE_NICE` and `CAGE_IONICE`.
+
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use std::time::{Duration, Instant};
+
+/// I/O scheduling class hint (Linux `ioprio_set`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoPriority {
+    /// Only perform I/O when no other process needs the disk
+    Idle,
+    /// Best-effort class with a level from 0 (highest) to 7 (lowest)
+    BestEffort(u8),
+}
+
+impl IoPriority {
+    /// Parse `idle`, `best-effort` or `best-effort:<0-7>`
+    pub fn parse(value: &str) -> AgeResult<Self> {
+        let lower = value.trim().to_lowercase();
+        if lower == "idle" {
+            return Ok(IoPriority::Idle);
+        }
+
+        let (class, level) = match lower.split_once(':') {
+            Some((class, level)) => (class, Some(level)),
+            None => (lower.as_str(), None),
+        };
+        if class != "best-effort" && class != "be" {
+            return Err(invalid(
+                "ionice",
+                value,
+                "Valid values: idle, best-effort[:0-7]",
+            ));
+        }
+
+        let level = match level {
+            Some(level) => level
+                .parse::<u8>()
+                .ok()
+                .filter(|l| *l <= 7)
+                .ok_or_else(|| invalid("ionice", value, "Best-effort level must be 0-7"))?,
+            None => 7,
+        };
+        Ok(IoPriority::BestEffort(level))
+    }
+}
+
+/// Parse a byte rate such as `50M`, `512K`, `1G` or `1048576` (optionally suffixed `B` or `/s`)
+pub fn parse_byte_rate(value: &str) -> AgeResult<u64> {
+    parse_byte_quantity(value.trim().trim_end_matches("/s"), "max_throughput")
+}
+
+/// Parse a byte size such as `256M`, `1G` or `65536` (optionally suffixed `B`)
+pub fn parse_byte_size(value: &str) -> AgeResult<u64> {
+    parse_byte_quantity(value, "byte_size")
+}
+
+fn parse_byte_quantity(value: &str, parameter: &str) -> AgeResult<u64> {
+    let trimmed = value.trim();
+    let normalized = trimmed.trim_end_matches(['B', 'b']).trim();
+    let (digits, multiplier) = match normalized.chars().last() {
+        Some('K') | Some('k') => (&normalized[..normalized.len() - 1], 1024u64),
+        Some('M') | Some('m') => (&normalized[..normalized.len() - 1], 1024 * 1024),
+        Some('G') | Some('g') => (&normalized[..normalized.len() - 1], 1024 * 1024 * 1024),
+        _ => (normalized, 1),
+    };
+
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|n| *n > 0)
+        .ok_or_else(|| {
+            invalid(
+                parameter,
+                trimmed,
+                "Expected a positive byte quantity such as 50M, 512K or 1G",
+            )
+        })
+}
+
+/// Byte-rate limiter applied between files of a multi-file operation
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    bytes_per_sec: Option<u64>,
+    started: Option<Instant>,
+    consumed: u64,
+}
+
+impl Throttle {
+    /// Create a throttle; `None` disables limiting
+    pub fn new(bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            bytes_per_sec,
+            started: None,
+            consumed: 0,
+        }
+    }
+
+    /// Build from config, honoring a `CAGE_MAX_THROUGHPUT` override
+    pub fn from_config(config: &AgeConfig) -> Self {
+        let rate = match std::env::var("CAGE_MAX_THROUGHPUT") {
+            Ok(value) if !value.is_empty() => parse_byte_rate(&value).ok(),
+            _ => config.max_throughput,
+        };
+        Self::new(rate)
+    }
+
+    /// Whether a limit is configured
+    pub fn is_enabled(&self) -> bool {
+        self.bytes_per_sec.is_some()
+    }
+
+    /// Configured limit in bytes per second
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        self.bytes_per_sec
+    }
+
+    /// Account for `bytes` processed and sleep long enough to stay under the limit
+    pub fn consume(&mut self, bytes: u64) -> Duration {
+        if self.bytes_per_sec.is_none() {
+            return Duration::ZERO;
+        }
+
+        let started = *self.started.get_or_insert_with(Instant::now);
+        self.consumed = self.consumed.saturating_add(bytes);
+        let delay = self.delay_for(started.elapsed());
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        delay
+    }
+
+    /// Time to wait so that consumed bytes over `elapsed` stay within the limit
+    fn delay_for(&self, elapsed: Duration) -> Duration {
+        match self.bytes_per_sec {
+            Some(rate) => {
+                let target = Duration::from_secs_f64(self.consumed as f64 / rate as f64);
+                target.saturating_sub(elapsed)
+            }
+            None => Duration::ZERO,
+        }
+    }
+}
+
+/// Apply nice/ionice hints from config (or `CAGE_NICE` / `CAGE_IONICE`) to this process.
+///
+/// Child `age` processes inherit both settings. Lowering priority never needs privileges;
+/// raising it may fail, which is reported as an error.
+pub fn apply_priority_hints(config: &AgeConfig) -> AgeResult<()> {
+    let nice = match std::env::var("CAGE_NICE") {
+        Ok(value) if !value.is_empty() => Some(
+            value
+                .trim()
+                .parse::<i32>()
+                .map_err(|_| invalid("nice", &value, "Expected an integer from -20 to 19"))?,
+        ),
+        _ => config.process_nice,
+    };
+    let io_priority = match std::env::var("CAGE_IONICE") {
+        Ok(value) if !value.is_empty() => Some(IoPriority::parse(&value)?),
+        _ => config.io_priority,
+    };
+
+    if let Some(nice) = nice {
+        set_nice(nice)?;
+    }
+    if let Some(priority) = io_priority {
+        set_io_priority(priority)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_nice(nice: i32) -> AgeResult<()> {
+    if !(-20..=19).contains(&nice) {
+        return Err(invalid(
+            "nice",
+            &nice.to_string(),
+            "Must be between -20 and 19",
+        ));
+    }
+
+    // SAFETY: setpriority has no memory-safety preconditions; `0` targets this process.
+    let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+    if rc != 0 {
+        return Err(invalid(
+            "nice",
+            &nice.to_string(),
+            &std::io::Error::last_os_error().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_nice(_nice: i32) -> AgeResult<()> {
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_io_priority(priority: IoPriority) -> AgeResult<()> {
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_SHIFT: u32 = 13;
+    let value: libc::c_long = match priority {
+        IoPriority::Idle => (3 << IOPRIO_CLASS_SHIFT) as libc::c_long,
+        IoPriority::BestEffort(level) => ((2 << IOPRIO_CLASS_SHIFT) | level as u32) as libc::c_long,
+    };
+
+    // SAFETY: ioprio_set takes plain integers; `0` targets the calling process.
+    let rc = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, value) };
+    if rc != 0 {
+        return Err(invalid(
+            "ionice",
+            &format!("{:?}", priority),
+            &std::io::Error::last_os_error().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_io_priority(_priority: IoPriority) -> AgeResult<()> {
+    // I/O scheduling classes are Linux-specific; treat as a no-op hint elsewhere.
+    Ok(())
+}
+
+fn invalid(parameter: &str, value: &str, reason: &str) -> AgeError {
+    AgeError::ConfigurationError {
+        parameter: parameter.to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_rate() {
+        assert_eq!(parse_byte_rate("50M").unwrap(), 50 * 1024 * 1024);
+        assert_eq!(parse_byte_rate("512k").unwrap(), 512 * 1024);
+        assert_eq!(parse_byte_rate("1GB/s").unwrap(), 1024 * 1024 * 1024);
+        assert_eq!(parse_byte_rate("4096").unwrap(), 4096);
+        assert!(parse_byte_rate("0").is_err());
+        assert!(parse_byte_rate("fast").is_err());
+    }
+
+    #[test]
+    fn test_parse_io_priority() {
+        assert_eq!(IoPriority::parse("idle").unwrap(), IoPriority::Idle);
+        assert_eq!(
+            IoPriority::parse("best-effort").unwrap(),
+            IoPriority::BestEffort(7)
+        );
+        assert_eq!(
+            IoPriority::parse("be:3").unwrap(),
+            IoPriority::BestEffort(3)
+        );
+        assert!(IoPriority::parse("best-effort:9").is_err());
+        assert!(IoPriority::parse("realtime").is_err());
+    }
+
+    #[test]
+    fn test_throttle_delay() {
+        let mut throttle = Throttle::new(Some(1000));
+        throttle.consumed = 2000;
+        assert_eq!(
+            throttle.delay_for(Duration::from_millis(500)),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(throttle.delay_for(Duration::from_secs(3)), Duration::ZERO);
+
+        let mut unlimited = Throttle::new(None);
+        assert_eq!(unlimited.consume(u64::MAX), Duration::ZERO);
+    }
+}
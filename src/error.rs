@@ -7,7 +7,7 @@
 use std::error::Error;
 use std::fmt;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Import RSB utilities for enhanced error handling
 #[allow(unused_imports)]
@@ -16,6 +16,48 @@ use rsb::prelude::*;
 /// Result type alias for Age automation operations
 pub type AgeResult<T> = Result<T, AgeError>;
 
+/// Process exit code for failures in general
+pub const EXIT_FAILURE: i32 = 1;
+
+/// Process exit code when a prompt was needed in non-interactive mode
+pub const EXIT_INTERACTION_REQUIRED: i32 = 3;
+
+/// Stage of a per-file operation, attached to errors by `AgeError::with_context`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Input checks and output preparation before any data is written
+    Prepare,
+    Backup,
+    Encrypt,
+    Decrypt,
+    /// Integrity checks and round-trip verification
+    Verify,
+    /// Output permissions and ownership
+    Finalize,
+    /// Removing originals, sidecars and backups afterwards
+    Cleanup,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Prepare => "prepare",
+            Stage::Backup => "backup",
+            Stage::Encrypt => "encrypt",
+            Stage::Decrypt => "decrypt",
+            Stage::Verify => "verify",
+            Stage::Finalize => "finalize",
+            Stage::Cleanup => "cleanup",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Comprehensive error types for Age automation
 #[derive(Debug)]
 pub enum AgeError {
@@ -163,6 +205,32 @@ pub enum AgeError {
         operation: String,
         reason: String,
     },
+
+    /// A prompt was needed while prompting is forbidden (`--non-interactive`)
+    InteractionRequired {
+        operation: String,
+        prompt: String,
+    },
+
+    /// Operation stopped by SIGINT/SIGTERM (see `core::interrupt`)
+    Interrupted {
+        operation: String,
+        signal: i32,
+    },
+
+    /// A problem that normally only warns, failed under `CommonOptions::strict`
+    StrictViolation {
+        warning: String,
+    },
+
+    /// Failure of one file in a multi-stage operation, with where it happened
+    Context {
+        stage: Stage,
+        path: PathBuf,
+        /// Identifies the `CageManager` operation, e.g. `lock-4242-3`
+        operation_id: String,
+        source: Box<AgeError>,
+    },
 }
 
 impl fmt::Display for AgeError {
@@ -438,6 +506,42 @@ impl fmt::Display for AgeError {
             AgeError::InvalidOperation { operation, reason } => {
                 write!(f, "Invalid operation '{}': {}", operation, reason)
             }
+
+            AgeError::InteractionRequired { operation, prompt } => {
+                write!(
+                    f,
+                    "Non-interactive mode: '{}' would prompt ({}). Supply the answer via flags or environment instead",
+                    operation, prompt
+                )
+            }
+
+            AgeError::Interrupted { operation, signal } => {
+                write!(
+                    f,
+                    "Operation '{}' interrupted by signal {}",
+                    operation, signal
+                )
+            }
+
+            AgeError::StrictViolation { warning } => {
+                write!(f, "Strict mode: {}", warning)
+            }
+
+            AgeError::Context {
+                stage,
+                path,
+                operation_id,
+                source,
+            } => {
+                write!(
+                    f,
+                    "[{}] {} stage failed for {}: {}",
+                    operation_id,
+                    stage,
+                    path.display(),
+                    source
+                )
+            }
         }
     }
 }
@@ -447,6 +551,7 @@ impl Error for AgeError {
         match self {
             AgeError::FileError { source, .. } => Some(source),
             AgeError::IoError { source, .. } => Some(source),
+            AgeError::Context { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
@@ -508,6 +613,47 @@ impl AgeError {
         }
     }
 
+    /// Process exit code the CLI reports for this error
+    pub fn exit_code(&self) -> i32 {
+        match self.root_cause() {
+            AgeError::InteractionRequired { .. } => EXIT_INTERACTION_REQUIRED,
+            AgeError::Interrupted { signal, .. } => 128 + signal,
+            _ => EXIT_FAILURE,
+        }
+    }
+
+    /// Attach the stage, file and operation an error occurred in.
+    ///
+    /// An error that already carries context keeps it, since the innermost stage is the
+    /// most precise one.
+    pub fn with_context(self, stage: Stage, path: &Path, operation_id: &str) -> Self {
+        match self {
+            AgeError::Context { .. } => self,
+            other => AgeError::Context {
+                stage,
+                path: path.to_path_buf(),
+                operation_id: operation_id.to_string(),
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Stage the error was attached to, if any
+    pub fn stage(&self) -> Option<Stage> {
+        match self {
+            AgeError::Context { stage, .. } => Some(*stage),
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without stage context
+    pub fn root_cause(&self) -> &AgeError {
+        match self {
+            AgeError::Context { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
+
     /// Create an injection attempt blocked error
     pub fn injection_blocked(attack_type: &str, pattern: &str) -> Self {
         AgeError::InjectionAttemptBlocked {
@@ -517,6 +663,21 @@ impl AgeError {
     }
 }
 
+/// `with_context` for results, to tag each stage of a pipeline
+pub trait StageContext<T> {
+    fn stage(self, stage: Stage, path: &Path, operation_id: &str) -> AgeResult<T>;
+}
+
+impl<T> StageContext<T> for AgeResult<T> {
+    fn stage(self, stage: Stage, path: &Path, operation_id: &str) -> AgeResult<T> {
+        match &self {
+            Ok(_) => tracing::debug!(%stage, path = %path.display(), "stage done"),
+            Err(e) => tracing::debug!(%stage, path = %path.display(), error = %e, "stage failed"),
+        }
+        self.map_err(|e| e.with_context(stage, path, operation_id))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -557,6 +718,50 @@ mod tests {
         assert!(display.contains("use shorter passphrase"));
     }
 
+    #[test]
+    fn test_interaction_required_exit_code() {
+        let err = AgeError::InteractionRequired {
+            operation: "reset".to_string(),
+            prompt: "Type 'CONFIRM_RESET'".to_string(),
+        };
+        assert_eq!(err.exit_code(), EXIT_INTERACTION_REQUIRED);
+        assert!(err.to_string().contains("Non-interactive mode"));
+        assert_eq!(
+            AgeError::InvalidAdapter("x".to_string()).exit_code(),
+            EXIT_FAILURE
+        );
+    }
+
+    #[test]
+    fn test_interrupted_exit_code() {
+        let err = AgeError::Interrupted {
+            operation: "lock".to_string(),
+            signal: 2,
+        };
+        assert_eq!(err.exit_code(), 130);
+        assert!(err.to_string().contains("interrupted by signal 2"));
+    }
+
+    #[test]
+    fn test_context_names_stage_and_keeps_cause() {
+        let result: AgeResult<()> = Err(AgeError::Interrupted {
+            operation: "encrypt".to_string(),
+            signal: 15,
+        });
+        let err = result
+            .stage(Stage::Encrypt, Path::new("/repo/a.txt"), "lock-1-1")
+            .unwrap_err()
+            .with_context(Stage::Cleanup, Path::new("/repo"), "lock-1-1");
+
+        assert_eq!(err.stage(), Some(Stage::Encrypt));
+        assert!(matches!(err.root_cause(), AgeError::Interrupted { .. }));
+        assert_eq!(err.exit_code(), 143);
+        assert!(err
+            .to_string()
+            .starts_with("[lock-1-1] encrypt stage failed for /repo/a.txt: Operation 'encrypt'"));
+        assert!(err.source().is_some());
+    }
+
     #[test]
     fn test_injection_blocked_error() {
         let err = AgeError::injection_blocked("command_injection", "; rm -rf /");
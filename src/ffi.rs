@@ -0,0 +1,271 @@
+//! C ABI for non-Rust integrators (feature `ffi`)
+//!
+//! Exposes the [`Cage`] facade through an opaque handle and integer status codes so
+//! Python, Go or Node wrappers can drive Cage without shelling out to the CLI. Build a
+//! shared library with:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! The matching declarations live in `include/cage.h`. Every function returns a
+//! `CAGE_*` code; on failure `cage_last_error()` describes the error for the calling
+//! thread. Strings are UTF-8 and NUL-terminated.
+
+use crate::error::{AgeError, AgeResult};
+use crate::mgr::Cage;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+/// Success
+pub const CAGE_OK: i32 = 0;
+/// Null pointer or invalid UTF-8 argument
+pub const CAGE_ERR_INVALID_ARGUMENT: i32 = 1;
+/// The operation failed; see `cage_last_error`
+pub const CAGE_ERR_OPERATION: i32 = 2;
+/// The operation ran but some files failed
+pub const CAGE_ERR_PARTIAL: i32 = 3;
+/// A Rust panic was caught at the boundary
+pub const CAGE_ERR_PANIC: i32 = 4;
+
+/// Process directories recursively
+pub const CAGE_FLAG_RECURSIVE: u32 = 1;
+/// Back up files before locking
+pub const CAGE_FLAG_BACKUP: u32 = 1 << 1;
+/// Write ASCII-armored output
+pub const CAGE_FLAG_ARMOR: u32 = 1 << 2;
+
+/// Opaque handle returned by `cage_new`
+pub struct CageHandle {
+    cage: Cage,
+}
+
+/// File counts for lock/unlock results
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CageResult {
+    pub processed: u64,
+    pub failed: u64,
+    pub skipped: u64,
+}
+
+/// Repository status counts
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CageStatus {
+    pub total_files: u64,
+    pub encrypted_files: u64,
+    pub unencrypted_files: u64,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+}
+
+/// Run `body`, translating errors and panics into status codes
+fn guard<F>(body: F) -> i32
+where
+    F: FnOnce() -> AgeResult<i32>,
+{
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(code)) => code,
+        Ok(Err(AgeError::InvalidOperation { operation, reason })) if operation == "ffi" => {
+            set_last_error(reason);
+            CAGE_ERR_INVALID_ARGUMENT
+        }
+        Ok(Err(err)) => {
+            set_last_error(err.to_string());
+            CAGE_ERR_OPERATION
+        }
+        Err(_) => {
+            set_last_error("panic in cage".to_string());
+            CAGE_ERR_PANIC
+        }
+    }
+}
+
+fn invalid(reason: &str) -> AgeError {
+    AgeError::InvalidOperation {
+        operation: "ffi".to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// # Safety
+/// `value` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(value: *const c_char, name: &str) -> AgeResult<&'a str> {
+    if value.is_null() {
+        return Err(invalid(&format!("{} must not be null", name)));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| invalid(&format!("{} is not valid UTF-8", name)))
+}
+
+/// # Safety
+/// `handle` must be null or a live pointer from `cage_new`.
+unsafe fn handle_mut<'a>(handle: *mut CageHandle) -> AgeResult<&'a mut CageHandle> {
+    handle
+        .as_mut()
+        .ok_or_else(|| invalid("handle must not be null"))
+}
+
+fn write_result(out: *mut CageResult, result: &crate::forge::OperationResult) -> i32 {
+    let counts = CageResult {
+        processed: result.processed_files.len() as u64,
+        failed: result.failed_files.len() as u64,
+        skipped: result.skipped_files.len() as u64,
+    };
+    if !out.is_null() {
+        // SAFETY: caller guarantees a non-null `out` points to a writable CageResult
+        unsafe { out.write(counts) };
+    }
+    if result.failed_files.is_empty() {
+        CAGE_OK
+    } else {
+        set_last_error(format!("Failed files: {}", result.failed_files.join(", ")));
+        CAGE_ERR_PARTIAL
+    }
+}
+
+/// Create a passphrase-based handle with `CAGE_FLAG_*` options.
+///
+/// # Safety
+/// `passphrase` must be a NUL-terminated string and `out` a writable pointer. The
+/// handle written to `out` must be released with `cage_free`.
+#[no_mangle]
+pub unsafe extern "C" fn cage_new(
+    passphrase: *const c_char,
+    flags: u32,
+    out: *mut *mut CageHandle,
+) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return Err(invalid("out must not be null"));
+        }
+        let passphrase = read_str(passphrase, "passphrase")?;
+        let cage = Cage::builder()
+            .passphrase(passphrase)
+            .recursive(flags & CAGE_FLAG_RECURSIVE != 0)
+            .backup(flags & CAGE_FLAG_BACKUP != 0)
+            .armor(flags & CAGE_FLAG_ARMOR != 0)
+            .build()?;
+        out.write(Box::into_raw(Box::new(CageHandle { cage })));
+        Ok(CAGE_OK)
+    })
+}
+
+/// Release a handle. Null is ignored.
+///
+/// # Safety
+/// `handle` must be null or a pointer from `cage_new` not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn cage_free(handle: *mut CageHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Encrypt a file or directory; `result` may be null.
+///
+/// # Safety
+/// `handle` must come from `cage_new`, `path` must be a NUL-terminated string and
+/// `result` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn cage_lock(
+    handle: *mut CageHandle,
+    path: *const c_char,
+    result: *mut CageResult,
+) -> i32 {
+    guard(|| {
+        let handle = handle_mut(handle)?;
+        let path = PathBuf::from(read_str(path, "path")?);
+        let outcome = handle.cage.lock(&path)?;
+        Ok(write_result(result, &outcome))
+    })
+}
+
+/// Decrypt a file or directory; `result` may be null.
+///
+/// # Safety
+/// Same requirements as `cage_lock`.
+#[no_mangle]
+pub unsafe extern "C" fn cage_unlock(
+    handle: *mut CageHandle,
+    path: *const c_char,
+    result: *mut CageResult,
+) -> i32 {
+    guard(|| {
+        let handle = handle_mut(handle)?;
+        let path = PathBuf::from(read_str(path, "path")?);
+        let outcome = handle.cage.unlock(&path)?;
+        Ok(write_result(result, &outcome))
+    })
+}
+
+/// Count encrypted and plaintext files under `path`.
+///
+/// # Safety
+/// `handle` must come from `cage_new`, `path` must be a NUL-terminated string and
+/// `status` writable.
+#[no_mangle]
+pub unsafe extern "C" fn cage_status(
+    handle: *mut CageHandle,
+    path: *const c_char,
+    status: *mut CageStatus,
+) -> i32 {
+    guard(|| {
+        let handle = handle_mut(handle)?;
+        if status.is_null() {
+            return Err(invalid("status must not be null"));
+        }
+        let path = PathBuf::from(read_str(path, "path")?);
+        let report = handle.cage.status(&path)?;
+        status.write(CageStatus {
+            total_files: report.total_files as u64,
+            encrypted_files: report.encrypted_files as u64,
+            unencrypted_files: report.unencrypted_files as u64,
+        });
+        Ok(CAGE_OK)
+    })
+}
+
+/// Message for the last failure on this thread, or null. Valid until the next call
+/// that fails on the same thread; do not free.
+#[no_mangle]
+pub extern "C" fn cage_last_error() -> *const c_char {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_arguments_are_rejected() {
+        let mut handle: *mut CageHandle = ptr::null_mut();
+        let code = unsafe { cage_new(ptr::null(), 0, &mut handle) };
+        assert_eq!(code, CAGE_ERR_INVALID_ARGUMENT);
+        assert!(handle.is_null());
+
+        let message = unsafe { CStr::from_ptr(cage_last_error()) };
+        assert!(message.to_str().unwrap().contains("passphrase"));
+
+        let path = CString::new("x").unwrap();
+        let code = unsafe { cage_lock(ptr::null_mut(), path.as_ptr(), ptr::null_mut()) };
+        assert_eq!(code, CAGE_ERR_INVALID_ARGUMENT);
+        unsafe { cage_free(ptr::null_mut()) };
+    }
+}
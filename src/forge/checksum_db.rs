@@ -0,0 +1,249 @@
+//! Checksum Database - Plaintext to ciphertext correspondence for archival sign-off
+//!
+//! With `[metadata] checksums = true`, every lock records the SHA-256 of the plaintext
+//! and of the ciphertext it produced in `.cage/checksums.json` at the repository root
+//! (the nearest directory above the target that already has one, else the target
+//! directory). `cage verify --against-source <dir>` then hashes a plaintext tree and
+//! confirms the encrypted repository corresponds to it exactly: every source file was
+//! locked from identical content, no ciphertext changed since, and nothing extra is
+//! encrypted. No key is needed.
+
+use super::repository_manager::{FileFilter, RecursiveTraversal, Traversal};
+use super::state_cache::STATE_DIR;
+use crate::core::markers::sha256_file;
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CHECKSUM_FILE: &str = "checksums.json";
+
+/// Hashes recorded when one ciphertext was produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecksumEntry {
+    /// Plaintext path relative to the database root
+    pub source: String,
+    pub plaintext_sha256: String,
+    pub ciphertext_sha256: String,
+    /// RFC 3339 time of the lock
+    pub recorded_at: String,
+}
+
+impl ChecksumEntry {
+    /// Hash `source` and `output`; call while the plaintext still exists.
+    ///
+    /// `source` is filled in relative to the root by `ChecksumDb::record`.
+    pub fn capture(source: &Path, output: &Path) -> AgeResult<Self> {
+        Ok(Self {
+            source: String::new(),
+            plaintext_sha256: sha256_file(source)?,
+            ciphertext_sha256: sha256_file(output)?,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// How an encrypted repository compares to a plaintext source tree
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceComparison {
+    /// Source files whose recorded plaintext hash and ciphertext both check out
+    pub matched: Vec<String>,
+    /// Source files never recorded as locked
+    pub missing: Vec<String>,
+    /// Source files whose content differs from what was locked
+    pub changed: Vec<String>,
+    /// Ciphertexts that are gone or no longer match their recorded hash
+    pub ciphertext_modified: Vec<String>,
+    /// Recorded ciphertexts with no file in the source tree
+    pub extra: Vec<String>,
+}
+
+impl SourceComparison {
+    /// Every source file is accounted for and nothing else is encrypted
+    pub fn is_exact(&self) -> bool {
+        self.missing.is_empty()
+            && self.changed.is_empty()
+            && self.ciphertext_modified.is_empty()
+            && self.extra.is_empty()
+    }
+}
+
+/// Checksum records of one repository, keyed by ciphertext path relative to the root
+#[derive(Debug, Clone)]
+pub struct ChecksumDb {
+    root: PathBuf,
+    entries: BTreeMap<String, ChecksumEntry>,
+}
+
+impl ChecksumDb {
+    /// Database file for a repository root
+    pub fn db_path(root: &Path) -> PathBuf {
+        root.join(STATE_DIR).join(CHECKSUM_FILE)
+    }
+
+    /// Database covering `target`: the nearest existing one above it, else a new one
+    /// rooted at `target` (or its directory, for a file)
+    pub fn for_target(target: &Path) -> AgeResult<Self> {
+        let absolute = std::path::absolute(target)
+            .map_err(|e| AgeError::file_error("resolve", target.to_path_buf(), e))?;
+        let existing = absolute
+            .ancestors()
+            .find(|dir| Self::db_path(dir).is_file())
+            .map(Path::to_path_buf);
+        let root = match existing {
+            Some(root) => root,
+            None if absolute.is_dir() => absolute,
+            None => absolute.parent().unwrap_or(&absolute).to_path_buf(),
+        };
+        Self::load(&root)
+    }
+
+    /// Load the database at `root`; a missing file is an empty database
+    pub fn load(root: &Path) -> AgeResult<Self> {
+        let path = Self::db_path(root);
+        let entries = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| AgeError::InvalidOperation {
+                operation: "read_checksums".to_string(),
+                reason: format!("{}: {}", path.display(), e),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(AgeError::file_error("read_checksums", path, e)),
+        };
+        Ok(Self {
+            root: root.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn entries(&self) -> &BTreeMap<String, ChecksumEntry> {
+        &self.entries
+    }
+
+    /// Record `entry` for `output` produced from `source`; paths outside the root are ignored
+    pub fn record(&mut self, source: &Path, output: &Path, mut entry: ChecksumEntry) -> bool {
+        let (Some(source), Some(output)) = (self.relative(source), self.relative(output)) else {
+            return false;
+        };
+        entry.source = source;
+        self.entries.insert(output, entry);
+        true
+    }
+
+    /// Drop entries whose ciphertext no longer exists (unlocked or deleted)
+    pub fn prune(&mut self) {
+        let root = self.root.clone();
+        self.entries.retain(|output, _| root.join(output).is_file());
+    }
+
+    /// Write the database, replacing the previous file in one rename
+    pub fn save(&self) -> AgeResult<()> {
+        let path = Self::db_path(&self.root);
+        let dir = self.root.join(STATE_DIR);
+        fs::create_dir_all(&dir)
+            .map_err(|e| AgeError::file_error("create_state_dir", dir.clone(), e))?;
+        let json =
+            serde_json::to_vec_pretty(&self.entries).map_err(|e| AgeError::InvalidOperation {
+                operation: "save_checksums".to_string(),
+                reason: e.to_string(),
+            })?;
+        let staging = dir.join(format!("{}.tmp", CHECKSUM_FILE));
+        fs::write(&staging, json)
+            .map_err(|e| AgeError::file_error("write_checksums", staging.clone(), e))?;
+        fs::rename(&staging, &path).map_err(|e| AgeError::file_error("write_checksums", path, e))
+    }
+
+    /// Compare the recorded locks with the plaintext tree at `source_dir`
+    pub fn compare_source(
+        &self,
+        source_dir: &Path,
+        config: &AgeConfig,
+    ) -> AgeResult<SourceComparison> {
+        let files = RecursiveTraversal.collect(source_dir, &FileFilter::all(config))?;
+        let sources: BTreeMap<String, PathBuf> = files
+            .into_iter()
+            .filter_map(|file| {
+                let relative = file.strip_prefix(source_dir).ok()?;
+                Some((relative.to_string_lossy().into_owned(), file))
+            })
+            .collect();
+
+        let mut comparison = SourceComparison::default();
+        let mut seen = BTreeSet::new();
+        for (output, entry) in &self.entries {
+            let Some(source) = sources.get(&entry.source) else {
+                comparison.extra.push(output.clone());
+                continue;
+            };
+            seen.insert(entry.source.as_str());
+            let ciphertext = self.root.join(output);
+            if !ciphertext.is_file() || sha256_file(&ciphertext)? != entry.ciphertext_sha256 {
+                comparison.ciphertext_modified.push(output.clone());
+            } else if sha256_file(source)? != entry.plaintext_sha256 {
+                comparison.changed.push(entry.source.clone());
+            } else {
+                comparison.matched.push(entry.source.clone());
+            }
+        }
+        comparison.missing = sources
+            .into_keys()
+            .filter(|relative| !seen.contains(relative.as_str()))
+            .collect();
+        Ok(comparison)
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let absolute = std::path::absolute(path).ok()?;
+        absolute
+            .strip_prefix(&self.root)
+            .ok()
+            .map(|relative| relative.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_records_compare_against_source_tree() {
+        let repo = TempDir::new().unwrap();
+        let source = TempDir::new().unwrap();
+        let config = AgeConfig::default();
+        let lock = |db: &mut ChecksumDb, name: &str, content: &str| {
+            let plain = repo.path().join(name);
+            let output = repo.path().join(format!("{}.cage", name));
+            fs::write(&plain, content).unwrap();
+            fs::write(&output, format!("sealed {}", content)).unwrap();
+            let entry = ChecksumEntry::capture(&plain, &output).unwrap();
+            assert!(db.record(&plain, &output, entry));
+            fs::remove_file(&plain).unwrap();
+            fs::write(source.path().join(name), content).unwrap();
+        };
+
+        let mut db = ChecksumDb::for_target(repo.path()).unwrap();
+        lock(&mut db, "a.txt", "alpha");
+        lock(&mut db, "b.txt", "bravo");
+        db.save().unwrap();
+
+        let db = ChecksumDb::for_target(&repo.path().join("a.txt.cage")).unwrap();
+        let exact = db.compare_source(source.path(), &config).unwrap();
+        assert!(exact.is_exact());
+        assert_eq!(exact.matched, vec!["a.txt", "b.txt"]);
+
+        fs::write(source.path().join("b.txt"), "edited").unwrap();
+        fs::write(source.path().join("c.txt"), "new").unwrap();
+        fs::write(repo.path().join("a.txt.cage"), "tampered").unwrap();
+        let drift = db.compare_source(source.path(), &config).unwrap();
+        assert!(!drift.is_exact());
+        assert_eq!(drift.ciphertext_modified, vec!["a.txt.cage"]);
+        assert_eq!(drift.changed, vec!["b.txt"]);
+        assert_eq!(drift.missing, vec!["c.txt"]);
+    }
+}
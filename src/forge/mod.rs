@@ -5,14 +5,35 @@
 //!
 //! Security Guardian: Edgar - Production operations framework
 
+pub mod checksum_db;
 pub mod file_operations;
+pub mod orphans;
+pub mod plugins;
+pub mod repository_manager;
 pub mod repository_operations;
+pub mod state_cache;
+pub mod status_tree;
+pub mod tree_report;
+
+pub use checksum_db::{ChecksumDb, ChecksumEntry, SourceComparison};
+pub use orphans::{Defect, SuspectOutput};
+pub use plugins::{OperationRegistry, SharedOperation};
+pub use repository_manager::{
+    group_hardlinks, special_file_reason, EntryKind, FileFilter, FileSelection, HardlinkGroup,
+    RecursiveTraversal, RepositoryManager, ShallowTraversal, SkippedEntries, Traversal,
+};
+pub use state_cache::{CachedTraversal, FileState, RefreshStats, StateCache, STATE_DIR};
+pub use status_tree::{StatusDir, StatusFile, StatusTree, STATUS_TREE_SCHEMA};
+pub use tree_report::{TreeNode, TreeReport};
 
 use super::core::OutputFormat;
 use super::error::AgeResult;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-/// Core operation trait defining common operation behavior
+/// Core operation trait defining common operation behavior.
+///
+/// Also the extension point for custom operations; see `plugins`.
 pub trait Operation {
     /// Get operation name for logging
     fn operation_name(&self) -> &'static str;
@@ -26,6 +47,19 @@ pub trait Operation {
     /// Validate operation postconditions
     fn validate_postconditions(&self) -> AgeResult<()>;
 
+    /// Paths the operation reads or writes; `CageManager` guards and audits them
+    fn targets(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Execute, reporting per-file outcomes into `result`.
+    ///
+    /// The default runs `execute` and reports nothing; multi-file operations override it.
+    fn execute_with(&self, result: &mut OperationResult) -> AgeResult<()> {
+        let _ = result;
+        self.execute()
+    }
+
     /// Full operation with validation
     fn perform(&self) -> AgeResult<()> {
         self.validate_preconditions()?;
@@ -108,14 +142,76 @@ impl RepositoryStatus {
     }
 }
 
+/// File that an operation deliberately did not process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Kind of non-fatal issue an operation ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCategory {
+    /// Old backups could not be pruned by the retention policy
+    Retention,
+    /// A backup, ciphertext or quarantine step could not be cleaned up
+    Cleanup,
+    /// A checksum, lock marker or metadata sidecar could not be written or removed
+    Metadata,
+    /// ASCII armor was requested but could not be applied as asked
+    Armor,
+    /// A hardlinked file was processed under the report policy
+    Hardlink,
+}
+
+impl WarningCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningCategory::Retention => "retention",
+            WarningCategory::Cleanup => "cleanup",
+            WarningCategory::Metadata => "metadata",
+            WarningCategory::Armor => "armor",
+            WarningCategory::Hardlink => "hardlink",
+        }
+    }
+
+    /// Whether strict mode turns this warning into a failure
+    pub fn fails_under_strict(&self) -> bool {
+        matches!(self, WarningCategory::Retention | WarningCategory::Cleanup)
+    }
+}
+
+/// Non-fatal issue recorded against a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationWarning {
+    pub category: WarningCategory,
+    pub path: String,
+    pub message: String,
+}
+
 /// Operation result with detailed information
 #[derive(Debug)]
 pub struct OperationResult {
     pub success: bool,
     pub processed_files: Vec<String>,
     pub failed_files: Vec<String>,
+    pub skipped_files: Vec<SkippedFile>,
+    /// Non-fatal issues, in the order they were hit
+    pub warnings: Vec<OperationWarning>,
     pub total_processed: usize,
     pub execution_time_ms: u64,
+    /// Total bytes read from processed input files
+    pub bytes_in: u64,
+    /// Total bytes written to produced output files
+    pub bytes_out: u64,
+    /// Per-file processing time in milliseconds, in processing order
+    pub file_durations_ms: Vec<(String, u64)>,
+    /// Per-file (path, bytes in, bytes out), for the tree report
+    pub file_bytes: Vec<(String, u64, u64)>,
+    /// (source, output, hashes) of each lock, when the checksum database or an
+    /// attestation needs them
+    pub checksums: Vec<(PathBuf, PathBuf, ChecksumEntry)>,
 }
 
 impl OperationResult {
@@ -124,8 +220,15 @@ impl OperationResult {
             success: false,
             processed_files: Vec::new(),
             failed_files: Vec::new(),
+            skipped_files: Vec::new(),
+            warnings: Vec::new(),
             total_processed: 0,
             execution_time_ms: 0,
+            bytes_in: 0,
+            bytes_out: 0,
+            file_durations_ms: Vec::new(),
+            file_bytes: Vec::new(),
+            checksums: Vec::new(),
         }
     }
 
@@ -138,9 +241,48 @@ impl OperationResult {
         self.failed_files.push(file_path);
     }
 
+    pub fn add_skipped(&mut self, file_path: String, reason: impl Into<String>) {
+        self.skipped_files.push(SkippedFile {
+            path: file_path,
+            reason: reason.into(),
+        });
+    }
+
+    pub fn add_warning(
+        &mut self,
+        category: WarningCategory,
+        file_path: String,
+        message: impl Into<String>,
+    ) {
+        self.warnings.push(OperationWarning {
+            category,
+            path: file_path,
+            message: message.into(),
+        });
+    }
+
+    /// Account for input and output bytes of a processed file
+    pub fn add_bytes(&mut self, bytes_in: u64, bytes_out: u64) {
+        self.bytes_in += bytes_in;
+        self.bytes_out += bytes_out;
+    }
+
+    /// Account for the input and output bytes of one processed file
+    pub fn add_file_bytes(&mut self, file_path: String, bytes_in: u64, bytes_out: u64) {
+        self.add_bytes(bytes_in, bytes_out);
+        self.file_bytes.push((file_path, bytes_in, bytes_out));
+    }
+
+    pub fn record_file_duration(&mut self, file_path: String, duration: std::time::Duration) {
+        self.file_durations_ms
+            .push((file_path, duration.as_millis() as u64));
+    }
+
     pub fn finalize(&mut self, start_time: std::time::Instant) {
         self.execution_time_ms = start_time.elapsed().as_millis() as u64;
-        self.success = self.failed_files.is_empty() && self.total_processed > 0;
+        // A run where every file was skipped (e.g. already encrypted) still succeeded
+        self.success = self.failed_files.is_empty()
+            && (self.total_processed > 0 || !self.skipped_files.is_empty());
     }
 
     pub fn success_rate(&self) -> f64 {
@@ -151,4 +293,63 @@ impl OperationResult {
             (self.processed_files.len() as f64 / total as f64) * 100.0
         }
     }
+
+    /// Input throughput in bytes per second, once the result has been finalized
+    pub fn throughput_bytes_per_sec(&self) -> Option<f64> {
+        if self.execution_time_ms == 0 || self.bytes_in == 0 {
+            return None;
+        }
+        Some(self.bytes_in as f64 * 1000.0 / self.execution_time_ms as f64)
+    }
+
+    /// One-line human summary of counts, bytes and throughput
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} processed, {} failed, {} skipped, {} bytes in, {} bytes out, {}ms",
+            self.processed_files.len(),
+            self.failed_files.len(),
+            self.skipped_files.len(),
+            self.bytes_in,
+            self.bytes_out,
+            self.execution_time_ms
+        );
+        if let Some(rate) = self.throughput_bytes_per_sec() {
+            summary.push_str(&format!(" ({:.1} KiB/s)", rate / 1024.0));
+        }
+        summary
+    }
+
+    /// Per-directory breakdown of this result below `root`
+    pub fn tree_report(&self, root: &Path) -> TreeReport {
+        TreeReport::from_result(root, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_operation_result_statistics() {
+        let mut result = OperationResult::new();
+        result.add_success("a".to_string());
+        result.add_bytes(2048, 2100);
+        result.record_file_duration("a".to_string(), Duration::from_millis(5));
+        result.add_skipped("b.cage".to_string(), "already encrypted");
+        result.add_warning(WarningCategory::Metadata, "a".to_string(), "sidecar");
+        result.execution_time_ms = 1000;
+
+        assert_eq!(result.bytes_in, 2048);
+        assert_eq!(result.bytes_out, 2100);
+        assert_eq!(result.file_durations_ms, vec![("a".to_string(), 5)]);
+        assert_eq!(result.skipped_files[0].reason, "already encrypted");
+        assert_eq!(result.throughput_bytes_per_sec(), Some(2048.0));
+        assert!(result.summary().contains("1 skipped"));
+        assert_eq!(result.warnings[0].category, WarningCategory::Metadata);
+        assert_eq!(
+            serde_json::to_value(&result.warnings[0]).unwrap()["category"],
+            "metadata"
+        );
+    }
 }
@@ -0,0 +1,170 @@
+//! Orphans - Detection of empty or truncated ciphertext
+//!
+//! A failed or killed encryption can leave an output that is named like ciphertext but
+//! does not hold a complete age file, and later runs would treat it as encrypted.
+//! `CageManager` removes such outputs when its own lock fails; `inspect` and `scan` find
+//! the ones left behind by crashes, older versions or other tools (`cage verify
+//! --partial`).
+//!
+//! The checks are structural and need no key: a binary file must have a complete header
+//! followed by the 16-byte payload nonce and STREAM chunks of at most 64 KiB plus a
+//! 16-byte tag; an armored file must have its END line.
+
+use crate::core::armor::{self, ARMOR_BEGIN};
+use crate::core::AgeHeader;
+use crate::error::{AgeError, AgeResult};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+
+const VERSION_LINE: &[u8] = b"age-encryption.org/v1\n";
+const NONCE_BYTES: u64 = 16;
+const TAG_BYTES: u64 = 16;
+/// Encrypted STREAM chunk: 64 KiB of plaintext plus its tag
+const CHUNK_BYTES: u64 = 64 * 1024 + TAG_BYTES;
+
+/// Why a ciphertext looks incomplete
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Defect {
+    Empty,
+    /// Does not start with an age header at all
+    MissingHeader,
+    /// Header incomplete or malformed
+    TruncatedHeader,
+    /// Header intact but the payload is cut off
+    TruncatedPayload,
+    /// Armor without its END line
+    TruncatedArmor,
+}
+
+impl Defect {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Defect::Empty => "empty file",
+            Defect::MissingHeader => "no age header",
+            Defect::TruncatedHeader => "header incomplete or malformed",
+            Defect::TruncatedPayload => "payload truncated",
+            Defect::TruncatedArmor => "armor missing END line",
+        }
+    }
+}
+
+/// A ciphertext file that failed the structural checks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SuspectOutput {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub defect: Defect,
+}
+
+/// Check `path` for signs of a partial write; `None` when it looks complete
+pub fn inspect(path: &Path) -> AgeResult<Option<Defect>> {
+    let io_error = |e| AgeError::file_error("inspect", path.to_path_buf(), e);
+    let len = fs::metadata(path).map_err(io_error)?.len();
+    if len == 0 {
+        return Ok(Some(Defect::Empty));
+    }
+
+    let mut reader = BufReader::new(File::open(path).map_err(io_error)?);
+    let mut head = Vec::new();
+    (&mut reader)
+        .take(ARMOR_BEGIN.len() as u64)
+        .read_to_end(&mut head)
+        .map_err(io_error)?;
+
+    if head.starts_with(ARMOR_BEGIN) {
+        let complete = armor::is_terminated(path)?;
+        return Ok((!complete).then_some(Defect::TruncatedArmor));
+    }
+    if !head.starts_with(VERSION_LINE) {
+        // A cut-off version line is a truncated header; anything else is not age
+        return Ok(Some(if VERSION_LINE.starts_with(&head) {
+            Defect::TruncatedHeader
+        } else {
+            Defect::MissingHeader
+        }));
+    }
+
+    reader.rewind().map_err(io_error)?;
+    if AgeHeader::read_from(&mut reader).is_err() {
+        return Ok(Some(Defect::TruncatedHeader));
+    }
+    let header_len = reader.stream_position().map_err(io_error)?;
+    let payload = len.saturating_sub(header_len);
+    if payload < NONCE_BYTES + TAG_BYTES {
+        return Ok(Some(Defect::TruncatedPayload));
+    }
+    // The final chunk may be short, but never shorter than its tag
+    let last_chunk = (payload - NONCE_BYTES) % CHUNK_BYTES;
+    if last_chunk != 0 && last_chunk < TAG_BYTES {
+        return Ok(Some(Defect::TruncatedPayload));
+    }
+    Ok(None)
+}
+
+/// Inspect `files`, returning those that look partially written
+pub fn scan(files: &[PathBuf]) -> AgeResult<Vec<SuspectOutput>> {
+    let mut suspects = Vec::new();
+    for file in files {
+        if let Some(defect) = inspect(file)? {
+            suspects.push(SuspectOutput {
+                path: file.clone(),
+                bytes: fs::metadata(file).map(|m| m.len()).unwrap_or(0),
+                defect,
+            });
+        }
+    }
+    Ok(suspects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_inspect_flags_partial_outputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let write = |name: &str, data: &[u8]| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, data).unwrap();
+            path
+        };
+        let header = b"age-encryption.org/v1\n-> X25519 abc\nAAAA\n--- bWFj\n".to_vec();
+        let with_payload = |bytes: usize| {
+            let mut data = header.clone();
+            data.extend(vec![0u8; bytes]);
+            data
+        };
+
+        let complete = write("ok.cage", &with_payload(16 + 40));
+        let empty = write("empty.cage", b"");
+        let cut_version = write("version.cage", b"age-encr");
+        let cut_header = write("header.cage", b"age-encryption.org/v1\n-> X25519 abc\n");
+        let cut_payload = write("payload.cage", &with_payload(20));
+        let cut_tag = write("tag.cage", &with_payload(16 + CHUNK_BYTES as usize + 5));
+        let plain = write("plain.cage", b"just some text");
+        let armor = write("armor.cage", b"-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n");
+
+        assert_eq!(inspect(&complete).unwrap(), None);
+        assert_eq!(inspect(&empty).unwrap(), Some(Defect::Empty));
+        assert_eq!(
+            inspect(&cut_version).unwrap(),
+            Some(Defect::TruncatedHeader)
+        );
+        assert_eq!(inspect(&cut_header).unwrap(), Some(Defect::TruncatedHeader));
+        assert_eq!(
+            inspect(&cut_payload).unwrap(),
+            Some(Defect::TruncatedPayload)
+        );
+        assert_eq!(inspect(&cut_tag).unwrap(), Some(Defect::TruncatedPayload));
+        assert_eq!(inspect(&plain).unwrap(), Some(Defect::MissingHeader));
+        assert_eq!(inspect(&armor).unwrap(), Some(Defect::TruncatedArmor));
+
+        let suspects = scan(&[complete, empty.clone()]).unwrap();
+        assert_eq!(suspects.len(), 1);
+        assert_eq!(suspects[0].path, empty);
+    }
+}
@@ -0,0 +1,70 @@
+//! Plugins - Registration of custom operations run by `CageManager`
+//!
+//! External crates implement `Operation` for domain-specific work (a database dump
+//! followed by encryption, say) and register it with `CageManager::register_operation`.
+//! `CageManager::execute_operation` then applies the same rails as the built-in
+//! operations: risky-path guards on the declared `targets`, pre/postcondition checks with
+//! stage context on errors, audit start/complete/error events and an aggregated
+//! `OperationResult` recorded in the operation history.
+//!
+//! The stable surface is the `Operation` trait, this registry and `OperationResult`;
+//! implementors only need `operation_name`, the two validation hooks and `execute`.
+
+use super::Operation;
+use crate::error::{AgeError, AgeResult};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Custom operation as stored in the registry
+pub type SharedOperation = Arc<dyn Operation + Send + Sync>;
+
+/// Custom operations keyed by `Operation::operation_name`
+#[derive(Default, Clone)]
+pub struct OperationRegistry {
+    operations: BTreeMap<&'static str, SharedOperation>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `operation`; names must be unique
+    pub fn register(&mut self, operation: SharedOperation) -> AgeResult<()> {
+        let name = operation.operation_name();
+        if self.operations.contains_key(name) {
+            return Err(AgeError::InvalidOperation {
+                operation: "register_operation".to_string(),
+                reason: format!("An operation named '{}' is already registered", name),
+            });
+        }
+        self.operations.insert(name, operation);
+        Ok(())
+    }
+
+    /// Remove and return the operation registered as `name`
+    pub fn unregister(&mut self, name: &str) -> Option<SharedOperation> {
+        self.operations.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<SharedOperation> {
+        self.operations.get(name).cloned()
+    }
+
+    /// Registered names in sorted order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.operations.keys().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+impl std::fmt::Debug for OperationRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperationRegistry")
+            .field("operations", &self.names())
+            .finish()
+    }
+}
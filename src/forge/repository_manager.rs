@@ -0,0 +1,813 @@
+//! Repository Manager - Pluggable repository traversal and batch execution
+//!
+//! `RepositoryManager` is the concrete `RepositoryOperations` implementation backed by the
+//! v2 adapter. Directory traversal (`Traversal`), file filtering (`FileFilter`) and
+//! parallelism are pluggable, so library users can drive repository operations directly.
+//! `CageManager` delegates its file discovery and repository status to this type.
+//!
+//! Besides a glob and the encryption state, a filter can bound modification times
+//! (`MtimeWindow`, the CLI's `--newer-than` / `--older-than`) for incremental runs.
+
+use super::{OperationResult, RepositoryOperations, RepositoryStatus};
+use crate::adp::v2::{AgeAdapterV2, ShellAdapterV2};
+use crate::core::{AgeConfig, Identity, MtimeWindow, OutputFormat};
+use crate::error::{AgeError, AgeResult};
+use globset::{Glob, GlobMatcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// What a path is, judged from its metadata without opening it (symlinks are followed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// Regular file, including zero-byte files
+    File,
+    Directory,
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+}
+
+impl EntryKind {
+    /// Classify `path`; `None` if it does not exist or is a dangling symlink
+    pub fn of(path: &Path) -> Option<Self> {
+        let file_type = std::fs::metadata(path).ok()?.file_type();
+        if file_type.is_file() {
+            return Some(EntryKind::File);
+        }
+        if file_type.is_dir() {
+            return Some(EntryKind::Directory);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            if file_type.is_fifo() {
+                return Some(EntryKind::Fifo);
+            }
+            if file_type.is_socket() {
+                return Some(EntryKind::Socket);
+            }
+            if file_type.is_block_device() {
+                return Some(EntryKind::BlockDevice);
+            }
+            if file_type.is_char_device() {
+                return Some(EntryKind::CharDevice);
+            }
+        }
+        None
+    }
+
+    /// Why a file of this kind is not locked or unlocked (`None` for files and directories)
+    pub fn skip_reason(self) -> Option<&'static str> {
+        match self {
+            EntryKind::File | EntryKind::Directory => None,
+            EntryKind::Fifo => Some("special file: FIFO"),
+            EntryKind::Socket => Some("special file: socket"),
+            EntryKind::BlockDevice => Some("special file: block device"),
+            EntryKind::CharDevice => Some("special file: character device"),
+        }
+    }
+}
+
+/// Skip reason for `path` if it is a FIFO, socket or device node
+pub fn special_file_reason(path: &Path) -> Option<&'static str> {
+    EntryKind::of(path).and_then(EntryKind::skip_reason)
+}
+
+/// Special files passed over by a traversal, with their kind
+pub type SkippedEntries = Vec<(PathBuf, EntryKind)>;
+
+/// Strategy for discovering candidate files beneath a root directory
+pub trait Traversal: Send + Sync {
+    /// Collect every file under `root` accepted by `filter`
+    fn collect(&self, root: &Path, filter: &FileFilter) -> AgeResult<Vec<PathBuf>>;
+
+    /// Like `collect`, also returning the special files accepted by `filter` that were
+    /// passed over. Special files are never opened.
+    fn collect_with_skips(
+        &self,
+        root: &Path,
+        filter: &FileFilter,
+    ) -> AgeResult<(Vec<PathBuf>, SkippedEntries)> {
+        Ok((self.collect(root, filter)?, Vec::new()))
+    }
+}
+
+/// Depth-first traversal of the whole tree with symlink-loop protection.
+///
+/// Unreadable directories are skipped with a warning rather than aborting the walk.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecursiveTraversal;
+
+impl Traversal for RecursiveTraversal {
+    fn collect(&self, root: &Path, filter: &FileFilter) -> AgeResult<Vec<PathBuf>> {
+        Ok(self.collect_with_skips(root, filter)?.0)
+    }
+
+    fn collect_with_skips(
+        &self,
+        root: &Path,
+        filter: &FileFilter,
+    ) -> AgeResult<(Vec<PathBuf>, SkippedEntries)> {
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        let mut visited = HashSet::new();
+        walk(root, filter, &mut files, &mut skipped, &mut visited);
+        Ok((files, skipped))
+    }
+}
+
+fn walk(
+    directory: &Path,
+    filter: &FileFilter,
+    files: &mut Vec<PathBuf>,
+    skipped: &mut SkippedEntries,
+    visited: &mut HashSet<PathBuf>,
+) {
+    // Canonicalize to detect symlink loops
+    let canonical = directory
+        .canonicalize()
+        .unwrap_or_else(|_| directory.to_path_buf());
+    if !visited.insert(canonical) {
+        return;
+    }
+
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Skipping directory {}: {}", directory.display(), e);
+            return;
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                tracing::warn!("Skipping entry: {}", e);
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        match EntryKind::of(&path) {
+            Some(EntryKind::File) => {
+                if filter.matches(&path) {
+                    files.push(path);
+                }
+            }
+            Some(EntryKind::Directory) => {
+                if entry.file_name() != super::state_cache::STATE_DIR {
+                    walk(&path, filter, files, skipped, visited);
+                }
+            }
+            Some(kind) => {
+                if filter.matches(&path) {
+                    skipped.push((path, kind));
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// Traversal of the immediate children of the root only
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShallowTraversal;
+
+impl Traversal for ShallowTraversal {
+    fn collect(&self, root: &Path, filter: &FileFilter) -> AgeResult<Vec<PathBuf>> {
+        Ok(self.collect_with_skips(root, filter)?.0)
+    }
+
+    fn collect_with_skips(
+        &self,
+        root: &Path,
+        filter: &FileFilter,
+    ) -> AgeResult<(Vec<PathBuf>, SkippedEntries)> {
+        let entries = std::fs::read_dir(root)
+            .map_err(|e| AgeError::file_error("read_dir", root.to_path_buf(), e))?;
+
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        for entry in entries {
+            let entry =
+                entry.map_err(|e| AgeError::file_error("read_entry", root.to_path_buf(), e))?;
+            let path = entry.path();
+            match EntryKind::of(&path) {
+                Some(EntryKind::File) if filter.matches(&path) => files.push(path),
+                Some(kind) if kind.skip_reason().is_some() && filter.matches(&path) => {
+                    skipped.push((path, kind))
+                }
+                _ => {}
+            }
+        }
+
+        Ok((files, skipped))
+    }
+}
+
+/// Files in one traversal that share a device and inode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardlinkGroup {
+    /// Instance that is actually processed
+    pub primary: PathBuf,
+    /// Other paths linking to the same inode
+    pub links: Vec<PathBuf>,
+}
+
+/// Split `files` into one instance per inode plus the hardlink groups found.
+///
+/// Order of the unique files is preserved. Always returns no groups off Unix.
+pub fn group_hardlinks(files: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<HardlinkGroup>) {
+    #[cfg(unix)]
+    {
+        use std::collections::HashMap;
+        use std::os::unix::fs::MetadataExt;
+
+        let mut unique = Vec::with_capacity(files.len());
+        let mut groups: Vec<HardlinkGroup> = Vec::new();
+        let mut by_inode: HashMap<(u64, u64), usize> = HashMap::new();
+        for file in files {
+            let inode = std::fs::metadata(&file)
+                .ok()
+                .filter(|meta| meta.nlink() > 1)
+                .map(|meta| (meta.dev(), meta.ino()));
+            let Some(inode) = inode else {
+                unique.push(file);
+                continue;
+            };
+            match by_inode.get(&inode) {
+                Some(&index) => groups[index].links.push(file),
+                None => {
+                    by_inode.insert(inode, groups.len());
+                    groups.push(HardlinkGroup {
+                        primary: file.clone(),
+                        links: Vec::new(),
+                    });
+                    unique.push(file);
+                }
+            }
+        }
+        groups.retain(|group| !group.links.is_empty());
+        (unique, groups)
+    }
+    #[cfg(not(unix))]
+    {
+        (files, Vec::new())
+    }
+}
+
+/// Which files a filter accepts based on their encryption state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSelection {
+    All,
+    EncryptedOnly,
+    UnencryptedOnly,
+}
+
+/// File filter combining a filename glob with encrypted-state selection
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    matcher: Option<GlobMatcher>,
+    selection: FileSelection,
+    mtime: MtimeWindow,
+    config: AgeConfig,
+}
+
+impl FileFilter {
+    /// Accept every file
+    pub fn all(config: &AgeConfig) -> Self {
+        Self {
+            matcher: None,
+            selection: FileSelection::All,
+            mtime: MtimeWindow::default(),
+            config: config.clone(),
+        }
+    }
+
+    /// Restrict to files whose name matches `pattern`
+    pub fn with_pattern(mut self, pattern: Option<&str>) -> AgeResult<Self> {
+        self.matcher = pattern
+            .map(|p| {
+                Glob::new(p)
+                    .map(|glob| glob.compile_matcher())
+                    .map_err(|e| AgeError::InvalidOperation {
+                        operation: "pattern_matching".to_string(),
+                        reason: format!("Invalid glob pattern '{}': {}", p, e),
+                    })
+            })
+            .transpose()?;
+        Ok(self)
+    }
+
+    /// Restrict by encryption state (judged by configured encrypted extensions)
+    pub fn with_selection(mut self, selection: FileSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Restrict to files modified inside `window`
+    pub fn with_mtime(mut self, window: MtimeWindow) -> Self {
+        self.mtime = window;
+        self
+    }
+
+    /// Whether `path` passes the filter (`.meta` sidecars never do)
+    pub fn matches(&self, path: &Path) -> bool {
+        if crate::core::meta::is_sidecar(path, &self.config) {
+            return false;
+        }
+        let encrypted = self.config.is_encrypted_file(path);
+        let selected = match self.selection {
+            FileSelection::All => true,
+            FileSelection::EncryptedOnly => encrypted,
+            FileSelection::UnencryptedOnly => !encrypted,
+        };
+        if !selected {
+            return false;
+        }
+
+        let named = match self.matcher {
+            Some(ref matcher) => path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .map_or(false, |name| matcher.is_match(name)),
+            None => true,
+        };
+        named && self.mtime.contains(path)
+    }
+}
+
+/// Repository operations with pluggable traversal, filtering and parallelism
+pub struct RepositoryManager {
+    config: AgeConfig,
+    traversal: Box<dyn Traversal>,
+    parallelism: usize,
+    adapter: Option<Arc<dyn AgeAdapterV2>>,
+    mtime: MtimeWindow,
+}
+
+impl RepositoryManager {
+    /// Create a manager using recursive traversal and sequential execution
+    pub fn new(config: AgeConfig) -> Self {
+        Self {
+            config,
+            traversal: Box::new(RecursiveTraversal),
+            parallelism: 1,
+            adapter: None,
+            mtime: MtimeWindow::default(),
+        }
+    }
+
+    /// Use a custom traversal strategy
+    pub fn with_traversal(mut self, traversal: impl Traversal + 'static) -> Self {
+        self.traversal = Box::new(traversal);
+        self
+    }
+
+    /// Process up to `parallelism` files concurrently (minimum 1)
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Use a specific adapter for `RepositoryOperations`; defaults to `ShellAdapterV2`
+    pub fn with_adapter(mut self, adapter: Arc<dyn AgeAdapterV2>) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    /// Only collect files modified inside `window`
+    pub fn with_mtime_window(mut self, window: MtimeWindow) -> Self {
+        self.mtime = window;
+        self
+    }
+
+    pub fn config(&self) -> &AgeConfig {
+        &self.config
+    }
+
+    pub fn parallelism(&self) -> usize {
+        self.parallelism
+    }
+
+    /// Build a filter bound to this manager's config
+    pub fn filter(&self, pattern: Option<&str>, selection: FileSelection) -> AgeResult<FileFilter> {
+        Ok(FileFilter::all(&self.config)
+            .with_pattern(pattern)?
+            .with_selection(selection)
+            .with_mtime(self.mtime))
+    }
+
+    /// Collect files under `root` using the configured traversal, in the configured order
+    pub fn collect_files(
+        &self,
+        root: &Path,
+        pattern: Option<&str>,
+        selection: FileSelection,
+    ) -> AgeResult<Vec<PathBuf>> {
+        let mut files = self
+            .traversal
+            .collect(root, &self.filter(pattern, selection)?)?;
+        self.config
+            .listing_order
+            .arrange(&mut files, PathBuf::as_path);
+        Ok(files)
+    }
+
+    /// Collect files under `root`, plus the special files that were passed over
+    pub fn collect_files_with_skips(
+        &self,
+        root: &Path,
+        pattern: Option<&str>,
+        selection: FileSelection,
+    ) -> AgeResult<(Vec<PathBuf>, SkippedEntries)> {
+        let (mut files, mut skipped) = self
+            .traversal
+            .collect_with_skips(root, &self.filter(pattern, selection)?)?;
+        let order = self.config.listing_order;
+        order.arrange(&mut files, PathBuf::as_path);
+        order.arrange(&mut skipped, |(path, _)| path.as_path());
+        Ok((files, skipped))
+    }
+
+    /// Count encrypted and unencrypted files under `root`
+    pub fn status(&self, root: &Path) -> AgeResult<RepositoryStatus> {
+        let mut status = RepositoryStatus::new();
+        for file in self
+            .traversal
+            .collect(root, &FileFilter::all(&self.config))?
+        {
+            status.total_files += 1;
+            if self.config.is_encrypted_file(&file) {
+                status.encrypted_files += 1;
+            } else {
+                status.unencrypted_files += 1;
+            }
+        }
+        Ok(status)
+    }
+
+    /// Output path for encrypting `file` (configured extension appended)
+    pub fn encrypted_output_path(&self, file: &Path) -> PathBuf {
+        let mut path = file.as_os_str().to_os_string();
+        path.push(self.config.extension_with_dot());
+        PathBuf::from(path)
+    }
+
+    /// Output path for decrypting `file`, or `None` if it lacks the configured extension
+    pub fn decrypted_output_path(&self, file: &Path) -> Option<PathBuf> {
+        let name = file.file_name()?.to_str()?;
+        let stem = name.strip_suffix(&self.config.extension_with_dot())?;
+        if stem.is_empty() {
+            return None;
+        }
+        Some(file.with_file_name(stem))
+    }
+
+    /// Run `op` on each file, up to `parallelism` at a time.
+    ///
+    /// Results are returned in input order regardless of completion order.
+    pub fn process<F>(&self, files: &[PathBuf], op: F) -> Vec<(PathBuf, AgeResult<()>)>
+    where
+        F: Fn(&Path) -> AgeResult<()> + Sync,
+    {
+        let workers = self.parallelism.min(files.len()).max(1);
+        if workers == 1 {
+            return files.iter().map(|f| (f.clone(), op(f))).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let slots: Mutex<Vec<Option<AgeResult<()>>>> =
+            Mutex::new(files.iter().map(|_| None).collect());
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(file) = files.get(index) else {
+                        break;
+                    };
+                    let outcome = op(file);
+                    slots.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(outcome);
+                });
+            }
+        });
+
+        files
+            .iter()
+            .cloned()
+            .zip(slots.into_inner().unwrap_or_else(|e| e.into_inner()))
+            .map(|(file, outcome)| (file, outcome.unwrap_or(Ok(()))))
+            .collect()
+    }
+
+    fn adapter(&self) -> AgeResult<Arc<dyn AgeAdapterV2>> {
+        match self.adapter {
+            Some(ref adapter) => Ok(Arc::clone(adapter)),
+            None => Ok(Arc::new(ShellAdapterV2::with_config(self.config.clone())?)),
+        }
+    }
+
+    fn run_batch<F>(&self, operation: &str, files: &[PathBuf], op: F) -> AgeResult<OperationResult>
+    where
+        F: Fn(&Path) -> AgeResult<()> + Sync,
+    {
+        let start_time = Instant::now();
+        let mut result = OperationResult::new();
+        let mut failures = Vec::new();
+
+        for (file, outcome) in self.process(files, op) {
+            match outcome {
+                Ok(()) => result.add_success(file.display().to_string()),
+                Err(e) => {
+                    tracing::error!("Failed to {} {}: {}", operation, file.display(), e);
+                    failures.push(format!("{}: {}", file.display(), e));
+                    result.add_failure(file.display().to_string());
+                }
+            }
+        }
+
+        result.finalize(start_time);
+        if !failures.is_empty() {
+            return Err(AgeError::BatchOperationFailed {
+                operation: operation.to_string(),
+                successful_count: result.processed_files.len(),
+                failed_count: failures.len(),
+                failures,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Encrypt every unencrypted file under `repo_path` with `identity`
+    pub fn encrypt_with_identity(
+        &self,
+        repo_path: &Path,
+        identity: &Identity,
+        format: OutputFormat,
+    ) -> AgeResult<OperationResult> {
+        let adapter = self.adapter()?;
+        let files = self.collect_files(repo_path, None, FileSelection::UnencryptedOnly)?;
+        self.run_batch("encrypt", &files, |file| {
+            adapter.encrypt_file(
+                file,
+                &self.encrypted_output_path(file),
+                identity,
+                None,
+                format,
+            )
+        })
+    }
+
+    /// Decrypt every encrypted file under `repo_path` with `identity`
+    pub fn decrypt_with_identity(
+        &self,
+        repo_path: &Path,
+        identity: &Identity,
+    ) -> AgeResult<OperationResult> {
+        let adapter = self.adapter()?;
+        let files = self.collect_files(repo_path, None, FileSelection::EncryptedOnly)?;
+        self.run_batch("decrypt", &files, |file| {
+            let output =
+                self.decrypted_output_path(file)
+                    .ok_or_else(|| AgeError::InvalidOperation {
+                        operation: "decrypt".to_string(),
+                        reason: format!("Cannot derive output path for {}", file.display()),
+                    })?;
+            adapter.decrypt_file(file, &output, identity)
+        })
+    }
+}
+
+impl RepositoryOperations for RepositoryManager {
+    fn encrypt_repository(
+        &self,
+        repo_path: &Path,
+        passphrase: &str,
+        format: OutputFormat,
+    ) -> AgeResult<()> {
+        self.encrypt_with_identity(
+            repo_path,
+            &Identity::Passphrase(passphrase.to_string()),
+            format,
+        )?;
+        Ok(())
+    }
+
+    fn decrypt_repository(&self, repo_path: &Path, passphrase: &str) -> AgeResult<()> {
+        self.decrypt_with_identity(repo_path, &Identity::Passphrase(passphrase.to_string()))?;
+        Ok(())
+    }
+
+    fn repository_status(&self, repo_path: &Path) -> AgeResult<RepositoryStatus> {
+        self.status(repo_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fixture() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("b.cage"), "b").unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("nested/c.txt"), "c").unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_group_hardlinks() {
+        let dir = fixture();
+        let a = dir.path().join("a.txt");
+        let link = dir.path().join("nested/a-link.txt");
+        std::fs::hard_link(&a, &link).unwrap();
+        let c = dir.path().join("nested/c.txt");
+
+        let (unique, groups) = group_hardlinks(vec![a.clone(), c.clone(), link.clone()]);
+        assert_eq!(unique, vec![a.clone(), c]);
+        assert_eq!(
+            groups,
+            vec![HardlinkGroup {
+                primary: a,
+                links: vec![link],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_traversal_and_filtering() {
+        let dir = fixture();
+        let manager = RepositoryManager::new(AgeConfig::default());
+
+        let mut all = manager
+            .collect_files(dir.path(), None, FileSelection::All)
+            .unwrap();
+        all.sort();
+        assert_eq!(all.len(), 3);
+
+        let encrypted = manager
+            .collect_files(dir.path(), None, FileSelection::EncryptedOnly)
+            .unwrap();
+        assert_eq!(encrypted, vec![dir.path().join("b.cage")]);
+
+        let shallow = RepositoryManager::new(AgeConfig::default()).with_traversal(ShallowTraversal);
+        let txt = shallow
+            .collect_files(dir.path(), Some("*.txt"), FileSelection::All)
+            .unwrap();
+        assert_eq!(txt, vec![dir.path().join("a.txt")]);
+
+        let status = manager.status(dir.path()).unwrap();
+        assert_eq!(status.total_files, 3);
+        assert_eq!(status.encrypted_files, 1);
+    }
+
+    #[test]
+    fn test_listings_sorted_by_path() {
+        let dir = fixture();
+        for name in ["z.txt", "m.txt", "a.md"] {
+            std::fs::write(dir.path().join(name), name).unwrap();
+        }
+        let manager = RepositoryManager::new(AgeConfig::default());
+        let files = manager
+            .collect_files(dir.path(), None, FileSelection::All)
+            .unwrap();
+        let relative: Vec<_> = files
+            .iter()
+            .map(|file| file.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        let expected: Vec<PathBuf> = ["a.md", "a.txt", "b.cage", "m.txt", "nested/c.txt", "z.txt"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert_eq!(relative, expected);
+
+        let unordered = AgeConfig {
+            listing_order: crate::core::ListingOrder::Filesystem,
+            ..AgeConfig::default()
+        };
+        let mut files = RepositoryManager::new(unordered)
+            .collect_files(dir.path(), None, FileSelection::All)
+            .unwrap();
+        files.sort();
+        assert_eq!(files.len(), expected.len());
+    }
+
+    #[test]
+    fn test_mtime_window_filters_traversal() {
+        use std::time::{Duration, SystemTime};
+        let dir = fixture();
+        let now = SystemTime::now();
+        let old = dir.path().join("a.txt");
+        std::fs::File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(now - Duration::from_secs(10 * 86_400))
+            .unwrap();
+
+        let week_ago = MtimeWindow::parse_bound("7d", now).unwrap();
+        let recent = RepositoryManager::new(AgeConfig::default()).with_mtime_window(MtimeWindow {
+            newer_than: Some(week_ago),
+            older_than: None,
+        });
+        let mut files = recent
+            .collect_files(dir.path(), None, FileSelection::All)
+            .unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![dir.path().join("b.cage"), dir.path().join("nested/c.txt")]
+        );
+
+        let stale = RepositoryManager::new(AgeConfig::default()).with_mtime_window(MtimeWindow {
+            newer_than: None,
+            older_than: Some(week_ago),
+        });
+        assert_eq!(
+            stale
+                .collect_files(dir.path(), None, FileSelection::All)
+                .unwrap(),
+            vec![old]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_special_files_are_skipped() {
+        let dir = fixture();
+        std::fs::write(dir.path().join("empty.txt"), b"").unwrap();
+        let fifo = dir.path().join("nested/pipe");
+        let fifo_c = std::ffi::CString::new(fifo.to_str().unwrap()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(fifo_c.as_ptr(), 0o600) }, 0);
+        let socket = dir.path().join("sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let manager = RepositoryManager::new(AgeConfig::default());
+        let (mut files, mut skipped) = manager
+            .collect_files_with_skips(dir.path(), None, FileSelection::All)
+            .unwrap();
+        files.sort();
+        skipped.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(files.len(), 4);
+        assert!(files.contains(&dir.path().join("empty.txt")));
+        assert_eq!(
+            skipped,
+            vec![(fifo.clone(), EntryKind::Fifo), (socket, EntryKind::Socket)]
+        );
+        assert_eq!(special_file_reason(&fifo), Some("special file: FIFO"));
+        assert_eq!(special_file_reason(&dir.path().join("a.txt")), None);
+
+        let shallow = RepositoryManager::new(AgeConfig::default()).with_traversal(ShallowTraversal);
+        let (_, skipped) = shallow
+            .collect_files_with_skips(dir.path(), Some("*.txt"), FileSelection::All)
+            .unwrap();
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_process_preserves_order_in_parallel() {
+        let manager = RepositoryManager::new(AgeConfig::default()).with_parallelism(4);
+        let files: Vec<PathBuf> = (0..16).map(|i| PathBuf::from(format!("f{}", i))).collect();
+
+        let results = manager.process(&files, |file| {
+            if file == Path::new("f3") {
+                Err(AgeError::InvalidOperation {
+                    operation: "test".to_string(),
+                    reason: "boom".to_string(),
+                })
+            } else {
+                Ok(())
+            }
+        });
+
+        let order: Vec<PathBuf> = results.iter().map(|(f, _)| f.clone()).collect();
+        assert_eq!(order, files);
+        assert_eq!(results.iter().filter(|(_, r)| r.is_err()).count(), 1);
+        assert!(results[3].1.is_err());
+    }
+
+    #[test]
+    fn test_output_paths() {
+        let manager = RepositoryManager::new(AgeConfig::default());
+        assert_eq!(
+            manager.encrypted_output_path(Path::new("/r/secret.txt")),
+            PathBuf::from("/r/secret.txt.cage")
+        );
+        assert_eq!(
+            manager.decrypted_output_path(Path::new("/r/secret.txt.cage")),
+            Some(PathBuf::from("/r/secret.txt"))
+        );
+        assert_eq!(
+            manager.decrypted_output_path(Path::new("/r/plain.txt")),
+            None
+        );
+    }
+}
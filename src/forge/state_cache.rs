@@ -0,0 +1,356 @@
+//! Repository State Cache - Fast status for large trees
+//!
+//! `.cage/state.json` at a repository root records, per directory, its mtime and the
+//! encryption state, size and mtime of each file in it. A refresh stats every directory
+//! but only re-lists (and re-stats the files of) directories whose mtime changed, so
+//! `cage status --fast` on an untouched tree never reads a directory listing.
+//!
+//! The cache is opt-in: it is created by the first fast status and afterwards kept
+//! current by lock and unlock for the paths they touch. A missing or corrupt cache is
+//! rebuilt from disk. File entries only refresh when their directory changes, so edits
+//! to file contents leave stale sizes until the next change in that directory.
+
+use super::repository_manager::{FileFilter, Traversal};
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory holding repository state; never traversed
+pub const STATE_DIR: &str = ".cage";
+const STATE_FILE: &str = "state.json";
+
+/// Cached state of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileState {
+    pub encrypted: bool,
+    pub size: u64,
+    pub mtime_ns: u128,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DirState {
+    mtime_ns: u128,
+    files: BTreeMap<String, FileState>,
+    subdirs: Vec<String>,
+}
+
+/// Work done by a refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshStats {
+    /// Directories whose cached listing was reused
+    pub reused_dirs: usize,
+    /// Directories re-listed because they changed or were not cached
+    pub rescanned_dirs: usize,
+}
+
+/// Per-directory state cache rooted at a repository directory
+#[derive(Debug, Clone)]
+pub struct StateCache {
+    root: PathBuf,
+    /// Keyed by path relative to `root` ("" for the root itself)
+    dirs: BTreeMap<String, DirState>,
+}
+
+impl StateCache {
+    /// Cache file for a repository root
+    pub fn state_path(root: &Path) -> PathBuf {
+        root.join(STATE_DIR).join(STATE_FILE)
+    }
+
+    /// Load the cache for `root`; missing or unreadable state starts empty
+    pub fn load(root: &Path) -> Self {
+        let dirs = fs::read(Self::state_path(root))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        Self {
+            root: root.to_path_buf(),
+            dirs,
+        }
+    }
+
+    /// Cache of the nearest directory at or above `path` that already has one
+    pub fn find(path: &Path) -> Option<Self> {
+        let absolute = std::path::absolute(path).ok()?;
+        absolute
+            .ancestors()
+            .filter(|dir| dir.is_dir())
+            .find(|dir| Self::state_path(dir).is_file())
+            .map(Self::load)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn save(&self) -> AgeResult<()> {
+        let path = Self::state_path(&self.root);
+        let dir = self.root.join(STATE_DIR);
+        fs::create_dir_all(&dir)
+            .map_err(|e| AgeError::file_error("create_state_dir", dir.clone(), e))?;
+        let json = serde_json::to_vec(&self.dirs).map_err(|e| AgeError::InvalidOperation {
+            operation: "save_state".to_string(),
+            reason: e.to_string(),
+        })?;
+        fs::write(&path, json).map_err(|e| AgeError::file_error("write_state", path, e))
+    }
+
+    /// Bring the subtree at `start` (inside the root) up to date with the disk
+    pub fn refresh(&mut self, start: &Path, config: &AgeConfig) -> RefreshStats {
+        let mut stats = RefreshStats::default();
+        let Some(key) = self.key_for(start) else {
+            return stats;
+        };
+        let mut visited = HashSet::new();
+        self.refresh_dir(&key, config, &mut stats, &mut visited);
+        stats
+    }
+
+    /// Cached files under `start`, with their state
+    pub fn files_under(&self, start: &Path) -> Vec<(PathBuf, &FileState)> {
+        let Some(key) = self.key_for(start) else {
+            return Vec::new();
+        };
+        let mut files = Vec::new();
+        let mut pending = vec![key];
+        while let Some(key) = pending.pop() {
+            let Some(dir) = self.dirs.get(&key) else {
+                continue;
+            };
+            let path = self.root.join(&key);
+            files.extend(
+                dir.files
+                    .iter()
+                    .map(|(name, state)| (path.join(name), state)),
+            );
+            pending.extend(dir.subdirs.iter().map(|sub| join_key(&key, sub)));
+        }
+        files
+    }
+
+    fn key_for(&self, path: &Path) -> Option<String> {
+        let absolute = std::path::absolute(path).ok()?;
+        let root = std::path::absolute(&self.root).ok()?;
+        let relative = absolute.strip_prefix(root).ok()?;
+        Some(relative.to_str()?.to_string())
+    }
+
+    fn refresh_dir(
+        &mut self,
+        key: &str,
+        config: &AgeConfig,
+        stats: &mut RefreshStats,
+        visited: &mut HashSet<PathBuf>,
+    ) {
+        let path = self.root.join(key);
+        // Canonicalize to detect symlink loops
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if !visited.insert(canonical) {
+            return;
+        }
+
+        let Some(mtime_ns) = fs::metadata(&path)
+            .ok()
+            .map(|meta| mtime_ns(meta.modified()))
+        else {
+            self.forget(key);
+            return;
+        };
+
+        let subdirs = match self.dirs.get(key) {
+            Some(cached) if cached.mtime_ns == mtime_ns => {
+                stats.reused_dirs += 1;
+                cached.subdirs.clone()
+            }
+            _ => {
+                stats.rescanned_dirs += 1;
+                let state = scan_dir(&path, mtime_ns, config);
+                let subdirs = state.subdirs.clone();
+                self.replace(key, state);
+                subdirs
+            }
+        };
+
+        for sub in subdirs {
+            self.refresh_dir(&join_key(key, &sub), config, stats, visited);
+        }
+    }
+
+    /// Store a fresh listing, dropping cached subtrees that disappeared
+    fn replace(&mut self, key: &str, state: DirState) {
+        let removed: Vec<String> = self
+            .dirs
+            .get(key)
+            .map(|old| {
+                old.subdirs
+                    .iter()
+                    .filter(|sub| !state.subdirs.contains(sub))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        for sub in removed {
+            self.forget(&join_key(key, &sub));
+        }
+        self.dirs.insert(key.to_string(), state);
+    }
+
+    fn forget(&mut self, key: &str) {
+        if let Some(state) = self.dirs.remove(key) {
+            for sub in state.subdirs {
+                self.forget(&join_key(key, &sub));
+            }
+        }
+    }
+}
+
+/// Traversal answering from the state cache at the traversal root.
+///
+/// The cache is refreshed and saved on every collect; failing to save only warns.
+#[derive(Debug, Clone)]
+pub struct CachedTraversal {
+    config: AgeConfig,
+}
+
+impl CachedTraversal {
+    pub fn new(config: AgeConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Traversal for CachedTraversal {
+    fn collect(&self, root: &Path, filter: &FileFilter) -> AgeResult<Vec<PathBuf>> {
+        let mut cache = StateCache::load(root);
+        cache.refresh(root, &self.config);
+        if let Err(e) = cache.save() {
+            tracing::warn!("State cache not saved: {}", e);
+        }
+        Ok(cache
+            .files_under(root)
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|path| filter.matches(path))
+            .collect())
+    }
+}
+
+fn scan_dir(path: &Path, mtime: u128, config: &AgeConfig) -> DirState {
+    let mut state = DirState {
+        mtime_ns: mtime,
+        ..DirState::default()
+    };
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Skipping directory {}: {}", path.display(), e);
+            return state;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let Some(name) = entry.file_name().to_str().map(String::from) else {
+            continue;
+        };
+        // Follow symlinks like the recursive traversal does
+        let Ok(meta) = fs::metadata(&entry_path) else {
+            continue;
+        };
+        if meta.is_file() {
+            state.files.insert(
+                name,
+                FileState {
+                    encrypted: config.is_encrypted_file(&entry_path),
+                    size: meta.len(),
+                    mtime_ns: mtime_ns(meta.modified()),
+                },
+            );
+        } else if meta.is_dir() && name != STATE_DIR {
+            state.subdirs.push(name);
+        }
+    }
+    state.subdirs.sort();
+    state
+}
+
+fn join_key(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        Path::new(parent).join(name).to_string_lossy().into_owned()
+    }
+}
+
+fn mtime_ns(modified: std::io::Result<SystemTime>) -> u128 {
+    modified
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names(cache: &StateCache, root: &Path) -> Vec<String> {
+        let mut names: Vec<String> = cache
+            .files_under(root)
+            .into_iter()
+            .map(|(path, _)| path.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_refresh_reuses_unchanged_directories() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let config = AgeConfig::default();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("top.txt"), b"x").unwrap();
+        fs::write(root.join("a/b/secret.txt.cage"), b"x").unwrap();
+
+        // Save first so creating the state directory does not count as a root change
+        let mut cache = StateCache::load(root);
+        cache.save().unwrap();
+        let first = cache.refresh(root, &config);
+        assert_eq!(first.rescanned_dirs, 3);
+        cache.save().unwrap();
+        assert_eq!(names(&cache, root), ["a/b/secret.txt.cage", "top.txt"]);
+        let state = &cache.files_under(&root.join("a/b"))[0].1;
+        assert!(state.encrypted);
+
+        // Reloaded cache answers without re-listing anything
+        let mut cache = StateCache::find(&root.join("a/b")).unwrap();
+        let second = cache.refresh(root, &config);
+        assert_eq!(
+            second,
+            RefreshStats {
+                reused_dirs: 3,
+                rescanned_dirs: 0
+            }
+        );
+
+        // Removing a subtree drops its entries
+        fs::remove_dir_all(root.join("a/b")).unwrap();
+        cache.refresh(root, &config);
+        assert_eq!(names(&cache, root), ["top.txt"]);
+    }
+
+    #[test]
+    fn test_state_dir_is_not_listed() {
+        let temp = TempDir::new().unwrap();
+        let config = AgeConfig::default();
+        let mut cache = StateCache::load(temp.path());
+        cache.save().unwrap();
+        cache.refresh(temp.path(), &config);
+        assert!(cache.files_under(temp.path()).is_empty());
+    }
+}
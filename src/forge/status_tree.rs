@@ -0,0 +1,266 @@
+//! Status Tree - Nested encryption coverage for dashboards
+//!
+//! `RepositoryStatus` only counts files. `StatusTree` keeps the directory structure:
+//! every directory carries aggregates for its whole subtree and lists its own files with
+//! size, modification time, whether they count as encrypted and the age encoding found
+//! in their header. A file that cannot be read is still listed, with `format: null`
+//! and the reason in `error`. `cage status --recursive --json` prints it; the shape is
+//! versioned by `schema` so dashboards ingesting many repositories can rely on it.
+
+use crate::core::chunked;
+use crate::core::header::HeaderFormat;
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Version of the JSON layout; bumped on incompatible changes
+pub const STATUS_TREE_SCHEMA: u32 = 1;
+
+/// One file of a status tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusFile {
+    pub name: String,
+    pub size: u64,
+    /// Modification time (RFC 3339, UTC) when the platform reports one
+    pub mtime: Option<String>,
+    /// Counted as encrypted by the configured extensions, as in `cage status`
+    pub encrypted: bool,
+    /// `binary`, `armored` or `chunked` from the file header; `None` when not age or
+    /// unreadable
+    pub format: Option<&'static str>,
+    /// Why the file could not be inspected; it is still listed and counted
+    pub error: Option<String>,
+}
+
+/// Directory with aggregates for everything beneath it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StatusDir {
+    /// Directory name (the root carries the full root path)
+    pub name: String,
+    pub total_files: usize,
+    pub encrypted_files: usize,
+    pub unencrypted_files: usize,
+    pub total_bytes: u64,
+    pub encrypted_bytes: u64,
+    /// Share of encrypted files in this subtree, 0-100
+    pub encryption_percentage: f64,
+    /// Files directly in this directory, sorted by name
+    pub files: Vec<StatusFile>,
+    /// Subdirectories, sorted by name
+    pub children: Vec<StatusDir>,
+}
+
+/// Nested status report for a directory
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusTree {
+    pub schema: u32,
+    /// When the tree was collected (RFC 3339, UTC)
+    pub generated_at: String,
+    pub root: StatusDir,
+}
+
+/// Mutable build-time node; children keyed by name for sorted output
+#[derive(Default)]
+struct Builder {
+    dir: StatusDir,
+    children: BTreeMap<String, Builder>,
+}
+
+impl Builder {
+    fn add(&mut self, dirs: &[String], file: StatusFile) {
+        self.dir.total_files += 1;
+        self.dir.total_bytes += file.size;
+        if file.encrypted {
+            self.dir.encrypted_files += 1;
+            self.dir.encrypted_bytes += file.size;
+        } else {
+            self.dir.unencrypted_files += 1;
+        }
+        match dirs.split_first() {
+            Some((name, rest)) => self
+                .children
+                .entry(name.clone())
+                .or_insert_with(|| Builder {
+                    dir: StatusDir {
+                        name: name.clone(),
+                        ..StatusDir::default()
+                    },
+                    children: BTreeMap::new(),
+                })
+                .add(rest, file),
+            None => self.dir.files.push(file),
+        }
+    }
+
+    fn build(self) -> StatusDir {
+        let mut dir = self.dir;
+        if dir.total_files > 0 {
+            dir.encryption_percentage = dir.encrypted_files as f64 / dir.total_files as f64 * 100.0;
+        }
+        dir.files.sort_by(|a, b| a.name.cmp(&b.name));
+        dir.children = self.children.into_values().map(Builder::build).collect();
+        dir
+    }
+}
+
+impl StatusTree {
+    /// Build the tree for `files` below `root` (a file target uses its parent directory)
+    pub fn build(root: &Path, files: &[PathBuf], config: &AgeConfig) -> AgeResult<Self> {
+        let base = if root.is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+        let mut builder = Builder {
+            dir: StatusDir {
+                name: base.display().to_string(),
+                ..StatusDir::default()
+            },
+            children: BTreeMap::new(),
+        };
+        for path in files {
+            let (dirs, name) = split_relative(base, path);
+            builder.add(&dirs, describe(path, name, config));
+        }
+        Ok(Self {
+            schema: STATUS_TREE_SCHEMA,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            root: builder.build(),
+        })
+    }
+}
+
+/// Entry for `path`; a file that cannot be stat'ed or read keeps its place in the tree
+/// with the failure in `error`
+fn describe(path: &Path, name: String, config: &AgeConfig) -> StatusFile {
+    let mut file = StatusFile {
+        name,
+        size: 0,
+        mtime: None,
+        encrypted: config.is_encrypted_file(path),
+        format: None,
+        error: None,
+    };
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            file.error = Some(AgeError::file_error("stat", path.to_path_buf(), e).to_string());
+            return file;
+        }
+    };
+    file.size = metadata.len();
+    file.mtime = metadata
+        .modified()
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339());
+    match header_format(path) {
+        Ok(format) => file.format = format,
+        Err(e) => file.error = Some(e.to_string()),
+    }
+    file
+}
+
+/// Encoding named by the first bytes of `path`
+fn header_format(path: &Path) -> AgeResult<Option<&'static str>> {
+    let mut head = Vec::new();
+    File::open(path)
+        .and_then(|file| {
+            file.take(HeaderFormat::DETECT_BYTES as u64)
+                .read_to_end(&mut head)
+        })
+        .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+    Ok(match HeaderFormat::detect(&head) {
+        Some(HeaderFormat::Binary) => Some("binary"),
+        Some(HeaderFormat::Armored) => Some("armored"),
+        None if head.starts_with(chunked::MAGIC) => Some("chunked"),
+        None => None,
+    })
+}
+
+/// Split `path` into directory components below `base` and its file name
+fn split_relative(base: &Path, path: &Path) -> (Vec<String>, String) {
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    let mut parts: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    let file = parts.pop().unwrap_or_else(|| path.display().to_string());
+    (parts, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_status_tree_nests_files_and_aggregates() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/deep")).unwrap();
+        let files = [
+            ("README.md", b"hello".as_slice()),
+            ("src/key.cage", b"age-encryption.org/v1\n".as_slice()),
+            ("src/deep/a.txt", b"abc".as_slice()),
+        ]
+        .map(|(name, data)| {
+            let path = root.join(name);
+            std::fs::write(&path, data).unwrap();
+            path
+        });
+
+        let tree = StatusTree::build(root, &files, &AgeConfig::default()).unwrap();
+        let top = &tree.root;
+        assert_eq!((top.total_files, top.encrypted_files), (3, 1));
+        assert_eq!(top.total_bytes, 5 + 22 + 3);
+        assert_eq!(top.files[0].name, "README.md");
+        assert!(top.files[0].mtime.is_some());
+
+        let src = &top.children[0];
+        assert_eq!(src.name, "src");
+        assert_eq!(src.encryption_percentage, 50.0);
+        assert_eq!(src.files[0].format, Some("binary"));
+        assert!(src.files[0].encrypted);
+        assert_eq!(src.children[0].files[0].format, None);
+
+        let json = serde_json::to_value(&tree).unwrap();
+        assert_eq!(json["schema"], STATUS_TREE_SCHEMA);
+        assert_eq!(json["root"]["children"][0]["encrypted_bytes"], 22);
+        assert!(json["root"]["files"][0]["error"].is_null());
+    }
+
+    #[test]
+    fn test_status_tree_keeps_unreadable_files() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let good = root.join("a.txt");
+        std::fs::write(&good, "abc").unwrap();
+        // Vanished between listing and inspection, and one that cannot be read
+        let gone = root.join("b.cage");
+        let unreadable = root.join("c.dir");
+        std::fs::create_dir(&unreadable).unwrap();
+
+        let files = [good, gone, unreadable];
+        let tree = StatusTree::build(root, &files, &AgeConfig::default()).unwrap();
+        let listed = &tree.root.files;
+        assert_eq!(tree.root.total_files, 3);
+        assert_eq!(listed[0].error, None);
+        assert_eq!(listed[1].name, "b.cage");
+        assert!(listed[1].encrypted);
+        assert_eq!(listed[1].format, None);
+        assert!(listed[1].error.as_deref().unwrap().contains("b.cage"));
+        assert_eq!(listed[2].format, None);
+        assert!(listed[2].error.is_some());
+
+        let json = serde_json::to_value(&tree).unwrap();
+        assert!(json["root"]["files"][1]["format"].is_null());
+        assert!(json["root"]["files"][1]["error"].is_string());
+    }
+}
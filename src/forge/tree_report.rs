@@ -0,0 +1,239 @@
+//! Tree Report - Hierarchical per-directory summary of an operation
+//!
+//! `OperationResult` keeps flat file lists. `TreeReport` folds them into a directory tree
+//! with cumulative counts, input sizes and failures per subtree, so a large recursive run
+//! shows at a glance which parts of the repository failed. The CLI renders it with
+//! `--tree`; it serializes to JSON for `--json` output.
+
+use super::OperationResult;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Directory node with totals for everything beneath it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TreeNode {
+    /// Directory name (the root node carries the full root path)
+    pub name: String,
+    pub processed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Bytes read from processed files in this subtree
+    pub bytes_in: u64,
+    /// Bytes written for processed files in this subtree
+    pub bytes_out: u64,
+    /// Failed files directly in this directory
+    pub failures: Vec<String>,
+    /// Subdirectories, sorted by name
+    pub children: Vec<TreeNode>,
+}
+
+/// Hierarchical summary of an `OperationResult` below a root directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TreeReport {
+    pub root: TreeNode,
+}
+
+#[derive(Clone, Copy)]
+enum Outcome {
+    Processed,
+    Failed,
+    Skipped,
+}
+
+/// Mutable build-time node; children keyed by name for sorted output
+#[derive(Default)]
+struct Builder {
+    node: TreeNode,
+    children: BTreeMap<String, Builder>,
+}
+
+impl Builder {
+    fn add(&mut self, dirs: &[String], file: &str, outcome: Outcome, bytes: (u64, u64)) {
+        match outcome {
+            Outcome::Processed => {
+                self.node.processed += 1;
+                self.node.bytes_in += bytes.0;
+                self.node.bytes_out += bytes.1;
+            }
+            Outcome::Failed => self.node.failed += 1,
+            Outcome::Skipped => self.node.skipped += 1,
+        }
+        match dirs.split_first() {
+            Some((dir, rest)) => self
+                .children
+                .entry(dir.clone())
+                .or_insert_with(|| Builder {
+                    node: TreeNode {
+                        name: dir.clone(),
+                        ..TreeNode::default()
+                    },
+                    children: BTreeMap::new(),
+                })
+                .add(rest, file, outcome, bytes),
+            None => {
+                if let Outcome::Failed = outcome {
+                    self.node.failures.push(file.to_string());
+                }
+            }
+        }
+    }
+
+    fn build(self) -> TreeNode {
+        let mut node = self.node;
+        node.children = self.children.into_values().map(Builder::build).collect();
+        node
+    }
+}
+
+impl TreeReport {
+    /// Fold `result` into a tree rooted at `root` (a file target uses its parent directory)
+    pub fn from_result(root: &Path, result: &OperationResult) -> Self {
+        let base = if root.is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+        let sizes: BTreeMap<&str, (u64, u64)> = result
+            .file_bytes
+            .iter()
+            .map(|(path, bytes_in, bytes_out)| (path.as_str(), (*bytes_in, *bytes_out)))
+            .collect();
+
+        let mut builder = Builder {
+            node: TreeNode {
+                name: base.display().to_string(),
+                ..TreeNode::default()
+            },
+            children: BTreeMap::new(),
+        };
+        let mut add = |path: &str, outcome: Outcome| {
+            let (dirs, file) = split_relative(base, path);
+            let bytes = sizes.get(path).copied().unwrap_or_default();
+            builder.add(&dirs, &file, outcome, bytes);
+        };
+        for path in &result.processed_files {
+            add(path, Outcome::Processed);
+        }
+        for path in &result.failed_files {
+            add(path, Outcome::Failed);
+        }
+        for skipped in &result.skipped_files {
+            add(&skipped.path, Outcome::Skipped);
+        }
+
+        Self {
+            root: builder.build(),
+        }
+    }
+
+    /// Paths (relative to the root) of directories that contain failures, depth first
+    pub fn failed_subtrees(&self) -> Vec<PathBuf> {
+        fn walk(node: &TreeNode, path: PathBuf, out: &mut Vec<PathBuf>) {
+            if node.failed == 0 {
+                return;
+            }
+            out.push(path.clone());
+            for child in &node.children {
+                walk(child, path.join(&child.name), out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(&self.root, PathBuf::new(), &mut out);
+        out
+    }
+
+    /// Render as an indented tree, one directory per line
+    pub fn render(&self) -> String {
+        fn line(node: &TreeNode) -> String {
+            let mut text = format!(
+                "{}/  {} processed, {} failed, {} skipped, {} bytes",
+                node.name, node.processed, node.failed, node.skipped, node.bytes_in
+            );
+            if node.failed > 0 {
+                text.push_str("  ✗");
+            }
+            text
+        }
+        fn walk(node: &TreeNode, prefix: &str, out: &mut String) {
+            let count = node.children.len() + node.failures.len();
+            let mut index = 0;
+            for failure in &node.failures {
+                index += 1;
+                let branch = if index == count {
+                    "└── "
+                } else {
+                    "├── "
+                };
+                out.push_str(&format!("{}{}✗ {}\n", prefix, branch, failure));
+            }
+            for child in &node.children {
+                index += 1;
+                let last = index == count;
+                let branch = if last { "└── " } else { "├── " };
+                out.push_str(&format!("{}{}{}\n", prefix, branch, line(child)));
+                let nested = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                walk(child, &nested, out);
+            }
+        }
+
+        let mut out = format!("{}\n", line(&self.root));
+        walk(&self.root, "", &mut out);
+        out
+    }
+}
+
+/// Split `path` into directory components below `base` and its file name
+fn split_relative(base: &Path, path: &str) -> (Vec<String>, String) {
+    let full = Path::new(path);
+    let relative = full.strip_prefix(base).unwrap_or(full);
+    let mut parts: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    let file = parts.pop().unwrap_or_else(|| path.to_string());
+    (parts, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_report_aggregates_subtrees() {
+        let mut result = OperationResult::new();
+        result.add_success("/repo/a.txt".to_string());
+        result.add_file_bytes("/repo/a.txt".to_string(), 10, 20);
+        result.add_success("/repo/src/lib.rs".to_string());
+        result.add_file_bytes("/repo/src/lib.rs".to_string(), 5, 7);
+        result.add_failure("/repo/src/deep/bad.rs".to_string());
+        result.add_skipped("/repo/docs/x.cage".to_string(), "already encrypted");
+
+        let report = TreeReport::from_result(Path::new("/repo"), &result);
+        let root = &report.root;
+        assert_eq!((root.processed, root.failed, root.skipped), (2, 1, 1));
+        assert_eq!((root.bytes_in, root.bytes_out), (15, 27));
+
+        let names: Vec<&str> = root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "src"]);
+        let src = &root.children[1];
+        assert_eq!((src.processed, src.failed, src.bytes_in), (1, 1, 5));
+        assert_eq!(src.children[0].failures, vec!["bad.rs".to_string()]);
+
+        assert_eq!(
+            report.failed_subtrees(),
+            vec![
+                PathBuf::new(),
+                PathBuf::from("src"),
+                PathBuf::from("src/deep")
+            ]
+        );
+        let rendered = report.render();
+        assert!(rendered.contains("deep/  0 processed, 1 failed"));
+        assert!(rendered.contains("✗ bad.rs"));
+        assert!(serde_json::to_value(&report).unwrap()["root"]["children"].is_array());
+    }
+}
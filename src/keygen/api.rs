@@ -5,8 +5,9 @@
 //! CAGE-22 (adapter-native identities). See `docs/ref/cage/KEYGEN_STRATEGY.md`
 //! for the authoritative specification.
 
-use crate::core::AgeConfig;
+use crate::core::{AgeConfig, GroupStore};
 use crate::keygen::error::KeygenError;
+use crate::keygen::roster::{RosterRequest, RosterSummary};
 use std::path::PathBuf;
 
 /// Request payload accepted by the key generation service.
@@ -30,6 +31,8 @@ pub struct KeygenRequest {
     pub proxy_mode: bool,
     /// Export mode: generate keypair to current directory without registry entry.
     pub export_mode: bool,
+    /// Encrypt the new identity to this admin/escrow recipient instead of writing plaintext.
+    pub wrap_to: Option<String>,
 }
 
 /// Result summary returned by the key generation workflow.
@@ -45,6 +48,8 @@ pub struct KeygenSummary {
     pub fingerprint_sha256: Option<String>,
     /// Recipient groups that were updated as part of the request.
     pub registered_groups: Vec<String>,
+    /// Recipient the identity file was encrypted to, when wrapped.
+    pub wrapped_to: Option<String>,
 }
 
 /// Primary key generation service entry point.
@@ -71,6 +76,7 @@ impl KeygenService {
 
         // Validate request
         self.validate_request(request)?;
+        self.verify_pinned_binaries()?;
 
         // Handle recipients-only mode
         if request.recipients_only {
@@ -85,13 +91,19 @@ impl KeygenService {
         // Check age-keygen availability
         helpers::check_age_keygen_available()?;
 
-        // Determine output path
+        // Determine output path; a wrapped identity defaults to `<name>.cagekey.age`
         let output_path = if let Some(ref path) = request.output_path {
             path.clone()
-        } else if request.export_mode {
-            helpers::export_identity_path()?
         } else {
-            helpers::default_identity_path()?
+            let path = if request.export_mode {
+                helpers::export_identity_path()?
+            } else {
+                helpers::default_identity_path()?
+            };
+            match request.wrap_to {
+                Some(_) => helpers::wrapped_identity_path(&path),
+                None => path,
+            }
         };
 
         // Check overwrite protection
@@ -110,23 +122,21 @@ impl KeygenService {
         }
 
         // Generate identity by invoking age-keygen
-        let output = Command::new("age-keygen")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| KeygenError::Subprocess(format!("failed to execute age-keygen: {}", e)))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(KeygenError::Subprocess(format!(
-                "age-keygen failed: {}",
-                stderr
-            )));
+        let identity_content = helpers::run_age_keygen()?;
+
+        // A wrapped identity never reaches the disk in plaintext
+        if let Some(ref recipient) = request.wrap_to {
+            let public_recipient = helpers::public_key_from_identity(&identity_content)
+                .ok_or_else(|| {
+                    KeygenError::Subprocess("age-keygen output has no public key".to_string())
+                })?;
+            let wrapped = helpers::wrap_identity(&identity_content, recipient)?;
+            fs::write(&output_path, wrapped)
+                .map_err(|e| KeygenError::Io(format!("failed to write identity: {}", e)))?;
+            helpers::set_identity_permissions(&output_path)?;
+            return self.finish_generate(request, output_path, public_recipient);
         }
 
-        // Parse identity output
-        let identity_content = String::from_utf8_lossy(&output.stdout);
-
         // Write identity to file
         let mut file = fs::File::create(&output_path)
             .map_err(|e| KeygenError::Io(format!("failed to create identity file: {}", e)))?;
@@ -159,6 +169,18 @@ impl KeygenService {
             .trim()
             .to_string();
 
+        self.finish_generate(request, output_path, public_recipient)
+    }
+
+    /// Fingerprint, register and summarize a freshly written identity.
+    fn finish_generate(
+        &self,
+        request: &KeygenRequest,
+        output_path: PathBuf,
+        public_recipient: String,
+    ) -> Result<KeygenSummary, KeygenError> {
+        use crate::keygen::{audit, helpers};
+
         // Compute fingerprints
         let fingerprint_md5 = helpers::compute_fingerprint_md5(&public_recipient);
         let fingerprint_sha256 = helpers::compute_fingerprint_sha256(&public_recipient);
@@ -177,6 +199,7 @@ impl KeygenService {
             fingerprint_md5: Some(fingerprint_md5),
             fingerprint_sha256: Some(fingerprint_sha256),
             registered_groups,
+            wrapped_to: request.wrap_to.clone(),
         };
 
         // Log completion
@@ -185,6 +208,29 @@ impl KeygenService {
         Ok(summary)
     }
 
+    /// Generate one identity per roster member and register them with a stored group.
+    ///
+    /// See `keygen::roster` for the roster format and the files written.
+    pub fn generate_roster(
+        &self,
+        request: &RosterRequest,
+        store: &mut GroupStore,
+    ) -> Result<RosterSummary, KeygenError> {
+        self.verify_pinned_binaries()?;
+        let summary = crate::keygen::roster::generate_roster(request, store)?;
+        crate::keygen::audit::log_roster_complete(&summary);
+        Ok(summary)
+    }
+
+    /// Refuse (or warn about) binaries that do not match the hashes pinned in the config.
+    fn verify_pinned_binaries(&self) -> Result<(), KeygenError> {
+        match &self.config {
+            Some(config) => crate::adp::pin::verify_pins(config)
+                .map_err(|e| KeygenError::BinaryIntegrity(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
     /// Validate the request for conflicting options.
     fn validate_request(&self, request: &KeygenRequest) -> Result<(), KeygenError> {
         // Export mode conflicts with register
@@ -201,6 +247,18 @@ impl KeygenService {
             ));
         }
 
+        // Wrapping needs a new identity written by cage and a usable recipient
+        if let Some(ref recipient) = request.wrap_to {
+            if request.recipients_only || request.proxy_mode || request.stdout_only {
+                return Err(KeygenError::InvalidRequest(
+                    "--wrap-to cannot be used with --recipients-only, --proxy or --stdout-only"
+                        .to_string(),
+                ));
+            }
+            crate::core::header::parse_recipient(recipient)
+                .map_err(|e| KeygenError::InvalidRequest(format!("--wrap-to: {}", e)))?;
+        }
+
         Ok(())
     }
 
@@ -248,6 +306,7 @@ impl KeygenService {
             fingerprint_md5: Some(fingerprint_md5),
             fingerprint_sha256: Some(fingerprint_sha256),
             registered_groups: Vec::new(),
+            wrapped_to: None,
         })
     }
 
@@ -1,11 +1,10 @@
 //! Audit logging for key generation operations.
 
 use crate::keygen::api::{KeygenRequest, KeygenSummary};
-use chrono::Utc;
+use crate::keygen::roster::RosterSummary;
 
 /// Log the start of a key generation operation.
 pub(crate) fn log_keygen_start(request: &KeygenRequest) {
-    let timestamp = Utc::now().to_rfc3339();
     let mode = if request.recipients_only {
         "recipients-only"
     } else if request.export_mode {
@@ -16,21 +15,19 @@ pub(crate) fn log_keygen_start(request: &KeygenRequest) {
         "generate"
     };
 
-    eprintln!(
-        "[AUDIT] {} KEYGEN_START mode={} output={:?} export={} register={:?}",
-        timestamp,
+    tracing::info!(
+        target: "cage::audit",
         mode,
-        request.output_path,
-        request.export_mode,
-        request.register_groups
+        output = ?request.output_path,
+        export = request.export_mode,
+        register = ?request.register_groups,
+        "KEYGEN_START"
     );
 }
 
 /// Log the completion of a key generation operation.
 /// Ensures NO secrets are logged (§6 requirement).
 pub(crate) fn log_keygen_complete(summary: &KeygenSummary) {
-    let timestamp = Utc::now().to_rfc3339();
-
     // Redact the full public key, only log a hash for audit trail
     let recipient_hash = summary
         .public_recipient
@@ -38,13 +35,32 @@ pub(crate) fn log_keygen_complete(summary: &KeygenSummary) {
         .map(|r| format!("{:x}", md5::compute(r.as_bytes())))
         .unwrap_or_else(|| "none".to_string());
 
-    eprintln!(
-        "[AUDIT] {} KEYGEN_COMPLETE path={:?} recipient_hash={} md5={:?} sha256={:?} groups={:?}",
-        timestamp,
-        summary.output_path,
-        recipient_hash,
-        summary.fingerprint_md5,
-        summary.fingerprint_sha256,
-        summary.registered_groups
+    tracing::info!(
+        target: "cage::audit",
+        path = ?summary.output_path,
+        %recipient_hash,
+        md5 = ?summary.fingerprint_md5,
+        sha256 = ?summary.fingerprint_sha256,
+        groups = ?summary.registered_groups,
+        wrapped = summary.wrapped_to.is_some(),
+        "KEYGEN_COMPLETE"
+    );
+}
+
+/// Log a completed roster run: the group and one fingerprint per member.
+pub(crate) fn log_roster_complete(summary: &RosterSummary) {
+    let fingerprints: Vec<&str> = summary
+        .members
+        .iter()
+        .map(|member| member.fingerprint_sha256.as_str())
+        .collect();
+
+    tracing::info!(
+        target: "cage::audit",
+        group = %summary.group,
+        group_hash = %summary.group_hash,
+        members = summary.members.len(),
+        ?fingerprints,
+        "KEYGEN_ROSTER_COMPLETE"
     );
 }
@@ -23,7 +23,13 @@ pub enum KeygenError {
     /// Required binary not found.
     #[error("required binary not found: {0}. Please install age-keygen (https://github.com/FiloSottile/age)")]
     BinaryNotFound(String),
+    /// SSH key type or format that cannot be used with age.
+    #[error("unsupported SSH key: {0}")]
+    UnsupportedKey(String),
     /// Invalid recipient group.
     #[error("invalid recipient group: {0}")]
     InvalidGroup(String),
+    /// Pinned binary hash did not match.
+    #[error("binary integrity check failed: {0}")]
+    BinaryIntegrity(String),
 }
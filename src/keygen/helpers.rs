@@ -5,11 +5,22 @@ use sha2::{Digest, Sha256};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Compute the default identity path for a newly generated key.
 /// Uses XDG_CONFIG_HOME/cage/identities/<timestamp>.cagekey pattern.
 pub(crate) fn default_identity_path() -> Result<PathBuf, KeygenError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    named_identity_path(&timestamp.to_string())
+}
+
+/// Identity path XDG_CONFIG_HOME/cage/identities/<name>.cagekey.
+pub fn named_identity_path(name: &str) -> Result<PathBuf, KeygenError> {
     let base = if let Ok(xdg) = env::var("XDG_CONFIG_HOME") {
         PathBuf::from(xdg)
     } else if let Ok(home) = env::var("HOME") {
@@ -20,14 +31,10 @@ pub(crate) fn default_identity_path() -> Result<PathBuf, KeygenError> {
         ));
     };
 
-    let identities_dir = base.join("cage").join("identities");
-
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or_default();
-
-    Ok(identities_dir.join(format!("{}.cagekey", timestamp)))
+    Ok(base
+        .join("cage")
+        .join("identities")
+        .join(format!("{}.cagekey", name)))
 }
 
 /// Generate export identity path (current directory with timestamp).
@@ -90,6 +97,63 @@ pub(crate) fn set_identity_permissions(path: &Path) -> Result<(), KeygenError> {
     Ok(())
 }
 
+/// Run `age-keygen` and return the identity file it prints.
+pub(crate) fn run_age_keygen() -> Result<String, KeygenError> {
+    let output = Command::new("age-keygen")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| KeygenError::Subprocess(format!("failed to execute age-keygen: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(KeygenError::Subprocess(format!(
+            "age-keygen failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Public key from the `# public key:` comment age-keygen writes into identity files.
+pub(crate) fn public_key_from_identity(identity: &str) -> Option<String> {
+    identity
+        .lines()
+        .find_map(|line| line.strip_prefix("# public key:"))
+        .map(|key| key.trim().to_string())
+        .filter(|key| key.starts_with("age1"))
+}
+
+/// `path` with `.age` appended, the default name of a wrapped identity.
+pub(crate) fn wrapped_identity_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".age");
+    PathBuf::from(name)
+}
+
+/// Encrypt an identity file to `recipient` (age or SSH key), ASCII-armored for handover.
+pub(crate) fn wrap_identity(identity: &str, recipient: &str) -> Result<Vec<u8>, KeygenError> {
+    use age::armor::{ArmoredWriter, Format};
+    use std::io::Write;
+
+    let recipient = crate::core::header::parse_recipient(recipient)
+        .map_err(|e| KeygenError::InvalidRequest(format!("--wrap-to: {}", e)))?;
+    let encryptor =
+        age::Encryptor::with_recipients(std::iter::once(recipient.as_ref() as &dyn age::Recipient))
+            .map_err(|e| KeygenError::Io(format!("failed to wrap identity: {}", e)))?;
+
+    let mut wrapped = Vec::new();
+    ArmoredWriter::wrap_output(&mut wrapped, Format::AsciiArmor)
+        .and_then(|armor| encryptor.wrap_output(armor))
+        .and_then(|mut writer| {
+            writer.write_all(identity.as_bytes())?;
+            writer.finish()?.finish()
+        })
+        .map_err(|e| KeygenError::Io(format!("failed to wrap identity: {}", e)))?;
+    Ok(wrapped)
+}
+
 /// Check if age-keygen binary is available on PATH.
 pub(crate) fn check_age_keygen_available() -> Result<(), KeygenError> {
     which::which("age-keygen")
@@ -142,6 +206,17 @@ mod tests {
         assert!(!base64_part.contains(':'));
     }
 
+    #[test]
+    fn public_key_comes_from_identity_comment() {
+        let identity =
+            "# created: 2026-01-01T00:00:00Z\n# public key: age1xyz\nAGE-SECRET-KEY-1ABC\n";
+        assert_eq!(
+            public_key_from_identity(identity).as_deref(),
+            Some("age1xyz")
+        );
+        assert_eq!(public_key_from_identity("AGE-SECRET-KEY-1ABC\n"), None);
+    }
+
     #[test]
     fn check_age_keygen_reports_missing_binary() {
         // This test will pass or fail depending on whether age-keygen is installed
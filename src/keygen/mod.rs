@@ -1,17 +1,27 @@
 //! Key generation module (see docs/ref/cage/KEYGEN_STRATEGY.md)
 //!
-//! Provides the orchestrated service API used by the `cage keygen` command.
+//! Provides the orchestrated service API used by the `cage keygen` command, SSH
+//! key conversion (`ssh`) used by `cage key from-ssh`, forge key discovery
+//! (`remote`) used by `cage recipients import`, and team onboarding from a roster
+//! (`roster`) used by `cage keygen --roster`.
 //!
 //! Layout follows MODULE_SPEC v3 guidance so the module can be promoted to a
 //! standalone tool in the future without entangling the rest of the crate.
 
 pub mod api;
 pub mod error;
+pub mod remote;
+pub mod roster;
+pub mod ssh;
 pub(crate) mod audit;
 pub(crate) mod helpers;
 
 pub use api::{KeygenRequest, KeygenService, KeygenSummary};
 pub use error::KeygenError;
+pub use helpers::named_identity_path;
+pub use remote::{import_public_keys, parse_public_keys, KeyImport, KeySource};
+pub use roster::{parse_roster, RosterEntry, RosterMember, RosterRequest, RosterSummary};
+pub use ssh::{ssh_key_to_age, write_identity_file, SshConversion, SshKeyKind};
 
 #[cfg(test)]
 mod tests {
@@ -49,6 +59,27 @@ mod tests {
         assert!(matches!(err, KeygenError::InvalidRequest(_)));
     }
 
+    #[test]
+    fn keygen_request_validation_wrap_to() {
+        let service = KeygenService::default();
+        let request = KeygenRequest {
+            wrap_to: Some("not-a-recipient".to_string()),
+            ..Default::default()
+        };
+        let err = service.generate(&request).unwrap_err();
+        assert!(err.to_string().contains("--wrap-to"));
+
+        let request = KeygenRequest {
+            wrap_to: Some("age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p".into()),
+            recipients_only: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            service.generate(&request),
+            Err(KeygenError::InvalidRequest(_))
+        ));
+    }
+
     #[test]
     fn keygen_fingerprint_helpers() {
         let public_key = "age1abcdefghijklmnopqrstuvwxyz1234567890";
@@ -0,0 +1,171 @@
+//! Public key discovery from code forges.
+//!
+//! GitHub and GitLab publish each user's SSH public keys at `https://<host>/<user>.keys`.
+//! Fetching goes through `curl` (HTTPS only, redirects included) so the crate carries no
+//! HTTP client; the listing is then converted with [`ssh_public_key_to_age`] and
+//! unsupported key types are reported instead of failing the whole import.
+
+use crate::keygen::error::KeygenError;
+use crate::keygen::ssh::{ssh_public_key_to_age, SshConversion};
+use std::process::Command;
+
+const FETCH_TIMEOUT_SECS: &str = "20";
+
+/// Where to look up a user's published SSH keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    GitHub(String),
+    GitLab(String),
+}
+
+impl KeySource {
+    /// Validated source; usernames are restricted to characters forges allow
+    pub fn github(user: &str) -> Result<Self, KeygenError> {
+        validate_username(user).map(|user| Self::GitHub(user.to_string()))
+    }
+
+    pub fn gitlab(user: &str) -> Result<Self, KeygenError> {
+        validate_username(user).map(|user| Self::GitLab(user.to_string()))
+    }
+
+    pub fn user(&self) -> &str {
+        match self {
+            Self::GitHub(user) | Self::GitLab(user) => user,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        match self {
+            Self::GitHub(user) => format!("https://github.com/{}.keys", user),
+            Self::GitLab(user) => format!("https://gitlab.com/{}.keys", user),
+        }
+    }
+
+    /// Short label recorded with imported keys (e.g. `github:alice`)
+    pub fn label(&self) -> String {
+        match self {
+            Self::GitHub(user) => format!("github:{}", user),
+            Self::GitLab(user) => format!("gitlab:{}", user),
+        }
+    }
+}
+
+/// Keys found in a published listing
+#[derive(Debug, Clone, Default)]
+pub struct KeyImport {
+    /// Keys usable as age recipients, in listing order
+    pub keys: Vec<SshConversion>,
+    /// Lines that could not be used, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// Download the raw key listing for `source`
+pub fn fetch_public_keys(source: &KeySource) -> Result<String, KeygenError> {
+    let curl = which::which("curl")
+        .map_err(|_| KeygenError::Subprocess("curl not found in PATH".to_string()))?;
+    let url = source.url();
+    let output = Command::new(curl)
+        .args([
+            "-fsSL",
+            "--proto",
+            "=https",
+            // -L follows redirects; never to anything but HTTPS
+            "--proto-redir",
+            "=https",
+            "--max-time",
+            FETCH_TIMEOUT_SECS,
+        ])
+        .arg(&url)
+        .output()
+        .map_err(|e| KeygenError::Subprocess(format!("failed to run curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(KeygenError::Subprocess(format!(
+            "fetching {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| KeygenError::InvalidRequest(format!("{} returned non-UTF-8 data", url)))
+}
+
+/// Convert an `authorized_keys`-style listing, labelling comment-less keys with `label`
+pub fn parse_public_keys(listing: &str, label: &str) -> KeyImport {
+    let mut import = KeyImport::default();
+    for line in listing
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    {
+        match ssh_public_key_to_age(line) {
+            Ok(mut key) => {
+                key.comment.get_or_insert_with(|| label.to_string());
+                if !import.keys.iter().any(|k| k.fingerprint == key.fingerprint) {
+                    import.keys.push(key);
+                }
+            }
+            Err(e) => {
+                let key_type = line.split_whitespace().next().unwrap_or_default();
+                import.skipped.push(format!("{}: {}", key_type, e));
+            }
+        }
+    }
+    import
+}
+
+/// Fetch and convert the published keys of `source`
+pub fn import_public_keys(source: &KeySource) -> Result<KeyImport, KeygenError> {
+    let listing = fetch_public_keys(source)?;
+    Ok(parse_public_keys(&listing, &source.label()))
+}
+
+fn validate_username(user: &str) -> Result<&str, KeygenError> {
+    let valid = !user.is_empty()
+        && !user.starts_with(['-', '.'])
+        && user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(user)
+    } else {
+        Err(KeygenError::InvalidRequest(format!(
+            "invalid forge username '{}'",
+            user
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHOc/MEtg7CheMZfb49U6aRgMkccqjaAkVTkjoobtBk2";
+
+    #[test]
+    fn test_source_urls_and_validation() {
+        let source = KeySource::github("octo-cat").unwrap();
+        assert_eq!(source.url(), "https://github.com/octo-cat.keys");
+        assert_eq!(source.label(), "github:octo-cat");
+        assert_eq!(
+            KeySource::gitlab("dev.ops").unwrap().url(),
+            "https://gitlab.com/dev.ops.keys"
+        );
+        for bad in ["", "-rf", "a/b", "x?y=1", "a b"] {
+            assert!(KeySource::github(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_listing_skips_unsupported_and_duplicates() {
+        let listing = format!(
+            "{0}\necdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTY=\n\n{0} laptop\n",
+            ED25519
+        );
+        let import = parse_public_keys(&listing, "github:octo-cat");
+        assert_eq!(import.keys.len(), 1);
+        assert_eq!(import.keys[0].comment.as_deref(), Some("github:octo-cat"));
+        assert_eq!(import.skipped.len(), 1);
+        assert!(import.skipped[0].starts_with("ecdsa-sha2-nistp256"));
+    }
+}
@@ -0,0 +1,232 @@
+//! Bulk key generation from a team roster.
+//!
+//! A roster lists one member per line as `name` or `name, email`; blank lines and `#`
+//! comments are ignored. `KeygenService::generate_roster` creates one identity per
+//! member, writes each private key to its own export bundle `<dir>/<slug>.cagekey`
+//! (mode 0600, to be handed to that member), registers every public recipient in a
+//! stored recipient group with the member as its comment, and records the result in
+//! `<dir>/summary.json`.
+//!
+//! Nothing is written until every entry has parsed and no bundle would be overwritten
+//! (unless `force`), so a typo in the roster does not leave half a team behind.
+
+use crate::core::{set_recipient_comment, GroupStore};
+use crate::keygen::error::KeygenError;
+use crate::keygen::helpers;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the summary written next to the bundles
+pub const ROSTER_SUMMARY_FILE: &str = "summary.json";
+
+/// File-name-safe form of `name`: lowercase ASCII alphanumerics, `_` and `.`, with
+/// every other run of characters collapsed to `-`
+pub fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches(['-', '.']).to_string()
+}
+
+/// One roster line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RosterEntry {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+impl RosterEntry {
+    /// File-name-safe form of the name (`Ada Lovelace` -> `ada-lovelace`)
+    pub fn slug(&self) -> String {
+        slug(&self.name)
+    }
+
+    /// `name <email>`, the comment recorded with the member's recipient
+    pub fn label(&self) -> String {
+        match &self.email {
+            Some(email) => format!("{} <{}>", self.name, email),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Parse a roster, rejecting malformed lines and members whose slugs collide
+pub fn parse_roster(contents: &str) -> Result<Vec<RosterEntry>, KeygenError> {
+    let mut entries = Vec::new();
+    let mut slugs = HashSet::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |reason: &str| {
+            KeygenError::InvalidRequest(format!("roster line {}: {}", index + 1, reason))
+        };
+        let (name, email) = match line.split_once(',') {
+            Some((name, email)) => (name.trim(), Some(email.trim())),
+            None => (line, None),
+        };
+        let email = email.filter(|email| !email.is_empty());
+        if email.is_some_and(|email| !email.contains('@') || email.contains(',')) {
+            return Err(invalid("expected `name` or `name, email`"));
+        }
+        let entry = RosterEntry {
+            name: name.to_string(),
+            email: email.map(str::to_string),
+        };
+        let slug = entry.slug();
+        if slug.is_empty() {
+            return Err(invalid("member name has no letters or digits"));
+        }
+        if !slugs.insert(slug.clone()) {
+            return Err(invalid(&format!("duplicate member '{}'", slug)));
+        }
+        entries.push(entry);
+    }
+    if entries.is_empty() {
+        return Err(KeygenError::InvalidRequest(
+            "roster lists no members".to_string(),
+        ));
+    }
+    Ok(entries)
+}
+
+/// Request for `KeygenService::generate_roster`
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RosterRequest {
+    /// Roster file to read
+    pub roster_path: PathBuf,
+    /// Stored recipient group receiving every public recipient
+    pub group: String,
+    /// Directory for the export bundles and the summary
+    pub bundle_dir: PathBuf,
+    /// Overwrite existing bundles
+    pub force: bool,
+}
+
+/// Generated identity of one member
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct RosterMember {
+    pub name: String,
+    pub email: Option<String>,
+    pub public_recipient: String,
+    pub fingerprint_sha256: String,
+    pub bundle_path: PathBuf,
+}
+
+/// Result of a roster run, also written to `summary.json`
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct RosterSummary {
+    pub group: String,
+    pub group_hash: String,
+    pub groups_file: PathBuf,
+    pub members: Vec<RosterMember>,
+}
+
+/// Generate identities for every member of `request.roster_path` (see module docs)
+pub(crate) fn generate_roster(
+    request: &RosterRequest,
+    store: &mut GroupStore,
+) -> Result<RosterSummary, KeygenError> {
+    if request.group.trim().is_empty() {
+        return Err(KeygenError::InvalidRequest(
+            "a roster needs a group to register recipients with".to_string(),
+        ));
+    }
+    let contents = fs::read_to_string(&request.roster_path).map_err(|e| {
+        KeygenError::Io(format!(
+            "failed to read roster {}: {}",
+            request.roster_path.display(),
+            e
+        ))
+    })?;
+    let entries = parse_roster(&contents)?;
+    helpers::check_age_keygen_available()?;
+
+    let bundles: Vec<PathBuf> = entries
+        .iter()
+        .map(|entry| bundle_path(&request.bundle_dir, entry))
+        .collect();
+    if !request.force {
+        if let Some(existing) = bundles.iter().find(|path| path.exists()) {
+            return Err(KeygenError::FileExists(
+                existing.to_string_lossy().to_string(),
+            ));
+        }
+    }
+    fs::create_dir_all(&request.bundle_dir)
+        .map_err(|e| KeygenError::Io(format!("failed to create bundle directory: {}", e)))?;
+
+    let mut members = Vec::with_capacity(entries.len());
+    for (entry, bundle) in entries.iter().zip(bundles) {
+        let identity = helpers::run_age_keygen()?;
+        let public_recipient = helpers::public_key_from_identity(&identity).ok_or_else(|| {
+            KeygenError::Subprocess("age-keygen output has no public key".to_string())
+        })?;
+        let contents = format!("# member: {}\n{}", entry.label(), identity);
+        fs::write(&bundle, contents)
+            .map_err(|e| KeygenError::Io(format!("failed to write {}: {}", bundle.display(), e)))?;
+        helpers::set_identity_permissions(&bundle)?;
+
+        members.push(RosterMember {
+            name: entry.name.clone(),
+            email: entry.email.clone(),
+            fingerprint_sha256: helpers::compute_fingerprint_sha256(&public_recipient),
+            public_recipient,
+            bundle_path: bundle,
+        });
+    }
+
+    let group = store.group_mut(&request.group);
+    for (entry, member) in entries.iter().zip(&members) {
+        group.add_recipient(member.public_recipient.clone());
+        set_recipient_comment(group, &member.public_recipient, entry.label());
+    }
+    group.set_metadata("last_modified".to_string(), chrono::Utc::now().to_rfc3339());
+    let group_hash = group.group_hash();
+    store
+        .save()
+        .map_err(|e| KeygenError::Io(format!("failed to save recipient groups: {}", e)))?;
+
+    let summary = RosterSummary {
+        group: request.group.clone(),
+        group_hash,
+        groups_file: store.path().to_path_buf(),
+        members,
+    };
+    let json = serde_json::to_string_pretty(&summary)
+        .map_err(|e| KeygenError::Io(format!("failed to encode summary: {}", e)))?;
+    fs::write(request.bundle_dir.join(ROSTER_SUMMARY_FILE), json + "\n")
+        .map_err(|e| KeygenError::Io(format!("failed to write summary: {}", e)))?;
+    Ok(summary)
+}
+
+fn bundle_path(dir: &Path, entry: &RosterEntry) -> PathBuf {
+    dir.join(format!("{}.cagekey", entry.slug()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roster_lines_parse_into_members() {
+        let roster = "# platform team\nAda Lovelace, ada@example.com\n\ngrace\n";
+        let entries = parse_roster(roster).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].slug(), "ada-lovelace");
+        assert_eq!(entries[0].label(), "Ada Lovelace <ada@example.com>");
+        assert_eq!(entries[1].email, None);
+
+        assert!(parse_roster("ada, not-an-email\n").is_err());
+        assert!(parse_roster("Ada\nada\n").is_err());
+        assert!(parse_roster("# nobody\n").is_err());
+    }
+}
@@ -51,6 +51,8 @@ pub const ERR_STREAM_PIPE_REQUIRES_RECIPIENTS: &str =
     "Pipe streaming requires at least one recipient";
 pub const ERR_STREAM_PIPE_REQUIRES_IDENTITY: &str =
     "Pipe streaming requires an identity file or SSH key";
+pub const ERR_IDENTITY_PROVIDER_UNRESOLVED: &str =
+    "Identity provider reference was not resolved before use";
 
 // ============================================================================
 // WARNING MESSAGES
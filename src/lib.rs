@@ -15,6 +15,23 @@
 //!
 //! # Quick Start
 //!
+//! The `Cage` builder is the stable API for embedders:
+//!
+//! ```rust,no_run
+//! use cage::prelude::*;
+//! use std::path::Path;
+//!
+//! # fn main() -> AgeResult<()> {
+//! let mut cage = Cage::builder().passphrase("passphrase").recursive(true).build()?;
+//! cage.lock(Path::new("secrets"))?;
+//! let status = cage.status(Path::new("secrets"))?;
+//! println!("Encrypted files: {}", status.encrypted_files);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! `CageManager` exposes the full operation set:
+//!
 //! ```rust,no_run
 //! use cage::prelude::*;
 //! use std::path::Path;
@@ -38,34 +55,50 @@
 pub mod adp; // Adapter implementations (v1, v2, pipe streaming)
 pub mod audit; // Audit logging and security validation
 pub mod buff; // Chunking and buffer management
+#[cfg(feature = "clipboard")]
+pub mod clip; // Clipboard copy with automatic clearing for `cage show --clip`
 pub mod core; // Core primitives (config, requests, engine, recovery)
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi; // C ABI for non-Rust integrators
 pub mod forge; // Repository operations
 pub mod keygen; // Key generation service module
 pub mod mgr; // CageManager lifecycle coordination
+#[cfg(all(feature = "mount", unix))]
+pub mod mount; // Read-only FUSE view of decrypted files
 pub mod passphrase; // Secure passphrase management
 pub mod pty; // PTY automation (wrap, tty methods)
+#[cfg(all(feature = "tui", unix))]
+pub mod tui; // Interactive repository triage
 
 // Supporting modules
+#[deprecated(
+    since = "0.6.0",
+    note = "`cage::cage::*` paths were flattened; import from `cage::*` (see `cage::cage` docs)"
+)]
+pub mod cage; // Legacy `cage::cage::*` re-export shims
 pub mod deps;
 pub mod lang;
 pub mod prelude;
 
 // Re-export core types for convenience
-pub use adp::{AdapterFactory, AgeAdapter};
+pub use adp::{AdapterFactory, AdapterSelection, AgeAdapter};
 pub use audit::{AuditLogger, SecurityValidator};
-pub use buff::{ChunkProcessingSummary, ChunkSpec, ChunkerConfig, FileChunker};
+pub use buff::{
+    ChunkMetrics, ChunkMetricsFn, ChunkProcessingSummary, ChunkSpec, ChunkerConfig, FileChunker,
+};
 pub use core::{
-    AgeAutomator, AgeConfig, InPlaceOperation, InPlaceOptions, OutputFormat, RecoveryManager,
-    SafetyValidator, TtyMethod,
+    AgeAutomator, AgeConfig, Confirmation, InPlaceOperation, InPlaceOptions, OutputFormat,
+    Prompter, RecoveryManager, SafetyValidator, TtyMethod,
 };
 pub use error::{AgeError, AgeResult};
 pub use forge::{
-    FileEncryption, Operation, OperationResult, RepositoryOperations, RepositoryStatus,
+    FileEncryption, Operation, OperationRegistry, OperationResult, OperationWarning,
+    RepositoryManager, RepositoryOperations, RepositoryStatus, SkippedFile, WarningCategory,
 };
 pub use keygen::{KeygenError, KeygenRequest, KeygenService, KeygenSummary};
-pub use mgr::{CageManager, LockOptions, UnlockOptions, VerificationResult};
-pub use passphrase::{PassphraseManager, PassphraseMode};
+pub use mgr::{Cage, CageBuilder, CageManager, LockOptions, UnlockOptions, VerificationResult};
+pub use passphrase::{PassphraseManager, PassphraseMode, PassphrasePolicy, PassphraseStrength};
 
 /// Library version - synchronized with Cargo.toml
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
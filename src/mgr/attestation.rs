@@ -0,0 +1,206 @@
+//! Attestation - in-toto statements describing a lock
+//!
+//! `LockRequest::with_attestation` (CLI: `cage lock --attest <PATH>`) writes an
+//! [in-toto Statement v1](https://github.com/in-toto/attestation/blob/main/spec/v1/statement.md)
+//! wrapped in a [DSSE envelope](https://github.com/secure-systems-lab/dsse) after a
+//! successful lock, so SLSA-style verification tooling can consume it:
+//!
+//! - `subject`: every ciphertext produced, with its SHA-256
+//! - `predicate.materials`: the plaintext inputs and their SHA-256
+//! - `predicate.recipients`: public keys with fingerprints (or `passphrase: true`)
+//! - `predicate.tool`, `operation_id`, `started_on` / `finished_on`, `format`
+//!
+//! The envelope is written unsigned (`signatures: []`); sign it with the pipeline's
+//! own DSSE tooling. Digests are captured while the plaintext still exists, so
+//! in-place locks attest the original content.
+
+use crate::core::meta::{CipherMeta, MetaRecipient};
+use crate::core::{LockRequest, OutputFormat};
+use crate::error::{AgeError, AgeResult};
+use crate::forge::OperationResult;
+use base64::Engine;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+pub const PREDICATE_TYPE: &str = "https://padlokk.dev/cage/encryption/v1";
+pub const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// Artifact reference with its digests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceDescriptor {
+    pub name: String,
+    pub digest: BTreeMap<String, String>,
+}
+
+impl ResourceDescriptor {
+    fn sha256(path: &Path, sha256: &str) -> Self {
+        Self {
+            name: path.display().to_string(),
+            digest: BTreeMap::from([("sha256".to_string(), sha256.to_string())]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolInfo {
+    pub name: String,
+    pub version: String,
+}
+
+/// What the lock did, beyond the subjects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EncryptionPredicate {
+    pub operation: String,
+    pub operation_id: String,
+    pub tool: ToolInfo,
+    pub started_on: String,
+    pub finished_on: String,
+    pub format: String,
+    pub passphrase: bool,
+    pub recipients: Vec<MetaRecipient>,
+    pub materials: Vec<ResourceDescriptor>,
+    pub failed: Vec<String>,
+}
+
+/// in-toto Statement v1 about one lock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Statement {
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<ResourceDescriptor>,
+    #[serde(rename = "predicateType")]
+    pub predicate_type: String,
+    pub predicate: EncryptionPredicate,
+}
+
+/// DSSE envelope carrying a statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Envelope {
+    #[serde(rename = "payloadType")]
+    pub payload_type: String,
+    /// Base64 of the statement JSON
+    pub payload: String,
+    pub signatures: Vec<serde_json::Value>,
+}
+
+impl Statement {
+    /// Describe `result` of `request`; needs the digests captured during the lock
+    pub fn for_lock(
+        request: &LockRequest,
+        result: &OperationResult,
+        operation_id: &str,
+        started_on: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        let meta = if request.all_recipients().is_empty() {
+            CipherMeta::passphrase()
+        } else {
+            CipherMeta::for_recipients(
+                request.recipients.as_deref().unwrap_or_default(),
+                request.multi_recipient_config.as_ref(),
+            )
+        };
+        let (subject, materials) = result
+            .checksums
+            .iter()
+            .map(|(source, output, entry)| {
+                (
+                    ResourceDescriptor::sha256(output, &entry.ciphertext_sha256),
+                    ResourceDescriptor::sha256(source, &entry.plaintext_sha256),
+                )
+            })
+            .unzip();
+
+        Self {
+            statement_type: STATEMENT_TYPE.to_string(),
+            subject,
+            predicate_type: PREDICATE_TYPE.to_string(),
+            predicate: EncryptionPredicate {
+                operation: "lock".to_string(),
+                operation_id: operation_id.to_string(),
+                tool: ToolInfo {
+                    name: "cage".to_string(),
+                    version: crate::VERSION.to_string(),
+                },
+                started_on: started_on.to_rfc3339(),
+                finished_on: chrono::Utc::now().to_rfc3339(),
+                format: match request.format {
+                    OutputFormat::Binary => "binary",
+                    OutputFormat::AsciiArmor => "ascii",
+                }
+                .to_string(),
+                passphrase: meta.passphrase,
+                recipients: meta.recipients,
+                materials,
+                failed: result.failed_files.clone(),
+            },
+        }
+    }
+
+    /// Wrap the statement in an unsigned DSSE envelope
+    pub fn envelope(&self) -> AgeResult<Envelope> {
+        let payload = serde_json::to_vec(self).map_err(|e| AgeError::InvalidOperation {
+            operation: "attestation".to_string(),
+            reason: e.to_string(),
+        })?;
+        Ok(Envelope {
+            payload_type: PAYLOAD_TYPE.to_string(),
+            payload: base64::engine::general_purpose::STANDARD.encode(payload),
+            signatures: Vec::new(),
+        })
+    }
+
+    /// Write the enveloped statement to `path`
+    pub fn write(&self, path: &Path) -> AgeResult<()> {
+        let json = serde_json::to_vec_pretty(&self.envelope()?).map_err(|e| {
+            AgeError::InvalidOperation {
+                operation: "attestation".to_string(),
+                reason: e.to_string(),
+            }
+        })?;
+        fs::write(path, json).map_err(|e| AgeError::file_error("write_attestation", path.into(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Identity, Recipient};
+    use crate::forge::ChecksumEntry;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_statement_lists_subjects_materials_and_recipients() {
+        let request = LockRequest::new(PathBuf::from("/repo"), Identity::Passphrase(String::new()))
+            .with_recipients(vec![Recipient::PublicKey("age1alice".to_string())]);
+        let mut result = OperationResult::new();
+        result.checksums.push((
+            PathBuf::from("/repo/a.txt"),
+            PathBuf::from("/repo/a.txt.cage"),
+            ChecksumEntry {
+                source: String::new(),
+                plaintext_sha256: "11".to_string(),
+                ciphertext_sha256: "22".to_string(),
+                recorded_at: String::new(),
+            },
+        ));
+
+        let statement = Statement::for_lock(&request, &result, "lock-1-1", chrono::Utc::now());
+        assert_eq!(statement.subject[0].name, "/repo/a.txt.cage");
+        assert_eq!(statement.subject[0].digest["sha256"], "22");
+        assert_eq!(statement.predicate.materials[0].digest["sha256"], "11");
+        assert_eq!(statement.predicate.recipients[0].recipient, "age1alice");
+        assert!(!statement.predicate.passphrase);
+
+        let envelope = statement.envelope().unwrap();
+        let payload = base64::engine::general_purpose::STANDARD
+            .decode(&envelope.payload)
+            .unwrap();
+        let decoded: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(decoded["_type"], STATEMENT_TYPE);
+        assert_eq!(decoded["predicateType"], PREDICATE_TYPE);
+        assert!(envelope.signatures.is_empty());
+    }
+}
@@ -6,37 +6,278 @@
 //!
 //! Security Guardian: Edgar - Production coordination with authority integration
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 #[allow(unused_imports)]
 use std::time::{Duration, Instant};
 
+use crate::adp::progress::ByteProgressFn;
+use crate::adp::supervisor;
 use crate::adp::v1::AgeAdapter;
 use crate::adp::v2::{AgeAdapterV2, ShellAdapterV2};
-use crate::core::{AgeConfig, OutputFormat, RetentionPolicyConfig};
-use crate::error::{AgeError, AgeResult};
-use crate::forge::{OperationResult, RepositoryStatus};
+use crate::core::armor;
+use crate::core::chunked;
+use crate::core::header;
+use crate::core::interrupt;
+use crate::core::meta::{self, CipherMeta};
+use crate::core::permissions;
+use crate::core::scheduling;
 use crate::core::{
-    BatchOperation, BatchRequest, Identity, LockRequest, Recipient, RotateRequest, StatusRequest,
-    StreamOperation, StreamRequest, UnlockRequest, VerifyRequest,
+    default_prompter, AgeConfig, CommonOptions, CompressionWrap, Confirmation, GroupStore,
+    HardlinkPolicy, IdentityProviderRegistry, LockPlan, MarkerStore, MemoryBudget, MtimeWindow,
+    OutputFormat, Prompter, ResolvedIdentity, RetentionPolicyConfig, SafetyValidator, Throttle,
+};
+use crate::error::{AgeError, AgeResult, Stage, StageContext};
+use crate::forge::{
+    group_hardlinks, special_file_reason, CachedTraversal, ChecksumDb, ChecksumEntry,
+    HardlinkGroup, SkippedEntries, SourceComparison, StateCache, FileSelection, Operation,
+    OperationRegistry, OperationResult, RepositoryManager, RepositoryStatus, SharedOperation,
+    ShallowTraversal, StatusTree, SuspectOutput, WarningCategory, orphans,
+};
+use crate::core::{
+    BatchOperation, BatchRequest, Identity, LockRequest, Recipient, RecipientGroup, RotateRequest,
+    StatusRequest, StreamOperation, StreamRequest, UnlockRequest, VerifyRequest,
 };
 use crate::audit::AuditLogger;
-use crate::lang::{fmt_deleted, fmt_error, fmt_preserved, fmt_warning};
+use crate::mgr::quarantine::Quarantine;
+use crate::mgr::attestation::Statement;
+use crate::mgr::rekey_plan::RekeyPlan;
+use crate::mgr::relock::{RelockEntry, RelockStore};
+use crate::mgr::session::{Session, SessionFile};
 #[allow(unused_imports)]
 use crate::pty::TtyAutomator;
-use globset::{Glob, GlobMatcher};
 use rsb::visual::glyphs::glyph_enable;
+use sha2::{Digest, Sha256};
 use tempfile::NamedTempFile;
 
+/// Bytes of a ciphertext read by `verify_file_integrity` to check its header
+const VERIFY_HEAD_BYTES: u64 = 256;
+
+/// Size of `path` in bytes, or 0 if it cannot be read
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Kind of identity as recorded in audit events
+fn identity_type(identity: &Identity) -> &'static str {
+    match identity {
+        Identity::Passphrase(_) => "passphrase",
+        Identity::IdentityFile(_) => "identity-file",
+        Identity::SshKey(_) => "ssh-key",
+        Identity::PromptPassphrase => "prompt-passphrase",
+        Identity::Provider(_) => "provider",
+    }
+}
+
+/// Decrypt `input` with `decrypt_fn`, chunk by chunk when it is a chunked container.
+///
+/// A container whose chunks do not fit `budget` is refused, since each chunk is held in
+/// memory while it is decrypted.
+fn decrypt_maybe_chunked<F>(
+    input: &Path,
+    output: &Path,
+    budget: MemoryBudget,
+    decrypt_fn: &mut F,
+) -> AgeResult<()>
+where
+    F: FnMut(&Path, &Path) -> AgeResult<()>,
+{
+    if let Some(manifest) = chunked::read_manifest(input)? {
+        if budget.clamp_chunk(manifest.chunk_size) < manifest.chunk_size {
+            return Err(AgeError::InvalidOperation {
+                operation: "unlock".to_string(),
+                reason: format!(
+                    "{} uses {}-byte chunks, more than the memory budget allows; raise \
+                     [limits] memory_budget or CAGE_MEMORY_BUDGET",
+                    input.display(),
+                    manifest.chunk_size
+                ),
+            });
+        }
+        chunked::unlock_chunked(input, output, decrypt_fn).map(|_| ())
+    } else {
+        decrypt_fn(input, output)
+    }
+}
+
+/// Refuse `operation` on a chunked container, which has no single age header to rewrite
+fn refuse_chunked(path: &Path, operation: &str) -> AgeResult<()> {
+    if chunked::is_chunked(path)? {
+        return Err(AgeError::InvalidOperation {
+            operation: operation.to_string(),
+            reason: format!(
+                "{} is a chunked container; unlock it and lock it again instead",
+                path.display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Refuse an explicit output path unless `path` is a single file other than the output
+fn check_output_path(path: &Path, options: &LockOptions) -> AgeResult<()> {
+    let Some(output) = &options.output_path else {
+        return Ok(());
+    };
+    let reason = if !path.is_file() {
+        "An explicit output path only applies to single-file locks"
+    } else if output.canonicalize().ok() == path.canonicalize().ok() {
+        "The output path must differ from the file being locked"
+    } else {
+        return Ok(());
+    };
+    Err(AgeError::InvalidOperation {
+        operation: "lock".to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+/// Lock-marker key: the recipients key for recipient locks, else just the output format
+/// (a passphrase is never fingerprinted)
+fn marker_key(options: &LockOptions) -> String {
+    options
+        .encryption_key
+        .clone()
+        .unwrap_or_else(|| format!("passphrase:{:?}", options.format))
+}
+
+/// Create `dir` (and its parents) readable by the owner only
+fn create_private_dir(dir: &Path) -> AgeResult<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(dir)
+        .map_err(|e| AgeError::file_error("create_dir", dir.to_path_buf(), e))
+}
+
+/// Write `target` through `write` into a staged file next to it, then rename it in place
+fn replace_via_staging(target: &Path, write: impl FnOnce(&Path) -> AgeResult<()>) -> AgeResult<()> {
+    // Stage next to the target so the final rename stays on one filesystem
+    let parent = target
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let staged = NamedTempFile::new_in(parent)
+        .map_err(|e| AgeError::file_error("create_temp", parent.to_path_buf(), e))?;
+    write(staged.path())?;
+    staged
+        .persist(target)
+        .map_err(|e| AgeError::file_error("persist", target.to_path_buf(), e.error))?;
+    Ok(())
+}
+
+/// Re-encode age ciphertext at `path` into `format` at `target` (may equal `path`)
+fn rewrap_armor(path: &Path, target: &Path, format: OutputFormat) -> AgeResult<()> {
+    // Stage next to the target so the final rename stays on one filesystem
+    let parent = target
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let staged = NamedTempFile::new_in(parent)
+        .map_err(|e| AgeError::file_error("create_temp", parent.to_path_buf(), e))?;
+
+    let input = std::fs::File::open(path)
+        .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+    let mut reader = age::armor::ArmoredReader::new(std::io::BufReader::new(input));
+    let armor = match format {
+        OutputFormat::Binary => age::armor::Format::Binary,
+        OutputFormat::AsciiArmor => age::armor::Format::AsciiArmor,
+    };
+    let staged = age::armor::ArmoredWriter::wrap_output(staged, armor)
+        .and_then(|mut writer| {
+            std::io::copy(&mut reader, &mut writer)?;
+            writer.finish()
+        })
+        .map_err(|e| AgeError::file_error("convert", path.to_path_buf(), e))?;
+
+    staged
+        .persist(target)
+        .map_err(|e| AgeError::file_error("persist", target.to_path_buf(), e.error))?;
+    Ok(())
+}
+
+/// Encoding of the age ciphertext at `path`, judged from its first line; `operation`
+/// names the caller in the error for a file that is not age ciphertext
+fn ciphertext_format(path: &Path, operation: &str) -> AgeResult<OutputFormat> {
+    match header::HeaderFormat::detect_file(path)? {
+        Some(header::HeaderFormat::Armored) => Ok(OutputFormat::AsciiArmor),
+        Some(header::HeaderFormat::Binary) => Ok(OutputFormat::Binary),
+        None => Err(AgeError::InvalidOperation {
+            operation: operation.to_string(),
+            reason: format!("{} is not an age encrypted file", path.display()),
+        }),
+    }
+}
+
+/// Run a streaming request through a fresh shell adapter, reading the input through a
+/// `request.buffer_size` buffer
+fn run_stream(
+    config: &AgeConfig,
+    request: &StreamRequest,
+    input: &mut (dyn Read + Send),
+    output: &mut (dyn Write + Send),
+    progress: Option<ByteProgressFn>,
+) -> AgeResult<u64> {
+    let mut adapter = ShellAdapterV2::with_config(config.clone())?;
+    if let Some(callback) = progress {
+        adapter = adapter.with_progress(callback);
+    }
+    let mut input = std::io::BufReader::with_capacity(request.buffer_size, input);
+    let input: &mut (dyn Read + Send) = &mut input;
+
+    match request.operation {
+        StreamOperation::Encrypt => {
+            let recipients_slice = request.recipients.as_ref().map(|list| list.as_slice());
+            adapter.encrypt_stream(
+                input,
+                output,
+                &request.identity,
+                recipients_slice,
+                request.format,
+            )
+        }
+        StreamOperation::Decrypt => adapter.decrypt_stream(input, output, &request.identity),
+    }
+}
+
 /// Options for lock operations
 #[derive(Debug, Clone)]
 pub struct LockOptions {
     pub recursive: bool,
     pub format: OutputFormat,
     pub pattern_filter: Option<String>,
+    /// Only files modified inside this window are collected from directories
+    pub modified: MtimeWindow,
     pub backup_before_lock: bool,
     pub backup_dir: Option<PathBuf>,
+    /// Retention for this operation's backups (None uses `backup_retention` from config)
+    pub backup_retention: Option<RetentionPolicyConfig>,
+    /// Backup file extension (None uses `.bak`)
+    pub backup_extension: Option<String>,
+    /// Skip files whose output is up to date with the source and `encryption_key`, and
+    /// record markers for the files it locks (see `core::markers`)
+    pub skip_existing: bool,
+    /// Decrypt each output with this identity and compare it to the source
+    pub verify_decryptable: Option<Identity>,
+    /// Mode for created ciphertext (None uses `ciphertext_mode` from config)
+    pub output_mode: Option<u32>,
+    /// Keep the source owner on ciphertext and backups (also enabled by config)
+    pub preserve_ownership: bool,
+    /// Sidecar written next to each ciphertext (see `core::meta`; None writes none)
+    pub meta: Option<CipherMeta>,
+    /// Write delta-friendly chunked containers with this chunk size (see `core::chunked`)
+    pub chunk_size: Option<u64>,
+    /// Ciphertext path for a single-file lock (None writes `<file>.<extension>`)
+    pub output_path: Option<PathBuf>,
+    /// Key of the recipients and format (`core::meta::recipients_key`); recipient locks
+    /// fill it in, and existing chunks are only reused for a matching key
+    pub encryption_key: Option<String>,
 }
 
 impl Default for LockOptions {
@@ -45,8 +286,19 @@ impl Default for LockOptions {
             recursive: false,
             format: OutputFormat::Binary,
             pattern_filter: None,
+            modified: MtimeWindow::default(),
             backup_before_lock: false,
             backup_dir: None,
+            backup_retention: None,
+            backup_extension: None,
+            skip_existing: false,
+            verify_decryptable: None,
+            output_mode: None,
+            preserve_ownership: false,
+            meta: None,
+            chunk_size: None,
+            output_path: None,
+            encryption_key: None,
         }
     }
 }
@@ -57,7 +309,15 @@ pub struct UnlockOptions {
     pub selective: bool,
     pub verify_before_unlock: bool,
     pub pattern_filter: Option<String>,
+    /// Only files modified inside this window are collected from directories
+    pub modified: MtimeWindow,
     pub preserve_encrypted: bool,
+    /// Re-decrypt with this identity and compare to the output before deleting ciphertext
+    pub verify_decryptable: Option<Identity>,
+    /// Mode for created plaintext (None uses `plaintext_mode` from config)
+    pub output_mode: Option<u32>,
+    /// Keep the ciphertext owner on plaintext (also enabled by config)
+    pub preserve_ownership: bool,
 }
 
 impl Default for UnlockOptions {
@@ -66,7 +326,11 @@ impl Default for UnlockOptions {
             selective: false,
             verify_before_unlock: true,
             pattern_filter: None,
+            modified: MtimeWindow::default(),
             preserve_encrypted: false,
+            verify_decryptable: None,
+            output_mode: None,
+            preserve_ownership: false,
         }
     }
 }
@@ -87,6 +351,52 @@ pub struct VerificationResult {
     pub failed_files: Vec<String>,
     pub authority_status: String,
     pub overall_status: String,
+    pub stream_digests: Vec<StreamVerifyDigest>,
+}
+
+/// Plaintext size and digest captured by streaming deep verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamVerifyDigest {
+    pub file_path: PathBuf,
+    pub plaintext_bytes: u64,
+    pub sha256: String,
+}
+
+/// Write sink that counts and hashes plaintext without retaining it
+struct HashingSink {
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl HashingSink {
+    fn new() -> Self {
+        Self {
+            hasher: Sha256::new(),
+            bytes: 0,
+        }
+    }
+
+    fn finish(self, file_path: PathBuf) -> StreamVerifyDigest {
+        let digest = self.hasher.finalize();
+        let sha256 = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        StreamVerifyDigest {
+            file_path,
+            plaintext_bytes: self.bytes,
+            sha256,
+        }
+    }
+}
+
+impl Write for HashingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.hasher.update(buf);
+        self.bytes += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Retention policy for backup lifecycle management
@@ -165,7 +475,7 @@ impl RetentionPolicy {
 }
 
 impl RetentionPolicyConfig {
-    fn to_retention_policy(&self) -> RetentionPolicy {
+    pub(crate) fn to_retention_policy(&self) -> RetentionPolicy {
         match self {
             RetentionPolicyConfig::KeepAll => RetentionPolicy::KeepAll,
             RetentionPolicyConfig::KeepDays(days) => RetentionPolicy::KeepDays(*days),
@@ -188,6 +498,7 @@ pub struct BackupManager {
     cleanup_on_success: bool,
     retention_policy: RetentionPolicy,
     registry: BackupRegistry,
+    preserve_ownership: bool,
 }
 
 impl BackupManager {
@@ -199,6 +510,7 @@ impl BackupManager {
             cleanup_on_success: true,
             retention_policy: RetentionPolicy::default(),
             registry: BackupRegistry::new(),
+            preserve_ownership: false,
         }
     }
 
@@ -213,6 +525,7 @@ impl BackupManager {
             cleanup_on_success: true,
             retention_policy: RetentionPolicy::default(),
             registry,
+            preserve_ownership: false,
         }
     }
 
@@ -238,6 +551,12 @@ impl BackupManager {
         self
     }
 
+    /// Give backups the owner of the original file instead of the running user
+    pub fn with_preserve_ownership(mut self, preserve: bool) -> Self {
+        self.preserve_ownership = preserve;
+        self
+    }
+
     /// Create backup of a file
     pub fn create_backup(&self, file_path: &Path) -> AgeResult<BackupInfo> {
         let outcome = self.create_backup_internal(file_path)?;
@@ -274,15 +593,22 @@ impl BackupManager {
             }
         }
 
-        // Copy file to backup location
-        std::fs::copy(file_path, &backup_path)
+        // Copy to a temp file beside the backup, then rename, so a crash never leaves
+        // a truncated backup under the final name
+        let cross_device = !same_device(file_path, &backup_path);
+        let (size_bytes, checksum) = durable_copy(file_path, &backup_path)
             .map_err(|e| AgeError::file_error("create_backup", backup_path.clone(), e))?;
+        if self.preserve_ownership {
+            permissions::copy_ownership(file_path, &backup_path)?;
+        }
 
         let info = BackupInfo {
             original_path: file_path.to_path_buf(),
             backup_path,
             created_at: std::time::SystemTime::now(),
-            size_bytes: std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0),
+            size_bytes,
+            checksum: Some(checksum),
+            cross_device,
         };
 
         Ok(BackupCreationOutcome {
@@ -301,7 +627,18 @@ impl BackupManager {
             ));
         }
 
-        std::fs::copy(&backup_info.backup_path, &backup_info.original_path).map_err(|e| {
+        if !backup_info.verify()? {
+            return Err(AgeError::InvalidOperation {
+                operation: "restore_backup".to_string(),
+                reason: format!(
+                    "Backup {} does not match its recorded checksum",
+                    backup_info.backup_path.display()
+                ),
+            });
+        }
+
+        // Never overwrite the original in place: a failed copy must leave it untouched
+        durable_copy(&backup_info.backup_path, &backup_info.original_path).map_err(|e| {
             AgeError::file_error("restore_backup", backup_info.original_path.clone(), e)
         })?;
 
@@ -337,6 +674,7 @@ impl BackupManager {
             created_at: backup_info.created_at,
             size_bytes: backup_info.size_bytes,
             generation,
+            checksum: backup_info.checksum.clone(),
         };
         self.registry.register(file_path.to_path_buf(), entry);
 
@@ -384,7 +722,17 @@ impl BackupManager {
             ));
         }
 
-        std::fs::copy(&entry.backup_path, file_path)
+        if !entry.verify()? {
+            return Err(AgeError::InvalidOperation {
+                operation: "restore_generation".to_string(),
+                reason: format!(
+                    "Backup {} does not match its recorded checksum",
+                    entry.backup_path.display()
+                ),
+            });
+        }
+
+        durable_copy(&entry.backup_path, file_path)
             .map_err(|e| AgeError::file_error("restore_generation", file_path.to_path_buf(), e))?;
 
         Ok(())
@@ -418,14 +766,7 @@ impl BackupManager {
     }
 
     fn collect_existing_backups(&self, original_path: &Path) -> AgeResult<Vec<BackupInfo>> {
-        let base_dir = if let Some(dir) = &self.backup_dir {
-            dir.clone()
-        } else {
-            original_path
-                .parent()
-                .unwrap_or_else(|| Path::new("."))
-                .to_path_buf()
-        };
+        let base_dir = self.backup_parent(original_path);
 
         if !base_dir.exists() {
             return Ok(Vec::new());
@@ -473,11 +814,20 @@ impl BackupManager {
                 .modified()
                 .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
 
+            // Checksum as recorded in the registry when this manager created the backup
+            let checksum = self
+                .registry
+                .list_for_file(original_path)
+                .into_iter()
+                .find(|entry| entry.backup_path == path)
+                .and_then(|entry| entry.checksum.clone());
             backups.push(BackupInfo {
                 original_path: original_path.to_path_buf(),
                 backup_path: path,
                 created_at,
                 size_bytes: metadata.len(),
+                checksum,
+                cross_device: false,
             });
         }
 
@@ -508,16 +858,33 @@ impl BackupManager {
 
         let backup_filename = format!("{}{}", file_name.to_string_lossy(), self.backup_extension);
 
-        let backup_path = if let Some(ref backup_dir) = self.backup_dir {
-            backup_dir.join(backup_filename)
-        } else {
-            file_path
-                .parent()
-                .unwrap_or_else(|| Path::new("."))
-                .join(backup_filename)
+        Ok(self.backup_parent(file_path).join(backup_filename))
+    }
+
+    /// Directory holding backups of `file_path`.
+    ///
+    /// With a backup directory, the file's absolute parent path is mirrored inside it
+    /// (`/repo/a/file.txt` -> `<backup_dir>/repo/a/file.txt.bak`), so same-named files
+    /// from different directories never share a backup name.
+    fn backup_parent(&self, file_path: &Path) -> PathBuf {
+        let parent = file_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let Some(backup_dir) = &self.backup_dir else {
+            return parent.to_path_buf();
         };
 
-        Ok(backup_path)
+        let absolute = parent.canonicalize().unwrap_or_else(|_| {
+            std::env::current_dir()
+                .map(|cwd| cwd.join(parent))
+                .unwrap_or_else(|_| parent.to_path_buf())
+        });
+        let mirrored: PathBuf = absolute
+            .components()
+            .filter(|component| matches!(component, std::path::Component::Normal(_)))
+            .collect();
+        backup_dir.join(mirrored)
     }
 
     /// Generate conflict resolution path
@@ -563,12 +930,104 @@ pub struct BackupInfo {
     pub backup_path: PathBuf,
     pub created_at: std::time::SystemTime,
     pub size_bytes: u64,
+    /// SHA-256 hex of the backup contents (None for backups the registry has no record of)
+    pub checksum: Option<String>,
+    /// Backup lives on a different filesystem from the original
+    pub cross_device: bool,
 }
 
 impl BackupInfo {
     pub fn age_seconds(&self) -> u64 {
         self.created_at.elapsed().unwrap_or_default().as_secs()
     }
+
+    /// Check the backup against its recorded checksum; true when none was recorded
+    pub fn verify(&self) -> AgeResult<bool> {
+        backup_matches(&self.backup_path, self.checksum.as_deref())
+    }
+}
+
+/// Whether the backup at `path` hashes to `expected`; true when no checksum was recorded
+fn backup_matches(path: &Path, expected: Option<&str>) -> AgeResult<bool> {
+    let Some(expected) = expected else {
+        return Ok(true);
+    };
+    let digest =
+        file_digest(path).map_err(|e| AgeError::file_error("verify_backup", path.into(), e))?;
+    Ok(digest == expected)
+}
+
+/// SHA-256 of the file at `path`, hex encoded
+fn file_digest(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex_digest(hasher))
+}
+
+fn hex_digest(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Copy `source` to `dest` via a synced temp file in `dest`'s directory and an atomic
+/// rename, then sync the directory. Permissions are preserved. Returns (bytes, sha256).
+fn durable_copy(source: &Path, dest: &Path) -> std::io::Result<(u64, String)> {
+    let parent = dest
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let mut input = std::fs::File::open(source)?;
+    let permissions = input.metadata()?.permissions();
+
+    let mut temp = tempfile::Builder::new()
+        .prefix(".cage-backup-")
+        .tempfile_in(parent)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let count = input.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[..count]);
+        temp.write_all(&buffer[..count])?;
+        total += count as u64;
+    }
+    temp.as_file().set_permissions(permissions)?;
+    temp.as_file().sync_all()?;
+    temp.persist(dest).map_err(|e| e.error)?;
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    std::fs::File::open(parent)?.sync_all()?;
+
+    Ok((total, hex_digest(hasher)))
+}
+
+/// Whether `a` and the directory that will hold `b` share a filesystem
+fn same_device(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let dir = b
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        match (std::fs::metadata(a), std::fs::metadata(dir)) {
+            (Ok(a), Ok(dir)) => a.dev() == dir.dev(),
+            _ => true,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (a, b);
+        true
+    }
 }
 
 /// Result of creating a backup, including any relocated paths from conflict resolution
@@ -584,9 +1043,17 @@ pub struct BackupEntry {
     pub created_at: std::time::SystemTime,
     pub size_bytes: u64,
     pub generation: u32, // Increments with each backup (1=first, 2=second, etc.)
+    /// SHA-256 hex of the backup contents (None for entries recorded before checksums)
+    #[serde(default)]
+    pub checksum: Option<String>,
 }
 
 impl BackupEntry {
+    /// Check the backup against its recorded checksum; true when none was recorded
+    pub fn verify(&self) -> AgeResult<bool> {
+        backup_matches(&self.backup_path, self.checksum.as_deref())
+    }
+
     /// Get age in seconds
     pub fn age_seconds(&self) -> u64 {
         self.created_at.elapsed().unwrap_or_default().as_secs()
@@ -714,6 +1181,8 @@ impl BackupRegistry {
                     backup_path: entry.backup_path.clone(),
                     created_at: entry.created_at,
                     size_bytes: entry.size_bytes,
+                    checksum: entry.checksum.clone(),
+                    cross_device: false,
                 })
                 .collect();
 
@@ -789,6 +1258,9 @@ impl FileVerificationStatus {
     }
 }
 
+/// Phrase an operator must type to authorize an emergency unlock
+pub const EMERGENCY_UNLOCK_CONFIRMATION: &str = "CONFIRM_EMERGENCY_UNLOCK";
+
 /// Emergency operation result
 #[derive(Debug, Clone)]
 pub struct EmergencyResult {
@@ -804,6 +1276,24 @@ pub struct CageManager {
     audit_logger: AuditLogger,
     config: AgeConfig,
     operation_history: Vec<OperationRecord>,
+    throttle: std::sync::Mutex<Throttle>,
+    markers: Option<MarkerStore>,
+    quarantine: Option<Quarantine>,
+    prompter: std::sync::Arc<dyn Prompter>,
+    /// Id of the current operation, attached to per-file errors
+    operation_id: String,
+    /// Custom operations registered by embedding crates
+    operations: OperationRegistry,
+    /// Hash inputs and outputs of locks even without the checksum database
+    capture_digests: bool,
+    /// Fail files on problems that otherwise only warn (`CommonOptions::strict`)
+    strict: bool,
+    /// Where time-boxed unlocks are recorded (see `mgr::relock`)
+    relock_store: Option<PathBuf>,
+    /// Persisted recipient groups that `reset` clears (see `core::groups`)
+    group_store: Option<PathBuf>,
+    /// Token for admin-gated operations (see `core::roles`)
+    admin_token: Option<String>,
 }
 
 /// Record of performed operations for audit and recovery
@@ -834,10 +1324,19 @@ impl CageManager {
             BackupManager::new()
         };
 
-        let retention = self.config.backup_retention.to_retention_policy();
+        let retention = options
+            .backup_retention
+            .as_ref()
+            .unwrap_or(&self.config.backup_retention)
+            .to_retention_policy();
 
         manager = manager.with_retention(retention);
+        if let Some(extension) = &options.backup_extension {
+            manager = manager.with_extension(extension.clone());
+        }
         manager = manager.with_cleanup(self.config.backup_cleanup);
+        manager = manager
+            .with_preserve_ownership(options.preserve_ownership || self.config.preserve_ownership);
         manager
     }
 
@@ -852,23 +1351,100 @@ impl CageManager {
             }
         }
 
-        let audit_logger = AuditLogger::with_format(
-            config.audit_log_path.clone().map(PathBuf::from),
-            config.telemetry_format,
-        )?;
+        let throttle = std::sync::Mutex::new(Throttle::from_config(&config));
+        let quarantine = Quarantine::from_config(&config);
+
+        let audit_logger = AuditLogger::from_config(&config)?;
 
         Ok(Self {
             adapter,
             audit_logger,
             config,
             operation_history: Vec::new(),
+            throttle,
+            markers: MarkerStore::from_env(),
+            quarantine,
+            prompter: default_prompter(),
+            operation_id: format!("cage-{}", std::process::id()),
+            operations: OperationRegistry::new(),
+            capture_digests: false,
+            strict: false,
+            relock_store: RelockStore::default_path(),
+            group_store: GroupStore::default_path(),
+            admin_token: crate::core::roles::env_token(),
         })
     }
 
+    /// Record time-boxed unlocks in `path` instead of the per-user default
+    pub fn with_relock_store(mut self, path: PathBuf) -> Self {
+        self.relock_store = Some(path);
+        self
+    }
+
+    /// Use the group store at `path` instead of the per-user default
+    pub fn with_group_store(mut self, path: PathBuf) -> Self {
+        self.group_store = Some(path);
+        self
+    }
+
+    /// Route confirmations (reset, emergency unlock, danger mode) through `prompter`.
+    ///
+    /// Defaults to prompting on the terminal when stdin is one and declining otherwise.
+    pub fn with_prompter(mut self, prompter: std::sync::Arc<dyn Prompter>) -> Self {
+        self.prompter = prompter;
+        self
+    }
+
+    pub fn set_prompter(&mut self, prompter: std::sync::Arc<dyn Prompter>) {
+        self.prompter = prompter;
+    }
+
+    /// Authorize admin-gated operations with `token` instead of `CAGE_ADMIN_TOKEN`
+    pub fn set_admin_token(&mut self, token: Option<String>) {
+        self.admin_token = token;
+    }
+
+    pub fn prompter(&self) -> std::sync::Arc<dyn Prompter> {
+        self.prompter.clone()
+    }
+
+    /// Configuration this manager runs with
+    pub fn config(&self) -> &AgeConfig {
+        &self.config
+    }
+
+    /// Accept a matching confirmation token, otherwise ask the prompter
+    fn confirm_action(&self, token: &str, confirmation: Confirmation) -> AgeResult<bool> {
+        if confirmation.phrase.as_deref() == Some(token) {
+            return Ok(true);
+        }
+        let granted = self.prompter.confirm(&confirmation)?;
+        if granted {
+            self.audit_logger.log_info(&format!(
+                "Confirmation '{}' granted by prompter",
+                confirmation.action
+            ))?;
+        }
+        Ok(granted)
+    }
+
+    /// Apply this manager's `[limits]` to every later `age` spawn in the process (the
+    /// concurrent process cap, the per-process timeout and the sandbox) and its
+    /// `[scheduling]` nice/ionice hints to the current process.
+    ///
+    /// These are process-wide, so creating a manager never touches them; the CLI applies
+    /// them once per run and embedders call this when they want the configured limits.
+    pub fn apply_process_settings(&self) {
+        if let Err(e) = scheduling::apply_priority_hints(&self.config) {
+            tracing::warn!("Failed to apply scheduling hints: {}", e);
+        }
+        supervisor::configure(&self.config);
+    }
+
     /// Create CageManager with default configuration
     pub fn with_defaults() -> AgeResult<Self> {
-        let adapter = crate::adp::v1::AdapterFactory::create_default()?;
         let config = AgeConfig::load_default()?;
+        let adapter = crate::adp::v1::AdapterFactory::create_configured(&config)?;
         Self::new(adapter, config)
     }
 
@@ -876,15 +1452,103 @@ impl CageManager {
     // UNIFIED REQUEST API (CAGE-11) - New interface using request structs
     // ========================================================================================
 
+    /// Buffer memory budget from `[limits] memory_budget` or `CAGE_MEMORY_BUDGET`
+    fn memory_budget(&self) -> MemoryBudget {
+        MemoryBudget::from_config(&self.config)
+    }
+
+    /// Chunk size for a lock, clamped so one chunk fits the memory budget
+    fn budgeted_chunk_size(&self, requested: Option<u64>) -> Option<u64> {
+        let requested = requested?;
+        let clamped = self.memory_budget().clamp_chunk(requested);
+        if clamped < requested {
+            tracing::warn!(
+                "Chunk size {} exceeds memory budget. Using {} bytes.",
+                requested,
+                clamped
+            );
+        }
+        Some(clamped)
+    }
+
+    /// Resolve `Identity::Provider` references through the configured providers
+    fn resolve_identity(&self, identity: &Identity) -> AgeResult<ResolvedIdentity> {
+        IdentityProviderRegistry::from_config(&self.config).resolve(identity)
+    }
+
     /// Lock operation using request struct (CAGE-11)
     pub fn lock_with_request(&mut self, request: &LockRequest) -> AgeResult<OperationResult> {
+        let _span = tracing::info_span!(
+            "lock",
+            target = %request.target.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        if request.recursive || request.in_place {
+            self.guard_risky_target(&request.target, &request.common)?;
+        }
+        let started_on = chrono::Utc::now();
+        self.capture_digests = request.attestation.is_some();
+        self.strict = request.common.strict;
+        let result = self.lock_request_inner(request);
+        self.capture_digests = false;
+        self.strict = false;
+        self.refresh_state_cache(&request.target);
+        let mut result = result?;
+        self.record_checksums(&request.target, &mut result);
+        if let Some(path) = &request.attestation {
+            Statement::for_lock(request, &result, &self.operation_id, started_on).write(path)?;
+            self.audit_logger
+                .log_info(&format!("Wrote attestation: {}", path.display()))?;
+        }
+        Ok(result)
+    }
+
+    fn lock_request_inner(&mut self, request: &LockRequest) -> AgeResult<OperationResult> {
+        if let Identity::Provider(_) = request.identity {
+            let resolved = self.resolve_identity(&request.identity)?;
+            let mut resolved_request = request.clone();
+            resolved_request.identity = resolved.identity().clone();
+            return self.lock_request_inner(&resolved_request);
+        }
+
+        if let Some(adapter) = &request.adapter {
+            let mut inner = request.clone();
+            inner.adapter = None;
+            return self
+                .with_adapter_override(adapter, |manager| manager.lock_request_inner(&inner));
+        }
+
+        request.verify_pinned_groups(&self.config)?;
+
+        if request.preflight_recipients {
+            header::preflight_recipients(&request.all_recipients())?;
+        }
+
+        let verify_decryptable = if request.verify_decryptable {
+            self.decryptability_identity(request)?
+        } else {
+            None
+        };
+
         // Convert to legacy options
         let options = LockOptions {
             format: request.format,
             recursive: request.recursive,
             pattern_filter: request.pattern.clone(),
+            modified: request.modified,
             backup_before_lock: request.backup,
             backup_dir: request.backup_dir.clone(),
+            backup_retention: request.backup_retention.clone(),
+            backup_extension: request.backup_extension.clone(),
+            skip_existing: request.skip_existing,
+            verify_decryptable,
+            output_mode: request.output_mode,
+            preserve_ownership: request.preserve_ownership,
+            meta: self.sidecar_meta(request),
+            chunk_size: self.budgeted_chunk_size(request.chunk_size),
+            output_path: request.output_path.clone(),
+            encryption_key: None,
         };
 
         // Handle multi-recipient configuration first (preferred)
@@ -921,16 +1585,62 @@ impl CageManager {
                 reason: "Identity-based encryption requires recipients and is not supported yet"
                     .to_string(),
             }),
+            Identity::Provider(_) => Err(AgeError::InvalidOperation {
+                operation: "lock".to_string(),
+                reason: crate::lang::ERR_IDENTITY_PROVIDER_UNRESOLVED.to_string(),
+            }),
         }
     }
 
     /// Unlock operation using request struct (CAGE-11)
     pub fn unlock_with_request(&mut self, request: &UnlockRequest) -> AgeResult<OperationResult> {
+        let _span = tracing::info_span!(
+            "unlock",
+            target = %request.target.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        if request.recursive || request.in_place {
+            self.guard_risky_target(&request.target, &request.common)?;
+        }
+        self.strict = request.common.strict;
+        let result = self.unlock_request_inner(request);
+        self.strict = false;
+        self.refresh_state_cache(&request.target);
+        if let Ok(result) = &result {
+            self.log_decryptions(result, &request.identity);
+        }
+        if let (Ok(result), Some(window)) = (&result, request.relock_after) {
+            self.schedule_relock(result, window)?;
+        }
+        result
+    }
+
+    fn unlock_request_inner(&mut self, request: &UnlockRequest) -> AgeResult<OperationResult> {
+        if let Identity::Provider(_) = request.identity {
+            let resolved = self.resolve_identity(&request.identity)?;
+            let mut resolved_request = request.clone();
+            resolved_request.identity = resolved.identity().clone();
+            return self.unlock_request_inner(&resolved_request);
+        }
+
+        if let Some(adapter) = &request.adapter {
+            let mut inner = request.clone();
+            inner.adapter = None;
+            return self
+                .with_adapter_override(adapter, |manager| manager.unlock_request_inner(&inner));
+        }
+
         let options = UnlockOptions {
             selective: request.selective,
             verify_before_unlock: request.verify_first,
             pattern_filter: request.pattern.clone(),
-            preserve_encrypted: request.preserve_encrypted,
+            modified: request.modified,
+            // A time-boxed unlock relocks by deleting the plaintext again
+            preserve_encrypted: request.preserve_encrypted || request.relock_after.is_some(),
+            verify_decryptable: request.verify_decryptable.then(|| request.identity.clone()),
+            output_mode: request.output_mode,
+            preserve_ownership: request.preserve_ownership,
         };
 
         match &request.identity {
@@ -941,75 +1651,344 @@ impl CageManager {
             Identity::PromptPassphrase => Err(AgeError::PassphraseError {
                 message: "Interactive prompt not yet implemented".to_string(),
             }),
+            Identity::Provider(_) => Err(AgeError::InvalidOperation {
+                operation: "unlock".to_string(),
+                reason: crate::lang::ERR_IDENTITY_PROVIDER_UNRESOLVED.to_string(),
+            }),
         }
     }
 
-    /// Rotate operation using request struct (CAGE-17)
-    pub fn rotate_with_request(&mut self, request: &RotateRequest) -> AgeResult<OperationResult> {
-        if request.pattern.is_some() {
-            return Err(AgeError::InvalidOperation {
-                operation: "rotate".to_string(),
-                reason: "Pattern-filtered rotation is not supported yet".to_string(),
-            });
+    /// Identity that round-trips each lock output, or `None` when the escrow recipient covers it.
+    ///
+    /// Passphrase locks decrypt with the same passphrase. Recipient locks are covered by
+    /// the configured `escrow_recipient` being among the recipients, otherwise by an
+    /// identity file or SSH key. Anything else is refused before a file is touched.
+    fn decryptability_identity(&self, request: &LockRequest) -> AgeResult<Option<Identity>> {
+        let recipients = request.all_recipients();
+        if recipients.is_empty() {
+            return Ok(Some(request.identity.clone()));
         }
 
-        if let Some(recipients) = &request.new_recipients {
-            if !recipients.is_empty() {
-                return Err(AgeError::InvalidOperation {
-                    operation: "rotate".to_string(),
-                    reason: "Recipient-based rotation is not implemented yet".to_string(),
-                });
+        if let Some(escrow) = &self.config.escrow_recipient {
+            if header::recipient_keys(&recipients)?.contains(escrow) {
+                self.audit_logger.log_info(&format!(
+                    "Escrow recipient {} present; skipping round-trip check",
+                    escrow
+                ))?;
+                return Ok(None);
             }
         }
 
-        if !request.atomic {
-            return Err(AgeError::InvalidOperation {
-                operation: "rotate".to_string(),
-                reason: "Non-atomic rotation is not supported".to_string(),
-            });
+        match &request.identity {
+            Identity::IdentityFile(_) | Identity::SshKey(_) => Ok(Some(request.identity.clone())),
+            _ => Err(AgeError::InvalidOperation {
+                operation: "lock".to_string(),
+                reason: "Cannot verify decryptability: pass an identity that is among the \
+                         recipients or configure an escrow_recipient that is"
+                    .to_string(),
+            }),
         }
+    }
 
-        let (old_pass, new_pass) = match (&request.current_identity, &request.new_identity) {
-            (Identity::Passphrase(old), Identity::Passphrase(new)) => (old.as_str(), new.as_str()),
-            _ => {
-                return Err(AgeError::InvalidOperation {
-                    operation: "rotate".to_string(),
-                    reason: "Rotation currently supports passphrase identities only".to_string(),
-                })
-            }
-        };
+    /// Decrypt `ciphertext` with `identity` and check it matches `plaintext` byte for byte.
+    ///
+    /// The scratch plaintext lives next to `ciphertext` and is removed afterwards.
+    pub fn verify_round_trip(
+        &self,
+        plaintext: &Path,
+        ciphertext: &Path,
+        identity: &Identity,
+    ) -> AgeResult<()> {
+        let parent = ciphertext
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let scratch = tempfile::Builder::new()
+            .prefix(".cage-roundtrip-")
+            .tempfile_in(parent)
+            .map_err(|e| AgeError::TemporaryResourceError {
+                resource_type: "file".to_string(),
+                operation: "verify_round_trip".to_string(),
+                reason: e.to_string(),
+            })?;
 
-        self.rotate(&request.target, old_pass, new_pass)
+        let adapter = ShellAdapterV2::with_config(self.config.clone())?;
+        let mut decrypt =
+            |input: &Path, output: &Path| adapter.decrypt_file(input, output, identity);
+        decrypt_maybe_chunked(
+            ciphertext,
+            scratch.path(),
+            self.memory_budget(),
+            &mut decrypt,
+        )?;
+
+        let expected = file_digest(plaintext)
+            .map_err(|e| AgeError::file_error("verify_round_trip", plaintext.to_path_buf(), e))?;
+        let actual = file_digest(scratch.path()).map_err(|e| {
+            AgeError::file_error("verify_round_trip", scratch.path().to_path_buf(), e)
+        })?;
+        if expected != actual {
+            return Err(AgeError::InvalidOperation {
+                operation: "verify_round_trip".to_string(),
+                reason: format!(
+                    "{} does not decrypt back to {}",
+                    ciphertext.display(),
+                    plaintext.display()
+                ),
+            });
+        }
+        Ok(())
     }
 
-    /// Status operation using request struct (CAGE-18 follow-up)
-    pub fn status_with_request(&self, request: &StatusRequest) -> AgeResult<RepositoryStatus> {
-        self.audit_logger
-            .log_operation_start_single("status", &request.target)?;
+    /// Refuse risky recursive/in-place targets; audit any `allow_risky_path` override
+    fn guard_risky_target(&self, target: &Path, common: &CommonOptions) -> AgeResult<()> {
+        let validator = SafetyValidator::new(false, false)
+            .with_denylist(self.config.risky_path_denylist.clone())
+            .allow_risky_path(common.allow_risky_path);
+        if let Some(reason) = validator.validate_target(target)? {
+            self.audit_logger.log_warning(&format!(
+                "Risky path override for {}: {}",
+                target.display(),
+                reason
+            ))?;
+        }
+        Ok(())
+    }
 
-        if !request.target.exists() {
-            return Err(AgeError::file_error(
-                "read",
-                request.target.to_path_buf(),
-                std::io::Error::new(std::io::ErrorKind::NotFound, "Path not found"),
-            ));
+    /// Audit each file an unlock touched; successful ones pass the anomaly heuristics
+    fn log_decryptions(&self, result: &OperationResult, identity: &Identity) {
+        let processed = result.processed_files.iter().map(|file| (file, true));
+        let failed = result.failed_files.iter().map(|file| (file, false));
+        let outcomes: Vec<(&Path, bool)> = processed
+            .chain(failed)
+            .map(|(file, success)| (Path::new(file), success))
+            .collect();
+        if let Err(e) = self
+            .audit_logger
+            .log_decryption_events(&outcomes, identity_type(identity))
+        {
+            tracing::warn!("Failed to log decryption event: {}", e);
         }
+    }
 
-        let status = if request.target.is_file() {
-            self.get_file_status(&request.target)?
-        } else {
-            let files = if request.recursive {
-                self.collect_files_with_pattern(&request.target, request.pattern.as_deref())?
-            } else {
-                self.collect_directory_files_shallow(&request.target, request.pattern.as_deref())?
+    /// Record the plaintext written by a time-boxed unlock, expiring after `window`
+    fn schedule_relock(&self, result: &OperationResult, window: Duration) -> AgeResult<()> {
+        let path = self.relock_store_path()?;
+        let expires_at = chrono::Utc::now()
+            + chrono::Duration::from_std(window).map_err(|e| AgeError::InvalidOperation {
+                operation: "relock".to_string(),
+                reason: e.to_string(),
+            })?;
+        let suffix = self.config.extension_with_dot();
+        let mut store = RelockStore::load(&path)?;
+        for processed in &result.processed_files {
+            let Some(plaintext) = processed.strip_suffix(&suffix).map(PathBuf::from) else {
+                continue;
             };
+            store.add(RelockEntry {
+                sha256: crate::core::markers::sha256_file(&plaintext)?,
+                plaintext: std::path::absolute(&plaintext).unwrap_or(plaintext),
+                ciphertext: std::path::absolute(processed)
+                    .unwrap_or_else(|_| PathBuf::from(processed)),
+                expires_at,
+            });
+        }
+        store.save()
+    }
 
-            let mut status = RepositoryStatus::new();
-            for file in files {
-                status.total_files += 1;
-                if self.config.is_encrypted_file(&file) {
-                    status.encrypted_files += 1;
-                } else {
+    fn relock_store_path(&self) -> AgeResult<PathBuf> {
+        self.relock_store
+            .clone()
+            .ok_or_else(|| AgeError::InvalidOperation {
+                operation: "relock".to_string(),
+                reason: "No relock store: set CAGE_RELOCK_FILE or HOME".to_string(),
+            })
+    }
+
+    /// Update the repository state cache covering `target`, if one exists.
+    ///
+    /// Only the touched directory (or subtree) is refreshed; a failed save is logged.
+    fn refresh_state_cache(&self, target: &Path) {
+        let Some(mut cache) = StateCache::find(target) else {
+            return;
+        };
+        let start = if target.is_dir() {
+            target
+        } else {
+            target.parent().unwrap_or(target)
+        };
+        cache.refresh(start, &self.config);
+        if let Err(e) = cache.save() {
+            let _ = self
+                .audit_logger
+                .log_warning(&format!("Failed to update state cache: {}", e));
+        }
+    }
+
+    /// Add the hashes captured by a lock of `target` to its checksum database.
+    ///
+    /// Entries of ciphertexts that no longer exist are pruned; a failed save is recorded as
+    /// a warning.
+    fn record_checksums(&self, target: &Path, result: &mut OperationResult) {
+        if !self.config.checksum_db || result.checksums.is_empty() {
+            return;
+        }
+        let saved = ChecksumDb::for_target(target).and_then(|mut db| {
+            for (source, output, entry) in &result.checksums {
+                db.record(source, output, entry.clone());
+            }
+            db.prune();
+            db.save()
+        });
+        if let Err(e) = saved {
+            let warning = format!("Failed to update checksum database: {}", e);
+            let _ = self.warn(WarningCategory::Metadata, target, warning, result);
+        }
+    }
+
+    /// Update the checksum database after `output` was re-encrypted from `plaintext`.
+    ///
+    /// `source` is where the plaintext belongs in the repository; a session's workspace
+    /// copy lives elsewhere. Failures are logged like `record_checksums`.
+    fn rerecord_checksum(&self, source: &Path, plaintext: &Path, output: &Path) {
+        if !self.config.checksum_db {
+            return;
+        }
+        let saved = ChecksumEntry::capture(plaintext, output).and_then(|entry| {
+            let mut db = ChecksumDb::for_target(output)?;
+            db.record(source, output, entry);
+            db.save()
+        });
+        if let Err(e) = saved {
+            let _ = self
+                .audit_logger
+                .log_warning(&format!("Failed to update checksum database: {}", e));
+        }
+    }
+
+    /// Compare the checksum database covering `repository` with the plaintext tree at
+    /// `source_dir` (see `forge::checksum_db`)
+    pub fn verify_against_source(
+        &self,
+        repository: &Path,
+        source_dir: &Path,
+    ) -> AgeResult<SourceComparison> {
+        if !source_dir.is_dir() {
+            return Err(AgeError::InvalidOperation {
+                operation: "verify_against_source".to_string(),
+                reason: format!("{} is not a directory", source_dir.display()),
+            });
+        }
+        let db = ChecksumDb::for_target(repository)?;
+        if db.entries().is_empty() {
+            return Err(AgeError::InvalidOperation {
+                operation: "verify_against_source".to_string(),
+                reason: format!(
+                    "No checksum database at {} (enable [metadata] checksums before locking)",
+                    ChecksumDb::db_path(db.root()).display()
+                ),
+            });
+        }
+        let comparison = db.compare_source(source_dir, &self.config)?;
+        self.audit_logger.log_info(&format!(
+            "Source comparison of {} against {}: {} matched, exact={}",
+            db.root().display(),
+            source_dir.display(),
+            comparison.matched.len(),
+            comparison.is_exact()
+        ))?;
+        Ok(comparison)
+    }
+
+    /// Run `operation` with the named adapter in place of the default one, restoring it after
+    fn with_adapter_override<T>(
+        &mut self,
+        name: &str,
+        operation: impl FnOnce(&mut Self) -> AgeResult<T>,
+    ) -> AgeResult<T> {
+        let adapter = crate::adp::v1::AdapterFactory::create_adapter(name)?;
+        let previous = std::mem::replace(&mut self.adapter, adapter);
+        let result = operation(self);
+        self.adapter = previous;
+        result
+    }
+
+    /// Rotate operation using request struct (CAGE-17)
+    pub fn rotate_with_request(&mut self, request: &RotateRequest) -> AgeResult<OperationResult> {
+        let _span = tracing::info_span!(
+            "rotate",
+            target = %request.target.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        if request.pattern.is_some() {
+            return Err(AgeError::InvalidOperation {
+                operation: "rotate".to_string(),
+                reason: "Pattern-filtered rotation is not supported yet".to_string(),
+            });
+        }
+
+        if let Some(recipients) = &request.new_recipients {
+            if !recipients.is_empty() {
+                return Err(AgeError::InvalidOperation {
+                    operation: "rotate".to_string(),
+                    reason: "Recipient-based rotation is not implemented yet".to_string(),
+                });
+            }
+        }
+
+        if !request.atomic {
+            return Err(AgeError::InvalidOperation {
+                operation: "rotate".to_string(),
+                reason: "Non-atomic rotation is not supported".to_string(),
+            });
+        }
+
+        let current = self.resolve_identity(&request.current_identity)?;
+        let new = self.resolve_identity(&request.new_identity)?;
+        let (old_pass, new_pass) = match (current.identity(), new.identity()) {
+            (Identity::Passphrase(old), Identity::Passphrase(new)) => (old.as_str(), new.as_str()),
+            _ => {
+                return Err(AgeError::InvalidOperation {
+                    operation: "rotate".to_string(),
+                    reason: "Rotation currently supports passphrase identities only".to_string(),
+                })
+            }
+        };
+
+        self.rotate(&request.target, old_pass, new_pass)
+    }
+
+    /// Status operation using request struct (CAGE-18 follow-up)
+    pub fn status_with_request(&self, request: &StatusRequest) -> AgeResult<RepositoryStatus> {
+        let _span = tracing::info_span!(
+            "status",
+            target = %request.target.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        self.audit_logger
+            .log_operation_start_single("status", &request.target)?;
+
+        if !request.target.exists() {
+            return Err(AgeError::file_error(
+                "read",
+                request.target.to_path_buf(),
+                std::io::Error::new(std::io::ErrorKind::NotFound, "Path not found"),
+            ));
+        }
+
+        let status = if request.target.is_file() {
+            self.get_file_status(&request.target)?
+        } else {
+            let files = self.collect_status_files(request)?;
+
+            let mut status = RepositoryStatus::new();
+            for file in files {
+                status.total_files += 1;
+                if self.config.is_encrypted_file(&file) {
+                    status.encrypted_files += 1;
+                } else {
                     status.unencrypted_files += 1;
                 }
             }
@@ -1021,6 +2000,90 @@ impl CageManager {
         Ok(status)
     }
 
+    /// Nested per-directory status with per-file size, mtime and header format
+    ///
+    /// Selects files like `status_with_request` (set `recursive` for the whole tree) and
+    /// is what `cage status --recursive --json` prints for dashboards.
+    pub fn status_tree(&self, request: &StatusRequest) -> AgeResult<StatusTree> {
+        self.audit_logger
+            .log_operation_start_single("status", &request.target)?;
+        if !request.target.exists() {
+            return Err(AgeError::file_error(
+                "read",
+                request.target.to_path_buf(),
+                std::io::Error::new(std::io::ErrorKind::NotFound, "Path not found"),
+            ));
+        }
+        let files = if request.target.is_file() {
+            vec![request.target.clone()]
+        } else {
+            self.collect_status_files(request)?
+        };
+        StatusTree::build(&request.target, &files, &self.config)
+    }
+
+    /// Files of a directory status request: cached, recursive or shallow
+    fn collect_status_files(&self, request: &StatusRequest) -> AgeResult<Vec<PathBuf>> {
+        if request.fast {
+            self.repository()
+                .with_traversal(CachedTraversal::new(self.config.clone()))
+                .collect_files(
+                    &request.target,
+                    request.pattern.as_deref(),
+                    FileSelection::All,
+                )
+        } else if request.recursive {
+            self.collect_files_with_pattern(&request.target, request.pattern.as_deref())
+        } else {
+            self.collect_directory_files_shallow(&request.target, request.pattern.as_deref())
+        }
+    }
+
+    /// Lock every entry of a plan as one audited run.
+    ///
+    /// Entries run in order with their own options; a failing entry is recorded and the
+    /// remaining entries still run. Results are merged into one `OperationResult`.
+    pub fn lock_plan(
+        &mut self,
+        plan: &LockPlan,
+        identity: &Identity,
+    ) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
+        self.audit_logger
+            .log_operation_start_single("lock_plan", &plan.source)?;
+
+        let mut merged = OperationResult::new();
+        for entry in &plan.entries {
+            let outcome = entry
+                .to_lock_request(identity, &self.config)
+                .and_then(|request| self.lock_with_request(&request));
+            match outcome {
+                Ok(result) => {
+                    merged.total_processed += result.total_processed;
+                    merged.processed_files.extend(result.processed_files);
+                    merged.failed_files.extend(result.failed_files);
+                    merged.skipped_files.extend(result.skipped_files);
+                    merged.warnings.extend(result.warnings);
+                    merged.file_durations_ms.extend(result.file_durations_ms);
+                    merged.add_bytes(result.bytes_in, result.bytes_out);
+                    merged.file_bytes.extend(result.file_bytes);
+                }
+                Err(e) => {
+                    // failed_files holds paths only, so `--failed-out` lists can be retried
+                    let message = format!("Plan entry {} failed: {}", entry.path.display(), e);
+                    tracing::error!("{}", message);
+                    self.audit_logger.log_error(&message)?;
+                    merged.add_failure(entry.path.display().to_string());
+                }
+            }
+        }
+
+        merged.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("lock_plan", &plan.source, &merged)?;
+        Ok(merged)
+    }
+
     /// Streaming operation using request struct (CAGE-18)
     pub fn stream_with_request(
         &mut self,
@@ -1028,23 +2091,169 @@ impl CageManager {
         input: &mut (dyn Read + Send),
         output: &mut (dyn Write + Send),
     ) -> AgeResult<u64> {
-        use crate::adp::v2::{AgeAdapterV2, ShellAdapterV2};
+        self.stream_with_progress(request, input, output, None)
+    }
+
+    /// Streaming operation reporting cumulative input bytes to `progress`
+    pub fn stream_with_progress(
+        &mut self,
+        request: &StreamRequest,
+        input: &mut (dyn Read + Send),
+        output: &mut (dyn Write + Send),
+        progress: Option<ByteProgressFn>,
+    ) -> AgeResult<u64> {
+        let outcome = self
+            .prepare_stream(request)
+            .and_then(|(_resolved, prepared)| {
+                run_stream(&self.config, &prepared, input, output, progress)
+            });
+        self.log_stream_decryption(request, &outcome);
+        outcome
+    }
+
+    /// Copy of `request` with a provider identity resolved and the buffer clamped to the
+    /// memory budget, as file operations do.
+    ///
+    /// The returned `ResolvedIdentity` keeps any staged identity file alive; hold it until
+    /// the stream is done.
+    fn prepare_stream(
+        &self,
+        request: &StreamRequest,
+    ) -> AgeResult<(ResolvedIdentity, StreamRequest)> {
+        let resolved = self.resolve_identity(&request.identity)?;
+        let mut prepared = request.clone();
+        prepared.identity = resolved.identity().clone();
+        prepared.buffer_size = self.memory_budget().clamp_buffer(request.buffer_size);
+        if prepared.buffer_size < request.buffer_size {
+            tracing::warn!(
+                "Buffer size {} exceeds memory budget. Using {} bytes.",
+                request.buffer_size,
+                prepared.buffer_size
+            );
+        }
+        Ok((resolved, prepared))
+    }
+
+    /// Log a streamed decryption so it reaches the anomaly heuristics like file unlocks
+    fn log_stream_decryption(&self, request: &StreamRequest, outcome: &AgeResult<u64>) {
+        if !matches!(request.operation, StreamOperation::Decrypt) {
+            return;
+        }
+        if let Err(e) = self.audit_logger.log_decryption_event(
+            Path::new("<stream>"),
+            identity_type(&request.identity),
+            outcome.is_ok(),
+        ) {
+            tracing::warn!("Failed to log decryption event: {}", e);
+        }
+    }
+
+    /// Decrypt one encrypted file into `output` without writing plaintext to disk
+    ///
+    /// age runs on pipes, so this needs an identity file or SSH key (directly or from a
+    /// provider): passphrases go through the PTY path, which stages plaintext in a temp
+    /// file, and are refused. Chunked containers are refused too: their chunks are
+    /// decrypted through files.
+    pub fn unlock_to_writer(
+        &mut self,
+        file: &Path,
+        identity: &Identity,
+        output: &mut (dyn Write + Send),
+    ) -> AgeResult<u64> {
+        let _span = tracing::info_span!(
+            "unlock",
+            target = %file.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        if !file.is_file() {
+            return Err(AgeError::InvalidOperation {
+                operation: "unlock".to_string(),
+                reason: "Decrypting to a stream needs a single encrypted file".to_string(),
+            });
+        }
+        if chunked::is_chunked(file)? {
+            return Err(AgeError::InvalidOperation {
+                operation: "unlock".to_string(),
+                reason: "Chunked containers cannot be decrypted to a stream; unlock to a file"
+                    .to_string(),
+            });
+        }
+        // `_resolved` is held until the stream is done so a staged identity file stays
+        let (_resolved, request) =
+            self.prepare_stream(&StreamRequest::decrypt(identity.clone()))?;
+        let identity = request.identity;
+        if matches!(
+            identity,
+            Identity::Passphrase(_) | Identity::PromptPassphrase
+        ) {
+            return Err(AgeError::InvalidOperation {
+                operation: "unlock".to_string(),
+                reason: "Decrypting to a stream needs an identity file or SSH key; passphrase \
+                         decryption stages plaintext in a temp file"
+                    .to_string(),
+            });
+        }
 
+        self.audit_logger
+            .log_operation_start_single("unlock_stream", file)?;
+        let input = std::fs::File::open(file)
+            .map_err(|e| AgeError::file_error("open", file.to_path_buf(), e))?;
+        let mut input = std::io::BufReader::with_capacity(request.buffer_size, input);
         let adapter = ShellAdapterV2::with_config(self.config.clone())?;
+        let bytes = adapter.decrypt_stream_no_spill(&mut input, output, &identity)?;
+        self.audit_logger.log_info(&format!(
+            "Decrypted {} to a stream ({} bytes)",
+            file.display(),
+            bytes
+        ))?;
+        self.audit_logger
+            .log_decryption_event(file, identity_type(&identity), true)?;
+        Ok(bytes)
+    }
 
-        match request.operation {
-            StreamOperation::Encrypt => {
-                let recipients_slice = request.recipients.as_ref().map(|list| list.as_slice());
-                adapter.encrypt_stream(
-                    input,
-                    output,
-                    &request.identity,
-                    recipients_slice,
-                    request.format,
-                )
+    /// Streaming operation over tokio I/O; the age process runs on the blocking pool
+    #[cfg(feature = "async")]
+    pub async fn stream_with_request_async<R, W>(
+        &self,
+        request: &StreamRequest,
+        input: R,
+        output: W,
+    ) -> AgeResult<u64>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        self.stream_with_progress_async(request, input, output, None)
+            .await
+    }
+
+    /// Async streaming operation reporting cumulative input bytes to `progress`
+    #[cfg(feature = "async")]
+    pub async fn stream_with_progress_async<R, W>(
+        &self,
+        request: &StreamRequest,
+        input: R,
+        output: W,
+        progress: Option<ByteProgressFn>,
+    ) -> AgeResult<u64>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        let config = self.config.clone();
+        let outcome = match self.prepare_stream(request) {
+            Ok((resolved, prepared)) => {
+                crate::adp::async_io::bridge_blocking(input, output, move |input, output| {
+                    let _resolved = resolved;
+                    run_stream(&config, &prepared, input, output, progress)
+                })
+                .await
             }
-            StreamOperation::Decrypt => adapter.decrypt_stream(input, output, &request.identity),
-        }
+            Err(e) => Err(e),
+        };
+        self.log_stream_decryption(request, &outcome);
+        outcome
     }
 
     /// Verify operation using request struct (CAGE-11)
@@ -1052,6 +2261,19 @@ impl CageManager {
         &mut self,
         request: &VerifyRequest,
     ) -> AgeResult<VerificationResult> {
+        let _span = tracing::info_span!(
+            "verify",
+            target = %request.target.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        if request.stream_verify && !request.deep_verify {
+            return Err(AgeError::InvalidOperation {
+                operation: "verify".to_string(),
+                reason: "Streaming verification requires deep verification".to_string(),
+            });
+        }
+
         let mut result = self.verify(&request.target)?;
 
         if request.deep_verify {
@@ -1076,7 +2298,13 @@ impl CageManager {
             let mut still_verified = Vec::new();
             for entry in result.verified_files.drain(..) {
                 let path = PathBuf::from(&entry);
-                match self.deep_verify_file(&adapter, &path, identity) {
+                let outcome = if request.stream_verify {
+                    self.stream_verify_file(&adapter, &path, identity)
+                        .map(|digest| result.stream_digests.push(digest))
+                } else {
+                    self.deep_verify_file(&adapter, &path, identity)
+                };
+                match outcome {
                     Ok(_) => still_verified.push(entry),
                     Err(err) => {
                         result
@@ -1110,6 +2338,7 @@ impl CageManager {
         options: LockOptions,
     ) -> AgeResult<OperationResult> {
         let start_time = Instant::now();
+        self.begin_operation("lock");
         self.audit_logger.log_operation_start_single("lock", path)?;
 
         let mut result = OperationResult::new();
@@ -1125,6 +2354,15 @@ impl CageManager {
 
         // Validate passphrase
         self.validate_passphrase(passphrase)?;
+        check_output_path(path, &options)?;
+        if options.chunk_size.is_some() {
+            return Err(AgeError::InvalidOperation {
+                operation: "lock".to_string(),
+                reason: "Chunked locks need recipients; a passphrase would be stretched \
+                         once per chunk"
+                    .to_string(),
+            });
+        }
 
         // Determine operation scope
         if path.is_file() {
@@ -1138,6 +2376,8 @@ impl CageManager {
                     reason: "Directory requires --recursive flag".to_string(),
                 });
             }
+        } else if let Some(reason) = special_file_reason(path) {
+            self.record_uncovered(path, reason, &mut result)?;
         }
 
         // Record operation
@@ -1301,14 +2541,7 @@ impl CageManager {
 
     /// Helper method to collect all encrypted files in a directory
     fn collect_encrypted_files(&self, directory: &Path, files: &mut Vec<PathBuf>) -> AgeResult<()> {
-        let entries = std::fs::read_dir(directory)
-            .map_err(|e| AgeError::file_error("read_dir", directory.to_path_buf(), e))?;
-
-        for entry in entries {
-            let entry = entry
-                .map_err(|e| AgeError::file_error("read_entry", directory.to_path_buf(), e))?;
-            let path = entry.path();
-
+        for path in self.list_dir(directory)? {
             if path.is_file() {
                 // Check if file is encrypted by checking Age header
                 if self.is_encrypted_file(&path)? {
@@ -1323,18 +2556,36 @@ impl CageManager {
         Ok(())
     }
 
+    /// Entries of `directory` in the configured listing order
+    fn list_dir(&self, directory: &Path) -> AgeResult<Vec<PathBuf>> {
+        let entries = std::fs::read_dir(directory)
+            .map_err(|e| AgeError::file_error("read_dir", directory.to_path_buf(), e))?;
+        let mut paths = entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(|e| AgeError::file_error("read_entry", directory.to_path_buf(), e))?;
+        self.config
+            .listing_order
+            .arrange(&mut paths, PathBuf::as_path);
+        Ok(paths)
+    }
+
     /// Check if a file is encrypted (basic heuristic)
     fn is_encrypted_file(&self, path: &Path) -> AgeResult<bool> {
         if !path.exists() {
             return Ok(false);
         }
 
-        let content =
-            std::fs::read(path).map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+        // Only the first line matters; never read the whole (possibly huge) file
+        let mut head = Vec::new();
+        std::fs::File::open(path)
+            .and_then(|file| {
+                file.take(header::HeaderFormat::DETECT_BYTES as u64)
+                    .read_to_end(&mut head)
+            })
+            .map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
 
-        // Check for Age headers
-        Ok(content.starts_with(b"age-encryption.org/v1")
-            || content.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----"))
+        Ok(header::HeaderFormat::detect(&head).is_some() || head.starts_with(chunked::MAGIC))
     }
 
     /// Rotate key for a single file with backup
@@ -1345,6 +2596,8 @@ impl CageManager {
         new_passphrase: &str,
         backup_dir: &Path,
     ) -> AgeResult<()> {
+        refuse_chunked(file_path, "rotate")?;
+
         // Create backup of original file
         let file_name = file_path.file_name().ok_or_else(|| {
             AgeError::file_error(
@@ -1428,7 +2681,7 @@ impl CageManager {
 
             let backup_path = backup_dir.join(file_name);
             if backup_path.exists() {
-                std::fs::copy(&backup_path, file_path)
+                durable_copy(&backup_path, file_path)
                     .map_err(|e| AgeError::file_error("restore_backup", backup_path, e))?;
             }
         }
@@ -1448,6 +2701,7 @@ impl CageManager {
         options: UnlockOptions,
     ) -> AgeResult<OperationResult> {
         let start_time = Instant::now();
+        self.begin_operation("unlock");
         self.audit_logger
             .log_operation_start_single("unlock", path)?;
 
@@ -1480,6 +2734,8 @@ impl CageManager {
             self.unlock_single_file(path, passphrase, &options, &mut result)?;
         } else if path.is_dir() {
             self.unlock_repository(path, passphrase, &options, &mut result)?;
+        } else if let Some(reason) = special_file_reason(path) {
+            self.record_uncovered(path, reason, &mut result)?;
         }
 
         self.record_operation("unlock", path, true, &result);
@@ -1498,6 +2754,7 @@ impl CageManager {
         options: UnlockOptions,
     ) -> AgeResult<OperationResult> {
         let start_time = Instant::now();
+        self.begin_operation("unlock");
         self.audit_logger
             .log_operation_start_single("unlock", path)?;
 
@@ -1530,6 +2787,8 @@ impl CageManager {
             self.unlock_single_file_internal(path, &options, &mut result, &mut decrypt)?;
         } else if path.is_dir() {
             self.unlock_repository_internal(path, &options, &mut result, &mut decrypt)?;
+        } else if let Some(reason) = special_file_reason(path) {
+            self.record_uncovered(path, reason, &mut result)?;
         }
 
         self.record_operation("unlock", path, true, &result);
@@ -1546,9 +2805,10 @@ impl CageManager {
         path: &Path,
         identity: &Identity,
         recipients: &[Recipient],
-        options: LockOptions,
+        mut options: LockOptions,
     ) -> AgeResult<OperationResult> {
         let start_time = Instant::now();
+        self.begin_operation("lock");
         self.audit_logger.log_operation_start_single("lock", path)?;
 
         let mut result = OperationResult::new();
@@ -1567,6 +2827,8 @@ impl CageManager {
                 reason: "Recipient list cannot be empty".to_string(),
             });
         }
+        check_output_path(path, &options)?;
+        options.encryption_key = Some(meta::recipients_key(recipients, identity, options.format));
 
         let adapter = ShellAdapterV2::with_config(self.config.clone())?;
         let identity_clone = identity.clone();
@@ -1592,6 +2854,8 @@ impl CageManager {
                     reason: "Directory requires --recursive flag".to_string(),
                 });
             }
+        } else if let Some(reason) = special_file_reason(path) {
+            self.record_uncovered(path, reason, &mut result)?;
         }
 
         self.record_operation("lock", path, true, &result);
@@ -1608,7 +2872,7 @@ impl CageManager {
         path: &Path,
         identity: &Identity,
         multi_config: &crate::core::MultiRecipientConfig,
-        options: LockOptions,
+        mut options: LockOptions,
     ) -> AgeResult<OperationResult> {
         use crate::core::Recipient;
 
@@ -1634,12 +2898,18 @@ impl CageManager {
                 reason: "Multi-recipient configuration has no recipients".to_string(),
             });
         }
+        check_output_path(path, &options)?;
 
         // Convert strings to Recipient enum for compatibility with existing adapter
         let recipient_objects: Vec<Recipient> = all_recipients
             .into_iter()
             .map(|r| Recipient::PublicKey(r))
             .collect();
+        options.encryption_key = Some(meta::recipients_key(
+            &recipient_objects,
+            identity,
+            options.format,
+        ));
 
         // Log multi-recipient operation with group metadata
         let total_recipients = multi_config.total_recipients();
@@ -1666,7 +2936,7 @@ impl CageManager {
         if multi_config.enforce_hierarchy {
             self.audit_logger
                 .log_info("Hierarchy enforcement enabled - checking tier compliance")?;
-            // TODO: Implement tier hierarchy validation when Ignite integration is ready
+            multi_config.validate_hierarchy()?;
         }
 
         let adapter = ShellAdapterV2::with_config(self.config.clone())?;
@@ -1693,6 +2963,8 @@ impl CageManager {
                     reason: "Directory encryption requires recursive option".to_string(),
                 });
             }
+        } else if let Some(reason) = special_file_reason(path) {
+            self.record_uncovered(path, reason, &mut result)?;
         }
 
         // Log structured encryption event for each group
@@ -1701,15 +2973,10 @@ impl CageManager {
             if let Err(e) = self.audit_logger.log_encryption_event(
                 path,
                 Some(group.recipients.clone()),
-                match identity {
-                    Identity::Passphrase(_) => "passphrase",
-                    Identity::IdentityFile(_) => "identity-file",
-                    Identity::SshKey(_) => "ssh-key",
-                    Identity::PromptPassphrase => "prompt-passphrase",
-                },
+                identity_type(identity),
                 result.processed_files.len() > 0,
             ) {
-                eprintln!("Warning: Failed to log encryption event: {}", e);
+                tracing::warn!("Failed to log encryption event: {}", e);
             }
         }
 
@@ -1769,13 +3036,25 @@ impl CageManager {
         })
     }
 
-    /// RESET: Emergency repository unlock/reset
+    /// RESET: Emergency repository reset
+    ///
+    /// Snapshots every encrypted file into a timestamped directory under the configured
+    /// backup directory (or `<repository>/.cage_reset_backup`), clears recipient group
+    /// authority metadata (including the persisted group store, copied into the snapshot
+    /// first), and writes a `recovery.json` bundle describing what changed.
+    /// Encrypted files in the repository itself are left untouched. An admin operation
+    /// once `[admin] token_hash` is set (see `set_admin_token`).
     pub fn reset(&mut self, repository: &Path, confirmation: &str) -> AgeResult<EmergencyResult> {
+        crate::core::roles::authorize(&self.config, "reset", self.admin_token.as_deref())?;
         self.audit_logger
             .log_emergency_operation("reset", repository)?;
 
         // Require explicit confirmation for destructive operation
-        if confirmation != "CONFIRM_RESET" {
+        let request = Confirmation::new("reset", "Emergency reset of repository metadata")
+            .detail(format!("Repository: {}", repository.display()))
+            .detail("Recipient group authority metadata will be cleared.")
+            .phrase("CONFIRM_RESET");
+        if !self.confirm_action(confirmation, request)? {
             return Err(AgeError::InvalidOperation {
                 operation: "reset".to_string(),
                 reason: "Reset requires explicit confirmation".to_string(),
@@ -1789,17 +3068,96 @@ impl CageManager {
             });
         }
 
-        // Emergency reset would involve:
-        // 1. Validate emergency access authorization
-        // 2. Create backup of current state
-        // 3. Reset authority chain to emergency state
-        // 4. Provide recovery procedures
+        let timestamp = chrono::Utc::now();
+        let backup_root = self
+            .config
+            .backup_directory
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| repository.join(".cage_reset_backup"));
+        let snapshot_dir = backup_root.join(format!("reset-{}", timestamp.format("%Y%m%dT%H%M%S")));
+        std::fs::create_dir_all(&snapshot_dir)
+            .map_err(|e| AgeError::file_error("create_snapshot", snapshot_dir.clone(), e))?;
+
+        // 1. Snapshot current encrypted state
+        let mut affected_files = Vec::new();
+        let files = self.collect_encrypted_files_with_pattern(repository, None)?;
+        for file in files.iter().filter(|f| !f.starts_with(&backup_root)) {
+            let relative = file.strip_prefix(repository).unwrap_or(file);
+            let target = snapshot_dir.join("files").join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    AgeError::file_error("create_directory", parent.to_path_buf(), e)
+                })?;
+            }
+            std::fs::copy(file, &target)
+                .map_err(|e| AgeError::file_error("snapshot", file.clone(), e))?;
+            affected_files.push(file.display().to_string());
+        }
+        self.audit_logger.log_warning(&format!(
+            "RESET_SNAPSHOT {} files -> {}",
+            affected_files.len(),
+            snapshot_dir.display()
+        ))?;
+
+        // 2. Remove authority metadata (recipient groups)
+        let removed_groups: Vec<_> = self.config.recipient_groups.values().cloned().collect();
+        self.config.recipient_groups.clear();
+        for group in &removed_groups {
+            self.audit_logger
+                .log_authority_operation("reset_remove_group", &group.name)?;
+        }
+        // Stored groups would be merged back into the config on the next load
+        let mut groups_backup = None;
+        if let Some(path) = &self.group_store {
+            let mut store = GroupStore::load(path)?.with_admin_token(self.admin_token.clone());
+            if !store.names().is_empty() {
+                let backup = snapshot_dir.join("groups.json");
+                std::fs::copy(path, &backup)
+                    .map_err(|e| AgeError::file_error("snapshot", path.clone(), e))?;
+                store.clear();
+                store.save()?;
+                groups_backup = Some(backup.display().to_string());
+            }
+        }
+
+        // 3. Emit recovery bundle
+        let bundle_path = snapshot_dir.join("recovery.json");
+        let bundle = serde_json::json!({
+            "operation": "reset",
+            "repository": repository.display().to_string(),
+            "created_at": timestamp.to_rfc3339(),
+            "snapshot_dir": snapshot_dir.display().to_string(),
+            "snapshot_files": affected_files,
+            "removed_recipient_groups": removed_groups,
+            "groups_file_backup": groups_backup,
+        });
+        let bundle_json =
+            serde_json::to_string_pretty(&bundle).map_err(|e| AgeError::InvalidOperation {
+                operation: "reset".to_string(),
+                reason: format!("Failed to serialize recovery bundle: {}", e),
+            })?;
+        std::fs::write(&bundle_path, bundle_json)
+            .map_err(|e| AgeError::file_error("write_recovery_bundle", bundle_path.clone(), e))?;
+
+        self.audit_logger.log_warning(&format!(
+            "RESET_COMPLETE groups_removed={} bundle={}",
+            removed_groups.len(),
+            bundle_path.display()
+        ))?;
 
         Ok(EmergencyResult {
             operation: "reset".to_string(),
-            affected_files: vec![repository.display().to_string()],
-            recovery_actions: vec!["Emergency reset completed".to_string()],
-            security_events: vec!["Emergency reset authorized".to_string()],
+            affected_files,
+            recovery_actions: vec![
+                format!("Encrypted state archived to {}", snapshot_dir.display()),
+                format!("Recovery bundle written to {}", bundle_path.display()),
+                "Re-create recipient groups from the bundle before re-locking".to_string(),
+            ],
+            security_events: vec![
+                "Emergency reset authorized".to_string(),
+                format!("{} recipient group(s) removed", removed_groups.len()),
+            ],
         })
     }
 
@@ -1997,6 +3355,34 @@ impl CageManager {
     // LIFECYCLE OPERATIONS - Integrity and emergency procedures
     // ========================================================================================
 
+    /// Find ciphertext under `path` that looks partially written (see `forge::orphans`)
+    pub fn scan_partial_outputs(&self, path: &Path) -> AgeResult<Vec<SuspectOutput>> {
+        let files = if path.is_file() {
+            vec![path.to_path_buf()]
+        } else {
+            self.repository()
+                .collect_files(path, None, FileSelection::EncryptedOnly)?
+        };
+        orphans::scan(&files)
+    }
+
+    /// Delete `suspects` found by `scan_partial_outputs`, together with their sidecars
+    pub fn remove_partial_outputs(&self, suspects: &[SuspectOutput]) -> AgeResult<Vec<PathBuf>> {
+        let mut removed = Vec::new();
+        for suspect in suspects {
+            std::fs::remove_file(&suspect.path)
+                .map_err(|e| AgeError::file_error("remove", suspect.path.clone(), e))?;
+            meta::remove_sidecar(&suspect.path)?;
+            self.audit_logger.log_warning(&format!(
+                "Removed partial output ({}): {}",
+                suspect.defect.describe(),
+                suspect.path.display()
+            ))?;
+            removed.push(suspect.path.clone());
+        }
+        Ok(removed)
+    }
+
     /// VERIFY: Integrity checking and validation
     pub fn verify(&self, path: &Path) -> AgeResult<VerificationResult> {
         self.audit_logger
@@ -2044,43 +3430,887 @@ impl CageManager {
             failed_files,
             authority_status: "Authority verification pending".to_string(),
             overall_status: "Verification completed".to_string(),
+            stream_digests: Vec::new(),
         })
     }
 
-    /// EMERGENCY: Fail-safe recovery operations
-    pub fn emergency_unlock(
-        &mut self,
-        repository: &Path,
-        emergency_passphrase: &str,
-    ) -> AgeResult<EmergencyResult> {
+    /// CONVERT: Rewrap ciphertext between ASCII armor and binary without decrypting
+    ///
+    /// Only the outer encoding changes; the age header and payload are copied verbatim,
+    /// so no identity is required. Writes to `output`, or replaces `path` in place.
+    pub fn convert(
+        &self,
+        path: &Path,
+        format: OutputFormat,
+        output: Option<&Path>,
+    ) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
         self.audit_logger
-            .log_emergency_operation("emergency_unlock", repository)?;
+            .log_operation_start_single("convert", path)?;
 
-        if !repository.exists() || !repository.is_dir() {
-            return Err(AgeError::InvalidOperation {
-                operation: "emergency_unlock".to_string(),
-                reason: "Repository path required".to_string(),
-            });
-        }
+        let mut result = OperationResult::new();
+        refuse_chunked(path, "convert")?;
+        let current = ciphertext_format(path, "convert")?;
 
-        self.validate_passphrase(emergency_passphrase)?;
+        let target = output.unwrap_or(path);
+        if current == format && target == path {
+            result.add_skipped(path.display().to_string(), "already in requested format");
+            result.finalize(start_time);
+            self.audit_logger
+                .log_operation_complete("convert", path, &result)?;
+            return Ok(result);
+        }
 
-        // Emergency unlock involves:
-        // 1. Validate emergency access credentials
-        // 2. Attempt unlock with emergency procedures
-        // 3. Document all security events
-        // 4. Provide recovery guidance
+        if format == OutputFormat::AsciiArmor {
+            if let Some(warning) = armor::check_input(path, &self.config)? {
+                self.warn(WarningCategory::Armor, path, warning, &mut result)?;
+            }
+        }
+        let bytes_in = file_size(path);
+        rewrap_armor(path, target, format)?;
 
-        Ok(EmergencyResult {
+        result.add_file_bytes(target.display().to_string(), bytes_in, file_size(target));
+        result.add_success(target.display().to_string());
+        result.record_file_duration(target.display().to_string(), start_time.elapsed());
+        result.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("convert", path, &result)?;
+        Ok(result)
+    }
+
+    /// REKEY: Add recipients by rewriting only the age header
+    ///
+    /// Unlike `rotate`, the payload is never re-encrypted: `identity` unwraps the file
+    /// key, which is then wrapped for each new recipient. Directories are processed
+    /// recursively; `output` is only honored for a single file.
+    pub fn rekey_add_recipients(
+        &self,
+        path: &Path,
+        identity: &Identity,
+        recipients: &[String],
+        output: Option<&Path>,
+    ) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
+        self.audit_logger
+            .log_operation_start_single("rekey", path)?;
+
+        if recipients.is_empty() {
+            return Err(AgeError::InvalidOperation {
+                operation: "rekey".to_string(),
+                reason: "At least one recipient to add is required".to_string(),
+            });
+        }
+        let resolved = self.resolve_identity(identity)?;
+        let identities = header::load_identities(resolved.identity())?;
+        let new_recipients = recipients
+            .iter()
+            .map(|r| header::parse_recipient(r))
+            .collect::<AgeResult<Vec<_>>>()?;
+
+        let targets = if path.is_dir() {
+            if output.is_some() {
+                return Err(AgeError::InvalidOperation {
+                    operation: "rekey".to_string(),
+                    reason: "--output is only supported for a single file".to_string(),
+                });
+            }
+            self.collect_encrypted_files_with_pattern(path, None)?
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut result = OperationResult::new();
+        for file in targets {
+            let file_start = Instant::now();
+            let target = output.unwrap_or(&file);
+            let bytes_in = file_size(&file);
+            let rekeyed = refuse_chunked(&file, "rekey")
+                .and_then(|_| header::add_recipients(&file, target, &identities, &new_recipients));
+            match rekeyed {
+                Ok(()) => {
+                    result.add_file_bytes(
+                        target.display().to_string(),
+                        bytes_in,
+                        file_size(target),
+                    );
+                    result.add_success(target.display().to_string());
+                    result.record_file_duration(target.display().to_string(), file_start.elapsed());
+                }
+                Err(e) => {
+                    self.audit_logger.log_warning(&format!(
+                        "Rekey failed for {}: {}",
+                        file.display(),
+                        e
+                    ))?;
+                    result.add_failure(file.display().to_string());
+                }
+            }
+        }
+
+        result.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("rekey", path, &result)?;
+        Ok(result)
+    }
+
+    /// REKEY: Plan re-encryption of files encrypted to an older membership of `group`
+    ///
+    /// Compares the `group_hash` recorded in each file's `.meta` sidecar with the group's
+    /// current one; files without a sidecar are listed as unknown.
+    pub fn plan_group_rekey(&self, path: &Path, group: &str) -> AgeResult<RekeyPlan> {
+        let group = self.known_group(group)?;
+        let files = if path.is_dir() {
+            self.collect_encrypted_files_with_pattern(path, None)?
+        } else {
+            vec![path.to_path_buf()]
+        };
+        RekeyPlan::scan(group, &files)
+    }
+
+    /// REKEY: Re-encrypt the files of `group`'s plan to its current membership
+    ///
+    /// `identity` must decrypt the files. The plan is saved after every file and a saved
+    /// plan for the same membership is resumed rather than rescanned.
+    pub fn rekey_group(
+        &self,
+        path: &Path,
+        group: &str,
+        identity: &Identity,
+    ) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
+        self.audit_logger
+            .log_operation_start_single("rekey_group", path)?;
+
+        let current = self.known_group(group)?.clone();
+        let plan_path = RekeyPlan::plan_path(path, group);
+        let mut plan = match RekeyPlan::load(&plan_path)? {
+            Some(saved) if saved.group_hash == current.group_hash() => saved,
+            _ => self.plan_group_rekey(path, group)?,
+        };
+        plan.save(&plan_path)?;
+
+        let adapter = ShellAdapterV2::with_config(self.config.clone())?;
+        let pending: Vec<PathBuf> = plan.pending().map(|entry| entry.path.clone()).collect();
+        let mut result = OperationResult::new();
+        for (index, file) in pending.iter().enumerate() {
+            if interrupt::interrupted().is_some() {
+                self.record_interrupted("rekey_group", &pending[index..], &mut result)?;
+                break;
+            }
+            let file_start = Instant::now();
+            let bytes_in = file_size(file);
+            let rekeyed = CipherMeta::load(file)?
+                .ok_or_else(|| AgeError::InvalidOperation {
+                    operation: "rekey_group".to_string(),
+                    reason: format!("{} lost its .meta sidecar", file.display()),
+                })
+                .map(|meta| meta.with_group_membership(&current))
+                .and_then(|meta| {
+                    self.reencrypt_file(&adapter, file, identity, &meta.to_recipients())?;
+                    meta.write(file)
+                });
+            match rekeyed {
+                Ok(_) => {
+                    plan.mark_done(file);
+                    plan.save(&plan_path)?;
+                    result.add_file_bytes(file.display().to_string(), bytes_in, file_size(file));
+                    result.add_success(file.display().to_string());
+                    result.record_file_duration(file.display().to_string(), file_start.elapsed());
+                }
+                Err(e) => {
+                    self.audit_logger.log_warning(&format!(
+                        "Rekey of {} to group {} failed: {}",
+                        file.display(),
+                        group,
+                        e
+                    ))?;
+                    result.add_failure(file.display().to_string());
+                }
+            }
+        }
+
+        if plan.pending().next().is_none() {
+            let _ = std::fs::remove_file(&plan_path);
+        }
+        result.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("rekey_group", path, &result)?;
+        Ok(result)
+    }
+
+    /// Recipient group `name` from config (including the group store)
+    fn known_group(&self, name: &str) -> AgeResult<&RecipientGroup> {
+        self.config
+            .get_recipient_group(name)
+            .ok_or_else(|| AgeError::InvalidOperation {
+                operation: "rekey_group".to_string(),
+                reason: format!("Unknown recipient group '{}'", name),
+            })
+    }
+
+    /// Decrypt `file` and encrypt it again to `recipients`, keeping its armor format
+    fn reencrypt_file(
+        &self,
+        adapter: &ShellAdapterV2,
+        file: &Path,
+        identity: &Identity,
+        recipients: &[Recipient],
+    ) -> AgeResult<()> {
+        refuse_chunked(file, "rekey")?;
+        let format = ciphertext_format(file, "rekey")?;
+        // Stage next to the file so the final rename stays on one filesystem
+        let parent = file
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let temp = || {
+            NamedTempFile::new_in(parent)
+                .map_err(|e| AgeError::file_error("create_temp", parent.to_path_buf(), e))
+        };
+        let plaintext = temp()?;
+        let staged = temp()?;
+        adapter.decrypt_file(file, plaintext.path(), identity)?;
+        adapter.encrypt_file(
+            plaintext.path(),
+            staged.path(),
+            identity,
+            Some(recipients),
+            format,
+        )?;
+        std::fs::metadata(file)
+            .and_then(|meta| std::fs::set_permissions(staged.path(), meta.permissions()))
+            .map_err(|e| AgeError::file_error("copy_permissions", file.to_path_buf(), e))?;
+        staged
+            .persist(file)
+            .map_err(|e| AgeError::file_error("persist", file.to_path_buf(), e.error))?;
+        Ok(())
+    }
+
+    /// Remove plaintext left by time-boxed unlocks once expired (all of it with `all`).
+    ///
+    /// Unchanged plaintext is deleted and its ciphertext left byte-identical, so backups
+    /// and sync tools see no churn. Edited plaintext is encrypted again to the recipients
+    /// in the ciphertext's `.meta` sidecar; without one (passphrase locks) it is reported
+    /// as failed and left for a manual lock. Failed entries stay recorded.
+    pub fn relock_due(&mut self, all: bool) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
+        let path = self.relock_store_path()?;
+        self.begin_operation("relock");
+        self.audit_logger
+            .log_operation_start_single("relock", &path)?;
+
+        let store = RelockStore::load(&path)?;
+        let due = if all {
+            store.entries().to_vec()
+        } else {
+            store.due(chrono::Utc::now())
+        };
+        let mut result = OperationResult::new();
+        let mut settled = Vec::new();
+        for entry in due {
+            let plaintext = entry.plaintext.display().to_string();
+            match self.relock_entry(&entry) {
+                Ok(true) => result.add_success(plaintext),
+                Ok(false) => result.add_skipped(plaintext, "plaintext already removed"),
+                Err(e) => {
+                    self.audit_logger.log_warning(&format!(
+                        "Relock of {} failed: {}",
+                        entry.plaintext.display(),
+                        e
+                    ))?;
+                    result.add_failure(plaintext);
+                    continue;
+                }
+            }
+            settled.push(entry);
+        }
+
+        // Reload so unlocks recorded while this ran are kept
+        let mut store = RelockStore::load(&path)?;
+        store.remove(&settled);
+        store.save()?;
+
+        self.record_operation("relock", &path, result.failed_files.is_empty(), &result);
+        result.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("relock", &path, &result)?;
+        Ok(result)
+    }
+
+    /// Put one expired plaintext back under its ciphertext; `false` when it is already gone
+    fn relock_entry(&self, entry: &RelockEntry) -> AgeResult<bool> {
+        if !entry.plaintext.exists() {
+            return Ok(false);
+        }
+        let status = self.verify_file_integrity(&entry.ciphertext)?;
+        if !status.is_valid() {
+            return Err(AgeError::InvalidOperation {
+                operation: "relock".to_string(),
+                reason: format!(
+                    "{} no longer verifies ({}); lock {} manually",
+                    entry.ciphertext.display(),
+                    status.error_message.unwrap_or_default(),
+                    entry.plaintext.display()
+                ),
+            });
+        }
+
+        if crate::core::markers::sha256_file(&entry.plaintext)? != entry.sha256 {
+            let recipients = CipherMeta::load(&entry.ciphertext)?
+                .map(|meta| meta.to_recipients())
+                .unwrap_or_default();
+            if recipients.is_empty() {
+                return Err(AgeError::InvalidOperation {
+                    operation: "relock".to_string(),
+                    reason: format!(
+                        "{} changed since unlock and {} has no recipient sidecar; lock it manually",
+                        entry.plaintext.display(),
+                        entry.ciphertext.display()
+                    ),
+                });
+            }
+            self.seal_over(&entry.plaintext, &entry.ciphertext, &recipients)?;
+            self.rerecord_checksum(&entry.plaintext, &entry.plaintext, &entry.ciphertext);
+        }
+
+        std::fs::remove_file(&entry.plaintext)
+            .map_err(|e| AgeError::file_error("delete", entry.plaintext.clone(), e))?;
+        Ok(true)
+    }
+
+    /// Encrypt `plaintext` to `recipients`, replacing `ciphertext` and keeping its format
+    fn seal_over(
+        &self,
+        plaintext: &Path,
+        ciphertext: &Path,
+        recipients: &[Recipient],
+    ) -> AgeResult<()> {
+        let adapter = ShellAdapterV2::with_config(self.config.clone())?;
+        let format = ciphertext_format(ciphertext, "seal")?;
+        replace_via_staging(ciphertext, |staged| {
+            adapter.encrypt_file(
+                plaintext,
+                staged,
+                &Identity::Passphrase(String::new()),
+                Some(recipients),
+                format,
+            )
+        })
+    }
+
+    /// SESSION: Decrypt every encrypted file under `repository` into a tracked workspace.
+    ///
+    /// `workspace` defaults to `Session::default_workspace` and must be empty or missing.
+    /// The repository itself is not modified; see `mgr::session`.
+    pub fn open_session(
+        &mut self,
+        repository: &Path,
+        identity: &Identity,
+        workspace: Option<&Path>,
+    ) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
+        if let Some(open) = Session::load(repository)? {
+            return Err(AgeError::InvalidOperation {
+                operation: "open".to_string(),
+                reason: format!(
+                    "A session is already open in {}; run cage close first",
+                    open.workspace.display()
+                ),
+            });
+        }
+        let repository = std::path::absolute(repository)
+            .map_err(|e| AgeError::file_error("resolve", repository.to_path_buf(), e))?;
+        if !repository.is_dir() {
+            return Err(AgeError::InvalidOperation {
+                operation: "open".to_string(),
+                reason: format!("{} is not a directory", repository.display()),
+            });
+        }
+        // Held until every file is decrypted so a staged identity file stays in place
+        let resolved = self.resolve_identity(identity)?;
+        let identity = resolved.identity().clone();
+
+        let workspace = workspace
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| Session::default_workspace(&repository));
+        let owns_workspace = !workspace.exists();
+        if !owns_workspace
+            && workspace
+                .read_dir()
+                .map_or(true, |mut entries| entries.next().is_some())
+        {
+            return Err(AgeError::InvalidOperation {
+                operation: "open".to_string(),
+                reason: format!(
+                    "Workspace {} is not an empty directory",
+                    workspace.display()
+                ),
+            });
+        }
+        create_private_dir(&workspace)?;
+
+        self.begin_operation("open");
+        self.audit_logger
+            .log_operation_start_single("open", &repository)?;
+        let mut session = Session {
+            repository: repository.clone(),
+            workspace: workspace.clone(),
+            opened_at: chrono::Utc::now().to_rfc3339(),
+            owns_workspace,
+            files: Vec::new(),
+        };
+        // Recorded up front so an interrupted open still points at its plaintext
+        session.save()?;
+
+        let (files, _) = self.repository().collect_files_with_skips(
+            &repository,
+            None,
+            FileSelection::EncryptedOnly,
+        )?;
+        let suffix = self.config.extension_with_dot();
+        let mut result = OperationResult::new();
+        for file in files {
+            let Ok(relative) = file.strip_prefix(&repository).map(Path::to_path_buf) else {
+                continue;
+            };
+            let Some(plain) = relative
+                .to_str()
+                .and_then(|name| name.strip_suffix(&suffix))
+                .map(PathBuf::from)
+            else {
+                continue;
+            };
+            let output = workspace.join(&plain);
+            let opened = output
+                .parent()
+                .map_or(Ok(()), create_private_dir)
+                .and_then(|_| permissions::prepare_output(&output, Some(0o600)))
+                .and_then(|_| self.decrypt_as(&file, &output, &identity))
+                .and_then(|_| crate::core::markers::sha256_file(&output));
+            match opened {
+                Ok(sha256) => {
+                    result.add_file_bytes(
+                        file.display().to_string(),
+                        file_size(&file),
+                        file_size(&output),
+                    );
+                    result.add_success(file.display().to_string());
+                    session.files.push(SessionFile {
+                        ciphertext: relative,
+                        plaintext: plain,
+                        sha256,
+                    });
+                }
+                Err(e) => {
+                    self.audit_logger.log_warning(&format!(
+                        "Opening {} failed: {}",
+                        file.display(),
+                        e
+                    ))?;
+                    let _ = std::fs::remove_file(&output);
+                    result.add_failure(file.display().to_string());
+                }
+            }
+        }
+        session.save()?;
+        self.log_decryptions(&result, &identity);
+
+        self.record_operation("open", &repository, result.failed_files.is_empty(), &result);
+        result.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("open", &repository, &result)?;
+        Ok(result)
+    }
+
+    /// SESSION: Encrypt changed and new workspace files back and remove the plaintext.
+    ///
+    /// Files are encrypted to the recipients in their `.meta` sidecar when there is one.
+    /// Passphrase files and new files use the passphrase in `identity`, which callers
+    /// should have confirmed. A file encrypted to recipients without a sidecar naming them
+    /// is refused, since age headers do not record recipient keys. Files deleted from the
+    /// workspace keep their ciphertext. If anything fails, the workspace and session are
+    /// kept.
+    pub fn close_session(
+        &mut self,
+        repository: &Path,
+        identity: Option<&Identity>,
+    ) -> AgeResult<OperationResult> {
+        let start_time = Instant::now();
+        let session = Session::load(repository)?.ok_or_else(|| AgeError::InvalidOperation {
+            operation: "close".to_string(),
+            reason: format!("No session is open for {}", repository.display()),
+        })?;
+        self.begin_operation("close");
+        self.audit_logger
+            .log_operation_start_single("close", &session.repository)?;
+
+        let changes = session.changes(&self.config)?;
+        let passphrase = match identity {
+            Some(Identity::Passphrase(pass)) => Some(pass.as_str()),
+            _ => None,
+        };
+        let suffix = self.config.extension_with_dot();
+        let added = changes.added.iter().map(|plain| {
+            let mut cipher = plain.clone().into_os_string();
+            cipher.push(&suffix);
+            (plain.clone(), PathBuf::from(cipher))
+        });
+        let mut result = OperationResult::new();
+        for (plain, cipher) in changes
+            .changed
+            .iter()
+            .map(|file| (file.plaintext.clone(), file.ciphertext.clone()))
+            .chain(added)
+        {
+            let plaintext = session.workspace.join(&plain);
+            let ciphertext = session.repository.join(&cipher);
+            match self.seal_session_file(&plaintext, &ciphertext, passphrase) {
+                Ok(()) => {
+                    let source = session.repository.join(&plain);
+                    self.rerecord_checksum(&source, &plaintext, &ciphertext);
+                    result.add_file_bytes(
+                        ciphertext.display().to_string(),
+                        file_size(&plaintext),
+                        file_size(&ciphertext),
+                    );
+                    result.add_success(ciphertext.display().to_string());
+                }
+                Err(e) => {
+                    self.audit_logger.log_warning(&format!(
+                        "Closing {} failed: {}",
+                        plaintext.display(),
+                        e
+                    ))?;
+                    result.add_failure(plaintext.display().to_string());
+                }
+            }
+        }
+        for file in &changes.removed {
+            let ciphertext = session.repository.join(&file.ciphertext);
+            result.add_skipped(
+                ciphertext.display().to_string(),
+                "removed from the workspace; ciphertext kept",
+            );
+        }
+
+        if result.failed_files.is_empty() {
+            let plaintext = session
+                .files
+                .iter()
+                .map(|file| &file.plaintext)
+                .chain(&changes.added);
+            self.clear_workspace(&session, plaintext)?;
+            session.discard()?;
+        }
+        self.refresh_state_cache(&session.repository);
+
+        self.record_operation(
+            "close",
+            &session.repository,
+            result.failed_files.is_empty(),
+            &result,
+        );
+        result.finalize(start_time);
+        self.audit_logger
+            .log_operation_complete("close", &session.repository, &result)?;
+        Ok(result)
+    }
+
+    /// SESSION: Drop an open session's plaintext without encrypting anything back
+    pub fn discard_session(&mut self, repository: &Path) -> AgeResult<()> {
+        let session = Session::load(repository)?.ok_or_else(|| AgeError::InvalidOperation {
+            operation: "close".to_string(),
+            reason: format!("No session is open for {}", repository.display()),
+        })?;
+        let changes = session.changes(&self.config)?;
+        let plaintext = session
+            .files
+            .iter()
+            .map(|file| &file.plaintext)
+            .chain(&changes.added);
+        self.clear_workspace(&session, plaintext)?;
+        session.discard()?;
+        self.audit_logger.log_info(&format!(
+            "Discarded session for {} ({} changed, {} added file(s) not encrypted)",
+            session.repository.display(),
+            changes.changed.len(),
+            changes.added.len()
+        ))
+    }
+
+    /// Decrypt `input` to `output` with any kind of identity
+    fn decrypt_as(&self, input: &Path, output: &Path, identity: &Identity) -> AgeResult<()> {
+        match identity {
+            Identity::Passphrase(pass) => self.adapter.decrypt(input, output, pass),
+            _ => ShellAdapterV2::with_config(self.config.clone())?
+                .decrypt_file(input, output, identity),
+        }
+    }
+
+    /// Encrypt a session file over `ciphertext`, to its sidecar recipients or, for
+    /// passphrase and new files, with `passphrase`
+    fn seal_session_file(
+        &self,
+        plaintext: &Path,
+        ciphertext: &Path,
+        passphrase: Option<&str>,
+    ) -> AgeResult<()> {
+        let existing = ciphertext.is_file();
+        let recipients = if existing {
+            CipherMeta::load(ciphertext)?
+                .map(|meta| meta.to_recipients())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        if !recipients.is_empty() {
+            return self.seal_over(plaintext, ciphertext, &recipients);
+        }
+        // Never turn a recipient file into a passphrase file
+        if existing && !header::AgeHeader::read_file(ciphertext)?.is_passphrase() {
+            return Err(AgeError::InvalidOperation {
+                operation: "close".to_string(),
+                reason: format!(
+                    "{} is encrypted to recipients that no .meta sidecar names; lock it \
+                     again with its recipients instead",
+                    plaintext.display()
+                ),
+            });
+        }
+
+        let passphrase = passphrase.ok_or_else(|| AgeError::InvalidOperation {
+            operation: "close".to_string(),
+            reason: format!(
+                "{} has no recipient sidecar; a passphrase is needed to encrypt it",
+                plaintext.display()
+            ),
+        })?;
+        let format = if existing {
+            ciphertext_format(ciphertext, "close")?
+        } else {
+            self.config.output_format
+        };
+        if let Some(dir) = ciphertext.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| AgeError::file_error("create_dir", dir.to_path_buf(), e))?;
+        }
+        replace_via_staging(ciphertext, |staged| {
+            self.adapter.encrypt(plaintext, staged, passphrase, format)
+        })
+    }
+
+    /// Remove a session's plaintext; the whole workspace when `open` created it
+    fn clear_workspace<'a>(
+        &self,
+        session: &Session,
+        plaintext: impl Iterator<Item = &'a PathBuf>,
+    ) -> AgeResult<()> {
+        if session.owns_workspace {
+            return match std::fs::remove_dir_all(&session.workspace) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(AgeError::file_error("delete", session.workspace.clone(), e))
+                }
+                _ => Ok(()),
+            };
+        }
+        for relative in plaintext {
+            let path = session.workspace.join(relative);
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(AgeError::file_error("delete", path, e));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// EMERGENCY: Fail-safe recovery operations
+    ///
+    /// Decrypts every encrypted file under `repository` with an escrow identity or
+    /// emergency passphrase. Encrypted originals are always preserved. When
+    /// `quarantine_dir` is provided, plaintext is written there (mirroring the
+    /// repository layout) instead of next to the encrypted files.
+    pub fn emergency_unlock(
+        &mut self,
+        repository: &Path,
+        identity: &Identity,
+        confirmation: &str,
+        quarantine_dir: Option<&Path>,
+    ) -> AgeResult<EmergencyResult> {
+        self.audit_logger
+            .log_emergency_operation("emergency_unlock", repository)?;
+
+        let request = Confirmation::new("emergency_unlock", "Emergency unlock of repository")
+            .detail(format!("Repository: {}", repository.display()))
+            .phrase(EMERGENCY_UNLOCK_CONFIRMATION);
+        if !self.confirm_action(confirmation, request)? {
+            return Err(AgeError::InvalidOperation {
+                operation: "emergency_unlock".to_string(),
+                reason: format!(
+                    "Emergency unlock requires typing {} to confirm",
+                    EMERGENCY_UNLOCK_CONFIRMATION
+                ),
+            });
+        }
+
+        if !repository.exists() {
+            return Err(AgeError::InvalidOperation {
+                operation: "emergency_unlock".to_string(),
+                reason: "Repository path required".to_string(),
+            });
+        }
+
+        let mut security_events = vec!["Emergency access confirmed by operator".to_string()];
+        match identity {
+            Identity::Passphrase(pass) => {
+                self.validate_passphrase(pass)?;
+                security_events.push("Emergency passphrase presented".to_string());
+            }
+            Identity::IdentityFile(path) | Identity::SshKey(path) => {
+                if !path.exists() {
+                    return Err(AgeError::file_error(
+                        "emergency_unlock",
+                        path.clone(),
+                        std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            "Escrow identity not found",
+                        ),
+                    ));
+                }
+                security_events.push(format!("Escrow identity presented: {}", path.display()));
+            }
+            Identity::PromptPassphrase => {
+                return Err(AgeError::PassphraseError {
+                    message: "Interactive prompt not yet implemented".to_string(),
+                })
+            }
+            Identity::Provider(reference) => {
+                let resolved = self.resolve_identity(identity)?;
+                self.audit_logger
+                    .log_warning(&format!("EMERGENCY_PROVIDER_IDENTITY {}", reference))?;
+                return self.emergency_unlock(
+                    repository,
+                    resolved.identity(),
+                    EMERGENCY_UNLOCK_CONFIRMATION,
+                    quarantine_dir,
+                );
+            }
+        }
+
+        if let Some(dir) = quarantine_dir {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| AgeError::file_error("create_quarantine", dir.to_path_buf(), e))?;
+            security_events.push(format!("Plaintext quarantined to {}", dir.display()));
+        }
+
+        let files = if repository.is_file() {
+            vec![repository.to_path_buf()]
+        } else {
+            self.collect_encrypted_files_with_pattern(repository, None)?
+        };
+
+        let suffix = self.config.extension_with_dot();
+        let mut affected_files = Vec::new();
+        let mut recovery_actions = Vec::new();
+
+        for file in files {
+            let file_name = file
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            let plain_name = file_name.strip_suffix(&suffix).unwrap_or(file_name);
+            if plain_name.is_empty() {
+                continue;
+            }
+
+            let output = match quarantine_dir {
+                Some(dir) => {
+                    let base = if repository.is_file() {
+                        repository.parent().unwrap_or(repository)
+                    } else {
+                        repository
+                    };
+                    let relative = file.strip_prefix(base).unwrap_or(&file);
+                    dir.join(relative).with_file_name(plain_name)
+                }
+                None => file.with_file_name(plain_name),
+            };
+
+            if output.exists() {
+                security_events.push(format!(
+                    "Skipped {}: plaintext target {} already exists",
+                    file.display(),
+                    output.display()
+                ));
+                continue;
+            }
+
+            if let Some(parent) = output.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    AgeError::file_error("create_directory", parent.to_path_buf(), e)
+                })?;
+            }
+
+            match self.decrypt_as(&file, &output, identity) {
+                Ok(()) => {
+                    self.audit_logger.log_warning(&format!(
+                        "EMERGENCY_DECRYPT {} -> {}",
+                        file.display(),
+                        output.display()
+                    ))?;
+                    affected_files.push(output.display().to_string());
+                }
+                Err(err) => {
+                    self.audit_logger.log_error(&format!(
+                        "EMERGENCY_DECRYPT_FAILED {}: {}",
+                        file.display(),
+                        err
+                    ))?;
+                    security_events.push(format!(
+                        "Decryption failed for {}: {}",
+                        file.display(),
+                        err
+                    ));
+                }
+            }
+        }
+
+        recovery_actions.push(format!(
+            "Decrypted {} file(s); encrypted originals preserved",
+            affected_files.len()
+        ));
+        if !affected_files.is_empty() {
+            recovery_actions.push(
+                "Re-lock or securely delete recovered plaintext once access is restored"
+                    .to_string(),
+            );
+        }
+
+        Ok(EmergencyResult {
             operation: "emergency_unlock".to_string(),
-            affected_files: vec![repository.display().to_string()],
-            recovery_actions: vec!["Emergency unlock procedures initiated".to_string()],
-            security_events: vec!["Emergency access authorized".to_string()],
+            affected_files,
+            recovery_actions,
+            security_events,
         })
     }
 
     /// BATCH: Bulk operations using request API (CAGE-20)
     pub fn batch_with_request(&mut self, request: &BatchRequest) -> AgeResult<OperationResult> {
+        let _span = tracing::info_span!(
+            "batch",
+            target = %request.target.display(),
+            operation_id = %self.operation_id
+        )
+        .entered();
+        if let Identity::Provider(_) = request.identity {
+            // Resolve once so providers are not invoked per file
+            let resolved = self.resolve_identity(&request.identity)?;
+            let mut resolved_request = request.clone();
+            resolved_request.identity = resolved.identity().clone();
+            return self.batch_with_request(&resolved_request);
+        }
+
         let op_label = match request.operation {
             BatchOperation::Lock => "batch_lock",
             BatchOperation::Unlock => "batch_unlock",
@@ -2101,6 +4331,7 @@ impl CageManager {
         } else {
             self.collect_directory_files_shallow(&request.target, request.pattern.as_deref())?
         };
+        let files = request.select_files(files);
 
         let start_time = Instant::now();
         let mut result = OperationResult::new();
@@ -2116,6 +4347,7 @@ impl CageManager {
                     }
 
                     lock_request.backup = request.backup;
+                    lock_request.backup_dir = request.backup_dir.clone();
                     lock_request.recursive = false;
                     lock_request.common = request.common.clone();
 
@@ -2127,10 +4359,9 @@ impl CageManager {
                             for failure in operation.failed_files {
                                 result.add_failure(failure);
                             }
+                            result.warnings.extend(operation.warnings);
                         }
-                        Err(err) => {
-                            result.add_failure(format!("{}: {}", file.display(), err));
-                        }
+                        Err(err) => self.record_batch_failure(file, &err, &mut result)?,
                     }
                 }
             }
@@ -2152,10 +4383,9 @@ impl CageManager {
                             for failure in operation.failed_files {
                                 result.add_failure(failure);
                             }
+                            result.warnings.extend(operation.warnings);
                         }
-                        Err(err) => {
-                            result.add_failure(format!("{}: {}", file.display(), err));
-                        }
+                        Err(err) => self.record_batch_failure(file, &err, &mut result)?,
                     }
                 }
             }
@@ -2174,6 +4404,20 @@ impl CageManager {
         Ok(result)
     }
 
+    /// Fail `file` in a batch, keeping the error out of `failed_files` so the list
+    /// stays usable for `--failed-out` and `--print0=failed`
+    fn record_batch_failure(
+        &self,
+        file: &Path,
+        error: &AgeError,
+        result: &mut OperationResult,
+    ) -> AgeResult<()> {
+        let message = format!("Batch entry {} failed: {}", file.display(), error);
+        tracing::error!("{}", message);
+        result.add_failure(file.display().to_string());
+        self.audit_logger.log_error(&message)
+    }
+
     /// BATCH: Bulk operations for directories/repositories
     pub fn batch_process(
         &mut self,
@@ -2246,6 +4490,88 @@ impl CageManager {
         Ok(result)
     }
 
+    // ========================================================================================
+    // CUSTOM OPERATIONS
+    // ========================================================================================
+
+    /// Register a custom operation to run later by name with `run_operation`
+    pub fn register_operation(&mut self, operation: SharedOperation) -> AgeResult<()> {
+        self.operations.register(operation)
+    }
+
+    /// Names of the registered custom operations
+    pub fn registered_operations(&self) -> Vec<&'static str> {
+        self.operations.names()
+    }
+
+    /// Run the custom operation registered as `name`
+    pub fn run_operation(&mut self, name: &str) -> AgeResult<OperationResult> {
+        let operation = self
+            .operations
+            .get(name)
+            .ok_or_else(|| AgeError::InvalidOperation {
+                operation: name.to_string(),
+                reason: "No custom operation registered under this name".to_string(),
+            })?;
+        self.execute_operation(operation.as_ref())
+    }
+
+    /// Run `operation` with the manager's safety rails.
+    ///
+    /// Declared targets pass the risky-path guard, preconditions and postconditions are
+    /// checked around `execute_with`, failures carry stage context, and the result is
+    /// audited and recorded like a built-in operation.
+    pub fn execute_operation(
+        &mut self,
+        operation: &(dyn Operation + Send + Sync),
+    ) -> AgeResult<OperationResult> {
+        let name = operation.operation_name();
+        self.begin_operation(name);
+        let targets = operation.targets();
+        let primary = targets
+            .first()
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("."));
+        for target in &targets {
+            self.guard_risky_target(target, &CommonOptions::default())?;
+        }
+
+        self.audit_logger
+            .log_operation_start_single(name, &primary)?;
+        let start_time = Instant::now();
+        let mut result = OperationResult::new();
+        let op = self.operation_id.clone();
+        let outcome = operation
+            .validate_preconditions()
+            .stage(Stage::Prepare, &primary, &op)
+            .and_then(|_| operation.execute_with(&mut result))
+            .and_then(|_| {
+                operation
+                    .validate_postconditions()
+                    .stage(Stage::Verify, &primary, &op)
+            });
+        result.finalize(start_time);
+        if result.total_processed == 0 && result.failed_files.is_empty() {
+            // Operations that report no files succeed when all their hooks did
+            result.success = outcome.is_ok();
+        }
+
+        let succeeded = outcome.is_ok() && result.success;
+        self.record_operation(name, &primary, succeeded, &result);
+        match outcome {
+            Ok(()) => {
+                self.audit_logger
+                    .log_operation_complete(name, &primary, &result)?;
+                Ok(result)
+            }
+            Err(e) => {
+                self.audit_logger
+                    .log_error(&format!("Custom operation {} failed: {}", name, e))?;
+                Err(e)
+            }
+        }
+    }
+
     // ========================================================================================
     // INTERNAL IMPLEMENTATION METHODS
     // ========================================================================================
@@ -2269,6 +4595,14 @@ impl CageManager {
         Ok(())
     }
 
+    /// Pace multi-file operations to the configured `max_throughput`
+    fn throttle_file(&self, file: &Path) {
+        let bytes = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        if let Ok(mut throttle) = self.throttle.lock() {
+            throttle.consume(bytes);
+        }
+    }
+
     /// Lock a single file using provided encrypt strategy
     fn lock_single_file_internal<F>(
         &self,
@@ -2280,11 +4614,38 @@ impl CageManager {
     where
         F: FnMut(&Path, &Path, OutputFormat) -> AgeResult<()>,
     {
-        let output_path = {
-            let mut path = file.as_os_str().to_os_string();
-            path.push(self.config.extension_with_dot());
-            PathBuf::from(path)
+        let _span = tracing::debug_span!("file", path = %file.display()).entered();
+        let output_path = match &options.output_path {
+            Some(path) => path.clone(),
+            None => self.encrypted_output_path(file),
         };
+        let op = self.operation_id.as_str();
+
+        if options.skip_existing {
+            if let Some(ref markers) = self.markers {
+                if markers
+                    .is_unchanged(file, &output_path, &marker_key(options))
+                    .stage(Stage::Prepare, file, op)?
+                {
+                    result.add_skipped(file.display().to_string(), "unchanged since last lock");
+                    return Ok(());
+                }
+            }
+        }
+
+        if options.format == OutputFormat::AsciiArmor {
+            match armor::check_input(file, &self.config) {
+                Ok(Some(warning)) => self.warn(WarningCategory::Armor, file, warning, result)?,
+                Ok(None) => {}
+                Err(e) => {
+                    result.add_failure(file.display().to_string());
+                    return Err(e.with_context(Stage::Prepare, file, op));
+                }
+            }
+        }
+
+        self.throttle_file(file);
+        let file_start = Instant::now();
 
         let mut backup_info: Option<BackupInfo> = None;
 
@@ -2309,11 +4670,17 @@ impl CageManager {
                             }
                         }
                         Err(e) => {
-                            self.audit_logger.log_warning(&format!(
+                            let warning = format!(
                                 "Failed to enforce backup retention for {}: {}",
                                 file.display(),
                                 e
-                            ))?;
+                            );
+                            self.warn(WarningCategory::Retention, file, warning.clone(), result)?;
+                            if self.strict {
+                                // Leave the file untouched rather than outgrow the policy
+                                let error = AgeError::StrictViolation { warning };
+                                return Err(error.with_context(Stage::Backup, file, op));
+                            }
                         }
                     }
                 }
@@ -2324,24 +4691,114 @@ impl CageManager {
                         e
                     ))?;
                     result.add_failure(file.display().to_string());
-                    return Err(e);
+                    return Err(e.with_context(Stage::Backup, file, op));
                 }
             }
         }
 
-        match encrypt_fn(file, &output_path, options.format) {
+        let output_mode = options.output_mode.or(self.config.ciphertext_mode);
+        let output_existed = output_path.exists();
+        let written = match options.chunk_size {
+            // The container is assembled in private temp files and renamed over the output
+            Some(chunk_size) => {
+                let key = options.encryption_key.as_deref().unwrap_or_default();
+                chunked::lock_chunked(
+                    file,
+                    &output_path,
+                    chunk_size,
+                    key,
+                    options.format,
+                    encrypt_fn,
+                )
+                .map(|stats| {
+                    tracing::debug!(written = stats.written, reused = stats.reused, "chunks")
+                })
+                .stage(Stage::Encrypt, file, op)
+            }
+            None => permissions::prepare_output(&output_path, output_mode)
+                .stage(Stage::Prepare, file, op)
+                .and_then(|_| {
+                    encrypt_fn(file, &output_path, options.format).stage(Stage::Encrypt, file, op)
+                }),
+        };
+        let encrypted = written
+            .and_then(|_| {
+                permissions::enforce_mode(&output_path, output_mode)
+                    .and_then(|_| {
+                        self.preserve_owner(options.preserve_ownership, file, &output_path)
+                    })
+                    .stage(Stage::Finalize, file, op)
+            })
+            .and_then(|_| match &options.verify_decryptable {
+                Some(identity) => self
+                    .verify_round_trip(file, &output_path, identity)
+                    .inspect_err(|_| {
+                        // Never leave behind ciphertext nobody can open
+                        let _ = std::fs::remove_file(&output_path);
+                    })
+                    .stage(Stage::Verify, file, op),
+                None => Ok(()),
+            });
+
+        match encrypted {
             Ok(_) => {
                 result.add_success(file.display().to_string());
+                result.add_file_bytes(
+                    file.display().to_string(),
+                    file_size(file),
+                    file_size(&output_path),
+                );
+                result.record_file_duration(file.display().to_string(), file_start.elapsed());
+
+                if self.config.checksum_db || self.capture_digests {
+                    match ChecksumEntry::capture(file, &output_path) {
+                        Ok(entry) => {
+                            result
+                                .checksums
+                                .push((file.to_path_buf(), output_path.clone(), entry))
+                        }
+                        Err(e) => self.warn(
+                            WarningCategory::Metadata,
+                            file,
+                            format!(
+                                "Failed to hash {} for the checksum database: {}",
+                                file.display(),
+                                e
+                            ),
+                            result,
+                        )?,
+                    }
+                }
+
+                if let Some(markers) = self.markers.as_ref().filter(|_| options.skip_existing) {
+                    if let Err(e) = markers.record(file, &output_path, &marker_key(options)) {
+                        let warning =
+                            format!("Failed to record lock marker for {}: {}", file.display(), e);
+                        self.warn(WarningCategory::Metadata, file, warning, result)?;
+                    }
+                }
+
+                if let Some(ref meta) = options.meta {
+                    if let Err(e) = meta.write(&output_path) {
+                        let warning = format!(
+                            "Failed to write metadata sidecar for {}: {}",
+                            output_path.display(),
+                            e
+                        );
+                        self.warn(WarningCategory::Metadata, file, warning, result)?;
+                    }
+                }
 
                 if let Some(backup) = backup_info {
                     let backup_manager = self.build_backup_manager(options);
                     if backup_manager.cleanup_on_success {
                         if let Err(e) = backup_manager.cleanup_backup(&backup) {
-                            self.audit_logger.log_warning(&format!(
+                            let warning = format!(
                                 "Failed to cleanup backup {}: {}",
                                 backup.backup_path.display(),
                                 e
-                            ))?;
+                            );
+                            self.warn(WarningCategory::Cleanup, file, warning, result)?;
                         } else {
                             self.audit_logger.log_info(&format!(
                                 "Cleaned up backup: {}",
@@ -2355,6 +4812,7 @@ impl CageManager {
             }
             Err(e) => {
                 result.add_failure(file.display().to_string());
+                self.remove_orphan_output(&output_path, output_existed)?;
 
                 if let Some(backup) = backup_info {
                     let backup_manager = self.build_backup_manager(options);
@@ -2377,6 +4835,55 @@ impl CageManager {
         }
     }
 
+    /// Give `output` the owner of `source` when requested or configured
+    fn preserve_owner(&self, requested: bool, source: &Path, output: &Path) -> AgeResult<()> {
+        if requested || self.config.preserve_ownership {
+            permissions::copy_ownership(source, output)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sidecar description for `request` when it or the config asks for `.meta` files
+    fn sidecar_meta(&self, request: &LockRequest) -> Option<CipherMeta> {
+        if !(request.write_meta || self.config.meta_sidecar) {
+            return None;
+        }
+        if request.all_recipients().is_empty() {
+            return Some(CipherMeta::passphrase());
+        }
+        Some(CipherMeta::for_recipients(
+            request.recipients.as_deref().unwrap_or_default(),
+            request.multi_recipient_config.as_ref(),
+        ))
+    }
+
+    /// Remove the output of a failed lock so it is never mistaken for ciphertext.
+    ///
+    /// Outputs this attempt created (the empty file pre-created for its mode, a killed
+    /// age's half-written file) always go; a pre-existing output only when it no longer
+    /// passes the structural checks in `forge::orphans`.
+    fn remove_orphan_output(&self, output: &Path, existed: bool) -> AgeResult<()> {
+        if !output.exists() {
+            return Ok(());
+        }
+        let orphan = !existed || !matches!(orphans::inspect(output), Ok(None));
+        if orphan {
+            match std::fs::remove_file(output) {
+                Ok(()) => self.audit_logger.log_warning(&format!(
+                    "Removed partial output of failed lock: {}",
+                    output.display()
+                ))?,
+                Err(e) => self.audit_logger.log_error(&format!(
+                    "Failed to remove partial output {}: {}",
+                    output.display(),
+                    e
+                ))?,
+            }
+        }
+        Ok(())
+    }
+
     /// Lock a single file with passphrase credentials
     fn lock_single_file(
         &self,
@@ -2402,19 +4909,193 @@ impl CageManager {
     where
         F: FnMut(&Path, &Path, OutputFormat) -> AgeResult<()>,
     {
-        let files =
-            self.collect_files_with_pattern(repository, options.pattern_filter.as_deref())?;
+        let (files, special) = self
+            .repository()
+            .with_mtime_window(options.modified)
+            .collect_files_with_skips(
+                repository,
+                options.pattern_filter.as_deref(),
+                FileSelection::All,
+            )?;
+        self.record_special_files(&special, result)?;
+        let (files, hardlinks) = group_hardlinks(files);
+
+        for (index, file) in files.iter().enumerate() {
+            if interrupt::interrupted().is_some() {
+                return self.record_interrupted("lock", &files[index..], result);
+            }
+            if self.config.is_encrypted_file(file) {
+                result.add_skipped(file.display().to_string(), "already encrypted");
+                continue;
+            }
+            if let Err(e) = self.lock_single_file_internal(file, options, result, encrypt_fn) {
+                self.audit_logger.log_error(&e.to_string())?;
+                tracing::error!("Failed to lock: {}", e);
+            }
+        }
+
+        if interrupt::interrupted().is_none() {
+            for group in &hardlinks {
+                self.lock_hardlinks(group, result)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Report special files passed over by a traversal as skipped
+    fn record_special_files(
+        &self,
+        special: &SkippedEntries,
+        result: &mut OperationResult,
+    ) -> AgeResult<()> {
+        for (path, kind) in special {
+            if let Some(reason) = kind.skip_reason() {
+                self.record_uncovered(path, reason, result)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a path the run passes over; under `--strict` the gap in coverage fails it
+    fn record_uncovered(
+        &self,
+        path: &Path,
+        reason: &str,
+        result: &mut OperationResult,
+    ) -> AgeResult<()> {
+        if self.strict {
+            let warning = format!("Skipped {}: {}", path.display(), reason);
+            return self.fail_strict(path, warning, result);
+        }
+        result.add_skipped(path.display().to_string(), reason);
+        Ok(())
+    }
+
+    /// Record a non-fatal problem with `path` on the result and in the audit log.
+    ///
+    /// Under `--strict`, categories that leave work undone fail the path instead.
+    fn warn(
+        &self,
+        category: WarningCategory,
+        path: &Path,
+        warning: String,
+        result: &mut OperationResult,
+    ) -> AgeResult<()> {
+        if self.strict && category.fails_under_strict() {
+            return self.fail_strict(path, warning, result);
+        }
+        self.audit_logger.log_warning(&warning)?;
+        result.add_warning(category, path.display().to_string(), warning);
+        Ok(())
+    }
+
+    /// Fail `path` for a warning that `--strict` does not tolerate
+    fn fail_strict(
+        &self,
+        path: &Path,
+        warning: String,
+        result: &mut OperationResult,
+    ) -> AgeResult<()> {
+        result.add_failure(path.display().to_string());
+        let error = AgeError::StrictViolation { warning };
+        tracing::error!("{}", error);
+        self.audit_logger.log_error(&error.to_string())
+    }
+
+    /// Report files not reached before an interrupt as failed, so the failure list resumes them
+    fn record_interrupted(
+        &self,
+        operation: &str,
+        pending: &[PathBuf],
+        result: &mut OperationResult,
+    ) -> AgeResult<()> {
+        self.audit_logger.log_warning(&format!(
+            "{} interrupted; {} files left for a resumed run",
+            operation,
+            pending.len()
+        ))?;
+        for file in pending {
+            result.add_failure(file.display().to_string());
+        }
+        Ok(())
+    }
+
+    /// Give the extra links of an already locked file their output per `hardlink_policy`
+    fn lock_hardlinks(&self, group: &HardlinkGroup, result: &mut OperationResult) -> AgeResult<()> {
+        let primary_output = self.encrypted_output_path(&group.primary);
+        let primary = group.primary.display();
+        for link in &group.links {
+            let link_name = link.display().to_string();
+            if self.config.is_encrypted_file(link) {
+                result.add_skipped(link_name, "already encrypted");
+                continue;
+            }
+            if !primary_output.exists() {
+                result.add_skipped(link_name, format!("hardlink of {} (not locked)", primary));
+                continue;
+            }
+            match self.config.hardlink_policy {
+                HardlinkPolicy::Report => {
+                    let warning = format!(
+                        "{} is a hardlink of {}; not encrypted separately",
+                        link.display(),
+                        primary
+                    );
+                    self.warn(WarningCategory::Hardlink, link, warning, result)?;
+                    result.add_skipped(link_name, format!("hardlink of {}", primary));
+                }
+                HardlinkPolicy::Relink => {
+                    // Replace a stale output the same way a fresh encryption would
+                    let output = self.encrypted_output_path(link);
+                    if output.exists() {
+                        self.discard_file(&output)?;
+                    }
+                    match std::fs::hard_link(&primary_output, &output) {
+                        Ok(()) => {
+                            self.audit_logger.log_info(&format!(
+                                "Relinked {} -> {}",
+                                output.display(),
+                                primary_output.display()
+                            ))?;
+                            result.add_success(link_name);
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to relink {}: {}", output.display(), e);
+                            result.add_failure(link_name);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 
-        for file in files {
-            if let Err(e) = self.lock_single_file_internal(&file, options, result, encrypt_fn) {
-                eprintln!(
-                    "{}",
-                    fmt_error(&format!("Failed to lock {}: {}", file.display(), e))
-                );
+    /// Remove `file`, moving it into the quarantine directory when one is configured
+    fn discard_file(&self, file: &Path) -> AgeResult<()> {
+        match &self.quarantine {
+            Some(quarantine) => {
+                let target = quarantine.remove(file)?;
+                self.audit_logger.log_info(&format!(
+                    "Quarantined {} -> {}",
+                    file.display(),
+                    target.display()
+                ))
+            }
+            None => {
+                std::fs::remove_file(file)
+                    .map_err(|e| AgeError::file_error("remove", file.to_path_buf(), e))?;
+                tracing::info!("Deleted {}", file.display());
+                Ok(())
             }
         }
+    }
 
-        Ok(())
+    /// Encrypted output path for `file` (configured extension appended)
+    fn encrypted_output_path(&self, file: &Path) -> PathBuf {
+        let mut path = file.as_os_str().to_os_string();
+        path.push(self.config.extension_with_dot());
+        PathBuf::from(path)
     }
 
     /// Lock repository (directory) with passphrase credentials
@@ -2442,6 +5123,20 @@ impl CageManager {
     where
         F: FnMut(&Path, &Path) -> AgeResult<()>,
     {
+        let _span = tracing::debug_span!("file", path = %file.display()).entered();
+        self.throttle_file(file);
+        let file_start = Instant::now();
+        let op = self.operation_id.as_str();
+
+        if let Some(wrap) = CompressionWrap::detect_file(file).stage(Stage::Prepare, file, op)? {
+            result.add_failure(file.display().to_string());
+            return Err(AgeError::InvalidOperation {
+                operation: "unlock".to_string(),
+                reason: wrap.diagnostic(file),
+            }
+            .with_context(Stage::Prepare, file, op));
+        }
+
         // Determine output path by stripping only the configured extension suffix
         let output_path = {
             let file_name_os = file.file_name().ok_or_else(|| {
@@ -2457,13 +5152,7 @@ impl CageManager {
                 Some(name) => name,
                 None => {
                     result.add_failure(file.display().to_string());
-                    eprintln!(
-                        "{}",
-                        fmt_warning(&format!(
-                            "Skipping file with non-UTF8 filename: {}",
-                            file.display()
-                        ))
-                    );
+                    tracing::warn!("Skipping file with non-UTF8 filename: {}", file.display());
                     return Err(AgeError::InvalidOperation {
                         operation: "unlock".to_string(),
                         reason: format!("Non-UTF8 filename not supported: {}", file.display()),
@@ -2474,13 +5163,10 @@ impl CageManager {
             let suffix = self.config.extension_with_dot();
             if !file_name.ends_with(&suffix) {
                 result.add_failure(file.display().to_string());
-                eprintln!(
-                    "{}",
-                    fmt_warning(&format!(
-                        "Skipping file without {} extension: {}",
-                        suffix,
-                        file.display()
-                    ))
+                tracing::warn!(
+                    "Skipping file without {} extension: {}",
+                    suffix,
+                    file.display()
                 );
                 return Err(AgeError::InvalidOperation {
                     operation: "unlock".to_string(),
@@ -2507,28 +5193,23 @@ impl CageManager {
                             .unwrap_or_else(|| "File failed integrity verification".to_string());
 
                         if options.selective {
-                            eprintln!(
-                                "{}",
-                                fmt_warning(&format!(
-                                    "Skipping {} (selective mode): {}",
-                                    file.display(),
-                                    error_msg
-                                ))
+                            tracing::warn!(
+                                "Skipping {} (selective mode): {}",
+                                file.display(),
+                                error_msg
                             );
                             return Ok(());
                         } else {
-                            eprintln!(
-                                "{}",
-                                fmt_warning(&format!(
-                                    "Skipping file that failed verification: {}: {}",
-                                    file.display(),
-                                    error_msg
-                                ))
+                            tracing::warn!(
+                                "Skipping file that failed verification: {}: {}",
+                                file.display(),
+                                error_msg
                             );
                             return Err(AgeError::InvalidOperation {
                                 operation: "unlock".to_string(),
                                 reason: format!("File failed verification: {}", error_msg),
-                            });
+                            }
+                            .with_context(Stage::Verify, file, op));
                         }
                     }
                 }
@@ -2536,58 +5217,99 @@ impl CageManager {
                     result.add_failure(file.display().to_string());
 
                     if options.selective {
-                        eprintln!(
-                            "{}",
-                            fmt_warning(&format!(
-                                "Skipping {} (selective mode): verification failed: {}",
-                                file.display(),
-                                e
-                            ))
+                        tracing::warn!(
+                            "Skipping {} (selective mode): verification failed: {}",
+                            file.display(),
+                            e
                         );
                         return Ok(());
                     } else {
-                        eprintln!(
-                            "{}",
-                            fmt_warning(&format!(
-                                "Skipping file that failed verification: {}: {}",
-                                file.display(),
-                                e
-                            ))
+                        tracing::warn!(
+                            "Skipping file that failed verification: {}: {}",
+                            file.display(),
+                            e
                         );
                         return Err(AgeError::InvalidOperation {
                             operation: "unlock".to_string(),
                             reason: format!("File failed verification: {}", e),
-                        });
+                        }
+                        .with_context(Stage::Verify, file, op));
                     }
                 }
             }
         }
 
-        match decrypt_fn(file, &output_path) {
+        let encrypted_size = file_size(file);
+        let output_mode = options.output_mode.or(self.config.plaintext_mode);
+        let round_trip = options
+            .verify_decryptable
+            .as_ref()
+            .filter(|_| !options.preserve_encrypted);
+        let decrypted = permissions::prepare_output(&output_path, output_mode)
+            .stage(Stage::Prepare, file, op)
+            .and_then(|_| {
+                let decrypted =
+                    decrypt_maybe_chunked(file, &output_path, self.memory_budget(), decrypt_fn);
+                decrypted.stage(Stage::Decrypt, file, op)
+            })
+            .and_then(|_| {
+                permissions::enforce_mode(&output_path, output_mode)
+                    .and_then(|_| {
+                        self.preserve_owner(options.preserve_ownership, file, &output_path)
+                    })
+                    .stage(Stage::Finalize, file, op)
+            })
+            .and_then(|_| match round_trip {
+                Some(identity) => self
+                    .verify_round_trip(&output_path, file, identity)
+                    .inspect_err(|_| {
+                        tracing::warn!("Keeping {}: round-trip check failed", file.display());
+                    })
+                    .stage(Stage::Verify, file, op),
+                None => Ok(()),
+            });
+
+        match decrypted {
             Ok(_) => {
                 result.add_success(file.display().to_string());
+                result.add_file_bytes(
+                    file.display().to_string(),
+                    encrypted_size,
+                    file_size(&output_path),
+                );
+                result.record_file_duration(file.display().to_string(), file_start.elapsed());
 
                 if !options.preserve_encrypted {
-                    if let Err(e) = std::fs::remove_file(file) {
-                        eprintln!(
-                            "{}",
-                            fmt_warning(&format!(
-                                "Failed to delete encrypted file {}: {}",
-                                file.display(),
-                                e
-                            ))
-                        );
-                    } else {
-                        eprintln!("{}", fmt_deleted(&file.display().to_string()));
+                    if let Err(e) = self.discard_file(file) {
+                        self.warn(
+                            WarningCategory::Cleanup,
+                            file,
+                            format!("Failed to remove encrypted file {}: {}", file.display(), e),
+                            result,
+                        )?;
                     }
                 } else {
-                    eprintln!("{}", fmt_preserved(&file.display().to_string()));
+                    tracing::info!("Preserved encrypted file: {}", file.display());
+                }
+
+                if !file.exists() {
+                    // The sidecar describes ciphertext that is gone now
+                    if let Err(e) = meta::remove_sidecar(file) {
+                        let e = e.with_context(Stage::Cleanup, file, op);
+                        self.warn(WarningCategory::Metadata, file, e.to_string(), result)?;
+                    }
                 }
 
                 Ok(())
             }
             Err(e) => {
                 result.add_failure(file.display().to_string());
+                if interrupt::interrupted().is_some()
+                    || (output_mode.is_some() && file_size(&output_path) == 0)
+                {
+                    // Ciphertext is intact; drop the partial or pre-created plaintext
+                    let _ = std::fs::remove_file(&output_path);
+                }
                 Err(e)
             }
         }
@@ -2617,15 +5339,23 @@ impl CageManager {
     where
         F: FnMut(&Path, &Path) -> AgeResult<()>,
     {
-        let files = self
-            .collect_encrypted_files_with_pattern(repository, options.pattern_filter.as_deref())?;
-
-        for file in files {
-            if let Err(e) = self.unlock_single_file_internal(&file, options, result, decrypt_fn) {
-                eprintln!(
-                    "{}",
-                    fmt_error(&format!("Failed to unlock {}: {}", file.display(), e))
-                );
+        let (files, special) = self
+            .repository()
+            .with_mtime_window(options.modified)
+            .collect_files_with_skips(
+                repository,
+                options.pattern_filter.as_deref(),
+                FileSelection::EncryptedOnly,
+            )?;
+        self.record_special_files(&special, result)?;
+
+        for (index, file) in files.iter().enumerate() {
+            if interrupt::interrupted().is_some() {
+                return self.record_interrupted("unlock", &files[index..], result);
+            }
+            if let Err(e) = self.unlock_single_file_internal(file, options, result, decrypt_fn) {
+                self.audit_logger.log_error(&e.to_string())?;
+                tracing::error!("Failed to unlock: {}", e);
             }
         }
 
@@ -2662,24 +5392,9 @@ impl CageManager {
 
     /// Get status for repository (directory)
     fn get_repository_status(&self, repository: &Path) -> AgeResult<RepositoryStatus> {
-        let mut status = RepositoryStatus::new();
-
-        for entry in std::fs::read_dir(repository)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_file() {
-                status.total_files += 1;
-
-                if self.config.is_encrypted_file(&path) {
-                    status.encrypted_files += 1;
-                } else {
-                    status.unencrypted_files += 1;
-                }
-            }
-        }
-
-        Ok(status)
+        self.repository()
+            .with_traversal(ShallowTraversal)
+            .status(repository)
     }
 
     /// Verify integrity of a single file
@@ -2701,6 +5416,17 @@ impl CageManager {
             ));
         }
 
+        if let Some(wrap) = CompressionWrap::detect_file(file)? {
+            return Ok(FileVerificationStatus {
+                file_path: file.to_path_buf(),
+                is_encrypted: false,
+                format_valid: false,
+                header_valid: false,
+                size_check: true,
+                error_message: Some(wrap.diagnostic(file)),
+            });
+        }
+
         // Check if file appears to be encrypted
         if !self.is_encrypted_file(file)? {
             return Ok(FileVerificationStatus {
@@ -2713,26 +5439,33 @@ impl CageManager {
             });
         }
 
-        // Read file content for verification
-        let content =
-            std::fs::read(file).map_err(|e| AgeError::file_error("read", file.to_path_buf(), e))?;
+        // Read the head (and for armor the tail) only, so multi-GB files are not buffered
+        let len = std::fs::metadata(file)
+            .map_err(|e| AgeError::file_error("read", file.to_path_buf(), e))?
+            .len();
+        let mut content = Vec::new();
+        std::fs::File::open(file)
+            .and_then(|input| input.take(VERIFY_HEAD_BYTES).read_to_end(&mut content))
+            .map_err(|e| AgeError::file_error("read", file.to_path_buf(), e))?;
 
         let mut status = FileVerificationStatus {
             file_path: file.to_path_buf(),
             is_encrypted: true,
             format_valid: false,
             header_valid: false,
-            size_check: content.len() > 0,
+            size_check: len > 0,
             error_message: None,
         };
 
         // Verify Age header format
-        if content.starts_with(b"age-encryption.org/v1") {
+        if let Some(format) = header::HeaderFormat::detect(&content) {
             status.format_valid = true;
-            status.header_valid = self.verify_age_binary_header(&content)?;
-        } else if content.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----") {
+            status.header_valid = self.verify_age_header(file, format, &content)?;
+        } else if content.starts_with(chunked::MAGIC) {
+            // Every chunk is its own age file; the manifest must parse and cover the data
             status.format_valid = true;
-            status.header_valid = self.verify_age_ascii_header(&content)?;
+            status.header_valid =
+                chunked::read_manifest(file).is_ok_and(|manifest| manifest.is_some());
         } else {
             status.error_message = Some("Invalid Age format header".to_string());
         }
@@ -2740,41 +5473,21 @@ impl CageManager {
         Ok(status)
     }
 
-    /// Verify Age binary format header
-    fn verify_age_binary_header(&self, content: &[u8]) -> AgeResult<bool> {
-        // Age binary format starts with "age-encryption.org/v1" followed by newline
-        if content.len() < 22 {
-            return Ok(false);
-        }
-
-        // Check for proper header structure
-        let header_end = content.iter().position(|&b| b == b'\n');
-        if let Some(pos) = header_end {
-            if pos >= 21 && pos < 100 {
-                // Reasonable header length
-                return Ok(true);
+    /// Verify the age header: version line, well-formed stanzas and a MAC line
+    fn verify_age_header(
+        &self,
+        file: &Path,
+        format: header::HeaderFormat,
+        head: &[u8],
+    ) -> AgeResult<bool> {
+        if format == header::HeaderFormat::Armored {
+            // Exact BEGIN line and an END line at the tail, checked before decoding
+            let first_line = head.split(|&b| b == b'\n').next().unwrap_or_default();
+            if first_line.trim_ascii_end() != armor::ARMOR_BEGIN || !armor::is_terminated(file)? {
+                return Ok(false);
             }
         }
-
-        Ok(false)
-    }
-
-    /// Verify Age ASCII armor format header
-    fn verify_age_ascii_header(&self, content: &[u8]) -> AgeResult<bool> {
-        let content_str = String::from_utf8_lossy(content);
-        let lines: Vec<&str> = content_str.lines().collect();
-
-        if lines.is_empty() {
-            return Ok(false);
-        }
-
-        // Check for proper ASCII armor structure
-        let has_begin = lines[0] == "-----BEGIN AGE ENCRYPTED FILE-----";
-        let has_end = lines
-            .iter()
-            .any(|line| *line == "-----END AGE ENCRYPTED FILE-----");
-
-        Ok(has_begin && has_end)
+        Ok(header::AgeHeader::read_file(file).is_ok())
     }
 
     /// Verify integrity of repository
@@ -2784,11 +5497,7 @@ impl CageManager {
         verified: &mut Vec<String>,
         failed: &mut Vec<String>,
     ) -> AgeResult<()> {
-        for entry in std::fs::read_dir(repository)? {
-            let entry =
-                entry.map_err(|e| AgeError::file_error("read_dir", repository.to_path_buf(), e))?;
-            let path = entry.path();
-
+        for path in self.list_dir(repository)? {
             if path.is_file() {
                 // Check if file appears to be encrypted (any format)
                 if self.is_encrypted_file(&path)? {
@@ -2815,117 +5524,19 @@ impl CageManager {
         Ok(())
     }
 
-    /// Collect files matching pattern
-    /// Create a glob matcher from pattern string
-    fn create_glob_matcher(&self, pattern: &str) -> AgeResult<GlobMatcher> {
-        let glob = Glob::new(pattern).map_err(|e| AgeError::InvalidOperation {
-            operation: "pattern_matching".to_string(),
-            reason: format!("Invalid glob pattern '{}': {}", pattern, e),
-        })?;
-        Ok(glob.compile_matcher())
-    }
-
-    /// Recursively traverse directory tree, collecting files
-    fn traverse_directory_recursive(
-        &self,
-        directory: &Path,
-        files: &mut Vec<PathBuf>,
-        visited: &mut HashSet<PathBuf>,
-        glob_matcher: &Option<GlobMatcher>,
-        encrypted_only: bool,
-    ) -> AgeResult<()> {
-        // Canonicalize to detect symlink loops
-        let canonical = directory
-            .canonicalize()
-            .unwrap_or_else(|_| directory.to_path_buf());
-
-        // Prevent symlink loops
-        if visited.contains(&canonical) {
-            return Ok(());
-        }
-        visited.insert(canonical);
-
-        // Read directory entries
-        let entries = match std::fs::read_dir(directory) {
-            Ok(entries) => entries,
-            Err(e) => {
-                eprintln!(
-                    "{}",
-                    fmt_warning(&format!(
-                        "Skipping directory {}: {}",
-                        directory.display(),
-                        e
-                    ))
-                );
-                return Ok(());
-            }
-        };
-
-        for entry in entries {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(e) => {
-                    eprintln!("{}", fmt_warning(&format!("Skipping entry: {}", e)));
-                    continue;
-                }
-            };
-
-            let path = entry.path();
-
-            if path.is_file() {
-                // Check if we only want encrypted files
-                if encrypted_only && !self.config.is_encrypted_file(&path) {
-                    continue;
-                }
-
-                // Apply glob pattern filter if specified
-                if let Some(ref matcher) = glob_matcher {
-                    if let Some(filename) = path.file_name().and_then(|s| s.to_str()) {
-                        if !matcher.is_match(filename) {
-                            continue;
-                        }
-                    } else {
-                        continue;
-                    }
-                }
-
-                files.push(path);
-            } else if path.is_dir() {
-                // Recurse into subdirectory
-                self.traverse_directory_recursive(
-                    &path,
-                    files,
-                    visited,
-                    glob_matcher,
-                    encrypted_only,
-                )?;
-            }
-        }
-
-        Ok(())
+    /// Repository manager bound to the current config (recursive traversal)
+    fn repository(&self) -> RepositoryManager {
+        RepositoryManager::new(self.config.clone())
     }
 
+    /// Collect files matching pattern
     fn collect_files_with_pattern(
         &self,
         directory: &Path,
         pattern: Option<&str>,
     ) -> AgeResult<Vec<PathBuf>> {
-        let mut files = Vec::new();
-        let mut visited = HashSet::new();
-
-        // Compile glob matcher once if pattern provided
-        let glob_matcher = pattern.map(|p| self.create_glob_matcher(p)).transpose()?;
-
-        // Use recursive traversal
-        self.traverse_directory_recursive(
-            directory,
-            &mut files,
-            &mut visited,
-            &glob_matcher,
-            false,
-        )?;
-
-        Ok(files)
+        self.repository()
+            .collect_files(directory, pattern, FileSelection::All)
     }
 
     fn collect_directory_files_shallow(
@@ -2933,31 +5544,9 @@ impl CageManager {
         directory: &Path,
         pattern: Option<&str>,
     ) -> AgeResult<Vec<PathBuf>> {
-        let matcher = pattern.map(|p| self.create_glob_matcher(p)).transpose()?;
-        let entries = std::fs::read_dir(directory)
-            .map_err(|e| AgeError::file_error("read_dir", directory.to_path_buf(), e))?;
-
-        let mut files = Vec::new();
-        for entry in entries {
-            let entry = entry
-                .map_err(|e| AgeError::file_error("read_entry", directory.to_path_buf(), e))?;
-            let path = entry.path();
-
-            if path.is_file() {
-                if let Some(ref matcher) = matcher {
-                    if let Some(filename) = path.file_name().and_then(|s| s.to_str()) {
-                        if !matcher.is_match(filename) {
-                            continue;
-                        }
-                    } else {
-                        continue;
-                    }
-                }
-                files.push(path);
-            }
-        }
-
-        Ok(files)
+        self.repository()
+            .with_traversal(ShallowTraversal)
+            .collect_files(directory, pattern, FileSelection::All)
     }
 
     fn deep_verify_file(
@@ -2984,28 +5573,38 @@ impl CageManager {
         Ok(())
     }
 
+    fn stream_verify_file(
+        &self,
+        adapter: &crate::adp::v2::ShellAdapterV2,
+        file: &Path,
+        identity: &Identity,
+    ) -> AgeResult<StreamVerifyDigest> {
+        let mut input = std::fs::File::open(file)
+            .map_err(|e| AgeError::file_error("verify", file.to_path_buf(), e))?;
+        let mut sink = HashingSink::new();
+
+        adapter.decrypt_stream_no_spill(&mut input, &mut sink, identity)?;
+        Ok(sink.finish(file.to_path_buf()))
+    }
+
     /// Collect encrypted files matching pattern
     fn collect_encrypted_files_with_pattern(
         &self,
         directory: &Path,
         pattern: Option<&str>,
     ) -> AgeResult<Vec<PathBuf>> {
-        let mut files = Vec::new();
-        let mut visited = HashSet::new();
-
-        // Compile glob matcher once if pattern provided
-        let glob_matcher = pattern.map(|p| self.create_glob_matcher(p)).transpose()?;
-
-        // Use recursive traversal (encrypted_only = true)
-        self.traverse_directory_recursive(
-            directory,
-            &mut files,
-            &mut visited,
-            &glob_matcher,
-            true,
-        )?;
+        self.repository()
+            .collect_files(directory, pattern, FileSelection::EncryptedOnly)
+    }
 
-        Ok(files)
+    /// Start a new operation id, e.g. `lock-4242-3` (kind, process id, sequence)
+    fn begin_operation(&mut self, kind: &str) {
+        self.operation_id = format!(
+            "{}-{}-{}",
+            kind,
+            std::process::id(),
+            self.operation_history.len() + 1
+        );
     }
 
     /// Record operation for audit and recovery purposes
@@ -3163,53 +5762,240 @@ mod tests {
             assert!(!result.is_valid());
             assert!(result.error_message.is_some());
 
-            // Test verification of file with Age binary header
+            // Test verification of a real age binary file
+            let recipient = age::x25519::Identity::generate().to_public();
+            let encryptor =
+                age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))
+                    .unwrap();
+            let mut binary = Vec::new();
+            let mut writer = encryptor.wrap_output(&mut binary).unwrap();
+            writer.write_all(b"test encrypted content").unwrap();
+            writer.finish().unwrap();
+
             let temp_age_file = tempfile::NamedTempFile::new().unwrap();
+            std::fs::write(temp_age_file.path(), &binary).unwrap();
+
+            let result = crud_manager
+                .verify_file_integrity(temp_age_file.path())
+                .unwrap();
+            assert!(result.is_encrypted);
+            assert!(result.format_valid);
+            assert!(result.header_valid);
+
+            // A version line alone is not a valid header
+            let temp_bogus_file = tempfile::NamedTempFile::new().unwrap();
+            std::fs::write(
+                temp_bogus_file.path(),
+                b"age-encryption.org/v1\ntest encrypted content",
+            )
+            .unwrap();
+
+            let result = crud_manager
+                .verify_file_integrity(temp_bogus_file.path())
+                .unwrap();
+            assert!(result.format_valid);
+            assert!(!result.header_valid);
+
+            // Test verification of the same file in ASCII armor
+            let mut armored = Vec::new();
+            let mut writer = age::armor::ArmoredWriter::wrap_output(
+                &mut armored,
+                age::armor::Format::AsciiArmor,
+            )
+            .unwrap();
+            writer.write_all(&binary).unwrap();
+            writer.finish().unwrap();
+
+            let temp_ascii_file = tempfile::NamedTempFile::new().unwrap();
+            std::fs::write(temp_ascii_file.path(), &armored).unwrap();
+
+            let result = crud_manager
+                .verify_file_integrity(temp_ascii_file.path())
+                .unwrap();
+            assert!(result.is_encrypted);
+            assert!(result.format_valid);
+            assert!(result.header_valid);
+        } else {
+            println!("Skipping file verification test - Age not available");
+        }
+    }
+
+    #[test]
+    fn test_verification_result_creation() {
+        if let Ok(crud_manager) = CageManager::with_defaults() {
+            // Test verification of non-existent path
+            let fake_path = std::path::Path::new("/non/existent/path");
+            let result = crud_manager.verify(fake_path);
+            assert!(result.is_err());
+
+            // Test verification of empty directory
+            let temp_dir = TempDir::new().unwrap();
+            let result = crud_manager.verify(temp_dir.path()).unwrap();
+            assert!(result.verified_files.is_empty());
+            assert!(result.failed_files.is_empty());
+        } else {
+            println!("Skipping verification result test - Age not available");
+        }
+    }
+
+    #[test]
+    fn test_hashing_sink_digest() {
+        let mut sink = HashingSink::new();
+        sink.write_all(b"ab").unwrap();
+        sink.write_all(b"c").unwrap();
+        let digest = sink.finish(PathBuf::from("sample.cage"));
+
+        assert_eq!(digest.plaintext_bytes, 3);
+        assert_eq!(
+            digest.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_stream_verify_requires_deep() {
+        if let Ok(mut crud_manager) = CageManager::with_defaults() {
+            let temp_dir = TempDir::new().unwrap();
+            let request = VerifyRequest::new(temp_dir.path().to_path_buf()).stream_verify(true);
+            assert!(crud_manager.verify_with_request(&request).is_err());
+        } else {
+            println!("Skipping stream verify test - Age not available");
+        }
+    }
+
+    #[test]
+    fn test_emergency_unlock_requires_confirmation() {
+        if let Ok(mut crud_manager) = CageManager::with_defaults() {
+            crud_manager.set_prompter(std::sync::Arc::new(crate::core::DenyPrompter));
+            let temp_dir = TempDir::new().unwrap();
+            let identity = Identity::Passphrase("emergency-passphrase".to_string());
+
+            let result = crud_manager.emergency_unlock(temp_dir.path(), &identity, "yes", None);
+            assert!(result.is_err());
+
+            let result = crud_manager.emergency_unlock(
+                temp_dir.path(),
+                &identity,
+                EMERGENCY_UNLOCK_CONFIRMATION,
+                None,
+            );
+            let result = result.unwrap();
+            assert!(result.affected_files.is_empty());
+            assert!(!result.security_events.is_empty());
+        } else {
+            println!("Skipping emergency unlock test - Age not available");
+        }
+    }
+
+    #[test]
+    fn test_emergency_unlock_uses_the_configured_adapter() {
+        use crate::pty::PtyAutomator;
+
+        let automator = std::sync::Arc::new(crate::pty::FakeAutomator::new());
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(automator.clone()).unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        manager.set_prompter(std::sync::Arc::new(crate::core::DenyPrompter));
+        let repo = TempDir::new().unwrap();
+        let quarantine = TempDir::new().unwrap();
+        std::fs::create_dir(repo.path().join("nested")).unwrap();
+        let plain = repo.path().join("nested/notes.txt");
+        std::fs::write(&plain, "escrowed").unwrap();
+        let locked = repo.path().join("nested/notes.txt.cage");
+        automator
+            .encrypt(
+                &plain,
+                &locked,
+                "emergency-passphrase",
+                OutputFormat::Binary,
+            )
+            .unwrap();
+        std::fs::remove_file(&plain).unwrap();
+
+        let identity = Identity::Passphrase("emergency-passphrase".to_string());
+        let result = manager
+            .emergency_unlock(
+                repo.path(),
+                &identity,
+                EMERGENCY_UNLOCK_CONFIRMATION,
+                Some(quarantine.path()),
+            )
+            .unwrap();
+
+        let recovered = quarantine.path().join("nested/notes.txt");
+        assert_eq!(result.affected_files, vec![recovered.display().to_string()]);
+        assert_eq!(automator.decrypt_calls(), 1);
+        assert_eq!(std::fs::read_to_string(&recovered).unwrap(), "escrowed");
+        assert!(locked.exists());
+        assert!(!plain.exists());
+    }
+
+    #[test]
+    fn test_reset_writes_recovery_bundle() {
+        if let Ok(crud_manager) = CageManager::with_defaults() {
+            let temp_dir = TempDir::new().unwrap();
+            let groups_dir = TempDir::new().unwrap();
+            let groups_file = groups_dir.path().join("groups.json");
+            let mut store = GroupStore::load(&groups_file).unwrap();
+            store
+                .group_mut("stored")
+                .add_recipient("age1stored".to_string());
+            store.save().unwrap();
+            let mut crud_manager = crud_manager.with_group_store(groups_file.clone());
+            crud_manager.config.backup_directory = None;
+            crud_manager.set_prompter(std::sync::Arc::new(crate::core::DenyPrompter));
+            crud_manager.create_recipient_group("ops", None).unwrap();
             std::fs::write(
-                temp_age_file.path(),
-                b"age-encryption.org/v1\ntest encrypted content",
+                temp_dir.path().join("secret.cage"),
+                b"age-encryption.org/v1",
             )
             .unwrap();
 
-            let result = crud_manager
-                .verify_file_integrity(temp_age_file.path())
-                .unwrap();
-            assert!(result.is_encrypted);
-            assert!(result.format_valid);
-            assert!(result.header_valid);
-
-            // Test verification of file with Age ASCII header
-            let temp_ascii_file = tempfile::NamedTempFile::new().unwrap();
-            let ascii_content = b"-----BEGIN AGE ENCRYPTED FILE-----\nencrypted content here\n-----END AGE ENCRYPTED FILE-----";
-            std::fs::write(temp_ascii_file.path(), ascii_content).unwrap();
+            assert!(crud_manager.reset(temp_dir.path(), "nope").is_err());
 
             let result = crud_manager
-                .verify_file_integrity(temp_ascii_file.path())
+                .reset(temp_dir.path(), "CONFIRM_RESET")
                 .unwrap();
-            assert!(result.is_encrypted);
-            assert!(result.format_valid);
-            assert!(result.header_valid);
+            assert_eq!(result.affected_files.len(), 1);
+            assert!(crud_manager.list_recipient_groups().is_empty());
+
+            let snapshots: Vec<_> = std::fs::read_dir(temp_dir.path().join(".cage_reset_backup"))
+                .unwrap()
+                .collect();
+            assert_eq!(snapshots.len(), 1);
+            let snapshot = snapshots[0].as_ref().unwrap().path();
+            assert!(snapshot.join("recovery.json").exists());
+            assert!(snapshot.join("files").join("secret.cage").exists());
+            // The stored groups are cleared too, with a copy in the snapshot
+            assert!(GroupStore::load(&groups_file).unwrap().names().is_empty());
+            let saved = GroupStore::load(&snapshot.join("groups.json")).unwrap();
+            assert_eq!(saved.names(), vec!["stored".to_string()]);
         } else {
-            println!("Skipping file verification test - Age not available");
+            println!("Skipping reset test - Age not available");
         }
     }
 
     #[test]
-    fn test_verification_result_creation() {
-        if let Ok(crud_manager) = CageManager::with_defaults() {
-            // Test verification of non-existent path
-            let fake_path = std::path::Path::new("/non/existent/path");
-            let result = crud_manager.verify(fake_path);
-            assert!(result.is_err());
-
-            // Test verification of empty directory
-            let temp_dir = TempDir::new().unwrap();
-            let result = crud_manager.verify(temp_dir.path()).unwrap();
-            assert!(result.verified_files.is_empty());
-            assert!(result.failed_files.is_empty());
-        } else {
-            println!("Skipping verification result test - Age not available");
-        }
+    fn test_reset_confirmed_by_prompter() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let prompter = std::sync::Arc::new(crate::core::ScriptedPrompter::new([false, true]));
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = manager
+            .with_prompter(prompter.clone())
+            .with_group_store(temp_dir.path().join("groups.json"));
+        manager.config.backup_directory = None;
+
+        assert!(manager.reset(temp_dir.path(), "").is_err());
+        assert!(manager.reset(temp_dir.path(), "").is_ok());
+        let asked = prompter.asked();
+        assert_eq!(asked.len(), 2);
+        assert_eq!(asked[0].phrase.as_deref(), Some("CONFIRM_RESET"));
+        // A matching token never reaches the prompter
+        manager.reset(temp_dir.path(), "CONFIRM_RESET").unwrap();
+        assert_eq!(prompter.asked().len(), 2);
     }
 
     #[test]
@@ -3226,6 +6012,120 @@ mod tests {
         assert!(!custom_manager.cleanup_on_success);
     }
 
+    #[test]
+    fn test_backup_checksum_and_permissions() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("config.env");
+        std::fs::write(&test_file, "KEY=value").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&test_file, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let backup_manager = BackupManager::new();
+        let info = backup_manager.create_backup(&test_file).unwrap();
+        assert_eq!(info.size_bytes, 9);
+        assert!(!info.cross_device);
+        assert!(info.verify().unwrap());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&info.backup_path)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // A tampered backup is refused and the original is left alone
+        std::fs::write(&test_file, "KEY=changed").unwrap();
+        std::fs::write(&info.backup_path, "KEY=evil").unwrap();
+        assert!(!info.verify().unwrap());
+        assert!(backup_manager.restore_backup(&info).is_err());
+        assert_eq!(std::fs::read_to_string(&test_file).unwrap(), "KEY=changed");
+    }
+
+    #[test]
+    fn test_verify_reports_compressed_ciphertext() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("secret.txt.cage.gz");
+        std::fs::write(&path, [0x1f, 0x8b, 0x08, 0x00, 0x00]).unwrap();
+
+        let result = manager.verify(&path).unwrap();
+        assert_eq!(result.failed_files.len(), 1);
+        assert!(result.failed_files[0].contains("gzip-compressed"));
+    }
+
+    #[test]
+    fn test_backup_dir_mirrors_source_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let backup_dir = temp_dir.path().join("backups");
+        let mut sources = Vec::new();
+        for (dir, contents) in [("a", "first"), ("b", "second")] {
+            let path = temp_dir.path().join("repo").join(dir).join("file.txt");
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, contents).unwrap();
+            sources.push(path);
+        }
+
+        let backup_manager = BackupManager::with_backup_dir(backup_dir.clone());
+        let first = backup_manager.create_backup(&sources[0]).unwrap();
+        let second = backup_manager.create_backup(&sources[1]).unwrap();
+
+        assert_ne!(first.backup_path, second.backup_path);
+        assert!(first.backup_path.starts_with(&backup_dir));
+        assert!(first.backup_path.ends_with("repo/a/file.txt.bak"));
+        assert_eq!(
+            std::fs::read_to_string(&first.backup_path).unwrap(),
+            "first"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&second.backup_path).unwrap(),
+            "second"
+        );
+        assert_eq!(
+            backup_manager
+                .collect_existing_backups(&sources[1])
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_lock_options_override_backup_settings() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+
+        let options = LockOptions {
+            backup_retention: Some(RetentionPolicyConfig::KeepLast(1)),
+            backup_extension: Some("orig".to_string()),
+            ..LockOptions::default()
+        };
+        let backup_manager = manager.build_backup_manager(&options);
+        assert_eq!(
+            backup_manager.retention_policy,
+            RetentionPolicy::KeepLast(1)
+        );
+        assert_eq!(backup_manager.backup_extension, ".orig");
+
+        let defaults = manager.build_backup_manager(&LockOptions::default());
+        assert_eq!(
+            defaults.retention_policy,
+            manager.config.backup_retention.to_retention_policy()
+        );
+        assert_eq!(defaults.backup_extension, ".bak");
+    }
+
     #[test]
     fn test_backup_system() {
         let temp_dir = TempDir::new().unwrap();
@@ -3278,6 +6178,744 @@ mod tests {
         assert_eq!(backup_content, b"modified");
     }
 
+    #[test]
+    fn test_adapter_override_restores_default() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("plain.txt");
+        std::fs::write(&file, b"override").unwrap();
+        let default_name = manager.adapter.adapter_name();
+
+        let request = LockRequest::new(file.clone(), Identity::Passphrase("pw".to_string()))
+            .with_adapter("library");
+        assert!(manager.lock_with_request(&request).is_err());
+        assert_eq!(manager.adapter.adapter_name(), default_name);
+        assert!(file.exists());
+    }
+
+    #[test]
+    fn test_decryptability_identity_uses_escrow() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let escrow = "age1escrowrecipient".to_string();
+        let other = Recipient::PublicKey("age1teammate".to_string());
+        let passphrase = Identity::Passphrase("pw".to_string());
+
+        let plain = LockRequest::new(PathBuf::from("f"), passphrase.clone());
+        assert!(matches!(
+            manager.decryptability_identity(&plain),
+            Ok(Some(Identity::Passphrase(_)))
+        ));
+
+        let uncovered = plain.clone().with_recipients(vec![other.clone()]);
+        assert!(manager.decryptability_identity(&uncovered).is_err());
+
+        manager.config.escrow_recipient = Some(escrow.clone());
+        let covered = plain.with_recipients(vec![other, Recipient::PublicKey(escrow)]);
+        assert!(matches!(
+            manager.decryptability_identity(&covered),
+            Ok(None)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sibling_outputs_get_configured_modes() {
+        use std::os::unix::fs::PermissionsExt;
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        manager.config.ciphertext_mode = Some(0o644);
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("notes.txt");
+        std::fs::write(&file, "secret").unwrap();
+        let mode_of = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let write = |path: &Path, data: &[u8]| {
+            std::fs::write(path, data).map_err(|e| AgeError::file_error("write", path.into(), e))
+        };
+
+        let mut result = OperationResult::new();
+        let mut encrypt = |_: &Path, output: &Path, _: OutputFormat| {
+            // The mode is in place before the adapter writes anything
+            assert_eq!(mode_of(output), 0o644);
+            write(output, b"ciphertext")
+        };
+        manager
+            .lock_single_file_internal(&file, &LockOptions::default(), &mut result, &mut encrypt)
+            .unwrap();
+        let locked = manager.encrypted_output_path(&file);
+        assert_eq!(mode_of(&locked), 0o644);
+
+        std::fs::remove_file(&file).unwrap();
+        let options = UnlockOptions {
+            verify_before_unlock: false,
+            preserve_encrypted: true,
+            output_mode: Some(0o600),
+            ..UnlockOptions::default()
+        };
+        let mut decrypt = |_: &Path, output: &Path| write(output, b"secret");
+        manager
+            .unlock_single_file_internal(&locked, &options, &mut result, &mut decrypt)
+            .unwrap();
+        assert_eq!(mode_of(&file), 0o600);
+    }
+
+    #[test]
+    fn test_chunked_lock_round_trips_and_verifies() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("disk.img");
+        std::fs::write(&file, "0123456789").unwrap();
+        let copy = |input: &Path, output: &Path| {
+            std::fs::copy(input, output)
+                .map(|_| ())
+                .map_err(|e| AgeError::file_error("copy", input.into(), e))
+        };
+
+        let options = LockOptions {
+            chunk_size: Some(4),
+            ..LockOptions::default()
+        };
+        let mut result = OperationResult::new();
+        let mut encrypt = |input: &Path, output: &Path, _: OutputFormat| copy(input, output);
+        manager
+            .lock_single_file_internal(&file, &options, &mut result, &mut encrypt)
+            .unwrap();
+        let locked = manager.encrypted_output_path(&file);
+        let manifest = chunked::read_manifest(&locked).unwrap().unwrap();
+        assert_eq!(manifest.chunks.len(), 3);
+        assert!(manager.is_encrypted_file(&locked).unwrap());
+        assert!(manager.verify_file_integrity(&locked).unwrap().is_valid());
+
+        std::fs::remove_file(&file).unwrap();
+        let options = UnlockOptions {
+            verify_before_unlock: false,
+            ..UnlockOptions::default()
+        };
+        let mut decrypt = copy;
+        manager
+            .unlock_single_file_internal(&locked, &options, &mut result, &mut decrypt)
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "0123456789");
+    }
+
+    #[test]
+    fn test_unlock_to_writer_needs_a_plain_ciphertext_file() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let key = temp.path().join("key.txt");
+        std::fs::write(&key, "AGE-SECRET-KEY-1UNUSED\n").unwrap();
+        let identity = Identity::IdentityFile(key);
+        let mut sink = Vec::new();
+        assert!(manager
+            .unlock_to_writer(temp.path(), &identity, &mut sink)
+            .is_err());
+
+        let file = temp.path().join("disk.img");
+        std::fs::write(&file, "0123456789").unwrap();
+        let locked = temp.path().join("disk.img.cage");
+        let mut encrypt = |input: &Path, output: &Path, _: OutputFormat| {
+            std::fs::copy(input, output)
+                .map(|_| ())
+                .map_err(|e| AgeError::file_error("copy", input.into(), e))
+        };
+        chunked::lock_chunked(&file, &locked, 4, "", OutputFormat::Binary, &mut encrypt).unwrap();
+        let refused = manager.unlock_to_writer(&locked, &identity, &mut sink);
+        assert!(matches!(refused, Err(AgeError::InvalidOperation { .. })));
+        assert!(sink.is_empty());
+    }
+
+    #[test]
+    fn test_unlock_to_writer_refuses_passphrases_without_staging() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let locked = temp.path().join("notes.txt.cage");
+        std::fs::write(&locked, b"age-encryption.org/v1\n").unwrap();
+
+        for identity in [
+            Identity::Passphrase("secret".into()),
+            Identity::PromptPassphrase,
+        ] {
+            let mut sink = Vec::new();
+            let refused = manager.unlock_to_writer(&locked, &identity, &mut sink);
+            // Refused before age runs, so no temp file ever holds the plaintext
+            assert!(
+                matches!(&refused, Err(AgeError::InvalidOperation { reason, .. })
+                    if reason.contains("temp file")),
+                "{:?}",
+                refused
+            );
+            assert!(sink.is_empty());
+        }
+        let entries: Vec<_> = std::fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("notes.txt.cage")]);
+    }
+
+    #[test]
+    fn test_explicit_output_path_for_single_file_lock() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("notes.txt");
+        std::fs::write(&file, "secret").unwrap();
+        let target = temp.path().join("out").join("notes.bin");
+        std::fs::create_dir(target.parent().unwrap()).unwrap();
+
+        let options = LockOptions {
+            output_path: Some(target.clone()),
+            ..LockOptions::default()
+        };
+        assert!(check_output_path(&file, &options).is_ok());
+        assert!(check_output_path(temp.path(), &options).is_err());
+        let onto_source = LockOptions {
+            output_path: Some(file.clone()),
+            ..LockOptions::default()
+        };
+        assert!(check_output_path(&file, &onto_source).is_err());
+
+        let mut result = OperationResult::new();
+        let mut encrypt = |_: &Path, output: &Path, _: OutputFormat| {
+            std::fs::write(output, b"ciphertext")
+                .map_err(|e| AgeError::file_error("write", output.into(), e))
+        };
+        manager
+            .lock_single_file_internal(&file, &options, &mut result, &mut encrypt)
+            .unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"ciphertext");
+        assert!(!manager.encrypted_output_path(&file).exists());
+    }
+
+    #[test]
+    fn test_meta_sidecar_follows_ciphertext() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("notes.txt");
+        std::fs::write(&file, "secret").unwrap();
+        let write = |path: &Path, data: &[u8]| {
+            std::fs::write(path, data).map_err(|e| AgeError::file_error("write", path.into(), e))
+        };
+
+        let request = LockRequest::new(file.clone(), Identity::Passphrase("unused".into()))
+            .with_recipients(vec![Recipient::PublicKey("age1alice".to_string())])
+            .write_meta(true);
+        let options = LockOptions {
+            meta: manager.sidecar_meta(&request),
+            ..LockOptions::default()
+        };
+        let mut result = OperationResult::new();
+        let mut encrypt = |_: &Path, output: &Path, _: OutputFormat| write(output, b"ciphertext");
+        manager
+            .lock_single_file_internal(&file, &options, &mut result, &mut encrypt)
+            .unwrap();
+        let locked = manager.encrypted_output_path(&file);
+        let recorded = CipherMeta::load(&locked).unwrap().unwrap();
+        assert_eq!(recorded.recipients[0].recipient, "age1alice");
+
+        // A directory scan does not offer the sidecar for locking
+        let (files, _) = manager
+            .repository()
+            .collect_files_with_skips(temp.path(), None, FileSelection::All)
+            .unwrap();
+        assert!(!files.contains(&meta::meta_path(&locked)));
+
+        std::fs::remove_file(&file).unwrap();
+        let options = UnlockOptions {
+            verify_before_unlock: false,
+            ..UnlockOptions::default()
+        };
+        let mut decrypt = |_: &Path, output: &Path| write(output, b"secret");
+        manager
+            .unlock_single_file_internal(&locked, &options, &mut result, &mut decrypt)
+            .unwrap();
+        assert!(!meta::meta_path(&locked).exists());
+    }
+
+    #[test]
+    fn test_failed_stage_is_attached_to_error() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        manager.begin_operation("lock");
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("notes.txt");
+        std::fs::write(&file, "secret").unwrap();
+
+        let mut result = OperationResult::new();
+        let mut encrypt = |input: &Path, output: &Path, _: OutputFormat| {
+            Err(AgeError::encryption_failed(
+                input.to_path_buf(),
+                output.to_path_buf(),
+                "age exited",
+            ))
+        };
+        let err = manager
+            .lock_single_file_internal(&file, &LockOptions::default(), &mut result, &mut encrypt)
+            .unwrap_err();
+        assert_eq!(err.stage(), Some(Stage::Encrypt));
+        assert!(matches!(
+            err.root_cause(),
+            AgeError::EncryptionFailed { .. }
+        ));
+        let message = err.to_string();
+        assert!(message.starts_with(&format!("[lock-{}-1] encrypt stage", std::process::id())));
+        assert!(message.contains(&file.display().to_string()));
+    }
+
+    /// Custom operation writing a dump file, optionally failing its postcondition
+    struct DumpOperation {
+        output: PathBuf,
+        fail_postcondition: bool,
+    }
+
+    impl Operation for DumpOperation {
+        fn operation_name(&self) -> &'static str {
+            "db_dump"
+        }
+
+        fn validate_preconditions(&self) -> AgeResult<()> {
+            Ok(())
+        }
+
+        fn execute(&self) -> AgeResult<()> {
+            std::fs::write(&self.output, "dump")
+                .map_err(|e| AgeError::file_error("write", self.output.clone(), e))
+        }
+
+        fn validate_postconditions(&self) -> AgeResult<()> {
+            if self.fail_postcondition {
+                return Err(AgeError::InvalidOperation {
+                    operation: "db_dump".to_string(),
+                    reason: "dump not encrypted".to_string(),
+                });
+            }
+            Ok(())
+        }
+
+        fn targets(&self) -> Vec<PathBuf> {
+            vec![self.output.clone()]
+        }
+
+        fn execute_with(&self, result: &mut OperationResult) -> AgeResult<()> {
+            self.execute()?;
+            result.add_success(self.output.display().to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registered_operation_runs_with_rails() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let output = temp.path().join("db.sql");
+        let dump = std::sync::Arc::new(DumpOperation {
+            output: output.clone(),
+            fail_postcondition: false,
+        });
+
+        manager.register_operation(dump.clone()).unwrap();
+        assert!(manager.register_operation(dump).is_err());
+        assert_eq!(manager.registered_operations(), vec!["db_dump"]);
+
+        let result = manager.run_operation("db_dump").unwrap();
+        assert!(result.success);
+        assert_eq!(result.processed_files, vec![output.display().to_string()]);
+        assert!(output.exists());
+        assert!(manager.run_operation("missing").is_err());
+
+        let failing = DumpOperation {
+            output,
+            fail_postcondition: true,
+        };
+        let err = manager.execute_operation(&failing).unwrap_err();
+        assert_eq!(err.stage(), Some(Stage::Verify));
+        assert_eq!(manager.operation_history.len(), 2);
+        assert!(!manager.operation_history[1].success);
+    }
+
+    #[test]
+    fn test_time_boxed_unlock_relocks_unchanged_plaintext() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let temp = TempDir::new().unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default())
+            .unwrap()
+            .with_relock_store(temp.path().join("relock.json"));
+        let file = temp.path().join("notes.txt");
+        let locked = temp.path().join("notes.txt.cage");
+        std::fs::write(&file, "secret").unwrap();
+        manager
+            .lock(&file, "hunter2", LockOptions::default())
+            .unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let request = UnlockRequest::new(locked.clone(), Identity::Passphrase("hunter2".into()))
+            .relock_after(Duration::ZERO);
+        manager.unlock_with_request(&request).unwrap();
+        assert!(file.exists() && locked.exists());
+
+        let sealed = std::fs::read(&locked).unwrap();
+        let result = manager.relock_due(false).unwrap();
+        assert_eq!(result.processed_files.len(), 1);
+        assert!(!file.exists());
+        assert_eq!(std::fs::read(&locked).unwrap(), sealed);
+
+        // Edited passphrase plaintext cannot be resealed and stays recorded
+        let request = request.relock_after(Duration::from_secs(3600));
+        manager.unlock_with_request(&request).unwrap();
+        std::fs::write(&file, "edited").unwrap();
+        let early = manager.relock_due(false).unwrap();
+        assert!(early.processed_files.is_empty());
+        let result = manager.relock_due(true).unwrap();
+        assert_eq!(result.failed_files.len(), 1);
+        assert!(file.exists());
+        let store = RelockStore::load(&temp.path().join("relock.json")).unwrap();
+        assert_eq!(store.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_session_close_encrypts_only_changes() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let config = AgeConfig {
+            checksum_db: true,
+            ..AgeConfig::default()
+        };
+        let mut manager = CageManager::new(Box::new(adapter), config).unwrap();
+        let repo = TempDir::new().unwrap();
+        let scratch = TempDir::new().unwrap();
+        let workspace = scratch.path().join("ws");
+        let identity = Identity::Passphrase("hunter2".to_string());
+        for (name, content) in [("a.txt", "alpha"), ("b.txt", "bravo")] {
+            let file = repo.path().join(name);
+            std::fs::write(&file, content).unwrap();
+            manager
+                .lock(&file, "hunter2", LockOptions::default())
+                .unwrap();
+            std::fs::remove_file(&file).unwrap();
+        }
+        let untouched = std::fs::read(repo.path().join("b.txt.cage")).unwrap();
+
+        let opened = manager
+            .open_session(repo.path(), &identity, Some(&workspace))
+            .unwrap();
+        assert_eq!(opened.processed_files.len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("a.txt")).unwrap(),
+            "alpha"
+        );
+        assert!(manager
+            .open_session(repo.path(), &identity, Some(&workspace))
+            .is_err());
+
+        std::fs::write(workspace.join("a.txt"), "edited").unwrap();
+        std::fs::write(workspace.join("c.txt"), "new").unwrap();
+        let closed = manager.close_session(repo.path(), Some(&identity)).unwrap();
+        assert_eq!(closed.processed_files.len(), 2);
+        assert!(!workspace.exists());
+        assert!(Session::load(repo.path()).unwrap().is_none());
+        assert_eq!(
+            std::fs::read(repo.path().join("b.txt.cage")).unwrap(),
+            untouched
+        );
+
+        let check = scratch.path().join("check.txt");
+        manager
+            .adapter
+            .decrypt(&repo.path().join("a.txt.cage"), &check, "hunter2")
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&check).unwrap(), "edited");
+        assert!(repo.path().join("c.txt.cage").exists());
+
+        // Only re-encrypted files get new checksum records
+        let db = ChecksumDb::load(repo.path()).unwrap();
+        let recorded: Vec<_> = db.entries().keys().map(String::as_str).collect();
+        assert_eq!(recorded, vec!["a.txt.cage", "c.txt.cage"]);
+        assert_eq!(db.entries()["a.txt.cage"].source, "a.txt");
+
+        // A recipient file without a sidecar never becomes a passphrase file
+        let recipient_file = repo.path().join("d.txt.cage");
+        let header = "age-encryption.org/v1\n-> X25519 c2hhcmU\n\n--- ZmFrZQ\n";
+        std::fs::write(&recipient_file, header).unwrap();
+        let refused = manager.seal_session_file(&check, &recipient_file, Some("hunter2"));
+        assert!(matches!(refused, Err(AgeError::InvalidOperation { .. })));
+        assert_eq!(std::fs::read_to_string(&recipient_file).unwrap(), header);
+    }
+
+    #[test]
+    fn test_fast_status_uses_state_cache() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("nested")).unwrap();
+        std::fs::write(temp.path().join("plain.txt"), b"x").unwrap();
+        std::fs::write(temp.path().join("nested/secret.txt.cage"), b"x").unwrap();
+
+        let request = StatusRequest::new(temp.path().to_path_buf()).fast(true);
+        let status = manager.status_with_request(&request).unwrap();
+        assert_eq!((status.total_files, status.encrypted_files), (2, 1));
+        assert!(StateCache::state_path(temp.path()).is_file());
+
+        // The state file itself is never counted
+        let status = manager.status_with_request(&request).unwrap();
+        assert_eq!(status.total_files, 2);
+    }
+
+    #[test]
+    fn test_rewrap_armor_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let binary = temp.path().join("secret.age");
+        let armored = temp.path().join("secret.asc");
+        let mut original = b"age-encryption.org/v1\n-> X25519 stanza\nbody\n--- mac\n".to_vec();
+        original.extend((0..=255u8).cycle().take(4096));
+        std::fs::write(&binary, &original).unwrap();
+
+        rewrap_armor(&binary, &armored, OutputFormat::AsciiArmor).unwrap();
+        let text = std::fs::read_to_string(&armored).unwrap();
+        assert!(text.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+
+        // In-place conversion back to binary restores the exact bytes
+        rewrap_armor(&armored, &armored, OutputFormat::Binary).unwrap();
+        assert_eq!(std::fs::read(&armored).unwrap(), original);
+    }
+
+    #[test]
+    fn test_large_armor_input_refused_before_encrypting() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let config = AgeConfig {
+            armor_oversize: crate::core::ArmorPolicy::Refuse,
+            ..AgeConfig::default()
+        };
+        let mut manager = CageManager::new(Box::new(adapter), config).unwrap();
+        manager.begin_operation("lock");
+        let temp = TempDir::new().unwrap();
+        // Sparse, so the 1 GiB+ input costs no disk
+        let file = temp.path().join("disk.img");
+        std::fs::File::create(&file)
+            .unwrap()
+            .set_len(armor::DEFAULT_ARMOR_THRESHOLD + 1)
+            .unwrap();
+
+        let mut result = OperationResult::new();
+        let mut encrypt = |_: &Path, _: &Path, _: OutputFormat| -> AgeResult<()> {
+            unreachable!("oversized armor must be refused before age runs")
+        };
+        let armored = LockOptions {
+            format: OutputFormat::AsciiArmor,
+            ..LockOptions::default()
+        };
+        let err = manager
+            .lock_single_file_internal(&file, &armored, &mut result, &mut encrypt)
+            .unwrap_err();
+        assert_eq!(err.stage(), Some(Stage::Prepare));
+        assert_eq!(result.failed_files, vec![file.display().to_string()]);
+        assert!(!manager.encrypted_output_path(&file).exists());
+    }
+
+    #[test]
+    fn test_strict_mode_fails_skipped_special_files() {
+        use crate::forge::repository_manager::EntryKind;
+
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let special = vec![(PathBuf::from("/repo/pipe"), EntryKind::Fifo)];
+
+        let mut lenient = OperationResult::new();
+        manager
+            .record_special_files(&special, &mut lenient)
+            .unwrap();
+        assert_eq!(lenient.skipped_files.len(), 1);
+        assert!(lenient.failed_files.is_empty());
+
+        manager.strict = true;
+        let mut strict = OperationResult::new();
+        manager.record_special_files(&special, &mut strict).unwrap();
+        assert!(strict.skipped_files.is_empty());
+        assert_eq!(strict.failed_files, vec!["/repo/pipe".to_string()]);
+        strict.finalize(Instant::now());
+        assert!(!strict.success);
+    }
+
+    #[test]
+    fn test_warnings_are_collected_with_category() {
+        use crate::forge::WarningCategory::{Cleanup, Metadata};
+
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let mut manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let file = Path::new("/repo/a.txt");
+
+        let mut result = OperationResult::new();
+        manager
+            .warn(Cleanup, file, "cleanup".to_string(), &mut result)
+            .unwrap();
+        assert_eq!(result.warnings[0].category, Cleanup);
+        assert_eq!(result.warnings[0].path, "/repo/a.txt");
+        assert!(result.failed_files.is_empty());
+
+        // Strict fails only the categories that leave work undone
+        manager.strict = true;
+        let mut strict = OperationResult::new();
+        manager
+            .warn(Metadata, file, "sidecar".to_string(), &mut strict)
+            .unwrap();
+        manager
+            .warn(Cleanup, file, "cleanup".to_string(), &mut strict)
+            .unwrap();
+        assert_eq!(strict.warnings.len(), 1);
+        assert_eq!(strict.failed_files, vec!["/repo/a.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_memory_budget_bounds_chunks() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let config = AgeConfig {
+            memory_budget: Some(8192),
+            ..AgeConfig::default()
+        };
+        let manager = CageManager::new(Box::new(adapter), config).unwrap();
+        assert_eq!(manager.budgeted_chunk_size(Some(1 << 20)), Some(8192));
+        assert_eq!(manager.budgeted_chunk_size(Some(4096)), Some(4096));
+        assert_eq!(manager.budgeted_chunk_size(None), None);
+
+        // A container with larger chunks is refused rather than buffered
+        let temp = TempDir::new().unwrap();
+        let plain = temp.path().join("data.bin");
+        let container = temp.path().join("data.bin.cage");
+        std::fs::write(&plain, vec![7u8; 20_000]).unwrap();
+        let mut copy = |input: &Path, output: &Path, _: OutputFormat| {
+            std::fs::copy(input, output)
+                .map(|_| ())
+                .map_err(|e| AgeError::file_error("copy", input.to_path_buf(), e))
+        };
+        chunked::lock_chunked(
+            &plain,
+            &container,
+            16_384,
+            "",
+            OutputFormat::Binary,
+            &mut copy,
+        )
+        .unwrap();
+        let mut decrypt = |input: &Path, output: &Path| {
+            std::fs::copy(input, output)
+                .map(|_| ())
+                .map_err(|e| AgeError::file_error("copy", input.to_path_buf(), e))
+        };
+        let output = temp.path().join("out.bin");
+        let refused =
+            decrypt_maybe_chunked(&container, &output, manager.memory_budget(), &mut decrypt);
+        assert!(refused.is_err());
+        decrypt_maybe_chunked(&container, &output, MemoryBudget::unlimited(), &mut decrypt)
+            .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), vec![7u8; 20_000]);
+    }
+
+    #[test]
+    fn test_prepare_stream_resolves_provider_and_budget() {
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let config = AgeConfig {
+            memory_budget: Some(8192),
+            ..AgeConfig::default()
+        };
+        let manager = CageManager::new(Box::new(adapter), config).unwrap();
+        std::env::set_var("CAGE_TEST_STREAM_SECRET", "hunter2");
+
+        let mut request =
+            StreamRequest::decrypt(Identity::Provider("env:CAGE_TEST_STREAM_SECRET".into()));
+        request.buffer_size = 1 << 20;
+        let (_resolved, prepared) = manager.prepare_stream(&request).unwrap();
+        assert!(matches!(&prepared.identity, Identity::Passphrase(p) if p == "hunter2"));
+        assert_eq!(prepared.buffer_size, 4096);
+    }
+
+    #[test]
+    fn test_verify_large_armored_file_reads_head_and_tail() {
+        use std::io::{Seek, SeekFrom};
+
+        let adapter = crate::adp::v1::ShellAdapter::with_automator(std::sync::Arc::new(
+            crate::pty::FakeAutomator::new(),
+        ))
+        .unwrap();
+        let manager = CageManager::new(Box::new(adapter), AgeConfig::default()).unwrap();
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("backup.tar.cage");
+        let len = 3 * armor::DEFAULT_ARMOR_THRESHOLD / 2;
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(b"-----BEGIN AGE ENCRYPTED FILE-----\n")
+            .unwrap();
+        file.set_len(len).unwrap();
+
+        // Without the END line the armor is reported truncated
+        let status = manager.verify_file_integrity(&path).unwrap();
+        assert!(status.format_valid);
+        assert!(!status.header_valid);
+
+        let end = b"-----END AGE ENCRYPTED FILE-----\n";
+        file.seek(SeekFrom::Start(len - end.len() as u64)).unwrap();
+        file.write_all(end).unwrap();
+        let status = manager.verify_file_integrity(&path).unwrap();
+        assert!(status.is_valid());
+        assert!(manager.is_encrypted_file(&path).unwrap());
+    }
+
     #[test]
     fn test_retention_policy_keep_all() {
         let policy = RetentionPolicy::KeepAll;
@@ -3317,18 +6955,24 @@ mod tests {
             backup_path: PathBuf::from("/test/file1.txt.bak"),
             created_at: now - std::time::Duration::from_secs(86400 * 2), // 2 days
             size_bytes: 100,
+            checksum: None,
+            cross_device: false,
         });
         backups.push(BackupInfo {
             original_path: PathBuf::from("/test/file2.txt"),
             backup_path: PathBuf::from("/test/file2.txt.bak"),
             created_at: now - std::time::Duration::from_secs(86400 * 5), // 5 days
             size_bytes: 100,
+            checksum: None,
+            cross_device: false,
         });
         backups.push(BackupInfo {
             original_path: PathBuf::from("/test/file3.txt"),
             backup_path: PathBuf::from("/test/file3.txt.bak"),
             created_at: now - std::time::Duration::from_secs(86400 * 10), // 10 days
             size_bytes: 100,
+            checksum: None,
+            cross_device: false,
         });
 
         let policy = RetentionPolicy::KeepDays(7);
@@ -3352,6 +6996,8 @@ mod tests {
                 backup_path: PathBuf::from(format!("/test/file{}.txt.bak", i)),
                 created_at: now - std::time::Duration::from_secs(86400 * (i + 1) as u64),
                 size_bytes: 100,
+                checksum: None,
+                cross_device: false,
             });
         }
 
@@ -3399,6 +7045,8 @@ mod tests {
                 backup_path: PathBuf::from(format!("/test/file{}.txt.bak", i)),
                 created_at: now - std::time::Duration::from_secs(i as u64 * 60),
                 size_bytes: 100,
+                checksum: None,
+                cross_device: false,
             });
         }
 
@@ -0,0 +1,310 @@
+//! Cage Facade - High-level builder API for embedders
+//!
+//! `Cage` wraps `CageManager` and the request structs behind a small builder so callers
+//! do not need to know about adapters or request types:
+//!
+//! ```rust,no_run
+//! use cage::Cage;
+//! use std::path::Path;
+//!
+//! # fn main() -> cage::AgeResult<()> {
+//! let mut cage = Cage::builder().passphrase("correct horse").build()?;
+//! cage.lock(Path::new("secrets/"))?;
+//! cage.unlock(Path::new("secrets/"))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! This is the stable API surface; `CageManager` and the request structs remain
+//! available for advanced use.
+
+use crate::adp::progress::ByteProgressFn;
+use crate::core::{
+    AgeConfig, Identity, LockRequest, OutputFormat, Prompter, Recipient, RotateRequest,
+    StatusRequest, StreamRequest, UnlockRequest, VerifyRequest,
+};
+use crate::error::{AgeError, AgeResult};
+use crate::forge::{OperationResult, RepositoryStatus};
+use crate::mgr::{CageManager, VerificationResult};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Settings shared by every facade operation
+#[derive(Clone, Default)]
+struct CageSettings {
+    identity: Option<Identity>,
+    recipients: Vec<Recipient>,
+    format: Option<OutputFormat>,
+    recursive: bool,
+    pattern: Option<String>,
+    backup: bool,
+    preserve_encrypted: bool,
+}
+
+impl CageSettings {
+    fn identity(&self, operation: &str) -> AgeResult<Identity> {
+        self.identity
+            .clone()
+            .ok_or_else(|| AgeError::InvalidOperation {
+                operation: operation.to_string(),
+                reason: "No passphrase or identity configured on Cage builder".to_string(),
+            })
+    }
+
+    fn lock_request(&self, path: &Path) -> AgeResult<LockRequest> {
+        let mut request = LockRequest::new(path.to_path_buf(), self.identity("lock")?)
+            .recursive(self.recursive)
+            .with_format(self.format.unwrap_or(OutputFormat::Binary));
+        request.backup = self.backup;
+        if !self.recipients.is_empty() {
+            request = request.with_recipients(self.recipients.clone());
+        }
+        if let Some(pattern) = &self.pattern {
+            request = request.with_pattern(pattern.clone());
+        }
+        Ok(request)
+    }
+
+    fn unlock_request(&self, path: &Path) -> AgeResult<UnlockRequest> {
+        let mut request = UnlockRequest::new(path.to_path_buf(), self.identity("unlock")?)
+            .recursive(self.recursive)
+            .preserve_encrypted(self.preserve_encrypted);
+        if let Some(pattern) = &self.pattern {
+            request = request.with_pattern(pattern.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Builder for [`Cage`]
+#[derive(Default)]
+pub struct CageBuilder {
+    settings: CageSettings,
+    config: Option<AgeConfig>,
+    progress: Option<ByteProgressFn>,
+    prompter: Option<Arc<dyn Prompter>>,
+}
+
+impl CageBuilder {
+    /// Encrypt and decrypt with a passphrase
+    pub fn passphrase(mut self, passphrase: impl Into<String>) -> Self {
+        self.settings.identity = Some(Identity::Passphrase(passphrase.into()));
+        self
+    }
+
+    /// Decrypt with an age identity file
+    pub fn identity_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.settings.identity = Some(Identity::IdentityFile(path.into()));
+        self
+    }
+
+    /// Use any identity, including provider references
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.settings.identity = Some(identity);
+        self
+    }
+
+    /// Encrypt to these age or SSH public keys
+    pub fn recipients<I, S>(mut self, recipients: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let keys: Vec<String> = recipients.into_iter().map(Into::into).collect();
+        if !keys.is_empty() {
+            self.settings.recipients.push(Recipient::MultipleKeys(keys));
+        }
+        self
+    }
+
+    /// ASCII armor output instead of binary
+    pub fn armor(mut self, enabled: bool) -> Self {
+        self.settings.format = Some(if enabled {
+            OutputFormat::AsciiArmor
+        } else {
+            OutputFormat::Binary
+        });
+        self
+    }
+
+    /// Process directories recursively (default false)
+    pub fn recursive(mut self, enabled: bool) -> Self {
+        self.settings.recursive = enabled;
+        self
+    }
+
+    /// Only process files matching this glob
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.settings.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Back up files before locking (default false)
+    pub fn backup(mut self, enabled: bool) -> Self {
+        self.settings.backup = enabled;
+        self
+    }
+
+    /// Keep encrypted files after unlocking (default false)
+    pub fn preserve_encrypted(mut self, enabled: bool) -> Self {
+        self.settings.preserve_encrypted = enabled;
+        self
+    }
+
+    /// Report cumulative bytes for streaming operations
+    pub fn progress(mut self, callback: ByteProgressFn) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Use an explicit configuration instead of the layered config files
+    pub fn config(mut self, config: AgeConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Decide confirmations (reset, emergency unlock) with `prompter`
+    pub fn prompter(mut self, prompter: Arc<dyn Prompter>) -> Self {
+        self.prompter = Some(prompter);
+        self
+    }
+
+    pub fn build(self) -> AgeResult<Cage> {
+        let mut manager = match self.config {
+            Some(config) => CageManager::new(
+                crate::adp::v1::AdapterFactory::create_configured(&config)?,
+                config,
+            )?,
+            None => CageManager::with_defaults()?,
+        };
+        if let Some(prompter) = self.prompter {
+            manager.set_prompter(prompter);
+        }
+        Ok(Cage {
+            manager,
+            settings: self.settings,
+            progress: self.progress,
+        })
+    }
+}
+
+/// High-level entry point for embedding Cage
+pub struct Cage {
+    manager: CageManager,
+    settings: CageSettings,
+    progress: Option<ByteProgressFn>,
+}
+
+impl Cage {
+    pub fn builder() -> CageBuilder {
+        CageBuilder::default()
+    }
+
+    /// Encrypt a file or directory
+    pub fn lock(&mut self, path: &Path) -> AgeResult<OperationResult> {
+        let request = self.settings.lock_request(path)?;
+        self.manager.lock_with_request(&request)
+    }
+
+    /// Decrypt a file or directory
+    pub fn unlock(&mut self, path: &Path) -> AgeResult<OperationResult> {
+        let request = self.settings.unlock_request(path)?;
+        self.manager.unlock_with_request(&request)
+    }
+
+    /// Count encrypted and plaintext files under `path`
+    pub fn status(&self, path: &Path) -> AgeResult<RepositoryStatus> {
+        let request = StatusRequest::new(path.to_path_buf());
+        self.manager.status_with_request(&request)
+    }
+
+    /// Check age headers, decrypting each file as well when an identity is configured
+    pub fn verify(&mut self, path: &Path) -> AgeResult<VerificationResult> {
+        let mut request = VerifyRequest::new(path.to_path_buf());
+        if let Some(identity) = &self.settings.identity {
+            request = request.deep_verify(identity.clone());
+        }
+        self.manager.verify_with_request(&request)
+    }
+
+    /// Re-encrypt under `new_passphrase`; later operations use the new passphrase
+    pub fn rotate(
+        &mut self,
+        path: &Path,
+        new_passphrase: impl Into<String>,
+    ) -> AgeResult<OperationResult> {
+        let new_identity = Identity::Passphrase(new_passphrase.into());
+        let request = RotateRequest::new(
+            path.to_path_buf(),
+            self.settings.identity("rotate")?,
+            new_identity.clone(),
+        );
+        let result = self.manager.rotate_with_request(&request)?;
+        self.settings.identity = Some(new_identity);
+        Ok(result)
+    }
+
+    /// Encrypt `input` into `output`; returns bytes read
+    pub fn encrypt_stream(
+        &mut self,
+        input: &mut (dyn Read + Send),
+        output: &mut (dyn Write + Send),
+    ) -> AgeResult<u64> {
+        let mut request = StreamRequest::encrypt(self.settings.identity("stream")?);
+        if !self.settings.recipients.is_empty() {
+            request.recipients = Some(self.settings.recipients.clone());
+        }
+        request.format = self.settings.format.unwrap_or(OutputFormat::Binary);
+        self.manager
+            .stream_with_progress(&request, input, output, self.progress.clone())
+    }
+
+    /// Decrypt `input` into `output`; returns bytes read
+    pub fn decrypt_stream(
+        &mut self,
+        input: &mut (dyn Read + Send),
+        output: &mut (dyn Write + Send),
+    ) -> AgeResult<u64> {
+        let request = StreamRequest::decrypt(self.settings.identity("stream")?);
+        self.manager
+            .stream_with_progress(&request, input, output, self.progress.clone())
+    }
+
+    /// Underlying manager for operations not covered by the facade
+    pub fn manager(&mut self) -> &mut CageManager {
+        &mut self.manager
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_settings_map_to_requests() {
+        let builder = Cage::builder()
+            .passphrase("secret")
+            .recipients(["age1example"])
+            .armor(true)
+            .recursive(true)
+            .pattern("*.env");
+
+        let lock = builder.settings.lock_request(Path::new("repo")).unwrap();
+        assert!(matches!(lock.identity, Identity::Passphrase(ref p) if p == "secret"));
+        assert_eq!(lock.format, OutputFormat::AsciiArmor);
+        assert!(lock.recursive);
+        assert!(!lock.backup);
+        assert_eq!(lock.pattern.as_deref(), Some("*.env"));
+        assert_eq!(lock.recipients.map(|r| r.len()), Some(1));
+
+        let unlock = builder.settings.unlock_request(Path::new("repo")).unwrap();
+        assert!(unlock.recursive);
+        assert!(!unlock.preserve_encrypted);
+
+        assert!(Cage::builder()
+            .settings
+            .lock_request(Path::new("repo"))
+            .is_err());
+    }
+}
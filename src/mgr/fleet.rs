@@ -0,0 +1,173 @@
+//! Fleet Runs - One command across every repository of a fleet manifest
+//!
+//! `run_fleet` drives a `CageManager` through each repository of a `FleetManifest` in
+//! order. A repository that fails (missing path, bad group, decrypt error) is recorded
+//! and the run moves on, so one broken repo never hides the state of the others. The
+//! consolidated `FleetReport` serializes to JSON for `cage fleet ... --json`.
+
+use crate::core::{FleetCommand, FleetManifest, FleetRepo, Identity};
+use crate::error::AgeResult;
+use crate::mgr::CageManager;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Outcome of the fleet command for one repository
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FleetRepoReport {
+    pub name: String,
+    pub path: PathBuf,
+    /// Files looked at: counted (status), locked (lock) or verified (verify)
+    pub files: usize,
+    /// Encrypted share of the files (status only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_files: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_percentage: Option<f64>,
+    pub failed_files: Vec<String>,
+    /// Why the repository could not be processed at all
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl FleetRepoReport {
+    /// True when the repository was processed without failed files
+    pub fn ok(&self) -> bool {
+        self.error.is_none() && self.failed_files.is_empty()
+    }
+}
+
+/// Consolidated result of a fleet run
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetReport {
+    pub command: FleetCommand,
+    pub manifest: PathBuf,
+    pub repos: Vec<FleetRepoReport>,
+}
+
+impl FleetReport {
+    /// Repositories with an error or failed files
+    pub fn failed_repos(&self) -> usize {
+        self.repos.iter().filter(|repo| !repo.ok()).count()
+    }
+
+    pub fn total_files(&self) -> usize {
+        self.repos.iter().map(|repo| repo.files).sum()
+    }
+
+    pub fn total_failed_files(&self) -> usize {
+        self.repos.iter().map(|repo| repo.failed_files.len()).sum()
+    }
+
+    /// One-line summary across all repositories
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {} repos: {} ok, {} failed; {} files, {} failed files",
+            self.command.as_str(),
+            self.repos.len(),
+            self.repos.len() - self.failed_repos(),
+            self.failed_repos(),
+            self.total_files(),
+            self.total_failed_files()
+        )
+    }
+}
+
+/// Run `command` over every repository of `manifest`.
+///
+/// `passphrase` locks repositories without recipients; `deep` makes verify decrypt
+/// with each repository's identity (repositories without one get a format check).
+pub fn run_fleet(
+    manager: &mut CageManager,
+    manifest: &FleetManifest,
+    command: FleetCommand,
+    passphrase: Option<&Identity>,
+    deep: bool,
+) -> FleetReport {
+    let repos = manifest
+        .repos
+        .iter()
+        .map(|repo| {
+            let _span = tracing::info_span!("fleet", repo = %repo.label()).entered();
+            let started = Instant::now();
+            let mut report = FleetRepoReport {
+                name: repo.label(),
+                path: repo.path.clone(),
+                ..FleetRepoReport::default()
+            };
+            if let Err(e) = run_repo(manager, repo, command, passphrase, deep, &mut report) {
+                report.error = Some(e.to_string());
+            }
+            report.duration_ms = started.elapsed().as_millis() as u64;
+            report
+        })
+        .collect();
+    FleetReport {
+        command,
+        manifest: manifest.source.clone(),
+        repos,
+    }
+}
+
+fn run_repo(
+    manager: &mut CageManager,
+    repo: &FleetRepo,
+    command: FleetCommand,
+    passphrase: Option<&Identity>,
+    deep: bool,
+    report: &mut FleetRepoReport,
+) -> AgeResult<()> {
+    match command {
+        FleetCommand::Status => {
+            let status = manager.status_with_request(&repo.status_request())?;
+            report.files = status.total_files;
+            report.encrypted_files = Some(status.encrypted_files);
+            report.encryption_percentage = Some(status.encryption_percentage());
+            report.failed_files = status.failed_files;
+        }
+        FleetCommand::Lock => {
+            let request = repo.lock_request(passphrase, manager.config())?;
+            let result = manager.lock_with_request(&request)?;
+            report.files = result.processed_files.len();
+            report.failed_files = result.failed_files;
+        }
+        FleetCommand::Verify => {
+            let result = manager.verify_with_request(&repo.verify_request(deep))?;
+            report.files = result.verified_files.len();
+            report.failed_files = result.failed_files;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fleet_report_counts_failed_repos() {
+        let repo = |name: &str, failed: &[&str], error: Option<&str>| FleetRepoReport {
+            name: name.to_string(),
+            files: 3,
+            failed_files: failed.iter().map(|f| f.to_string()).collect(),
+            error: error.map(str::to_string),
+            ..FleetRepoReport::default()
+        };
+        let report = FleetReport {
+            command: FleetCommand::Verify,
+            manifest: PathBuf::from("fleet.toml"),
+            repos: vec![
+                repo("a", &[], None),
+                repo("b", &["b/x.cage"], None),
+                repo("c", &[], Some("Path not found")),
+            ],
+        };
+        assert_eq!(report.failed_repos(), 2);
+        assert_eq!(report.total_files(), 9);
+        assert_eq!(
+            report.summary(),
+            "verify 3 repos: 1 ok, 2 failed; 9 files, 1 failed files"
+        );
+        assert_eq!(serde_json::to_value(&report).unwrap()["command"], "verify");
+    }
+}
@@ -7,7 +7,29 @@
 //!
 //! Security Guardian: Edgar - Production management framework
 
+pub mod attestation;
 pub mod cage_manager;
+pub mod facade;
+pub mod fleet;
+pub mod quarantine;
+pub mod rekey_plan;
+pub mod relock;
+pub mod self_test;
+pub mod session;
+pub mod worker;
 
 // Re-export core manager types
-pub use cage_manager::{CageManager, LockOptions, UnlockOptions, VerificationResult};
+pub use attestation::{Envelope, Statement};
+pub use cage_manager::{
+    CageManager, LockOptions, StreamVerifyDigest, UnlockOptions, VerificationResult,
+};
+pub use facade::{Cage, CageBuilder};
+pub use fleet::{run_fleet, FleetRepoReport, FleetReport};
+pub use quarantine::Quarantine;
+pub use rekey_plan::{RekeyEntry, RekeyPlan};
+pub use relock::{RelockEntry, RelockStore};
+pub use self_test::{
+    run_self_test, run_self_test_with_config, SelfTestCheck, SelfTestReport, SelfTestStatus,
+};
+pub use session::{Session, SessionChanges, SessionFile};
+pub use worker::{QueueWorker, WorkerJob, WorkerRequest, WorkerResult, WorkerSummary};
@@ -0,0 +1,228 @@
+//! Quarantine - Soft delete for files removed by Cage operations
+//!
+//! When a quarantine directory is configured (`[quarantine] directory`, or the
+//! `CAGE_QUARANTINE_DIR` override), files that an operation would unlink or replace —
+//! the ciphertext removed after a successful unlock, the plaintext an in-place lock
+//! overwrites, a stale output replaced by a hardlink — are kept there instead. Entries
+//! mirror the original absolute path below the directory, are stamped with the removal
+//! time and pruned per original path according to `[quarantine] retention` (default
+//! `keep_days:7`), so same-named files in different directories never evict each other.
+//!
+//! This is a lighter safety net than full backups: nothing is copied up front, and a
+//! fat-fingered unlock can be undone by moving the quarantined file back.
+
+use super::cage_manager::{BackupInfo, RetentionPolicy};
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Separator between the original file name and the removal timestamp
+const STAMP_SEPARATOR: &str = ".quarantined-";
+
+/// Removal timestamp format; lexicographic order matches chronological order
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.9fZ";
+
+/// Quarantine directory with retention
+#[derive(Debug, Clone)]
+pub struct Quarantine {
+    directory: PathBuf,
+    retention: RetentionPolicy,
+}
+
+impl Quarantine {
+    pub fn new(directory: PathBuf, retention: RetentionPolicy) -> Self {
+        Self {
+            directory,
+            retention,
+        }
+    }
+
+    /// Build from config, honoring a `CAGE_QUARANTINE_DIR` override; `None` if disabled
+    pub fn from_config(config: &AgeConfig) -> Option<Self> {
+        let directory = match std::env::var("CAGE_QUARANTINE_DIR") {
+            Ok(dir) if !dir.is_empty() => dir,
+            _ => config.quarantine_directory.clone()?,
+        };
+        Some(Self::new(
+            PathBuf::from(directory),
+            config.quarantine_retention.to_retention_policy(),
+        ))
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Move `path` into quarantine and prune old entries; returns the quarantined path
+    pub fn remove(&self, path: &Path) -> AgeResult<PathBuf> {
+        let target = self.entry_path(path)?;
+        if fs::rename(path, &target).is_err() {
+            // Cross-filesystem moves fall back to copy + unlink
+            fs::copy(path, &target)
+                .map_err(|e| AgeError::file_error("quarantine_copy", target.clone(), e))?;
+            fs::remove_file(path)
+                .map_err(|e| AgeError::file_error("quarantine_remove", path.to_path_buf(), e))?;
+        }
+
+        self.enforce_retention()?;
+        Ok(target)
+    }
+
+    /// Copy `path` into quarantine before the caller replaces it; returns the copy
+    pub fn preserve(&self, path: &Path) -> AgeResult<PathBuf> {
+        let target = self.entry_path(path)?;
+        fs::copy(path, &target)
+            .map_err(|e| AgeError::file_error("quarantine_copy", target.clone(), e))?;
+        self.enforce_retention()?;
+        Ok(target)
+    }
+
+    /// Stamped location for `path`, mirroring its absolute path below the directory
+    fn entry_path(&self, path: &Path) -> AgeResult<PathBuf> {
+        let absolute = std::path::absolute(path)
+            .map_err(|e| AgeError::file_error("quarantine", path.to_path_buf(), e))?;
+        let file_name = absolute
+            .file_name()
+            .ok_or_else(|| AgeError::InvalidOperation {
+                operation: "quarantine".to_string(),
+                reason: format!("Cannot extract filename from path: {}", path.display()),
+            })?
+            .to_string_lossy()
+            .to_string();
+        let mut dir = self.directory.clone();
+        if let Some(parent) = absolute.parent() {
+            dir.extend(parent.components().filter_map(|component| match component {
+                Component::Normal(part) => Some(part),
+                _ => None,
+            }));
+        }
+        fs::create_dir_all(&dir)
+            .map_err(|e| AgeError::file_error("create_quarantine", dir.clone(), e))?;
+        let stamp = chrono::Utc::now().format(STAMP_FORMAT);
+        Ok(dir.join(format!("{}{}{}", file_name, STAMP_SEPARATOR, stamp)))
+    }
+
+    /// Apply the retention policy per original path; returns pruned paths
+    pub fn enforce_retention(&self) -> AgeResult<Vec<PathBuf>> {
+        let mut groups: HashMap<PathBuf, Vec<BackupInfo>> = HashMap::new();
+        self.collect_entries(&self.directory, &mut groups)?;
+
+        let mut pruned = Vec::new();
+        for entries in groups.values_mut() {
+            // Retention policies expect oldest first; stamps sort chronologically
+            entries.sort_by(|a, b| a.backup_path.cmp(&b.backup_path));
+            for index in self.retention.apply(entries) {
+                let path = &entries[index].backup_path;
+                fs::remove_file(path)
+                    .map_err(|e| AgeError::file_error("quarantine_prune", path.clone(), e))?;
+                pruned.push(path.clone());
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Gather stamped entries below `dir`, grouped by the path they were removed from
+    fn collect_entries(
+        &self,
+        dir: &Path,
+        groups: &mut HashMap<PathBuf, Vec<BackupInfo>>,
+    ) -> AgeResult<()> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(AgeError::file_error("read_dir", dir.to_path_buf(), e)),
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                self.collect_entries(&path, groups)?;
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some((original, stamp)) = name.rsplit_once(STAMP_SEPARATOR) else {
+                continue;
+            };
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            // The stamp records when the file was quarantined; mtime is only a fallback
+            let created_at = chrono::NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+                .map(|t| std::time::SystemTime::from(t.and_utc()))
+                .or_else(|_| meta.modified())
+                .unwrap_or_else(|_| std::time::SystemTime::now());
+            let original = dir.join(original);
+            groups
+                .entry(original.clone())
+                .or_default()
+                .push(BackupInfo {
+                    original_path: original,
+                    backup_path: path,
+                    created_at,
+                    size_bytes: meta.len(),
+                    checksum: None,
+                    cross_device: false,
+                });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_quarantine_moves_and_prunes() {
+        let temp = TempDir::new().unwrap();
+        let quarantine = Quarantine::new(temp.path().join("trash"), RetentionPolicy::KeepLast(2));
+        let file = temp.path().join("secret.txt.cage");
+
+        let mut moved = Vec::new();
+        for generation in 0..3 {
+            fs::write(&file, format!("ciphertext {}", generation)).unwrap();
+            moved.push(quarantine.remove(&file).unwrap());
+            assert!(!file.exists());
+        }
+
+        let remaining: Vec<_> = fs::read_dir(moved[2].parent().unwrap())
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(fs::read_to_string(&moved[2]).unwrap(), "ciphertext 2");
+        assert!(moved[2].starts_with(quarantine.directory()));
+    }
+
+    #[test]
+    fn test_quarantine_retention_per_full_path() {
+        let temp = TempDir::new().unwrap();
+        let quarantine = Quarantine::new(temp.path().join("trash"), RetentionPolicy::KeepLast(1));
+        let (a, b) = (temp.path().join("a"), temp.path().join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+
+        fs::write(a.join("config.yaml.cage"), "from a").unwrap();
+        let from_a = quarantine.remove(&a.join("config.yaml.cage")).unwrap();
+        fs::write(b.join("config.yaml.cage"), "from b").unwrap();
+        let from_b = quarantine.remove(&b.join("config.yaml.cage")).unwrap();
+
+        // Same file name, different directories: neither evicts the other
+        assert_eq!(fs::read_to_string(&from_a).unwrap(), "from a");
+        assert_eq!(fs::read_to_string(&from_b).unwrap(), "from b");
+
+        // preserve copies and leaves the original in place
+        fs::write(a.join("plain.txt"), "plaintext").unwrap();
+        let copy = quarantine.preserve(&a.join("plain.txt")).unwrap();
+        assert!(a.join("plain.txt").exists());
+        assert_eq!(fs::read_to_string(copy).unwrap(), "plaintext");
+    }
+}
@@ -0,0 +1,204 @@
+//! Rekey Plan - Re-encryption of files encrypted to an outdated group membership
+//!
+//! Removing a recipient from a group does not revoke their access to files already
+//! encrypted to it; those files have to be re-encrypted to the current membership. The
+//! `.meta` sidecars (see `core::meta`) record the `group_hash` each file was encrypted to,
+//! so `RekeyPlan::scan` can list exactly the stale files with their total size and an ETA.
+//!
+//! Plans are saved to `<root>/.cage/rekey-<group slug>.json` and updated after every
+//! file, so an interrupted run resumes where it stopped. The plan is discarded once it
+//! completes or the group changes again.
+
+use crate::core::meta::CipherMeta;
+use crate::core::RecipientGroup;
+use crate::error::{AgeError, AgeResult};
+use crate::forge::STATE_DIR;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Assumed decrypt + encrypt throughput (bytes/sec) when `max_throughput` is unset
+pub const DEFAULT_REKEY_THROUGHPUT: u64 = 32 * 1024 * 1024;
+
+/// One file to re-encrypt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RekeyEntry {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Group hash recorded in the file's sidecar
+    pub recorded_hash: String,
+    pub done: bool,
+}
+
+/// Files under a root that are encrypted to an older membership of one group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RekeyPlan {
+    pub group: String,
+    /// Current `group_hash` the files are re-encrypted to
+    pub group_hash: String,
+    pub created_at: String,
+    pub entries: Vec<RekeyEntry>,
+    /// Encrypted files without a sidecar; their recipients are unknown
+    pub unknown: Vec<PathBuf>,
+}
+
+impl RekeyPlan {
+    /// Select the files in `files` whose sidecar names `group` with a different hash
+    pub fn scan(group: &RecipientGroup, files: &[PathBuf]) -> AgeResult<Self> {
+        let group_hash = group.group_hash();
+        let mut entries = Vec::new();
+        let mut unknown = Vec::new();
+        for file in files {
+            let Some(meta) = CipherMeta::load(file)? else {
+                unknown.push(file.clone());
+                continue;
+            };
+            let recorded = meta.groups.iter().find(|g| g.name == group.name);
+            if let Some(recorded) = recorded.filter(|g| g.group_hash != group_hash) {
+                entries.push(RekeyEntry {
+                    path: file.clone(),
+                    bytes: fs::metadata(file).map(|m| m.len()).unwrap_or(0),
+                    recorded_hash: recorded.group_hash.clone(),
+                    done: false,
+                });
+            }
+        }
+        Ok(Self {
+            group: group.name.clone(),
+            group_hash,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            entries,
+            unknown,
+        })
+    }
+
+    /// Where the plan for `group` under `root` is kept; the name is slugged so a group
+    /// called `ops/prod` cannot point outside the state directory
+    pub fn plan_path(root: &Path, group: &str) -> PathBuf {
+        let dir = if root.is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+        dir.join(STATE_DIR)
+            .join(format!("rekey-{}.json", crate::keygen::roster::slug(group)))
+    }
+
+    /// Saved plan at `path`, if any
+    pub fn load(path: &Path) -> AgeResult<Option<Self>> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AgeError::file_error("read_plan", path.to_path_buf(), e)),
+        };
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| AgeError::InvalidOperation {
+                operation: "read_plan".to_string(),
+                reason: format!("{}: {}", path.display(), e),
+            })
+    }
+
+    /// Write the plan to `path`, replacing the previous file in one rename
+    pub fn save(&self, path: &Path) -> AgeResult<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| AgeError::file_error("create_state_dir", dir.to_path_buf(), e))?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(|e| AgeError::InvalidOperation {
+            operation: "save_plan".to_string(),
+            reason: e.to_string(),
+        })?;
+        let mut staging = path.as_os_str().to_owned();
+        staging.push(".tmp");
+        let staging = PathBuf::from(staging);
+        fs::write(&staging, json)
+            .map_err(|e| AgeError::file_error("write_plan", staging.clone(), e))?;
+        fs::rename(&staging, path)
+            .map_err(|e| AgeError::file_error("write_plan", path.to_path_buf(), e))
+    }
+
+    /// Entries not re-encrypted yet
+    pub fn pending(&self) -> impl Iterator<Item = &RekeyEntry> {
+        self.entries.iter().filter(|entry| !entry.done)
+    }
+
+    /// Bytes still to re-encrypt
+    pub fn pending_bytes(&self) -> u64 {
+        self.pending().map(|entry| entry.bytes).sum()
+    }
+
+    /// Estimated time for the pending entries at `throughput` bytes/sec
+    pub fn eta(&self, throughput: u64) -> Duration {
+        Duration::from_secs(self.pending_bytes().div_ceil(throughput.max(1)))
+    }
+
+    pub fn mark_done(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path) {
+            entry.done = true;
+        }
+    }
+
+    /// One-line summary: counts, size and ETA
+    pub fn summary(&self, throughput: u64) -> String {
+        format!(
+            "group '{}': {} of {} file(s) pending, {} bytes, ETA {}s ({} without .meta)",
+            self.group,
+            self.pending().count(),
+            self.entries.len(),
+            self.pending_bytes(),
+            self.eta(throughput).as_secs(),
+            self.unknown.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MultiRecipientConfig;
+    use tempfile::TempDir;
+
+    fn encrypted_to(dir: &Path, name: &str, group: &RecipientGroup) -> PathBuf {
+        let file = dir.join(name);
+        fs::write(&file, vec![0u8; 100]).unwrap();
+        let multi = MultiRecipientConfig::new().add_group(group.clone());
+        CipherMeta::for_recipients(&[], Some(&multi))
+            .write(&file)
+            .unwrap();
+        file
+    }
+
+    #[test]
+    fn test_scan_selects_stale_membership_and_resumes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut team = RecipientGroup::new("team".to_string());
+        team.add_recipient("age1alice".to_string());
+        team.add_recipient("age1mallory".to_string());
+        let stale = encrypted_to(temp_dir.path(), "old.txt.cage", &team);
+
+        team.recipients.retain(|r| r != "age1mallory");
+        let current = encrypted_to(temp_dir.path(), "new.txt.cage", &team);
+        let bare = temp_dir.path().join("bare.txt.cage");
+        fs::write(&bare, "x").unwrap();
+
+        let mut plan = RekeyPlan::scan(&team, &[stale.clone(), current, bare.clone()]).unwrap();
+        assert_eq!(plan.entries.len(), 1);
+        assert_eq!(plan.entries[0].path, stale);
+        assert_eq!(plan.unknown, vec![bare]);
+        assert_eq!(plan.pending_bytes(), 100);
+        assert_eq!(plan.eta(50), Duration::from_secs(2));
+
+        let path = RekeyPlan::plan_path(temp_dir.path(), "team");
+        plan.mark_done(&stale);
+        plan.save(&path).unwrap();
+        let resumed = RekeyPlan::load(&path).unwrap().unwrap();
+        assert_eq!(resumed.pending().count(), 0);
+
+        // Group names never escape the state directory
+        let escaped = RekeyPlan::plan_path(temp_dir.path(), "../Ops Team");
+        assert_eq!(escaped.parent(), path.parent());
+        assert!(escaped.ends_with("rekey-ops-team.json"));
+    }
+}
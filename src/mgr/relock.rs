@@ -0,0 +1,130 @@
+//! Relock - Session state for time-boxed unlocks
+//!
+//! `UnlockRequest::relock_after` (CLI: `cage unlock --for 30m`) keeps the ciphertext and
+//! records each plaintext it wrote here, with the SHA-256 it had and when it expires.
+//! `CageManager::relock_due` (CLI: `cage relock`, run by the timer the unlock scheduled)
+//! then removes expired plaintext: unchanged files are simply deleted since their
+//! ciphertext is still in place, while edited ones are encrypted again to the recipients
+//! in the `.cage.meta` sidecar. Anything that cannot be relocked safely stays recorded
+//! and is reported.
+//!
+//! The store is per user: `$CAGE_RELOCK_FILE`, else `$XDG_DATA_HOME/cage/relock.json`,
+//! else `~/.local/share/cage/relock.json`.
+
+use crate::error::{AgeError, AgeResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One plaintext written by a time-boxed unlock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelockEntry {
+    pub plaintext: PathBuf,
+    /// Ciphertext the plaintext was decrypted from (kept by the unlock)
+    pub ciphertext: PathBuf,
+    /// SHA-256 of the plaintext as written
+    pub sha256: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Recorded time-boxed plaintext, saved as JSON
+#[derive(Debug, Clone)]
+pub struct RelockStore {
+    path: PathBuf,
+    entries: Vec<RelockEntry>,
+}
+
+impl RelockStore {
+    /// Store location from the environment; `None` without `$HOME` or an override
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(file) = std::env::var("CAGE_RELOCK_FILE") {
+            if !file.is_empty() {
+                return Some(PathBuf::from(file));
+            }
+        }
+        let base = match std::env::var("XDG_DATA_HOME") {
+            Ok(xdg) if !xdg.is_empty() => PathBuf::from(xdg),
+            _ => PathBuf::from(std::env::var("HOME").ok()?).join(".local/share"),
+        };
+        Some(base.join("cage").join("relock.json"))
+    }
+
+    /// Load the store at `path`; a missing file is an empty store
+    pub fn load(path: &Path) -> AgeResult<Self> {
+        let entries = match fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| AgeError::InvalidOperation {
+                operation: "read_relock".to_string(),
+                reason: format!("{}: {}", path.display(), e),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(AgeError::file_error("read_relock", path.to_path_buf(), e)),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[RelockEntry] {
+        &self.entries
+    }
+
+    /// Record `entry`, replacing any earlier entry for the same plaintext
+    pub fn add(&mut self, entry: RelockEntry) {
+        self.entries
+            .retain(|existing| existing.plaintext != entry.plaintext);
+        self.entries.push(entry);
+    }
+
+    /// Entries expired at `now`
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<RelockEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.expires_at <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// Drop `settled` entries (matched on plaintext and expiry, so a newer unlock survives)
+    pub fn remove(&mut self, settled: &[RelockEntry]) {
+        self.entries.retain(|entry| {
+            !settled.iter().any(|done| {
+                done.plaintext == entry.plaintext && done.expires_at == entry.expires_at
+            })
+        });
+    }
+
+    /// Earliest recorded expiry later than `after`
+    pub fn next_expiry(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.entries
+            .iter()
+            .map(|entry| entry.expires_at)
+            .filter(|expiry| *expiry > after)
+            .min()
+    }
+
+    /// Write the store, replacing the previous file in one rename
+    pub fn save(&self) -> AgeResult<()> {
+        if let Some(dir) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| AgeError::file_error("create_relock_dir", dir.to_path_buf(), e))?;
+        }
+        let json =
+            serde_json::to_vec_pretty(&self.entries).map_err(|e| AgeError::InvalidOperation {
+                operation: "save_relock".to_string(),
+                reason: e.to_string(),
+            })?;
+        let mut staging = self.path.clone().into_os_string();
+        staging.push(".tmp");
+        let staging = PathBuf::from(staging);
+        fs::write(&staging, json)
+            .map_err(|e| AgeError::file_error("write_relock", staging.clone(), e))?;
+        fs::rename(&staging, &self.path)
+            .map_err(|e| AgeError::file_error("write_relock", self.path.clone(), e))
+    }
+}
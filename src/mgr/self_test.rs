@@ -0,0 +1,304 @@
+//! Self-Test - End-to-end round trips against the local environment
+//!
+//! `cage test --self` runs every core capability in a throwaway workspace so a broken
+//! `age` install, missing PTY support or bad config is caught before production use.
+//! Each capability is reported separately; a failed environment check skips the rest.
+
+use crate::core::{AgeConfig, Identity};
+use crate::error::{AgeError, AgeResult};
+use crate::mgr::{Cage, CageBuilder};
+use age::secrecy::ExposeSecret;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tempfile::TempDir;
+
+const SAMPLE: &[u8] = b"cage self-test payload\nline two\n";
+const PASSPHRASE: &str = "cage-self-test-passphrase";
+const ROTATED_PASSPHRASE: &str = "cage-self-test-rotated";
+
+/// Outcome of one capability check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Result of one capability check
+#[derive(Debug, Clone)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub status: SelfTestStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Results of a full self-test run
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// True when no check failed or was skipped
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status == SelfTestStatus::Passed)
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status != SelfTestStatus::Passed)
+            .count()
+    }
+
+    fn record(&mut self, name: &'static str, check: impl FnOnce() -> AgeResult<String>) -> bool {
+        let started = Instant::now();
+        let (status, detail) = match check() {
+            Ok(detail) => (SelfTestStatus::Passed, detail),
+            Err(e) => (SelfTestStatus::Failed, e.to_string()),
+        };
+        self.checks.push(SelfTestCheck {
+            name,
+            status,
+            detail,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        status == SelfTestStatus::Passed
+    }
+
+    fn skip(&mut self, name: &'static str, reason: &str) {
+        self.checks.push(SelfTestCheck {
+            name,
+            status: SelfTestStatus::Skipped,
+            detail: reason.to_string(),
+            duration_ms: 0,
+        });
+    }
+}
+
+/// Capabilities checked after the environment, in run order
+pub const SELF_TEST_CHECKS: &[&str] = &["identity", "lock-unlock", "rotate", "stream", "verify"];
+
+/// Run every self-test check in a temporary workspace with the loaded configuration
+pub fn run_self_test() -> SelfTestReport {
+    run_checks(None)
+}
+
+/// Run the self-test with `config` instead of the loaded configuration, so backups and
+/// audit logs go where it says rather than to the user's state directories
+pub fn run_self_test_with_config(config: AgeConfig) -> SelfTestReport {
+    run_checks(Some(&config))
+}
+
+fn run_checks(config: Option<&AgeConfig>) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    let mut workspace = None;
+    let ready = report.record("environment", || {
+        let temp = TempDir::new().map_err(|e| io_error("create workspace", e))?;
+        let cage = passphrase_cage(config, PASSPHRASE)?;
+        drop(cage);
+        let detail = format!("workspace {}", temp.path().display());
+        workspace = Some(temp);
+        Ok(detail)
+    });
+    let Some(workspace) = workspace.filter(|_| ready) else {
+        for name in SELF_TEST_CHECKS {
+            report.skip(name, "environment check failed");
+        }
+        return report;
+    };
+    let root = workspace.path();
+
+    let mut identity_file = None;
+    report.record("identity", || {
+        let (path, recipient) = write_identity(root)?;
+        identity_file = Some((path, recipient.clone()));
+        Ok(format!("generated {}", recipient))
+    });
+    report.record("lock-unlock", || check_lock_unlock(config, root));
+    report.record("rotate", || check_rotate(config, root));
+    match identity_file {
+        Some((path, recipient)) => {
+            report.record("stream", || check_stream(config, &path, &recipient));
+        }
+        None => report.skip("stream", "identity generation failed"),
+    }
+    report.record("verify", || check_verify(config, root));
+    report
+}
+
+/// Builder using `config` when given, else the loaded configuration
+fn builder(config: Option<&AgeConfig>) -> CageBuilder {
+    match config {
+        Some(config) => Cage::builder().config(config.clone()),
+        None => Cage::builder(),
+    }
+}
+
+fn passphrase_cage(config: Option<&AgeConfig>, passphrase: &str) -> AgeResult<Cage> {
+    builder(config).passphrase(passphrase).build()
+}
+
+fn io_error(operation: &str, source: std::io::Error) -> AgeError {
+    AgeError::IoError {
+        operation: operation.to_string(),
+        context: "self-test".to_string(),
+        source,
+    }
+}
+
+fn mismatch(operation: &str, reason: &str) -> AgeError {
+    AgeError::InvalidOperation {
+        operation: format!("self-test {}", operation),
+        reason: reason.to_string(),
+    }
+}
+
+/// Write a throwaway x25519 identity; returns its path and public recipient
+fn write_identity(root: &Path) -> AgeResult<(PathBuf, String)> {
+    let identity = age::x25519::Identity::generate();
+    let recipient = identity.to_public().to_string();
+    let path = root.join("self-test.key");
+    fs::write(&path, format!("{}\n", identity.to_string().expose_secret()))
+        .map_err(|e| AgeError::file_error("write", path.clone(), e))?;
+    Ok((path, recipient))
+}
+
+fn sample_file(root: &Path, name: &str) -> AgeResult<PathBuf> {
+    let path = root.join(name);
+    fs::write(&path, SAMPLE).map_err(|e| AgeError::file_error("write", path.clone(), e))?;
+    Ok(path)
+}
+
+/// Encrypted companion of `path` produced by lock
+fn locked_path(config: Option<&AgeConfig>, path: &Path) -> AgeResult<PathBuf> {
+    let extension = match config {
+        Some(config) => config.extension_with_dot(),
+        None => AgeConfig::load_default()?.extension_with_dot(),
+    };
+    let mut locked = path.as_os_str().to_owned();
+    locked.push(extension);
+    Ok(PathBuf::from(locked))
+}
+
+/// Remove the plaintext so a following unlock has to recreate it
+fn remove_plaintext(path: &Path) -> AgeResult<()> {
+    fs::remove_file(path).map_err(|e| AgeError::file_error("remove", path.to_path_buf(), e))
+}
+
+fn expect_plaintext(operation: &str, path: &Path) -> AgeResult<()> {
+    let contents =
+        fs::read(path).map_err(|e| AgeError::file_error("read", path.to_path_buf(), e))?;
+    if contents == SAMPLE {
+        Ok(())
+    } else {
+        Err(mismatch(
+            operation,
+            "decrypted content differs from original",
+        ))
+    }
+}
+
+fn check_lock_unlock(config: Option<&AgeConfig>, root: &Path) -> AgeResult<String> {
+    let path = sample_file(root, "lock-unlock.txt")?;
+    let mut cage = passphrase_cage(config, PASSPHRASE)?;
+    cage.lock(&path)?;
+    let locked = locked_path(config, &path)?;
+    if !locked.exists() {
+        return Err(mismatch("lock", "encrypted file was not produced"));
+    }
+    remove_plaintext(&path)?;
+    cage.unlock(&locked)?;
+    expect_plaintext("unlock", &path)?;
+    Ok(format!("{} bytes round-tripped", SAMPLE.len()))
+}
+
+fn check_rotate(config: Option<&AgeConfig>, root: &Path) -> AgeResult<String> {
+    let path = sample_file(root, "rotate.txt")?;
+    let mut cage = passphrase_cage(config, PASSPHRASE)?;
+    cage.lock(&path)?;
+    let locked = locked_path(config, &path)?;
+    cage.rotate(&locked, ROTATED_PASSPHRASE)?;
+
+    // Rotated ciphertext must open with the new passphrase
+    remove_plaintext(&path)?;
+    passphrase_cage(config, ROTATED_PASSPHRASE)?.unlock(&locked)?;
+    expect_plaintext("rotate", &path)?;
+    Ok("re-encrypted under new passphrase".to_string())
+}
+
+fn check_stream(
+    config: Option<&AgeConfig>,
+    identity_file: &Path,
+    recipient: &str,
+) -> AgeResult<String> {
+    let mut encryptor = builder(config)
+        .identity(Identity::IdentityFile(identity_file.to_path_buf()))
+        .recipients([recipient])
+        .build()?;
+    let mut ciphertext = Vec::new();
+    encryptor.encrypt_stream(&mut &SAMPLE[..], &mut ciphertext)?;
+
+    let mut decryptor = builder(config).identity_file(identity_file).build()?;
+    let mut plaintext = Vec::new();
+    decryptor.decrypt_stream(&mut ciphertext.as_slice(), &mut plaintext)?;
+    if plaintext != SAMPLE {
+        return Err(mismatch("stream", "decrypted stream differs from input"));
+    }
+    Ok(format!("{} bytes through recipient key", SAMPLE.len()))
+}
+
+fn check_verify(config: Option<&AgeConfig>, root: &Path) -> AgeResult<String> {
+    let path = sample_file(root, "verify.txt")?;
+    let mut cage = passphrase_cage(config, PASSPHRASE)?;
+    cage.lock(&path)?;
+    let result = cage.verify(&locked_path(config, &path)?)?;
+    if !result.failed_files.is_empty() || result.verified_files.is_empty() {
+        return Err(mismatch("verify", &result.overall_status));
+    }
+    Ok(format!("{} file(s) verified", result.verified_files.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_counts_and_skips() {
+        let mut report = SelfTestReport::default();
+        assert!(report.record("ok", || Ok("fine".to_string())));
+        assert!(!report.record("bad", || Err(mismatch("bad", "broken"))));
+        report.skip("later", "bad failed");
+
+        assert!(!report.passed());
+        assert_eq!(report.failed_count(), 2);
+        assert_eq!(report.checks[1].status, SelfTestStatus::Failed);
+        assert_eq!(report.checks[2].status, SelfTestStatus::Skipped);
+    }
+
+    #[test]
+    fn test_identity_file_round_trips_with_age() {
+        let temp = TempDir::new().unwrap();
+        let (path, recipient) = write_identity(temp.path()).unwrap();
+        let contents = fs::read_to_string(path).unwrap();
+        let identity: age::x25519::Identity = contents.trim().parse().unwrap();
+        assert_eq!(identity.to_public().to_string(), recipient);
+    }
+
+    #[test]
+    fn test_run_self_test_reports_every_check() {
+        // Explicit config so nothing is read from or written to the user's directories
+        let state = TempDir::new().unwrap();
+        let config = AgeConfig {
+            backup_directory: Some(state.path().join("backups").display().to_string()),
+            ..AgeConfig::default()
+        };
+        let report = run_self_test_with_config(config);
+        assert_eq!(report.checks.len(), SELF_TEST_CHECKS.len() + 1);
+        assert_eq!(report.checks[0].name, "environment");
+    }
+}
@@ -0,0 +1,218 @@
+//! Session - Tracked edit cycles over an encrypted repository
+//!
+//! `CageManager::open_session` (CLI: `cage open <repo>`) decrypts every encrypted file
+//! into a separate workspace, by default on tmpfs (`/dev/shm`) so plaintext never reaches
+//! disk, and records each file with the SHA-256 it was opened with in
+//! `<repo>/.cage/session.json`. `CageManager::close_session` (CLI: `cage close <repo>`)
+//! encrypts back only the files whose hash changed or that were added, reports files
+//! deleted from the workspace (their ciphertext is kept) and removes the plaintext. A
+//! close that cannot encrypt everything leaves the session open so nothing is lost.
+//!
+//! Untouched files keep byte-identical ciphertext, so backups and sync tools see only
+//! real edits; re-encrypted files get fresh records in the checksum database when it is
+//! enabled.
+//!
+//! The repository itself is never touched while a session is open; its ciphertext stays
+//! authoritative until `close`.
+
+use crate::core::markers::sha256_file;
+use crate::core::AgeConfig;
+use crate::error::{AgeError, AgeResult};
+use crate::forge::{FileFilter, RecursiveTraversal, Traversal, STATE_DIR};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SESSION_FILE: &str = "session.json";
+
+/// One decrypted file in a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionFile {
+    /// Ciphertext path relative to the repository
+    pub ciphertext: PathBuf,
+    /// Plaintext path relative to the workspace
+    pub plaintext: PathBuf,
+    /// SHA-256 of the plaintext as opened
+    pub sha256: String,
+}
+
+/// How a workspace differs from what was opened
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionChanges {
+    pub changed: Vec<SessionFile>,
+    /// New plaintext files, relative to the workspace
+    pub added: Vec<PathBuf>,
+    /// Opened files no longer in the workspace
+    pub removed: Vec<SessionFile>,
+    pub unchanged: usize,
+}
+
+impl SessionChanges {
+    /// Nothing needs to be encrypted on close
+    pub fn is_clean(&self) -> bool {
+        self.changed.is_empty() && self.added.is_empty()
+    }
+}
+
+/// An open session, as recorded in the repository's state directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub repository: PathBuf,
+    pub workspace: PathBuf,
+    pub opened_at: String,
+    /// Whether `open` created the workspace, so `close` removes it entirely
+    pub owns_workspace: bool,
+    pub files: Vec<SessionFile>,
+}
+
+impl Session {
+    /// Where the session for `repository` is recorded
+    pub fn session_path(repository: &Path) -> PathBuf {
+        repository.join(STATE_DIR).join(SESSION_FILE)
+    }
+
+    /// Workspace used when none is given: `/dev/shm` when present, else the temp dir
+    pub fn default_workspace(repository: &Path) -> PathBuf {
+        let absolute = std::path::absolute(repository).unwrap_or_else(|_| repository.into());
+        let key = format!(
+            "{:x}",
+            Sha256::digest(absolute.to_string_lossy().as_bytes())
+        );
+        let shm = Path::new("/dev/shm");
+        let base = if shm.is_dir() {
+            shm.to_path_buf()
+        } else {
+            std::env::temp_dir()
+        };
+        base.join(format!("cage-session-{}", &key[..12]))
+    }
+
+    /// Session open for `repository`, if any
+    pub fn load(repository: &Path) -> AgeResult<Option<Self>> {
+        let path = Self::session_path(repository);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AgeError::file_error("read_session", path, e)),
+        };
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| AgeError::InvalidOperation {
+                operation: "read_session".to_string(),
+                reason: format!("{}: {}", path.display(), e),
+            })
+    }
+
+    /// Record the session, replacing the previous file in one rename
+    pub fn save(&self) -> AgeResult<()> {
+        let path = Self::session_path(&self.repository);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| AgeError::file_error("create_state_dir", dir.to_path_buf(), e))?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(|e| AgeError::InvalidOperation {
+            operation: "save_session".to_string(),
+            reason: e.to_string(),
+        })?;
+        let mut staging = path.as_os_str().to_owned();
+        staging.push(".tmp");
+        let staging = PathBuf::from(staging);
+        fs::write(&staging, json)
+            .map_err(|e| AgeError::file_error("write_session", staging.clone(), e))?;
+        fs::rename(&staging, &path).map_err(|e| AgeError::file_error("write_session", path, e))
+    }
+
+    /// Forget the session (the workspace is left alone)
+    pub fn discard(&self) -> AgeResult<()> {
+        let path = Self::session_path(&self.repository);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(AgeError::file_error("delete_session", path, e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Compare the workspace with the hashes recorded at open
+    pub fn changes(&self, config: &AgeConfig) -> AgeResult<SessionChanges> {
+        let present: BTreeSet<PathBuf> = if self.workspace.is_dir() {
+            RecursiveTraversal
+                .collect(&self.workspace, &FileFilter::all(config))?
+                .into_iter()
+                .filter_map(|file| {
+                    file.strip_prefix(&self.workspace)
+                        .ok()
+                        .map(Path::to_path_buf)
+                })
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+
+        let mut changes = SessionChanges::default();
+        for file in &self.files {
+            if !present.contains(&file.plaintext) {
+                changes.removed.push(file.clone());
+            } else if sha256_file(&self.workspace.join(&file.plaintext))? != file.sha256 {
+                changes.changed.push(file.clone());
+            } else {
+                changes.unchanged += 1;
+            }
+        }
+        let opened: BTreeSet<&PathBuf> = self.files.iter().map(|file| &file.plaintext).collect();
+        changes.added = present
+            .into_iter()
+            .filter(|relative| !opened.contains(relative))
+            .collect();
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_changes_classify_workspace_files() {
+        let repo = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = workspace.path().join(name);
+            fs::write(&path, content).unwrap();
+            SessionFile {
+                ciphertext: PathBuf::from(format!("{}.cage", name)),
+                plaintext: PathBuf::from(name),
+                sha256: sha256_file(&path).unwrap(),
+            }
+        };
+        let session = Session {
+            repository: repo.path().to_path_buf(),
+            workspace: workspace.path().to_path_buf(),
+            opened_at: String::new(),
+            owns_workspace: false,
+            files: vec![
+                write("same.txt", "a"),
+                write("edit.txt", "b"),
+                write("gone.txt", "c"),
+            ],
+        };
+        session.save().unwrap();
+        assert_eq!(Session::load(repo.path()).unwrap(), Some(session.clone()));
+
+        fs::write(workspace.path().join("edit.txt"), "edited").unwrap();
+        fs::remove_file(workspace.path().join("gone.txt")).unwrap();
+        fs::write(workspace.path().join("new.txt"), "new").unwrap();
+        let changes = session.changes(&AgeConfig::default()).unwrap();
+        assert_eq!(changes.unchanged, 1);
+        assert_eq!(changes.changed[0].plaintext, PathBuf::from("edit.txt"));
+        assert_eq!(changes.removed[0].plaintext, PathBuf::from("gone.txt"));
+        assert_eq!(changes.added, vec![PathBuf::from("new.txt")]);
+        assert!(!changes.is_clean());
+
+        session.discard().unwrap();
+        assert!(Session::load(repo.path()).unwrap().is_none());
+    }
+}
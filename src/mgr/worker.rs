@@ -0,0 +1,474 @@
+//! Work-Queue Worker - Bulk Service Mode
+//!
+//! Reads newline-delimited JSON job records from a file, FIFO or stdin, executes each one
+//! through a `CageManager`, and writes one JSON result record per job to an output stream.
+//! Jobs run sequentially by default or across a fixed pool of worker threads, each owning
+//! its own manager instance.
+//!
+//! Job record format (one per line; blank lines and `#` comments are ignored):
+//!
+//! ```text
+//! {"id": "job-1", "operation": "lock", "request": {"target": "/data/a", "identity": {"provider": "env:KEY"}, ...}}
+//! ```
+//!
+//! Secrets are never accepted inline; requests reference them through identity providers
+//! (see `core::identity_provider`).
+
+use std::io::{BufRead, Write};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::cage_manager::CageManager;
+use crate::core::{
+    BatchRequest, LockRequest, MemoryBudget, RotateRequest, UnlockRequest, VerifyRequest,
+};
+use crate::error::{AgeError, AgeResult};
+use crate::forge::{OperationResult, OperationWarning};
+
+/// Estimated peak buffer memory per concurrent job (one default 64 MiB chunk)
+pub const WORKER_MEMORY_ESTIMATE: u64 = 64 * 1024 * 1024;
+
+/// Request payload of a queued job, tagged by operation name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "operation", content = "request", rename_all = "snake_case")]
+pub enum WorkerRequest {
+    Lock(LockRequest),
+    Unlock(UnlockRequest),
+    Rotate(RotateRequest),
+    Verify(VerifyRequest),
+    Batch(BatchRequest),
+}
+
+impl WorkerRequest {
+    /// Operation name as used in job and result records
+    pub fn operation(&self) -> &'static str {
+        match self {
+            WorkerRequest::Lock(_) => "lock",
+            WorkerRequest::Unlock(_) => "unlock",
+            WorkerRequest::Rotate(_) => "rotate",
+            WorkerRequest::Verify(_) => "verify",
+            WorkerRequest::Batch(_) => "batch",
+        }
+    }
+}
+
+/// A single job record read from the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerJob {
+    /// Caller-supplied job identifier, echoed in the result record
+    #[serde(default)]
+    pub id: Option<String>,
+
+    /// Operation and request to execute
+    #[serde(flatten)]
+    pub request: WorkerRequest,
+}
+
+/// Result record written for every job (including unparseable lines)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkerResult {
+    /// 1-based line number of the job in the queue
+    pub line: usize,
+    pub id: Option<String>,
+    pub operation: Option<String>,
+    pub success: bool,
+    pub processed: usize,
+    pub failed: usize,
+    pub failed_files: Vec<String>,
+    #[serde(default)]
+    pub skipped: usize,
+    #[serde(default)]
+    pub bytes_in: u64,
+    #[serde(default)]
+    pub bytes_out: u64,
+    /// Non-fatal issues the job ran into
+    #[serde(default)]
+    pub warnings: Vec<OperationWarning>,
+    pub execution_time_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Totals for a worker run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkerSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl WorkerSummary {
+    fn record(&mut self, result: &WorkerResult) {
+        self.total += 1;
+        if result.success {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+}
+
+type ManagerFactory = dyn Fn() -> AgeResult<CageManager> + Send + Sync;
+
+/// Queue-driven bulk executor
+pub struct QueueWorker {
+    parallelism: usize,
+    memory_budget: MemoryBudget,
+    manager_factory: Arc<ManagerFactory>,
+}
+
+impl Default for QueueWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueueWorker {
+    /// Create a sequential worker backed by `CageManager::with_defaults`
+    pub fn new() -> Self {
+        Self {
+            parallelism: 1,
+            memory_budget: MemoryBudget::unlimited(),
+            manager_factory: Arc::new(CageManager::with_defaults),
+        }
+    }
+
+    /// Number of jobs to execute concurrently (minimum 1)
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Bound concurrent jobs so their buffers fit within `budget`
+    pub fn with_memory_budget(mut self, budget: MemoryBudget) -> Self {
+        self.memory_budget = budget;
+        self
+    }
+
+    /// Override how each worker thread constructs its manager
+    pub fn with_manager_factory<F>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> AgeResult<CageManager> + Send + Sync + 'static,
+    {
+        self.manager_factory = Arc::new(factory);
+        self
+    }
+
+    /// Configured parallelism
+    pub fn parallelism(&self) -> usize {
+        self.parallelism
+    }
+
+    /// Parallelism after applying the memory budget
+    pub fn effective_parallelism(&self) -> usize {
+        self.memory_budget
+            .max_parallelism(self.parallelism, WORKER_MEMORY_ESTIMATE)
+    }
+
+    /// Consume jobs from `input` until EOF, writing one JSON result line per job.
+    ///
+    /// With parallelism above 1, results are written in completion order; use the
+    /// `line`/`id` fields to correlate them with jobs.
+    pub fn run<R: BufRead, W: Write>(&self, input: R, output: &mut W) -> AgeResult<WorkerSummary> {
+        let parallelism = self.effective_parallelism();
+        if parallelism < self.parallelism {
+            tracing::warn!(
+                "Memory budget limits worker parallelism from {} to {}",
+                self.parallelism,
+                parallelism
+            );
+        }
+
+        if parallelism <= 1 {
+            self.run_sequential(input, output)
+        } else {
+            self.run_parallel(input, output, parallelism)
+        }
+    }
+
+    fn run_sequential<R: BufRead, W: Write>(
+        &self,
+        input: R,
+        output: &mut W,
+    ) -> AgeResult<WorkerSummary> {
+        let mut summary = WorkerSummary::default();
+        let mut manager: Option<CageManager> = None;
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.map_err(|e| worker_io_error("read job queue", e))?;
+            if is_skippable(&line) {
+                continue;
+            }
+
+            let result = if manager.is_none() {
+                match (self.manager_factory)() {
+                    Ok(created) => {
+                        manager = Some(created);
+                        process_line(manager.as_mut(), index + 1, &line)
+                    }
+                    Err(e) => manager_failure(index + 1, &line, &e),
+                }
+            } else {
+                process_line(manager.as_mut(), index + 1, &line)
+            };
+
+            summary.record(&result);
+            write_result(output, &result)?;
+        }
+
+        output
+            .flush()
+            .map_err(|e| worker_io_error("flush results", e))?;
+        Ok(summary)
+    }
+
+    fn run_parallel<R: BufRead, W: Write>(
+        &self,
+        input: R,
+        output: &mut W,
+        parallelism: usize,
+    ) -> AgeResult<WorkerSummary> {
+        let mut summary = WorkerSummary::default();
+        let (job_tx, job_rx) = mpsc::sync_channel::<(usize, String)>(parallelism * 2);
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let (result_tx, result_rx) = mpsc::channel::<WorkerResult>();
+
+        std::thread::scope(|scope| -> AgeResult<()> {
+            for _ in 0..parallelism {
+                let job_rx = Arc::clone(&job_rx);
+                let result_tx = result_tx.clone();
+                let factory = Arc::clone(&self.manager_factory);
+                scope.spawn(move || {
+                    let mut manager = factory();
+                    loop {
+                        let next = job_rx.lock().map(|rx| rx.recv());
+                        let (line_no, line) = match next {
+                            Ok(Ok(job)) => job,
+                            _ => break,
+                        };
+                        let result = match manager.as_mut() {
+                            Ok(m) => process_line(Some(m), line_no, &line),
+                            Err(e) => manager_failure(line_no, &line, e),
+                        };
+                        if result_tx.send(result).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(result_tx);
+
+            let mut read_error = None;
+            for (index, line) in input.lines().enumerate() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        read_error = Some(worker_io_error("read job queue", e));
+                        break;
+                    }
+                };
+                if is_skippable(&line) {
+                    continue;
+                }
+                if job_tx.send((index + 1, line)).is_err() {
+                    break;
+                }
+                while let Ok(result) = result_rx.try_recv() {
+                    summary.record(&result);
+                    write_result(output, &result)?;
+                }
+            }
+            drop(job_tx);
+
+            for result in result_rx.iter() {
+                summary.record(&result);
+                write_result(output, &result)?;
+            }
+
+            match read_error {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        })?;
+
+        output
+            .flush()
+            .map_err(|e| worker_io_error("flush results", e))?;
+        Ok(summary)
+    }
+}
+
+fn worker_io_error(context: &str, source: std::io::Error) -> AgeError {
+    AgeError::IoError {
+        operation: "worker".to_string(),
+        context: context.to_string(),
+        source,
+    }
+}
+
+fn is_skippable(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn write_result<W: Write>(output: &mut W, result: &WorkerResult) -> AgeResult<()> {
+    let json = serde_json::to_string(result).map_err(|e| AgeError::InvalidOperation {
+        operation: "worker_result".to_string(),
+        reason: e.to_string(),
+    })?;
+    writeln!(output, "{}", json).map_err(|e| worker_io_error("write result", e))
+}
+
+fn failed_result(line_no: usize, job: Option<&WorkerJob>, error: String) -> WorkerResult {
+    WorkerResult {
+        line: line_no,
+        id: job.and_then(|j| j.id.clone()),
+        operation: job.map(|j| j.request.operation().to_string()),
+        success: false,
+        processed: 0,
+        failed: 0,
+        failed_files: Vec::new(),
+        skipped: 0,
+        bytes_in: 0,
+        bytes_out: 0,
+        warnings: Vec::new(),
+        execution_time_ms: 0,
+        error: Some(error),
+    }
+}
+
+fn manager_failure(line_no: usize, line: &str, error: &AgeError) -> WorkerResult {
+    let job = serde_json::from_str::<WorkerJob>(line).ok();
+    failed_result(
+        line_no,
+        job.as_ref(),
+        format!("Failed to initialise manager: {}", error),
+    )
+}
+
+fn process_line(manager: Option<&mut CageManager>, line_no: usize, line: &str) -> WorkerResult {
+    let job: WorkerJob = match serde_json::from_str(line) {
+        Ok(job) => job,
+        Err(e) => return failed_result(line_no, None, format!("Invalid job record: {}", e)),
+    };
+    let manager = match manager {
+        Some(manager) => manager,
+        None => {
+            return failed_result(line_no, Some(&job), "No manager available".to_string());
+        }
+    };
+
+    let start = Instant::now();
+    let outcome = match &job.request {
+        WorkerRequest::Lock(request) => manager.lock_with_request(request),
+        WorkerRequest::Unlock(request) => manager.unlock_with_request(request),
+        WorkerRequest::Rotate(request) => manager.rotate_with_request(request),
+        WorkerRequest::Batch(request) => manager.batch_with_request(request),
+        WorkerRequest::Verify(request) => manager.verify_with_request(request).map(|r| {
+            let mut result = OperationResult::new();
+            result.success = r.failed_files.is_empty();
+            result.total_processed = r.verified_files.len();
+            result.failed_files = r.failed_files;
+            result
+        }),
+    };
+    let execution_time_ms = start.elapsed().as_millis() as u64;
+
+    match outcome {
+        Ok(result) => WorkerResult {
+            line: line_no,
+            id: job.id.clone(),
+            operation: Some(job.request.operation().to_string()),
+            success: result.success,
+            processed: result.total_processed,
+            failed: result.failed_files.len(),
+            skipped: result.skipped_files.len(),
+            bytes_in: result.bytes_in,
+            bytes_out: result.bytes_out,
+            failed_files: result.failed_files,
+            warnings: result.warnings,
+            execution_time_ms,
+            error: None,
+        },
+        Err(e) => {
+            let mut result = failed_result(line_no, Some(&job), e.to_string());
+            result.execution_time_ms = execution_time_ms;
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Identity;
+
+    #[test]
+    fn test_job_record_parsing() {
+        let job: WorkerJob = serde_json::from_str(
+            r#"{"id":"j1","operation":"verify","request":{"target":"/data","identity":null,
+                "recursive":true,"pattern":null,"deep_verify":false,"stream_verify":false,
+                "report_format":"json"}}"#,
+        )
+        .unwrap();
+        assert_eq!(job.id.as_deref(), Some("j1"));
+        assert_eq!(job.request.operation(), "verify");
+
+        let lock = WorkerJob {
+            id: None,
+            request: WorkerRequest::Lock(LockRequest::new(
+                "/data/file".into(),
+                Identity::Provider("env:KEY".to_string()),
+            )),
+        };
+        let json = serde_json::to_string(&lock).unwrap();
+        assert!(json.contains(r#""operation":"lock""#));
+    }
+
+    #[test]
+    fn test_invalid_lines_produce_failure_records() {
+        let input = "# comment\n\nnot json\n{\"operation\":\"explode\",\"request\":{}}\n";
+        let worker = QueueWorker::new()
+            .with_manager_factory(|| Err(AgeError::AdapterNotImplemented("test".to_string())));
+
+        let mut output = Vec::new();
+        let summary = worker.run(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.failed, 2);
+
+        let lines: Vec<WorkerResult> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0].line, 3);
+        assert_eq!(lines[1].line, 4);
+        assert!(lines.iter().all(|r| !r.success && r.error.is_some()));
+    }
+
+    #[test]
+    fn test_parallel_run_reports_every_job() {
+        let input: String = (0..20)
+            .map(|i| format!("{{\"id\":\"{}\",\"operation\":\"bogus\"}}\n", i))
+            .collect();
+        let worker = QueueWorker::new()
+            .with_parallelism(4)
+            .with_manager_factory(|| Err(AgeError::AdapterNotImplemented("test".to_string())));
+
+        let mut output = Vec::new();
+        let summary = worker.run(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(summary.total, 20);
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 20);
+    }
+
+    #[test]
+    fn test_memory_budget_degrades_parallelism() {
+        let worker = QueueWorker::new()
+            .with_parallelism(8)
+            .with_memory_budget(MemoryBudget::new(Some(2 * WORKER_MEMORY_ESTIMATE)));
+        assert_eq!(worker.parallelism(), 8);
+        assert_eq!(worker.effective_parallelism(), 2);
+    }
+}
@@ -0,0 +1,413 @@
+//! Read-only decrypted view of a repository over FUSE (feature `mount`, Unix only)
+//!
+//! `cage mount <repo> <mountpoint>` serves the repository with every encrypted file shown
+//! under its plaintext name (`notes.txt.cage` appears as `notes.txt`), so tools can read
+//! encrypted configs without a bulk unlock. Files are decrypted with the streaming
+//! adapter when opened and held in memory only while a handle is open; nothing is
+//! written to disk. The identity is resolved once at mount time, so provider-backed
+//! identities are fetched a single time for the life of the mount.
+//!
+//! The view is read-only: writes fail with `EROFS`. Unencrypted files pass through as
+//! they are; `.meta` sidecars and the `.cage` state directory are hidden. Until a file
+//! has been opened its size is reported as the ciphertext size; reads use direct I/O so
+//! tools read up to the real end of the plaintext regardless.
+
+use crate::adp::v2::{AgeAdapterV2, ShellAdapterV2};
+use crate::core::meta;
+use crate::core::{interrupt, AgeConfig, Identity, IdentityProviderRegistry, ResolvedIdentity};
+use crate::error::{AgeError, AgeResult};
+use crate::forge::STATE_DIR;
+use fuser::{
+    FileAttr, FileType, Filesystem, MountOption, ReplyAttr, ReplyData, ReplyDirectory, ReplyEmpty,
+    ReplyEntry, ReplyOpen, Request,
+};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// How long the kernel may cache attributes and lookups
+const TTL: Duration = Duration::from_secs(1);
+const ROOT_INODE: u64 = fuser::FUSE_ROOT_ID;
+
+/// What a directory entry is shown as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewKind {
+    Directory,
+    /// Ciphertext shown decrypted under its plaintext name
+    Encrypted,
+    /// Unencrypted file passed through unchanged
+    Plain,
+}
+
+/// One entry of the decrypted view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewEntry {
+    /// Name shown in the mount
+    pub name: OsString,
+    /// File or directory in the repository
+    pub source: PathBuf,
+    pub kind: ViewKind,
+}
+
+/// Entries the mount shows for `dir`, sorted by name.
+///
+/// When `a.txt` and `a.txt.cage` both exist, the decrypted view of the ciphertext wins.
+/// Symlinks are not followed out of the repository and are left out.
+pub fn view_dir(dir: &Path, config: &AgeConfig) -> AgeResult<Vec<ViewEntry>> {
+    let suffix = config.extension_with_dot();
+    let read = fs::read_dir(dir).map_err(|e| AgeError::file_error("read_dir", dir.into(), e))?;
+    let mut entries = Vec::new();
+    for entry in read.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name();
+        let source = entry.path();
+        if file_type.is_dir() {
+            if name != STATE_DIR {
+                entries.push(ViewEntry {
+                    name,
+                    source,
+                    kind: ViewKind::Directory,
+                });
+            }
+        } else if file_type.is_file() && !meta::is_sidecar(&source, config) {
+            let plain = name
+                .to_str()
+                .and_then(|name| name.strip_suffix(&suffix))
+                .filter(|plain| !plain.is_empty());
+            entries.push(match plain {
+                Some(plain) => ViewEntry {
+                    name: plain.into(),
+                    source,
+                    kind: ViewKind::Encrypted,
+                },
+                None => ViewEntry {
+                    name,
+                    source,
+                    kind: ViewKind::Plain,
+                },
+            });
+        }
+    }
+    // Encrypted sorts first among equal names, so dedup keeps it
+    entries.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then((b.kind == ViewKind::Encrypted).cmp(&(a.kind == ViewKind::Encrypted)))
+    });
+    entries.dedup_by(|later, earlier| later.name == earlier.name);
+    Ok(entries)
+}
+
+struct Node {
+    source: PathBuf,
+    kind: ViewKind,
+}
+
+/// FUSE filesystem serving the decrypted view of one repository
+pub struct CageFs {
+    config: AgeConfig,
+    adapter: ShellAdapterV2,
+    identity: ResolvedIdentity,
+    /// Inode `n` is `nodes[n - 1]`; inode 1 is the repository root
+    nodes: Vec<Node>,
+    inodes: HashMap<PathBuf, u64>,
+    /// Plaintext of open handles
+    handles: HashMap<u64, Vec<u8>>,
+    /// Plaintext sizes learned from opens
+    sizes: HashMap<u64, u64>,
+    next_handle: u64,
+    uid: u32,
+    gid: u32,
+}
+
+impl CageFs {
+    pub fn new(repository: &Path, identity: &Identity, config: AgeConfig) -> AgeResult<Self> {
+        let metadata = fs::metadata(repository)
+            .map_err(|e| AgeError::file_error("mount", repository.to_path_buf(), e))?;
+        if !metadata.is_dir() {
+            return Err(AgeError::InvalidOperation {
+                operation: "mount".to_string(),
+                reason: format!("{} is not a directory", repository.display()),
+            });
+        }
+        let identity = IdentityProviderRegistry::from_config(&config).resolve(identity)?;
+        let adapter = ShellAdapterV2::with_config(config.clone())?;
+        Ok(Self {
+            config,
+            adapter,
+            identity,
+            nodes: vec![Node {
+                source: repository.to_path_buf(),
+                kind: ViewKind::Directory,
+            }],
+            inodes: HashMap::from([(repository.to_path_buf(), ROOT_INODE)]),
+            handles: HashMap::new(),
+            sizes: HashMap::new(),
+            next_handle: 1,
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+        })
+    }
+
+    fn node(&self, ino: u64) -> Option<&Node> {
+        self.nodes.get(usize::try_from(ino.checked_sub(1)?).ok()?)
+    }
+
+    fn directory(&self, ino: u64) -> Option<PathBuf> {
+        self.node(ino)
+            .filter(|node| node.kind == ViewKind::Directory)
+            .map(|node| node.source.clone())
+    }
+
+    fn inode(&mut self, entry: &ViewEntry) -> u64 {
+        if let Some(ino) = self.inodes.get(&entry.source) {
+            return *ino;
+        }
+        self.nodes.push(Node {
+            source: entry.source.clone(),
+            kind: entry.kind,
+        });
+        let ino = self.nodes.len() as u64;
+        self.inodes.insert(entry.source.clone(), ino);
+        ino
+    }
+
+    fn attr(&self, ino: u64) -> Option<FileAttr> {
+        let node = self.node(ino)?;
+        let metadata = fs::metadata(&node.source).ok()?;
+        let (kind, perm, nlink, size) = match node.kind {
+            ViewKind::Directory => (FileType::Directory, 0o500, 2, 0),
+            ViewKind::Encrypted => {
+                let size = self.sizes.get(&ino).copied().unwrap_or(metadata.len());
+                (FileType::RegularFile, 0o400, 1, size)
+            }
+            ViewKind::Plain => (FileType::RegularFile, 0o400, 1, metadata.len()),
+        };
+        let mtime = metadata.modified().unwrap_or(UNIX_EPOCH);
+        Some(FileAttr {
+            ino,
+            size,
+            blocks: size.div_ceil(512),
+            atime: mtime,
+            mtime,
+            ctime: mtime,
+            crtime: mtime,
+            kind,
+            perm,
+            nlink,
+            uid: self.uid,
+            gid: self.gid,
+            rdev: 0,
+            blksize: 4096,
+            flags: 0,
+        })
+    }
+
+    /// Contents served for a file: decrypted through the streaming adapter, or as-is
+    fn contents(&self, node: &Node) -> AgeResult<Vec<u8>> {
+        let read_error = |e| AgeError::file_error("read", node.source.clone(), e);
+        match node.kind {
+            ViewKind::Encrypted => {
+                let mut input = File::open(&node.source).map_err(read_error)?;
+                let mut plaintext = Vec::new();
+                self.adapter.decrypt_stream(
+                    &mut input,
+                    &mut plaintext,
+                    self.identity.identity(),
+                )?;
+                Ok(plaintext)
+            }
+            ViewKind::Plain => fs::read(&node.source).map_err(read_error),
+            ViewKind::Directory => Err(AgeError::InvalidOperation {
+                operation: "mount".to_string(),
+                reason: format!("{} is a directory", node.source.display()),
+            }),
+        }
+    }
+}
+
+impl Filesystem for CageFs {
+    fn lookup(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        let Some(dir) = self.directory(parent) else {
+            return reply.error(libc::ENOENT);
+        };
+        let found = view_dir(&dir, &self.config)
+            .ok()
+            .and_then(|entries| entries.into_iter().find(|entry| entry.name == name));
+        let Some(entry) = found else {
+            return reply.error(libc::ENOENT);
+        };
+        let ino = self.inode(&entry);
+        match self.attr(ino) {
+            Some(attr) => reply.entry(&TTL, &attr, 0),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request<'_>, ino: u64, _fh: Option<u64>, reply: ReplyAttr) {
+        match self.attr(ino) {
+            Some(attr) => reply.attr(&TTL, &attr),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn open(&mut self, _req: &Request<'_>, ino: u64, flags: i32, reply: ReplyOpen) {
+        if flags & libc::O_ACCMODE != libc::O_RDONLY {
+            return reply.error(libc::EROFS);
+        }
+        let Some(node) = self.node(ino) else {
+            return reply.error(libc::ENOENT);
+        };
+        match self.contents(node) {
+            Ok(plaintext) => {
+                self.sizes.insert(ino, plaintext.len() as u64);
+                let handle = self.next_handle;
+                self.next_handle += 1;
+                self.handles.insert(handle, plaintext);
+                reply.opened(handle, fuser::consts::FOPEN_DIRECT_IO);
+            }
+            Err(e) => {
+                tracing::error!("mount: {}", e);
+                reply.error(libc::EIO);
+            }
+        }
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let Some(plaintext) = self.handles.get(&fh) else {
+            return reply.error(libc::EBADF);
+        };
+        let start = usize::try_from(offset).unwrap_or(0).min(plaintext.len());
+        let end = start.saturating_add(size as usize).min(plaintext.len());
+        reply.data(&plaintext[start..end]);
+    }
+
+    fn release(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        _flush: bool,
+        reply: ReplyEmpty,
+    ) {
+        if let Some(mut plaintext) = self.handles.remove(&fh) {
+            plaintext.fill(0);
+        }
+        reply.ok();
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: ReplyDirectory,
+    ) {
+        let Some(dir) = self.directory(ino) else {
+            return reply.error(libc::ENOENT);
+        };
+        let Ok(entries) = view_dir(&dir, &self.config) else {
+            return reply.error(libc::EIO);
+        };
+        let mut listing = vec![
+            (ino, FileType::Directory, OsString::from(".")),
+            (ROOT_INODE, FileType::Directory, OsString::from("..")),
+        ];
+        for entry in entries {
+            let kind = match entry.kind {
+                ViewKind::Directory => FileType::Directory,
+                _ => FileType::RegularFile,
+            };
+            listing.push((self.inode(&entry), kind, entry.name));
+        }
+        let skip = usize::try_from(offset).unwrap_or(0);
+        for (index, (child, kind, name)) in listing.into_iter().enumerate().skip(skip) {
+            // `add` reports a full buffer; the kernel asks again from the next offset
+            if reply.add(child, (index + 1) as i64, kind, name) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+}
+
+/// Mount `repository` read-only at `mountpoint` until interrupted or unmounted
+pub fn run(
+    repository: &Path,
+    mountpoint: &Path,
+    identity: &Identity,
+    config: AgeConfig,
+) -> AgeResult<()> {
+    let filesystem = CageFs::new(repository, identity, config)?;
+    let options = [
+        MountOption::RO,
+        MountOption::NoExec,
+        MountOption::FSName("cage".to_string()),
+        MountOption::Subtype("cage".to_string()),
+    ];
+    let session = fuser::spawn_mount2(filesystem, mountpoint, &options)
+        .map_err(|e| AgeError::file_error("mount", mountpoint.to_path_buf(), e))?;
+    // Dropping the session unmounts; `fusermount -u` ends the background thread instead
+    while interrupt::interrupted().is_none() && !session.guard.is_finished() {
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    drop(session);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_view_dir_shows_plaintext_names() {
+        let repo = TempDir::new().unwrap();
+        let config = AgeConfig::default();
+        for name in [
+            "a.txt.cage",
+            "a.txt",
+            "b.txt.cage",
+            "b.txt.cage.meta",
+            "notes.md",
+        ] {
+            fs::write(repo.path().join(name), "x").unwrap();
+        }
+        fs::create_dir_all(repo.path().join(STATE_DIR)).unwrap();
+        fs::create_dir_all(repo.path().join("sub")).unwrap();
+
+        let entries = view_dir(repo.path(), &config).unwrap();
+        let shown: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.name.to_str().unwrap(), entry.kind))
+            .collect();
+        assert_eq!(
+            shown,
+            vec![
+                ("a.txt", ViewKind::Encrypted),
+                ("b.txt", ViewKind::Encrypted),
+                ("notes.md", ViewKind::Plain),
+                ("sub", ViewKind::Directory),
+            ]
+        );
+        assert_eq!(entries[0].source, repo.path().join("a.txt.cage"));
+    }
+}
@@ -4,8 +4,15 @@
 //! - Interactive terminal prompting (secure, hidden input)
 //! - Stdin passphrase mode for automation
 //! - Environment variable fallback
+//! - External command output (e.g. `pass show repo/key`)
 //! - Command line argument detection and warnings
+//!
+//! New passphrases (prompted with confirmation) follow a [`PassphrasePolicy`]: a limited
+//! number of confirmation attempts, a nudge towards a longer passphrase, and an optional
+//! masked strength meter. Non-interactive modes ignore the policy.
 
+use crate::core::prompt::require_interactive;
+use crate::core::AgeConfig;
 use crate::error::{AgeError, AgeResult};
 use crate::lang::{fmt_info, fmt_warning};
 use rpassword::read_password;
@@ -23,13 +30,97 @@ pub enum PassphraseMode {
     Environment(String),
     /// Command line argument (insecure, warn user)
     CommandLine(String),
+    /// Run a command and read the passphrase from its stdout (no shell)
+    Command(String),
+}
+
+/// How new passphrases are entered interactively
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassphrasePolicy {
+    /// Tries at typing a passphrase and a matching confirmation
+    pub confirm_attempts: u32,
+    /// Shorter new passphrases get one chance to be replaced by a longer one
+    pub min_length: usize,
+    /// Show a strength meter (never the passphrase) before confirmation
+    pub strength_meter: bool,
+}
+
+impl Default for PassphrasePolicy {
+    fn default() -> Self {
+        Self {
+            confirm_attempts: 3,
+            min_length: 8,
+            strength_meter: false,
+        }
+    }
+}
+
+impl PassphrasePolicy {
+    pub fn from_config(config: &AgeConfig) -> Self {
+        Self {
+            confirm_attempts: config.passphrase_confirm_attempts,
+            min_length: config.passphrase_min_length,
+            strength_meter: config.passphrase_strength_meter,
+        }
+    }
+}
+
+/// Rough passphrase strength, for display only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PassphraseStrength {
+    Weak,
+    Fair,
+    Good,
+    Strong,
+}
+
+impl PassphraseStrength {
+    /// Score length (8/12/16 characters) and variety (3+ character classes)
+    pub fn assess(passphrase: &str) -> Self {
+        let length = passphrase.chars().count();
+        let classes = [
+            passphrase.chars().any(|c| c.is_lowercase()),
+            passphrase.chars().any(|c| c.is_uppercase()),
+            passphrase.chars().any(|c| c.is_ascii_digit()),
+            passphrase.chars().any(|c| !c.is_alphanumeric()),
+        ]
+        .iter()
+        .filter(|present| **present)
+        .count();
+        let score = [length >= 8, length >= 12, length >= 16, classes >= 3]
+            .iter()
+            .filter(|met| **met)
+            .count();
+        match score {
+            0 | 1 => Self::Weak,
+            2 => Self::Fair,
+            3 => Self::Good,
+            _ => Self::Strong,
+        }
+    }
+
+    /// Masked meter such as `[##--] fair`
+    pub fn meter(self) -> String {
+        let (filled, label) = match self {
+            Self::Weak => (1, "weak"),
+            Self::Fair => (2, "fair"),
+            Self::Good => (3, "good"),
+            Self::Strong => (4, "strong"),
+        };
+        format!(
+            "[{}{}] {}",
+            "#".repeat(filled),
+            "-".repeat(4 - filled),
+            label
+        )
+    }
 }
 
 /// Secure passphrase manager with multiple input methods
 pub struct PassphraseManager {
     tty_available: bool,
-    #[allow(dead_code)]
     stdin_is_tty: bool,
+    policy: PassphrasePolicy,
 }
 
 impl Default for PassphraseManager {
@@ -44,9 +135,16 @@ impl PassphraseManager {
         Self {
             tty_available: Self::detect_tty(),
             stdin_is_tty: Self::detect_stdin_tty(),
+            policy: PassphrasePolicy::default(),
         }
     }
 
+    /// Prompt new passphrases according to `policy`
+    pub fn with_policy(mut self, policy: PassphrasePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Detect if TTY is available for interactive prompts
     fn detect_tty() -> bool {
         // Check if we can access /dev/tty (Unix) or have interactive terminal
@@ -98,6 +196,7 @@ impl PassphraseManager {
                 self.warn_insecure_usage();
                 Ok(pass)
             }
+            PassphraseMode::Command(cmd) => self.read_from_command(&cmd),
         }
     }
 
@@ -113,6 +212,11 @@ impl PassphraseManager {
             return Ok(PassphraseMode::Stdin);
         }
 
+        require_interactive(
+            "passphrase",
+            "no CAGE_PASSPHRASE, --stdin-passphrase or --passphrase-cmd given",
+        )?;
+
         // Default to interactive if TTY available
         if self.tty_available {
             Ok(PassphraseMode::Interactive)
@@ -125,48 +229,21 @@ impl PassphraseManager {
 
     /// Prompt for passphrase interactively with secure hidden input
     fn prompt_interactive(&self, prompt: &str, confirm: bool) -> AgeResult<String> {
+        require_interactive("passphrase", prompt)?;
         if !self.tty_available {
             return Err(AgeError::PassphraseError {
                 message: "TTY not available for interactive prompt".to_string(),
             });
         }
 
-        // Print prompt to stderr to avoid interfering with stdout
-        eprint!("{} {}: ", glyph("lock"), prompt);
-        io::stderr()
-            .flush()
-            .map_err(|e| AgeError::PassphraseError {
-                message: format!("Failed to flush stderr: {}", e),
-            })?;
-
-        let passphrase = read_password().map_err(|e| AgeError::PassphraseError {
-            message: format!("Failed to read passphrase: {}", e),
-        })?;
-
-        if passphrase.is_empty() {
-            return Err(AgeError::PassphraseError {
-                message: "Empty passphrase not allowed".to_string(),
-            });
-        }
-
         // Confirmation for critical operations
         if confirm {
-            eprint!("{} Confirm {}: ", glyph("lock"), prompt);
-            io::stderr()
-                .flush()
-                .map_err(|e| AgeError::PassphraseError {
-                    message: format!("Failed to flush stderr: {}", e),
-                })?;
-
-            let confirmation = read_password().map_err(|e| AgeError::PassphraseError {
-                message: format!("Failed to read confirmation: {}", e),
-            })?;
+            return self.prompt_new_passphrase(prompt);
+        }
 
-            if passphrase != confirmation {
-                return Err(AgeError::PassphraseError {
-                    message: "Passphrases do not match".to_string(),
-                });
-            }
+        let passphrase = self.read_hidden(prompt)?;
+        if passphrase.is_empty() {
+            return Err(empty_passphrase());
         }
 
         // Validate passphrase strength
@@ -175,8 +252,77 @@ impl PassphraseManager {
         Ok(passphrase)
     }
 
+    /// Prompt for a new passphrase and its confirmation, retrying mismatches
+    fn prompt_new_passphrase(&self, prompt: &str) -> AgeResult<String> {
+        let attempts = self.policy.confirm_attempts.max(1);
+        for attempt in 1..=attempts {
+            let mut passphrase = self.read_hidden(prompt)?;
+            if passphrase.is_empty() {
+                return Err(empty_passphrase());
+            }
+
+            if passphrase.chars().count() < self.policy.min_length {
+                eprintln!(
+                    "{}",
+                    fmt_warning(&format!(
+                        "Passphrase is shorter than {} characters.",
+                        self.policy.min_length
+                    ))
+                );
+                let longer = self.read_hidden("Type a longer one, or press Enter to keep it")?;
+                if !longer.is_empty() {
+                    passphrase = longer;
+                }
+            }
+
+            if self.policy.strength_meter {
+                eprintln!(
+                    "   Strength: {}",
+                    PassphraseStrength::assess(&passphrase).meter()
+                );
+            }
+
+            let confirmation = self.read_hidden(&format!("Confirm {}", prompt))?;
+            if passphrase == confirmation {
+                self.validate_passphrase_strength(&passphrase)?;
+                return Ok(passphrase);
+            }
+            if attempt < attempts {
+                eprintln!(
+                    "{}",
+                    fmt_warning(&format!(
+                        "Passphrases do not match, try again ({}/{})",
+                        attempt, attempts
+                    ))
+                );
+            }
+        }
+
+        Err(AgeError::PassphraseError {
+            message: format!("Passphrases did not match after {} attempts", attempts),
+        })
+    }
+
+    /// Prompt on stderr (keeping stdout clean) and read hidden input
+    fn read_hidden(&self, prompt: &str) -> AgeResult<String> {
+        eprint!("{} {}: ", glyph("lock"), prompt);
+        io::stderr()
+            .flush()
+            .map_err(|e| AgeError::PassphraseError {
+                message: format!("Failed to flush stderr: {}", e),
+            })?;
+
+        read_password().map_err(|e| AgeError::PassphraseError {
+            message: format!("Failed to read passphrase: {}", e),
+        })
+    }
+
     /// Read passphrase from stdin (for scripting/automation)
     fn read_from_stdin(&self) -> AgeResult<String> {
+        // A terminal on stdin would mean waiting for someone to type
+        if self.stdin_is_tty {
+            require_interactive("passphrase", "read passphrase from terminal stdin")?;
+        }
         let mut input = String::new();
         io::stdin()
             .read_line(&mut input)
@@ -201,6 +347,35 @@ impl PassphraseManager {
         })
     }
 
+    /// Run a passphrase command and read the secret from its stdout.
+    ///
+    /// The command line is split into argv without invoking a shell, so no
+    /// variable expansion, globbing or command substitution takes place.
+    fn read_from_command(&self, command: &str) -> AgeResult<String> {
+        let argv = split_command_line(command)?;
+        let output = crate::core::identity_provider::run_secret_command(&argv).map_err(|e| {
+            AgeError::PassphraseError {
+                message: format!("Passphrase command failed: {}", e),
+            }
+        })?;
+
+        // Only the trailing line terminator is stripped; the passphrase itself
+        // may legitimately contain surrounding whitespace.
+        let passphrase = output
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('\r')
+            .to_string();
+        if passphrase.is_empty() {
+            return Err(AgeError::PassphraseError {
+                message: "Passphrase command produced no output".to_string(),
+            });
+        }
+
+        Ok(passphrase)
+    }
+
     /// Warn about insecure command line usage
     fn warn_insecure_usage(&self) {
         eprintln!(
@@ -214,8 +389,14 @@ impl PassphraseManager {
 
     /// Validate passphrase strength and provide recommendations
     fn validate_passphrase_strength(&self, passphrase: &str) -> AgeResult<()> {
-        if passphrase.len() < 8 {
-            eprintln!("{}", fmt_warning("Passphrase is less than 8 characters."));
+        if passphrase.chars().count() < self.policy.min_length {
+            eprintln!(
+                "{}",
+                fmt_warning(&format!(
+                    "Passphrase is less than {} characters.",
+                    self.policy.min_length
+                ))
+            );
             eprintln!("   Consider using a longer passphrase for better security.");
         }
 
@@ -271,6 +452,88 @@ impl PassphraseManager {
     }
 }
 
+/// Split a command line into argv with simple POSIX-style quoting.
+///
+/// Supports single quotes, double quotes and backslash escapes; nothing is
+/// expanded. Unterminated quotes and empty commands are rejected.
+pub fn split_command_line(command: &str) -> AgeResult<Vec<String>> {
+    let mut argv = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err(unterminated_quote(command)),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\')) => current.push(ch),
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err(unterminated_quote(command)),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err(unterminated_quote(command)),
+                    }
+                }
+            }
+            '\\' => {
+                in_token = true;
+                if let Some(ch) = chars.next() {
+                    current.push(ch);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_token {
+                    argv.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_token {
+        argv.push(current);
+    }
+
+    if argv.is_empty() {
+        return Err(AgeError::PassphraseError {
+            message: "Passphrase command is empty".to_string(),
+        });
+    }
+    Ok(argv)
+}
+
+fn empty_passphrase() -> AgeError {
+    AgeError::PassphraseError {
+        message: "Empty passphrase not allowed".to_string(),
+    }
+}
+
+fn unterminated_quote(command: &str) -> AgeError {
+    AgeError::PassphraseError {
+        message: format!("Unterminated quote in passphrase command: {}", command),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +545,38 @@ mod tests {
         assert!(manager.tty_available || !manager.tty_available);
     }
 
+    #[test]
+    fn test_passphrase_strength() {
+        assert_eq!(PassphraseStrength::assess("abc"), PassphraseStrength::Weak);
+        assert_eq!(
+            PassphraseStrength::assess("abcdefghijkl"),
+            PassphraseStrength::Fair
+        );
+        assert_eq!(
+            PassphraseStrength::assess("Abcdefgh1234"),
+            PassphraseStrength::Good
+        );
+        assert_eq!(
+            PassphraseStrength::assess("Correct-Horse-Battery-9"),
+            PassphraseStrength::Strong
+        );
+        assert_eq!(PassphraseStrength::Fair.meter(), "[##--] fair");
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let mut config = AgeConfig::default();
+        assert_eq!(
+            PassphrasePolicy::from_config(&config),
+            PassphrasePolicy::default()
+        );
+        config.passphrase_confirm_attempts = 5;
+        config.passphrase_strength_meter = true;
+        let policy = PassphrasePolicy::from_config(&config);
+        assert_eq!(policy.confirm_attempts, 5);
+        assert!(policy.strength_meter);
+    }
+
     #[test]
     fn test_insecure_usage_detection() {
         let args1 = vec![
@@ -338,4 +633,52 @@ mod tests {
         assert_eq!(mode, PassphraseMode::Stdin);
         std::env::remove_var("CAGE_STDIN_PASSPHRASE");
     }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line("pass show repo/key").unwrap(),
+            vec!["pass", "show", "repo/key"]
+        );
+        assert_eq!(
+            split_command_line("op read 'op://vault/item name/field'").unwrap(),
+            vec!["op", "read", "op://vault/item name/field"]
+        );
+        assert_eq!(
+            split_command_line(r#"echo "a \"b\" $HOME" x\ y"#).unwrap(),
+            vec!["echo", "a \"b\" $HOME", "x y"]
+        );
+        assert_eq!(split_command_line("cmd ''").unwrap(), vec!["cmd", ""]);
+        assert!(split_command_line("   ").is_err());
+        assert!(split_command_line("echo 'open").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_passphrase_from_command() {
+        let manager = PassphraseManager::new();
+        let pass = manager
+            .get_passphrase_with_mode(
+                "Passphrase",
+                false,
+                PassphraseMode::Command("printf 'hunter2\\n'".to_string()),
+            )
+            .unwrap();
+        assert_eq!(pass, "hunter2");
+
+        assert!(manager
+            .get_passphrase_with_mode(
+                "Passphrase",
+                false,
+                PassphraseMode::Command("true".to_string())
+            )
+            .is_err());
+        assert!(manager
+            .get_passphrase_with_mode(
+                "Passphrase",
+                false,
+                PassphraseMode::Command("false".to_string())
+            )
+            .is_err());
+    }
 }
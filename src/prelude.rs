@@ -5,25 +5,48 @@
 //!
 //! ```rust
 //! use cage::prelude::*;
+//!
+//! let request = LockRequest::new("secrets".into(), Identity::Passphrase("hunter22".into()))
+//!     .with_recipients(vec![Recipient::PublicKey("age1...".to_string())])
+//!     .skip_existing(true);
+//! assert_eq!(request.format, OutputFormat::Binary);
 //! ```
 //!
 //! # Included Types
 //!
+//! - **Facade**: `Cage`, `CageBuilder` - Stable high-level API for embedders
 //! - **Core Management**: `CageManager` - Main entry point for encryption operations
-//! - **Request API**: `LockRequest`, `UnlockRequest`, `RotateRequest` - Typed operation builders
+//! - **Request API**: `LockRequest`, `UnlockRequest`, `RotateRequest`, `StatusRequest`,
+//!   `VerifyRequest`, `BatchRequest`, `StreamRequest` and their option types
+//! - **Identities**: `Identity`, `Recipient`, `RecipientGroup`, `MultiRecipientConfig`,
+//!   `AuthorityTier` - Who encrypts and who can decrypt
 //! - **Configuration**: `AgeConfig`, `OutputFormat`, `TtyMethod` - Runtime configuration
 //! - **Options**: `LockOptions`, `UnlockOptions` - Operation-specific settings
 //! - **Results**: `AgeResult`, `AgeError`, `OperationResult` - Error handling types
 //! - **Adapters**: `AgeAdapter`, `AgeAdapterV2` - Core adapter traits
 //! - **Security**: `SecurityValidator`, `AuditLogger` - Security components
-//! - **Progress**: `ProgressManager`, `ProgressReporter` - Progress tracking
+//!
+//! # Stability
+//!
+//! The prelude follows semver: names are only added in minor releases, and removing or
+//! renaming one waits for a major release, so `use cage::prelude::*` keeps compiling
+//! across minor upgrades. Types used mainly inside the library (the CLI conversion traits
+//! `FromCliArgs`/`ToOperationParams`, adapters other than the shell ones) stay under
+//! `cage::core` and `cage::adp` and are not promised here.
 
 // Core types from the cage module
 pub use crate::{
     adp::v2::{AgeAdapterV2, ShellAdapterV2},
 
+    // Identities and recipients
+    core::{AuthorityTier, Identity, MultiRecipientConfig, Recipient, RecipientGroup},
+
     // Request API (CAGE-11)
-    core::{LockRequest, RotateRequest, UnlockRequest},
+    core::{
+        BatchOperation, BatchOrder, BatchRequest, CommonOptions, LockRequest, MtimeWindow,
+        ReportFormat, RetentionPolicyConfig, RotateRequest, StatusRequest, StreamOperation,
+        StreamRequest, UnlockRequest, VerifyRequest,
+    },
 
     AdapterFactory,
     // Adapters
@@ -35,6 +58,10 @@ pub use crate::{
     AgeResult,
     AuditLogger,
 
+    // Facade
+    Cage,
+    CageBuilder,
+
     // Management
     CageManager,
 
@@ -44,6 +71,7 @@ pub use crate::{
     // Operations
     Operation,
     OperationResult,
+    OperationWarning,
 
     OutputFormat,
     // Passphrase
@@ -54,10 +82,12 @@ pub use crate::{
     RepositoryStatus,
     // Security
     SecurityValidator,
+    SkippedFile,
     TtyMethod,
 
     UnlockOptions,
     VerificationResult,
+    WarningCategory,
 };
 
 // Constants
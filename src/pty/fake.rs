@@ -0,0 +1,189 @@
+//! Fake Age Automation - In-process stand-in for PTY-driven age
+//!
+//! `FakeAutomator` implements `PtyAutomator` without spawning anything, so
+//! `CageManager` can be tested where there is no PTY or age binary. Its output starts
+//! with a real age header (or armor lines), which is enough for format detection and
+//! verification, but the body is only XOR-masked with the passphrase: it is NOT
+//! encryption and must never be used outside tests. A wrong passphrase fails to
+//! decrypt, like age.
+
+use super::PtyAutomator;
+use crate::core::OutputFormat;
+use crate::error::{AgeError, AgeResult};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const BINARY_HEADER: &str = "age-encryption.org/v1";
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+
+/// Test double for `PtyAgeAutomator`; counts the operations it performs
+#[derive(Debug, Default)]
+pub struct FakeAutomator {
+    encrypt_calls: AtomicUsize,
+    decrypt_calls: AtomicUsize,
+}
+
+impl FakeAutomator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encryptions performed so far
+    pub fn encrypt_calls(&self) -> usize {
+        self.encrypt_calls.load(Ordering::SeqCst)
+    }
+
+    /// Decryption attempts so far, including failed ones
+    pub fn decrypt_calls(&self) -> usize {
+        self.decrypt_calls.load(Ordering::SeqCst)
+    }
+
+    fn seal(passphrase: &str, plaintext: &[u8], format: OutputFormat) -> Vec<u8> {
+        let mut sealed = fake_header(passphrase).into_bytes();
+        sealed.extend(mask(passphrase, plaintext));
+        match format {
+            OutputFormat::Binary => sealed,
+            OutputFormat::AsciiArmor => {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(sealed);
+                let mut armored = format!("{}\n", ARMOR_BEGIN);
+                for line in encoded.as_bytes().chunks(64) {
+                    armored.push_str(&String::from_utf8_lossy(line));
+                    armored.push('\n');
+                }
+                armored.push_str(ARMOR_END);
+                armored.push('\n');
+                armored.into_bytes()
+            }
+        }
+    }
+
+    fn open(passphrase: &str, sealed: &[u8]) -> Result<Vec<u8>, String> {
+        let binary = if sealed.starts_with(ARMOR_BEGIN.as_bytes()) {
+            let text = String::from_utf8_lossy(sealed);
+            let body: String = text
+                .lines()
+                .filter(|line| *line != ARMOR_BEGIN && *line != ARMOR_END)
+                .collect();
+            base64::engine::general_purpose::STANDARD
+                .decode(body.trim())
+                .map_err(|e| format!("invalid armor: {}", e))?
+        } else {
+            sealed.to_vec()
+        };
+
+        let expected = fake_header(passphrase);
+        if !binary.starts_with(BINARY_HEADER.as_bytes()) {
+            return Err("not an age file".to_string());
+        }
+        match binary.strip_prefix(expected.as_bytes()) {
+            Some(body) => Ok(mask(passphrase, body)),
+            None => Err("incorrect passphrase".to_string()),
+        }
+    }
+}
+
+impl PtyAutomator for FakeAutomator {
+    fn encrypt(
+        &self,
+        input: &Path,
+        output: &Path,
+        passphrase: &str,
+        format: OutputFormat,
+    ) -> AgeResult<()> {
+        self.encrypt_calls.fetch_add(1, Ordering::SeqCst);
+        let plaintext =
+            fs::read(input).map_err(|e| AgeError::file_error("read", input.to_path_buf(), e))?;
+        fs::write(output, Self::seal(passphrase, &plaintext, format))
+            .map_err(|e| AgeError::file_error("write", output.to_path_buf(), e))
+    }
+
+    fn decrypt(&self, input: &Path, output: &Path, passphrase: &str) -> AgeResult<()> {
+        self.decrypt_calls.fetch_add(1, Ordering::SeqCst);
+        let sealed =
+            fs::read(input).map_err(|e| AgeError::file_error("read", input.to_path_buf(), e))?;
+        let plaintext = Self::open(passphrase, &sealed).map_err(|reason| {
+            AgeError::decryption_failed(input.to_path_buf(), output.to_path_buf(), &reason)
+        })?;
+        fs::write(output, plaintext)
+            .map_err(|e| AgeError::file_error("write", output.to_path_buf(), e))
+    }
+
+    fn check_age_binary(&self) -> AgeResult<()> {
+        Ok(())
+    }
+
+    fn perform_health_check(&self) -> AgeResult<()> {
+        let sealed = Self::seal("health", b"cage", OutputFormat::Binary);
+        match Self::open("health", &sealed) {
+            Ok(plaintext) if plaintext == b"cage" => Ok(()),
+            _ => Err(AgeError::HealthCheckFailed(
+                "fake automator round trip failed".to_string(),
+            )),
+        }
+    }
+
+    fn available_methods(&self) -> Vec<String> {
+        vec!["fake".to_string()]
+    }
+}
+
+/// Short tag identifying the passphrase in the fake header
+/// Well-formed age header with one `scrypt` stanza, so header parsing sees a passphrase file
+fn fake_header(passphrase: &str) -> String {
+    format!(
+        "{}\n-> scrypt {} 18\n\n--- ZmFrZQ\n",
+        BINARY_HEADER,
+        passphrase_tag(passphrase)
+    )
+}
+
+fn passphrase_tag(passphrase: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(passphrase.as_bytes()));
+    digest[..16].to_string()
+}
+
+/// XOR `data` with a keystream derived from the passphrase (self-inverse)
+fn mask(passphrase: &str, data: &[u8]) -> Vec<u8> {
+    let key = Sha256::digest(passphrase.as_bytes());
+    data.iter()
+        .zip(key.iter().cycle())
+        .map(|(byte, k)| byte ^ k)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fake_round_trip_and_wrong_passphrase() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("secret.txt");
+        let sealed = temp_dir.path().join("secret.txt.cage");
+        let opened = temp_dir.path().join("opened.txt");
+        fs::write(&input, "top secret").unwrap();
+        let automator = FakeAutomator::new();
+
+        for format in [OutputFormat::Binary, OutputFormat::AsciiArmor] {
+            automator
+                .encrypt(&input, &sealed, "hunter2", format)
+                .unwrap();
+            let content = fs::read(&sealed).unwrap();
+            assert!(
+                content.starts_with(BINARY_HEADER.as_bytes())
+                    || content.starts_with(ARMOR_BEGIN.as_bytes())
+            );
+            assert!(automator.decrypt(&sealed, &opened, "wrong").is_err());
+            automator.decrypt(&sealed, &opened, "hunter2").unwrap();
+            assert_eq!(fs::read_to_string(&opened).unwrap(), "top secret");
+        }
+        assert_eq!(automator.encrypt_calls(), 2);
+        assert_eq!(automator.decrypt_calls(), 4);
+        assert!(automator.perform_health_check().is_ok());
+    }
+}
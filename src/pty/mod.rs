@@ -8,6 +8,7 @@
 //!
 //! - `wrap` - PTY-based Age automation using portable-pty (primary method)
 //! - `tty` - TTY automation using script/expect methods (fallback/alternative)
+//! - `fake` - In-process `FakeAutomator` for tests without a PTY or age binary
 //!
 //! # Primary Method: PTY Wrapper
 //!
@@ -28,10 +29,62 @@
 //! - Secure temporary file handling
 //! - Proper cleanup on success and failure
 //! - Timeout protection against hanging processes
+//!
+//! # Testing Without a PTY
+//!
+//! `ShellAdapter` drives any `PtyAutomator`. CI runners often have neither a PTY nor
+//! age, so tests (here and in downstream crates) inject `FakeAutomator` through
+//! `ShellAdapter::with_automator` and exercise `CageManager` end to end. Tests that need
+//! a real PTY only run when `CAGE_REAL_PTY_TESTS=1`; see `real_pty_tests_enabled`.
 
+pub mod fake;
 pub mod tty;
 pub mod wrap;
 
 // Re-export primary types for convenience
+pub use fake::FakeAutomator;
 pub use tty::TtyAutomator;
 pub use wrap::PtyAgeAutomator;
+
+use crate::core::OutputFormat;
+use crate::error::AgeResult;
+use std::path::Path;
+
+/// Environment variable that opts into tests spawning age under a real PTY
+pub const REAL_PTY_TESTS_ENV: &str = "CAGE_REAL_PTY_TESTS";
+
+/// Backend that runs age for `ShellAdapter`
+pub trait PtyAutomator: Send + Sync {
+    /// Encrypt `input` to `output` with a passphrase
+    fn encrypt(
+        &self,
+        input: &Path,
+        output: &Path,
+        passphrase: &str,
+        format: OutputFormat,
+    ) -> AgeResult<()>;
+
+    /// Decrypt `input` to `output` with a passphrase
+    fn decrypt(&self, input: &Path, output: &Path, passphrase: &str) -> AgeResult<()>;
+
+    /// Check that the age binary (or its stand-in) is usable
+    fn check_age_binary(&self) -> AgeResult<()>;
+
+    /// Run an encrypt/decrypt round trip
+    fn perform_health_check(&self) -> AgeResult<()>;
+
+    /// Names of the automation methods this backend uses
+    fn available_methods(&self) -> Vec<String>;
+
+    /// Validate dependencies
+    fn validate_dependencies(&self) -> AgeResult<()> {
+        self.check_age_binary()
+    }
+}
+
+/// Whether tests needing a real PTY and age binary should run (`CAGE_REAL_PTY_TESTS=1`)
+pub fn real_pty_tests_enabled() -> bool {
+    std::env::var(REAL_PTY_TESTS_ENV)
+        .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
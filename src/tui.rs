@@ -0,0 +1,404 @@
+//! Interactive repository triage (feature `tui`, Unix only)
+//!
+//! `cage tui <dir>` shows the repository as a tree with each file's encryption state and
+//! lets an operator mark entries and lock, unlock or verify them in place. The screen is
+//! drawn with plain ANSI escapes over a raw-mode terminal, so no extra dependencies are
+//! needed.
+//!
+//! Keys: `j`/`k` or arrows move, space marks, `a` marks all, `l` lock, `u` unlock,
+//! `v` verify, `r` rescan, `q` quit. Actions apply to marked entries, or to the entry
+//! under the cursor when nothing is marked.
+
+use crate::core::{AgeConfig, Identity, LockRequest, UnlockRequest, VerifyRequest};
+use crate::error::{AgeError, AgeResult};
+use crate::mgr::CageManager;
+use crate::passphrase::PassphraseManager;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// One row of the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub path: PathBuf,
+    pub depth: usize,
+    pub is_dir: bool,
+    pub encrypted: bool,
+}
+
+/// Key presses the triage view understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Mark,
+    MarkAll,
+    Lock,
+    Unlock,
+    Verify,
+    Refresh,
+    Quit,
+    Other,
+}
+
+/// What the event loop should do after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TuiAction {
+    Redraw,
+    Lock(Vec<PathBuf>),
+    Unlock(Vec<PathBuf>),
+    Verify(Vec<PathBuf>),
+    Refresh,
+    Quit,
+}
+
+/// Tree state: entries, cursor and marked rows
+#[derive(Debug, Default)]
+pub struct TreeModel {
+    root: PathBuf,
+    entries: Vec<TreeEntry>,
+    cursor: usize,
+    marked: BTreeSet<usize>,
+}
+
+impl TreeModel {
+    /// Walk `root` (hidden entries skipped) and classify files with `config`
+    pub fn scan(root: &Path, config: &AgeConfig) -> AgeResult<Self> {
+        let mut entries = Vec::new();
+        collect(root, 0, config, &mut entries)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            entries,
+            cursor: 0,
+            marked: BTreeSet::new(),
+        })
+    }
+
+    pub fn entries(&self) -> &[TreeEntry] {
+        &self.entries
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Files targeted by an action: marked rows, else the row under the cursor
+    fn targets(&self) -> Vec<PathBuf> {
+        let rows: Vec<usize> = if self.marked.is_empty() {
+            vec![self.cursor]
+        } else {
+            self.marked.iter().copied().collect()
+        };
+        rows.into_iter()
+            .filter_map(|row| self.entries.get(row))
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> TuiAction {
+        match key {
+            Key::Up => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down => {
+                if self.cursor + 1 < self.entries.len() {
+                    self.cursor += 1;
+                }
+            }
+            Key::Mark => {
+                if !self.marked.remove(&self.cursor) {
+                    self.marked.insert(self.cursor);
+                }
+            }
+            Key::MarkAll => {
+                if self.marked.len() == self.entries.len() {
+                    self.marked.clear();
+                } else {
+                    self.marked = (0..self.entries.len()).collect();
+                }
+            }
+            Key::Lock => return TuiAction::Lock(self.targets()),
+            Key::Unlock => return TuiAction::Unlock(self.targets()),
+            Key::Verify => return TuiAction::Verify(self.targets()),
+            Key::Refresh => return TuiAction::Refresh,
+            Key::Quit => return TuiAction::Quit,
+            Key::Other => {}
+        }
+        TuiAction::Redraw
+    }
+
+    fn render(&self, status: &str, height: usize) -> String {
+        let encrypted = self.entries.iter().filter(|e| e.encrypted).count();
+        let files = self.entries.iter().filter(|e| !e.is_dir).count();
+        let mut out = String::from("\x1b[2J\x1b[H");
+        out.push_str(&format!(
+            "cage tui - {}  ({}/{} files encrypted)\r\n\r\n",
+            self.root.display(),
+            encrypted,
+            files
+        ));
+
+        // Keep the cursor visible in a window of `height` rows
+        let rows = height.saturating_sub(5).max(1);
+        let start = self.cursor.saturating_sub(rows - 1);
+        for (index, entry) in self.entries.iter().enumerate().skip(start).take(rows) {
+            let pointer = if index == self.cursor { '>' } else { ' ' };
+            let mark = if self.marked.contains(&index) {
+                '*'
+            } else {
+                ' '
+            };
+            let state = match (entry.is_dir, entry.encrypted) {
+                (true, _) => "   ",
+                (false, true) => "[E]",
+                (false, false) => "[ ]",
+            };
+            let name = entry
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let suffix = if entry.is_dir { "/" } else { "" };
+            out.push_str(&format!(
+                "{}{} {} {}{}{}\r\n",
+                pointer,
+                mark,
+                state,
+                "  ".repeat(entry.depth),
+                name,
+                suffix
+            ));
+        }
+
+        out.push_str(
+            "\r\nj/k move  space mark  a all  l lock  u unlock  v verify  r rescan  q quit\r\n",
+        );
+        out.push_str(status);
+        out
+    }
+}
+
+fn collect(
+    dir: &Path,
+    depth: usize,
+    config: &AgeConfig,
+    entries: &mut Vec<TreeEntry>,
+) -> AgeResult<()> {
+    let mut children: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| AgeError::file_error("read_dir", dir.to_path_buf(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    children.sort();
+
+    for path in children {
+        let is_dir = path.is_dir();
+        entries.push(TreeEntry {
+            encrypted: !is_dir && config.is_encrypted_file(&path),
+            path: path.clone(),
+            depth,
+            is_dir,
+        });
+        if is_dir {
+            collect(&path, depth + 1, config, entries)?;
+        }
+    }
+    Ok(())
+}
+
+/// Raw-mode guard restoring the previous terminal settings on drop
+struct RawMode {
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        // SAFETY: termios is plain data; tcgetattr fills it before use
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: restores settings captured in `enable`
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+fn read_key(input: &mut impl Read) -> io::Result<Key> {
+    let mut byte = [0u8; 1];
+    input.read_exact(&mut byte)?;
+    Ok(match byte[0] {
+        b'k' => Key::Up,
+        b'j' => Key::Down,
+        b' ' => Key::Mark,
+        b'a' => Key::MarkAll,
+        b'l' => Key::Lock,
+        b'u' => Key::Unlock,
+        b'v' => Key::Verify,
+        b'r' => Key::Refresh,
+        b'q' => Key::Quit,
+        0x1b => {
+            let mut seq = [0u8; 2];
+            input.read_exact(&mut seq)?;
+            match seq {
+                [b'[', b'A'] => Key::Up,
+                [b'[', b'B'] => Key::Down,
+                _ => Key::Other,
+            }
+        }
+        _ => Key::Other,
+    })
+}
+
+fn terminal_height() -> usize {
+    // SAFETY: winsize is plain data filled by the ioctl
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    if ok && size.ws_row > 0 {
+        size.ws_row as usize
+    } else {
+        24
+    }
+}
+
+/// Run the interactive triage view for `root`
+pub fn run(root: &Path) -> AgeResult<()> {
+    crate::core::prompt::require_interactive("tui", "interactive tree view")?;
+    let config = AgeConfig::load_default()?;
+    let mut manager = CageManager::with_defaults()?;
+    let mut model = TreeModel::scan(root, &config)?;
+    let mut identity: Option<Identity> = None;
+    let mut status = String::new();
+
+    let io_error = |e: io::Error| AgeError::IoError {
+        operation: "tui".to_string(),
+        context: "terminal".to_string(),
+        source: e,
+    };
+
+    loop {
+        let key = {
+            let _raw = RawMode::enable().map_err(io_error)?;
+            let mut stdout = io::stdout();
+            write!(stdout, "{}", model.render(&status, terminal_height())).map_err(io_error)?;
+            stdout.flush().map_err(io_error)?;
+            read_key(&mut io::stdin().lock()).map_err(io_error)?
+        };
+
+        let action = model.handle_key(key);
+        let (verb, targets) = match action {
+            TuiAction::Redraw => continue,
+            TuiAction::Quit => break,
+            TuiAction::Refresh => {
+                model = TreeModel::scan(root, &config)?;
+                status = "Rescanned".to_string();
+                continue;
+            }
+            TuiAction::Lock(targets) => ("lock", targets),
+            TuiAction::Unlock(targets) => ("unlock", targets),
+            TuiAction::Verify(targets) => ("verify", targets),
+        };
+        if targets.is_empty() {
+            status = "No files selected".to_string();
+            continue;
+        }
+
+        // Prompt outside raw mode so the passphrase manager controls echo
+        if verb != "verify" && identity.is_none() {
+            print!("\x1b[2J\x1b[H");
+            let passphrase = PassphraseManager::new().get_passphrase(
+                &format!("Passphrase to {} {} file(s)", verb, targets.len()),
+                false,
+            )?;
+            identity = Some(Identity::Passphrase(passphrase));
+        }
+
+        let mut ok = 0usize;
+        let mut failed = Vec::new();
+        for path in &targets {
+            let outcome = match (verb, &identity) {
+                ("lock", Some(identity)) => manager
+                    .lock_with_request(&LockRequest::new(path.clone(), identity.clone()))
+                    .map(|r| r.failed_files.is_empty()),
+                ("unlock", Some(identity)) => manager
+                    .unlock_with_request(&UnlockRequest::new(path.clone(), identity.clone()))
+                    .map(|r| r.failed_files.is_empty()),
+                _ => manager
+                    .verify_with_request(&VerifyRequest::new(path.clone()))
+                    .map(|r| r.failed_files.is_empty()),
+            };
+            match outcome {
+                Ok(true) => ok += 1,
+                Ok(false) => failed.push(path.display().to_string()),
+                Err(e) => failed.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+
+        status = format!("{}: {} ok, {} failed", verb, ok, failed.len());
+        if let Some(first) = failed.first() {
+            status.push_str(&format!(" (first: {})", first));
+        }
+        model = TreeModel::scan(root, &config)?;
+    }
+
+    print!("\x1b[2J\x1b[H");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tree_model_navigation_and_targets() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("nested")).unwrap();
+        fs::write(temp.path().join("a.txt"), "a").unwrap();
+        fs::write(temp.path().join("nested/b.txt.cage"), "b").unwrap();
+        fs::write(temp.path().join(".hidden"), "h").unwrap();
+
+        let mut model = TreeModel::scan(temp.path(), &AgeConfig::default()).unwrap();
+        let names: Vec<(usize, bool)> = model
+            .entries()
+            .iter()
+            .map(|e| (e.depth, e.encrypted))
+            .collect();
+        assert_eq!(names, vec![(0, false), (0, false), (1, true)]);
+
+        // Cursor on a.txt: lock targets it alone
+        assert_eq!(
+            model.handle_key(Key::Lock),
+            TuiAction::Lock(vec![temp.path().join("a.txt")])
+        );
+
+        // Marking the directory and the nested file targets only the file
+        model.handle_key(Key::Down);
+        model.handle_key(Key::Mark);
+        model.handle_key(Key::Down);
+        model.handle_key(Key::Mark);
+        model.handle_key(Key::Down);
+        assert_eq!(model.cursor(), 2);
+        assert_eq!(
+            model.handle_key(Key::Unlock),
+            TuiAction::Unlock(vec![temp.path().join("nested/b.txt.cage")])
+        );
+        assert_eq!(model.handle_key(Key::Quit), TuiAction::Quit);
+    }
+}
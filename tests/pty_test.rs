@@ -12,6 +12,11 @@ fn test_pty_creation() {
     println!("🧪 PTY Creation Test");
     println!("====================");
 
+    if !cage::pty::real_pty_tests_enabled() {
+        println!("⚠️  PTY creation skipped: set CAGE_REAL_PTY_TESTS=1 to open a real PTY");
+        return;
+    }
+
     let pty_system = native_pty_system();
     let pty_size = PtySize {
         rows: 24,
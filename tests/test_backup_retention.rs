@@ -28,6 +28,7 @@ fn test_backup_registry_save_load() {
         created_at: SystemTime::now(),
         size_bytes: 100,
         generation: 1,
+        checksum: None,
     };
 
     registry.register(PathBuf::from("test.txt"), entry1);
@@ -63,6 +64,7 @@ fn test_backup_registry_generation_tracking() {
         created_at: SystemTime::now(),
         size_bytes: 100,
         generation: 1,
+        checksum: None,
     };
     registry.register(file_path.clone(), entry1);
 
@@ -75,6 +77,7 @@ fn test_backup_registry_generation_tracking() {
         created_at: SystemTime::now(),
         size_bytes: 200,
         generation: 2,
+        checksum: None,
     };
     registry.register(file_path.clone(), entry2);
 
@@ -103,6 +106,7 @@ fn test_retention_policy_keep_last() {
             created_at: SystemTime::now(),
             size_bytes: 100,
             generation: i,
+            checksum: None,
         };
         registry.register(file_path.clone(), entry);
     }
@@ -141,6 +145,7 @@ fn test_retention_policy_keep_days() {
         created_at: old_time,
         size_bytes: 100,
         generation: 1,
+        checksum: None,
     };
     registry.register(file_path.clone(), entry_old);
 
@@ -153,6 +158,7 @@ fn test_retention_policy_keep_days() {
         created_at: recent_time,
         size_bytes: 100,
         generation: 2,
+        checksum: None,
     };
     registry.register(file_path.clone(), entry_recent);
 
@@ -273,6 +279,29 @@ fn test_backup_restore_by_generation() {
     assert_eq!(restored, gen2_content, "Generation 2 restore failed");
 }
 
+#[test]
+fn test_restore_generation_rejects_tampered_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let backup_dir = temp_dir.path().join("backups");
+    let test_file = temp_dir.path().join("test.txt");
+    fs::write(&test_file, "original").unwrap();
+
+    let mut manager =
+        BackupManager::with_backup_dir(backup_dir.clone()).with_retention(RetentionPolicy::KeepAll);
+    let info = manager.create_backup_with_retention(&test_file).unwrap();
+
+    // The checksum survives a reload of the registry
+    let reloaded = BackupManager::with_backup_dir(backup_dir);
+    let backups = reloaded.list_backups(&test_file);
+    assert_eq!(backups[0].checksum, info.checksum);
+    assert!(backups[0].checksum.is_some());
+
+    fs::write(&info.backup_path, "tampered").unwrap();
+    fs::write(&test_file, "current").unwrap();
+    assert!(reloaded.restore_backup_generation(&test_file, 1).is_err());
+    assert_eq!(fs::read_to_string(&test_file).unwrap(), "current");
+}
+
 #[test]
 fn test_registry_stats() {
     let temp_dir = TempDir::new().unwrap();
@@ -318,6 +347,7 @@ fn test_retention_keep_last_and_days() {
             created_at,
             size_bytes: 100,
             generation: i,
+            checksum: None,
         };
         registry.register(file_path.clone(), entry);
     }
@@ -371,6 +401,7 @@ fn test_atomic_registry_save() {
         created_at: SystemTime::now(),
         size_bytes: 100,
         generation: 1,
+        checksum: None,
     };
     registry.register(PathBuf::from("test.txt"), entry);
 
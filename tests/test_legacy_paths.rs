@@ -0,0 +1,59 @@
+#![allow(deprecated)]
+
+//! Legacy path compatibility (MOD4-07 shims)
+//! Imports through the old `cage::cage::*` layout must keep naming the flattened types
+
+use std::any::TypeId;
+
+mod legacy {
+    pub use cage::cage::adapter::ShellAdapter;
+    pub use cage::cage::adapter_v2::ShellAdapterV2;
+    pub use cage::cage::chunker::FileChunker;
+    pub use cage::cage::config::OutputFormat;
+    pub use cage::cage::core::AgeConfig;
+    pub use cage::cage::error::AgeError;
+    pub use cage::cage::lifecycle::{CrudManager, UnlockOptions};
+    pub use cage::cage::manager::LockOptions;
+    pub use cage::cage::operations::RepositoryStatus;
+    pub use cage::cage::pty_wrap::PtyAgeAutomator;
+    pub use cage::cage::requests::{Identity, LockRequest};
+    pub use cage::cage::security::AuditLogger;
+    pub use cage::cage::strings::OP_LOCK;
+    pub use cage::cage::{CageManager, SafetyValidator};
+}
+
+fn same_type<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+#[test]
+fn test_legacy_module_paths_resolve_to_flattened_types() {
+    assert!(same_type::<legacy::AgeConfig, cage::core::AgeConfig>());
+    assert!(same_type::<legacy::OutputFormat, cage::OutputFormat>());
+    assert!(same_type::<legacy::LockOptions, cage::LockOptions>());
+    assert!(same_type::<legacy::ShellAdapter, cage::adp::v1::ShellAdapter>());
+    assert!(same_type::<legacy::ShellAdapterV2, cage::adp::ShellAdapterV2>());
+    assert!(same_type::<legacy::AuditLogger, cage::AuditLogger>());
+    assert!(same_type::<legacy::RepositoryStatus, cage::RepositoryStatus>());
+    assert!(same_type::<legacy::FileChunker, cage::FileChunker>());
+    assert!(same_type::<legacy::AgeError, cage::AgeError>());
+    assert!(same_type::<legacy::LockRequest, cage::core::LockRequest>());
+    assert!(same_type::<legacy::Identity, cage::core::Identity>());
+    assert!(same_type::<
+        legacy::PtyAgeAutomator,
+        cage::pty::PtyAgeAutomator,
+    >());
+    assert!(same_type::<legacy::CageManager, cage::CageManager>());
+    assert!(same_type::<legacy::SafetyValidator, cage::SafetyValidator>());
+    assert_eq!(legacy::OP_LOCK, cage::lang::OP_LOCK);
+}
+
+#[test]
+fn test_crud_manager_alias_is_cage_manager() {
+    assert!(same_type::<legacy::CrudManager, cage::CageManager>());
+    assert!(same_type::<legacy::UnlockOptions, cage::UnlockOptions>());
+
+    let with_defaults: fn() -> cage::AgeResult<cage::CageManager> =
+        legacy::CrudManager::with_defaults;
+    let _ = with_defaults;
+}